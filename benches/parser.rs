@@ -0,0 +1,28 @@
+//! Throughput of the vhdl tokenizer and symbol parser over a generated corpus.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use orbit::perf;
+
+fn parser(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    perf::generate_corpus(dir.path(), 1, 20_000).unwrap();
+    let mem_init = std::fs::read_to_string(dir.path().join("rtl/mem_init.vhd")).unwrap();
+    let unit = std::fs::read_to_string(dir.path().join("rtl/unit_0.vhd")).unwrap();
+
+    let mut group = c.benchmark_group("tokenize");
+    for (name, text) in [("mem_init", &mem_init), ("unit", &unit)] {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_function(name, |b| b.iter(|| perf::tokenize(text)));
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("parse");
+    for (name, text) in [("mem_init", &mem_init), ("unit", &unit)] {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_function(name, |b| b.iter(|| perf::parse(text)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parser);
+criterion_main!(benches);
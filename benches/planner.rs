@@ -0,0 +1,24 @@
+//! Time to build the hdl graph and to plan a generated ip from start to finish.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use orbit::perf;
+
+fn planner(c: &mut Criterion) {
+    let home = tempfile::tempdir().unwrap();
+    let mut group = c.benchmark_group("plan");
+    group.sample_size(20);
+    for units in [64, 512] {
+        let dir = tempfile::tempdir().unwrap();
+        perf::generate_corpus(dir.path(), units, 4_096).unwrap();
+        group.bench_function(format!("graph build/{}", units), |b| b.iter(|| perf::build_graph(dir.path()).unwrap()));
+
+        // plan within the generated ip using an empty orbit home
+        std::env::set_var("ORBIT_HOME", home.path());
+        std::env::set_current_dir(dir.path()).unwrap();
+        group.bench_function(format!("end to end/{}", units), |b| b.iter(|| perf::run(&["plan", "--top", "top"]).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, planner);
+criterion_main!(benches);
@@ -0,0 +1,167 @@
+//! Generates the manual registry from the command pages in `docs/src/6_commands/`
+//! and the topic pages in `docs/src/7_help/`, and records the build metadata
+//! reported by `orbit version`.
+//!
+//! The markdown pages are the single source of truth for each manual. Every page
+//! linked from an index is split into its sections and lines, then written to
+//! `$OUT_DIR/manuals.rs` as a list of `Manual` values, which
+//! `src/commands/manuals/mod.rs` includes and renders as plain text or roff.
+//!
+//! The build metadata is passed to the compiler as the environment variables
+//! `BUILD_COMMIT`, `BUILD_DATE`, `BUILD_TARGET`, `BUILD_PROFILE`, and
+//! `BUILD_FEATURES`.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// The registries to generate as (constant, directory, index).
+const REGISTRIES: [(&str, &str, &str); 2] = [
+    ("MANUALS", "docs/src/6_commands", "0_commands.md"),
+    ("TOPICS", "docs/src/7_help", "0_help.md"),
+];
+
+fn main() {
+    let mut registry = String::new();
+    for (constant, dir, index) in REGISTRIES {
+        write_registry(&mut registry, constant, Path::new(dir), index);
+    }
+    let out = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("manuals.rs");
+    std::fs::write(out, registry).expect("failed to write the manual registry");
+
+    write_metadata();
+}
+
+/// Sets the environment variables describing this build of orbit.
+fn write_metadata() {
+    // rebuild when the checked out commit changes
+    let head = Path::new(".git/HEAD");
+    if head.exists() {
+        println!("cargo:rerun-if-changed={}", head.display());
+        if let Some(branch) = std::fs::read_to_string(head).ok().and_then(|s| s.strip_prefix("ref: ").map(|r| r.trim().to_string())) {
+            for path in [Path::new(".git").join(branch), Path::new(".git/packed-refs").to_path_buf()] {
+                if path.exists() {
+                    println!("cargo:rerun-if-changed={}", path.display());
+                }
+            }
+        }
+    }
+    let commit = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=BUILD_COMMIT={}", commit);
+
+    // honor a fixed timestamp for reproducible builds
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let secs = match std::env::var("SOURCE_DATE_EPOCH").ok().and_then(|s| s.parse::<u64>().ok()) {
+        Some(s) => s,
+        None => std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs(),
+    };
+    println!("cargo:rustc-env=BUILD_DATE={}", utc_date(secs));
+
+    println!("cargo:rustc-env=BUILD_TARGET={}", std::env::var("TARGET").unwrap());
+    println!("cargo:rustc-env=BUILD_PROFILE={}", std::env::var("PROFILE").unwrap());
+
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(k, _)| k.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase().replace('_', "-")))
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=BUILD_FEATURES={}", features.join(","));
+}
+
+/// Formats the seconds since the unix epoch `secs` as a `YYYY-MM-DD` date in UTC.
+fn utc_date(secs: u64) -> String {
+    // converts days to a civil date (see http://howardhinnant.github.io/date_algorithms.html)
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Writes the constant `name` listing the manual of every page linked from the
+/// `index` file within `dir`.
+fn write_registry(out: &mut String, name: &str, dir: &Path, index: &str) {
+    println!("cargo:rerun-if-changed={}", dir.display());
+    let text = std::fs::read_to_string(dir.join(index))
+        .unwrap_or_else(|e| panic!("failed to read index {}: {}", dir.join(index).display(), e));
+    writeln!(out, "pub const {}: &[Manual] = &[", name).unwrap();
+    for (name, file) in read_index(&text) {
+        let path = dir.join(&file);
+        println!("cargo:rerun-if-changed={}", path.display());
+        let text = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("failed to read manual page {}: {}", path.display(), e));
+        write_manual(out, &name, &text);
+    }
+    out.push_str("];\n");
+}
+
+/// Collects the page names and file names from the links in an index, such as
+/// `- ### [orbit tree](./7_tree.md)`. A page is named by the last word of its
+/// link's text.
+fn read_index(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let link = line.split_once("[orbit ")?.1;
+            let (words, rest) = link.split_once("](./")?;
+            let name = words.split_whitespace().last()?;
+            let file = rest.split_once(')')?.0;
+            Some((name.to_string(), file.to_string()))
+        })
+        .collect()
+}
+
+/// Writes the `Manual` for the markdown page `text` as rust source code.
+///
+/// Section headers are the `## __TITLE__` lines. Within a section, fenced code is
+/// kept as code lines, lines opening with a code span in a section listing
+/// options or commands are terms, indented lines are the descriptions of the
+/// term above them, and lines of only whitespace break paragraphs. Backslash
+/// escapes are removed while code spans are left for the renderer.
+fn write_manual(out: &mut String, name: &str, text: &str) {
+    writeln!(out, "    Manual {{ name: {:?}, sections: &[", name).unwrap();
+    let mut open = false;
+    let mut in_code = false;
+    let mut lists_terms = false;
+    for line in text.lines() {
+        let line = line.replace('\\', "");
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            writeln!(out, "            Line::Code({:?}),", line.trim_end()).unwrap();
+        } else if let Some(title) = line.strip_prefix("## ") {
+            if open {
+                out.push_str("        ] },\n");
+            }
+            let title = title.trim().trim_matches('_');
+            lists_terms = title == "OPTIONS" || title == "COMMANDS";
+            writeln!(out, "        Section {{ title: {:?}, lines: &[", title).unwrap();
+            open = true;
+        // the page title and text before the first section are not part of the manual
+        } else if !open || line.is_empty() {
+            continue;
+        } else if line.trim().is_empty() {
+            out.push_str("            Line::Break,\n");
+        } else if lists_terms && line.starts_with('`') {
+            writeln!(out, "            Line::Term({:?}),", line.trim_end()).unwrap();
+        } else if line.starts_with(char::is_whitespace) {
+            writeln!(out, "            Line::Desc({:?}),", line.trim()).unwrap();
+        } else {
+            writeln!(out, "            Line::Text({:?}),", line.trim_end()).unwrap();
+        }
+    }
+    if open {
+        out.push_str("        ] },\n");
+    }
+    out.push_str("    ] },\n");
+}
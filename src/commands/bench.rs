@@ -0,0 +1,342 @@
+use crate::Command;
+use crate::FromCli;
+use crate::commands::plan::BLUEPRINT_FILE;
+use crate::core::extgit::ExtGit;
+use crate::core::lockfile::LockFile;
+use crate::core::manifest::{IpManifest, IP_MANIFEST_FILE};
+use crate::core::pkgid::PkgId;
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Optional, Positional};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::diff;
+use crate::util::environment::{self, Environment};
+use crate::util::filesystem;
+use crate::util::theme;
+use git2::Repository;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq)]
+pub struct Bench {
+    command: Option<BenchSubcommand>,
+}
+
+impl FromCli for Bench {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Bench {
+            command: cli.check_command(Positional::new("command"))?,
+        });
+        command
+    }
+}
+
+impl Command for Bench {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        match &self.command {
+            Some(cmd) => cmd.exec(c),
+            None => {
+                println!("{}", HELP);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum BenchSubcommand {
+    Compare(Compare),
+}
+
+impl FromCli for BenchSubcommand {
+    fn from_cli<'c>(cli: &'c mut Cli<'_>) -> Result<Self, CliError<'c>> {
+        match cli.match_command(&[
+            "compare",
+        ])?.as_ref() {
+            "compare" => Ok(BenchSubcommand::Compare(Compare::from_cli(cli)?)),
+            _ => panic!("an unimplemented command was passed through!")
+        }
+    }
+}
+
+impl Command for BenchSubcommand {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        match self {
+            BenchSubcommand::Compare(p) => p.exec(c),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Compare {
+    base: String,
+    head: Option<String>,
+    top: Option<String>,
+    bench: Option<String>,
+    plugin: Option<String>,
+}
+
+impl FromCli for Compare {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_COMPARE);
+        let command = Ok(Compare {
+            top: cli.check_option(Optional::new("top").value("unit"))?,
+            bench: cli.check_option(Optional::new("bench").value("tb"))?,
+            plugin: cli.check_option(Optional::new("plugin"))?,
+            base: cli.require_positional(Positional::new("base"))?,
+            head: cli.check_positional(Positional::new("head"))?,
+        });
+        command
+    }
+}
+
+impl Command for Compare {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+        let ip_path = c.get_ip_path().unwrap().canonicalize()?;
+        let target = IpManifest::from_path(&ip_path)?;
+
+        let repo = match Repository::discover(&ip_path) {
+            Ok(r) => r,
+            Err(_) => return Err(AnyError(format!("the current ip is not within a git repository")))?,
+        };
+        // locate the ip within the repository to find it again in each revision
+        let subdir = match repo.workdir() {
+            Some(dir) => ip_path.strip_prefix(dir.canonicalize()?).unwrap_or(Path::new("")).to_path_buf(),
+            None => return Err(AnyError(format!("the current ip's repository has no working tree")))?,
+        };
+
+        let head = self.head.as_deref().unwrap_or("HEAD");
+        let before = self.plan_revision(&repo, &self.base, &subdir, target.get_pkgid(), c.get_build_dir())?;
+        let after = self.plan_revision(&repo, head, &subdir, target.get_pkgid(), c.get_build_dir())?;
+
+        println!("{}", theme::info(format!("comparing plans of {} ({:.7}) and {} ({:.7})", self.base, before.0, head, after.0)));
+        let changes = Snapshot::compare(&before.1, &after.1);
+        match changes.is_empty() {
+            true => println!("{}", theme::info("the plans are identical")),
+            false => print!("{}", changes.join("\n") + "\n"),
+        }
+        Ok(())
+    }
+}
+
+impl Compare {
+    /// Plans the ip found at `subdir` of `repo` as it existed at the revision `rev`.
+    ///
+    /// The revision is written to a temporary directory and planned by a separate orbit
+    /// process so the current working tree is left untouched.
+    fn plan_revision(&self, repo: &Repository, rev: &str, subdir: &Path, target: &PkgId, build_dir: &str) -> Result<(git2::Oid, Snapshot), Fault> {
+        let dir = tempfile::tempdir()?;
+        let commit = ExtGit::export_rev(repo, rev, dir.path())?;
+        // collecting the components drops the trailing separator of joining an empty `subdir`
+        let root: PathBuf = dir.path().canonicalize()?.join(subdir).components().collect();
+        if root.join(IP_MANIFEST_FILE).exists() == false {
+            return Err(AnyError(format!("revision '{}' has no ip at {}", rev, filesystem::to_standard(subdir))))?
+        }
+
+        let mut plan = std::process::Command::new(std::env::current_exe()?);
+        plan.current_dir(&root)
+            .args(["plan", "--no-input", "--build-dir", build_dir]);
+        if let Some(top) = &self.top {
+            plan.args(["--top", top]);
+        }
+        if let Some(bench) = &self.bench {
+            plan.args(["--bench", bench]);
+        }
+        if let Some(plugin) = &self.plugin {
+            plan.args(["--plugin", plugin]);
+        }
+        let output = plan.output()?;
+        if output.status.success() == false {
+            return Err(AnyError(format!("failed to plan revision '{}'\n\n{}{}", rev, String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr))))?
+        }
+        Ok((commit, Snapshot::read(&root, build_dir, target)?))
+    }
+}
+
+/// The results of planning an ip at a single revision.
+#[derive(Debug, PartialEq)]
+struct Snapshot {
+    /// blueprint rules in order, with paths within the ip made relative to its root
+    rules: Vec<String>,
+    /// versions of the locked dependencies by pkgid
+    deps: BTreeMap<String, String>,
+    top: String,
+    bench: String,
+}
+
+impl Snapshot {
+    /// Reads the blueprint, environment, and lockfile written by planning the `target` ip
+    /// located at `root` into `build_dir`.
+    fn read(root: &PathBuf, build_dir: &str, target: &PkgId) -> Result<Self, Fault> {
+        let build_path = root.join(build_dir);
+        let blueprint = std::fs::read_to_string(build_path.join(BLUEPRINT_FILE))?;
+        let env = Environment::new().from_env_file(&build_path)?;
+        let var = |key: &str| env.get(key).map(|v| v.get_value().to_string()).unwrap_or_default();
+        let lock = LockFile::from_path(root)?;
+        Ok(Self {
+            rules: Self::relative_rules(&blueprint, root),
+            deps: lock.inner().iter()
+                .filter(|entry| entry.get_name() != target)
+                .map(|entry| (entry.get_name().to_string(), entry.get_version().to_string()))
+                .collect(),
+            top: var(environment::ORBIT_TOP),
+            bench: var(environment::ORBIT_BENCH),
+        })
+    }
+
+    /// Lists the rules of the `blueprint` with the paths under `root` made relative to it.
+    fn relative_rules(blueprint: &str, root: &Path) -> Vec<String> {
+        let prefix = filesystem::to_standard(root) + "/";
+        blueprint.lines()
+            .map(|line| line.split('\t')
+                .map(|field| field.strip_prefix(&prefix).unwrap_or(field))
+                .collect::<Vec<&str>>()
+                .join("\t"))
+            .collect()
+    }
+
+    /// Describes the changes from the `before` plan to the `after` plan, one line each.
+    ///
+    /// Returns an empty list when the plans are identical.
+    fn compare(before: &Snapshot, after: &Snapshot) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (name, a, b) in [("top", &before.top, &after.top), ("bench", &before.bench, &after.bench)] {
+            if a != b {
+                lines.push(format!("{}: {} -> {}", name, Self::or_none(a), Self::or_none(b)));
+            }
+        }
+
+        let mut deps = Vec::new();
+        for (pkgid, version) in &before.deps {
+            match after.deps.get(pkgid) {
+                Some(v) if v == version => (),
+                Some(v) => deps.push(format!("    ~ {} {} -> {}", pkgid, version, v)),
+                None => deps.push(format!("    - {} {}", pkgid, version)),
+            }
+        }
+        for (pkgid, version) in after.deps.iter().filter(|(p, _)| !before.deps.contains_key(*p)) {
+            deps.push(format!("    + {} {}", pkgid, version));
+        }
+        // order by pkgid, past the indent and marker
+        deps.sort_by(|a, b| a[6..].cmp(&b[6..]));
+        if !deps.is_empty() {
+            lines.push(String::from("dependencies:"));
+            lines.append(&mut deps);
+        }
+
+        let mut rules = Vec::new();
+        for rule in before.rules.iter().filter(|r| !after.rules.contains(r)) {
+            rules.push(format!("    - {}", rule));
+        }
+        for rule in after.rules.iter().filter(|r| !before.rules.contains(r)) {
+            rules.push(format!("    + {}", rule));
+        }
+        let old: Vec<&str> = before.rules.iter().map(|r| r.as_str()).collect();
+        let new: Vec<&str> = after.rules.iter().map(|r| r.as_str()).collect();
+        for rule in diff::moved(&old, &new) {
+            rules.push(format!("    > {}", rule));
+        }
+        if !rules.is_empty() {
+            lines.push(String::from("blueprint:"));
+            lines.append(&mut rules);
+        }
+        lines
+    }
+
+    fn or_none(value: &str) -> &str {
+        match value.is_empty() {
+            true => "(none)",
+            false => value,
+        }
+    }
+}
+
+const HELP: &str = "\
+Measure the impact of changes to the current ip.
+
+Usage:
+    orbit bench <command> [options]
+
+Commands:
+    compare         diff the plans of two revisions
+
+Use 'orbit help bench' to learn more about the command.
+";
+
+const HELP_COMPARE: &str = "\
+Diff the plans of two revisions of the current ip.
+
+Usage:
+    orbit bench compare [options] <base> [<head>]
+
+Args:
+    <base>              the git revision to compare from
+    <head>              the git revision to compare to (default: HEAD)
+
+Options:
+    --top <unit>        override the top-level design unit
+    --bench <tb>        override the testbench
+    --plugin <alias>    plan with the filesets of a plugin
+
+Use 'orbit help bench' to learn more about the command.
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn snapshot(rules: &[&str], deps: &[(&str, &str)], top: &str) -> Snapshot {
+        Snapshot {
+            rules: rules.iter().map(|r| r.to_string()).collect(),
+            deps: deps.iter().map(|(p, v)| (p.to_string(), v.to_string())).collect(),
+            top: top.to_string(),
+            bench: String::new(),
+        }
+    }
+
+    #[test]
+    fn relative_rules() {
+        let blueprint = "VHDL-RTL\trary\t/cache/gates-1.0.0/and_gate.vhd\nVHDL-RTL\twork\t/tmp/ip/rtl/top.vhd\t2008\n";
+        assert_eq!(Snapshot::relative_rules(blueprint, Path::new("/tmp/ip")), vec![
+            "VHDL-RTL\trary\t/cache/gates-1.0.0/and_gate.vhd",
+            "VHDL-RTL\twork\trtl/top.vhd\t2008",
+        ]);
+    }
+
+    #[test]
+    fn compare() {
+        let before = snapshot(
+            &["VHDL-RTL\twork\ta.vhd", "VHDL-RTL\twork\tb.vhd", "VHDL-RTL\twork\tc.vhd", "VHDL-SIM\twork\ttb.vhd"],
+            &[("ks-tech.rary.gates", "1.0.0"), ("acme.rary.logic", "0.1.0")],
+            "top",
+        );
+        assert_eq!(Snapshot::compare(&before, &before), Vec::<String>::new());
+
+        let after = snapshot(
+            &["VHDL-RTL\twork\tc.vhd", "VHDL-RTL\twork\ta.vhd", "VHDL-RTL\twork\tb.vhd", "VHDL-RTL\twork\td.vhd"],
+            &[("ks-tech.rary.gates", "1.1.0"), ("ks-tech.util.math", "2.0.0")],
+            "top_v2",
+        );
+        assert_eq!(Snapshot::compare(&before, &after), vec![
+            "top: top -> top_v2",
+            "dependencies:",
+            "    - acme.rary.logic 0.1.0",
+            "    ~ ks-tech.rary.gates 1.0.0 -> 1.1.0",
+            "    + ks-tech.util.math 2.0.0",
+            "blueprint:",
+            "    - VHDL-SIM\twork\ttb.vhd",
+            "    + VHDL-RTL\twork\td.vhd",
+            "    > VHDL-RTL\twork\tc.vhd",
+        ]);
+    }
+}
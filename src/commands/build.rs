@@ -9,12 +9,18 @@ use crate::interface::cli::Cli;
 use crate::interface::arg::{Optional, Flag};
 use crate::interface::errors::CliError;
 use crate::core::context::Context;
-use crate::util::anyerror::AnyError;
+use crate::util::anyerror::{AnyError, Category, CategoryError};
 use crate::core::plugin::Plugin;
+use crate::core::report;
 use crate::util::environment;
 use crate::util::environment::ORBIT_BLUEPRINT;
+use crate::util::environment::ORBIT_BLUEPRINT_SUMS;
 use crate::util::environment::ORBIT_BUILD_DIR;
+use crate::util::environment::ORBIT_LOG_FILE;
+use crate::util::theme;
+use chrono::{DateTime, Local};
 use super::plan::BLUEPRINT_FILE;
+use super::plan::BLUEPRINT_SUMS_FILE;
 
 #[derive(Debug, PartialEq)]
 pub struct Build {
@@ -23,7 +29,6 @@ pub struct Build {
     command: Option<String>,
     build_dir: Option<String>,
     args: Vec<String>,
-    verbose: bool,
 }
 
 impl FromCli for Build {
@@ -32,7 +37,6 @@ impl FromCli for Build {
         let command = Ok(Build {
             alias: cli.check_option(Optional::new("plugin").value("alias"))?,
             list: cli.check_flag(Flag::new("list"))?,
-            verbose: cli.check_flag(Flag::new("verbose"))?,
             build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
             command: cli.check_option(Optional::new("command").value("cmd"))?,
             args: cli.check_remainder()?,
@@ -86,6 +90,7 @@ impl Command for Build {
             // read ip manifest for env variables
             .from_ip(&IpManifest::from_path(c.get_ip_path().unwrap())?)?
             .add(EnvVar::new().key(ORBIT_BLUEPRINT).value(BLUEPRINT_FILE))
+            .add(EnvVar::new().key(ORBIT_BLUEPRINT_SUMS).value(BLUEPRINT_SUMS_FILE))
             .add(EnvVar::new().key(ORBIT_BUILD_DIR).value(b_dir))
             .initialize();
 
@@ -121,26 +126,63 @@ impl Command for Build {
             return Err(AnyError(format!("pass a plugin or a command for building")))?
         }
 
-        self.run(plug)
+        let run = report::Run::start(report::Step::Build);
+        let name = plug.map(|p| p.alias().as_str()).or(self.command.as_deref());
+        // capture the output to a new log file that the plugin can also append to
+        let log_dir = c.get_ip_path().unwrap().join(b_dir).join(LOG_DIR);
+        let log_name = Build::log_file_name(name.unwrap(), &Local::now());
+        std::fs::create_dir_all(&log_dir)?;
+        let log = std::fs::OpenOptions::new().create(true).append(true).open(log_dir.join(&log_name))?;
+        // the log file is relative to the build directory like the blueprint
+        Environment::new()
+            .add(EnvVar::new().key(ORBIT_LOG_FILE).value(&format!("{}/{}", LOG_DIR, log_name)))
+            .initialize();
+
+        let result = self.run(plug, &log, c.verbose);
+        // the outcome of the build is reported even if it cannot be recorded
+        if let Err(e) = run.finish(c.get_config(), &c.get_ip_path().unwrap().join(b_dir).join(BLUEPRINT_FILE), name, result.is_ok()) {
+            println!("{}", theme::warning(format!("failed to record the build in the report: {}", e)));
+        }
+        result
     }
 }
 
 impl Build {
+    /// Creates a `Build` that executes the plugin `alias` with `args`.
+    ///
+    /// If `alias` is `None`, the plugin is recalled from the build directory's .env file.
+    pub fn new(alias: Option<String>, build_dir: Option<String>, args: Vec<String>) -> Self {
+        Self {
+            alias,
+            list: false,
+            command: None,
+            build_dir,
+            args,
+        }
+    }
+
+    /// Names the log file for a run of the plugin or command `name` started at `time`.
+    fn log_file_name(name: &str, time: &DateTime<Local>) -> String {
+        // a command may be given as a path to a script
+        let stem = std::path::Path::new(name).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or(name.to_string());
+        format!("{}-{}.log", stem, time.format("%Y%m%d-%H%M%S"))
+    }
 
-    fn run(&self, plug: Option<&Plugin>) -> Result<(), Box<dyn std::error::Error>> {
+    /// Executes the plugin `plug` or the command, displaying what is executed when
+    /// `verbose` is set.
+    fn run(&self, plug: Option<&Plugin>, log: &std::fs::File, verbose: bool) -> Result<(), Box<dyn std::error::Error>> {
         // if there is a match run with the plugin then run it
         if let Some(p) = plug {
-            p.execute(&self.args, self.verbose)
+            p.execute(&self.args, verbose, log)
         } else if let Some(cmd) = &self.command {
-            if self.verbose == true {
+            if verbose == true {
                 let s = self.args.iter().fold(String::new(), |x, y| { x + "\"" + &y + "\" " });
                 println!("running: {} {}", cmd, s);
             }
-            let mut proc = crate::util::filesystem::invoke(cmd, &self.args, Context::enable_windows_bat_file_match())?;
-            let exit_code = proc.wait()?;
+            let exit_code = crate::util::filesystem::invoke_teed(cmd, &self.args, log, Context::enable_windows_bat_file_match())?;
             match exit_code.code() {
-                Some(num) => if num != 0 { Err(AnyError(format!("exited with error code: {}", num)))? } else { Ok(()) },
-                None =>  Err(AnyError(format!("terminated by signal")))?
+                Some(num) => if num != 0 { Err(CategoryError(Category::Plugin, format!("exited with error code: {}", num)))? } else { Ok(()) },
+                None =>  Err(CategoryError(Category::Plugin, String::from("terminated by signal")))?
             }
         } else {
             Ok(())
@@ -148,6 +190,9 @@ impl Build {
     }
 }
 
+/// Directory within the build directory that keeps the output of each build.
+pub const LOG_DIR: &str = "logs";
+
 const HELP: &str = "\
 Execute a backend tool/workflow.
 
@@ -0,0 +1,787 @@
+use crate::Command;
+use crate::FromCli;
+use crate::commands::install::{self, Install};
+use crate::core::catalog::{CacheSlot, Catalog};
+use crate::core::config::Config;
+use crate::core::lockfile::LockFile;
+use crate::core::manifest::IpManifest;
+use crate::core::pkgid::PkgId;
+use crate::core::signature;
+use crate::core::store::Store;
+use crate::core::version::AnyVersion;
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Flag, Optional, Positional};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Category, CategoryError, Fault};
+use crate::util::filesystem;
+use crate::util::filesystem::Unit;
+use crate::util::progress::ProgressBar;
+use crate::util::sha256::Sha256Hash;
+use crate::util::theme;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq)]
+pub struct Cache {
+    command: Option<CacheSubcommand>,
+}
+
+impl FromCli for Cache {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Cache {
+            command: cli.check_command(Positional::new("command"))?,
+        });
+        command
+    }
+}
+
+impl Command for Cache {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        match &self.command {
+            Some(cmd) => cmd.exec(c),
+            None => {
+                println!("{}", HELP);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum CacheSubcommand {
+    List(List),
+    Size(Size),
+    Prune(Prune),
+    Gc(Gc),
+    Verify(Verify),
+    Restore(Restore),
+    Stats(Stats),
+}
+
+impl FromCli for CacheSubcommand {
+    fn from_cli<'c>(cli: &'c mut Cli<'_>) -> Result<Self, CliError<'c>> {
+        match cli.match_command(&[
+            "list",
+            "size",
+            "prune",
+            "gc",
+            "verify",
+            "restore",
+            "stats",
+        ])?.as_ref() {
+            "list" => Ok(CacheSubcommand::List(List::from_cli(cli)?)),
+            "size" => Ok(CacheSubcommand::Size(Size::from_cli(cli)?)),
+            "prune" => Ok(CacheSubcommand::Prune(Prune::from_cli(cli)?)),
+            "gc" => Ok(CacheSubcommand::Gc(Gc::from_cli(cli)?)),
+            "verify" => Ok(CacheSubcommand::Verify(Verify::from_cli(cli)?)),
+            "restore" => Ok(CacheSubcommand::Restore(Restore::from_cli(cli)?)),
+            "stats" => Ok(CacheSubcommand::Stats(Stats::from_cli(cli)?)),
+            _ => panic!("an unimplemented command was passed through!")
+        }
+    }
+}
+
+impl Command for CacheSubcommand {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        match self {
+            CacheSubcommand::List(l) => l.exec(c),
+            CacheSubcommand::Size(z) => z.exec(c),
+            CacheSubcommand::Prune(p) => p.exec(c),
+            CacheSubcommand::Gc(g) => g.exec(c),
+            CacheSubcommand::Verify(v) => v.exec(c),
+            CacheSubcommand::Restore(r) => r.exec(c),
+            CacheSubcommand::Stats(s) => s.exec(c),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct List;
+
+impl FromCli for List {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_LIST);
+        Ok(List)
+    }
+}
+
+impl Command for List {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        let mut rows = Vec::new();
+        for slot in cache_slots(c.get_cache_path())? {
+            let size = filesystem::compute_size(&slot, Unit::MegaBytes)?;
+            rows.push((IpManifest::from_path(&slot).ok(), slot, size));
+        }
+        println!("{}", List::fmt_table(&rows));
+        Ok(())
+    }
+}
+
+impl List {
+    fn fmt_table(rows: &[(Option<IpManifest>, PathBuf, f32)]) -> String {
+        let header = format!("\
+{:<36}{:<12}{:<12}{:<30}
+{:->36}{4:->12}{4:->12}{4:->30}\n",
+            "Package", "Version", "Size (MB)", "Slot", " ");
+        let mut body = String::new();
+        for (ip, slot, size) in rows {
+            let (pkgid, version) = match ip {
+                Some(ip) => (ip.get_pkgid().to_string(), ip.get_version().to_string()),
+                None => (String::from("?"), String::from("?")),
+            };
+            body.push_str(&format!("{:<36}{:<12}{:<12.2}{:<30}\n",
+                pkgid,
+                version,
+                size,
+                slot.file_name().unwrap().to_string_lossy(),
+            ));
+        }
+        header + &body
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Size;
+
+impl FromCli for Size {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_SIZE);
+        Ok(Size)
+    }
+}
+
+impl Command for Size {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        let slots = cache_slots(c.get_cache_path())?;
+        let repos = cache_slots(c.get_store_path())?;
+        println!("cache: {:.2} MB ({} installation(s))", total_size(&slots)?, slots.len());
+        println!("store: {:.2} MB ({} repository(s))", total_size(&repos)?, repos.len());
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Prune {
+    roots: Option<Vec<PathBuf>>,
+    dry_run: bool,
+}
+
+impl FromCli for Prune {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_PRUNE);
+        let command = Ok(Prune {
+            dry_run: cli.check_flag(Flag::new("dry-run"))?,
+            roots: cli.check_option_all(Optional::new("root").value("path"))?,
+        });
+        command
+    }
+}
+
+impl Command for Prune {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        let roots = self.project_roots(c.get_config(), c.get_development_paths())?;
+        if roots.is_empty() {
+            return Err(AnyError(String::from("no project roots to search for lockfiles\n\nTry setting `cache.roots` in the configuration or passing `--root <path>`")))?
+        }
+        self.run(c.get_cache_path(), &roots)
+    }
+}
+
+impl Prune {
+    /// Removes the installations in `cache` that no lockfile under the `roots` references.
+    fn run(&self, cache: &PathBuf, roots: &[PathBuf]) -> Result<(), Fault> {
+        // collect every installation referenced by a lockfile
+        let mut slot_names = HashSet::new();
+        let mut versions = HashSet::new();
+        for root in roots {
+            for ip in IpManifest::detect_all(root)? {
+                for entry in LockFile::from_path(&ip.get_root())?.inner() {
+                    // the lockfile's own ip has no checksum
+                    if entry.get_sum().is_some() {
+                        slot_names.insert(entry.to_cache_slot().as_ref().to_string());
+                        versions.insert((entry.get_name().clone(), entry.get_version().clone()));
+                    }
+                }
+            }
+        }
+
+        let mut removed = Vec::new();
+        for slot in cache_slots(cache)? {
+            // leave unreadable installations to `orbit cache verify`
+            let ip = match IpManifest::from_path(&slot) {
+                Ok(ip) => ip,
+                Err(_) => continue,
+            };
+            let name = slot.file_name().unwrap().to_string_lossy().to_string();
+            let referenced = slot_names.contains(&name) || 
+                // transformed installations are kept while their original version is referenced
+                (ip.is_dynamic() && versions.contains(&(ip.get_pkgid().clone(), ip.get_version().clone())));
+            if !referenced {
+                removed.push((slot, name));
+            }
+        }
+
+        let freed = total_size(&removed.iter().map(|r| r.0.clone()).collect::<Vec<PathBuf>>())?;
+        for (slot, name) in &removed {
+            match self.dry_run {
+                true => println!("info: would remove {}", name),
+                false => {
+                    println!("info: removing {}", name);
+                    filesystem::remove_all(slot)?;
+                }
+            }
+        }
+        println!("info: {} {} unreferenced installation(s) ({:.2} MB)", if self.dry_run { "found" } else { "pruned" }, removed.len(), freed);
+        Ok(())
+    }
+
+    /// Determines the directories to search for lockfiles.
+    /// 
+    /// Command-line roots take precedence over the `cache.roots` entry in the `config`,
+    /// which takes precedence over the `development` paths.
+    fn project_roots(&self, config: &Config, development: &[PathBuf]) -> Result<Vec<PathBuf>, Fault> {
+        if let Some(roots) = &self.roots {
+            return Ok(roots.clone())
+        }
+        let configured: Vec<PathBuf> = config.collect_as_array_of_str("cache", "roots")?
+            .into_iter()
+            .map(|(p, root)| PathBuf::from(filesystem::resolve_rel_path(root, p)))
+            .collect();
+        Ok(match configured.is_empty() {
+            false => configured,
+            true => development.to_vec(),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Gc {
+    dry_run: bool,
+}
+
+impl FromCli for Gc {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_GC);
+        let command = Ok(Gc {
+            dry_run: cli.check_flag(Flag::new("dry-run"))?,
+        });
+        command
+    }
+}
+
+impl Command for Gc {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        self.run(c.get_cache_path(), c.get_store_path())
+    }
+}
+
+impl Gc {
+    /// Removes the repositories and objects in the `store` that no installation in
+    /// the `cache` uses.
+    fn run(&self, cache: &PathBuf, store: &PathBuf) -> Result<(), Fault> {
+        // the store keeps repositories named by the hash of their ip's pkgid
+        let installed: HashSet<String> = cache_slots(cache)?
+            .iter()
+            .filter_map(|slot| IpManifest::from_path(slot).ok())
+            .map(|ip| ip.get_pkgid().into_hash().to_string())
+            .collect();
+
+        let unused: Vec<PathBuf> = cache_slots(store)?
+            .into_iter()
+            .filter(|repo| !installed.contains(repo.file_name().unwrap().to_string_lossy().as_ref()))
+            .collect();
+        let freed = total_size(&unused)?;
+        if !self.dry_run {
+            for repo in &unused {
+                std::fs::remove_dir_all(repo)?;
+            }
+        }
+        println!("info: {} {} unused store repository(s) ({:.2} MB)", if self.dry_run { "found" } else { "removed" }, unused.len(), freed);
+
+        // shared file contents are kept until no installation links to them
+        let unreferenced: Vec<(PathBuf, u64, u64)> = Store::new(store)
+            .objects(cache)?
+            .into_iter()
+            .filter(|(_, _, refs)| *refs == 0)
+            .collect();
+        let freed: u64 = unreferenced.iter().map(|(_, size, _)| size).sum();
+        if !self.dry_run {
+            for (object, _, _) in &unreferenced {
+                // windows refuses to delete read-only files
+                #[cfg(not(unix))]
+                filesystem::set_readonly(object, false)?;
+                std::fs::remove_file(object)?;
+                // only succeeds once the object's directory is empty
+                let _ = std::fs::remove_dir(object.parent().unwrap());
+            }
+        }
+        println!("info: {} {} unreferenced object(s) ({:.2} MB)", if self.dry_run { "found" } else { "removed" }, unreferenced.len(), freed as f32 / 1000000.0);
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Verify {
+    repair: bool,
+}
+
+impl FromCli for Verify {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_VERIFY);
+        let command = Ok(Verify {
+            repair: cli.check_flag(Flag::new("repair"))?,
+        });
+        command
+    }
+}
+
+impl Command for Verify {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        let slots = cache_slots(c.get_cache_path())?;
+
+        let mut corrupt = Vec::new();
+        let mut bar = ProgressBar::new("verifying", slots.len());
+        for slot in &slots {
+            let name = slot.file_name().unwrap().to_string_lossy().to_string();
+            bar.inc(&name);
+            if let Err(e) = verify_slot(slot) {
+                corrupt.push((slot, name, e));
+            }
+        }
+        bar.finish();
+
+        for (slot, name, e) in &corrupt {
+            println!("corrupt: {} ({})", name, e);
+            // modified installations can be brought back from their source
+            if let (SlotError::ChecksumMismatch(_, _), Ok(ip)) = (e, IpManifest::from_path(slot)) {
+                println!("{}", theme::hint(format!("restore it with `orbit cache restore {}@{}`", ip.get_pkgid(), ip.get_version())));
+            }
+        }
+        if corrupt.is_empty() {
+            println!("info: verified {} cache slot(s)", slots.len());
+            Ok(())
+        } else if self.repair {
+            for (slot, _, _) in &corrupt {
+                filesystem::remove_all(slot)?;
+            }
+            println!("info: removed {} corrupt cache slot(s)", corrupt.len());
+            Ok(())
+        } else {
+            Err(CategoryError(Category::Checksum, format!("found {} corrupt cache slot(s)\n\nTry `orbit cache restore <ip>@<version>` to reinstall a modified installation or `orbit cache verify --repair` to remove them", corrupt.len())))?
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Restore {
+    target: IpVersion,
+}
+
+impl FromCli for Restore {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_RESTORE);
+        let command = Ok(Restore {
+            target: cli.require_positional(Positional::new("ip@version"))?,
+        });
+        command
+    }
+}
+
+impl Command for Restore {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        let catalog = Catalog::new()
+            .store(c.get_store_path())
+            .trust(c.get_trust())
+            .installations(c.get_cache_path())?
+            .available(c.get_vendors())?;
+        let ids = catalog.inner().keys().collect();
+        let target = crate::core::ip::find_ip(&self.target.ip, ids)?;
+        let status = catalog.inner().get(&target).unwrap();
+        let version = match status.get_install(&self.target.version) {
+            Some(ip) => ip.get_version().clone(),
+            None => return Err(AnyError(format!("ip '{}' is not installed to the cache under version '{}'", target, self.target.version)))?,
+        };
+        // every installation of the version, including its transformed copies
+        let dirty: Vec<&IpManifest> = status.get_installations()
+            .iter()
+            .filter(|ip| ip.get_version() == &version && verify_slot(&ip.get_root()).is_err())
+            .collect();
+        if dirty.is_empty() {
+            println!("info: ip '{}' v{} is intact in the cache", target, version);
+            return Ok(())
+        }
+
+        // reinstall the version from its original source
+        let temp_dir = tempfile::tempdir()?;
+        let spec = AnyVersion::Specific(version.to_partial_version());
+        let source = install::fetch_install_path(&target, &spec, &catalog, false, &temp_dir)?;
+        let restored = Install::install(&source, &spec, c.get_cache_path(), true, catalog.get_store())?;
+        if let Err(e) = signature::verify(&restored, catalog.get_trust(), catalog.get_vendor(&target)) {
+            restored.remove()?;
+            return Err(e)
+        }
+        // transformed copies are recreated from the restored installation when needed
+        for ip in dirty {
+            if ip.get_root().exists() && !filesystem::paths_equal(ip.get_root(), restored.get_root()) {
+                filesystem::remove_all(&ip.get_root())?;
+            }
+        }
+        println!("info: restored ip '{}' v{} in the cache", target, version);
+        Ok(())
+    }
+}
+
+/// An ip and one of its versions written as `<ip>@<version>`.
+#[derive(Debug, PartialEq)]
+struct IpVersion {
+    ip: PkgId,
+    version: AnyVersion,
+}
+
+impl FromStr for IpVersion {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (ip, version) = match s.split_once('@') {
+            Some(pair) => pair,
+            None => return Err(AnyError(format!("missing '@' between the ip and version in '{}'", s))),
+        };
+        let ip = PkgId::from_str(ip).map_err(|e| AnyError(e.to_string()))?;
+        // the version may be written with a leading 'v'
+        let version = AnyVersion::from_str(version.strip_prefix('v').unwrap_or(version)).map_err(|e| AnyError(e.to_string()))?;
+        if version == AnyVersion::Dev {
+            return Err(AnyError(String::from("a development version is not installed to the cache")))
+        }
+        Ok(Self { ip, version })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Stats;
+
+impl FromCli for Stats {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_STATS);
+        Ok(Stats)
+    }
+}
+
+impl Command for Stats {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        let slots = cache_slots(c.get_cache_path())?;
+        let objects = Store::new(c.get_store_path()).objects(c.get_cache_path())?;
+
+        let unique: u64 = objects.iter().map(|(_, size, _)| size).sum();
+        let shared = objects.iter().filter(|(_, _, refs)| *refs > 1).count();
+        let links: u64 = objects.iter().map(|(_, _, refs)| refs).sum();
+        // every link after the first would otherwise be its own copy
+        let saved: u64 = objects.iter().map(|(_, size, refs)| size * refs.saturating_sub(1)).sum();
+        let total = total_size(&slots)?;
+        let mb = |bytes: u64| bytes as f32 / 1000000.0;
+
+        println!("installations: {}", slots.len());
+        println!("objects: {} ({:.2} MB)", objects.len(), mb(unique));
+        println!("shared objects: {} ({} installed file(s) link to an object)", shared, links);
+        println!("saved: {:.2} MB of {:.2} MB ({:.1}%)", mb(saved), total, match total > 0.0 {
+            true => mb(saved) / total * 100.0,
+            false => 0.0,
+        });
+        Ok(())
+    }
+}
+
+/// Collects the directories within the cache at `root`, sorted by name.
+///
+/// Hidden directories are skipped.
+fn cache_slots(root: &PathBuf) -> Result<Vec<PathBuf>, Fault> {
+    let mut slots = Vec::new();
+    if !root.exists() {
+        return Ok(slots)
+    }
+    for entry in std::fs::read_dir(root)? {
+        let path = entry?.path();
+        if path.is_dir() && !path.file_name().unwrap().to_string_lossy().starts_with('.') {
+            slots.push(path);
+        }
+    }
+    slots.sort();
+    Ok(slots)
+}
+
+/// Sums the sizes of the directories `paths` in megabytes.
+fn total_size(paths: &[PathBuf]) -> Result<f32, Fault> {
+    let mut sum = 0.0;
+    for p in paths {
+        sum += filesystem::compute_size(p, Unit::MegaBytes)?;
+    }
+    Ok(sum)
+}
+
+/// Checks the installation at `slot` against its stored checksum proof.
+fn verify_slot(slot: &PathBuf) -> Result<(), SlotError> {
+    let ip = match IpManifest::from_path(slot) {
+        Ok(ip) => ip,
+        Err(e) => return Err(SlotError::BadManifest(e.to_string())),
+    };
+    let proof = match ip.read_checksum_proof() {
+        Some(sum) => sum,
+        None => return Err(SlotError::MissingChecksum),
+    };
+    let computed = ip.compute_checksum();
+    if computed != proof {
+        return Err(SlotError::ChecksumMismatch(proof, computed))
+    }
+    // the slot's name is derived from the checksum
+    let expected = CacheSlot::new(ip.get_pkgid().get_name(), ip.get_version(), &proof);
+    if slot.file_name().unwrap().to_string_lossy() != expected.as_ref() {
+        return Err(SlotError::BadSlotName(expected.as_ref().to_string()))
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum SlotError {
+    BadManifest(String),
+    MissingChecksum,
+    /// expected, computed
+    ChecksumMismatch(Sha256Hash, Sha256Hash),
+    BadSlotName(String),
+}
+
+impl std::error::Error for SlotError {}
+
+impl std::fmt::Display for SlotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadManifest(e) => write!(f, "unreadable manifest: {}", e),
+            Self::MissingChecksum => write!(f, "missing checksum proof"),
+            Self::ChecksumMismatch(exp, got) => write!(f, "checksum mismatch: expected {} but computed {}", exp, got),
+            Self::BadSlotName(exp) => write!(f, "slot name does not match its contents; expected '{}'", exp),
+        }
+    }
+}
+
+const HELP: &str = "\
+Manage the ip installed to the cache.
+
+Usage:
+    orbit cache <command> [options]
+
+Commands:
+    list            view installations and their sizes
+    size            view the disk space used by the cache and store
+    prune           remove installations not referenced by any lockfile
+    gc              remove store repositories and shared files with no installations
+    verify          check installations against their checksums
+    restore         reinstall a modified installation from its source
+    stats           view the disk space saved by sharing files
+
+Use 'orbit help cache' to learn more about the command.
+";
+
+const HELP_VERIFY: &str = "\
+Check installations against their checksums.
+
+Usage:
+    orbit cache verify [options]
+
+Options:
+    --repair        remove corrupt installations from the cache
+
+Use 'orbit help cache' to learn more about the command.
+";
+
+const HELP_RESTORE: &str = "\
+Reinstall a modified installation from its source.
+
+Usage:
+    orbit cache restore <ip>@<version>
+
+Args:
+    <ip>@<version>  the installed ip and version to restore
+
+Use 'orbit help cache' to learn more about the command.
+";
+
+const HELP_LIST: &str = "\
+View installations and their sizes.
+
+Usage:
+    orbit cache list
+
+Use 'orbit help cache' to learn more about the command.
+";
+
+const HELP_SIZE: &str = "\
+View the disk space used by the cache and store.
+
+Usage:
+    orbit cache size
+
+Use 'orbit help cache' to learn more about the command.
+";
+
+const HELP_PRUNE: &str = "\
+Remove installations not referenced by any lockfile.
+
+Usage:
+    orbit cache prune [options]
+
+Options:
+    --root <path>...    directory to search for ip lockfiles
+    --dry-run           list the installations without removing them
+
+Use 'orbit help cache' to learn more about the command.
+";
+
+const HELP_GC: &str = "\
+Remove store repositories and shared files with no installations.
+
+Usage:
+    orbit cache gc [options]
+
+Options:
+    --dry-run           count the repositories and files without removing them
+
+Use 'orbit help cache' to learn more about the command.
+";
+
+const HELP_STATS: &str = "\
+View the disk space saved by sharing files.
+
+Usage:
+    orbit cache stats
+
+Use 'orbit help cache' to learn more about the command.
+";
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::manifest::IP_MANIFEST_FILE;
+    use crate::core::lockfile::IP_LOCK_FILE;
+    use tempfile::tempdir;
+
+    /// Writes a manifest for version `version` of the gates ip into `dir`.
+    fn write_gates(dir: &PathBuf, version: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join(IP_MANIFEST_FILE), format!("[ip]\nname = \"gates\"\nlibrary = \"rary\"\nversion = \"{}\"\nvendor = \"ks-tech\"\n", version)).unwrap();
+        std::fs::write(dir.join("gate.vhd"), format!("-- v{}\nentity and_gate", version)).unwrap();
+    }
+
+    #[test]
+    fn prune_unreferenced_slots() {
+        let home = tempdir().unwrap();
+        let cache = home.path().join("cache");
+        let projects = home.path().join("projects");
+
+        // a project locks v1.0.0 of the gates ip
+        let project = projects.join("top");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join(IP_MANIFEST_FILE), "[ip]\nname = \"top\"\nlibrary = \"rary\"\nversion = \"0.1.0\"\nvendor = \"ks-tech\"\n").unwrap();
+        std::fs::write(project.join(IP_LOCK_FILE), "version = 2\n\n[[ip]]\nname = \"ks-tech.rary.top\"\nversion = \"0.1.0\"\ndependencies = [{ name = \"ks-tech.rary.gates\", version = \"1\", resolved = \"1.0.0\" }]\n\n[[ip]]\nname = \"ks-tech.rary.gates\"\nversion = \"1.0.0\"\nsum = \"c4cb4e9c5b7e0e8b5f5b3bfe7b63bd1e2e48e4d0e2a5f0e5d7d4e0b5a4f3e2d1\"\n").unwrap();
+
+        let referenced = cache.join("gates-1.0.0-c4cb4e9c5b");
+        let unreferenced = cache.join("gates-0.9.0-0123456789");
+        let unreadable = cache.join("gates-0.8.0-9876543210");
+        write_gates(&referenced, "1.0.0");
+        write_gates(&unreferenced, "0.9.0");
+        std::fs::create_dir_all(&unreadable).unwrap();
+
+        // a dry run removes nothing
+        Prune { roots: None, dry_run: true }.run(&cache, &[projects.clone()]).unwrap();
+        assert_eq!(unreferenced.exists(), true);
+
+        Prune { roots: None, dry_run: false }.run(&cache, &[projects.clone()]).unwrap();
+        assert_eq!(referenced.exists(), true);
+        assert_eq!(unreferenced.exists(), false);
+        // unreadable installations are left for `orbit cache verify`
+        assert_eq!(unreadable.exists(), true);
+
+        // without any lockfiles every readable installation is unreferenced
+        Prune { roots: None, dry_run: false }.run(&cache, &[home.path().join("empty")]).unwrap();
+        assert_eq!(referenced.exists(), false);
+    }
+
+    #[test]
+    fn prune_project_roots() {
+        let home = tempdir().unwrap();
+        let development = vec![home.path().join("dev")];
+        let config_file = home.path().join("config.toml");
+        std::fs::write(&config_file, "").unwrap();
+        let config = Config::from_path(&config_file).unwrap();
+
+        // the development paths are searched when nothing else is given
+        let prune = Prune { roots: None, dry_run: false };
+        assert_eq!(prune.project_roots(&config, &development).unwrap(), development);
+
+        // configured roots are resolved relative to their configuration file
+        std::fs::create_dir_all(home.path().join("projects")).unwrap();
+        std::fs::write(&config_file, "[cache]\nroots = [\"projects\"]\n").unwrap();
+        let config = Config::from_path(&config_file).unwrap();
+        let roots = prune.project_roots(&config, &development).unwrap();
+        assert_eq!(roots, vec![filesystem::normalize_path(home.path().join("projects"))]);
+
+        // command-line roots take precedence
+        let prune = Prune { roots: Some(vec![PathBuf::from("ws")]), dry_run: false };
+        assert_eq!(prune.project_roots(&config, &development).unwrap(), vec![PathBuf::from("ws")]);
+    }
+
+    #[test]
+    fn gc_unused_store_entries() {
+        let home = tempdir().unwrap();
+        let cache = home.path().join("cache");
+        let root = home.path().join("store");
+        let slot = cache.join("gates-1.0.0-c4cb4e9c5b");
+        write_gates(&slot, "1.0.0");
+
+        let used = root.join(PkgId::from_str("ks-tech.rary.gates").unwrap().into_hash().to_string());
+        let unused = root.join(PkgId::from_str("ks-tech.rary.mux").unwrap().into_hash().to_string());
+        std::fs::create_dir_all(&used).unwrap();
+        std::fs::create_dir_all(&unused).unwrap();
+
+        // the installation's files become objects and an orphaned object is left behind
+        let store = Store::new(&root);
+        store.share(&slot).unwrap();
+        let orphan = store.get_objects_path().join("ab").join("cdef0123");
+        std::fs::create_dir_all(orphan.parent().unwrap()).unwrap();
+        std::fs::write(&orphan, "entity mux").unwrap();
+        let objects = store.objects(&cache).unwrap().len();
+
+        // a dry run removes nothing
+        Gc { dry_run: true }.run(&cache, &root).unwrap();
+        assert_eq!(unused.exists(), true);
+        assert_eq!(orphan.exists(), true);
+
+        Gc { dry_run: false }.run(&cache, &root).unwrap();
+        assert_eq!(used.exists(), true);
+        assert_eq!(unused.exists(), false);
+        assert_eq!(orphan.exists(), false);
+        assert_eq!(orphan.parent().unwrap().exists(), false);
+        // the objects linked to the installation are kept
+        let remaining = store.objects(&cache).unwrap();
+        assert_eq!(remaining.len(), objects - 1);
+        assert!(remaining.iter().all(|(_, _, refs)| *refs > 0));
+        assert_eq!(std::fs::read_to_string(slot.join("gate.vhd")).unwrap(), "-- v1.0.0\nentity and_gate");
+    }
+}
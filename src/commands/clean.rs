@@ -0,0 +1,248 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::lockfile::IP_LOCK_FILE;
+use crate::core::manifest::IpManifest;
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Flag, Optional};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
+use crate::util::prompt;
+use super::plan::Plan;
+use git2::{Repository, Status};
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq)]
+pub struct Clean {
+    build: bool,
+    lock: bool,
+    all: bool,
+    build_dir: Option<String>,
+    disable_ssh: bool,
+}
+
+impl FromCli for Clean {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Clean {
+            build: cli.check_flag(Flag::new("build"))?,
+            lock: cli.check_flag(Flag::new("lock"))?,
+            all: cli.check_flag(Flag::new("all"))?,
+            build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
+            disable_ssh: cli.check_flag(Flag::new("disable-ssh"))?,
+        });
+        command
+    }
+}
+
+impl Command for Clean {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+        let ip = IpManifest::from_path(c.get_ip_path().unwrap())?;
+        let repo = Repository::open(ip.get_root()).ok();
+        let force = c.force;
+
+        // the build directory is cleaned when no scope is given
+        let build = self.build || self.all || !self.lock;
+        if build {
+            // determine the build directory (command-line arg overrides configuration setting)
+            let b_dir = match &self.build_dir {
+                Some(dir) => dir,
+                None => c.get_build_dir(),
+            };
+            Self::clean_build(&ip.get_root().join(b_dir), repo.as_ref(), force)?;
+        }
+        if self.lock || self.all {
+            let backup = Self::clean_lock(&ip.get_root(), repo.as_ref(), force)?;
+            Self::regenerate_lock(&ip.get_root(), backup, || Plan::for_lock(self.disable_ssh).exec(c))?;
+            println!("info: regenerated {}", IP_LOCK_FILE);
+        }
+        Ok(())
+    }
+}
+
+impl Clean {
+    /// Removes the build directory at `build_path`.
+    ///
+    /// Files the ip's repository tracks are kept unless the user confirms their
+    /// removal or `force` is set.
+    fn clean_build(build_path: &PathBuf, repo: Option<&Repository>, force: bool) -> Result<(), Fault> {
+        if !build_path.exists() {
+            println!("info: build directory {} does not exist", filesystem::normalize_path(build_path.clone()).display());
+            return Ok(())
+        }
+        let tracked: Vec<String> = Self::build_files(build_path)?
+            .into_iter()
+            .filter(|f| repo.map(|r| Self::is_tracked(r, Path::new(f))).unwrap_or(false))
+            .collect();
+
+        let keep = match tracked.is_empty() || force {
+            true => false,
+            false => {
+                println!("info: the build directory contains {} file(s) tracked by the ip's repository:", tracked.len());
+                for f in &tracked {
+                    println!("    {}", f);
+                }
+                !prompt::prompt("remove these files")?
+            }
+        };
+        match keep {
+            true => {
+                // remove everything except the tracked files
+                for f in Self::build_files(build_path)? {
+                    if !tracked.contains(&f) {
+                        std::fs::remove_file(&f)?;
+                    }
+                }
+                // deepest directories come last; only the empty ones can be removed
+                let dirs: Vec<PathBuf> = WalkBuilder::new(build_path)
+                    .standard_filters(false)
+                    .build()
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.into_path())
+                    .filter(|p| p.is_dir())
+                    .collect();
+                for dir in dirs.iter().rev() {
+                    let _ = std::fs::remove_dir(dir);
+                }
+                println!("info: cleaned build directory {} (kept {} tracked file(s))", filesystem::normalize_path(build_path.clone()).display(), tracked.len());
+            },
+            false => {
+                std::fs::remove_dir_all(build_path)?;
+                println!("info: removed build directory {}", filesystem::normalize_path(build_path.clone()).display());
+            },
+        }
+        Ok(())
+    }
+
+    /// Moves the lock file at the ip's `root` aside so it can be written again.
+    ///
+    /// Returns the path the old lock file was moved to, if one existed. Errors if the
+    /// lock file has uncommitted changes and the user does not confirm its
+    /// replacement, unless `force` is set.
+    fn clean_lock(root: &Path, repo: Option<&Repository>, force: bool) -> Result<Option<PathBuf>, Fault> {
+        let lock_path = root.join(IP_LOCK_FILE);
+        if !lock_path.exists() {
+            return Ok(None)
+        }
+        let modified = repo
+            .and_then(|r| r.status_file(Path::new(IP_LOCK_FILE)).ok())
+            .map(|st| st.intersects(Status::WT_MODIFIED | Status::INDEX_MODIFIED))
+            .unwrap_or(false);
+        if modified && !force && !prompt::prompt(&format!("{} has uncommitted changes; is it okay to replace it", IP_LOCK_FILE))? {
+            return Err(AnyError(format!("kept {} with uncommitted changes", IP_LOCK_FILE)))?
+        }
+        let backup = root.join(format!("{}.bak", IP_LOCK_FILE));
+        std::fs::rename(&lock_path, &backup)?;
+        Ok(Some(backup))
+    }
+
+    /// Writes the lock file at the ip's `root` again with `write`.
+    ///
+    /// The old lock file at `backup` is removed once `write` succeeds, and put back
+    /// in place if it fails.
+    fn regenerate_lock<F>(root: &Path, backup: Option<PathBuf>, write: F) -> Result<(), Fault>
+    where F: FnOnce() -> Result<(), Fault> {
+        let result = write();
+        if let Some(backup) = backup {
+            match &result {
+                Ok(()) => std::fs::remove_file(&backup)?,
+                Err(_) => {
+                    std::fs::rename(&backup, root.join(IP_LOCK_FILE))?;
+                    println!("info: restored the previous {}", IP_LOCK_FILE);
+                },
+            }
+        }
+        result
+    }
+
+    /// Lists every file under `build_path`, including ignored files.
+    fn build_files(build_path: &Path) -> Result<Vec<String>, Fault> {
+        let mut files = Vec::new();
+        for entry in WalkBuilder::new(build_path).standard_filters(false).build() {
+            let entry = entry?;
+            if entry.path().is_file() {
+                files.push(filesystem::to_standard(entry.path()));
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Checks if the `file` is in the index of the `repo`.
+    fn is_tracked(repo: &Repository, file: &Path) -> bool {
+        let workdir = match repo.workdir() {
+            Some(w) => w,
+            None => return false,
+        };
+        let relative = filesystem::remove_base(&PathBuf::from(filesystem::to_standard(workdir)), &file.to_path_buf());
+        match repo.index() {
+            Ok(index) => index.get_path(&relative, 0).is_some(),
+            Err(_) => false,
+        }
+    }
+}
+
+const HELP: &str = "\
+Remove files generated for the current ip.
+
+Usage:
+    orbit clean [options]
+
+Options:
+    --build             remove the build directory (default)
+    --lock              write the lock file again from the latest dependencies
+    --all               perform every clean operation
+    --force             remove files with user changes without asking
+    --build-dir <dir>   the build directory to remove
+    --disable-ssh       convert SSH repositories to HTTPS for dependencies
+
+Use 'orbit help clean' to learn more about the command.
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn failed_regeneration_keeps_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join(IP_LOCK_FILE);
+        std::fs::write(&lock_path, "# old lock\n").unwrap();
+
+        let backup = Clean::clean_lock(dir.path(), None, true).unwrap();
+        assert_eq!(lock_path.exists(), false);
+        let result = Clean::regenerate_lock(dir.path(), backup.clone(), || {
+            // a partially written lock file is replaced by the old one
+            std::fs::write(&lock_path, "# partial\n").unwrap();
+            Err(AnyError(format!("failed to resolve dependencies")))?
+        });
+        assert_eq!(result.is_err(), true);
+        assert_eq!(std::fs::read_to_string(&lock_path).unwrap(), "# old lock\n");
+        assert_eq!(backup.unwrap().exists(), false);
+    }
+
+    #[test]
+    fn regeneration_replaces_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join(IP_LOCK_FILE);
+        std::fs::write(&lock_path, "# old lock\n").unwrap();
+
+        let backup = Clean::clean_lock(dir.path(), None, true).unwrap();
+        Clean::regenerate_lock(dir.path(), backup.clone(), || {
+            std::fs::write(&lock_path, "# new lock\n")?;
+            Ok(())
+        }).unwrap();
+        assert_eq!(std::fs::read_to_string(&lock_path).unwrap(), "# new lock\n");
+        assert_eq!(backup.unwrap().exists(), false);
+
+        // nothing to restore when there was no lock file
+        std::fs::remove_file(&lock_path).unwrap();
+        assert_eq!(Clean::clean_lock(dir.path(), None, true).unwrap(), None);
+    }
+}
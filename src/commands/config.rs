@@ -57,7 +57,7 @@ impl Command for Config {
         }
         let mut cfg = if self.local == true {
             match c.get_ip_path() {
-                Some(path) => config::Config::from_path(&path.join(".orbit").join(config::CONFIG_FILE))?,
+                Some(path) => config::Config::from_path(&path.join(config::LOCAL_CONFIG_DIR).join(config::CONFIG_FILE))?,
                 None => return Err(AnyError(format!("no ip detected in the current directory to modify local configurations")))?,
             }
         } else {
@@ -76,6 +76,7 @@ impl Config {
             match entry.0.as_ref() {
                 "include" => cfg.append_include(&entry.1),
                 "vendor.index" => cfg.append_vendor_index(&entry.1),
+                "core.paths" => cfg.append_dev_path(&entry.1),
                 _ => return Err(AnyError(format!("unsupported key '{}' cannot be appended", entry.0)))?
             };
         }
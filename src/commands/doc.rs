@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::Command;
+use crate::FromCli;
+use crate::core::catalog::Catalog;
+use crate::core::ip;
+use crate::core::manifest::IpManifest;
+use crate::core::vhdl::doc::{self, UnitDoc};
+use crate::core::vhdl::index::IdentifierIndex;
+use crate::core::vhdl::symbol::CompoundIdentifier;
+use crate::core::vhdl::token::Identifier;
+use crate::interface::cli::Cli;
+use crate::interface::arg::Optional;
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use super::plan::Plan;
+
+#[derive(Debug, PartialEq)]
+enum DocFormat {
+    Html,
+    Markdown,
+}
+
+impl std::str::FromStr for DocFormat {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "html" => Ok(Self::Html),
+            "markdown" => Ok(Self::Markdown),
+            _ => Err(AnyError(format!("unknown format '{}'; expecting 'html' or 'markdown'", s))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Doc {
+    format: DocFormat,
+    build_dir: Option<String>,
+}
+
+impl FromCli for Doc {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        Ok(Doc {
+            format: cli.check_option_default(Optional::new("format").value("fmt").choices(&["html", "markdown"]).default("html"))?,
+            build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
+        })
+    }
+}
+
+impl Command for Doc {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+        let ip = IpManifest::from_path(c.get_ip_path().unwrap())?;
+
+        let catalog = Catalog::new()
+            .store(c.get_store_path())
+            .aliases(c.get_aliases())
+            .development(c.get_development_paths())?
+            .installations(c.get_cache_path())?;
+        let ip_graph = ip::compute_final_ip_graph(&ip, &catalog)?;
+        let files = ip::build_ip_file_list(&ip_graph);
+        let graph = Plan::build_full_graph(&files, ip.get_providers())?;
+
+        // label units from dependencies with their library
+        let label = |key: &CompoundIdentifier| -> String {
+            match key.get_prefix() {
+                Some(lib) if lib != &Identifier::new_working() => key.to_string(),
+                _ => key.get_suffix().to_string(),
+            }
+        };
+
+        let root = ip.get_root().display().to_string().replace('\\', "/");
+        let mut sources: HashMap<String, IdentifierIndex<UnitDoc>> = HashMap::new();
+        let mut units = Vec::new();
+        let mut hierarchy = Vec::new();
+        for (key, node) in graph.get_map() {
+            // only document the units declared in the current ip
+            if node.as_ref().is_placeholder() {
+                continue
+            }
+            let file = node.as_ref().get_associated_files()[0];
+            if file.get_ip_manifest().get_pkgid() != ip.get_pkgid() {
+                continue
+            }
+            if !sources.contains_key(file.get_file()) {
+                let text = std::fs::read_to_string(file.get_file())?;
+                sources.insert(file.get_file().clone(), doc::read(&text).unwrap_or_default().into_iter().map(|d| (d.get_name().clone(), d)).collect());
+            }
+            let docs = sources.get_mut(file.get_file()).unwrap();
+            let unit = match docs.remove(key.get_suffix()).into_iter().next() {
+                Some(u) => u,
+                None => continue,
+            };
+            let index = node.index();
+            let mut dependencies: Vec<String> = graph.get_graph().predecessors(index).map(|n| label(graph.get_key_by_index(n).unwrap())).collect();
+            let mut dependents: Vec<String> = graph.get_graph().successors(index).map(|n| label(graph.get_key_by_index(n).unwrap())).collect();
+            dependencies.sort();
+            dependents.sort();
+            // each unit that no other unit depends on roots a tree of its dependencies
+            if dependents.is_empty() {
+                let tree = graph.get_graph().treeview(index)
+                    .into_iter()
+                    .map(|(twig, n)| format!("{}{}", twig, label(graph.get_key_by_index(n).unwrap())))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                hierarchy.push(tree);
+            }
+            units.push(UnitPage {
+                source: file.get_file().strip_prefix(&root).map(|f| f.trim_start_matches('/')).unwrap_or(file.get_file()).to_string(),
+                unit,
+                dependencies,
+                dependents,
+            });
+        }
+        if units.is_empty() {
+            return Err(AnyError(format!("no primary design units found in ip '{}'", ip.get_pkgid())))?
+        }
+        units.sort_by_key(|u| u.unit.get_name().to_string());
+        hierarchy.sort();
+
+        let title = ip.get_pkgid().to_string();
+        let summary = ip.get_summary().map(|s| s.as_str()).unwrap_or("");
+        let (name, text) = match self.format {
+            DocFormat::Html => ("index.html", render_html(&title, summary, &hierarchy, &units)),
+            DocFormat::Markdown => ("index.md", render_markdown(&title, summary, &hierarchy, &units)),
+        };
+        let dir = PathBuf::from(self.build_dir.as_ref().unwrap_or(c.get_build_dir())).join("doc");
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(name), text)?;
+        println!("info: documented {} unit(s) in {}", units.len(), dir.join(name).display());
+        Ok(())
+    }
+}
+
+/// A primary design unit and its place in the design hierarchy.
+struct UnitPage {
+    unit: UnitDoc,
+    source: String,
+    dependencies: Vec<String>,
+    dependents: Vec<String>,
+}
+
+/// Creates the fragment identifier for the section documenting `name`.
+fn anchor(name: &str) -> String {
+    name.to_lowercase().chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '-' }).collect()
+}
+
+/// Checks if the unit `name` is documented in `units`, and therefore can be linked.
+fn is_documented(units: &[UnitPage], name: &str) -> bool {
+    units.iter().any(|u| u.unit.get_name().to_string() == name)
+}
+
+fn render_markdown(title: &str, summary: &str, hierarchy: &[String], units: &[UnitPage]) -> String {
+    let links = |names: &[String]| -> String {
+        names.iter()
+            .map(|n| match is_documented(units, n) {
+                true => format!("[`{}`](#{})", n, anchor(n)),
+                false => format!("`{}`", n),
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    let mut result = format!("# {}\n\n", title);
+    if !summary.is_empty() {
+        result.push_str(&format!("{}\n\n", summary));
+    }
+    result.push_str("## Units\n\n");
+    for page in units {
+        result.push_str(&format!("- {} [`{}`](#{})\n", page.unit.get_kind(), page.unit.get_name(), anchor(&page.unit.get_name().to_string())));
+    }
+    if !hierarchy.is_empty() {
+        result.push_str(&format!("\n## Hierarchy\n\n```\n{}\n```\n", hierarchy.join("\n\n")));
+    }
+    for page in units {
+        let name = page.unit.get_name().to_string();
+        result.push_str(&format!("\n<a id=\"{}\"></a>\n\n## {} `{}`\n\n", anchor(&name), page.unit.get_kind(), name));
+        if !page.unit.get_comment().is_empty() {
+            result.push_str(&format!("{}\n\n", page.unit.get_comment()));
+        }
+        result.push_str(&format!("```vhdl\n{}\n```\n\n", page.unit.get_declaration()));
+        result.push_str(&format!("Source: `{}`\n", page.source));
+        if !page.dependencies.is_empty() {
+            result.push_str(&format!("\nDepends on: {}\n", links(&page.dependencies)));
+        }
+        if !page.dependents.is_empty() {
+            result.push_str(&format!("\nUsed by: {}\n", links(&page.dependents)));
+        }
+    }
+    result
+}
+
+/// Escapes the characters reserved in html text.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn render_html(title: &str, summary: &str, hierarchy: &[String], units: &[UnitPage]) -> String {
+    let links = |names: &[String]| -> String {
+        names.iter()
+            .map(|n| match is_documented(units, n) {
+                true => format!("<a href=\"#{}\"><code>{}</code></a>", anchor(n), escape(n)),
+                false => format!("<code>{}</code>", escape(n)),
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    let mut result = format!("<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{0}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 60em; margin: auto; padding: 1em; }}
+pre {{ background: #f4f4f4; padding: 0.75em; overflow-x: auto; }}
+section {{ border-top: 1px solid #ddd; }}
+</style>
+</head>
+<body>
+<h1>{0}</h1>\n", escape(title));
+    if !summary.is_empty() {
+        result.push_str(&format!("<p>{}</p>\n", escape(summary)));
+    }
+    result.push_str("<h2>Units</h2>\n<ul>\n");
+    for page in units {
+        let name = page.unit.get_name().to_string();
+        result.push_str(&format!("<li>{} <a href=\"#{}\"><code>{}</code></a></li>\n", page.unit.get_kind(), anchor(&name), escape(&name)));
+    }
+    result.push_str("</ul>\n");
+    if !hierarchy.is_empty() {
+        result.push_str(&format!("<h2>Hierarchy</h2>\n<pre>{}</pre>\n", escape(&hierarchy.join("\n\n"))));
+    }
+    for page in units {
+        let name = page.unit.get_name().to_string();
+        result.push_str(&format!("<section id=\"{}\">\n<h2>{} <code>{}</code></h2>\n", anchor(&name), page.unit.get_kind(), escape(&name)));
+        // paragraphs of the doc comment are separated by blank lines
+        for paragraph in page.unit.get_comment().split("\n\n").filter(|p| !p.trim().is_empty()) {
+            result.push_str(&format!("<p>{}</p>\n", escape(paragraph)));
+        }
+        result.push_str(&format!("<pre><code>{}</code></pre>\n", escape(page.unit.get_declaration())));
+        result.push_str(&format!("<p>Source: <code>{}</code></p>\n", escape(&page.source)));
+        if !page.dependencies.is_empty() {
+            result.push_str(&format!("<p>Depends on: {}</p>\n", links(&page.dependencies)));
+        }
+        if !page.dependents.is_empty() {
+            result.push_str(&format!("<p>Used by: {}</p>\n", links(&page.dependents)));
+        }
+        result.push_str("</section>\n");
+    }
+    result.push_str("</body>\n</html>\n");
+    result
+}
+
+const HELP: &str = "\
+Generate documentation for the design units in the current ip.
+
+Usage:
+    orbit doc [options]
+
+Options:
+    --format <fmt>      write 'html' or 'markdown' (default: html)
+    --build-dir <dir>   the build directory to write doc/ into
+
+Use 'orbit help doc' to learn more about the command.
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pages() -> Vec<UnitPage> {
+        let text = "\
+-- Drives <q> from a & b.
+entity and_gate is
+    port (a, b : in bit; q : out bit);
+end entity;
+
+entity top is end entity;
+";
+        let mut docs = doc::read(text).unwrap().into_iter();
+        vec![
+            UnitPage {
+                unit: docs.next().unwrap(),
+                source: String::from("rtl/and_gate.vhd"),
+                dependencies: Vec::new(),
+                dependents: vec![String::from("top")],
+            },
+            UnitPage {
+                unit: docs.next().unwrap(),
+                source: String::from("rtl/top.vhd"),
+                dependencies: vec![String::from("and_gate"), String::from("gates.or_gate")],
+                dependents: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn markdown() {
+        let text = render_markdown("ks-tech.rary.top", "", &[String::from("top\n└─ and_gate")], &pages());
+        assert!(text.starts_with("# ks-tech.rary.top\n\n## Units\n\n- entity [`and_gate`](#and_gate)\n- entity [`top`](#top)\n"));
+        assert!(text.contains("## entity `and_gate`\n\nDrives <q> from a & b.\n\n```vhdl\nentity and_gate is\n"));
+        assert!(text.contains("Used by: [`top`](#top)\n"));
+        assert!(text.contains("Depends on: [`and_gate`](#and_gate), `gates.or_gate`\n"));
+    }
+
+    #[test]
+    fn html() {
+        let text = render_html("ks-tech.rary.top", "A & B", &[], &pages());
+        assert!(text.contains("<p>A &amp; B</p>\n"));
+        assert!(text.contains("<section id=\"and_gate\">\n<h2>entity <code>and_gate</code></h2>\n<p>Drives &lt;q&gt; from a &amp; b.</p>\n"));
+        assert!(text.contains("<p>Depends on: <a href=\"#and_gate\"><code>and_gate</code></a>, <code>gates.or_gate</code></p>\n"));
+        assert!(!text.contains("Hierarchy"));
+    }
+}
\ No newline at end of file
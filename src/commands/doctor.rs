@@ -0,0 +1,277 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::config::CONFIG_FILE;
+use crate::core::context::Context;
+use crate::core::extgit::ExtGit;
+use crate::core::layout::{Layout, LAYOUT_VERSION};
+use crate::core::manifest::{self, IpManifest, IP_MANIFEST_FILE};
+use crate::core::network::{self, Network};
+use crate::core::vendor::VendorManifest;
+use crate::interface::cli::Cli;
+use crate::interface::arg::Flag;
+use crate::interface::errors::CliError;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::environment;
+use crate::util::filesystem;
+use super::edit::Edit;
+use super::upgrade;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq)]
+pub struct Doctor {
+    migrate: bool,
+}
+
+impl FromCli for Doctor {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Doctor {
+            migrate: cli.check_flag(Flag::new("migrate"))?,
+        });
+        command
+    }
+}
+
+/// The outcome of a single check.
+#[derive(Debug, PartialEq)]
+enum Level {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Collects the outcomes of the checks as they are performed.
+struct Report {
+    warnings: usize,
+    failures: usize,
+    passes: usize,
+}
+
+impl Report {
+    fn new() -> Self {
+        Self { warnings: 0, failures: 0, passes: 0 }
+    }
+
+    /// Displays the outcome of the check on `subject`, followed by a remediation `hint`.
+    fn add(&mut self, level: Level, subject: &str, message: &str, hint: Option<&str>) {
+        let tag = match level {
+            Level::Pass => { self.passes += 1; "ok".green() },
+            Level::Warn => { self.warnings += 1; "warn".yellow() },
+            Level::Fail => { self.failures += 1; "fail".red() },
+        };
+        println!("{:<6}{}: {}", tag, subject, message);
+        if let Some(h) = hint {
+            println!("{:<6}hint: {}", "", h);
+        }
+    }
+
+    fn pass(&mut self, subject: &str, message: &str) {
+        self.add(Level::Pass, subject, message, None)
+    }
+
+    /// Ends the report, erroring if any critical check failed.
+    fn finish(self) -> Result<(), Fault> {
+        println!("\ninfo: {} passed, {} warning(s), {} failure(s)", self.passes, self.warnings, self.failures);
+        match self.failures {
+            0 => Ok(()),
+            n => Err(AnyError(format!("{} critical check(s) failed", n)))?,
+        }
+    }
+}
+
+impl Command for Doctor {
+    type Err = Fault;
+
+    /// Sets up its own context step by step to report the step that fails, keeping
+    /// the global options of the `options` context.
+    fn exec(&self, options: &Context) -> Result<(), Self::Err> {
+        let mut report = Report::new();
+
+        let c = match Context::new().home(environment::ORBIT_HOME) {
+            Ok(c) => c,
+            Err(e) => {
+                report.add(Level::Fail, "home", &e.to_string(), Some("set ORBIT_HOME to an existing directory"));
+                return report.finish()
+            }
+        };
+        report.pass("home", &display(c.get_home_path()));
+
+        let c = match c.cache(environment::ORBIT_CACHE).and_then(|c| c.store(environment::ORBIT_STORE)) {
+            Ok(c) => c,
+            Err(e) => {
+                report.add(Level::Fail, "cache", &e.to_string(), Some("set ORBIT_CACHE and ORBIT_STORE to existing directories"));
+                return report.finish()
+            }
+        };
+        for (subject, dir) in [("cache", c.get_cache_path()), ("store", c.get_store_path())] {
+            match tempfile::tempfile_in(dir) {
+                Ok(_) => report.pass(subject, &display(dir)),
+                Err(e) => report.add(Level::Fail, subject, &format!("cannot write to {}: {}", display(dir), e), Some("check the permissions of the directory")),
+            }
+        }
+
+        self.check_layout(&mut report, &c);
+
+        let c = match c.current_ip_dir(environment::ORBIT_IP_PATH).map_err(|e| Box::new(e) as Fault).and_then(|c| c.settings(CONFIG_FILE)) {
+            Ok(c) => c,
+            Err(e) => {
+                report.add(Level::Fail, "configuration", &e.to_string(), Some("fix the entry in the configuration file; see `orbit help config`"));
+                return report.finish()
+            }
+        };
+        report.pass("configuration", &format!("{} plugin(s), {} template(s)", c.get_plugins().len(), c.get_templates().len()));
+
+        // the development path is verified here instead of when it is set
+        let c = c.development_path(environment::ORBIT_DEV_PATH, false)?;
+        for dev_path in c.get_development_paths() {
+            Self::check_development_path(&mut report, dev_path);
+        }
+
+        match Edit::configure_editor(&None, c.get_config()) {
+            Ok(editor) => match filesystem::find_program(&editor) {
+                Some(_) => report.pass("editor", &editor),
+                None => report.add(Level::Warn, "editor", &format!("'{}' was not found", editor), Some("install the editor or change `core.editor` in the configuration")),
+            },
+            Err(_) => report.add(Level::Warn, "editor", "no editor is set", Some("set the EDITOR environment variable or `core.editor` in the configuration")),
+        }
+
+        network::set_network(c.get_network().clone());
+        self.check_network(&mut report, &c, options.offline)?;
+        self.check_vendors(&mut report, &c, options.offline)?;
+
+        let mut plugins: Vec<_> = c.get_plugins().values().collect();
+        plugins.sort_by_key(|p| p.alias());
+        for plugin in plugins {
+            let subject = format!("plugin '{}'", plugin.alias());
+            match plugin.resolve_command(&[]) {
+                Ok((command, _)) => match filesystem::find_program(&command) {
+                    Some(path) => report.pass(&subject, &display(&path)),
+                    None => report.add(Level::Warn, &subject, &format!("command '{}' was not found", command), Some("install the program or add its directory to the PATH")),
+                },
+                Err(e) => report.add(Level::Warn, &subject, &e.to_string(), Some("define the variable; see `orbit env --check`")),
+            }
+        }
+        report.finish()
+    }
+}
+
+impl Doctor {
+    /// Checks the development path exists and every ip within it can be read.
+    fn check_development_path(report: &mut Report, dev_path: &Path) {
+        if !dev_path.is_dir() {
+            report.add(Level::Fail, "dev path", &format!("{} is not an existing directory", display(dev_path)), Some("create the directory or change `core.path` or `core.paths` in the configuration"));
+            return
+        }
+        let manifests = match manifest::find_file(dev_path, IP_MANIFEST_FILE, true) {
+            Ok(m) => m,
+            Err(e) => return report.add(Level::Warn, "dev path", &format!("failed to search {}: {}", display(dev_path), e), Some("check the permissions of the directory")),
+        };
+        let mut dangling = 0;
+        for m in &manifests {
+            if let Err(e) = IpManifest::from_path(&m.parent().unwrap().to_path_buf()) {
+                dangling += 1;
+                report.add(Level::Warn, "dev path", &e.to_string(), Some("fix or remove the ip's manifest"));
+            }
+        }
+        report.pass("dev path", &format!("{} ({} readable ip)", display(dev_path), manifests.len() - dangling));
+    }
+
+    /// Checks the structure of the home is up to date, applying the pending migrations
+    /// when requested.
+    fn check_layout(&self, report: &mut Report, c: &Context) {
+        let layout = Layout::new(c.get_home_path(), c.get_cache_path(), c.get_store_path());
+        let pending = match layout.is_new().and_then(|new| match new { true => Ok(Vec::new()), false => layout.pending() }) {
+            Ok(p) => p,
+            Err(e) => return report.add(Level::Fail, "layout", &e.to_string(), Some("upgrade orbit or use a different ORBIT_HOME")),
+        };
+        if pending.is_empty() {
+            return report.pass("layout", &format!("version {}", LAYOUT_VERSION))
+        }
+        match self.migrate {
+            true => match layout.migrate() {
+                Ok(n) => report.pass("layout", &format!("version {} ({} migration(s) applied)", LAYOUT_VERSION, n)),
+                Err(e) => report.add(Level::Fail, "layout", &format!("failed to migrate: {}", e), Some("resolve the error and run `orbit doctor --migrate` again")),
+            },
+            false => report.add(Level::Warn, "layout", &format!("{} pending migration(s) to version {}: {}", pending.len(), LAYOUT_VERSION, pending.join("; ")), Some("run `orbit doctor --migrate`; other commands also migrate before they run")),
+        }
+    }
+
+    /// Checks the network settings are usable and, unless `offline`, that orbit's
+    /// release channel can be reached through the configured proxy.
+    fn check_network(&self, report: &mut Report, c: &Context, offline: bool) -> Result<(), Fault> {
+        let network = c.get_network();
+        let client = match network.client() {
+            Ok(client) => client,
+            Err(e) => {
+                report.add(Level::Fail, "network", &e.to_string(), Some("fix `network.ca-bundle` in the configuration"));
+                return Ok(())
+            }
+        };
+        let url = c.get_config().get_as_str("upgrade", "url")?.unwrap_or(upgrade::DEFAULT_CHANNEL);
+        let route = match network.proxy_for(url) {
+            Some(proxy) => format!("through proxy {}", proxy),
+            None => String::from("without a configured proxy"),
+        };
+        if offline == true {
+            return Ok(report.pass("network", &format!("{} is reached {} (not checked while offline)", url, route)))
+        }
+        match probe(network, &client, url) {
+            Ok(status) => report.pass("network", &format!("{} reached {} ({})", url, route, status)),
+            Err(e) => report.add(Level::Warn, "network", &format!("failed to reach {} {}: {}", url, route, e), Some("check `network.proxy` and `network.ca-bundle` in the configuration, or use `--offline`")),
+        }
+        Ok(())
+    }
+
+    /// Checks every vendor index can be read and its repository's remote can be reached,
+    /// unless `offline`.
+    fn check_vendors(&self, report: &mut Report, c: &Context, offline: bool) -> Result<(), Fault> {
+        for (index, root) in c.get_config().collect_as_array_of_str("vendor", "index")? {
+            let path = PathBuf::from(filesystem::resolve_rel_path(root, index));
+            let vendor = match VendorManifest::from_path(&path) {
+                Ok(v) => v,
+                Err(e) => {
+                    report.add(Level::Fail, "vendor", &format!("{}: {}", index, e), Some("fix or remove the path from `vendor.index` in the configuration"));
+                    continue
+                }
+            };
+            let subject = format!("vendor '{}'", vendor.get_name());
+            let has_remote = git2::Repository::open(vendor.get_root())
+                .and_then(|r| r.remotes().map(|names| !names.is_empty()))
+                .unwrap_or(false);
+            if !has_remote || offline {
+                report.pass(&subject, &display(&vendor.get_root()));
+                continue
+            }
+            match ExtGit::new(None).path(vendor.get_root()).transport(vendor.get_transport().clone()).ls_remote() {
+                Ok(()) => report.pass(&subject, &format!("{} (remote reachable)", display(&vendor.get_root()))),
+                Err(e) => report.add(Level::Warn, &subject, &format!("remote is unreachable: {}", e.to_string().trim()), Some("check the network connection and credentials, or use `--offline`")),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sends a request for `url` with the `network` settings, returning the status of
+/// any response.
+#[tokio::main]
+async fn probe(network: &Network, client: &reqwest::Client, url: &str) -> Result<reqwest::StatusCode, Fault> {
+    Ok(network.send(client, url).await?.status())
+}
+
+fn display(path: &Path) -> String {
+    filesystem::normalize_path(path.to_path_buf()).display().to_string()
+}
+
+const HELP: &str = "\
+Check the health of the orbit environment.
+
+Usage:
+    orbit doctor [options]
+
+Options:
+    --offline       skip checks that reach remote hosts
+    --migrate       apply pending migrations to the ORBIT_HOME layout
+
+Use 'orbit help doctor' to learn more about the command.
+";
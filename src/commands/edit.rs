@@ -7,12 +7,16 @@ use crate::core::catalog::Catalog;
 use crate::core::config::CONFIG_FILE;
 use crate::core::config::Config;
 use crate::core::extgit::ExtGitError;
+use crate::core::manifest::{self, IpManifest};
+use crate::core::version::AnyVersion;
 use crate::interface::cli::Cli;
 use crate::interface::arg::{Flag, Optional};
 use crate::interface::errors::CliError;
 use crate::core::context::Context;
 use crate::core::pkgid::PkgId;
 use crate::util::anyerror::AnyError;
+use crate::util::filesystem::{self, normalize_path};
+use crate::util::theme;
 
 
 #[derive(Debug, PartialEq)]
@@ -39,16 +43,18 @@ pub struct Edit {
     ip: Option<PkgId>,
     config: bool,
     mode: EditMode,
+    checkout: Option<PathBuf>,
 }
 
 impl FromCli for Edit {
     fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
         cli.set_help(HELP);
         let command = Ok(Edit {
-            mode: cli.check_option(Optional::new("mode"))?.unwrap_or(EditMode::Open),
+            mode: cli.check_option_default(Optional::new("mode").choices(&["open", "path"]).default("open"))?,
             config: cli.check_flag(Flag::new("config"))?,
             editor: cli.check_option(Optional::new("editor"))?,
             ip: cli.check_option(Optional::new("ip").value("pkgid"))?,
+            checkout: cli.check_option(Optional::new("checkout").value("path"))?,
         });
         command
     }
@@ -57,7 +63,14 @@ impl FromCli for Edit {
 impl Command for Edit {
     type Err = Box<dyn std::error::Error>;
     fn exec(&self, c: &Context) -> Result<(), Self::Err> {
-        let sel_editor = Self::configure_editor(&self.editor, &c.get_config())?;
+        if self.checkout.is_some() && self.ip.is_none() {
+            return Err(AnyError(format!("'{}' requires an ip to be selected with '{}'", theme::argument("--checkout"), theme::argument("--ip"))))?
+        }
+        // the editor is only required to open the ip
+        let sel_editor = match self.mode {
+            EditMode::Open => Self::configure_editor(&self.editor, c.get_config())?,
+            EditMode::Path => String::new(),
+        };
         // open global configuration file
         if self.config == true {
             let config_path = c.get_config().get_root().join(CONFIG_FILE);
@@ -69,11 +82,11 @@ impl Command for Edit {
         } else if self.ip.is_some() == true {
             // collect manifest from DEV_PATH
             let catalog = Catalog::new()
-                .development(c.get_development_path().as_ref().unwrap())?
+                .development(c.get_development_paths())?
                 .installations(c.get_cache_path())?
                 .available(c.get_vendors())?
                 .store(c.get_store_path());
-            self.run(&catalog, &sel_editor)
+            self.run(&catalog, &sel_editor, c)
         } else {
             panic!("nothing to edit")
         }
@@ -127,26 +140,69 @@ impl Edit {
         }
     }
 
-    fn run(&self, catalog: &Catalog, editor: &str) -> Result<(), Box<dyn std::error::Error>> {
+    fn run(&self, catalog: &Catalog, editor: &str, c: &Context) -> Result<(), Box<dyn std::error::Error>> {
         let ids: Vec<&PkgId> = catalog.inner().keys().map(|f| f).collect();
         // find the full ip name among the manifests to get the path
         let result = ip::find_ip(&self.ip.as_ref().unwrap(), ids)?;
-
-        let ip = match catalog.inner().get(&result).unwrap().get_dev() {
-            // perform the process
-           Some(ip) => ip,
-           None => return Err(AnyError(format!("ip '{}' is not found on the DEV_PATH", result)))?    
+        let status = catalog.inner().get(&result).unwrap();
+
+        // prefer the development state before falling back to the cache
+        let root = match (status.get_dev(), &self.checkout) {
+            (Some(ip), None) => ip.get_root(),
+            (Some(ip), Some(_)) => return Err(AnyError(format!("ip '{}' is already in development at {}", result, normalize_path(ip.get_root()).display())))?,
+            (None, checkout) => {
+                let ip = match status.get_install(&AnyVersion::Latest) {
+                    Some(ip) => ip,
+                    None => return Err(AnyError(format!("ip '{}' is not found on the DEV_PATH or in the cache", result)))?,
+                };
+                match checkout {
+                    Some(dest) => Self::checkout(ip, dest, c)?,
+                    None => {
+                        if self.mode == EditMode::Open {
+                            println!("{}", theme::warning(format!("ip '{}' is not in development; opening the read-only installation of v{} from the cache\n\nTry `--checkout <path>` to create a working copy to modify", result, ip.get_version())));
+                        }
+                        ip.get_root()
+                    }
+                }
+            }
         };
         match &self.mode {
             EditMode::Open => {
-                Self::invoke(editor, &ip.get_root())
+                Self::invoke(editor, &root)
             }
             EditMode::Path => {
-                println!("{}", crate::util::filesystem::normalize_path(ip.get_root()).display());
+                println!("{}", normalize_path(root).display());
                 Ok(())
             }
         }
     }
+
+    /// Copies the installed `ip` to `dest` as a working copy and registers it on the
+    /// development path if it is not already within one.
+    /// 
+    /// Returns the path to the working copy.
+    fn checkout(ip: &IpManifest, dest: &PathBuf, c: &Context) -> Result<PathBuf, Fault> {
+        let dest = normalize_path(std::env::current_dir()?.join(dest));
+        if dest.exists() && std::fs::read_dir(&dest)?.next().is_some() {
+            return Err(AnyError(format!("cannot check out ip '{}' to {}: directory is not empty", ip.get_pkgid(), dest.display())))?
+        }
+        filesystem::copy(&ip.get_root(), &dest, true)?;
+        // the working copy is no longer an installation
+        for file in [manifest::ORBIT_SUM_FILE, manifest::ORBIT_METADATA_FILE] {
+            if dest.join(file).exists() {
+                std::fs::remove_file(dest.join(file))?;
+            }
+        }
+        println!("{}", theme::info(format!("checked out ip '{}' v{} to {}", ip.get_pkgid(), ip.get_version(), dest.display())));
+
+        if c.locate_development_path(&dest).is_none() {
+            let mut cfg = Config::from_path(&c.get_config().get_root().join(CONFIG_FILE))?;
+            cfg.append_dev_path(&dest.display().to_string());
+            cfg.write()?;
+            println!("{}", theme::info(format!("added {} to the development paths in 'core.paths'", dest.display())));
+        }
+        Ok(dest)
+    }
 }
 
 const HELP: &str = "\
@@ -158,8 +214,9 @@ Usage:
 Options:
     --ip <pkgid>       ip to open in development state
     --editor <cmd>     the command to call a text-editor
-    --mode <mode>      select how to edit: 'open' or 'path'
+    --mode <mode>      select how to edit: 'open' or 'path' (default: open)
     --config           modify the global configuration file
+    --checkout <path>  copy an installed ip to a working copy on the dev path
 
 Use 'orbit help edit' to learn more about the command.
 ";
\ No newline at end of file
@@ -4,26 +4,35 @@ use crate::Command;
 use crate::FromCli;
 use crate::core::manifest::IpManifest;
 use crate::interface::cli::Cli;
-use crate::interface::arg::Positional;
+use crate::interface::arg::{Flag, Positional};
 use crate::interface::errors::CliError;
 use crate::core::context::Context;
+use crate::core::plugin::Plugin;
+use crate::core::template;
+use crate::core::variable::VariableTable;
+use crate::util::anyerror::AnyError;
 use crate::util::environment;
 use crate::util::environment::EnvVar;
 use crate::util::environment::Environment;
 use crate::util::environment::ORBIT_BLUEPRINT;
+use crate::util::environment::ORBIT_BLUEPRINT_SUMS;
 use crate::util::environment::ORBIT_WIN_LITERAL_CMD;
 use crate::util::filesystem;
+use crate::util::theme;
 
 use super::plan::BLUEPRINT_FILE;
+use super::plan::BLUEPRINT_SUMS_FILE;
 
 #[derive(Debug, PartialEq)]
 pub struct Env {
     keys: Vec<String>,
+    check: bool,
 }
 
 impl FromCli for Env {
     fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
         cli.set_help(HELP);
+        let check = cli.check_flag(Flag::new("check"))?;
         // collect all positional arguments
         let mut keys: Vec<String> = Vec::new();
         while let Some(c) = cli.check_positional(Positional::new("key"))? {
@@ -31,6 +40,7 @@ impl FromCli for Env {
         }
         let command = Ok(Env {
             keys: keys,
+            check,
         });
         command
     }
@@ -39,20 +49,25 @@ impl FromCli for Env {
 impl Command for Env {
     type Err = Box<dyn std::error::Error>;
     fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        if self.check {
+            return Self::check(c)
+        }
         // assemble environment information
         let mut env = Environment::from_vec(vec![
             // @todo: context should own an `Environment` struct instead of this data transformation
             EnvVar::new().key(environment::ORBIT_HOME).value(filesystem::normalize_path(c.get_home_path().clone()).to_str().unwrap()),
             EnvVar::new().key(environment::ORBIT_CACHE).value(filesystem::normalize_path(c.get_cache_path().to_path_buf()).to_str().unwrap()),
             EnvVar::new().key(environment::ORBIT_BUILD_DIR).value(c.get_build_dir()),
-            EnvVar::new().key(environment::ORBIT_DEV_PATH).value(filesystem::normalize_path(c.get_development_path().unwrap_or(&PathBuf::new()).clone()).to_str().unwrap()),
+            EnvVar::new().key(environment::ORBIT_DEV_PATH).value(std::env::join_paths(c.get_development_paths().iter().map(|p| filesystem::normalize_path(p.clone())))?.to_str().unwrap()),
+            EnvVar::new().key(environment::ORBIT_PLUGIN_PATH).value(std::env::join_paths(c.get_plugin_paths().iter().map(|p| filesystem::normalize_path(p.clone())))?.to_str().unwrap()),
             EnvVar::new().key(environment::ORBIT_IP_PATH).value(filesystem::normalize_path(c.get_ip_path().unwrap_or(&PathBuf::new()).clone()).to_str().unwrap()),
             EnvVar::new().key(environment::ORBIT_STORE).value(filesystem::normalize_path(c.get_store_path().clone()).to_str().unwrap()),
             EnvVar::new().key("EDITOR").value(&std::env::var("EDITOR").unwrap_or(String::new())),
             EnvVar::new().key("NO_COLOR").value(&std::env::var("NO_COLOR").unwrap_or(String::new())),
             ])
             .from_config(c.get_config())?
-            .add(EnvVar::new().key(ORBIT_BLUEPRINT).value(BLUEPRINT_FILE));
+            .add(EnvVar::new().key(ORBIT_BLUEPRINT).value(BLUEPRINT_FILE))
+            .add(EnvVar::new().key(ORBIT_BLUEPRINT_SUMS).value(BLUEPRINT_SUMS_FILE));
 
         // add platform-specific environment variables
         if cfg!(target_os = "windows") {
@@ -74,6 +89,40 @@ impl Command for Env {
 }
 
 impl Env {
+    /// Verifies every variable referenced by the configured plugins and the current
+    /// ip's manifest is defined.
+    fn check(c: &Context) -> Result<(), Box<dyn std::error::Error>> {
+        let vars = VariableTable::new().load_system();
+        let mut errors = Vec::new();
+        // the manifest expands its variables when it is read
+        if c.goto_ip_path().is_ok() {
+            if let Err(e) = IpManifest::from_path(c.get_ip_path().unwrap()) {
+                errors.push(e.to_string());
+            }
+        }
+        let mut plugins: Vec<&Plugin> = c.get_plugins().values().collect();
+        plugins.sort_by_key(|p| p.alias().clone());
+        for plug in plugins {
+            if let Err(e) = plug.resolve_command(&[]) {
+                errors.push(e.to_string());
+            }
+            for fset in plug.filesets() {
+                for pattern in fset.get_patterns() {
+                    if let Err(e) = template::expand(&pattern, &vars) {
+                        errors.push(format!("plugin '{}': fileset '{}': {}", plug.alias(), fset.get_name(), e));
+                    }
+                }
+            }
+        }
+        match errors.is_empty() {
+            true => {
+                println!("{}", theme::info("all variables are defined"));
+                Ok(())
+            },
+            false => Err(AnyError(format!("{} value(s) reference undefined variables:\n  {}", errors.len(), errors.join("\n  "))))?,
+        }
+    }
+
     fn run(&self, env: Environment) -> Result<(), Box<dyn std::error::Error>> {
         let mut result = String::new();
 
@@ -115,6 +164,7 @@ Usage:
 
 Options:
     <key>...     A environment variable to display its value
+    --check      verify every ${VAR} in the manifest and plugins is defined
 
 Use 'orbit help env' to learn more about the command.
 ";
\ No newline at end of file
@@ -0,0 +1,85 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::export::{Blueprint, Format};
+use crate::interface::cli::Cli;
+use crate::interface::arg::Optional;
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
+use crate::util::theme;
+use super::plan::{Plan, BLUEPRINT_FILE};
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq)]
+pub struct Export {
+    format: Option<Format>,
+    output: Option<PathBuf>,
+    plan: Plan,
+}
+
+impl FromCli for Export {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Export {
+            format: cli.check_option(Optional::new("format").value("fmt"))?,
+            output: cli.check_option(Optional::new("output").value("file"))?,
+            plan: Plan::from_cli_planning(cli)?,
+        });
+        command
+    }
+}
+
+impl Command for Export {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        let format = match &self.format {
+            Some(f) => f,
+            None => return Err(AnyError(format!("select a format with '{}': 'vivado-tcl', 'quartus-qsf', 'ghdl', or 'nvc'", theme::argument("--format <fmt>"))))?,
+        };
+        // generate the blueprint to read the planned file list from
+        self.plan.exec(c)?;
+
+        let b_dir = self.plan.get_build_dir().unwrap_or(c.get_build_dir());
+        let build_path = c.get_ip_path().unwrap().join(b_dir);
+        let blueprint = Blueprint::read(&std::fs::read_to_string(build_path.join(BLUEPRINT_FILE))?);
+
+        let export = match &self.output {
+            Some(path) => path.clone(),
+            None => build_path.join(format.export_file()),
+        };
+        std::fs::write(&export, format.export(&blueprint))?;
+        println!("info: {} project file created at: {}", format, filesystem::normalize_path(export).display());
+        Ok(())
+    }
+}
+
+const HELP: &str = "\
+Generate a project file for a backend tool from the planned file list.
+
+Usage:
+    orbit export [options]
+
+Options:
+    --format <fmt>          project format: vivado-tcl, quartus-qsf, ghdl, nvc
+    --output <file>         write the project file to this path
+    --plugin <alias>        plugin to plan for
+    --top <unit>            override auto-detected toplevel entity
+    --bench <tb>            override auto-detected toplevel testbench
+    --build-dir <dir>       set the output build directory
+    --fileset <key=glob>... set an additional fileset
+    --generic <name=value>... override a generic of the top-level
+    --include-dir <dir>...  add a directory to search for headers
+    --define <name[=value]>... define a preprocessor macro for the tools
+    --target <name>         collect the constraints of a board or device
+    --features <list>...    enable comma-separated features of the ip or its dependencies
+    --all                   include all found HDL files
+    --disable-ssh           convert SSH repositories to HTTPS for dependencies
+    --jobs <n>              install up to n missing dependencies at once (default: 1)
+    --vendored              only use dependencies copied by 'orbit vendor'
+    --force                 skip reading from the lock file
+    --no-input              do not ask to choose the top-level or testbench
+
+Use 'orbit help export' to learn more about the command.
+";
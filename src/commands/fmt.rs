@@ -0,0 +1,92 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::fileset;
+use crate::core::manifest::IpManifest;
+use crate::core::vhdl::printer::{KeywordCase, Printer};
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Flag, Optional};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
+
+#[derive(Debug, PartialEq)]
+pub struct Fmt {
+    check: bool,
+    indent: Option<usize>,
+    keyword_case: Option<KeywordCase>,
+}
+
+impl FromCli for Fmt {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        Ok(Fmt {
+            check: cli.check_flag(Flag::new("check"))?,
+            indent: cli.check_option(Optional::new("indent").value("n"))?,
+            keyword_case: cli.check_option(Optional::new("keyword-case").value("case"))?,
+        })
+    }
+}
+
+impl Command for Fmt {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+        let ip = IpManifest::from_path(c.get_ip_path().unwrap())?;
+
+        let mut printer = Printer::new();
+        if let Some(n) = self.indent {
+            printer = printer.indent(n);
+        }
+        if let Some(case) = self.keyword_case {
+            printer = printer.keyword_case(case);
+        }
+
+        // only format the files that belong to the current ip
+        let root = ip.get_root().display().to_string().replace('\\', "/");
+        let mut unformatted = Vec::new();
+        for file in filesystem::gather_current_files(&ip.get_root()) {
+            if !fileset::is_vhdl(&file) {
+                continue
+            }
+            let display = file.strip_prefix(&root).map(|f| f.trim_start_matches('/')).unwrap_or(&file).to_string();
+            let text = std::fs::read_to_string(&file)?;
+            let result = match printer.format(&text) {
+                Ok(r) => r,
+                Err(e) => return Err(AnyError(format!("failed to format file '{}': {}", display, e)))?,
+            };
+            if result == text {
+                continue
+            }
+            match self.check {
+                true => println!("{}", display),
+                false => {
+                    std::fs::write(&file, result)?;
+                    println!("info: formatted {}", display);
+                },
+            }
+            unformatted.push(display);
+        }
+
+        match self.check && !unformatted.is_empty() {
+            true => Err(AnyError(format!("{} file(s) are not formatted\n\nTry `orbit fmt` to format them", unformatted.len())))?,
+            false => Ok(()),
+        }
+    }
+}
+
+const HELP: &str = "\
+Format the vhdl files in the current ip.
+
+Usage:
+    orbit fmt [options]
+
+Options:
+    --check                 list unformatted files and exit without writing
+    --indent <n>            the number of spaces per indentation level
+    --keyword-case <case>   write keywords in 'lower' or 'upper' case
+
+Use 'orbit help fmt' to learn more about the command.
+";
\ No newline at end of file
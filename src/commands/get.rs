@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::path::PathBuf;
 
 use colored::Colorize;
@@ -10,6 +9,7 @@ use crate::core::manifest::IpManifest;
 use crate::core::parser::Symbol;
 use crate::core::version::AnyVersion;
 use crate::core::version::Version;
+use crate::core::vhdl::index::IdentifierIndex;
 use crate::core::vhdl::interface;
 use crate::core::vhdl::primaryunit::VhdlIdentifierError;
 use crate::core::vhdl::symbol::Architecture;
@@ -93,7 +93,7 @@ impl Command for Get {
             // gather the catalog (all manifests)
             let catalog = Catalog::new()
                 .store(c.get_store_path())
-                .development(c.get_development_path().unwrap())?
+                .development(c.get_development_paths())?
                 .installations(c.get_cache_path())?
                 .available(c.get_vendors())?;
             let ids = catalog.inner().keys().map(|f| { f }).collect();
@@ -125,7 +125,7 @@ impl Get {
     fn run(&self, ip: &IpManifest, is_self: bool, current_ip: Option<IpManifest>, ver: &AnyVersion) -> Result<(), Fault> {
         // collect all hdl files and parse them
         let ent = match Self::fetch_entity(&self.unit, &ip) {
-            Ok(r) => r,
+            Ok((_, r)) => r,
             Err(e) => return Err(GetError::SuggestProbe(e.to_string(), ip.get_pkgid().clone(), ver.clone()))?
         };
 
@@ -176,7 +176,7 @@ impl Get {
         if self.instance == true {
             let name = match &self.name {
                 Some(iden) => iden.clone(),
-                None => Identifier::Basic("uX".to_string()),
+                None => Identifier::Basic("uX".into()),
             };
             println!("{}", ent.into_instance(&name, lib));
         }
@@ -184,13 +184,14 @@ impl Get {
         Ok(())
     }
 
-    /// Parses through the vhdl files and returns a desired entity struct.
-    fn fetch_entity(iden: &Identifier, ip: &IpManifest) -> Result<symbol::Entity, Fault> {
+    /// Parses through the vhdl files and returns a desired entity struct along
+    /// with the file it is declared in.
+    pub fn fetch_entity(iden: &Identifier, ip: &IpManifest) -> Result<(String, symbol::Entity), Fault> {
         let files = crate::util::filesystem::gather_current_files(&ip.get_root());
         // @todo: generate all units first (store architectures, and entities, and then process)
         let mut result: Option<(String, Entity)> = None;
         // store map of all architectures while parsing all code
-        let mut architectures: HashMap<Identifier, Vec<Architecture>> = HashMap::new();
+        let mut architectures: IdentifierIndex<Architecture> = IdentifierIndex::new();
         for f in files {
             // lex and parse
             if crate::core::fileset::is_vhdl(&f) == true {
@@ -204,10 +205,7 @@ impl Get {
                         match unit.as_ref().as_architecture() {
                             Some(_) => {
                                 let arch = unit.take().into_architecture().unwrap();
-                                match architectures.get_mut(arch.entity()) {
-                                    Some(list) => { list.push(arch); () },
-                                    None => { architectures.insert(arch.entity().clone(), vec![arch]); () }
-                                }
+                                architectures.insert(&arch.entity().clone(), arch);
                                 None 
                             },
                             None => Some(unit)
@@ -231,12 +229,11 @@ impl Get {
             }
         }
         match result {
-            Some((_, mut entity)) => {
-                match architectures.remove(entity.get_name()) {
-                    Some(archs) => for arch in archs { entity.link_architecture(arch) }
-                    None => (),
+            Some((file, mut entity)) => {
+                for arch in architectures.remove(entity.get_name()) {
+                    entity.link_architecture(arch)
                 }
-                Ok(entity)
+                Ok((file, entity))
             }
             None => Err(GetError::EntityNotFound(iden.clone(), ip.get_pkgid().clone(), ip.get_version().clone()))?
         }
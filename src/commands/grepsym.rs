@@ -0,0 +1,202 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::catalog::Catalog;
+use crate::core::ip;
+use crate::core::lexer::{Position, Token};
+use crate::core::manifest::IpManifest;
+use crate::core::vhdl::index::IdentifierIndex;
+use crate::core::vhdl::token::{Delimiter, Identifier, Keyword, VHDLToken, VHDLTokenizer};
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Flag, Positional};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::Fault;
+
+#[derive(Debug, PartialEq)]
+pub struct GrepSym {
+    symbol: Identifier,
+    decl: bool,
+}
+
+impl FromCli for GrepSym {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        Ok(GrepSym {
+            decl: cli.check_flag(Flag::new("decl"))?,
+            symbol: cli.require_positional(Positional::new("symbol"))?,
+        })
+    }
+}
+
+impl Command for GrepSym {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+        let ip = IpManifest::from_path(c.get_ip_path().unwrap())?;
+
+        // gather the catalog
+        let catalog = Catalog::new()
+            .store(c.get_store_path())
+            .aliases(c.get_aliases())
+            .development(c.get_development_paths())?
+            .installations(c.get_cache_path())?;
+
+        self.run(&ip, &catalog)
+    }
+}
+
+impl GrepSym {
+    fn run(&self, target: &IpManifest, catalog: &Catalog) -> Result<(), Fault> {
+        let ip_graph = ip::compute_final_ip_graph(target, catalog)?;
+        let files = ip::build_ip_file_list(&ip_graph);
+
+        let mut found = 0;
+        // files are listed ip by ip, so a heading is written whenever the ip changes
+        let mut heading: Option<String> = None;
+        for file in files.iter().filter(|f| crate::core::fileset::is_vhdl(f.get_file())) {
+            let text = std::fs::read_to_string(file.get_file())?;
+            let tokens = VHDLTokenizer::from_source_code(&text).into_tokens();
+            let hits: Vec<Occurrence> = find_symbol(&tokens, &self.symbol)
+                .into_iter()
+                .filter(|o| self.decl == false || o.decl == true)
+                .collect();
+            if hits.is_empty() == true {
+                continue
+            }
+            let manifest = file.get_ip_manifest();
+            let spec = ip::IpSpec::new(manifest.get_pkgid().clone(), manifest.get_version().clone()).to_string();
+            if heading.as_ref() != Some(&spec) {
+                if heading.is_some() {
+                    println!();
+                }
+                println!("{}", spec);
+                heading = Some(spec);
+            }
+            let root = manifest.get_root().display().to_string().replace('\\', "/");
+            let display = file.get_file().strip_prefix(&root).map(|f| f.trim_start_matches('/')).unwrap_or(file.get_file());
+            let lines: Vec<&str> = text.lines().collect();
+            for hit in &hits {
+                let line = lines.get(hit.pos.line() - 1).map(|l| l.trim()).unwrap_or("");
+                println!("    {}{}  {}  {}", display, hit.pos, if hit.decl { "decl" } else { "ref " }, line);
+            }
+            found += hits.len();
+        }
+
+        if found == 0 {
+            println!("info: no {} of '{}' found", if self.decl { "declarations" } else { "occurrences" }, self.symbol);
+        }
+        Ok(())
+    }
+}
+
+/// A place where an identifier is written in the source code.
+#[derive(Debug, PartialEq)]
+struct Occurrence {
+    pos: Position,
+    decl: bool,
+}
+
+/// Keywords that declare the identifier directly after them.
+const DECLARING_KEYWORDS: [Keyword; 14] = [
+    Keyword::Entity, Keyword::Architecture, Keyword::Package, Keyword::Body,
+    Keyword::Configuration, Keyword::Context, Keyword::Component, Keyword::Signal,
+    Keyword::Constant, Keyword::Variable, Keyword::Type, Keyword::Subtype,
+    Keyword::Function, Keyword::Procedure,
+];
+
+/// Finds each identifier token in `tokens` equal to `symbol`.
+///
+/// An occurrence is marked as a declaration when it follows a declaring keyword
+/// (such as `signal` or `entity`, but not `end entity`), or when it is within an
+/// identifier list ending with ':' (such as ports, generics, and labels).
+fn find_symbol(tokens: &[Token<VHDLToken>], symbol: &Identifier) -> Vec<Occurrence> {
+    let index: IdentifierIndex<usize> = tokens.iter()
+        .enumerate()
+        .filter_map(|(i, t)| t.as_type().as_identifier().map(|name| (name.clone(), i)))
+        .collect();
+    let mut result = Vec::new();
+    for &i in index.get(symbol) {
+        let tkn = &tokens[i];
+        let after_keyword = match i.checked_sub(1).and_then(|j| tokens[j].as_type().as_keyword()) {
+            Some(kw) => DECLARING_KEYWORDS.contains(kw) && match i.checked_sub(2).map(|j| tokens[j].as_type()) {
+                // 'end entity <name>' and 'u0 : component <name>' do not declare the name
+                Some(t) => t.check_keyword(&Keyword::End) == false && t.check_delimiter(&Delimiter::Colon) == false,
+                None => true,
+            },
+            None => false,
+        };
+        result.push(Occurrence {
+            pos: tkn.locate().clone(),
+            decl: after_keyword || ends_with_colon(&tokens[i + 1..]),
+        });
+    }
+    result
+}
+
+/// Checks if the `tokens` continue an identifier list that ends with ':'.
+fn ends_with_colon(tokens: &[Token<VHDLToken>]) -> bool {
+    let mut iter = tokens.iter().map(|t| t.as_type());
+    loop {
+        match iter.next() {
+            Some(t) if t.check_delimiter(&Delimiter::Colon) => return true,
+            Some(t) if t.check_delimiter(&Delimiter::Comma) => match iter.next() {
+                Some(t) if t.as_identifier().is_some() => continue,
+                _ => return false,
+            },
+            _ => return false,
+        }
+    }
+}
+
+const HELP: &str = "\
+Find where an identifier is declared and referenced.
+
+Usage:
+    orbit grep-sym [options] <symbol>
+
+Args:
+    <symbol>        identifier of an entity, signal, port, or package member
+
+Options:
+    --decl          only list declarations of the identifier
+
+Use 'orbit help grep-sym' to learn more about the command.
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn find_declarations_and_references() {
+        let code = "\
+entity Top is
+    port (clk, rst : in std_logic; q : out std_logic);
+end entity top;
+
+architecture rtl of top is
+    signal count : natural;
+    component top port (clk : in std_logic); end component;
+begin
+    u0 : component top port map (clk => CLK);
+    u1 : entity work.top port map (clk => clk);
+    count <= count + 1 when rising_edge(clk);
+end architecture;
+";
+        let tokens = VHDLTokenizer::from_source_code(code).into_tokens();
+        let lines = |sym: &str| -> Vec<(usize, bool)> {
+            find_symbol(&tokens, &Identifier::from_str(sym).unwrap())
+                .into_iter()
+                .map(|o| (o.pos.line(), o.decl))
+                .collect()
+        };
+        assert_eq!(lines("top"), vec![(1, true), (3, false), (5, false), (7, true), (9, false), (10, false)]);
+        assert_eq!(lines("rst"), vec![(2, true)]);
+        assert_eq!(lines("clk"), vec![(2, true), (7, true), (9, false), (9, false), (10, false), (10, false), (11, false)]);
+        assert_eq!(lines("count"), vec![(6, true), (11, false), (11, false)]);
+        assert_eq!(lines("u1"), vec![(10, true)]);
+    }
+}
@@ -1,80 +1,30 @@
 use crate::Command;
 use crate::FromCli;
 use crate::interface::cli::Cli;
-use crate::interface::arg::{Positional};
+use crate::interface::arg::{Positional, Flag, Optional};
 use crate::interface::errors::CliError;
 use crate::core::context::Context;
-use crate::commands::manuals;
+use crate::commands::manuals::{self, Manual};
 use crate::util::anyerror::AnyError;
 
 #[derive(Debug, PartialEq)]
 pub struct Help {
     topic: Option<Topic>,
+    man: bool,
+    list: bool,
+    search: Option<String>,
 }
 
+/// A subcommand or topic with a manual page.
 #[derive(Debug, PartialEq)]
-enum Topic {
-    New,
-    Plan,
-    Build,
-    Launch,
-    Edit,
-    Install,
-    Tree,
-    Search,
-    Get,
-    Init,
-    Probe,
-    Env,
-    Config,
-    Uninstall,
-    Read,
-}
+struct Topic(&'static Manual);
 
 impl std::str::FromStr for Topic {
     type Err = AnyError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "env" => Self::Env,
-            "new" => Self::New,
-            "plan" => Self::Plan,
-            "build" => Self::Build,
-            "search" => Self::Search,
-            "launch" => Self::Launch,
-            "edit" => Self::Edit,
-            "install" => Self::Install,
-            "tree" => Self::Tree,
-            "get" => Self::Get,
-            "init" => Self::Init,
-            "probe" => Self::Probe,
-            "config" => Self::Config,
-            "uninstall" => Self::Uninstall,
-            "read" => Self::Read,
-            _ => return Err(AnyError(format!("topic '{}' not found", s)))
-        })
-    }
-}
-
-impl Topic {
-    /// Transforms the variant to its corresponding manual page.
-    fn as_manual(&self) -> &str {
-        use Topic::*;
-        match &self {
-            Env => manuals::env::MANUAL,
-            Probe => manuals::probe::MANUAL,
-            Get => manuals::get::MANUAL,
-            Tree => manuals::tree::MANUAL,
-            Edit => manuals::edit::MANUAL,
-            New => manuals::new::MANUAL,
-            Plan => manuals::plan::MANUAL,
-            Search => manuals::search::MANUAL,
-            Build => manuals::build::MANUAL,
-            Launch => manuals::launch::MANUAL,
-            Install => manuals::install::MANUAL,
-            Init => manuals::init::MANUAL,
-            Config => manuals::config::MANUAL,
-            Uninstall => manuals::uninstall::MANUAL,
-            Read => manuals::read::MANUAL,
+        match manuals::find(s) {
+            Some(m) => Ok(Self(m)),
+            None => Err(AnyError(format!("topic '{}' not found; try 'orbit help --list' to see available topics", s))),
         }
     }
 }
@@ -89,20 +39,60 @@ impl Command for Help {
 
 impl Help {
     fn run(&self) -> Result<(), AnyError> {
-        let contents = match &self.topic {
-            Some(t) => t.as_manual(),
-            None => manuals::orbit::MANUAL
+        if self.list == true {
+            print!("{}", Self::format_list());
+            return Ok(())
+        }
+        if let Some(term) = &self.search {
+            match Self::format_search(term) {
+                Some(text) => print!("{}", text),
+                None => return Err(AnyError(format!("no topics mention '{}'", term))),
+            }
+            return Ok(())
+        }
+        let manual = match &self.topic {
+            Some(t) => t.0,
+            None => &manuals::orbit::MANUAL,
         };
         // @todo/idea: check for a pager program to pipe contents into?
-        println!("{}", contents);
+        match self.man {
+            true => print!("{}", manual.to_roff()),
+            false => println!("{}", manual.to_plain()),
+        }
         Ok(())
     }
+
+    /// Lists every subcommand and topic with its summary.
+    fn format_list() -> String {
+        let list = |manuals: &[Manual]| manuals.iter()
+            .map(|m| format!("    {:<16}{}\n", m.name, m.get_summary()))
+            .collect::<String>();
+        format!("Commands:\n{}\nTopics:\n{}", list(manuals::MANUALS), list(manuals::TOPICS))
+    }
+
+    /// Lists the lines mentioning `term` in every subcommand and topic, prefixed
+    /// by the name of the page they are found in.
+    /// 
+    /// Returns `None` if no page mentions `term`.
+    fn format_search(term: &str) -> Option<String> {
+        let result: String = manuals::MANUALS.iter()
+            .chain(manuals::TOPICS.iter())
+            .flat_map(|m| m.find_lines(term).into_iter().map(move |l| format!("{}: {}\n", m.name, l)))
+            .collect();
+        match result.is_empty() {
+            true => None,
+            false => Some(result),
+        }
+    }
 }
 
 impl FromCli for Help {
     fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
         cli.set_help(HELP);
         let command = Ok(Help {
+            man: cli.check_flag(Flag::new("man"))?,
+            list: cli.check_flag(Flag::new("list"))?,
+            search: cli.check_option(Optional::new("search").value("term"))?,
             topic: cli.check_positional(Positional::new("topic"))?,
         });
         command
@@ -113,18 +103,44 @@ const HELP: &str = "\
 Read in-depth documentation around Orbit topics.
 
 Usage:
-    orbit help [<topic>]
+    orbit help [options] [<topic>]
 
 Args:
     <topic>         a listed topic or any orbit subcommand
 
+Options:
+    --man           render the manual as roff to be read by 'man'
+    --list          list every subcommand and topic
+    --search <term> list the lines of every manual mentioning a term
+
 Topics:
-    toml            learn about .toml files
-    cache           learn about orbit's caching system
-    manifest        learn about the Orbit.toml file
-    template        learn about templates
-    blueprint       learn about generating a pre-build data file
-    vendor          learn about hosting multiple ip together
+    ip              a package of hdl code managed by orbit
+    pkgid           the unique identifier of an ip
+    version         the state of an ip at a point in time
+    catalog         every ip known to orbit
+    plugins         commands that run backend tools on an ip
+    blueprint       the file list passed from orbit to a backend tool
 
 Use 'orbit help --list' to see all available topics.
-";
\ No newline at end of file
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn list_topics() {
+        let list = Help::format_list();
+        assert_eq!(list.starts_with("Commands:\n    new             "), true);
+        assert_eq!(list.contains("\nTopics:\n    ip              a package of hdl code managed by orbit\n"), true);
+        assert_eq!(list.lines().count(), manuals::MANUALS.len() + manuals::TOPICS.len() + 3);
+    }
+
+    #[test]
+    fn search_topics() {
+        let found = Help::format_search("ORBIT_PLUGIN_PATH").unwrap();
+        assert_eq!(found.lines().any(|l| l.starts_with("plugins: ")), true);
+        assert_eq!(found.lines().all(|l| l.to_lowercase().contains("orbit_plugin_path")), true);
+        assert_eq!(Help::format_search("no manual mentions this"), None);
+    }
+}
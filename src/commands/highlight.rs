@@ -0,0 +1,127 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::lexer::Span;
+use crate::core::vhdl::highlight::{self, TokenCategory};
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Positional, Optional};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use std::path::PathBuf;
+
+/// The values accepted by `--format`.
+const FORMATS: &[&str] = &["table", "json"];
+
+#[derive(Debug, PartialEq)]
+enum HighlightFormat {
+    Table,
+    Json,
+}
+
+impl std::str::FromStr for HighlightFormat {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            _ => Err(AnyError(format!("unknown format '{}'; expecting 'table' or 'json'", s))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Highlight {
+    format: HighlightFormat,
+    file: PathBuf,
+}
+
+impl FromCli for Highlight {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        Ok(Highlight {
+            format: cli.check_option_default(Optional::new("format").value("fmt").choices(FORMATS).default("table"))?,
+            file: cli.require_positional(Positional::new("file"))?,
+        })
+    }
+}
+
+impl Command for Highlight {
+    type Err = Fault;
+
+    fn exec(&self, _: &Context) -> Result<(), Self::Err> {
+        let text = match std::fs::read_to_string(&self.file) {
+            Ok(t) => t,
+            Err(e) => return Err(AnyError(format!("failed to read file {:?}: {}", self.file, e)))?,
+        };
+        let regions = highlight::classify(&text);
+        match self.format {
+            HighlightFormat::Table => print!("{}", format_table(&text, &regions)),
+            HighlightFormat::Json => println!("{}", format_json(&regions)),
+        }
+        Ok(())
+    }
+}
+
+/// Lists each region as its span, category, and the text it covers, escaping
+/// line breaks so every region stays on one line.
+fn format_table(text: &str, regions: &[(Span, TokenCategory)]) -> String {
+    let mut table = format!("{:<16}{:<12}{}\n{:->16}{:->12}{:->4}\n", "Span", "Category", "Text", " ", " ", "");
+    for (span, category) in regions {
+        let pos = format!("{}:{}-{}:{}", span.start().line(), span.start().col(), span.end().line(), span.end().col());
+        table.push_str(&format!("{:<16}{:<12}{}\n", pos, category.to_string(), text[span.byte_range()].replace('\r', "\\r").replace('\n', "\\n")));
+    }
+    table
+}
+
+/// Lists each region as an object with its category and the line, column, and
+/// byte offset where it starts and ends.
+fn format_json(regions: &[(Span, TokenCategory)]) -> String {
+    let entries: Vec<serde_json::Value> = regions.iter().map(|(span, category)| serde_json::json!({
+        "category": category.to_string(),
+        "start": { "line": span.start().line(), "col": span.start().col(), "offset": span.start().offset() },
+        "end": { "line": span.end().line(), "col": span.end().col(), "offset": span.end().offset() },
+    })).collect();
+    serde_json::to_string_pretty(&entries).unwrap()
+}
+
+const HELP: &str = "\
+Classify the tokens of a vhdl file for syntax highlighting.
+
+Usage:
+    orbit highlight [options] <file>
+
+Args:
+    <file>              path to the vhdl file to read
+
+Options:
+    --format <fmt>      display as a 'table' or 'json' (default: table)
+
+Use 'orbit help highlight' to learn more about the command.
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_regions() {
+        let text = "a <= '1'; -- set\n";
+        let regions = highlight::classify(text);
+        assert_eq!(format_table(text, &regions), "\
+Span            Category    Text
+--------------- ----------- ----
+1:1-1:2         identifier  a
+1:3-1:5         delimiter   <=
+1:6-1:9         literal     '1'
+1:9-1:10        delimiter   ;
+1:11-1:17       comment     -- set
+");
+        let json: serde_json::Value = serde_json::from_str(&format_json(&regions)).unwrap();
+        assert_eq!(json[2], serde_json::json!({
+            "category": "literal",
+            "start": { "line": 1, "col": 6, "offset": 5 },
+            "end": { "line": 1, "col": 9, "offset": 8 },
+        }));
+    }
+}
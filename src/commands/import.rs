@@ -0,0 +1,219 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::catalog::Catalog;
+use crate::core::import::{Project, Source};
+use crate::core::manifest::IpManifest;
+use crate::core::pkgid::PkgId;
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Positional, Optional, Arg};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
+use crate::util::theme;
+use std::path::{Path, PathBuf};
+
+/// Directory files from outside the ip's root are copied into, by kind.
+const HDL_DIR: &str = "rtl";
+const CONSTRAINTS_DIR: &str = "constraints";
+
+#[derive(Debug, PartialEq)]
+pub struct Import {
+    from: Option<Source>,
+    ip: Option<PkgId>,
+    target: Option<String>,
+    rel_path: Option<PathBuf>,
+    project: PathBuf,
+}
+
+impl FromCli for Import {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Import {
+            from: cli.check_option(Optional::new("from").value("tool"))?,
+            ip: cli.check_option(Optional::new("ip").value("pkgid"))?,
+            target: cli.check_option(Optional::new("target").value("name"))?,
+            rel_path: cli.check_option(Optional::new("path"))?,
+            project: cli.require_positional(Positional::new("project"))?,
+        });
+        command
+    }
+}
+
+/// A file of the tool project and where it is placed within the ip.
+struct Placement<'a> {
+    source: PathBuf,
+    /// path relative to the ip's root written with forward slashes
+    dest: String,
+    copy: bool,
+    file: &'a crate::core::import::SourceFile,
+}
+
+impl Command for Import {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        let from = match &self.from {
+            Some(f) => f,
+            None => return Err(AnyError(format!("select the tool to import from with '{}': 'vivado' or 'filelist'", theme::argument("--from <tool>"))))?,
+        };
+        let ip = match &self.ip {
+            Some(ip) => ip,
+            None => return Err(AnyError(format!("label the imported ip with '{}'", theme::argument("--ip <pkgid>"))))?,
+        };
+        // extra validation for a new IP spec to contain all fields (V.L.N)
+        if let Err(e) = ip.fully_qualified() {
+            return Err(Box::new(CliError::BadType(Arg::Optional(Optional::new("ip").value("pkgid")), e.to_string())));
+        }
+
+        // verify the pkgid is not taken
+        {
+            let catalog = Catalog::new()
+                .development(c.get_development_paths())?
+                .installations(c.get_cache_path())?
+                .available(c.get_vendors())?;
+            if catalog.inner().contains_key(ip) == true {
+                return Err(AnyError(format!("ip pkgid '{}' already taken", ip)))?
+            }
+        }
+
+        let project = from.read(&self.project)?;
+        if project.get_files().is_empty() {
+            return Err(AnyError(format!("found no files to import in {} project {}", from, filesystem::normalize_path(self.project.clone()).display())))?
+        }
+
+        let root = match &self.rel_path {
+            Some(p) => std::env::current_dir()?.join(p),
+            None => std::env::current_dir()?,
+        };
+        if c.locate_development_path(&root).is_none() {
+            println!("{}", theme::warning("importing ip outside of DEV_PATH"));
+        }
+        // verify the ip would exist alone on this path (cannot nest IPs)
+        {
+            let mut path_clone = root.clone();
+            while path_clone.exists() == false {
+                path_clone.pop();
+            }
+            if let Some(other_path) = Context::find_ip_path(&path_clone) {
+                return Err(AnyError(format!("an ip already exists at path {}", filesystem::normalize_path(other_path).display())))?
+            }
+        }
+        std::fs::create_dir_all(&root)?;
+        let root = root.canonicalize()?;
+
+        let placements = Self::place(&project, &root)?;
+        Self::check_libraries(&project, ip);
+
+        // create the ip and bring in the files from outside of its root
+        let mut manifest = IpManifest::create(root.clone(), ip, false, true)?;
+        for p in placements.iter().filter(|p| p.copy) {
+            let dest = root.join(&p.dest);
+            std::fs::create_dir_all(dest.parent().unwrap())?;
+            std::fs::copy(&p.source, &dest)?;
+        }
+        let target = self.target.clone().unwrap_or_else(|| self.project.file_stem().unwrap().to_string_lossy().to_string());
+        Self::write_manifest(&mut manifest, &placements, project.get_part(), &target)?;
+
+        let copied = placements.iter().filter(|p| p.copy).count();
+        println!("{}", theme::info(format!("imported {} files ({} copied) from {} project into ip '{}' at {}", placements.len(), copied, from, ip, filesystem::normalize_path(root).display())));
+        Ok(())
+    }
+}
+
+impl Import {
+    /// Decides where each file of the `project` is placed within the ip at `root`.
+    ///
+    /// Files already under `root` are referenced in place. Other files are copied into a
+    /// directory for their kind. Missing files are skipped with a warning.
+    fn place<'a>(project: &'a Project, root: &Path) -> Result<Vec<Placement<'a>>, Fault> {
+        let mut placements: Vec<Placement> = Vec::new();
+        for file in project.get_files() {
+            let source = match file.get_path().canonicalize() {
+                Ok(p) => p,
+                Err(_) => {
+                    println!("{}", theme::warning(format!("skipping missing file {}", filesystem::normalize_path(file.get_path().clone()).display())));
+                    continue;
+                }
+            };
+            let (dest, copy) = match source.strip_prefix(root) {
+                Ok(rel) => (filesystem::to_standard(rel), false),
+                Err(_) => {
+                    let dir = match file.is_constraint() {
+                        true => CONSTRAINTS_DIR,
+                        false => HDL_DIR,
+                    };
+                    (format!("{}/{}", dir, source.file_name().unwrap().to_string_lossy()), true)
+                }
+            };
+            if let Some(other) = placements.iter().find(|p| p.dest == dest) {
+                return Err(AnyError(format!("files {} and {} would both be copied to {}; move one of them into the ip's directory first", other.source.display(), source.display(), dest)))?
+            }
+            placements.push(Placement { source, dest, copy, file });
+        }
+        Ok(placements)
+    }
+
+    /// Warns about the files compiled into a library other than the `ip`'s library.
+    fn check_libraries(project: &Project, ip: &PkgId) {
+        let ip_lib = ip.get_library().as_ref().unwrap().to_string();
+        let common = project.common_library();
+        if let Some(common) = common {
+            if common != &ip_lib {
+                println!("{}", theme::warning(format!("most files were compiled into library '{}' but the ip's library is '{}'; the ip's pkgid can be changed in its manifest", common, ip_lib)));
+            }
+        }
+        let others: Vec<&String> = project.get_files().iter()
+            .filter_map(|f| f.get_library())
+            .filter(|l| l != &&ip_lib && Some(*l) != common)
+            .fold(Vec::new(), |mut acc, l| { if !acc.contains(&l) { acc.push(l) } acc });
+        for lib in others {
+            println!("{}", theme::warning(format!("files from library '{}' are imported into the ip's library; consider moving them into their own ip and adding it as a dependency", lib)));
+        }
+    }
+
+    /// Records the files' VHDL standards and the project's constraints as `target` in the ip's
+    /// manifest.
+    fn write_manifest(manifest: &mut IpManifest, placements: &[Placement], part: Option<&String>, target: &str) -> Result<(), Fault> {
+        let doc = manifest.get_manifest_mut().get_mut_doc();
+        let standards: Vec<&Placement> = placements.iter().filter(|p| p.file.get_standard().is_some()).collect();
+        if !standards.is_empty() {
+            doc["standards"] = toml_edit::table();
+            for p in standards {
+                doc["standards"][&p.dest] = toml_edit::value(p.file.get_standard().unwrap().to_string());
+            }
+        }
+        let mut constraints = toml_edit::Array::new();
+        for p in placements.iter().filter(|p| p.file.is_constraint()) {
+            constraints.push(p.dest.as_str());
+        }
+        if !constraints.is_empty() {
+            doc["targets"] = toml_edit::table();
+            doc["targets"].as_table_mut().unwrap().set_implicit(true);
+            doc["targets"][target] = toml_edit::table();
+            if let Some(part) = part {
+                doc["targets"][target]["part"] = toml_edit::value(part.as_str());
+            }
+            doc["targets"][target]["constraints"] = toml_edit::value(constraints);
+        }
+        manifest.get_manifest_mut().save()
+    }
+}
+
+const HELP: &str = "\
+Create an ip from the file list of an existing tool project.
+
+Usage:
+    orbit import [options] <project>
+
+Args:
+    <project>           the project file to read the file list from
+
+Options:
+    --from <tool>       tool the project belongs to: vivado, filelist
+    --ip <pkgid>        the pkgid to label the imported ip
+    --path <path>       destination path to create the ip
+    --target <name>     name of the target for the project's constraints
+
+Use 'orbit help import' to learn more about the command.
+";
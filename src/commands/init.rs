@@ -48,7 +48,7 @@ impl Command for Init {
         // verify the pkgid is not taken
         {
             let catalog = Catalog::new()
-                .development(c.get_development_path().unwrap())?
+                .development(c.get_development_paths())?
                 .installations(c.get_cache_path())?
                 .available(c.get_vendors())?;
             if catalog.inner().contains_key(&self.ip) == true {
@@ -60,7 +60,7 @@ impl Command for Init {
             None => {
                 let p = std::env::current_dir()?;
                 // check if ip_path is within DEV_PATH
-                if p.starts_with(c.get_development_path().unwrap()) == false {
+                if c.locate_development_path(&p).is_none() {
                     println!("warning: initializing ip outside of DEV_PATH")
                 }
                 p
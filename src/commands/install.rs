@@ -3,8 +3,10 @@ use crate::FromCli;
 use crate::commands::plan::Plan;
 use crate::core::catalog::CacheSlot;
 use crate::core::catalog::Catalog;
+use crate::core::archive;
+use crate::core::signature;
 use crate::core::ip;
-use crate::core::lockfile::LockFile;
+use crate::core::lockfile::{LockEntry, LockFile};
 use crate::core::manifest;
 use crate::core::manifest::IpManifest;
 use crate::core::version;
@@ -17,22 +19,30 @@ use crate::core::version::Version;
 use crate::util::anyerror::{AnyError, Fault};
 use crate::core::version::AnyVersion;
 use crate::util::filesystem;
+use crate::util::writeguard::WriteGuard;
 use crate::util::url::Url;
 use colored::Colorize;
 use git2::Repository;
 use tempfile::TempDir;
 use tempfile::tempdir;
 use crate::core::store::Store;
-use std::path::PathBuf;
-use crate::core::extgit::ExtGit;
+use std::path::{Path, PathBuf};
+use crate::core::extgit::{ExtGit, Transport};
+use crate::util::progress::{ProgressBar, Spinner};
+use crate::util::theme;
 
 #[derive(Debug, PartialEq)]
 pub struct Install {
     ip: Option<PkgId>,
     path: Option<std::path::PathBuf>,
     git: Option<Url>,
+    url: Option<Url>,
     version: AnyVersion,
     disable_ssh: bool,
+    dry_run: bool,
+    deps: bool,
+    sparse: bool,
+    exact: bool,
 }
 
 impl FromCli for Install {
@@ -40,10 +50,15 @@ impl FromCli for Install {
         cli.set_help(HELP);
         let command = Ok(Install {
             git: cli.check_option(Optional::new("git").value("url"))?,
+            url: cli.check_option(Optional::new("url"))?,
             path: cli.check_option(Optional::new("path"))?,
-            version: cli.check_option(Optional::new("variant").switch('v').value("version"))?.unwrap_or(AnyVersion::Latest),
+            version: cli.check_option_default(Optional::new("variant").switch('v').value("version").default("latest"))?,
             ip: cli.check_option(Optional::new("ip"))?,
             disable_ssh: cli.check_flag(Flag::new("disable-ssh"))?,
+            dry_run: cli.check_flag(Flag::new("dry-run"))?,
+            deps: cli.check_flag(Flag::new("deps"))?,
+            sparse: cli.check_flag(Flag::new("sparse"))?,
+            exact: cli.check_flag(Flag::new("exact"))?,
         });
         command
     }
@@ -59,31 +74,67 @@ impl Command for Install {
             _ => ()
         };
 
+        if self.deps {
+            let other = [("--ip", self.ip.is_some()), ("--git", self.git.is_some()), ("--url", self.url.is_some()), ("--path", self.path.is_some())]
+                .iter()
+                .find(|(_, given)| *given)
+                .map(|(name, _)| *name);
+            if let Some(name) = other {
+                return Err(AnyError(format!("'{}' cannot be combined with '{}'", theme::argument("--deps"), theme::argument(name))))?
+            }
+            return self.install_dependencies(c)
+        }
+
         // only allow one type of option to be selected
-        if (self.ip.is_some() ^ self.git.is_some() ^ self.path.is_some()) == false {
-            return Err(AnyError(format!("select one option to install from '{}', '{}', or '{}'", "--ip".yellow(), "--git".yellow(), "--path".yellow())))?
+        if [self.ip.is_some(), self.git.is_some(), self.url.is_some(), self.path.is_some()].iter().filter(|given| **given).count() != 1 {
+            return Err(AnyError(format!("select one option to install from '{}', '{}', '{}', or '{}'", "--ip".yellow(), "--git".yellow(), "--url".yellow(), "--path".yellow())))?
         }
         
         // let temporary directory exist for lifetime of install in case of using it
         let temp_dir = tempdir()?;
+        let guard = WriteGuard::new(self.dry_run);
 
         // gather the catalog (all manifests)
         let catalog = Catalog::new()
             .store(c.get_store_path())
-            .development(c.get_development_path().unwrap())?
+            .development(c.get_development_paths())?
+            .trust(c.get_trust())
             .installations(c.get_cache_path())?
             .available(c.get_vendors())?;
 
         // get to the repository (root path)
         let ip_root = if let Some(ip) = &self.ip {
-            // grab install path
-            fetch_install_path(ip, &catalog, self.disable_ssh, &temp_dir)?
+            // scripts can refuse to complete a partial pkgid
+            if self.exact {
+                ip::find_exact_ip(ip, catalog.inner().keys().collect())?;
+            }
+            match stored_install_path(ip, &catalog)? {
+                // a dry run must not check out versions in the store
+                Some(root) if guard.is_dry_run() => {
+                    let tmp_path = temp_dir.path().to_path_buf();
+                    filesystem::copy(&root, &tmp_path, false)?;
+                    Some(tmp_path)
+                },
+                Some(root) => Some(root),
+                None => guard.perform(&format!("fetch the repository of ip '{}' to resolve version '{}'", ip, self.version), || {
+                    fetch_install_path(ip, &self.version, &catalog, self.disable_ssh, &temp_dir)
+                })?,
+            }
         } else if let Some(url) = &self.git {
             // clone from remote repository
-            let path = temp_dir.path().to_path_buf();
-            println!("info: fetching repository ...");
-            ExtGit::new(None).clone(url, &path, self.disable_ssh)?;
-            path
+            guard.perform(&format!("clone {} to resolve version '{}'", url, self.version), || {
+                let path = temp_dir.path().to_path_buf();
+                println!("info: fetching repository ...");
+                ExtGit::new(None)
+                    .transport(Transport::new().sparse(self.sparse))
+                    .clone(url, &path, self.disable_ssh)?;
+                Ok(path)
+            })?
+        } else if let Some(url) = &self.url {
+            // download and extract the release archive
+            guard.perform(&format!("download {} to resolve version '{}'", url, self.version), || {
+                archive::fetch(url, temp_dir.path())
+            })?
         } else if let Some(path) = &self.path {
             // verify path exists
             if path.exists() == false {
@@ -94,17 +145,29 @@ impl Command for Install {
             let tmp_path = tmp.to_path_buf();
             filesystem::copy(path, &tmp_path, false)?;
             // traverse filesystem
-            tmp_path
+            Some(tmp_path)
         } else {
-            return Err(AnyError(format!("select an option to install from '{}', '{}', or '{}'", "--ip".yellow(), "--git".yellow(), "--path".yellow())))?
+            return Err(AnyError(format!("select an option to install from '{}', '{}', '{}', '{}', or '{}'", "--ip".yellow(), "--git".yellow(), "--url".yellow(), "--path".yellow(), "--deps".yellow())))?
+        };
+        // a dry run stops where the repository would need to be fetched
+        let ip_root = match ip_root {
+            Some(root) => root,
+            None => return Ok(()),
         };
         // enter action
-        self.run(&ip_root, &catalog, c.force)
+        self.run(&ip_root, &catalog, c.force, &guard)
     }
 }
 
+/// Finds the repository of the `ip` already kept in the store.
+pub fn stored_install_path(ip: &PkgId, catalog: &Catalog) -> Result<Option<PathBuf>, Fault> {
+    let ids = catalog.inner().keys().collect();
+    let target = crate::core::ip::find_ip(ip, ids)?;
+    Ok(catalog.get_store().as_stored(&target))
+}
+
 /// Grabs the root path to the repository to perform the installation on.
-pub fn fetch_install_path(ip: &PkgId, catalog: &Catalog, disable_ssh: bool, temp_dir: &TempDir) -> Result<PathBuf, Fault> {
+pub fn fetch_install_path(ip: &PkgId, version: &AnyVersion, catalog: &Catalog, disable_ssh: bool, temp_dir: &TempDir) -> Result<PathBuf, Fault> {
     let ids = catalog.inner().keys().map(|f| { f }).collect();
 
     let target = crate::core::ip::find_ip(ip, ids)?;
@@ -114,11 +177,19 @@ pub fn fetch_install_path(ip: &PkgId, catalog: &Catalog, disable_ssh: bool, temp
     // check the store/ for the repository
     if let Some(root) = catalog.get_store().as_stored(&target) {
         Ok(root)
+    // let the vendor fetch the ip through its own hook
+    } else if let Some(path) = fetch_from_vendor(&target, version, catalog, temp_dir.path())? {
+        Ok(path)
+    // download the release archive if the ip's source is one
+    } else if let Some(url) = status.try_repository().filter(|url| archive::is_archive(url)) {
+        archive::fetch(url, temp_dir.path())
     // clone from remote repository if exists
     } else if let Some(url) = status.try_repository() {
         let path = temp_dir.path().to_path_buf();
         println!("info: fetching repository ...");
-        ExtGit::new(None).clone(&url, &path, disable_ssh)?;
+        ExtGit::new(None)
+            .transport(catalog.get_transport(&target))
+            .clone(url, &path, disable_ssh)?;
         Ok(path)
     } else {
         // @TODO last resort, clone the actual dev directory to a temp folder
@@ -126,9 +197,32 @@ pub fn fetch_install_path(ip: &PkgId, catalog: &Catalog, disable_ssh: bool, temp
     }
 }
 
+/// Runs the fetch hook of the vendor of `ip` to place the requested `version` into `dest`.
+/// 
+/// Returns the path to install from, or `None` if the vendor does not have a fetch hook.
+pub fn fetch_from_vendor(ip: &PkgId, version: &AnyVersion, catalog: &Catalog, dest: &Path) -> Result<Option<PathBuf>, Fault> {
+    let vendor = match catalog.get_vendor(ip) {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+    // pass the exact version when the vendor's index knows of it
+    let version = catalog.inner().get(ip)
+        .and_then(|status| status.get_available(version))
+        .map(|m| m.get_version().to_string())
+        .unwrap_or_else(|| version.to_string());
+    if !vendor.fetch(ip, &version, dest)? {
+        return Ok(None)
+    }
+    // the hook places either a repository or the files of a single version
+    match dest.join(".git").exists() {
+        true => Ok(Some(dest.to_path_buf())),
+        false => Ok(Some(archive::find_root(dest, None)?)),
+    }
+}
+
 impl Install {
 
-    pub fn install_from_lock_file(&self, lock: &LockFile, catalog: &Catalog) -> Result<(), Fault> {
+    pub fn install_from_lock_file(&self, lock: &LockFile, catalog: &Catalog, guard: &WriteGuard) -> Result<(), Fault> {
         // build entire dependency graph from lockfile @todo: denote which ip's are from dev path to ensure they are "develop_from_lock_entry"
         let graph = ip::graph_ip_from_lock(&lock)?;
         // sort to topological ordering
@@ -136,21 +230,121 @@ impl Install {
         // remove target ip from the list of intermediate installations
         order.pop();
 
+        let mut bar = ProgressBar::new("dependencies", order.len());
         for i in order {
             let entry = graph.get_node_by_index(i).unwrap().as_ref();
+            if !guard.is_dry_run() {
+                bar.inc(&format!("{} v{}", entry.get_name(), entry.get_version()));
+            }
             // check if already installed
             match std::path::Path::exists(&catalog.get_cache_path().join(entry.to_cache_slot().as_ref())) {
                 true => println!("info: {} v{} already installed", entry.get_name(), entry.get_version()),
-                false => Plan::install_from_lock_entry(entry, &AnyVersion::Specific(entry.get_version().to_partial_version()), &catalog, self.disable_ssh)?,
+                false => {
+                    guard.perform(&format!("install dependency {} v{} from the lock file", entry.get_name(), entry.get_version()), || {
+                        Plan::install_from_lock_entry(entry, &AnyVersion::Specific(entry.get_version().to_partial_version()), catalog, self.disable_ssh)
+                    })?;
+                },
             }
         }
+        if !guard.is_dry_run() {
+            bar.finish();
+        }
         Ok(())
     }
 
+    /// Installs every dependency in the current ip's lock file that is missing
+    /// from the cache, without planning.
+    ///
+    /// Every dependency is attempted and a table reports what happened to each.
+    fn install_dependencies(&self, c: &Context) -> Result<(), Fault> {
+        c.goto_ip_path()?;
+        let target = IpManifest::from_path(c.get_ip_path().unwrap())?;
+        if !target.can_use_lock() {
+            return Err(AnyError(format!("lock file for ip '{}' is missing or out of date\n\nTry `orbit plan --lock-only` to update the lock file", target.get_pkgid())))?
+        }
+        let lock = target.into_lockfile()?;
+        let guard = WriteGuard::new(self.dry_run);
+
+        let catalog = Catalog::new()
+            .store(c.get_store_path())
+            .development(c.get_development_paths())?
+            .trust(c.get_trust())
+            .installations(c.get_cache_path())?
+            .available(c.get_vendors())?;
+
+        let entries: Vec<&LockEntry> = lock.inner()
+            .iter()
+            .filter(|e| e.get_name() != target.get_pkgid())
+            .collect();
+        let mut results: Vec<(&LockEntry, DepStatus)> = Vec::with_capacity(entries.len());
+        let mut failures: Vec<(&LockEntry, String)> = Vec::new();
+        let mut bar = ProgressBar::new("dependencies", entries.len());
+        for entry in entries {
+            if !guard.is_dry_run() {
+                bar.inc(&format!("{} v{}", entry.get_name(), entry.get_version()));
+            }
+            // entries without a checksum cannot be verified once installed
+            let installed = c.get_cache_path().join(entry.to_cache_slot().as_ref()).exists();
+            if entry.get_sum().is_none() || (installed && !c.force) {
+                results.push((entry, DepStatus::Skipped));
+                continue
+            }
+            let ver = AnyVersion::Specific(entry.get_version().to_partial_version());
+            let status = match guard.perform(&format!("install dependency {} v{} from the lock file", entry.get_name(), entry.get_version()), || {
+                Plan::install_from_lock_entry(entry, &ver, &catalog, self.disable_ssh)
+            }) {
+                Ok(Some(_)) => DepStatus::Installed,
+                Ok(None) => DepStatus::Pending,
+                Err(e) => {
+                    failures.push((entry, e.to_string()));
+                    DepStatus::Failed
+                }
+            };
+            results.push((entry, status));
+        }
+        if !guard.is_dry_run() {
+            bar.finish();
+        }
+        println!("{}", theme::table(&Self::fmt_dependency_table(&results)));
+
+        match failures.is_empty() {
+            true => Ok(()),
+            false => {
+                let reasons = failures.iter().fold(String::new(), |acc, (entry, e)| {
+                    acc + &format!("\n  {} v{}: {}", entry.get_name(), entry.get_version(), e)
+                });
+                Err(AnyError(format!("failed to install {} of {} dependencies:{}", failures.len(), results.len(), reasons)))?
+            }
+        }
+    }
+
+    fn fmt_dependency_table(results: &[(&LockEntry, DepStatus)]) -> String {
+        let header = format!("\
+{:<40}{:<12}{:<9}
+{:->40}{3:->12}{3:->9}\n",
+            "Ip", "Version", "Status", " ");
+        let mut body = String::new();
+        for (entry, status) in results {
+            body.push_str(&format!("{:<40}{:<12}{:<9}\n", entry.get_name().to_string(), entry.get_version().to_string(), status.to_string()));
+        }
+        header + &body
+    }
+
     /// Searches through a given root as a git repository to find a tagged commit
     /// matching `version` with highest compatibility and contains a manifest.
+    /// 
+    /// A `root` without git history, such as an extracted archive, holds a single
+    /// version that must match `version`.
     fn detect_manifest(root: &PathBuf, version: &AnyVersion, store: &Store) -> Result<IpManifest, Fault>{
-        let repo = Repository::open(&root)?;
+        let repo = match Repository::open(root) {
+            Ok(r) => r,
+            Err(_) if root.join(manifest::IP_MANIFEST_FILE).exists() => {
+                let ip = IpManifest::from_path(root)?;
+                version::get_target_version(version, &vec![ip.get_version()])?;
+                return Ok(ip)
+            },
+            Err(e) => return Err(e)?,
+        };
 
         // find the specified version for the given ip
         let space = ExtGit::gather_version_tags(&repo)?;
@@ -174,23 +368,28 @@ impl Install {
         ExtGit::checkout_tag_state(&repo, &version)?;
 
         // make an ip manifest
-        Ok(IpManifest::from_path(&root)?)
+        IpManifest::from_path(&ExtGit::ip_root(&repo, root))
     }
 
     /// Installs the `ip` with particular partial `version` to the `cache_root`.
     /// It will reinstall if it finds the original installation has a mismatching checksum.
     /// 
+    /// An `installation_path` without git history is installed as-is, without being stored.
+    /// 
     /// Errors if the ip is already installed unless `force` is true.
     pub fn install(installation_path: &PathBuf, version: &AnyVersion, cache_root: &std::path::PathBuf, force: bool, store: &Store) -> Result<IpManifest, Fault> {
         // make an ip manifest
         let ip = Self::detect_manifest(&installation_path, &version, &store)?;
+        if Repository::open(installation_path).is_err() {
+            return Self::install_root(&ip, None, cache_root, store, force)
+        }
         let target = ip.get_pkgid();
 
         // move into stored directory to compute checksum for the tagged version
         let temp = match store.is_stored(&target) {
-            true => ip.get_root(),
+            true => installation_path.to_path_buf(),
             // throw repository into the store/ for future use
-            false => store.store(&ip)?,
+            false => store.store(target, installation_path)?,
         };
         // update version to be a specific complete spec
         let version = ip.get_version();
@@ -198,8 +397,41 @@ impl Install {
         let repo = Repository::open(&temp)?;
         ExtGit::checkout_tag_state(&repo, &version)?;
 
-        let root = IpManifest::from_path(&temp).unwrap();
-        println!("info: installing {} v{} ...", root.get_pkgid(), root.get_version());
+        let commit = ExtGit::tag_commit(&repo, version)?;
+
+        // only the ip's directory is installed when it is within a subdirectory of the repository
+        let root = IpManifest::from_path(&ExtGit::ip_root(&repo, &temp)).unwrap();
+        let result = Self::install_root(&root, Some(&commit), cache_root, store, force);
+        // revert the store back to its HEAD
+        ExtGit::checkout_head(&repo)?;
+        // keep the installed commit in the store to rebuild the installation without the remote
+        if result.is_ok() {
+            store.snapshot(target, version, &commit, &temp)?;
+        }
+        result
+    }
+
+    /// Installs the ip at the exact `commit` of its stored repository at `root` to the `cache_root`.
+    /// 
+    /// The version's tag and the repository's remote are not needed, so an installation recorded in
+    /// a lock file can be rebuilt after its source disappears.
+    pub fn install_from_snapshot(root: &PathBuf, commit: &str, cache_root: &std::path::PathBuf, force: bool, store: &Store) -> Result<IpManifest, Fault> {
+        let repo = Repository::open(root)?;
+        ExtGit::checkout_commit(&repo, commit)?;
+        let result = IpManifest::from_path(&ExtGit::ip_root(&repo, root))
+            .and_then(|ip| Self::install_root(&ip, Some(commit), cache_root, store, force));
+        // revert the store back to its HEAD
+        ExtGit::checkout_head(&repo)?;
+        result
+    }
+
+    /// Copies the files of the checked out `root` ip into its cache slot within `cache_root`.
+    /// 
+    /// The `commit` is recorded in the installation's metadata when the ip came from a repository.
+    fn install_root(root: &IpManifest, commit: Option<&str>, cache_root: &std::path::Path, store: &Store, force: bool) -> Result<IpManifest, Fault> {
+        let target = root.get_pkgid();
+        let version = root.get_version();
+        let spinner = Spinner::new(&format!("installing {} v{} ...", root.get_pkgid(), root.get_version()));
 
         // perform sha256 on the temporary cloned directory 
         let checksum = root.compute_checksum();
@@ -211,7 +443,7 @@ impl Install {
         if std::path::Path::exists(&cache_slot) == true {
             // check if we should proceed with force regardless if the installation is valid
             if force == true {
-                std::fs::remove_dir_all(&cache_slot)?;
+                filesystem::remove_all(&cache_slot)?;
             } else {
                 let cached_ip = IpManifest::from_path(&cache_slot)?;
                 // verify the installed version is valid
@@ -224,35 +456,107 @@ impl Install {
                 println!("info: reinstalling ip '{}' as version '{}' due to bad checksum", target, version);
 
                 // blow directory up for re-install
-                std::fs::remove_dir_all(&cache_slot)?;
+                filesystem::remove_all(&cache_slot)?;
             }
         }
-        // copy contents into cache slot
-        crate::util::filesystem::copy(&temp, &cache_slot, true)?;
-        // revert the store back to its HEAD
-        ExtGit::checkout_head(&repo)?;
+        // stage the installation outside of the cache so an interrupted install never leaves a partial slot
+        let staging = tempfile::Builder::new()
+            .prefix(".orbit-install-")
+            .tempdir_in(Self::staging_root(cache_root))?;
+        let staged_slot = staging.path().join(cache_slot_name.as_ref());
+        // copy contents into staged slot
+        crate::util::filesystem::copy(&root.get_root(), &staged_slot, true)?;
 
         // write the checksum to the directory
-        std::fs::write(&cache_slot.join(manifest::ORBIT_SUM_FILE), checksum.to_string().as_bytes())?;
+        std::fs::write(staged_slot.join(manifest::ORBIT_SUM_FILE), checksum.to_string().as_bytes())?;
         // write the metadata to the directory
-        let mut installed_ip = IpManifest::from_path(&cache_slot)?;
-        installed_ip.write_metadata()?;
+        let mut staged_ip = IpManifest::from_path(&staged_slot)?;
+        if let Some(commit) = commit {
+            staged_ip.set_commit(commit);
+        }
+        staged_ip.write_metadata()?;
+        // reuse the files other installations already have
+        store.share(&staged_slot)?;
+        // ensure the contents are on disk before they become visible in the cache
+        filesystem::sync_all(&staged_slot)?;
+        // move the complete installation into the cache slot in one step
+        std::fs::rename(&staged_slot, &cache_slot)?;
+        // guard the installation against accidental edits
+        filesystem::set_readonly(&cache_slot, true)?;
+        let installed_ip = IpManifest::from_path(&cache_slot)?;
+        spinner.finish(Some(&format!("installed {} v{}", root.get_pkgid(), root.get_version())));
         Ok(installed_ip)
     }
 
-    fn run(&self, installation_path: &PathBuf, catalog: &Catalog, force: bool) -> Result<(), Fault> {
+    /// Determines the directory to stage installations in before moving them into `cache_root`.
+    /// 
+    /// The directory is next to the cache to stay on the same filesystem, but not inside it
+    /// to avoid being detected as an installation.
+    fn staging_root(cache_root: &std::path::Path) -> PathBuf {
+        match cache_root.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+            _ => PathBuf::from("."),
+        }
+    }
+
+    fn run(&self, installation_path: &PathBuf, catalog: &Catalog, force: bool, guard: &WriteGuard) -> Result<(), Fault> {
         // check if there is a potential lockfile to use
         let man = Self::detect_manifest(&installation_path, &self.version, catalog.get_store())?;
+        if guard.is_dry_run() {
+            println!("info: resolved '{}' to ip {} v{}", self.version, man.get_pkgid(), man.get_version());
+        }
         if let Some(lock) = man.get_lockfile() {
-            Self::install_from_lock_file(&self, &lock, &catalog)?;
+            self.install_from_lock_file(&lock, catalog, guard)?;
         }
         // if the lockfile is invalid, then it will only install the current request and zero dependencies
         
-        let _ = Self::install(&installation_path, &self.version, &catalog.get_cache_path(), force, &catalog.get_store())?;
+        match guard.is_dry_run() {
+            true => Self::preview(&man, catalog.get_cache_path(), force),
+            false => {
+                let ip = Self::install(installation_path, &self.version, catalog.get_cache_path(), force, catalog.get_store())?;
+                // verify the release is signed as the vendor's trust policy expects
+                if let Err(e) = signature::verify(&ip, catalog.get_trust(), catalog.get_vendor(ip.get_pkgid())) {
+                    ip.remove()?;
+                    return Err(e)
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Reports the cache slot the checked out `ip` would be installed to.
+    fn preview(ip: &IpManifest, cache_root: &std::path::Path, force: bool) -> Result<(), Fault> {
+        let slot = CacheSlot::new(ip.get_pkgid().get_name(), ip.get_version(), &ip.compute_checksum());
+        match cache_root.join(slot.as_ref()).exists() && !force {
+            true => println!("info: {} v{} is already installed in cache slot {}", ip.get_pkgid(), ip.get_version(), slot.as_ref()),
+            false => println!("info: would install {} v{} to cache slot {}", ip.get_pkgid(), ip.get_version(), slot.as_ref()),
+        }
         Ok(())
     }
 }
 
+/// What happened to a dependency during `orbit install --deps`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum DepStatus {
+    Installed,
+    /// Already in the cache, or without a checksum to verify against.
+    Skipped,
+    Failed,
+    /// Would be installed outside of a dry run.
+    Pending,
+}
+
+impl std::fmt::Display for DepStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Installed => write!(f, "installed"),
+            Self::Skipped => write!(f, "skipped"),
+            Self::Failed => write!(f, "failed"),
+            Self::Pending => write!(f, "pending"),
+        }
+    }
+}
+
 const HELP: &str = "\
 Places an immutable version of an ip to the cache for dependency usage.
 
@@ -261,11 +565,16 @@ Usage:
 
 Options:
     --ip <ip>               pkgid to access an orbit ip to install
-    --variant, -v <version> version to install
+    --variant, -v <version> version to install (default: latest)
     --path <path>           local filesystem path to install from
     --git <url>             remote repository to clone
+    --url <url>             release archive to download (.zip, .tar.gz, .tgz, .tar)
+    --sparse                only fetch the subdirectory of a --git url holding the ip
     --force                 install regardless of cache slot occupancy
     --disable-ssh           convert SSH repositories to HTTPS for dependencies
+    --dry-run               list the resolution decisions without installing
+    --deps                  install the missing dependencies of the current ip
+    --exact                 require a fully qualified --ip without completion
 
 Use 'orbit help install' to learn more about the command.
 ";
\ No newline at end of file
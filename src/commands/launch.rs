@@ -51,6 +51,7 @@ pub struct Launch {
     ready: bool,
     message: Option<String>,
     no_install: bool,
+    allow_branch: bool,
 }
 
 impl FromCli for Launch {
@@ -61,6 +62,7 @@ impl FromCli for Launch {
             next: cli.check_option(Optional::new("next").value("version"))?,
             message: cli.check_option(Optional::new("message").switch('m'))?,
             no_install: cli.check_flag(Flag::new("no-install"))?,
+            allow_branch: cli.check_flag(Flag::new("allow-branch"))?,
         });
         command
     }
@@ -138,8 +140,6 @@ impl Command for Launch {
             r?
         };
 
-        // @todo: report if there are unsaved changes in the working directory/staging index?
-
         // verify the repository's HEAD is up-to-date (git remote update)
         print!("info: checking git repository remotes ... ");
         std::io::stdout().flush().ok().expect("could not flush stdout");
@@ -154,6 +154,27 @@ impl Command for Launch {
         let local_name = b.name()?.unwrap().to_string();
         println!("info: on local git branch '{}'", local_name);
 
+        // verify the branch is one of the configured release branches
+        let branches: Vec<&str> = c.get_config().collect_as_array_of_str("launch", "branches")?
+            .into_iter()
+            .map(|f| f.0)
+            .collect();
+        if branches.is_empty() == false && branches.contains(&local_name.as_str()) == false {
+            match self.allow_branch {
+                true => println!("info: releasing from non-release branch '{}' ... {}", local_name, "allowed".yellow()),
+                false => return Err(AnyError(format!("git branch '{}' is not a release branch\n\nRelease branches: {}\nUse `{}` to launch from this branch anyway", local_name, branches.join(", "), "--allow-branch".green())))?,
+            }
+        }
+
+        // verify the working tree has no uncommitted changes that would be left out of the release
+        {
+            print!("info: checking working tree ... ");
+            std::io::stdout().flush().ok().expect("could not flush stdout");
+            let r = Launch::verify_clean_tree(&repo, &[IP_MANIFEST_FILE, IP_LOCK_FILE]);
+            println!("{}", util::prompt::report_eval(r.is_ok()));
+            r?
+        }
+
         let up_b = match b.upstream() {
             Ok(r) => Some(r),
             Err(_) => None,
@@ -246,7 +267,8 @@ impl Command for Launch {
 
         // find the registry by using the vendor
         let registry = c.get_vendors().get(manifest.get_pkgid().get_vendor().as_ref().unwrap());
-        let publish = registry.is_some() && manifest.get_repository().is_some() && push;
+        // a vendor with a publish hook delivers releases without needing the ip's repository
+        let publish = registry.map(|r| manifest.get_repository().is_some() || r.has_publish_hook()).unwrap_or(false) && push;
         println!("info: publishing to registry ... {}", util::prompt::report_eval(publish));
 
         // --- verify git things
@@ -309,7 +331,7 @@ impl Command for Launch {
 
             // store the repository
             let store = Store::new(c.get_store_path());
-            store.store(&manifest)?;
+            store.store(manifest.get_pkgid(), &manifest.get_root())?;
 
             // perform installation to the cache
             if self.no_install == false {
@@ -341,7 +363,8 @@ impl Launch {
         // gather the catalog
         let catalog = Catalog::new()
             .store(c.get_store_path())
-            .development(c.get_development_path().unwrap())?
+            .aliases(c.get_aliases())
+            .development(c.get_development_paths())?
             .installations(c.get_cache_path())?
             .available(c.get_vendors())?;
         // build entire ip graph and resolve with dynamic symbol transformation
@@ -349,7 +372,7 @@ impl Launch {
             .get_map()
             .iter()
             .map(|p| { p.1.as_ref().as_original_ip() })
-            .collect()))
+            .collect(), catalog.get_aliases()))
     }
 
     /// Verifies the `file` is committed (not in staging or working directory if
@@ -361,6 +384,25 @@ impl Launch {
             false => Err(AnyError(format!("file '{}' is dirty; move changes out of working directory or staging index to enter a clean state", file)))?,
         }
     }
+
+    /// Verifies no tracked files besides those in `skip` have changes in the
+    /// working directory or staging index.
+    /// 
+    /// Untracked and ignored files are not considered because they are never
+    /// part of the tagged commit.
+    fn verify_clean_tree(repo: &Repository, skip: &[&str]) -> Result<(), Fault> {
+        let mut opts = git2::StatusOptions::new();
+        opts.include_untracked(false).include_ignored(false);
+        let dirty: Vec<String> = repo.statuses(Some(&mut opts))?
+            .iter()
+            .filter_map(|e| e.path().map(|p| p.to_string()))
+            .filter(|p| skip.contains(&p.as_str()) == false)
+            .collect();
+        match dirty.is_empty() {
+            true => Ok(()),
+            false => Err(AnyError(format!("working tree has uncommitted changes to {}; commit or stash them before launching", dirty.iter().map(|p| format!("'{}'", p)).collect::<Vec<String>>().join(", "))))?,
+        }
+    }
 }
 
 const HELP: &str = "\
@@ -374,6 +416,7 @@ Options:
     --next <version>        semver version or 'major', 'minor', or 'patch'
     --message, -m <message> message to apply to the commit when using '--next'
     --no-install            skip installing newly launched version
+    --allow-branch          launch from a branch not listed in 'launch.branches'
 
 Use 'orbit help launch' to learn more about the command.
 ";
\ No newline at end of file
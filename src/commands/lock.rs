@@ -0,0 +1,126 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::lockfile::{LockFile, IP_LOCK_FILE};
+use crate::core::manifest::IpManifest;
+use crate::interface::cli::Cli;
+use crate::interface::arg::Flag;
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::Fault;
+use super::plan::Plan;
+
+#[derive(Debug, PartialEq)]
+pub struct Lock {
+    sync: bool,
+    disable_ssh: bool,
+}
+
+impl FromCli for Lock {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Lock {
+            sync: cli.check_flag(Flag::new("sync"))?,
+            disable_ssh: cli.check_flag(Flag::new("disable-ssh"))?,
+        });
+        command
+    }
+}
+
+impl Command for Lock {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+        let ip = IpManifest::from_path(c.get_ip_path().unwrap())?;
+        let before = ip.into_lockfile()?;
+
+        match self.sync {
+            true => Plan::for_lock_sync(self.disable_ssh).exec(c)?,
+            false => Plan::for_lock(self.disable_ssh).exec(c)?,
+        }
+
+        let changes = Self::changes(&before, &ip.into_lockfile()?);
+        match changes.is_empty() {
+            true => println!("info: {} is up to date", IP_LOCK_FILE),
+            false => {
+                println!("info: updated {}:", IP_LOCK_FILE);
+                for line in changes {
+                    println!("    {}", line);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Lock {
+    /// Lists the ip versions that were added, removed, or updated from the `before` lockfile
+    /// to the `after` lockfile.
+    fn changes(before: &LockFile, after: &LockFile) -> Vec<String> {
+        let added: Vec<_> = after.inner().iter()
+            .filter(|e| before.get(e.get_name(), e.get_version()).is_none())
+            .collect();
+        let removed: Vec<_> = before.inner().iter()
+            .filter(|e| after.get(e.get_name(), e.get_version()).is_none())
+            .collect();
+
+        let mut changes = Vec::new();
+        for entry in &added {
+            // a single version replacing a single version of the same ip is an update
+            let old: Vec<_> = removed.iter().filter(|e| e.get_name() == entry.get_name()).collect();
+            let new_count = added.iter().filter(|e| e.get_name() == entry.get_name()).count();
+            match (old.as_slice(), new_count) {
+                ([prev], 1) => changes.push(format!("updated {} v{} -> v{}", entry.get_name(), prev.get_version(), entry.get_version())),
+                _ => changes.push(format!("added {} v{}", entry.get_name(), entry.get_version())),
+            }
+        }
+        for entry in &removed {
+            let old_count = removed.iter().filter(|e| e.get_name() == entry.get_name()).count();
+            let new_count = added.iter().filter(|e| e.get_name() == entry.get_name()).count();
+            if old_count != 1 || new_count != 1 {
+                changes.push(format!("removed {} v{}", entry.get_name(), entry.get_version()));
+            }
+        }
+        changes
+    }
+}
+
+const HELP: &str = "\
+Write the lock file for the current ip.
+
+Usage:
+    orbit lock [options]
+
+Options:
+    --sync              only update the entries affected by manifest changes
+    --disable-ssh       convert SSH repositories to HTTPS for dependencies
+
+Use 'orbit help lock' to learn more about the command.
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::config::FromToml;
+    use toml_edit::Document;
+
+    fn lock(entries: &[(&str, &str)]) -> LockFile {
+        let contents: String = entries.iter()
+            .map(|(name, version)| format!("[[ip]]\nname = \"{}\"\nversion = \"{}\"\n\n", name, version))
+            .collect();
+        LockFile::from_toml(contents.parse::<Document>().unwrap().as_table()).unwrap()
+    }
+
+    #[test]
+    fn changes() {
+        let before = lock(&[("ks-tech.rary.gates", "1.0.0"), ("ks-tech.rary.mux", "0.2.0"), ("ks-tech.rary.uart", "2.0.0")]);
+        let after = lock(&[("ks-tech.rary.gates", "1.0.0"), ("ks-tech.rary.mux", "0.3.0"), ("ks-tech.rary.spi", "1.1.0")]);
+        assert_eq!(Lock::changes(&before, &after), vec![
+            "updated ks-tech.rary.mux v0.2.0 -> v0.3.0".to_string(),
+            "added ks-tech.rary.spi v1.1.0".to_string(),
+            "removed ks-tech.rary.uart v2.0.0".to_string(),
+        ]);
+        assert_eq!(Lock::changes(&before, &before).len(), 0);
+    }
+}
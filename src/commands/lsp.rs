@@ -0,0 +1,417 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::catalog::Catalog;
+use crate::core::lexer::{Position, Span};
+use crate::core::manifest::IpManifest;
+use crate::core::vhdl::doc;
+use crate::core::vhdl::index::IdentifierIndex;
+use crate::core::vhdl::symbol::{VHDLParser, VHDLSymbol};
+use crate::core::vhdl::token::{Identifier, VHDLToken, VHDLTokenizer};
+use crate::interface::cli::Cli;
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::progress;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq)]
+pub struct Lsp {}
+
+impl FromCli for Lsp {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        Ok(Lsp {})
+    }
+}
+
+impl Command for Lsp {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+        let ip = IpManifest::from_path(c.get_ip_path().unwrap())?;
+
+        // stdout is reserved for protocol messages
+        progress::set_quiet(true);
+        colored::control::set_override(false);
+
+        let mut server = Server {
+            index: Index::build(c, &ip),
+            documents: HashMap::new(),
+            ip,
+            shutdown: false,
+        };
+
+        let stdin = std::io::stdin();
+        let mut input = stdin.lock();
+        let stdout = std::io::stdout();
+        let mut output = stdout.lock();
+        while let Some(msg) = read_message(&mut input)? {
+            let method = msg.get("method").and_then(|m| m.as_str()).unwrap_or_default().to_string();
+            if method == "exit" {
+                break
+            }
+            for reply in server.handle(c, &method, &msg) {
+                write_message(&mut output, &reply)?;
+            }
+        }
+        match server.shutdown {
+            true => Ok(()),
+            false => Err(AnyError(String::from("language server exited without a shutdown request")))?,
+        }
+    }
+}
+
+/// A design unit that can be the target of a go-to-definition request.
+#[derive(Debug, PartialEq)]
+struct Definition {
+    file: PathBuf,
+    pos: Position,
+    library: String,
+    detail: String,
+}
+
+/// The primary design units declared in the current ip and its dependencies.
+#[derive(Debug, PartialEq)]
+struct Index(IdentifierIndex<Definition>);
+
+impl Index {
+    /// Reads every vhdl file from the `ip`'s dependency graph.
+    ///
+    /// Falls back to only the `ip`'s own files when its dependencies cannot be
+    /// resolved from the catalog (such as missing installations).
+    fn build(c: &Context, ip: &IpManifest) -> Self {
+        let catalog = Catalog::new()
+            .store(c.get_store_path())
+            .aliases(c.get_aliases())
+            .development(c.get_development_paths())
+            .and_then(|cat| cat.installations(c.get_cache_path()));
+        let files: Vec<(String, String)> = match catalog.map(|cat| {
+            crate::core::ip::compute_final_ip_graph(ip, &cat).map(|graph| {
+                crate::core::ip::build_ip_file_list(&graph)
+                    .iter()
+                    .map(|f| (f.get_file().to_string(), f.get_library().to_string()))
+                    .collect::<Vec<(String, String)>>()
+            })
+        }) {
+            Ok(Ok(files)) => files,
+            _ => crate::util::filesystem::gather_current_files(&ip.get_root())
+                .into_iter()
+                .map(|f| (f, Identifier::new_working().to_string()))
+                .collect(),
+        };
+
+        let mut index = IdentifierIndex::new();
+        for (file, library) in files {
+            if !crate::core::fileset::is_vhdl(&file) {
+                continue
+            }
+            let symbols = match std::fs::File::open(&file).and_then(|f| VHDLParser::read_from(std::io::BufReader::new(f))) {
+                Ok(parser) => parser.into_symbols(),
+                Err(_) => continue,
+            };
+            for sym in symbols {
+                let detail = match &sym {
+                    VHDLSymbol::Entity(e) => e.into_component().to_plain(),
+                    VHDLSymbol::Package(_) | VHDLSymbol::Context(_) | VHDLSymbol::Configuration(_) => sym.to_string(),
+                    _ => continue,
+                };
+                index.insert(sym.as_iden().unwrap(), Definition {
+                    file: PathBuf::from(&file),
+                    pos: sym.get_position().clone(),
+                    library: library.clone(),
+                    detail,
+                });
+            }
+        }
+        Self(index)
+    }
+
+    /// Finds the definition for `name`, preferring units in the working library.
+    fn get(&self, name: &Identifier) -> Option<&Definition> {
+        let defs = self.0.get(name);
+        defs.iter()
+            .find(|d| d.library == Identifier::new_working().to_string())
+            .or_else(|| defs.first())
+    }
+}
+
+struct Server {
+    index: Index,
+    documents: HashMap<String, String>,
+    ip: IpManifest,
+    shutdown: bool,
+}
+
+impl Server {
+    /// Processes a single message `msg` and returns the messages to send back.
+    fn handle(&mut self, c: &Context, method: &str, msg: &Value) -> Vec<Value> {
+        let params = msg.get("params").cloned().unwrap_or(Value::Null);
+        let uri = params.pointer("/textDocument/uri").and_then(|u| u.as_str()).unwrap_or_default().to_string();
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "hoverProvider": true,
+                    "documentSymbolProvider": true,
+                },
+                "serverInfo": { "name": "orbit", "version": env!("CARGO_PKG_VERSION") },
+            }),
+            "shutdown" => {
+                self.shutdown = true;
+                Value::Null
+            },
+            "textDocument/didOpen" => {
+                let text = params.pointer("/textDocument/text").and_then(|t| t.as_str()).unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_string());
+                return vec![self.publish_diagnostics(&uri)]
+            },
+            "textDocument/didChange" => {
+                // full document sync sends the entire text as the last change
+                if let Some(text) = params.pointer("/contentChanges").and_then(|c| c.as_array()).and_then(|c| c.last()).and_then(|c| c.get("text")).and_then(|t| t.as_str()) {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+                return vec![self.publish_diagnostics(&uri)]
+            },
+            "textDocument/didSave" => {
+                // definitions may have moved within the saved file
+                self.index = Index::build(c, &self.ip);
+                return Vec::new()
+            },
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![notification("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": [] }))]
+            },
+            "textDocument/definition" => match self.lookup(&uri, &params) {
+                Some(def) => json!({
+                    "uri": to_uri(&def.file),
+                    "range": to_range(&def.pos, 0),
+                }),
+                None => Value::Null,
+            },
+            "textDocument/hover" => match self.lookup(&uri, &params) {
+                Some(def) => json!({
+                    "contents": {
+                        "kind": "markdown",
+                        "value": format!("```vhdl\n{}\n```\n\nlibrary `{}`", def.detail.trim_end(), def.library),
+                    }
+                }),
+                None => Value::Null,
+            },
+            "textDocument/documentSymbol" => self.document_symbols(&uri),
+            _ => {
+                // notifications without a handler are ignored
+                return match msg.get("id") {
+                    Some(id) => vec![json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": -32601, "message": format!("unsupported method '{}'", method) },
+                    })],
+                    None => Vec::new(),
+                }
+            }
+        };
+        match msg.get("id") {
+            Some(id) => vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })],
+            None => Vec::new(),
+        }
+    }
+
+    /// References the text of the document at `uri`, reading it from disk if it is not open.
+    fn text(&self, uri: &str) -> Option<String> {
+        match self.documents.get(uri) {
+            Some(t) => Some(t.clone()),
+            None => std::fs::read_to_string(from_uri(uri)?).ok(),
+        }
+    }
+
+    /// Resolves the identifier under the cursor in `params` to its definition.
+    fn lookup(&self, uri: &str, params: &Value) -> Option<&Definition> {
+        let line = params.pointer("/position/line")?.as_u64()? as usize;
+        let character = params.pointer("/position/character")?.as_u64()? as usize;
+        let text = self.text(uri)?;
+        let name = identifier_at(&text, line, character)?;
+        self.index.get(&name)
+    }
+
+    /// Lists the design units declared in the document at `uri`.
+    fn document_symbols(&self, uri: &str) -> Value {
+        let text = match self.text(uri) {
+            Some(t) => t,
+            None => return json!([]),
+        };
+        let symbols: Vec<Value> = VHDLParser::read(&text).into_symbols().iter().map(|sym| {
+            let (name, kind) = match sym {
+                VHDLSymbol::Entity(e) => (e.get_name().to_string(), SYMBOL_INTERFACE),
+                VHDLSymbol::Architecture(a) => (format!("{} of {}", sym.as_iden().unwrap(), a.entity()), SYMBOL_CLASS),
+                VHDLSymbol::Package(_) => (sym.as_iden().unwrap().to_string(), SYMBOL_PACKAGE),
+                VHDLSymbol::PackageBody(pb) => (format!("{} body", pb.get_owner()), SYMBOL_PACKAGE),
+                VHDLSymbol::Configuration(_) => (sym.as_iden().unwrap().to_string(), SYMBOL_OBJECT),
+                VHDLSymbol::Context(_) => (sym.as_iden().unwrap().to_string(), SYMBOL_NAMESPACE),
+            };
+            // the whole unit is the range while its first keyword is selected
+            json!({ "name": name, "kind": kind, "range": span_to_range(&sym.get_span()), "selectionRange": to_range(sym.get_position(), 0) })
+        }).collect();
+        Value::Array(symbols)
+    }
+
+    /// Checks the document at `uri` for tokenizing errors and features beyond its declared standard.
+    fn publish_diagnostics(&self, uri: &str) -> Value {
+        let text = self.text(uri).unwrap_or_default();
+        let mut diagnostics: Vec<Value> = VHDLTokenizer::from_source_code(&text)
+            .into_errors()
+            .iter()
+            .map(|e| json!({
+                "range": to_range(e.locate(), 1),
+                "severity": SEVERITY_ERROR,
+                "source": "orbit",
+                "message": e.as_err().to_string().trim_end(),
+            }))
+            .collect();
+        // an annotated standard takes precedence over the manifest
+        let annotated = doc::read(&text).unwrap_or_default().iter()
+            .find_map(|d| d.get_annotations().ok().and_then(|a| a.get_standard().copied()));
+        let declared = from_uri(uri).and_then(|p| self.ip.get_file_standard(&p.display().to_string()).copied());
+        if let Some(std) = annotated.or(declared) {
+            let (_, violations) = VHDLParser::read_as(&text, &std);
+            violations.iter().for_each(|v| diagnostics.push(json!({
+                "range": span_to_range(v.get_span()),
+                "severity": SEVERITY_ERROR,
+                "source": "orbit",
+                "message": format!("{} but the file is declared as VHDL-{}", v, std),
+            })));
+        }
+        notification("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": diagnostics }))
+    }
+}
+
+/// Finds the identifier covering the zero-indexed `line` and `character` in `text`.
+fn identifier_at(text: &str, line: usize, character: usize) -> Option<Identifier> {
+    VHDLTokenizer::from_source_code(text)
+        .into_tokens()
+        .into_iter()
+        .filter(|t| t.locate().line() == line + 1)
+        .find_map(|t| {
+            let start = t.locate().col().saturating_sub(1);
+            let end = t.locate_end().col().saturating_sub(1);
+            match t.take() {
+                VHDLToken::Identifier(id) if character >= start && character < end => Some(id),
+                _ => None,
+            }
+        })
+}
+
+/// Creates a notification message for the `method`.
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+/// Converts a one-indexed `pos` into a zero-indexed range spanning `len` characters.
+fn to_range(pos: &Position, len: usize) -> Value {
+    let line = pos.line().saturating_sub(1);
+    let character = pos.col().saturating_sub(1);
+    json!({
+        "start": { "line": line, "character": character },
+        "end": { "line": line, "character": character + len },
+    })
+}
+
+/// Converts a one-indexed `span` into a zero-indexed range.
+fn span_to_range(span: &Span) -> Value {
+    json!({
+        "start": { "line": span.start().line().saturating_sub(1), "character": span.start().col().saturating_sub(1) },
+        "end": { "line": span.end().line().saturating_sub(1), "character": span.end().col().saturating_sub(1) },
+    })
+}
+
+fn to_uri(path: &Path) -> String {
+    url::Url::from_file_path(path).map(|u| u.to_string()).unwrap_or_else(|_| path.display().to_string())
+}
+
+fn from_uri(uri: &str) -> Option<PathBuf> {
+    url::Url::parse(uri).ok()?.to_file_path().ok()
+}
+
+/// Reads a single message framed with a `Content-Length` header.
+///
+/// Returns `None` when the input is closed.
+fn read_message(input: &mut impl BufRead) -> Result<Option<Value>, Fault> {
+    let mut length: Option<usize> = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None)
+        }
+        let header = header.trim_end();
+        // an empty line ends the header section
+        if header.is_empty() {
+            break
+        }
+        if let Some((key, value)) = header.split_once(':') {
+            if key.trim().eq_ignore_ascii_case("content-length") {
+                length = Some(value.trim().parse()?);
+            }
+        }
+    }
+    let length = match length {
+        Some(n) => n,
+        None => return Err(AnyError(String::from("message is missing the 'Content-Length' header")))?,
+    };
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+/// Writes the message `msg` framed with a `Content-Length` header.
+fn write_message(output: &mut impl Write, msg: &Value) -> Result<(), Fault> {
+    let body = msg.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()?;
+    Ok(())
+}
+
+// LSP constants for the values used by the server
+const SEVERITY_ERROR: u8 = 1;
+const SYMBOL_NAMESPACE: u8 = 3;
+const SYMBOL_PACKAGE: u8 = 4;
+const SYMBOL_CLASS: u8 = 5;
+const SYMBOL_INTERFACE: u8 = 11;
+const SYMBOL_OBJECT: u8 = 19;
+
+const HELP: &str = "\
+Run a language server for the current ip.
+
+Usage:
+    orbit lsp
+
+Use 'orbit help lsp' to learn more about the command.
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn framing() {
+        let mut out = Vec::new();
+        write_message(&mut out, &json!({ "jsonrpc": "2.0", "id": 1, "result": null })).unwrap();
+        assert_eq!(String::from_utf8(out.clone()).unwrap(), "Content-Length: 38\r\n\r\n{\"id\":1,\"jsonrpc\":\"2.0\",\"result\":null}");
+        let mut input = std::io::Cursor::new(out);
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({ "jsonrpc": "2.0", "id": 1, "result": null })));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn find_identifier() {
+        let text = "architecture rtl of top is\nbegin\n  u0 : entity work.and_gate port map(a, b);\nend;";
+        assert_eq!(identifier_at(text, 2, 20), Some(Identifier::Basic("and_gate".into())));
+        assert_eq!(identifier_at(text, 2, 17), Some(Identifier::Basic("work".into())));
+        assert_eq!(identifier_at(text, 2, 5), None);
+        assert_eq!(identifier_at(text, 0, 14), Some(Identifier::Basic("rtl".into())));
+    }
+}
\ No newline at end of file
@@ -1,16 +1,229 @@
+//! Manual pages for orbit, its subcommands, and its topics.
+//!
+//! The pages for the subcommands and topics are generated at build time from
+//! their markdown source in `docs/src/6_commands/` and `docs/src/7_help/` (see
+//! `build.rs`), so editing a page there updates both the book and `orbit help`.
+
 pub mod orbit;
-pub mod new;
-pub mod build;
-pub mod launch;
-pub mod plan;
-pub mod edit;
-pub mod tree;
-pub mod install;
-pub mod search;
-pub mod get;
-pub mod init;
-pub mod probe;
-pub mod env;
-pub mod config;
-pub mod read;
-pub mod uninstall;
\ No newline at end of file
+
+include!(concat!(env!("OUT_DIR"), "/manuals.rs"));
+
+/// A manual page divided into its sections.
+#[derive(Debug, PartialEq)]
+pub struct Manual {
+    pub name: &'static str,
+    pub sections: &'static [Section],
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Section {
+    pub title: &'static str,
+    pub lines: &'static [Line],
+}
+
+/// A line of a manual's section. Text may contain `code` spans.
+#[derive(Debug, PartialEq)]
+pub enum Line {
+    /// A line of a paragraph.
+    Text(&'static str),
+    /// A line of a verbatim block, such as an example.
+    Code(&'static str),
+    /// An option or subcommand being described by the lines after it.
+    Term(&'static str),
+    /// A line describing the term above it.
+    Desc(&'static str),
+    /// The end of a paragraph or entry.
+    Break,
+}
+
+/// Finds the manual page for the subcommand or topic `name`.
+pub fn find(name: &str) -> Option<&'static Manual> {
+    MANUALS.iter().chain(TOPICS.iter()).find(|m| m.name == name)
+}
+
+impl Manual {
+    /// Returns the short description given in the manual's NAME section.
+    pub fn get_summary(&self) -> &str {
+        self.sections.iter()
+            .find(|s| s.title == "NAME")
+            .and_then(|s| s.lines.iter().find_map(|l| match l {
+                Line::Text(t) => t.split_once(" - ").map(|(_, summary)| summary),
+                _ => None,
+            }))
+            .unwrap_or("")
+    }
+
+    /// Collects the lines of the manual mentioning `term`, ignoring case, as
+    /// they are rendered in plain text.
+    pub fn find_lines(&self, term: &str) -> Vec<String> {
+        let term = term.to_lowercase();
+        self.sections.iter()
+            .flat_map(|s| s.lines.iter())
+            .filter_map(|l| match l {
+                Line::Text(t) | Line::Code(t) | Line::Term(t) | Line::Desc(t) => Some(t.replace('`', "")),
+                Line::Break => None,
+            })
+            .filter(|t| t.to_lowercase().contains(&term))
+            .collect()
+    }
+
+    /// Renders the manual as plain text for the terminal.
+    pub fn to_plain(&self) -> String {
+        let mut result = String::new();
+        for (i, section) in self.sections.iter().enumerate() {
+            if i > 0 {
+                result.push('\n');
+            }
+            result.push_str(section.title);
+            result.push('\n');
+            for line in section.lines {
+                match line {
+                    Line::Text(s) | Line::Code(s) | Line::Term(s) => result.push_str(&format!("    {}", s.replace('`', ""))),
+                    Line::Desc(s) => result.push_str(&format!("          {}", s.replace('`', ""))),
+                    Line::Break => (),
+                }
+                result.push('\n');
+            }
+        }
+        result
+    }
+
+    /// Renders the manual as a roff document to be read by `man`.
+    pub fn to_roff(&self) -> String {
+        let title = match self.name {
+            "orbit" => String::from("ORBIT"),
+            _ => format!("ORBIT-{}", self.name.to_uppercase()),
+        };
+        let mut result = format!(".TH {} 1 \"\" \"orbit {}\" \"Orbit Manual\"\n", title, env!("CARGO_PKG_VERSION"));
+        for section in self.sections {
+            result.push_str(&format!(".SH {}\n", section.title));
+            let mut in_code = false;
+            let mut lines = section.lines.iter().peekable();
+            while let Some(line) = lines.next() {
+                if in_code != matches!(line, Line::Code(_)) {
+                    in_code = !in_code;
+                    result.push_str(if in_code { ".nf\n" } else { ".fi\n" });
+                }
+                let text = match line {
+                    Line::Text(s) | Line::Desc(s) => roff_text(s),
+                    Line::Code(s) => roff_escape(s),
+                    Line::Term(s) => {
+                        result.push_str(".TP\n");
+                        roff_text(s)
+                    }
+                    // the next entry starts its own paragraph
+                    Line::Break => match lines.peek() {
+                        Some(Line::Term(_)) | Some(Line::Break) | None => continue,
+                        _ => String::from(".PP"),
+                    },
+                };
+                // a line opening with a control character would be read as a request
+                if line != &Line::Break && (text.starts_with('.') || text.starts_with('\'')) {
+                    result.push_str("\\&");
+                }
+                result.push_str(&text);
+                result.push('\n');
+            }
+            if in_code == true {
+                result.push_str(".fi\n");
+            }
+        }
+        result
+    }
+}
+
+/// Escapes `s` for roff and sets its code spans in bold.
+fn roff_text(s: &str) -> String {
+    s.split('`')
+        .enumerate()
+        .map(|(i, part)| match i % 2 {
+            1 => format!("\\fB{}\\fR", roff_escape(part)),
+            _ => roff_escape(part),
+        })
+        .collect()
+}
+
+/// Escapes the characters roff treats specially in `s`.
+fn roff_escape(s: &str) -> String {
+    s.replace('\\', "\\e").replace('-', "\\-")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn registry_has_every_command() {
+        for name in ["new", "plan", "tree", "grep-sym", "highlight", "plugin"] {
+            assert_eq!(find(name).unwrap().name, name);
+        }
+        // pages left out of the command index are not registered
+        assert_eq!(find("develop"), None);
+        for manual in MANUALS {
+            let titles: Vec<&str> = manual.sections.iter().map(|s| s.title).collect();
+            assert_eq!(&titles[..3], ["NAME", "SYNOPSIS", "DESCRIPTION"], "manual for {}", manual.name);
+        }
+    }
+
+    #[test]
+    fn registry_has_every_topic() {
+        for name in ["ip", "pkgid", "version", "catalog", "plugins", "blueprint"] {
+            let manual = find(name).unwrap();
+            assert_eq!(TOPICS.contains(manual), true);
+            assert_eq!(manual.get_summary().is_empty(), false, "summary for {}", name);
+            assert_eq!(manual.sections.iter().any(|s| s.title == "DESCRIPTION"), true, "manual for {}", name);
+        }
+        // topics do not share names with subcommands
+        for topic in TOPICS {
+            assert_eq!(MANUALS.iter().any(|m| m.name == topic.name), false, "topic {}", topic.name);
+        }
+    }
+
+    #[test]
+    fn render_manual() {
+        const PAGE: Manual = Manual { name: "tree", sections: &[
+            Section { title: "NAME", lines: &[Line::Text("tree - view the dependency graph")] },
+            Section { title: "OPTIONS", lines: &[
+                Line::Term("`--ip`"),
+                Line::Desc("View the `ip` graph"),
+                Line::Break,
+                Line::Term("`--ascii`"),
+                Line::Desc("'ascii' characters only"),
+            ] },
+            Section { title: "EXAMPLES", lines: &[Line::Code("orbit tree --ip")] },
+        ] };
+        assert_eq!(PAGE.get_summary(), "view the dependency graph");
+        assert_eq!(PAGE.find_lines("IP"), vec!["--ip", "View the ip graph", "orbit tree --ip"]);
+        assert_eq!(PAGE.find_lines("missing").is_empty(), true);
+        assert_eq!(PAGE.to_plain(), "\
+NAME
+    tree - view the dependency graph
+
+OPTIONS
+    --ip
+          View the ip graph
+
+    --ascii
+          'ascii' characters only
+
+EXAMPLES
+    orbit tree --ip
+");
+        assert_eq!(PAGE.to_roff(), format!("\
+.TH ORBIT-TREE 1 \"\" \"orbit {}\" \"Orbit Manual\"
+.SH NAME
+tree \\- view the dependency graph
+.SH OPTIONS
+.TP
+\\fB\\-\\-ip\\fR
+View the \\fBip\\fR graph
+.TP
+\\fB\\-\\-ascii\\fR
+\\&'ascii' characters only
+.SH EXAMPLES
+.nf
+orbit tree \\-\\-ip
+.fi
+", env!("CARGO_PKG_VERSION")));
+    }
+}
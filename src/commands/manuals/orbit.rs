@@ -1,18 +1,23 @@
 // General help for orbit command-line tool.
-pub const MANUAL: &str = "\
-NAME
-    orbit - hdl package manager
+use super::{Line, Manual, Section};
 
-SYNOPSIS
-    orbit [options] [command]
-
-DESCRIPTION
-    Orbit is a tool for hdl package management and development.
-
-OPTIONS
-    --version
-        Print version information and exit
-     
-EXAMPLES
-    orbit --upgrade
-";
\ No newline at end of file
+pub const MANUAL: Manual = Manual { name: "orbit", sections: &[
+    Section { title: "NAME", lines: &[
+        Line::Text("orbit - hdl package manager"),
+    ] },
+    Section { title: "SYNOPSIS", lines: &[
+        Line::Code("orbit [options] [command]"),
+    ] },
+    Section { title: "DESCRIPTION", lines: &[
+        Line::Text("Orbit is a tool for hdl package management and development."),
+    ] },
+    Section { title: "OPTIONS", lines: &[
+        Line::Term("`--version`"),
+        Line::Desc("Print version information and exit. Add `--verbose` for the commit,"),
+        Line::Desc("build date, and supported format versions, or `--format json` to"),
+        Line::Desc("print them as JSON"),
+    ] },
+    Section { title: "EXAMPLES", lines: &[
+        Line::Code("orbit upgrade"),
+    ] },
+] };
@@ -3,7 +3,7 @@ mod help;
 mod new;
 mod manuals;
 mod search;
-mod plan;
+pub(crate) mod plan;
 mod build;
 mod edit;
 mod launch;
@@ -14,6 +14,25 @@ mod get;
 mod develop;
 mod env;
 mod init;
+mod import;
 mod config;
 mod read;
-mod uninstall;
\ No newline at end of file
+mod uninstall;
+mod run;
+mod cache;
+mod vendor;
+mod test;
+mod export;
+mod lsp;
+mod fmt;
+mod refactor;
+mod doc;
+mod clean;
+mod lock;
+mod doctor;
+mod report;
+mod bench;
+mod grepsym;
+mod highlight;
+mod plugin;
+mod upgrade;
\ No newline at end of file
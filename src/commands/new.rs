@@ -19,6 +19,10 @@ use std::error::Error;
 use std::path::PathBuf;
 use crate::util::anyerror::AnyError;
 use crate::core::template::Template;
+use crate::core::vhdl::syntax::SourceFile;
+use crate::core::vhdl::testbench;
+use crate::core::vhdl::token::Identifier;
+use super::get::Get;
 
 #[derive(Debug, PartialEq)]
 pub struct New {
@@ -28,6 +32,7 @@ pub struct New {
     list: bool,
     file: bool,
     from: Option<PathBuf>,
+    tb_for: Option<Identifier>,
 }
 
 impl FromCli for New {
@@ -40,6 +45,7 @@ impl FromCli for New {
             file: cli.check_flag(Flag::new("file"))?,
             template: cli.check_option(Optional::new("template").value("alias"))?,
             ip: cli.check_option(Optional::new("ip"))?,
+            tb_for: cli.check_option(Optional::new("tb-for").value("entity"))?,
         });
         command
     }
@@ -67,6 +73,16 @@ impl Command for New {
             return Ok(())
         }
 
+        // generate a testbench for an entity in the current ip
+        if let Some(entity) = &self.tb_for {
+            if self.ip.is_some() || self.file || self.template.is_some() || self.from.is_some() {
+                return Err(AnyError(format!("'{}' cannot be combined with '{}', '{}', '{}', or '{}'", "--tb-for".yellow(), "--ip".yellow(), "--file".yellow(), "--template".yellow(), "--from".yellow())))?
+            }
+            c.goto_ip_path()?;
+            let ip = IpManifest::from_path(c.get_ip_path().unwrap())?;
+            return self.new_testbench(&ip, entity, c.force)
+        }
+
         // user must either provide an pkgid or use --file flag (not both)
         if self.ip.is_some() && self.file == true {
             return Err(AnyError(format!("cannot create new ip with '{}' and file with '{}' at the same time", "--ip".yellow(), "--file".yellow())))?
@@ -119,7 +135,7 @@ impl Command for New {
             // verify the pkgid is not taken
             {
                 let catalog = Catalog::new()
-                    .development(c.get_development_path().unwrap())?
+                    .development(c.get_development_paths())?
                     .installations(c.get_cache_path())?
                     .available(c.get_vendors())?;
                 if catalog.inner().contains_key(&ip) == true {
@@ -195,6 +211,36 @@ impl New {
         Ok(())
     }
 
+    /// Writes a testbench skeleton for `entity` into the ip's simulation directory.
+    /// 
+    /// The default destination is sim/<entity>_tb.vhd unless --to is specified.
+    fn new_testbench(&self, ip: &IpManifest, entity: &Identifier, force: bool) -> Result<(), Fault> {
+        let (src, ent) = Get::fetch_entity(entity, ip)?;
+
+        let rel = match &self.to {
+            Some(p) => p.clone(),
+            None => PathBuf::from("sim").join(format!("{}.vhd", ent.get_name().into_extension("_tb"))),
+        };
+        let dest = ip.get_root().join(&rel);
+        if dest.exists() && !force {
+            return Err(AnyError(format!("destination {} already exists; use '{}' to overwrite", filesystem::normalize_path(rel).display(), "--force".yellow())))?
+        }
+
+        // reuse the libraries and packages visible to the entity
+        let context = match SourceFile::parse(&std::fs::read_to_string(&src)?) {
+            Ok(file) => testbench::context_clause(&file, ent.get_name()),
+            Err(_) => String::new(),
+        };
+        let code = testbench::generate(&ent, &context, ip.get_hdl_standard());
+
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest, code)?;
+        println!("info: new testbench for {} created at {}", ent.get_name(), filesystem::normalize_path(rel).display());
+        Ok(())
+    }
+
     fn run(&self, root: &std::path::PathBuf, force: bool, template: Option<&Template>, lut: &mut VariableTable) -> Result<(), Fault> {
         // create ip stemming from DEV_PATH with default /VENDOR/LIBRARY/NAME
         let ip_path = if self.to.is_none() {
@@ -263,6 +309,7 @@ Options:
     --file              create a file rather than an ip
     --from <path>       specify a source path to copy
     --list              view available templates
+    --tb-for <entity>   create a testbench for an entity in the current ip
     --force             overwrite the existing destination
 
 Use 'orbit help new' to read more about the command.
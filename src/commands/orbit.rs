@@ -6,17 +6,21 @@ use crate::interface::cli::Cli;
 use crate::interface::arg::{Flag, Positional};
 use crate::interface::errors::CliError;
 use crate::util::environment;
-use crate::util::prompt;
-use crate::core::context::Context;
-use crate::util::sha256::Sha256Hash;
+use crate::util::profile;
+use crate::util::buildinfo::{self, BuildInfo};
+use crate::core::context::{Context, GlobalOptions};
+use crate::core::mirror;
+use crate::core::network;
 use std::env;
 
 #[derive(Debug, PartialEq)]
 pub struct Orbit {
     help: bool,
-    upgrade: bool,
     version: bool,
-    force: bool,
+    format: buildinfo::Format,
+    options: GlobalOptions,
+    no_mirror: bool,
+    profile: Option<profile::Format>,
     command: Option<OrbitSubcommand>,
 }
 
@@ -31,29 +35,47 @@ impl Orbit {
     fn run(&self, _: &Context) -> Result<(), Box<dyn std::error::Error>> {
         // prioritize version information
         if self.version == true {
-            println!("orbit {}", VERSION);
-            Ok(())
-        // prioritize upgrade information
-        } else if self.upgrade == true {
-            println!("info: checking for latest orbit binary...");
-            let info = self.upgrade()?;
-            println!("info: {}", info);
+            match self.format {
+                buildinfo::Format::Text => print!("{}", buildinfo::format_text(&BuildInfo::current(), self.options.verbose)),
+                buildinfo::Format::Json => println!("{}", buildinfo::format_json(&BuildInfo::current())),
+            }
             Ok(())
+        // diagnose the environment before a context can fail to be set up
+        } else if let Some(OrbitSubcommand::Doctor(d)) = &self.command {
+            d.exec(&Context::new().retain_options(&self.options))
         // run the specified command
         } else if let Some(c) = &self.command {
+            if self.profile.is_some() {
+                profile::enable();
+            }
+            let clock = std::time::Instant::now();
+            let phase = profile::Phase::start("context");
             // set up the context (ignores the context passed in)
             let context = Context::new()
                 .home(environment::ORBIT_HOME)?
                 .cache(environment::ORBIT_CACHE)?
                 .store(environment::ORBIT_STORE)?
+                .layout()?
                 .current_ip_dir(environment::ORBIT_IP_PATH)? // must come before .settings() call
                 .settings(crate::core::config::CONFIG_FILE)?
+                .plugin_path(environment::ORBIT_PLUGIN_PATH)?
                 .build_dir(environment::ORBIT_BUILD_DIR)?
                 .development_path(environment::ORBIT_DEV_PATH, c.bypass_check() == false)?
                 .read_vendors()?
-                .retain_options(self.force);
+                .retain_options(&self.options);
+            // reach git sources through the configured mirrors unless bypassed
+            if !self.no_mirror {
+                mirror::set_mirrors(context.get_mirrors().clone());
+            }
+            network::set_network(context.get_network().clone());
+            drop(phase);
             // pass the context to the given command
-            c.exec(&context)
+            let result = c.exec(&context);
+            // report the breakdown even when the command fails
+            if let Some(format) = self.profile {
+                eprint!("{}", profile::fmt(&profile::take(), clock.elapsed(), format));
+            }
+            result
         // if no command is given then print default help
         } else {
             Ok(println!("{}", HELP))
@@ -64,20 +86,43 @@ impl Orbit {
 impl FromCli for Orbit {
     fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
         cli.set_help(HELP);
+        // global options are read before the subcommand to take them from anywhere on the command-line
         // need to set this coloring mode ASAP
-        match cli.check_option(Optional::new("color").value("when"))?.unwrap_or(ColorMode::Auto) {
+        match cli.check_option_default(Optional::new("color").value("when").choices(&["auto", "always", "never"]).default("auto"))? {
             ColorMode::Always => colored::control::set_override(true),
             ColorMode::Never => colored::control::set_override(false),
             ColorMode::Auto => (),
         }
-        let orbit = Ok(Orbit {
-            help: cli.check_flag(Flag::new("help").switch('h'))?,
-            upgrade: cli.check_flag(Flag::new("upgrade"))?,
-            version: cli.check_flag(Flag::new("version"))?,
-            force: cli.check_flag(Flag::new("force"))?,
+        // silence progress reporting before any command can begin
+        crate::util::progress::set_quiet(cli.check_flag(Flag::new("quiet").switch('q'))?);
+        let help = cli.check_flag(Flag::new("help").switch('h'))?;
+        let upgrade = cli.check_flag(Flag::new("upgrade"))?;
+        let version = cli.check_flag(Flag::new("version"))?;
+        let mut orbit = Orbit {
+            help: help,
+            version: version,
+            options: GlobalOptions {
+                force: cli.check_flag(Flag::new("force"))?,
+                verbose: cli.check_flag(Flag::new("verbose"))?,
+                offline: cli.check_flag(Flag::new("offline"))?,
+            },
+            no_mirror: cli.check_flag(Flag::new("no-mirror"))?,
+            profile: cli.check_option(Optional::new("profile").value("format").choices(&["table", "json"]))?,
+            format: {
+                cli.end_globals();
+                // the version is printed instead of running a command, so the format is not taken from one
+                match version {
+                    true => cli.check_option_default(Optional::new("format").value("fmt").choices(&["text", "json"]).default("text"))?,
+                    false => buildinfo::Format::Text,
+                }
+            },
             command: cli.check_command(Positional::new("command"))?,
-        });
-        orbit
+        };
+        // '--upgrade' is kept as a shorthand for 'orbit upgrade'
+        if upgrade == true && orbit.command.is_none() {
+            orbit.command = Some(OrbitSubcommand::Upgrade(Upgrade::default()));
+        }
+        Ok(orbit)
     }
 }
 
@@ -92,11 +137,30 @@ use crate::commands::install::Install;
 use crate::commands::tree::Tree;
 use crate::commands::get::Get;
 use crate::commands::init::Init;
+use crate::commands::import::Import;
 use crate::commands::probe::Probe;
 use crate::commands::env::Env;
 use crate::commands::config::Config;
 use crate::commands::uninstall::Uninstall;
 use crate::commands::read::Read;
+use crate::commands::run::Run;
+use crate::commands::cache::Cache;
+use crate::commands::vendor::Vendor;
+use crate::commands::test::Test;
+use crate::commands::export::Export;
+use crate::commands::lsp::Lsp;
+use crate::commands::fmt::Fmt;
+use crate::commands::refactor::Refactor;
+use crate::commands::doc::Doc;
+use crate::commands::clean::Clean;
+use crate::commands::lock::Lock;
+use crate::commands::doctor::Doctor;
+use crate::commands::report::Report;
+use crate::commands::bench::Bench;
+use crate::commands::grepsym::GrepSym;
+use crate::commands::highlight::Highlight;
+use crate::commands::upgrade::Upgrade;
+use crate::commands::plugin::Plugin;
 
 #[derive(Debug, PartialEq)]
 enum OrbitSubcommand {
@@ -111,11 +175,30 @@ enum OrbitSubcommand {
     Tree(Tree),
     Get(Get),
     Init(Init),
+    Import(Import),
     Probe(Probe),
     Env(Env),
     Config(Config),
     Uninstall(Uninstall),
     Read(Read),
+    Run(Run),
+    Cache(Cache),
+    Vendor(Vendor),
+    Test(Test),
+    Export(Export),
+    Lsp(Lsp),
+    Fmt(Fmt),
+    Refactor(Refactor),
+    Doc(Doc),
+    Clean(Clean),
+    Lock(Lock),
+    Doctor(Doctor),
+    Report(Report),
+    Bench(Bench),
+    GrepSym(GrepSym),
+    Highlight(Highlight),
+    Upgrade(Upgrade),
+    Plugin(Plugin),
 }
 
 impl FromCli for OrbitSubcommand {
@@ -131,6 +214,7 @@ impl FromCli for OrbitSubcommand {
             "install",
             "get",
             "init",
+            "import",
             "tree",
             "probe",
             "b",
@@ -138,6 +222,24 @@ impl FromCli for OrbitSubcommand {
             "config",
             "uninstall",
             "read",
+            "run",
+            "cache",
+            "vendor",
+            "test",
+            "export",
+            "lsp",
+            "fmt",
+            "refactor",
+            "doc",
+            "clean",
+            "lock",
+            "doctor",
+            "report",
+            "bench",
+            "grep-sym",
+            "highlight",
+            "upgrade",
+            "plugin",
         ])?.as_ref() {
             "get" => Ok(OrbitSubcommand::Get(Get::from_cli(cli)?)),
             "help" => Ok(OrbitSubcommand::Help(Help::from_cli(cli)?)),
@@ -147,6 +249,7 @@ impl FromCli for OrbitSubcommand {
       "b" | "build" => Ok(OrbitSubcommand::Build(Build::from_cli(cli)?)),
             "edit" => Ok(OrbitSubcommand::Edit(Edit::from_cli(cli)?)),
             "init" => Ok(OrbitSubcommand::Init(Init::from_cli(cli)?)),
+            "import" => Ok(OrbitSubcommand::Import(Import::from_cli(cli)?)),
             "launch" => Ok(OrbitSubcommand::Launch(Launch::from_cli(cli)?)),
             "install" => Ok(OrbitSubcommand::Install(Install::from_cli(cli)?)),
             "tree" => Ok(OrbitSubcommand::Tree(Tree::from_cli(cli)?)),
@@ -155,6 +258,24 @@ impl FromCli for OrbitSubcommand {
             "config" => Ok(OrbitSubcommand::Config(Config::from_cli(cli)?)),
             "uninstall" => Ok(OrbitSubcommand::Uninstall(Uninstall::from_cli(cli)?)),
             "read" => Ok(OrbitSubcommand::Read(Read::from_cli(cli)?)),
+            "run" => Ok(OrbitSubcommand::Run(Run::from_cli(cli)?)),
+            "cache" => Ok(OrbitSubcommand::Cache(Cache::from_cli(cli)?)),
+            "vendor" => Ok(OrbitSubcommand::Vendor(Vendor::from_cli(cli)?)),
+            "test" => Ok(OrbitSubcommand::Test(Test::from_cli(cli)?)),
+            "export" => Ok(OrbitSubcommand::Export(Export::from_cli(cli)?)),
+            "lsp" => Ok(OrbitSubcommand::Lsp(Lsp::from_cli(cli)?)),
+            "fmt" => Ok(OrbitSubcommand::Fmt(Fmt::from_cli(cli)?)),
+            "refactor" => Ok(OrbitSubcommand::Refactor(Refactor::from_cli(cli)?)),
+            "doc" => Ok(OrbitSubcommand::Doc(Doc::from_cli(cli)?)),
+            "clean" => Ok(OrbitSubcommand::Clean(Clean::from_cli(cli)?)),
+            "lock" => Ok(OrbitSubcommand::Lock(Lock::from_cli(cli)?)),
+            "doctor" => Ok(OrbitSubcommand::Doctor(Doctor::from_cli(cli)?)),
+            "report" => Ok(OrbitSubcommand::Report(Report::from_cli(cli)?)),
+            "bench" => Ok(OrbitSubcommand::Bench(Bench::from_cli(cli)?)),
+            "grep-sym" => Ok(OrbitSubcommand::GrepSym(GrepSym::from_cli(cli)?)),
+            "highlight" => Ok(OrbitSubcommand::Highlight(Highlight::from_cli(cli)?)),
+            "upgrade" => Ok(OrbitSubcommand::Upgrade(Upgrade::from_cli(cli)?)),
+            "plugin" => Ok(OrbitSubcommand::Plugin(Plugin::from_cli(cli)?)),
             _ => panic!("an unimplemented command was passed through!")
         }
     }
@@ -164,6 +285,7 @@ impl OrbitSubcommand {
     fn bypass_check(&self) -> bool {
         match self {
             Self::Config(_) => true,
+            Self::Upgrade(_) => true,
             _ => false,
         }
     }
@@ -184,11 +306,30 @@ impl Command for OrbitSubcommand {
             OrbitSubcommand::Launch(c) => c.exec(context),
             OrbitSubcommand::Tree(c) => c.exec(context),
             OrbitSubcommand::Init(c) => c.exec(context),
+            OrbitSubcommand::Import(c) => c.exec(context),
             OrbitSubcommand::Probe(c) => c.exec(context),
             OrbitSubcommand::Env(c) => c.exec(context),
             OrbitSubcommand::Config(c) => c.exec(context),
             OrbitSubcommand::Uninstall(c) => c.exec(context),
             OrbitSubcommand::Read(c) => c.exec(context),
+            OrbitSubcommand::Run(c) => c.exec(context),
+            OrbitSubcommand::Cache(c) => c.exec(context),
+            OrbitSubcommand::Vendor(c) => c.exec(context),
+            OrbitSubcommand::Test(c) => c.exec(context),
+            OrbitSubcommand::Export(c) => c.exec(context),
+            OrbitSubcommand::Lsp(c) => c.exec(context),
+            OrbitSubcommand::Fmt(c) => c.exec(context),
+            OrbitSubcommand::Refactor(c) => c.exec(context),
+            OrbitSubcommand::Doc(c) => c.exec(context),
+            OrbitSubcommand::Clean(c) => c.exec(context),
+            OrbitSubcommand::Lock(c) => c.exec(context),
+            OrbitSubcommand::Doctor(c) => c.exec(context),
+            OrbitSubcommand::Report(c) => c.exec(context),
+            OrbitSubcommand::Bench(c) => c.exec(context),
+            OrbitSubcommand::GrepSym(c) => c.exec(context),
+            OrbitSubcommand::Highlight(c) => c.exec(context),
+            OrbitSubcommand::Upgrade(c) => c.exec(context),
+            OrbitSubcommand::Plugin(c) => c.exec(context),
         }
     }
 }
@@ -205,198 +346,80 @@ Usage:
 Commands:
     new             create a new ip
     init            initialize an ip from an existing project
+    import          create an ip from a tool project's file list
     edit            open an ip in a text editor
     probe           access information about an ip
     read            inspect hdl design unit source code
+    grep-sym        find declarations and references of an identifier
     get             fetch an entity
     tree            view the dependency graph
     plan            generate a blueprint file
     build, b        execute a plugin
+    run             generate a blueprint file and execute a plugin
+    test            simulate every testbench in the current ip
+    export          generate a project file for a backend tool
+    clean           remove files generated for the current ip
+    lock            write the lock file for the current ip
+    report          summarize the recent plans and builds
+    bench           compare the plans of two revisions
+    fmt             format the vhdl files in the current ip
+    refactor        rewrite references to units, ports, and libraries
+    doc             generate documentation for the current ip
+    lsp             run a language server for the current ip
+    highlight       classify the tokens of a vhdl file
     launch          release a new ip version
     search          browse the ip catalog 
     install         store an immutable reference to an ip
     env             print Orbit environment information
+    doctor          check the health of the orbit environment
     config          modify configuration values
+    plugin          create plugins for backend tools
     uninstall       remove an ip from the catalog
+    cache           manage the ip installed to the cache
+    vendor          copy locked dependencies into the current ip
+    upgrade         update orbit to the latest release
 
 Options:
-    --version       print version information and exit
-    --upgrade       check for the latest orbit binary
-    --force         bypass interactive prompts
-    --color <when>  coloring: auto, always, never
-    --help, -h      print help information
+    --version           print version information and exit
+    --upgrade           same as 'orbit upgrade'
+    --force             bypass interactive prompts
+    --verbose           display the commands orbit executes
+    --offline           never reach remote hosts
+    --no-mirror         reach git sources without the configured mirrors
+    --color <when>      coloring: auto, always, never (default: auto)
+    --quiet, -q         hide progress indicators
+    --profile <format>  time the phases of the command: table, json
+    --help, -h          print help information
 
 Use 'orbit help <command>' for more information about a command.
 ";
 
-use reqwest;
-use crate::core::version;
-use crate::util::sha256;
-use std::str::FromStr;
-use std::io::Write;
-use zip;
-use tempfile;
-use crate::util::filesystem::get_exe_path;
-
-impl Orbit {
-    /// Returns current machine's target as `<arch>-<os>`.
-    fn target_triple() -> String {
-        format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS)
-    }
-
-    /// Runs a process to check for an updated version of Orbit on GitHub to install.
-    /// 
-    /// Steps it follows:  
-    /// 1. Removes any old version existing in executables' current folder
-    /// 2. Gets website data from GitHub releases page to check for latest version
-    /// 3. If new version, download checksum file and search for a compatible platform
-    /// 4. Download compatible platform zip file and verify checksum matches
-    /// 5. Unzip the file and replace the Orbit executable in-place.
-    /// 6. Rename the old executable as `orbit-<version>`.
-    #[tokio::main]
-    async fn upgrade(&self) -> Result<String, Box<dyn std::error::Error>> {
-        // check for stale versions at the current executable's path
-        let exe_path = get_exe_path()?;
-        let mut current_exe_dir = exe_path.clone();
-        current_exe_dir.pop();
-        // find any old versions existing in executable's current folder
-        let paths = std::fs::read_dir(&current_exe_dir)?;
-        for path in paths {
-            if path.as_ref().unwrap().path().file_name().unwrap().to_str().unwrap().starts_with("orbit-") {
-                // remove stale binaries
-                std::fs::remove_file(path.as_ref().unwrap().path())?;
-            }
-        }
-
-        // check the connection to grab latest html data
-        let base_url: &str = "https://github.com/c-rus/orbit/releases";
-        let res = reqwest::get(base_url).await?;
-        if res.status() != 200 {
-            return Err(Box::new(UpgradeError::FailedConnection(base_url.to_string(), res.status())))?
-        }
-
-        // create body into string to find the latest version
-        let body = res.text().await?;
-        let key = "href=\"/c-rus/orbit/releases/tag/";
-
-        // if cannot find the key then the releases page failed to auto-detect in html data
-        let pos = match body.find(&key) {
-            Some(r) => r,
-            None => return Err(Box::new(UpgradeError::NoReleasesFound))?
-        };
-        // assumes tag is complete version i.e. 1.0.0
-        let (_, sub) = body.split_at(pos+key.len());
-        let (version, _) = sub.split_once('\"').unwrap();
-
-        // our current version is guaranteed to be valid
-        let current = version::Version::from_str(VERSION).unwrap();
-        // the latest version 
-        let latest = version::Version::from_str(version).expect("invalid version released");
-        if latest > current {
-            // await user input
-            if self.force == false {
-                if prompt::prompt(&format!("info: a new version is available ({}), would you like to upgrade", latest))? == false {
-                    return Ok(String::from("upgrade cancelled"))
-                }
-            }
-        } else {
-            return Ok(format!("the latest version is already installed ({})", &latest));
-        }
-
-        // download the list of checksums
-        println!("info: downloading update...");
-        let sum_url = format!("{0}/download/{1}/orbit-{1}-checksums.txt", &base_url, &latest);
-        let res = reqwest::get(&sum_url).await?;
-        if res.status() != 200 {
-            return Err(Box::new(UpgradeError::FailedDownload(sum_url.to_string(), res.status())))?
-        }
-
-        // store user's target
-        let target = Orbit::target_triple();
-        
-        let checksums = String::from_utf8(res.bytes().await?.to_vec())?;
-        let pkg = format!("orbit-{}-{}.zip", &latest, &target);
-        // search the checksums to check if the desired pkg is available for download
-        let cert = checksums.split_terminator('\n').find_map(|p| {
-            let (cert, key) = p.split_once(' ').expect("bad checksum file format");
-            if key == pkg  {
-                Some(sha256::Sha256Hash::from_str(cert).expect("bad checksum format"))
-            } else {
-                None
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::errors::CliError;
+
+    /// Each command's help text lists exactly the options the command accepts.
+    #[test]
+    fn help_matches_options() {
+        let commands = [
+            "", "new", "search", "plan", "build", "edit", "launch", "install", "tree", "get u", "init ip", "import p", 
+            "probe ip", "env", "config", "uninstall ip", "read u", "run", "vendor", "test", "export", "lsp", "fmt", 
+            "clean", "lock", "doctor", "report", "bench", "bench compare a", "grep-sym s", "highlight f", "doc", "cache", "cache list", "cache size", "cache prune", "cache gc", 
+            "cache stats", "refactor", "refactor rename-entity a b", "refactor rename-port e a b", 
+            "refactor change-library a b", "plugin", "plugin new p", "plugin list", "help", "upgrade",
+        ];
+        for command in commands {
+            let args = std::iter::once("orbit")
+                .chain(command.split_whitespace())
+                .chain(std::iter::once("--help"))
+                .map(String::from);
+            let mut cli = Cli::tokenize(args);
+            Orbit::from_cli(&mut cli).unwrap();
+            match cli.is_empty() {
+                Err(CliError::Help(text)) => assert_eq!(text, cli.get_help(), "help for `orbit {}` does not match its options", command),
+                _ => panic!("expecting help for `orbit {}`", command),
             }
-        });
-        // verify there was a certificate/checksum for the requested pkg
-        let cert = match cert {
-            Some(c) => c,
-            None => return Err(Box::new(UpgradeError::UnsupportedTarget(target)))?,
-        };
-
-        // download the zip pkg file
-        let pkg_url = format!("{}/download/{}/{}",&base_url, &latest, &pkg);
-        let res = reqwest::get(&pkg_url).await?;
-        if res.status() != 200 {
-            return Err(Box::new(UpgradeError::FailedDownload(pkg_url.to_string(), res.status())))?
-        }
-        let body_bytes = res.bytes().await?;
-
-        // compute the checksum on the downloaded zip file
-        let sum = sha256::compute_sha256(&body_bytes);
-        // verify the checksums match
-        match sum == cert {
-            true => println!("info: verified download"),
-            false =>  return Err(Box::new(UpgradeError::BadChecksum(sum, cert)))?,
-        };
-
-        // unzip the bytes and put file in temporary file
-        println!("info: installing update...");
-        let mut temp_file = tempfile::tempfile()?;
-        temp_file.write_all(&body_bytes)?;
-        let mut zip_archive = zip::ZipArchive::new(temp_file)?;
-
-        // decompress zip file to a temporary directory
-        let temp_dir = tempfile::tempdir()?;
-        zip_archive.extract(&temp_dir)?;
-
-        let exe_ext = if std::env::consts::EXE_EXTENSION.is_empty() == true { "" } else { ".exe" };
-
-        // verify the path to the new executable exists before renaming current binary
-        let temp_exe_path = temp_dir.path().join(&format!("orbit-{}-{}/bin/orbit{}", &latest, &target, &exe_ext));
-        if std::path::Path::exists(&temp_exe_path) == false {
-            return Err(Box::new(UpgradeError::MissingExe))?;
-        }
-
-        // rename the current binary with its version to become a 'stale binary'
-        let stale_exe_path = current_exe_dir.join(&format!("orbit-{}", VERSION));
-        std::fs::rename(&exe_path, &stale_exe_path)?;
-
-        // copy the executable from the temporary directory to the original location
-        std::fs::copy(&temp_exe_path, &exe_path)?;
-
-        Ok(String::from(format!("successfully upgraded orbit to version {}", &latest)))
-    }
-}
-
-#[derive(Debug, PartialEq)]
-enum UpgradeError {
-    UnsupportedTarget(String),
-    FailedConnection(String, reqwest::StatusCode),
-    FailedDownload(String, reqwest::StatusCode),
-    NoReleasesFound,
-    BadChecksum(Sha256Hash, Sha256Hash),
-    MissingExe,
-}
-
-impl std::error::Error for UpgradeError {}
-
-impl std::fmt::Display for UpgradeError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> { 
-        match self {
-            Self::MissingExe => write!(f, "failed to find the binary in the downloaded package"),
-            Self::BadChecksum(computed, ideal) => write!(f, "checksums did not match, please try again\n\ncomputed: {}\nexpected: {}", computed, ideal),
-            Self::FailedConnection(url, status) => write!(f, "connection failed\n\nurl: {}\nstatus: {}", url, status),
-            Self::FailedDownload(url, status) => write!(f, "download failed\n\nurl: {}\nstatus: {}", url, status),
-            Self::UnsupportedTarget(t) => write!(f, "no pre-compiled binaries exist for the current target {}", t),
-            Self::NoReleasesFound => write!(f, "no releases were found"),
         }
     }
 }
\ No newline at end of file
@@ -1,31 +1,52 @@
-use colored::Colorize;
+use std::str::FromStr;
 use tempfile::tempdir;
 
 use crate::Command;
 use crate::FromCli;
+use crate::core::alias::AliasMap;
+use crate::core::archive;
+use crate::core::signature;
 use crate::core::catalog::Catalog;
 use crate::core::extgit;
+use crate::core::feature::{self, FeatureMap, FeatureRequest};
+use crate::core::filelist::{self, FileList};
 use crate::core::ip::IpFileNode;
 use crate::core::ip::IpNode;
 use crate::core::ip::IpSpec;
-use crate::core::lockfile::LockFile;
-use crate::core::manifest::IpManifest;
+use crate::core::lockfile::{LockFile, IP_LOCK_FILE};
+use crate::core::manifest::{Generator, IpManifest, Manifest, ProviderTable, UnitRole, VENDOR_DIR};
 use crate::core::lockfile::LockEntry;
+use crate::core::pkgid::PkgId;
 use crate::core::plugin::PluginError;
+use crate::core::sbom::Sbom;
+use crate::core::report;
 use crate::core::template;
 use crate::core::variable::VariableTable;
-use crate::core::version::AnyVersion;
+use crate::core::version::{self, AnyVersion, Version};
 use crate::core::vhdl::subunit::SubUnit;
 use crate::core::vhdl::symbol::CompoundIdentifier;
+use crate::core::vhdl::doc::{self, Annotations, UnitDoc};
+use crate::core::vhdl::index::IdentifierIndex;
+use crate::core::vhdl::standard::VhdlStandard;
 use crate::interface::cli::Cli;
-use crate::util::anyerror::Fault;
+use crate::util::anyerror::{Category, CategoryError, Fault};
 use crate::util::environment::EnvVar;
 use crate::interface::arg::{Flag, Optional};
 use crate::interface::errors::CliError;
 use crate::core::context::Context;
 use crate::util::graphmap::GraphMap;
+use crate::util::checksum;
+use crate::util::profile;
+use crate::util::progress;
+use crate::util::prompt;
+use crate::util::theme;
+use crate::util::progress::ProgressBar;
+use crate::util::diff;
+use crate::util::writeguard::WriteGuard;
 use std::collections::HashMap;
-use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
 use crate::core::fileset::Fileset;
 use crate::core::vhdl::token::Identifier;
 use crate::core::plugin::Plugin;
@@ -36,31 +57,177 @@ pub struct Plan {
     plugin: Option<String>,
     bench: Option<Identifier>,
     top: Option<Identifier>,
-    clean: bool,
     list: bool,
     all: bool,
     build_dir: Option<String>,
     filesets: Option<Vec<Fileset>>,
+    generics: Option<Vec<Generic>>,
+    include_dirs: Option<Vec<PathBuf>>,
+    defines: Option<Vec<Define>>,
+    target: Option<String>,
+    features: Option<Vec<FeatureRequest>>,
     disable_ssh: bool,
     only_lock: bool,
+    sync_lock: bool,
+    jobs: usize,
+    vendored: bool,
+    sbom: Option<PathBuf>,
+    emit_f: Option<PathBuf>,
+    dry_run: bool,
+    no_input: bool,
+    save: bool,
+    touch: bool,
+    profile_json: bool,
 }
 
 impl FromCli for Plan {
     fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
         cli.set_help(HELP);
+        let only_lock = cli.check_flag(Flag::new("lock-only"))?;
+        let list = cli.check_flag(Flag::new("list"))?;
+        let sbom = cli.check_option(Optional::new("sbom").value("file"))?;
+        let emit_f = cli.check_option(Optional::new("emit-f").value("file"))?;
+        let dry_run = cli.check_flag(Flag::new("dry-run"))?;
+        let save = cli.check_flag(Flag::new("save"))?;
+        let touch = cli.check_flag(Flag::new("touch"))?;
+        let profile_json = cli.check_flag(Flag::new("profile-json"))?;
         let command = Ok(Plan {
-            only_lock: cli.check_flag(Flag::new("lock-only"))?,
+            only_lock,
+            list,
+            sbom,
+            emit_f,
+            dry_run,
+            save,
+            touch,
+            profile_json,
+            ..Plan::from_cli_planning(cli)?
+        });
+        command
+    }
+}
+
+impl Plan {
+    /// Collects the arguments that determine how a blueprint is generated.
+    ///
+    /// This subset is shared with other commands that perform planning as a
+    /// step (such as `orbit run`). It does not set any help text.
+    pub fn from_cli_planning<'c>(cli: &mut Cli<'c>) -> Result<Self, CliError<'c>> {
+        Ok(Plan {
+            only_lock: false,
+            sync_lock: false,
+            list: false,
             all : cli.check_flag(Flag::new("all"))?,
-            clean: cli.check_flag(Flag::new("clean"))?,
-            list: cli.check_flag(Flag::new("list"))?,
             top: cli.check_option(Optional::new("top").value("unit"))?,
             bench: cli.check_option(Optional::new("bench").value("tb"))?,
             plugin: cli.check_option(Optional::new("plugin"))?,
             build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
-            filesets: cli.check_option_all(Optional::new("fileset").value("key=glob"))?,
+            filesets: cli.check_option_all(Optional::new("fileset").value("key=glob").map())?,
+            generics: cli.check_option_all(Optional::new("generic").value("name=value").map())?,
+            include_dirs: cli.check_option_all(Optional::new("include-dir").value("dir"))?,
+            defines: cli.check_option_all(Optional::new("define").value("name[=value]"))?,
+            target: cli.check_option(Optional::new("target").value("name"))?,
+            features: cli.check_option_all(Optional::new("features").value("list").list())?,
             disable_ssh: cli.check_flag(Flag::new("disable-ssh"))?,
-        });
-        command
+            jobs: cli.check_option_default(Optional::new("jobs").value("n").default("1"))?,
+            vendored: cli.check_flag(Flag::new("vendored"))?,
+            sbom: None,
+            emit_f: None,
+            dry_run: false,
+            no_input: cli.check_flag(Flag::new("no-input"))?,
+            save: false,
+            touch: false,
+            profile_json: false,
+        })
+    }
+
+    /// Creates a `Plan` for the testbench `bench` that places its blueprint in `build_dir`.
+    pub fn for_testbench(bench: Identifier, build_dir: String, plugin: Option<String>, disable_ssh: bool, jobs: usize, vendored: bool) -> Self {
+        Plan {
+            plugin,
+            bench: Some(bench),
+            top: None,
+            list: false,
+            all: false,
+            build_dir: Some(build_dir),
+            filesets: None,
+            generics: None,
+            include_dirs: None,
+            defines: None,
+            target: None,
+            features: None,
+            disable_ssh,
+            only_lock: false,
+            sync_lock: false,
+            jobs,
+            vendored,
+            sbom: None,
+            emit_f: None,
+            dry_run: false,
+            no_input: true,
+            save: false,
+            touch: false,
+            profile_json: false,
+        }
+    }
+
+    /// Creates a `Plan` that includes every hdl file and places its blueprint in `build_dir`.
+    /// 
+    /// Used to hand the entire design to a test framework that discovers its own testbenches.
+    pub fn for_regression(build_dir: String, plugin: Option<String>, disable_ssh: bool, jobs: usize, vendored: bool) -> Self {
+        Plan {
+            bench: None,
+            all: true,
+            ..Plan::for_testbench(Identifier::new_working(), build_dir, plugin, disable_ssh, jobs, vendored)
+        }
+    }
+
+    /// Creates a `Plan` that only writes the lock file.
+    pub fn for_lock(disable_ssh: bool) -> Self {
+        Plan {
+            plugin: None,
+            bench: None,
+            top: None,
+            list: false,
+            all: false,
+            build_dir: None,
+            filesets: None,
+            generics: None,
+            include_dirs: None,
+            defines: None,
+            target: None,
+            features: None,
+            disable_ssh,
+            only_lock: true,
+            sync_lock: false,
+            jobs: 1,
+            vendored: false,
+            sbom: None,
+            emit_f: None,
+            dry_run: false,
+            no_input: true,
+            save: false,
+            touch: false,
+            profile_json: false,
+        }
+    }
+
+    /// Creates a `Plan` that only writes the lock file, keeping the locked version of each
+    /// dependency that still satisfies its requirement.
+    pub fn for_lock_sync(disable_ssh: bool) -> Self {
+        Plan {
+            sync_lock: true,
+            ..Plan::for_lock(disable_ssh)
+        }
+    }
+
+    /// References the plugin alias requested for planning.
+    pub fn get_plugin(&self) -> Option<&String> {
+        self.plugin.as_ref()
+    }
+
+    /// References the build directory requested from the command-line.
+    pub fn get_build_dir(&self) -> Option<&String> {
+        self.build_dir.as_ref()
     }
 }
 
@@ -68,6 +235,12 @@ impl Command for Plan {
     type Err = Fault;
 
     fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // record the phases of planning to write alongside the blueprint
+        if self.profile_json == true {
+            profile::enable();
+        }
+        let clock = std::time::Instant::now();
+
         // locate the plugin
         let plugin = match &self.plugin {
             // verify the plugin alias matches
@@ -89,28 +262,64 @@ impl Command for Plan {
             return Ok(())
         }
         
+        if self.save && self.only_lock {
+            return Err(AnyError(format!("'{}' cannot be combined with '{}'", theme::argument("--save"), theme::argument("--lock-only"))))?
+        }
+
+        let run = report::Run::start(report::Step::Plan);
+        let guard = WriteGuard::new(self.dry_run);
         // check that user is in an IP directory
         c.goto_ip_path()?;
 
         // create the ip manifest
         let target_ip = IpManifest::from_path(c.get_ip_path().unwrap())?;
 
+        let vendor_dir = target_ip.get_root().join(VENDOR_DIR);
+        if self.vendored && !vendor_dir.is_dir() {
+            return Err(AnyError(format!("no vendored dependencies found for ip '{}'\n\nTry `orbit vendor` to copy the lock file's dependencies into the ip", target_ip.get_pkgid())))?
+        }
+
         // gather the catalog
-        let mut catalog = Catalog::new()
-            .store(c.get_store_path())
-            .development(c.get_development_path().unwrap())?
-            .installations(c.get_cache_path())?
-            .available(c.get_vendors())?;
+        let mut catalog = match self.vendored {
+            // only see the ip copied into the vendor directory
+            true => Catalog::new()
+                .store(c.get_store_path())
+                .aliases(c.get_aliases())
+                .trust(c.get_trust())
+                .installations(&vendor_dir)?,
+            false => Catalog::new()
+                .store(c.get_store_path())
+                .aliases(c.get_aliases())
+                .trust(c.get_trust())
+                .ignore_locks(c.force)
+                .prefer_locks(self.sync_lock)
+                .development(c.get_development_paths())?
+                .installations(c.get_cache_path())?
+                .available(c.get_vendors())?,
+        };
 
         // @todo: recreate the ip graph from the lockfile, then read each installation
         // see Install::install_from_lock_file
 
-        // this code is only ran if the lock file matches the manifest and we aren't force to recompute
-        if target_ip.can_use_lock() == true && c.force == false {
+        if self.vendored {
+            // verify every dependency in the lock file was vendored
+            if target_ip.can_use_lock() {
+                Plan::verify_vendored(&target_ip, &target_ip.into_lockfile()?, &catalog)?;
+            }
+        // this code is only ran if the lock file matches the manifest (or is being synced) and we aren't force to recompute
+        } else if (target_ip.can_use_lock() || self.sync_lock) && !c.force {
+            let lock = target_ip.into_lockfile()?;
+            // entries for dependencies removed from the manifest are dropped when syncing
+            let kept = match self.sync_lock {
+                true => Some(Plan::kept_lock_entries(target_ip.get_pkgid(), target_ip.get_dependencies().inner(), &lock)),
+                false => None,
+            };
             // fill in the catalog with missing modules according the lock file if available
-            for entry in target_ip.into_lockfile()?.inner() {
+            let mut missing = Vec::new();
+            for entry in lock.inner() {
                 // skip the current project's ip entry
                 if entry.get_name() == target_ip.get_pkgid() { continue }
+                if kept.as_ref().map(|k| !k.contains(&entry)).unwrap_or(false) { continue }
                 let ver = AnyVersion::Specific(entry.get_version().to_partial_version());
                 // try to use the lock file to fill in missing pieces
                 match catalog.inner().get(entry.get_name()) {
@@ -120,13 +329,21 @@ impl Command for Plan {
                             // no action required
                             Some(_) => (),
                             // install
-                            None => Plan::install_from_lock_entry(&entry, &ver, &catalog, self.disable_ssh)?,
+                            None => missing.push((entry, ver)),
                         }
                     }
                     // install
-                    None => Plan::install_from_lock_entry(&entry, &ver, &catalog, self.disable_ssh)?,
+                    None => missing.push((entry, ver)),
                 }
             }
+            if guard.is_dry_run() && !missing.is_empty() {
+                for (entry, _) in &missing {
+                    println!("{}", theme::info(format!("would install {} v{} from the lock file", theme::name(entry.get_name()), entry.get_version())));
+                }
+                println!("{}", theme::info("the blueprint cannot be previewed until the missing dependencies are installed"));
+                return Ok(())
+            }
+            Plan::install_from_lock_entries(&missing, &catalog, self.disable_ssh, self.jobs)?;
             // recollect the installations to update the catalog
             catalog = catalog.installations(c.get_cache_path())?;
         }
@@ -137,7 +354,19 @@ impl Command for Plan {
             None => c.get_build_dir(),
         };
 
-        self.run(target_ip, b_dir, plugin, catalog, c.force)
+        self.run(target_ip, b_dir, plugin, catalog, c.force, &guard)?;
+        if self.profile_json && !self.only_lock && !guard.is_dry_run() {
+            let path = c.get_ip_path().unwrap().join(b_dir).join(PROFILE_FILE);
+            std::fs::write(&path, profile::fmt(&profile::snapshot(), clock.elapsed(), profile::Format::Json))?;
+            println!("{}", theme::info(format!("Profile written to: {}", path.display())));
+        }
+        if !self.only_lock && !guard.is_dry_run() {
+            // the plan already succeeded even if it cannot be recorded
+            if let Err(e) = run.finish(c.get_config(), &c.get_ip_path().unwrap().join(b_dir).join(BLUEPRINT_FILE), self.plugin.as_deref(), true) {
+                println!("{}", theme::warning(format!("failed to record the plan in the report: {}", e)));
+            }
+        }
+        Ok(())
     }
 }
 
@@ -172,18 +401,89 @@ impl<'a> SubUnitNode<'a> {
 pub struct HdlNode<'a> {
     sym: symbol::VHDLSymbol,
     files: Vec<&'a IpFileNode<'a>>, // must use a vector to retain file order in blueprint
+    annotations: Annotations,
+    role: Option<UnitRole>,
+    external: Option<String>, // fileset of a unit declared in the manifest's externals
+    generated: bool,
 }
 
 impl<'a> HdlNode<'a> {
-    fn new(sym: symbol::VHDLSymbol, file: &'a IpFileNode) -> Self {
+    fn new(sym: symbol::VHDLSymbol, file: &'a IpFileNode, annotations: Annotations) -> Self {
         let mut set = Vec::with_capacity(1);
         set.push(file);
+        // the unit's ip may assign it a role in its manifest
+        let role = sym.as_iden().and_then(|iden| file.get_ip_manifest().get_unit_role(iden)).cloned();
         Self {
             sym: sym,
             files: set,
+            annotations,
+            role,
+            external: None,
+            generated: false,
+        }
+    }
+
+    /// Creates a leaf node for a unit declared in an ip's `[externals]` table.
+    fn external(name: Identifier, files: Vec<&'a IpFileNode<'a>>, fileset: &str) -> Self {
+        Self {
+            sym: symbol::VHDLSymbol::Entity(symbol::Entity::black_box(name)),
+            files,
+            annotations: Annotations::default(),
+            role: None,
+            external: Some(Fileset::new().name(fileset).get_name().to_string()),
+            generated: false,
+        }
+    }
+
+    /// Creates a leaf node for a unit declared by a generator whose outputs do not exist yet.
+    fn generated(name: Identifier, files: Vec<&'a IpFileNode<'a>>) -> Self {
+        Self {
+            sym: symbol::VHDLSymbol::Entity(symbol::Entity::black_box(name)),
+            files,
+            annotations: Annotations::default(),
+            role: None,
+            external: None,
+            generated: true,
         }
     }
 
+    /// Checks if the node stands in for a unit without source code to read.
+    pub fn is_placeholder(&self) -> bool {
+        self.external.is_some() || self.generated
+    }
+
+    /// Checks if the node is explicitly marked as a testbench or not.
+    /// 
+    /// A role in the manifest takes precedence over a `testbench` annotation.
+    fn marked_testbench(&self) -> Option<bool> {
+        match &self.role {
+            Some(role) => Some(role.is_testbench()),
+            None => self.annotations.is_testbench(),
+        }
+    }
+
+    /// Checks if the node is a testbench entity.
+    /// 
+    /// Entities without ports are treated as testbenches unless marked otherwise.
+    pub fn is_testbench(&self) -> bool {
+        match self.sym.as_entity() {
+            Some(e) if !self.is_placeholder() => self.marked_testbench().unwrap_or(e.is_testbench()),
+            _ => false,
+        }
+    }
+
+    /// Checks if the node's files belong in the rtl fileset.
+    /// 
+    /// Units marked as testbenches are not synthesizable unless annotated otherwise.
+    pub fn is_synthesizable(&self) -> bool {
+        self.annotations.is_synthesizable().unwrap_or(self.marked_testbench() != Some(true))
+    }
+
+    /// Checks if the node is marked as the top-level in its ip's manifest.
+    pub fn is_marked_top(&self) -> bool {
+        self.role == Some(UnitRole::Top)
+    }
+
     fn add_file(&mut self, ipf: &'a IpFileNode) {
         if self.files.contains(&ipf) == false {
             self.files.push(ipf);
@@ -191,7 +491,7 @@ impl<'a> HdlNode<'a> {
     }
 
     /// References the VHDL symbol
-    fn get_symbol(&self) -> &symbol::VHDLSymbol {
+    pub fn get_symbol(&self) -> &symbol::VHDLSymbol {
         &self.sym
     }
 
@@ -199,61 +499,255 @@ impl<'a> HdlNode<'a> {
         &mut self.sym
     }
 
-    fn get_associated_files(&self) -> &Vec<&'a IpFileNode<'a>> {
+    pub fn get_associated_files(&self) -> &Vec<&'a IpFileNode<'a>> {
         &self.files
     }
 }
 
 impl Plan {
-    /// Clones the ip entry's repository to a temporary directory and then installs the appropriate version `ver`.
+    /// Performs variable substitution and expands environment variables in each
+    /// of the patterns of `fset`.
+    fn expand_fileset(fset: &Fileset, vtable: &VariableTable) -> Result<Fileset, Fault> {
+        let mut expanded = Fileset::new().name(fset.get_name());
+        for pattern in fset.get_patterns() {
+            let pattern = template::expand(&template::substitute(pattern, vtable), vtable)
+                .map_err(|e| AnyError(format!("fileset '{}': {}", fset.get_name(), e)))?;
+            expanded = expanded.pattern(&pattern)?;
+        }
+        Ok(expanded)
+    }
+
+    /// Finds the VHDL standard annotated on the units documented in `docs`.
+    /// 
+    /// Errors if the units of the same file annotate different standards.
+    fn annotated_standard(docs: &Vec<UnitDoc>) -> Result<Option<VhdlStandard>, AnyError> {
+        let mut result: Option<VhdlStandard> = None;
+        for std in docs.iter().filter_map(|d| d.get_annotations().ok().and_then(|a| a.get_standard().copied())) {
+            match result {
+                Some(prev) if prev != std => return Err(AnyError(format!("units annotate different standards VHDL-{} and VHDL-{}", prev, std))),
+                _ => result = Some(std),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Installs the lock entry as the appropriate version `ver` and verifies its checksum.
+    /// 
+    /// An entry whose commit is kept in the store is installed from the store without fetching its source.
     pub fn install_from_lock_entry(entry: &LockEntry, ver: &AnyVersion, catalog: &Catalog, disable_ssh: bool) -> Result<(), Fault> {
+        let snapshot = entry.get_commit()
+            .and_then(|commit| catalog.get_store().find_snapshot(entry.get_name(), commit).map(|root| (root, commit)));
+        let ip = match snapshot {
+            Some((root, commit)) => install::Install::install_from_snapshot(&root, commit, catalog.get_cache_path(), true, catalog.get_store())?,
+            None => Self::fetch_lock_entry(entry, ver, catalog, disable_ssh)?,
+        };
+        // verify the checksums align
+        let computed = ip.read_checksum_proof().unwrap();
+        match &computed == entry.get_sum().unwrap() {
+            true => {
+                // verify the release is signed as the vendor's trust policy expects
+                if let Err(e) = signature::verify(&ip, catalog.get_trust(), catalog.get_vendor(ip.get_pkgid())) {
+                    ip.remove()?;
+                    return Err(e)
+                }
+                Ok(())
+            },
+            false => {
+                // delete the entry from the cache slot
+                ip.remove()?;
+                Err(CategoryError(Category::Checksum, format!("failed to install ip '{}' from lockfile due to differing checksums\n\ncomputed: {}\nexpected: {}", entry.get_name(), computed, entry.get_sum().unwrap())))?
+            }
+        } 
+    }
+
+    /// Fetches the lock entry's source to a temporary directory and then installs the appropriate version `ver`.
+    fn fetch_lock_entry(entry: &LockEntry, ver: &AnyVersion, catalog: &Catalog, disable_ssh: bool) -> Result<IpManifest, Fault> {
         let temp = tempdir()?;
         // try to use the source
-        let from = if let Some(source) = entry.get_source() {
+        // the vendor's fetch hook takes precedence over the recorded source
+        let from = if let Some(path) = install::fetch_from_vendor(entry.get_name(), ver, catalog, temp.as_ref())? {
+            path
+        } else if let Some(source) = entry.get_source().filter(|src| archive::is_archive(src)) {
+            archive::fetch(source, temp.as_ref())?
+        } else if let Some(source) = entry.get_source() {
             let temp = temp.as_ref().to_path_buf();
-            println!("info: fetching {} repository ...", entry.get_name());
             extgit::ExtGit::new(None)
+                .transport(catalog.get_transport(entry.get_name()))
                 .clone(source, &temp, disable_ssh)?;
             temp
         // try to find an install path
         } else {
-            install::fetch_install_path(entry.get_name(), &catalog, disable_ssh, &temp)?
+            install::fetch_install_path(entry.get_name(), ver, catalog, disable_ssh, &temp)?
+        };
+        install::Install::install(&from, ver, catalog.get_cache_path(), true, catalog.get_store())
+    }
+
+    /// Collects the entries of the `lock` that the `target`'s `requirements` still reach.
+    /// 
+    /// A dependency of the `target` is kept while the manifest still requires it and its
+    /// locked version satisfies the requirement, along with every entry it depends on.
+    fn kept_lock_entries<'a>(target: &PkgId, requirements: &HashMap<PkgId, AnyVersion>, lock: &'a LockFile) -> Vec<&'a LockEntry> {
+        let resolve = |name: &PkgId, dep_version: &AnyVersion, resolved: Option<&Version>| match resolved {
+            Some(v) => lock.get(name, v),
+            None => lock.get_highest(name, dep_version),
         };
-        let ip = install::Install::install(&from, &ver, catalog.get_cache_path(), true, catalog.get_store())?;
+        let mut processing: Vec<&LockEntry> = lock.inner()
+            .iter()
+            .filter(|e| e.get_name() == target)
+            .flat_map(|e| e.get_deps().into_iter().flatten())
+            .filter_map(|d| {
+                let required = requirements.get(d.get_requested_name())?;
+                let entry = resolve(d.get_name(), d.get_version(), d.get_resolved())?;
+                version::get_target_version(required, &vec![entry.get_version()]).ok().map(|_| entry)
+            })
+            .collect();
+        let mut kept: Vec<&LockEntry> = Vec::new();
+        while let Some(entry) = processing.pop() {
+            if kept.contains(&entry) { continue }
+            for d in entry.get_deps().into_iter().flatten() {
+                if let Some(dep) = resolve(d.get_name(), d.get_version(), d.get_resolved()) {
+                    processing.push(dep);
+                }
+            }
+            kept.push(entry);
+        }
+        kept
+    }
 
-        // verify the checksums align
-        match &ip.read_checksum_proof().unwrap() == entry.get_sum().unwrap() {
+    /// Checks that every entry in the `lock` besides the `target` has its exact version in the `catalog`.
+    fn verify_vendored(target: &IpManifest, lock: &LockFile, catalog: &Catalog) -> Result<(), Fault> {
+        for entry in lock.inner() {
+            if entry.get_name() == target.get_pkgid() { continue }
+            let ver = AnyVersion::Specific(entry.get_version().to_partial_version());
+            let found = catalog.inner()
+                .get(entry.get_name())
+                .and_then(|status| status.get(&ver, true))
+                .is_some();
+            if !found {
+                return Err(CategoryError(Category::Resolution, format!("ip '{}' v{} from the lock file is not vendored\n\nTry `orbit vendor` to update the vendored dependencies", entry.get_name(), entry.get_version())))?
+            }
+        }
+        Ok(())
+    }
+
+    /// Installs the lock entries `entries` that are missing from the catalog.
+    /// 
+    /// Entries are fetched and installed by up to `jobs` worker threads at once. Every
+    /// entry is attempted and all failures are reported together.
+    pub fn install_from_lock_entries(entries: &[(&LockEntry, AnyVersion)], catalog: &Catalog, disable_ssh: bool, jobs: usize) -> Result<(), Fault> {
+        if jobs <= 1 || entries.len() <= 1 {
+            for (entry, ver) in entries {
+                Plan::install_from_lock_entry(entry, ver, catalog, disable_ssh)?;
+            }
+            return Ok(())
+        }
+        // keep versions of the same ip on one worker because they share a repository in the store
+        let mut groups: Vec<Vec<&(&LockEntry, AnyVersion)>> = Vec::new();
+        for item in entries {
+            match groups.iter_mut().find(|g| g[0].0.get_name() == item.0.get_name()) {
+                Some(g) => g.push(item),
+                None => groups.push(vec![item]),
+            }
+        }
+        let next = AtomicUsize::new(0);
+        let (tx, rx) = mpsc::channel();
+        let mut bar = ProgressBar::new("installing", entries.len());
+        let mut errors: Vec<(&LockEntry, String)> = Vec::new();
+        std::thread::scope(|s| {
+            for _ in 0..jobs.min(groups.len()) {
+                let tx = tx.clone();
+                let (next, groups) = (&next, &groups);
+                s.spawn(move || {
+                    // the main thread's progress bar owns the terminal line
+                    progress::set_thread_quiet(true);
+                    while let Some(group) = groups.get(next.fetch_add(1, Ordering::SeqCst)) {
+                        for (entry, ver) in group {
+                            let result = Plan::install_from_lock_entry(entry, ver, catalog, disable_ssh).map_err(|e| e.to_string());
+                            if tx.send((*entry, result)).is_err() { return }
+                        }
+                    }
+                });
+            }
+            drop(tx);
+            for (entry, result) in rx {
+                bar.inc(&format!("{} v{}", entry.get_name(), entry.get_version()));
+                if let Err(e) = result {
+                    errors.push((entry, e));
+                }
+            }
+        });
+        bar.finish();
+
+        match errors.is_empty() {
             true => Ok(()),
             false => {
-                // delete the entry from the cache slot
-                ip.remove()?;
-                Err(AnyError(format!("failed to install ip '{}' from lockfile due to differing checksums\n\ncomputed: {}\nexpected: {}", entry.get_name(), ip.read_checksum_proof().unwrap(), entry.get_sum().unwrap())))?
+                let reasons = errors.iter().fold(String::new(), |acc, (entry, e)| {
+                    acc + &format!("\n  {} v{}: {}", entry.get_name(), entry.get_version(), e)
+                });
+                Err(AnyError(format!("failed to install {} of {} ip from lockfile:{}", errors.len(), entries.len(), reasons)))?
             }
-        } 
+        }
     }
 
     /// Builds a graph of design units. Used for planning.
-    fn build_full_graph<'a>(files: &'a Vec<IpFileNode>) -> GraphMap<CompoundIdentifier, HdlNode<'a>, ()> {
+    /// 
+    /// Files are read according to their declared VHDL standard. Errors if any file
+    /// uses features from a later standard. A unit declared by more than one ip is
+    /// taken from the ip chosen in `providers`, and errors if there is no choice.
+    pub fn build_full_graph<'a>(files: &'a Vec<IpFileNode>, providers: &ProviderTable) -> Result<GraphMap<CompoundIdentifier, HdlNode<'a>, ()>, Fault> {
             let mut graph_map: GraphMap<CompoundIdentifier, HdlNode, ()> = GraphMap::new();
     
+            // store the primary units with the ip and location declaring them
+            let mut primaries: Vec<(CompoundIdentifier, &IpManifest, String, HdlNode)> = Vec::new();
             let mut sub_nodes: Vec<(Identifier, SubUnitNode)> = Vec::new();
-            let mut bodies: Vec<(Identifier, symbol::PackageBody)> = Vec::new();
+            let mut bodies: Vec<(Identifier, symbol::PackageBody, &IpFileNode)> = Vec::new();
             // store the (suffix, prefix) for all entities
             let mut component_pairs: HashMap<Identifier, Identifier> = HashMap::new();
+            // collect uses of features beyond an ip's declared standard
+            let mut violations = String::new();
+            // collect annotations that cannot be understood
+            let mut invalid = String::new();
             // read all files
             for source_file in files {
-                if crate::core::fileset::is_vhdl(&source_file.get_file()) == true {
+                // generated files that do not exist yet are represented by their declared units
+                if crate::core::fileset::is_vhdl(source_file.get_file()) && std::path::Path::new(source_file.get_file()).exists() {
+                    let phase = profile::Phase::start("parse");
                     let contents = std::fs::read_to_string(&source_file.get_file()).unwrap();
-                    let symbols = symbol::VHDLParser::read(&contents).into_symbols();
+                    // read the annotations written above each primary design unit
+                    let docs = doc::read(&contents).unwrap_or_default();
+                    let documented: IdentifierIndex<&UnitDoc> = docs.iter().map(|d| (d.get_name().clone(), d)).collect();
+                    // an annotated standard applies to the unit's entire file
+                    let annotated = match Self::annotated_standard(&docs) {
+                        Ok(std) => std,
+                        Err(e) => {
+                            invalid.push_str(&format!("\n  {}: {}", source_file.get_file(), e));
+                            None
+                        }
+                    };
+                    let symbols = match annotated.as_ref().or(source_file.get_ip_manifest().get_file_standard(source_file.get_file())) {
+                        Some(std) => {
+                            let (parser, found) = symbol::VHDLParser::read_as(&contents, std);
+                            found.iter().for_each(|v| {
+                                violations.push_str(&format!("\n  {}{}: {} but the file is declared as VHDL-{}", source_file.get_file(), v.get_position(), v, std));
+                            });
+                            parser.into_symbols()
+                        },
+                        None => symbol::VHDLParser::read(&contents).into_symbols(),
+                    };
 
                     let lib = source_file.get_library();
+                    drop(phase);
 
                     // add all entities to a graph and store architectures for later analysis
                     let mut iter = symbols.into_iter()
                         .filter_map(|f| {
                             match f {
                                 symbol::VHDLSymbol::Entity(_) => {
-                                    component_pairs.insert(f.as_entity().unwrap().get_name().clone(), lib.clone());
+                                    let name = f.as_entity().unwrap().get_name();
+                                    // components resolve to the ip chosen to provide the entity
+                                    if providers.get(name).map_or(true, |p| p == source_file.get_ip_manifest().get_pkgid()) {
+                                        component_pairs.insert(name.clone(), lib.clone());
+                                    }
                                     Some(f)
                                 },
                                 symbol::VHDLSymbol::Package(_) => Some(f),
@@ -268,28 +762,106 @@ impl Plan {
                                 }
                                 // package bodies are usually in same design file as package
                                 symbol::VHDLSymbol::PackageBody(pb) => {
-                                    bodies.push((lib.clone(), pb));
+                                    bodies.push((lib.clone(), pb, source_file));
                                     None
                                 }
                             }
                         });
                     while let Some(e) = iter.next() {
-                        // add primary design units into the graph
-                        graph_map.add_node(
+                        let annotations = match e.as_iden().and_then(|n| documented.get(n).first()).map(|d| d.get_annotations()) {
+                            Some(Ok(a)) => a,
+                            Some(Err(err)) => {
+                                invalid.push_str(&format!("\n  {}: {}", source_file.get_file(), err));
+                                Annotations::default()
+                            },
+                            None => Annotations::default(),
+                        };
+                        // store primary design units to be added into the graph
+                        primaries.push((
                             CompoundIdentifier::new(
                                 Identifier::from(lib.clone()), 
                                 e.as_iden().unwrap().clone()), 
-                            HdlNode::new(e, source_file)
-                            );
+                            source_file.get_ip_manifest(),
+                            source_file.get_file().clone(),
+                            HdlNode::new(e, source_file, annotations)
+                            ));
+                    }
+                }
+            }
+
+            if !violations.is_empty() {
+                return Err(AnyError(format!("files use features beyond their declared vhdl standard:{}", violations)))?
+            }
+            if !invalid.is_empty() {
+                return Err(AnyError(format!("files contain invalid annotations:{}", invalid)))?
+            }
+
+            // add the units declared in each ip's externals and generators as leaf nodes
+            let mut ips: Vec<&IpFileNode> = Vec::new();
+            for source_file in files {
+                if !ips.iter().any(|i| i.get_ip_manifest().get_pkgid() == source_file.get_ip_manifest().get_pkgid()) {
+                    ips.push(source_file);
+                }
+            }
+            let mut missing = String::new();
+            for node in ips {
+                let ip = node.get_ip_manifest();
+                let lib = node.get_library();
+                let manifest_path = ip.get_manifest().get_path().display().to_string();
+                for (name, external) in ip.get_externals().inner() {
+                    let key = CompoundIdentifier::new(lib.clone(), name.clone());
+                    if primaries.iter().any(|(k, i, _, _)| k == &key && i.get_pkgid() == ip.get_pkgid()) {
+                        return Err(AnyError(format!("external unit '{}' of ip '{}' is also declared in its source code", name, ip.get_pkgid())))?
                     }
+                    let mut ext_files = Vec::new();
+                    for path in external.get_files() {
+                        let path = ip.get_root().join(path);
+                        match files.iter().find(|f| f.get_ip_manifest().get_pkgid() == ip.get_pkgid() && std::path::Path::new(f.get_file()) == path) {
+                            Some(f) => ext_files.push(f),
+                            None => missing.push_str(&format!("\n  {}: {}", name, path.display())),
+                        }
+                    }
+                    component_pairs.insert(name.clone(), lib.clone());
+                    primaries.push((key, ip, manifest_path.clone(), HdlNode::external(name.clone(), ext_files, external.get_fileset())));
+                }
+                for generator in ip.get_generators().inner().values() {
+                    let gen_files: Vec<&IpFileNode> = files.iter()
+                        .filter(|f| f.get_ip_manifest().get_pkgid() == ip.get_pkgid())
+                        .filter(|f| generator.get_outputs().iter().any(|o| ip.get_root().join(o) == std::path::Path::new(f.get_file())))
+                        .collect();
+                    for name in generator.get_units() {
+                        let key = CompoundIdentifier::new(lib.clone(), name.clone());
+                        // units in outputs from a previous build are read from their source code
+                        if primaries.iter().any(|(k, i, _, _)| k == &key && i.get_pkgid() == ip.get_pkgid()) {
+                            continue
+                        }
+                        component_pairs.insert(name.clone(), lib.clone());
+                        primaries.push((key, ip, manifest_path.clone(), HdlNode::generated(name.clone(), gen_files.clone())));
+                    }
+                }
+            }
+            if !missing.is_empty() {
+                return Err(AnyError(format!("external units list files that do not exist:{}", missing)))?
+            }
+
+            // add primary design units into the graph from the ips providing them
+            let rejected = Self::resolve_providers(&primaries, providers)?;
+            let is_rejected = |key: &CompoundIdentifier, ip: &IpManifest| rejected.iter().any(|(k, p)| k == key && *p == ip.get_pkgid());
+            for (key, ip, _, node) in primaries {
+                if is_rejected(&key, ip) == false {
+                    graph_map.add_node(key, node);
                 }
             }
 
             // go through all package bodies and update package dependencies
             let mut bodies = bodies.into_iter();
-            while let Some((lib, pb)) = bodies.next() {
+            while let Some((lib, pb, file)) = bodies.next() {
+                let key = CompoundIdentifier::new(lib, pb.get_owner().clone());
+                if is_rejected(&key, file.get_ip_manifest()) {
+                    continue
+                }
                 // verify the package exists
-                if let Some(p_node) = graph_map.get_node_by_key_mut(&CompoundIdentifier::new(lib, pb.get_owner().clone())) {
+                if let Some(p_node) = graph_map.get_node_by_key_mut(&key) {
                     // link to package owner by adding refs
                     p_node.as_ref_mut().get_symbol_mut().add_refs(&mut pb.take_refs());
                 }
@@ -300,6 +872,10 @@ impl Plan {
             while let Some((lib, node)) = sub_nodes_iter.next() {
 
                 let node_name = CompoundIdentifier::new(lib, node.get_sub().get_entity().clone());
+                // skip the architectures of an entity taken from another ip
+                if is_rejected(&node_name, node.file.get_ip_manifest()) {
+                    continue
+                }
         
                 // link to the owner and add architecture's source file
                 let entity_node = match graph_map.get_node_by_key_mut(&node_name) {
@@ -336,32 +912,209 @@ impl Plan {
                     graph_map.add_edge_by_key(dep, &iden, ());
             }
         }
-        graph_map
+        // units in a cycle can never be ordered for the blueprint
+        if let Some(cycle) = graph_map.get_graph().find_cycle() {
+            return Err(PlanError::Cycle(Self::describe_cycle(&graph_map, &cycle)))?
+        }
+        Ok(graph_map)
+    }
+
+    /// Finds the primary units declared by more than one ip and chooses which ip provides
+    /// each one according to `providers`.
+    ///
+    /// Returns the (unit, ip) pairs that were turned down. Errors if an ip is not chosen for
+    /// every unit declared by more than one ip.
+    fn resolve_providers<'a>(primaries: &[(CompoundIdentifier, &'a IpManifest, String, HdlNode)], providers: &ProviderTable) -> Result<Vec<(CompoundIdentifier, &'a PkgId)>, Fault> {
+        // group the declarations of each unit while keeping the order they were found
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut index: HashMap<&CompoundIdentifier, usize> = HashMap::new();
+        for (i, (key, _, _, _)) in primaries.iter().enumerate() {
+            match index.get(key) {
+                Some(g) => groups[*g].push(i),
+                None => {
+                    index.insert(key, groups.len());
+                    groups.push(vec![i]);
+                }
+            }
+        }
+        let mut rejected = Vec::new();
+        let mut collisions: Vec<String> = Vec::new();
+        for group in groups {
+            // the first declaration from each ip
+            let mut declared: Vec<usize> = Vec::new();
+            for i in group {
+                if !declared.iter().any(|d| primaries[*d].1.get_pkgid() == primaries[i].1.get_pkgid()) {
+                    declared.push(i);
+                }
+            }
+            if declared.len() < 2 {
+                continue
+            }
+            let key = &primaries[declared[0]].0;
+            let chosen = providers.get(key.get_suffix())
+                .filter(|p| declared.iter().any(|d| primaries[*d].1.get_pkgid() == *p));
+            match chosen {
+                Some(p) => declared.iter()
+                    .map(|d| primaries[*d].1.get_pkgid())
+                    .filter(|i| i != &p)
+                    .for_each(|i| rejected.push((key.clone(), i))),
+                None => {
+                    let mut ips: Vec<String> = declared.iter()
+                        .map(|d| format!("{} ({})", primaries[*d].1.get_pkgid(), primaries[*d].2))
+                        .collect();
+                    ips.sort();
+                    collisions.push(format!("\n  {}: {}", key, ips.join(", ")));
+                }
+            }
+        }
+        if !collisions.is_empty() {
+            collisions.sort();
+            return Err(AnyError(format!("units are declared by more than one ip:{}\n\nChoose the ip to use for each unit in the manifest's [providers] table", collisions.concat())))?
+        }
+        Ok(rejected)
+    }
+
+    /// Lists the units along the `cycle` of node indices in the order they use one another,
+    /// each with the location of its declaration.
+    fn describe_cycle(graph: &GraphMap<CompoundIdentifier, HdlNode, ()>, cycle: &[usize]) -> Vec<(String, Option<String>)> {
+        // edges point from a dependency to the unit using it
+        cycle.iter().rev().map(|i| {
+            let key = graph.get_key_by_index(*i).unwrap();
+            let node = graph.get_node_by_index(*i).unwrap().as_ref();
+            let location = match node.get_associated_files().first() {
+                Some(file) if !node.is_placeholder() => Some(format!("{}{}", file.get_file(), node.get_symbol().get_position())),
+                Some(file) => Some(file.get_file().to_string()),
+                None => None,
+            };
+            (key.to_string(), location)
+        }).collect()
+    }
+
+    /// Creates the lockfile data for the constructed `ip_graph` with its enabled `features`.
+    fn build_lockfile(ip_graph: &GraphMap<IpSpec, IpNode, ()>, aliases: &AliasMap, features: &FeatureMap) -> LockFile {
+        // create build list
+        let mut build_list: Vec<&IpManifest> = ip_graph.get_map()
+            .iter()
+            .map(|p| { p.1.as_ref().as_original_ip() })
+            .collect();
+        let mut lock = LockFile::from_build_list(&mut build_list, aliases);
+        lock.set_features(features);
+        lock
+    }
+
+    /// Finds the units in `order` that use units from an ip that is neither a direct
+    /// dependency of their own ip nor re-exported by one.
+    /// 
+    /// Returns a message for each unit and the hidden unit it uses.
+    fn find_hidden_uses(graph: &GraphMap<CompoundIdentifier, HdlNode, ()>, order: &[usize], ip_graph: &GraphMap<IpSpec, IpNode, ()>, aliases: &AliasMap) -> Vec<String> {
+        let manifests: Vec<&IpManifest> = ip_graph.get_map().values().map(|n| n.as_ref().as_original_ip()).collect();
+        let canonical = |p: &PkgId| aliases.get(p).cloned().unwrap_or(p.clone());
+        // the ips whose units each ip can see
+        let mut visible: HashMap<PkgId, Vec<PkgId>> = HashMap::new();
+        let mut see = |ip: &IpManifest| -> Vec<PkgId> {
+            visible.entry(ip.get_pkgid().clone()).or_insert_with(|| {
+                let mut seen: Vec<PkgId> = ip.get_dependencies().inner().keys().map(|p| canonical(p)).collect();
+                let mut queue = seen.clone();
+                while let Some(pkgid) = queue.pop() {
+                    for m in manifests.iter().filter(|m| m.get_pkgid() == &pkgid) {
+                        for r in m.get_reexports().iter().map(|p| canonical(p)) {
+                            if seen.contains(&r) == false {
+                                seen.push(r.clone());
+                                queue.push(r);
+                            }
+                        }
+                    }
+                }
+                seen
+            }).clone()
+        };
+
+        let ip_of = |i: usize| graph.get_node_by_index(i)
+            .and_then(|n| n.as_ref().get_associated_files().first().map(|f| f.get_ip_manifest()));
+        let mut warnings = Vec::new();
+        for user in order {
+            let user_ip = match ip_of(*user) {
+                Some(ip) => ip,
+                None => continue,
+            };
+            let seen = see(user_ip);
+            for used in graph.get_graph().predecessors(*user) {
+                let used_ip = match ip_of(used) {
+                    Some(ip) => ip,
+                    None => continue,
+                };
+                if used_ip.get_pkgid() == user_ip.get_pkgid() || seen.contains(used_ip.get_pkgid()) {
+                    continue
+                }
+                let message = format!("unit '{}' in ip '{}' uses '{}' from ip '{}', which is not a direct dependency or re-exported by one",
+                    graph.get_key_by_index(*user).unwrap().get_suffix(),
+                    user_ip.get_pkgid(),
+                    graph.get_key_by_index(used).unwrap().get_suffix(),
+                    used_ip.get_pkgid());
+                if warnings.contains(&message) == false {
+                    warnings.push(message);
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Checks if the `file` of the `ip` is planned with the enabled `features`.
+    fn is_feature_enabled(features: &FeatureMap, ip: &IpManifest, file: &str) -> bool {
+        match features.get(&ip.into_ip_spec()) {
+            Some(enabled) => ip.get_features().is_file_enabled(&ip.get_root(), file, enabled),
+            None => true,
+        }
+    }
+
+    /// Determines the features to enable for every ip in the `ip_graph`.
+    fn resolve_features(&self, target: &IpManifest, ip_graph: &GraphMap<IpSpec, IpNode, ()>) -> Result<FeatureMap, Fault> {
+        let build_list: Vec<&IpManifest> = ip_graph.get_map()
+            .iter()
+            .map(|p| { p.1.as_ref().as_original_ip() })
+            .collect();
+        let requested = self.features.as_deref().unwrap_or(&[]);
+        feature::resolve(&build_list, target, requested)
     }
 
     /// Writes the lockfile according to the constructed `ip_graph`. Only writes if the lockfile is
     /// out of date or `force` is `true`.
-    fn write_lockfile(target: &IpManifest, ip_graph: &GraphMap<IpSpec, IpNode, ()>, force: bool) -> Result<(), Fault> {
+    fn write_lockfile(target: &IpManifest, ip_graph: &GraphMap<IpSpec, IpNode, ()>, aliases: &AliasMap, features: &FeatureMap, force: bool, guard: &WriteGuard) -> Result<(), Fault> {
+        let _phase = profile::Phase::start("lockfile write");
         // only modify the lockfile if it is out-of-date
-        if target.can_use_lock() == false || force == true { 
-            // create build list
-            let mut build_list: Vec<&IpManifest> = ip_graph.get_map()
-                .iter()
-                .map(|p| { p.1.as_ref().as_original_ip() })
-                .collect();
-            let lock = LockFile::from_build_list(&mut build_list);
-            target.write_lock(&lock, None)?;
+        let lock = if !target.can_use_lock() || force {
+            Self::build_lockfile(ip_graph, aliases, features)
+        } else {
+            // upgrade a lockfile from an older format or with other features without changing its selections
+            let mut lock = target.into_lockfile()?;
+            if !lock.is_outdated() && lock.has_features(features) {
+                return Ok(())
+            }
+            lock.set_features(features);
+            lock
+        };
+        match guard.is_dry_run() {
+            true => {
+                let path = target.get_root().join(IP_LOCK_FILE);
+                let old = std::fs::read_to_string(&path).unwrap_or_default();
+                let changes = diff::unified(&old, &target.render_lock(&lock, None), &format!("a/{}", IP_LOCK_FILE), &format!("b/{}", IP_LOCK_FILE));
+                match changes.is_empty() {
+                    true => println!("{}", theme::info(format!("{} would not change", IP_LOCK_FILE))),
+                    false => print!("{}\n{}", theme::info(format!("would update {}:", IP_LOCK_FILE)), changes),
+                }
+            }
+            false => target.write_lock(&lock, None)?,
         }
         Ok(())
     }
 
-    fn detect_bench(&self, graph: &GraphMap<CompoundIdentifier, HdlNode, ()>, working_lib: &Identifier) -> Result<(Option<usize>, Option<usize>), PlanError> {
-        Ok(if let Some(t) = &self.bench {
+    fn detect_bench(graph: &GraphMap<CompoundIdentifier, HdlNode, ()>, working_lib: &Identifier, top: Option<&Identifier>, bench: Option<&Identifier>) -> Result<(Option<usize>, Option<usize>), PlanError> {
+        Ok(if let Some(t) = bench {
             match graph.get_node_by_key(&CompoundIdentifier::new(working_lib.clone(), t.clone())) {
-                // verify the unit is an entity that is a testbench
+                // verify the unit is an entity that is not marked as something other than a testbench
                 Some(node) => {
-                    if let Some(e) = node.as_ref().get_symbol().as_entity() {
-                        if e.is_testbench() == false {
+                    if node.as_ref().get_symbol().as_entity().is_some() {
+                        if node.as_ref().marked_testbench() == Some(false) {
                             return Err(PlanError::BadTestbench(t.clone()))?
                         }
                         (None, Some(node.index()))
@@ -372,12 +1125,12 @@ impl Plan {
                 None => return Err(PlanError::UnknownEntity(t.clone()))?
             }
         // try to find the naturally occurring top-level if user did not provide --bench and did not provide --top
-        } else if self.top.is_none() {
+        } else if top.is_none() {
             // filter to display tops that have ports (not testbenches)
             // traverse subset of graph by filtering only for working library entities
             let shallow_graph: GraphMap<&CompoundIdentifier, &HdlNode, &()> = graph.iter()
                 .filter(|f| match f.0.get_prefix() { 
-                    Some(iden) => iden == working_lib, 
+                    Some(iden) => iden == working_lib && !f.1.is_placeholder(), 
                     None => false } )
                 .collect();
             match shallow_graph.find_root() {
@@ -385,8 +1138,8 @@ impl Plan {
                 Ok(n) => {
                     let n = graph.get_node_by_key(shallow_graph.get_key_by_index(n.index()).unwrap()).unwrap();
                     // verify the root is a testbench
-                    if let Some(ent) = n.as_ref().get_symbol().as_entity() {
-                        if ent.is_testbench() == true {
+                    if n.as_ref().get_symbol().as_entity().is_some() {
+                        if n.as_ref().is_testbench() {
                             (None, Some(n.index()))
                         // otherwise we found the toplevel node that is not a testbench "natural top"
                         } else {
@@ -409,20 +1162,136 @@ impl Plan {
         })
     }
 
+    /// Asks the user to choose one of the ambiguous `roots` as the top-level or testbench.
+    /// 
+    /// A chosen top-level can be saved to the manifest's `[ip.units]` table so later
+    /// plans do not ask again. Returns the index of the chosen entity, or `None` if
+    /// no entity was chosen.
+    fn select_root(target: &IpManifest, graph: &GraphMap<CompoundIdentifier, HdlNode, ()>, working_lib: &Identifier, roots: &[Identifier], guard: &WriteGuard) -> Result<Option<usize>, Fault> {
+        let root_dir = target.get_root();
+        // only entities can be planned as the top-level or testbench
+        let candidates: Vec<(&Identifier, usize, &HdlNode)> = roots.iter()
+            .filter_map(|r| graph.get_node_by_key(&CompoundIdentifier::new(working_lib.clone(), r.clone())).map(|n| (r, n)))
+            .filter(|(_, n)| n.as_ref().get_symbol().as_entity().is_some())
+            .map(|(r, n)| (r, n.index(), n.as_ref()))
+            .collect();
+        if candidates.is_empty() {
+            return Ok(None)
+        }
+        let options: Vec<String> = candidates.iter().map(|(name, _, node)| {
+            let ipfs = node.get_associated_files();
+            let files: Vec<String> = ipfs.iter()
+                .map(|f| PathBuf::from(f.get_file()).strip_prefix(&root_dir).map(|p| p.display().to_string()).unwrap_or_else(|_| f.get_file().clone()))
+                .collect();
+            format!("{:<24}{:<11}{:<12}{}", 
+                name.to_string(), 
+                match node.is_testbench() { true => "testbench", false => "entity" }, 
+                ipfs.first().map(|f| f.get_library().to_string()).unwrap_or_default(),
+                files.join(", "))
+        }).collect();
+        let choice = match prompt::select("multiple roots were found; choose the unit to plan", &options)? {
+            Some(i) => candidates[i],
+            None => return Ok(None),
+        };
+        let (name, index, node) = choice;
+        if node.is_testbench() {
+            println!("{}", theme::info(format!("use `{} {}` to plan this testbench without asking", theme::argument("--bench"), name)));
+            return Ok(Some(index))
+        }
+        if prompt::prompt(&format!("Save '{}' as the top in the manifest's [ip.units] table", name))? {
+            guard.perform(&format!("mark '{}' as \"top\" in {}", name, target.get_manifest().get_path().display()), || {
+                let mut manifest = Manifest::from_path(target.get_manifest().get_path().clone())?;
+                let ip = &mut manifest.get_mut_doc()["ip"];
+                if ip.get("units").is_none() {
+                    ip["units"] = toml_edit::table();
+                }
+                ip["units"][&name.to_string()] = toml_edit::value("top");
+                manifest.save()
+            })?;
+        }
+        Ok(Some(index))
+    }
+
+    /// Writes the `top` and `bench` names to the manifest's `[plan]` table.
+    /// 
+    /// An empty name removes its entry from the table.
+    fn save_defaults(target: &IpManifest, top: &str, bench: &str, guard: &WriteGuard) -> Result<(), Fault> {
+        let path = target.get_manifest().get_path();
+        let saved = guard.perform(&format!("save the top-level and testbench to the [plan] table in {}", path.display()), || {
+            let mut manifest = Manifest::from_path(path.clone())?;
+            let doc = manifest.get_mut_doc();
+            if doc.get("plan").is_none() {
+                doc["plan"] = toml_edit::table();
+            }
+            let table = match doc["plan"].as_table_mut() {
+                Some(t) => t,
+                None => return Err(AnyError(String::from("expects key 'plan' to be a toml table")))?,
+            };
+            for (key, name) in [("top", top), ("bench", bench)] {
+                match name.is_empty() {
+                    true => { table.remove(key); }
+                    false => table[key] = toml_edit::value(name),
+                }
+            }
+            manifest.save()
+        })?;
+        if saved.is_some() {
+            println!("{}", theme::info(format!("saved the top-level and testbench to the [plan] table in {}", path.display())));
+        }
+        Ok(())
+    }
+
+    /// Writes the rules of the user-defined `fset` for its collected `files`.
+    ///
+    /// A collected file list (ending with .f) is expanded into a rule for each file it names,
+    /// in list order, and its include directories and macros are gathered into `lists`.
+    fn write_fileset(fset: &Fileset, files: Vec<&String>, data: &mut String, written: &mut Vec<String>, lists: &mut FileList) -> Result<(), Fault> {
+        for f in files {
+            if filelist::is_filelist(f) == false {
+                *data += &fset.to_blueprint_string(f);
+                written.push(f.to_string());
+                continue;
+            }
+            let list = FileList::read(std::path::Path::new(f))?;
+            for entry in list.get_files() {
+                let path = crate::util::filesystem::to_standard(entry.get_path());
+                *data += &fset.to_blueprint_string(&path);
+                written.push(path);
+                lists.add_file(entry.get_path().clone(), None);
+            }
+            list.get_incdirs().iter().for_each(|d| lists.add_incdir(d.clone()));
+            list.get_defines().iter().for_each(|(n, v)| lists.add_define(n, v.as_deref()));
+        }
+        Ok(())
+    }
+
+    /// Finds the entity in the `working_lib` marked as the top-level by the manifest.
+    /// 
+    /// Errors if more than one entity is marked as the top-level.
+    fn detect_marked_top(graph: &GraphMap<CompoundIdentifier, HdlNode, ()>, working_lib: &Identifier) -> Result<Option<Identifier>, PlanError> {
+        let mut marked: Vec<Identifier> = graph.iter()
+            .filter(|(key, node, _)| key.get_prefix() == Some(working_lib) && node.is_marked_top())
+            .map(|(key, _, _)| key.get_suffix().clone())
+            .collect();
+        match marked.len() {
+            0 | 1 => Ok(marked.pop()),
+            _ => Err(PlanError::Ambiguous("units marked as top".to_string(), marked)),
+        }
+    }
     
     /// Given a `graph` and optionally a `bench`, detect the index corresponding
     /// to the top.
     /// 
     /// This function looks and checks if there is a single predecessor to the
     /// `bench` node.
-    fn detect_top(&self, graph: &GraphMap<CompoundIdentifier, HdlNode, ()>, working_lib: &Identifier, natural_top: Option<usize>, mut bench: Option<usize>) -> Result<(Option<usize>, Option<usize>), PlanError> {
+    fn detect_top(&self, graph: &GraphMap<CompoundIdentifier, HdlNode, ()>, working_lib: &Identifier, top: Option<&Identifier>, natural_top: Option<usize>, mut bench: Option<usize>) -> Result<(Option<usize>, Option<usize>), PlanError> {
         // determine the top-level node index
-        let top = if let Some(t) = &self.top {
+        let top = if let Some(t) = top {
             match graph.get_node_by_key(&CompoundIdentifier::new(working_lib.clone(), t.clone())) {
                 Some(node) => {
-                    // verify the unit is an entity that is not a testbench
-                    if let Some(e) = node.as_ref().get_symbol().as_entity() {
-                        if e.is_testbench() == true {
+                    // verify the unit is an entity that is not marked as a testbench
+                    if node.as_ref().get_symbol().as_entity().is_some() {
+                        if node.as_ref().marked_testbench() == Some(true) {
                             return Err(PlanError::BadTop(t.clone()))?
                         }
                     } else {
@@ -433,7 +1302,7 @@ impl Plan {
                     if bench.is_none() {
                         // check if only 1 is a testbench
                         let benches: Vec<usize> =  graph.get_graph().successors(n)
-                            .filter(|f| graph.get_node_by_index(*f).unwrap().as_ref().get_symbol().as_entity().unwrap().is_testbench() )
+                            .filter(|f| graph.get_node_by_index(*f).unwrap().as_ref().is_testbench() )
                             .collect();
                         // detect the testbench
                         bench = match benches.len() {
@@ -452,6 +1321,7 @@ impl Plan {
                 None => {
                     if let Some(b) = bench {
                         let entities: Vec<(usize, &symbol::Entity)> = graph.get_graph().predecessors(b)
+                            .filter(|f| !graph.get_node_by_index(*f).unwrap().as_ref().is_placeholder())
                             .filter_map(|f| {
                                 if let Some(e) = graph.get_node_by_index(f).unwrap().as_ref().get_symbol().as_entity() { 
                                     Some((f, e)) } else { None }
@@ -473,39 +1343,88 @@ impl Plan {
     }
 
     /// Performs the backend logic for creating a blueprint file (planning a design).
-    fn run(&self, target: IpManifest, build_dir: &str, plug: Option<&Plugin>, catalog: Catalog, force: bool) -> Result<(), Fault> {
+    fn run(&self, target: IpManifest, build_dir: &str, plug: Option<&Plugin>, catalog: Catalog, force: bool, guard: &WriteGuard) -> Result<(), Fault> {
         // create the build path to know where to begin storing files
         let mut build_path = std::env::current_dir().unwrap();
         build_path.push(build_dir);
-        
-        // check if to clean the directory
-        if self.clean == true && std::path::Path::exists(&build_path) == true {
-            std::fs::remove_dir_all(&build_path)?;
-        }
 
         // build entire ip graph and resolve with dynamic symbol transformation
-        let ip_graph = crate::core::ip::compute_final_ip_graph(&target, &catalog)?;
+        let ip_graph = profile::time("graph build", || crate::core::ip::compute_final_ip_graph(&target, &catalog))?;
+        let features = self.resolve_features(&target, &ip_graph)?;
+
+        // [!] report the references dynamic symbol transformation left for review
+        let mut unresolved: Vec<String> = ip_graph.get_map().values()
+            .flat_map(|n| n.as_ref().get_unresolved())
+            .map(|(file, name, pos)| format!("{}{}: 'work.{}' was not renamed by dynamic symbol transformation because the file is compiled into a different library", file.display(), pos, name))
+            .collect();
+        unresolved.sort();
+        for warning in unresolved {
+            println!("{}", theme::warning(warning));
+        }
 
         // only write lockfile and exit if flag is raised 
         if self.only_lock == true {
-            Self::write_lockfile(&target, &ip_graph, force)?;
+            Self::write_lockfile(&target, &ip_graph, catalog.get_aliases(), &features, force, guard)?;
             return Ok(())
         }
 
-        let files = crate::core::ip::build_ip_file_list(&ip_graph);
-        let current_graph = Self::build_full_graph(&files);
+        // [!] select the board or device to collect constraints for
+        let board = match &self.target {
+            Some(name) => match target.get_targets().inner().get(name) {
+                Some(t) => Some((name, t)),
+                None => {
+                    let mut names: Vec<&String> = target.get_targets().inner().keys().collect();
+                    names.sort();
+                    let hint = match names.is_empty() {
+                        true => String::from("Declare targets in the manifest's [targets] table"),
+                        false => format!("Available targets: {}", names.iter().map(|n| n.as_str()).collect::<Vec<&str>>().join(", ")),
+                    };
+                    return Err(AnyError(format!("no target named '{}' in the manifest\n\n{}", name, hint)))?
+                },
+            },
+            None => None,
+        };
+
+        let mut files = crate::core::ip::build_ip_file_list(&ip_graph);
+        files.append(&mut crate::core::ip::build_generated_file_list(&ip_graph));
+        // leave out the files gated behind features that are not enabled
+        files.retain(|f| Self::is_feature_enabled(&features, f.get_ip_manifest(), f.get_file()));
+        let current_graph = profile::time("graph build", || Self::build_full_graph(&files, target.get_providers()))?;
 
         let working_lib = Identifier::new_working();
 
-        let (top, bench) = match self.detect_bench(&current_graph, &working_lib) {
+        // the manifest's [plan] table is only used when neither --top nor --bench is given
+        let (top_unit, bench_unit) = match (&self.top, &self.bench) {
+            (None, None) => (target.get_plan_defaults().get_top().cloned(), target.get_plan_defaults().get_bench().cloned()),
+            _ => (self.top.clone(), self.bench.clone()),
+        };
+
+        // a unit marked as the top-level in the manifest stands in for --top
+        let top_unit = match (top_unit, &bench_unit) {
+            (Some(t), _) => Some(t),
+            (None, None) => Self::detect_marked_top(&current_graph, &working_lib)?,
+            (None, Some(_)) => None,
+        };
+
+        let (top, bench) = match Self::detect_bench(&current_graph, &working_lib, top_unit.as_ref(), bench_unit.as_ref()) {
             Ok(r) => r,
             Err(e) => match e {
-                PlanError::Ambiguous(_, _) => if self.all == true { (None, None) } else { return Err(e)? }
+                PlanError::Ambiguous(_, _) if self.all => (None, None),
+                // let the user choose a root when running from a terminal
+                PlanError::Ambiguous(_, ref roots) if !self.no_input && prompt::is_interactive() => {
+                    match Self::select_root(&target, &current_graph, &working_lib, roots, guard)? {
+                        Some(n) => match current_graph.get_node_by_index(n).unwrap().as_ref().is_testbench() {
+                            true => (None, Some(n)),
+                            false => (Some(n), None),
+                        },
+                        None => return Err(e)?,
+                    }
+                }
                 _ => return Err(e)?
             }
         };
         // determine the top-level node index
-        let (top, bench) = match self.detect_top(&current_graph, &working_lib, top, bench) {
+        let (top, bench) = match self.detect_top(&current_graph, &working_lib, top_unit.as_ref(), top, bench) {
             Ok(r) => r,
             Err(e) => match e {
                 PlanError::Ambiguous(_, _) => if self.all == true { (top, bench) } else { return Err(e)? }
@@ -523,7 +1442,7 @@ impl Plan {
         }
 
         // [!] write the lock file
-        Self::write_lockfile(&target, &ip_graph, force)?;
+        Self::write_lockfile(&target, &ip_graph, catalog.get_aliases(), &features, force, guard)?;
 
         // compute minimal topological ordering
         let min_order = match self.all {
@@ -540,6 +1459,11 @@ impl Plan {
             }
         };
 
+        // [!] warn about units used from dependencies the ip cannot see
+        for warning in Self::find_hidden_uses(&current_graph, &min_order, &ip_graph, catalog.get_aliases()) {
+            println!("{}", theme::warning(warning));
+        }
+
         // gather the files from each node in-order (multiple files can exist for a node)
         let file_order = { 
             let mut f_list = Vec::new();
@@ -552,6 +1476,18 @@ impl Plan {
             f_list
         };
 
+        // [!] record the ip and files that went into the blueprint
+        if let Some(path) = &self.sbom {
+            let lock = Self::build_lockfile(&ip_graph, catalog.get_aliases(), &features);
+            let entry = LockEntry::from(&target);
+            // files that are not generated yet have no contents to record
+            let existing: Vec<&IpFileNode> = file_order.iter().copied().filter(|f| std::path::Path::new(f.get_file()).exists()).collect();
+            let sbom = Sbom::new(&entry, &lock, &existing)?;
+            if guard.perform(&format!("write bill of materials to {}", path.display()), || sbom.write(path))?.is_some() {
+                println!("{}", theme::info(format!("Bill of materials created at: {}", path.display())));
+            }
+        }
+
         // grab the names as strings
         let top_name = match top {
             Some(i) => current_graph.get_key_by_index(i).unwrap().get_suffix().to_string(),
@@ -562,24 +1498,85 @@ impl Plan {
             None => String::new()
         };
 
+        // [!] verify the generics to override are declared by the top-level
+        let generics = match &self.generics {
+            Some(list) => match top {
+                Some(i) => {
+                    let node = current_graph.get_node_by_index(i).unwrap().as_ref();
+                    let entity = node.get_symbol().as_entity().unwrap();
+                    for g in list {
+                        if entity.get_generics().0.iter().find(|d| d.get_identifier() == g.get_name()).is_none() {
+                            return Err(PlanError::UnknownGeneric(entity.get_name().clone(), g.get_name().clone()))?
+                        }
+                    }
+                    list.as_slice()
+                },
+                None => return Err(AnyError(format!("generics can only be overridden on a top-level entity\n\nSet the top-level with '--top <unit>'")))?,
+            },
+            None => &[],
+        };
+
         // print information (maybe also print the plugin saved to .env too?)
         match top_name.is_empty() {
-            false => println!("info: top-level set to {}", top_name.blue()),
-            true =>  println!("{} no top-level set", "warning:".yellow()),
+            false => println!("{}", theme::info(format!("top-level set to {}", theme::name(&top_name)))),
+            true =>  println!("{}", theme::warning("no top-level set")),
         }
         match bench_name.is_empty() {
-            false => println!("info: testbench set to {}", bench_name.blue()),
-            true =>  println!("{} no testbench set", "warning:".yellow()),
+            false => println!("{}", theme::info(format!("testbench set to {}", theme::name(&bench_name)))),
+            true =>  println!("{}", theme::warning("no testbench set")),
+        }
+
+        // [!] remember the units for later plans
+        if self.save {
+            Self::save_defaults(&target, &top_name, &bench_name, guard)?;
         }
 
+        let _phase = profile::Phase::start("blueprint write");
         // store data in blueprint TSV format
         let mut blueprint_data = String::new();
+        // remember every file written to the blueprint to record its checksum
+        let mut blueprint_files: Vec<String> = Vec::new();
+        // gather the entries of the file lists expanded into the blueprint
+        let mut lists = FileList::new();
+
+        // [!] gather the header directories of every planned ip and the macros of the current ip
+        for dir in target.get_include_dirs() {
+            lists.add_incdir(dir);
+        }
+        for file in &file_order {
+            file.get_ip_manifest().get_include_dirs().into_iter().for_each(|d| lists.add_incdir(d));
+        }
+        for (name, value) in target.get_defines().inner() {
+            lists.add_define(name, value.as_deref());
+        }
+
+        // [!] write the generic overrides for the top-level first so plugins can forward them to tools
+        for g in generics {
+            blueprint_data += &format!("{}\t{}\t{}\n", GENERIC_FILESET, g.get_name(), g.get_value());
+        }
+
+        // [!] write the generators of the planned files first so plugins can run them before building
+        let mut generators: Vec<(&IpManifest, &String, &Generator)> = Vec::new();
+        for file in &file_order {
+            let ip = file.get_ip_manifest();
+            if let Some((name, generator)) = ip.get_generator_of(file.get_file()) {
+                if !generators.iter().any(|(i, n, _)| i.get_pkgid() == ip.get_pkgid() && *n == name) {
+                    generators.push((ip, name, generator));
+                }
+            }
+        }
+        for (ip, name, generator) in generators {
+            blueprint_data += &format!("{}\t{}\t{}\t{}\n", GENERATE_FILESET, name, crate::util::filesystem::to_standard(ip.get_root()), generator.get_command());
+        }
 
         // [!] collect user-defined filesets
         {
-            let current_files: Vec<String> = crate::util::filesystem::gather_current_files(&std::env::current_dir().unwrap());
+            let current_files: Vec<String> = crate::util::filesystem::gather_current_files(&std::env::current_dir().unwrap())
+                .into_iter()
+                .filter(|f| Self::is_feature_enabled(&features, &target, f))
+                .collect();
 
-            let mut vtable = VariableTable::new();
+            let mut vtable = VariableTable::new().load_system();
             // variables could potentially store empty strings if units are not set
             vtable.add("orbit.bench", &bench_name);
             vtable.add("orbit.top", &top_name);
@@ -587,14 +1584,9 @@ impl Plan {
             // use command-line set filesets
             if let Some(fsets) = &self.filesets {
                 for fset in fsets {
-                    // perform variable substitution
-                    let fset = Fileset::new()
-                        .name(fset.get_name())
-                        .pattern(&template::substitute(fset.get_pattern().to_string(), &vtable))?;
+                    let fset = Self::expand_fileset(fset, &vtable)?;
                     // match files
-                    fset.collect_files(&current_files).into_iter().for_each(|f| {
-                        blueprint_data += &fset.to_blueprint_string(f);
-                    });
+                    Self::write_fileset(&fset, fset.collect_files(&current_files), &mut blueprint_data, &mut blueprint_files, &mut lists)?;
                 }
             }
     
@@ -603,57 +1595,256 @@ impl Plan {
                 let fsets = p.filesets();
                 // check against every defined fileset for the plugin
                 for fset in fsets {
-                    // perform variable substitution
-                    let fset = Fileset::new()
-                        .name(fset.get_name())
-                        .pattern(&template::substitute(fset.get_pattern().to_string(), &vtable))?;
+                    let fset = Self::expand_fileset(fset, &vtable)?;
                     // match files
-                    fset.collect_files(&current_files).into_iter().for_each(|f| {
-                        blueprint_data += &fset.to_blueprint_string(&f);
-                    });
+                    Self::write_fileset(&fset, fset.collect_files(&current_files), &mut blueprint_data, &mut blueprint_files, &mut lists)?;
                 }
             }
+
+            // the command-line takes precedence over the manifests and file lists
+            for dir in self.include_dirs.iter().flatten() {
+                lists.add_incdir(target.get_root().join(dir));
+            }
+            for d in self.defines.iter().flatten() {
+                lists.add_define(d.get_name(), d.get_value());
+            }
+
+            // pass along the include directories and macros
+            for dir in lists.get_incdirs() {
+                let dir = crate::util::filesystem::to_standard(dir);
+                let name = dir.rsplit('/').next().unwrap_or(&dir).to_string();
+                blueprint_data += &format!("{}\t{}\t{}\n", INCDIR_FILESET, name, dir);
+            }
+            for (name, value) in lists.get_defines() {
+                blueprint_data += &format!("{}\t{}\t{}\n", DEFINE_FILESET, name, value.as_deref().unwrap_or(""));
+            }
+
+            // collect the constraints for the selected target
+            if let Some((name, t)) = board {
+                let fset = t.get_constraints();
+                let found = fset.collect_files(&current_files);
+                if found.is_empty() {
+                    println!("{}", theme::warning(format!("no constraint files found for target {}", theme::name(name))));
+                }
+                found.into_iter().for_each(|f| {
+                    blueprint_data += &fset.to_blueprint_string(&f);
+                    blueprint_files.push(f.to_string());
+                });
+            }
         }
 
+        // files of units that are not synthesizable are only for simulation
+        let sim_only: Vec<&String> = min_order.iter()
+            .map(|i| current_graph.get_node_by_index(*i).unwrap().as_ref())
+            .filter(|n| !n.is_synthesizable())
+            .flat_map(|n| n.get_associated_files().iter().map(|f| f.get_file()))
+            .collect();
+
+        // files of external units are written under the fileset named in the manifest
+        let externals: Vec<(&String, &String)> = min_order.iter()
+            .map(|i| current_graph.get_node_by_index(*i).unwrap().as_ref())
+            .filter_map(|n| n.external.as_ref().map(|fset| (n, fset)))
+            .flat_map(|(n, fset)| n.get_associated_files().iter().map(move |f| (f.get_file(), fset)))
+            .collect();
+
+        // files of units annotated with a standard take it over their manifest's standard
+        let annotated: Vec<(&String, &VhdlStandard)> = min_order.iter()
+            .map(|i| current_graph.get_node_by_index(*i).unwrap().as_ref())
+            .filter_map(|n| n.annotations.get_standard().map(|std| (n, std)))
+            .flat_map(|(n, std)| n.get_associated_files().iter().map(move |f| (f.get_file(), std)))
+            .collect();
+
         // collect in-order HDL file list
         for file in file_order {
-            if crate::core::fileset::is_rtl(&file.get_file()) == true {
-                blueprint_data += &format!("VHDL-RTL\t{}\t{}\n", file.get_library(), file.get_file());
-            } else {
-                blueprint_data += &format!("VHDL-SIM\t{}\t{}\n", file.get_library(), file.get_file());
+            let fileset = match externals.iter().find(|(f, _)| f == &file.get_file()) {
+                Some((_, fset)) => fset.as_str(),
+                None => match crate::core::fileset::is_rtl(file.get_file()) && !sim_only.contains(&file.get_file()) {
+                    true => "VHDL-RTL",
+                    false => "VHDL-SIM",
+                },
+            };
+            blueprint_data += &format!("{}\t{}\t{}", fileset, file.get_library(), file.get_file());
+            // append the standard when the file declares one
+            let standard = match annotated.iter().find(|(f, _)| f == &file.get_file()) {
+                Some((_, std)) => Some(*std),
+                None => file.get_ip_manifest().get_file_standard(file.get_file()),
+            };
+            if let (true, Some(std)) = (crate::core::fileset::is_vhdl(file.get_file()), standard) {
+                blueprint_data += &format!("\t{}", std);
+            }
+            blueprint_data.push('\n');
+            blueprint_files.push(file.get_file().to_string());
+            if !externals.iter().any(|(f, _)| f == &file.get_file()) {
+                lists.add_file(PathBuf::from(file.get_file()), Some(&file.get_library().to_string()));
             }
         }
 
         // create a output build directorie(s) if they do not exist
         if std::path::PathBuf::from(build_dir).exists() == false {
-            std::fs::create_dir_all(build_dir).expect("could not create build dir");
+            guard.perform(&format!("create build directory {}", build_dir), || Ok(std::fs::create_dir_all(build_dir)?))?;
         }
 
-        // [!] create the blueprint file
+        // [!] create the blueprint file (left alone when unchanged so make-style tools do not rebuild)
         let blueprint_path = build_path.join(BLUEPRINT_FILE);
-        let mut blueprint_file = std::fs::File::create(&blueprint_path).expect("could not create blueprint file");
-        // write the data
-        blueprint_file.write_all(blueprint_data.as_bytes()).expect("failed to write data to blueprint");
+        let updated = guard.update(&blueprint_path, &blueprint_data, self.touch)?;
+        if guard.is_dry_run() {
+            print!("{}", blueprint_data);
+        }
+
+        // [!] create the checksum file for the blueprint's files (readable by `sha256sum --check`)
+        let mut sums_data = String::new();
+        for f in blueprint_files.iter().filter(|f| std::path::Path::new(f).exists()) {
+            sums_data += &format!("{}  {}\n", checksum::file_sum(std::path::Path::new(f))?, f);
+        }
+        guard.update(&build_path.join(BLUEPRINT_SUMS_FILE), &sums_data, self.touch)?;
+
+        // [!] write the ordered file list for tools that read `.f` files
+        if let Some(path) = &self.emit_f {
+            for g in generics {
+                lists.add_define(&g.get_name().to_string(), Some(g.get_value()));
+            }
+            if guard.perform(&format!("write file list to {}", path.display()), || Ok(std::fs::write(path, lists.write())?))?.is_some() {
+                println!("{}", theme::info(format!("File list created at: {}", path.display())));
+            }
+        }
         
         // create environment variables to .env file
         let mut envs = environment::Environment::from_vec(vec![
             EnvVar::new().key(environment::ORBIT_TOP).value(&top_name), 
             EnvVar::new().key(environment::ORBIT_BENCH).value(&bench_name)
         ]);
+        for g in generics {
+            envs.insert(EnvVar::new().key(&g.to_env_key()).value(g.get_value()));
+        }
+        let incdirs = std::env::join_paths(lists.get_incdirs())?;
+        envs.insert(EnvVar::new().key(environment::ORBIT_INCDIRS).value(&incdirs.to_string_lossy()));
+        let defines: Vec<String> = lists.get_defines().iter().map(|(n, v)| match v {
+            Some(v) => format!("{}={}", n, v),
+            None => n.to_string(),
+        }).collect();
+        envs.insert(EnvVar::new().key(environment::ORBIT_DEFINES).value(&defines.join(";")));
+        // conditionally set the target being built for
+        if let Some((name, t)) = board {
+            envs.insert(EnvVar::new().key(environment::ORBIT_TARGET).value(name));
+            if let Some(part) = t.get_part() {
+                envs.insert(EnvVar::new().key(environment::ORBIT_TARGET_PART).value(part));
+            }
+        }
         // conditionally set the plugin used to plan
         match plug {
             Some(p) => { envs.insert(EnvVar::new().key(environment::ORBIT_PLUGIN).value(&p.alias())); () },
             None => (),
         };
-        crate::util::environment::save_environment(&envs, &build_path)?;
+        let env_path = build_path.join(environment::DOT_ENV_FILE);
+        let existing_env = match env_path.exists() {
+            true => std::fs::read_to_string(&env_path)?,
+            false => String::new(),
+        };
+        guard.update(&env_path, &environment::environment_contents(&envs, &existing_env), self.touch)?;
 
         // create a blueprint file
-        println!("info: Blueprint created at: {}", blueprint_path.display());
+        if !guard.is_dry_run() {
+            match updated {
+                true => println!("{}", theme::info(format!("Blueprint created at: {}", blueprint_path.display()))),
+                false => println!("{}", theme::info(format!("Blueprint is up to date at: {}", blueprint_path.display()))),
+            }
+        }
         Ok(())
     }
 }
 
 pub const BLUEPRINT_FILE: &str = "blueprint.tsv";
+pub const BLUEPRINT_SUMS_FILE: &str = "blueprint.sums";
+/// File in the build directory holding the time spent in each phase of planning.
+pub const PROFILE_FILE: &str = "profile.json";
+/// Fileset of the rules that list the commands producing generated files.
+pub const GENERATE_FILESET: &str = "GENERATE";
+/// Fileset of the rules that list the values to override the top-level's generics with.
+pub const GENERIC_FILESET: &str = "GENERIC";
+/// Fileset of the rules that list the include directories of expanded file lists.
+pub const INCDIR_FILESET: &str = "INCDIR";
+/// Fileset of the rules that list the macros defined by expanded file lists.
+pub const DEFINE_FILESET: &str = "DEFINE";
+
+/// A preprocessor macro to define for the backend tools.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Define {
+    name: String,
+    value: Option<String>,
+}
+
+impl Define {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+}
+
+impl FromStr for Define {
+    type Err = AnyError;
+
+    /// Parses a macro from `NAME` or `NAME=VALUE`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match s.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (s, None),
+        };
+        if crate::core::manifest::is_macro_name(name) == false {
+            return Err(AnyError(format!("invalid macro name '{}'", name)))
+        }
+        // the value is written to a single field of the blueprint and joined by semicolons for plugins
+        if value.as_ref().map(|v| v.contains(|c| c == '\t' || c == '\n' || c == '\r' || c == ';')).unwrap_or(false) {
+            return Err(AnyError(format!("value for macro '{}' cannot contain tabs, newlines, or semicolons", name)))
+        }
+        Ok(Self { name: name.to_string(), value })
+    }
+}
+
+/// A value to set for a generic of the top-level entity.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Generic {
+    name: Identifier,
+    value: String,
+}
+
+impl Generic {
+    pub fn get_name(&self) -> &Identifier {
+        &self.name
+    }
+
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
+    /// Creates the name of the environment variable storing the generic's value.
+    pub fn to_env_key(&self) -> String {
+        format!("{}{}", environment::ORBIT_GENERIC_PREFIX, self.name.to_string().to_uppercase())
+    }
+}
+
+impl FromStr for Generic {
+    type Err = AnyError;
+
+    /// Parses a generic from `NAME=VALUE`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match s.split_once('=') {
+            Some(pair) => pair,
+            None => return Err(AnyError(format!("missing separator '='; expecting 'name=value'"))),
+        };
+        let name = match name.parse::<Identifier>() {
+            Ok(iden) => iden,
+            Err(e) => return Err(AnyError(format!("invalid generic name '{}': {}", name, e))),
+        };
+        // the value is written to a single field of the blueprint
+        if value.contains(|c| c == '\t' || c == '\n' || c == '\r') {
+            return Err(AnyError(format!("value for generic '{}' cannot contain tabs or newlines", name)))
+        }
+        Ok(Self { name, value: value.to_string() })
+    }
+}
 
 #[derive(Debug)]
 pub enum PlanError {
@@ -663,7 +1854,11 @@ pub enum PlanError {
     TestbenchNoTest(Identifier),
     UnknownUnit(Identifier),
     UnknownEntity(Identifier),
+    /// the top-level entity and the generic it does not declare
+    UnknownGeneric(Identifier, Identifier),
     Ambiguous(String, Vec<Identifier>),
+    /// the units along a dependency cycle and their locations, each using the next
+    Cycle(Vec<(String, Option<String>)>),
     Empty,
 }
 
@@ -676,12 +1871,23 @@ impl std::fmt::Display for PlanError {
             Self::UnknownEntity(id) => write!(f, "no entity named '{}' in the current ip", id),
             Self::Empty => write!(f, "no entities found"),
             Self::BadEntity(id) => write!(f, "primary design unit '{}' is not an entity", id),
-            Self::BadTestbench(id) => write!(f, "entity '{}' is not a testbench and cannot be bench; use --top", id),
-            Self::BadTop(id) => write!(f, "entity '{}' is a testbench and cannot be top; use --bench", id),
+            Self::BadTestbench(id) => write!(f, "entity '{}' is marked as not a testbench and cannot be bench; use --top", id),
+            Self::BadTop(id) => write!(f, "entity '{}' is marked as a testbench and cannot be top; use --bench", id),
             Self::UnknownUnit(id) => write!(f, "no primary design unit named '{}' in the current ip", id),
+            Self::UnknownGeneric(ent, id) => write!(f, "top-level entity '{}' has no generic named '{}'", ent, id),
             Self::Ambiguous(name, tbs) => write!(f, "multiple {} were found:\n {}", name, tbs.iter().fold(String::new(), |sum, x| {
                 sum + &format!("\t{}\n", x)
             })),
+            Self::Cycle(units) => {
+                write!(f, "design units depend on each other in a cycle:")?;
+                for (name, location) in units {
+                    match location {
+                        Some(l) => write!(f, "\n  {} ({}) uses", name, l)?,
+                        None => write!(f, "\n  {} uses", name)?,
+                    }
+                }
+                write!(f, "\n  {}\n\nMove the declarations they share into a separate package to break the cycle", units.first().map(|(n, _)| n.as_str()).unwrap_or_default())
+            },
         }
     }
 }
@@ -698,11 +1904,128 @@ Options:
     --plugin <alias>        collect filesets defined for a plugin
     --build-dir <dir>       set the output build directory
     --fileset <key=glob>... set an additional fileset
-    --clean                 remove all files from the build directory
+    --generic <name=value>... override a generic of the top-level
+    --include-dir <dir>...  add a directory to search for headers
+    --define <name[=value]>... define a preprocessor macro for the tools
+    --target <name>         collect the constraints of a board or device
+    --features <list>...    enable comma-separated features of the ip or its dependencies
     --list                  view available plugins
     --all                   include all found HDL files
     --disable-ssh           convert SSH repositories to HTTPS for dependencies
+    --jobs <n>              install up to n missing dependencies at once (default: 1)
+    --vendored              only use dependencies copied by 'orbit vendor'
+    --sbom <file>           write a bill of materials for the blueprint
+    --emit-f <file>         write the ordered file list in the .f format
     --force                 skip reading from the lock file
+    --lock-only             only write the lock file
+    --dry-run               print the blueprint and lock file changes without writing them
+    --no-input              do not ask to choose the top-level or testbench
+    --save                  write the top-level and testbench to the manifest's [plan] table
+    --touch                 rewrite the blueprint and .env file even when unchanged
+    --profile-json          write the time spent in each phase to profile.json
 
 Use 'orbit help plan' to learn more about the command.
-";
\ No newline at end of file
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn kept_lock_entries() {
+        use crate::core::config::FromToml;
+        let lock = LockFile::from_toml(r#"
+version = 2
+
+[[ip]]
+name = "ks-tech.rary.top"
+version = "0.1.0"
+dependencies = [
+    { name = "ks-tech.rary.gates", version = "1", resolved = "1.2.0" },
+    { name = "ks-tech.rary.mux", version = "2", resolved = "2.0.0" },
+]
+
+[[ip]]
+name = "ks-tech.rary.gates"
+version = "1.2.0"
+dependencies = [{ name = "ks-tech.rary.util", version = "1", resolved = "1.0.1" }]
+
+[[ip]]
+name = "ks-tech.rary.mux"
+version = "2.0.0"
+dependencies = [{ name = "ks-tech.rary.buf", version = "3", resolved = "3.1.0" }]
+
+[[ip]]
+name = "ks-tech.rary.util"
+version = "1.0.1"
+
+[[ip]]
+name = "ks-tech.rary.buf"
+version = "3.1.0"
+"#.parse::<toml_edit::Document>().unwrap().as_table()).unwrap();
+        let top = PkgId::from_str("ks-tech.rary.top").unwrap();
+        let names = |kept: Vec<&LockEntry>| {
+            let mut names: Vec<String> = kept.iter().map(|e| e.get_name().to_string()).collect();
+            names.sort();
+            names
+        };
+        let requirements = |reqs: &[(&str, &str)]| -> HashMap<PkgId, AnyVersion> {
+            reqs.iter().map(|(n, v)| (PkgId::from_str(n).unwrap(), AnyVersion::from_str(v).unwrap())).collect()
+        };
+
+        // every entry is kept while the manifest is unchanged
+        let reqs = requirements(&[("ks-tech.rary.gates", "1"), ("ks-tech.rary.mux", "2")]);
+        assert_eq!(names(Plan::kept_lock_entries(&top, &reqs, &lock)), vec![
+            "ks-tech.rary.buf", "ks-tech.rary.gates", "ks-tech.rary.mux", "ks-tech.rary.util",
+        ]);
+
+        // a removed dependency drops the entries only it reached
+        let reqs = requirements(&[("ks-tech.rary.gates", "1")]);
+        assert_eq!(names(Plan::kept_lock_entries(&top, &reqs, &lock)), vec!["ks-tech.rary.gates", "ks-tech.rary.util"]);
+
+        // a requirement the locked version no longer satisfies is resolved again
+        let reqs = requirements(&[("ks-tech.rary.gates", "1"), ("ks-tech.rary.mux", "3")]);
+        assert_eq!(names(Plan::kept_lock_entries(&top, &reqs, &lock)), vec!["ks-tech.rary.gates", "ks-tech.rary.util"]);
+    }
+
+    #[test]
+    fn lock_only_plans() {
+        let plan = Plan::for_lock(true);
+        assert_eq!(plan.only_lock, true);
+        assert_eq!(plan.sync_lock, false);
+        assert_eq!(plan.disable_ssh, true);
+        assert_eq!(plan.bench, None);
+        assert_eq!(plan.get_build_dir(), None);
+        assert_eq!(plan.get_plugin(), None);
+
+        // syncing only differs by keeping the locked versions
+        assert_eq!(Plan::for_lock_sync(true), Plan { sync_lock: true, ..plan });
+    }
+
+    #[test]
+    fn generic_from_str() {
+        let g = Generic::from_str("data_width=32").unwrap();
+        assert_eq!(g.get_name(), &Identifier::from_str("data_width").unwrap());
+        assert_eq!(g.get_value(), "32");
+        assert_eq!(g.to_env_key(), "ORBIT_GENERIC_DATA_WIDTH");
+        // values can contain the separator
+        assert_eq!(Generic::from_str("INIT=x\"0=1\"").unwrap().get_value(), "x\"0=1\"");
+
+        assert!(Generic::from_str("data_width").is_err());
+        assert!(Generic::from_str("9lives=1").is_err());
+        assert!(Generic::from_str("name=a\tb").is_err());
+    }
+
+    #[test]
+    fn define_from_str() {
+        let d = Define::from_str("WIDTH=8").unwrap();
+        assert_eq!(d.get_name(), "WIDTH");
+        assert_eq!(d.get_value(), Some("8"));
+        assert_eq!(Define::from_str("SIM").unwrap().get_value(), None);
+        assert_eq!(Define::from_str("EMPTY=").unwrap().get_value(), Some(""));
+
+        assert!(Define::from_str("9LIVES").is_err());
+        assert!(Define::from_str("=1").is_err());
+        assert!(Define::from_str("LIST=a;b").is_err());
+    }
+}
@@ -0,0 +1,451 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::config;
+use crate::core::plugin::{self, PluginError};
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Flag, Optional, Positional};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
+use colored::Colorize;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq)]
+pub struct Plugin {
+    command: Option<PluginSubcommand>,
+}
+
+impl FromCli for Plugin {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Plugin {
+            command: cli.check_command(Positional::new("command"))?,
+        });
+        command
+    }
+}
+
+impl Command for Plugin {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        match &self.command {
+            Some(cmd) => cmd.exec(c),
+            None => {
+                println!("{}", HELP);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum PluginSubcommand {
+    New(New),
+    List(List),
+}
+
+impl FromCli for PluginSubcommand {
+    fn from_cli<'c>(cli: &'c mut Cli<'_>) -> Result<Self, CliError<'c>> {
+        match cli.match_command(&[
+            "new",
+            "list",
+        ])?.as_ref() {
+            "new" => Ok(PluginSubcommand::New(New::from_cli(cli)?)),
+            "list" => Ok(PluginSubcommand::List(List::from_cli(cli)?)),
+            _ => panic!("an unimplemented command was passed through!")
+        }
+    }
+}
+
+impl Command for PluginSubcommand {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        match self {
+            PluginSubcommand::New(n) => n.exec(c),
+            PluginSubcommand::List(l) => l.exec(c),
+        }
+    }
+}
+
+/// The scripting languages a starter plugin script can be written in.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Lang {
+    Python,
+    Sh,
+    Pwsh,
+}
+
+impl FromStr for Lang {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_ref() {
+            "python" | "py" => Ok(Self::Python),
+            "sh" | "bash" => Ok(Self::Sh),
+            "pwsh" | "powershell" => Ok(Self::Pwsh),
+            _ => Err(AnyError(format!("unknown language '{}'; expecting 'python', 'sh', or 'pwsh'", s))),
+        }
+    }
+}
+
+impl Lang {
+    /// The program that runs the script.
+    fn command(&self) -> &str {
+        match self {
+            Self::Python => "python",
+            Self::Sh => "sh",
+            Self::Pwsh => "pwsh",
+        }
+    }
+
+    /// The file extension for the script.
+    fn extension(&self) -> &str {
+        match self {
+            Self::Python => "py",
+            Self::Sh => "sh",
+            Self::Pwsh => "ps1",
+        }
+    }
+
+    /// The starter script's contents for the plugin `alias`.
+    fn script(&self, alias: &str) -> String {
+        match self {
+            Self::Python => PYTHON_SCRIPT,
+            Self::Sh => SH_SCRIPT,
+            Self::Pwsh => PWSH_SCRIPT,
+        }.replace("{{ alias }}", alias)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct New {
+    alias: String,
+    lang: Lang,
+    local: bool,
+}
+
+impl FromCli for New {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_NEW);
+        let command = Ok(New {
+            local: cli.check_flag(Flag::new("local"))?,
+            lang: cli.check_option_default(Optional::new("lang").value("lang").default("python"))?,
+            alias: cli.require_positional(Positional::new("alias"))?,
+        });
+        command
+    }
+}
+
+impl Command for New {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        if New::is_valid_alias(&self.alias) == false {
+            return Err(AnyError(format!("plugin alias '{}' must begin with a letter and only contain letters, digits, hyphens, and underscores", self.alias)))?
+        }
+        if c.get_plugins().contains_key(&self.alias) == true {
+            return Err(AnyError(format!("plugin '{}' already exists\n\nTry `orbit plan --list` to see available plugins", self.alias)))?
+        }
+        // select the configuration file to define the plugin in
+        let cfg_path = if self.local == true {
+            match c.get_ip_path() {
+                Some(path) => path.join(config::LOCAL_CONFIG_DIR).join(config::CONFIG_FILE),
+                None => return Err(AnyError(format!("no ip detected in the current directory to modify local configurations")))?,
+            }
+        } else {
+            c.get_config().get_root().join(config::CONFIG_FILE)
+        };
+        let mut cfg = config::Config::from_path(&cfg_path)?;
+        self.run(&mut cfg)
+    }
+}
+
+impl New {
+    /// Writes the starter script next to the configuration file `cfg` and
+    /// defines the plugin in `cfg` to call it.
+    fn run(&self, cfg: &mut config::Config) -> Result<(), Fault> {
+        // the script's path is relative to the configuration file so it resolves when the plugin runs
+        let rel_script = format!("{}/{}.{}", PLUGINS_DIR, self.alias, self.lang.extension());
+        let script = cfg.get_root().join(&rel_script);
+        if script.exists() == true {
+            return Err(AnyError(format!("script {} already exists", filesystem::normalize_path(script).display())))?
+        }
+        std::fs::create_dir_all(script.parent().unwrap())?;
+        std::fs::write(&script, self.lang.script(&self.alias))?;
+
+        cfg.append_plugin(&self.alias, self.lang.command(), &[&rel_script], &format!("{} plugin", self.alias))?;
+        cfg.write()?;
+
+        println!("info: created plugin '{}' in {}", self.alias, filesystem::normalize_path(cfg.get_root().join(config::CONFIG_FILE)).display());
+        println!("info: starter script written to {}", filesystem::normalize_path(script).display());
+        println!("hint: try `{}` and then `{}`", format!("orbit plan --plugin {}", self.alias).green(), "orbit build".green());
+        Ok(())
+    }
+
+    /// Checks `alias` begins with a letter and is only letters, digits, hyphens,
+    /// and underscores.
+    fn is_valid_alias(alias: &str) -> bool {
+        alias.starts_with(|c: char| c.is_ascii_alphabetic()) &&
+            alias.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+}
+
+/// The values accepted by `--format`.
+const FORMATS: &[&str] = &["table", "json"];
+
+#[derive(Debug, PartialEq)]
+enum ListFormat {
+    Table,
+    Json,
+}
+
+impl FromStr for ListFormat {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            _ => Err(AnyError(format!("unknown format '{}'; expecting 'table' or 'json'", s))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct List {
+    alias: Option<String>,
+    format: ListFormat,
+}
+
+impl FromCli for List {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_LIST);
+        let command = Ok(List {
+            format: cli.check_option_default(Optional::new("format").value("fmt").choices(FORMATS).default("table"))?,
+            alias: cli.check_positional(Positional::new("alias"))?,
+        });
+        command
+    }
+}
+
+impl Command for List {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        let mut plugs: Vec<&plugin::Plugin> = match &self.alias {
+            Some(alias) => match c.get_plugins().get(alias) {
+                Some(plg) => vec![plg],
+                None => return Err(PluginError::Missing(alias.to_owned()))?,
+            },
+            None => c.get_plugins().values().collect(),
+        };
+        match self.format {
+            ListFormat::Table => match &self.alias {
+                // display entire contents about the particular plugin
+                Some(_) => println!("{}", plugs[0]),
+                // display quick overview of all plugins
+                None => println!("{}", plugin::Plugin::list_plugins(&mut plugs)),
+            },
+            ListFormat::Json => println!("{}", List::fmt_json(&mut plugs)),
+        }
+        Ok(())
+    }
+}
+
+impl List {
+    /// Lists the `plugs` as a JSON array in alphabetical order by alias.
+    fn fmt_json(plugs: &mut [&plugin::Plugin]) -> String {
+        plugs.sort_by(|a, b| a.alias().cmp(b.alias()));
+        let entries: Vec<serde_json::Value> = plugs.iter()
+            .map(|p| p.to_json())
+            .collect();
+        serde_json::to_string_pretty(&entries).unwrap()
+    }
+}
+
+/// Directory relative to the configuration file where starter scripts are written.
+const PLUGINS_DIR: &str = "plugins";
+
+const PYTHON_SCRIPT: &str = "\
+# Plugin: {{ alias }}
+#
+# Orbit runs this script from the ip's root directory with `orbit build`. The
+# blueprint and .env files are written to the build directory by
+# `orbit plan --plugin {{ alias }}`.
+import os
+import sys
+
+build_dir = os.environ.get('ORBIT_BUILD_DIR', 'build')
+blueprint = os.environ.get('ORBIT_BLUEPRINT', 'blueprint.tsv')
+
+# read the variables saved during planning
+env = {}
+with open(os.path.join(build_dir, '.env'), 'r') as f:
+    for line in f:
+        line = line.strip()
+        if len(line) == 0 or line.startswith('#'):
+            continue
+        key, _, value = line.partition('=')
+        env[key] = value
+
+print('top:', env.get('ORBIT_TOP', ''))
+print('bench:', env.get('ORBIT_BENCH', ''))
+
+# read the files collected during planning in their compilation order
+with open(os.path.join(build_dir, blueprint), 'r') as f:
+    for line in f:
+        rule = line.rstrip('\\n').split('\\t')
+        if len(rule) < 3:
+            continue
+        (fileset, identifier, path) = rule[0:3]
+        print(fileset, identifier, path)
+
+# arguments after `--` on the command-line are passed to the plugin
+print('arguments:', sys.argv[1:])
+";
+
+const SH_SCRIPT: &str = "\
+#!/bin/sh
+# Plugin: {{ alias }}
+#
+# Orbit runs this script from the ip's root directory with `orbit build`. The
+# blueprint and .env files are written to the build directory by
+# `orbit plan --plugin {{ alias }}`.
+
+build_dir=\"${ORBIT_BUILD_DIR:-build}\"
+blueprint=\"${ORBIT_BLUEPRINT:-blueprint.tsv}\"
+
+# read the variables saved during planning
+while IFS='=' read -r key value; do
+    case \"$key\" in
+        ''|'#'*) continue ;;
+        ORBIT_TOP) top=\"$value\" ;;
+        ORBIT_BENCH) bench=\"$value\" ;;
+    esac
+done < \"$build_dir/.env\"
+
+echo \"top: $top\"
+echo \"bench: $bench\"
+
+# read the files collected during planning in their compilation order
+tab=$(printf '\\t')
+while IFS=\"$tab\" read -r fileset identifier path rest; do
+    [ -z \"$path\" ] && continue
+    echo \"$fileset $identifier $path\"
+done < \"$build_dir/$blueprint\"
+
+# arguments after `--` on the command-line are passed to the plugin
+echo \"arguments: $*\"
+";
+
+const PWSH_SCRIPT: &str = "\
+# Plugin: {{ alias }}
+#
+# Orbit runs this script from the ip's root directory with `orbit build`. The
+# blueprint and .env files are written to the build directory by
+# `orbit plan --plugin {{ alias }}`.
+
+$buildDir = if ($env:ORBIT_BUILD_DIR) { $env:ORBIT_BUILD_DIR } else { 'build' }
+$blueprint = if ($env:ORBIT_BLUEPRINT) { $env:ORBIT_BLUEPRINT } else { 'blueprint.tsv' }
+
+# read the variables saved during planning
+$vars = @{}
+foreach ($line in Get-Content (Join-Path $buildDir '.env')) {
+    $line = $line.Trim()
+    if ($line.Length -eq 0 -or $line.StartsWith('#')) { continue }
+    $key, $value = $line.Split('=', 2)
+    $vars[$key] = $value
+}
+
+Write-Output \"top: $($vars['ORBIT_TOP'])\"
+Write-Output \"bench: $($vars['ORBIT_BENCH'])\"
+
+# read the files collected during planning in their compilation order
+foreach ($line in Get-Content (Join-Path $buildDir $blueprint)) {
+    $rule = $line.Split(\"`t\")
+    if ($rule.Length -lt 3) { continue }
+    $fileset, $identifier, $path = $rule[0..2]
+    Write-Output \"$fileset $identifier $path\"
+}
+
+# arguments after `--` on the command-line are passed to the plugin
+Write-Output \"arguments: $args\"
+";
+
+const HELP: &str = "\
+Manage the plugins defined in configuration.
+
+Usage:
+    orbit plugin <command> [options]
+
+Commands:
+    new             create a plugin with a starter script
+    list            view the available plugins
+
+Use 'orbit help plugin' to learn more about the command.
+";
+
+const HELP_NEW: &str = "\
+Create a plugin with a starter script.
+
+Usage:
+    orbit plugin new [options] <alias>
+
+Args:
+    <alias>         the name to call the plugin by
+
+Options:
+    --lang <lang>   script language: python, sh, pwsh (default: python)
+    --local         define the plugin in the current ip's configuration
+
+Use 'orbit help plugin' to learn more about the command.
+";
+
+const HELP_LIST: &str = "\
+View the available plugins.
+
+Usage:
+    orbit plugin list [options] [<alias>]
+
+Args:
+    <alias>         view the details of only this plugin
+
+Options:
+    --format <fmt>  output format: table, json (default: table)
+
+Use 'orbit help plugin' to learn more about the command.
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn valid_alias() {
+        assert_eq!(New::is_valid_alias("xsim"), true);
+        assert_eq!(New::is_valid_alias("ghdl-08_sim"), true);
+        assert_eq!(New::is_valid_alias("8sim"), false);
+        assert_eq!(New::is_valid_alias(""), false);
+        assert_eq!(New::is_valid_alias("my sim"), false);
+    }
+
+    #[test]
+    fn new_writes_script_and_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = config::Config::from_path(&dir.path().join(config::CONFIG_FILE)).unwrap();
+        let cmd = New { alias: String::from("xsim"), lang: Lang::Sh, local: false };
+        cmd.run(&mut cfg).unwrap();
+
+        let script = std::fs::read_to_string(dir.path().join("plugins").join("xsim.sh")).unwrap();
+        assert!(script.contains("orbit plan --plugin xsim"));
+        let mut cfg = config::Config::from_path(&dir.path().join(config::CONFIG_FILE)).unwrap();
+        let plugs = cfg.collect_as_array_of_tables("plugin").unwrap();
+        let plug = plugs[0].0.get(0).unwrap();
+        assert_eq!(plug.get("command").unwrap().as_str(), Some("sh"));
+        // the script already exists
+        assert!(cmd.run(&mut cfg).is_err());
+    }
+}
@@ -18,8 +18,31 @@ use crate::interface::cli::Cli;
 use crate::interface::arg::{Positional, Flag, Optional};
 use crate::interface::errors::CliError;
 use crate::core::context::Context;
-use crate::util::anyerror::AnyError;
+use crate::util::anyerror::{AnyError, Category, CategoryError};
 use crate::util::anyerror::Fault;
+use crate::util::theme;
+use crate::util::filesystem;
+
+/// The values accepted by `--format`.
+const FORMATS: &[&str] = &["table", "json"];
+
+#[derive(Debug, PartialEq)]
+enum ProbeFormat {
+    Table,
+    Json,
+}
+
+impl std::str::FromStr for ProbeFormat {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            _ => Err(AnyError(format!("unknown format '{}'; expecting 'table' or 'json'", s))),
+        }
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub struct Probe {
@@ -29,6 +52,8 @@ pub struct Probe {
     version: Option<AnyVersion>,
     changelog: bool,
     readme: bool,
+    exact: bool,
+    format: ProbeFormat,
 }
 
 impl FromCli for Probe {
@@ -39,7 +64,9 @@ impl FromCli for Probe {
             units: cli.check_flag(Flag::new("units"))?,
             changelog: cli.check_flag(Flag::new("changes"))?,
             readme: cli.check_flag(Flag::new("readme"))?,
+            exact: cli.check_flag(Flag::new("exact"))?,
             version: cli.check_option(Optional::new("variant").switch('v').value("version"))?,
+            format: cli.check_option_default(Optional::new("format").value("fmt").choices(FORMATS).default("table"))?,
             ip: cli.require_positional(Positional::new("ip"))?,
         });
         command
@@ -53,18 +80,21 @@ impl Command for Probe {
         // gather the catalog (all manifests)
         let catalog = Catalog::new()
             .store(c.get_store_path())
-            .development(c.get_development_path().unwrap())?
+            .development(c.get_development_paths())?
             .installations(c.get_cache_path())?
             .available(c.get_vendors())?;
 
         let ids = catalog.inner().keys().map(|f| { f }).collect();
-        let target = crate::core::ip::find_ip(&self.ip, ids)?;
+        let target = match self.exact {
+            true => crate::core::ip::find_exact_ip(&self.ip, ids)?,
+            false => crate::core::ip::find_ip(&self.ip, ids)?,
+        };
         // ips under this key
         let status = catalog.inner().get(&target).unwrap();
 
         // collect all ip in the user's universe to see if ip exists
         if self.tags == true {
-            println!("{}", format_version_table(status, catalog.get_store().as_stored(&target)));
+            println!("{}", theme::table(&format_version_table(status, catalog.get_store().as_stored(&target))));
             return Ok(())
         }
 
@@ -82,7 +112,7 @@ impl Command for Probe {
             Some(i) => i,
             None => {
                 if stored_ip.is_none() {
-                    return Err(AnyError(format!("ip '{}' is not found as version '{}'", target, v)))?
+                    return Err(CategoryError(Category::Resolution, format!("ip '{}' is not found as version '{}'", target, v)))?
                 }
                 // try to create from store
                 stored_ip.as_ref().unwrap()
@@ -102,11 +132,25 @@ impl Command for Probe {
                 // force computing the primary design units if a development version
                 ip.collect_units(&state == &IpState::Development)?
             };
-            println!("{}", format_units_table(units.into_iter().map(|(_, unit)| unit).collect()));
+            match self.format {
+                ProbeFormat::Table => println!("{}", theme::table(&format_units_table(units.into_values().collect()))),
+                ProbeFormat::Json => println!("{}", format_units_json(units.into_values().collect())),
+            }
             return Ok(())
         }
 
         println!("{}", ip.display_information(&state));
+        if status.is_yanked(ip.get_version()) {
+            println!("{}", theme::warning(format!("version {} is yanked by its vendor and is not selected for new lock files", ip.get_version())));
+        }
+        if let Some(reason) = status.get_deprecation(ip.get_version()) {
+            println!("{}", theme::warning(format!("version {} is deprecated: {}", ip.get_version(), reason)));
+        }
+        if state == IpState::Development {
+            if let Some(dev_path) = c.locate_development_path(&ip.get_root()) {
+                println!("{}", theme::info(format!("found in development path {}", theme::name(filesystem::normalize_path(dev_path.clone()).display()))));
+            }
+        }
         self.run()
     }
 }
@@ -136,6 +180,18 @@ fn format_units_table(table: Vec<PrimaryUnit>) -> String {
     header + &body
 }
 
+/// Lists the primary design units as a JSON array sorted by identifier.
+fn format_units_json(table: Vec<PrimaryUnit>) -> String {
+    let mut table = table;
+    table.sort_by(|a, b| a.get_iden().cmp(b.get_iden()));
+    let entries: Vec<serde_json::Value> = table.iter().map(|unit| serde_json::json!({
+        "identifier": unit.get_iden().to_string(),
+        "type": unit.to_string(),
+        "description": unit.get_unit().get_description(),
+    })).collect();
+    serde_json::to_string_pretty(&entries).unwrap()
+}
+
 /// Creates a string for a version table for the particular ip.
 fn format_version_table(table: &IpLevel, stored_path: Option<PathBuf>) -> String {
     let header = format!("\
@@ -178,11 +234,21 @@ fn format_version_table(table: &IpLevel, stored_path: Option<PathBuf>) -> String
     // create body text
     let mut body = String::new();
     for (ver, status) in btmap.iter().rev() {
-        body.push_str(&format!("{:<15}{:<2}{:<2}{:<2}\n", 
+        // flag the versions the vendor's registry marks
+        let notice = if table.is_yanked(ver) {
+            "yanked"
+        } else if table.get_deprecation(ver).is_some() {
+            "deprecated"
+        } else {
+            ""
+        };
+        body.push_str(&format!("{:<15}{:<2}{:<2}{:<2}{:<3}{}\n", 
             ver.to_string(),
             { if status.0 { "D" } else { "" } },
             { if status.1 { "I" } else { "" } },
             { if status.2 { "A" } else { "" } },
+            "",
+            notice,
         ));
     }
     header + &body
@@ -199,11 +265,12 @@ Args:
 
 Options:
     --versions                  display the list of possible versions
-    --range <version:version>   narrow the displayed version list
     --variant, -v <version>     select a particular existing ip version
     --units                     display primary design units within an ip
     --changes                   view the changelog
     --readme                    view the readme
+    --exact                     require a fully qualified pkgid without completion
+    --format <fmt>              display units as a 'table' or 'json' (default: table)
 
 Use 'orbit help query' to learn more about the command.
 ";
\ No newline at end of file
@@ -43,7 +43,7 @@ impl FromCli for Read {
             version: cli.check_option(Optional::new("variant").switch('v').value("version"))?,
             ip: cli.check_option(Optional::new("ip").value("pkgid"))?,
             editor: cli.check_option(Optional::new("editor"))?,
-            mode: cli.check_option(Optional::new("mode"))?.unwrap_or(EditMode::Open),
+            mode: cli.check_option_default(Optional::new("mode").choices(&["open", "path"]).default("open"))?,
             location: cli.check_flag(Flag::new("location"))?,
             no_clean: cli.check_flag(Flag::new("no-clean"))?,
             unit: cli.require_positional(Positional::new("unit"))?,
@@ -85,7 +85,7 @@ impl Command for Read {
             // gather the catalog (all manifests)
             let catalog = Catalog::new()
                 .store(c.get_store_path())
-                .development(c.get_development_path().unwrap())?
+                .development(c.get_development_paths())?
                 .installations(c.get_cache_path())?
                 .available(c.get_vendors())?;
             let ids = catalog.inner().keys().map(|f| { f }).collect();
@@ -205,7 +205,7 @@ Options:
     --variant, -v <version> ip version to use
     --editor <editor>       the command to invoke a text-editor
     --location              append the :line:col to the filepath
-    --mode <mode>           select how to read: 'open' or 'path'
+    --mode <mode>           select how to read: 'open' or 'path' (default: open)
     --no-clean              do not delete previous files read
 
 Use 'orbit help read' to learn more about the command.
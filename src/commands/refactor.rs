@@ -0,0 +1,329 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use crate::Command;
+use crate::FromCli;
+use crate::core::catalog::Catalog;
+use crate::core::fileset;
+use crate::core::manifest::IpManifest;
+use crate::core::vhdl::refactor;
+use crate::core::vhdl::symbol::{VHDLParser, VHDLSymbol};
+use crate::core::vhdl::syntax::{Rewriter, SourceFile};
+use crate::core::vhdl::token::Identifier;
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Flag, Positional};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::diff;
+use crate::util::filesystem;
+
+#[derive(Debug, PartialEq)]
+pub struct Refactor {
+    command: Option<RefactorSubcommand>,
+}
+
+impl FromCli for Refactor {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        Ok(Refactor {
+            command: cli.check_command(Positional::new("command"))?,
+        })
+    }
+}
+
+impl Command for Refactor {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        match &self.command {
+            Some(cmd) => cmd.exec(c),
+            None => {
+                println!("{}", HELP);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum RefactorSubcommand {
+    RenameEntity(RenameEntity),
+    RenamePort(RenamePort),
+    ChangeLibrary(ChangeLibrary),
+}
+
+impl FromCli for RefactorSubcommand {
+    fn from_cli<'c>(cli: &'c mut Cli<'_>) -> Result<Self, CliError<'c>> {
+        match cli.match_command(&[
+            "rename-entity",
+            "rename-port",
+            "change-library",
+        ])?.as_ref() {
+            "rename-entity" => Ok(RefactorSubcommand::RenameEntity(RenameEntity::from_cli(cli)?)),
+            "rename-port" => Ok(RefactorSubcommand::RenamePort(RenamePort::from_cli(cli)?)),
+            "change-library" => Ok(RefactorSubcommand::ChangeLibrary(ChangeLibrary::from_cli(cli)?)),
+            _ => panic!("an unimplemented command was passed through!")
+        }
+    }
+}
+
+impl Command for RefactorSubcommand {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+        let ip = IpManifest::from_path(c.get_ip_path().unwrap())?;
+        match self {
+            RefactorSubcommand::RenameEntity(r) => r.run(c, &ip),
+            RefactorSubcommand::RenamePort(r) => r.run(&ip),
+            RefactorSubcommand::ChangeLibrary(r) => r.run(&ip),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct RenameEntity {
+    old: Identifier,
+    new: Identifier,
+    dry_run: bool,
+}
+
+impl FromCli for RenameEntity {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_RENAME_ENTITY);
+        Ok(RenameEntity {
+            dry_run: cli.check_flag(Flag::new("dry-run"))?,
+            old: cli.require_positional(Positional::new("old"))?,
+            new: cli.require_positional(Positional::new("new"))?,
+        })
+    }
+}
+
+impl RenameEntity {
+    fn run(&self, c: &Context, ip: &IpManifest) -> Result<(), Fault> {
+        let units = local_units(ip)?;
+        if !units.iter().any(|u| matches!(u, VHDLSymbol::Entity(e) if e.get_name() == &self.old)) {
+            return Err(AnyError(format!("entity '{}' is not declared in ip '{}'", self.old, ip.get_pkgid())))?
+        }
+        // the new name must not shadow any unit visible to the ip
+        if graph_units(c, ip)?.contains(&self.new) {
+            return Err(AnyError(format!("a primary design unit named '{}' already exists in the ip graph", self.new)))?
+        }
+        rewrite_ip(ip, self.dry_run, |file| refactor::rename_entity(file, &self.old, &self.new))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct RenamePort {
+    entity: Identifier,
+    old: Identifier,
+    new: Identifier,
+    dry_run: bool,
+}
+
+impl FromCli for RenamePort {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_RENAME_PORT);
+        Ok(RenamePort {
+            dry_run: cli.check_flag(Flag::new("dry-run"))?,
+            entity: cli.require_positional(Positional::new("entity"))?,
+            old: cli.require_positional(Positional::new("old"))?,
+            new: cli.require_positional(Positional::new("new"))?,
+        })
+    }
+}
+
+impl RenamePort {
+    fn run(&self, ip: &IpManifest) -> Result<(), Fault> {
+        let mut ports = None;
+        for file in vhdl_files(ip) {
+            let source = SourceFile::parse(&std::fs::read_to_string(&file)?)?;
+            let declared = refactor::declared_ports(&source, &self.entity);
+            if !declared.is_empty() {
+                ports = Some(declared);
+                break
+            }
+        }
+        let ports = match ports {
+            Some(p) => p,
+            None => return Err(AnyError(format!("entity '{}' with ports is not declared in ip '{}'", self.entity, ip.get_pkgid())))?,
+        };
+        if !ports.contains(&self.old) {
+            return Err(AnyError(format!("entity '{}' has no port named '{}'", self.entity, self.old)))?
+        }
+        if ports.contains(&self.new) {
+            return Err(AnyError(format!("entity '{}' already has a port named '{}'", self.entity, self.new)))?
+        }
+        rewrite_ip(ip, self.dry_run, |file| refactor::rename_port(file, &self.entity, &self.old, &self.new))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ChangeLibrary {
+    old: Identifier,
+    new: Identifier,
+    dry_run: bool,
+}
+
+impl FromCli for ChangeLibrary {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP_CHANGE_LIBRARY);
+        Ok(ChangeLibrary {
+            dry_run: cli.check_flag(Flag::new("dry-run"))?,
+            old: cli.require_positional(Positional::new("old"))?,
+            new: cli.require_positional(Positional::new("new"))?,
+        })
+    }
+}
+
+impl ChangeLibrary {
+    fn run(&self, ip: &IpManifest) -> Result<(), Fault> {
+        if self.old == self.new {
+            return Err(AnyError(format!("library '{}' already has that name", self.old)))?
+        }
+        rewrite_ip(ip, self.dry_run, |file| refactor::change_library(file, &self.old, &self.new))
+    }
+}
+
+/// Lists the vhdl files that belong to the `ip`.
+fn vhdl_files(ip: &IpManifest) -> Vec<String> {
+    filesystem::gather_current_files(&ip.get_root())
+        .into_iter()
+        .filter(|f| fileset::is_vhdl(f))
+        .collect()
+}
+
+/// Reads the design units declared in the `ip`'s own files.
+fn local_units(ip: &IpManifest) -> Result<Vec<VHDLSymbol>, Fault> {
+    let mut units = Vec::new();
+    for file in vhdl_files(ip) {
+        units.append(&mut VHDLParser::read_from(BufReader::new(File::open(&file)?))?.into_symbols());
+    }
+    Ok(units)
+}
+
+/// Collects the names of the primary design units in the `ip` and its dependencies.
+///
+/// Only the `ip`'s own files are read when its dependencies cannot be resolved.
+fn graph_units(c: &Context, ip: &IpManifest) -> Result<Vec<Identifier>, Fault> {
+    let catalog = Catalog::new()
+        .store(c.get_store_path())
+        .aliases(c.get_aliases())
+        .development(c.get_development_paths())?
+        .installations(c.get_cache_path())?;
+    let files: Vec<String> = match crate::core::ip::compute_final_ip_graph(ip, &catalog) {
+        Ok(graph) => crate::core::ip::build_ip_file_list(&graph)
+            .iter()
+            .map(|f| f.get_file().to_string())
+            .filter(|f| fileset::is_vhdl(f))
+            .collect(),
+        Err(_) => vhdl_files(ip),
+    };
+    let mut names = Vec::new();
+    for file in files {
+        for sym in VHDLParser::read_from(BufReader::new(File::open(&file)?))?.into_symbols() {
+            match sym {
+                VHDLSymbol::Entity(_) | VHDLSymbol::Package(_) | VHDLSymbol::Configuration(_) | VHDLSymbol::Context(_) => {
+                    names.push(sym.as_iden().unwrap().clone())
+                },
+                _ => (),
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Applies the edits from `refactor` to every vhdl file in the `ip`.
+///
+/// No file is written unless every file can be read and rewritten. With `dry_run`,
+/// the changes are displayed as a unified diff instead.
+fn rewrite_ip<F>(ip: &IpManifest, dry_run: bool, refactor: F) -> Result<(), Fault>
+    where F: Fn(&SourceFile) -> Rewriter {
+    let root = ip.get_root().display().to_string().replace('\\', "/");
+    let mut changes = Vec::new();
+    for file in vhdl_files(ip) {
+        let display = file.strip_prefix(&root).map(|f| f.trim_start_matches('/')).unwrap_or(&file).to_string();
+        let text = std::fs::read_to_string(&file)?;
+        let source = match SourceFile::parse(&text) {
+            Ok(s) => s,
+            Err(e) => return Err(AnyError(format!("failed to read file '{}': {}", display, e)))?,
+        };
+        let edits = refactor(&source);
+        if edits.is_empty() {
+            continue
+        }
+        let result = edits.apply();
+        // the rewritten text must remain readable for the next refactor
+        if let Err(e) = SourceFile::parse(&result) {
+            return Err(AnyError(format!("failed to rewrite file '{}': {}", display, e)))?
+        }
+        changes.push((file, display, text, result, edits.len()));
+    }
+
+    if changes.is_empty() {
+        println!("info: no references found in ip '{}'", ip.get_pkgid());
+        return Ok(())
+    }
+    for (file, display, before, after, count) in changes {
+        match dry_run {
+            true => print!("{}", diff::unified(&before, &after, &format!("a/{}", display), &format!("b/{}", display))),
+            false => {
+                std::fs::write(&file, after)?;
+                println!("info: updated {} reference(s) in {}", count, display);
+            },
+        }
+    }
+    Ok(())
+}
+
+const HELP: &str = "\
+Rewrite references to design units, ports, and libraries.
+
+Usage:
+    orbit refactor <command>
+
+Commands:
+    rename-entity   rename an entity and its references
+    rename-port     rename a port of an entity and its references
+    change-library  rename a library in clauses and selected names
+
+Use 'orbit help refactor' to learn more about the command.
+";
+
+const HELP_RENAME_ENTITY: &str = "\
+Rename an entity and its references.
+
+Usage:
+    orbit refactor rename-entity [options] <old> <new>
+
+Options:
+    --dry-run       display the changes as a diff without writing them
+
+Use 'orbit help refactor' to learn more about the command.
+";
+
+const HELP_RENAME_PORT: &str = "\
+Rename a port of an entity and its references.
+
+Usage:
+    orbit refactor rename-port [options] <entity> <old> <new>
+
+Options:
+    --dry-run       display the changes as a diff without writing them
+
+Use 'orbit help refactor' to learn more about the command.
+";
+
+const HELP_CHANGE_LIBRARY: &str = "\
+Rename a library in clauses and selected names.
+
+Usage:
+    orbit refactor change-library [options] <old> <new>
+
+Options:
+    --dry-run       display the changes as a diff without writing them
+
+Use 'orbit help refactor' to learn more about the command.
+";
\ No newline at end of file
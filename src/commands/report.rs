@@ -0,0 +1,148 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::report::{self, Record};
+use crate::interface::cli::Cli;
+use crate::interface::arg::Optional;
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Fault};
+
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    build_dir: Option<String>,
+    limit: usize,
+}
+
+impl FromCli for Report {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Report {
+            build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
+            limit: cli.check_option_default(Optional::new("limit").value("n").default("10"))?,
+        });
+        command
+    }
+}
+
+impl Command for Report {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+
+        // determine the build directory (command-line arg overrides configuration setting)
+        let b_dir = match &self.build_dir {
+            Some(dir) => dir,
+            None => c.get_build_dir(),
+        };
+        let records = report::read(&c.get_ip_path().unwrap().join(b_dir))?;
+        if records.is_empty() {
+            return match report::is_enabled(c.get_config())? {
+                true => Err(AnyError(format!("no runs are recorded in build directory '{}'\n\nTry `orbit plan` or `orbit build` to record a run", b_dir)))?,
+                false => Err(AnyError(format!("no runs are recorded in build directory '{}'\n\nTry setting `core.report = \"on\"` in the configuration to record runs", b_dir)))?,
+            }
+        }
+        let recent = &records[records.len().saturating_sub(self.limit)..];
+        println!("{}", Self::fmt_table(recent));
+        for line in Self::summarize(recent) {
+            println!("info: {}", line);
+        }
+        Ok(())
+    }
+}
+
+impl Report {
+    fn fmt_table(records: &[Record]) -> String {
+        let header = format!("\
+{:<21}{:<7}{:<8}{:<14}{:<7}{:<16}{:<24}{:<24}
+{:->21}{8:->7}{8:->8}{8:->14}{8:->7}{8:->16}{8:->24}{8:->24}\n",
+            "Started", "Step", "Status", "Duration (s)", "Files", "Plugin", "Top", "Bench", " ");
+        let mut body = String::new();
+        for r in records {
+            body.push_str(&format!("{:<21}{:<7}{:<8}{:<14.2}{:<7}{:<16}{:<24}{:<24}\n",
+                r.get_started().format("%Y-%m-%d %H:%M:%S").to_string(),
+                r.get_step().to_string(),
+                match r.is_success() { true => "ok", false => "failed" },
+                r.get_duration().as_secs_f64(),
+                r.get_files(),
+                r.get_plugin(),
+                r.get_top(),
+                r.get_bench(),
+            ));
+        }
+        header + &body
+    }
+
+    /// Compares the latest successful run of each step and plugin to the average
+    /// of the successful runs before it.
+    fn summarize(records: &[Record]) -> Vec<String> {
+        let mut groups: Vec<(String, &str)> = Vec::new();
+        for r in records.iter().filter(|r| r.is_success()) {
+            let key = (r.get_step().to_string(), r.get_plugin());
+            if !groups.contains(&key) {
+                groups.push(key);
+            }
+        }
+        let mut lines = Vec::new();
+        for (step, plugin) in groups {
+            let durations: Vec<f64> = records.iter()
+                .filter(|r| r.is_success() && r.get_step().to_string() == step && r.get_plugin() == plugin)
+                .map(|r| r.get_duration().as_secs_f64())
+                .collect();
+            let (latest, previous) = durations.split_last().unwrap();
+            let name = match plugin.is_empty() {
+                true => step.clone(),
+                false => format!("{} ({})", step, plugin),
+            };
+            lines.push(match previous.is_empty() {
+                true => format!("latest {} took {:.2}s", name, latest),
+                false => {
+                    let average = previous.iter().sum::<f64>() / previous.len() as f64;
+                    let change = match average > 0.0 {
+                        true => format!(" ({:+.1}%)", (latest - average) / average * 100.0),
+                        false => String::new(),
+                    };
+                    format!("latest {} took {:.2}s; the previous {} run(s) averaged {:.2}s{}", name, latest, previous.len(), average, change)
+                }
+            });
+        }
+        lines
+    }
+}
+
+const HELP: &str = "\
+Summarize the recent plans and builds of the current ip.
+
+Usage:
+    orbit report [options]
+
+Options:
+    --build-dir <dir>   the build directory to read runs from
+    --limit <n>         number of recent runs to show (default: 10)
+
+Runs are recorded to the build directory's report.tsv file when the
+configuration sets 'core.report = \"on\"'.
+
+Use 'orbit help report' to learn more about the command.
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn summarize_runs() {
+        let records: Vec<Record> = [
+            "2022-08-01T09:00:00+00:00\tplan\tok\t500\t4\tfakesim\tcounter\t-\t0.9.0",
+            "2022-08-01T09:01:00+00:00\tbuild\tok\t2000\t4\tfakesim\tcounter\t-\t0.9.0",
+            "2022-08-01T09:02:00+00:00\tbuild\tok\t4000\t4\tfakesim\tcounter\t-\t0.9.0",
+            "2022-08-01T09:03:00+00:00\tbuild\tfailed\t100\t4\tfakesim\tcounter\t-\t0.9.0",
+            "2022-08-01T09:04:00+00:00\tbuild\tok\t4500\t4\tfakesim\tcounter\t-\t0.9.0",
+        ].iter().map(|l| l.parse().unwrap()).collect();
+        assert_eq!(Report::summarize(&records), vec![
+            String::from("latest plan (fakesim) took 0.50s"),
+            String::from("latest build (fakesim) took 4.50s; the previous 2 run(s) averaged 3.00s (+50.0%)"),
+        ]);
+    }
+}
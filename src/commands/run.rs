@@ -0,0 +1,69 @@
+use crate::Command;
+use crate::FromCli;
+use crate::interface::cli::Cli;
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::Fault;
+use super::plan::Plan;
+use super::build::Build;
+
+#[derive(Debug, PartialEq)]
+pub struct Run {
+    plan: Plan,
+    args: Vec<String>,
+}
+
+impl FromCli for Run {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Run {
+            plan: Plan::from_cli_planning(cli)?,
+            args: cli.check_remainder()?,
+        });
+        command
+    }
+}
+
+impl Command for Run {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // generate the blueprint (uses the lockfile when it is in sync with the manifest)
+        self.plan.exec(c)?;
+        // execute the plugin remembered during planning with the forwarded arguments
+        Build::new(
+            self.plan.get_plugin().cloned(),
+            self.plan.get_build_dir().cloned(),
+            self.args.clone(),
+        ).exec(c)
+    }
+}
+
+const HELP: &str = "\
+Generate a blueprint file and execute a plugin.
+
+Usage:
+    orbit run [options] [--] [args]...
+
+Options:
+    --plugin <alias>        plugin to plan for and execute
+    --top <unit>            override auto-detected toplevel entity
+    --bench <tb>            override auto-detected toplevel testbench
+    --build-dir <dir>       set the output build directory
+    --fileset <key=glob>... set an additional fileset
+    --generic <name=value>... override a generic of the top-level
+    --include-dir <dir>...  add a directory to search for headers
+    --define <name[=value]>... define a preprocessor macro for the tools
+    --target <name>         collect the constraints of a board or device
+    --features <list>...    enable comma-separated features of the ip or its dependencies
+    --all                   include all found HDL files
+    --disable-ssh           convert SSH repositories to HTTPS for dependencies
+    --jobs <n>              install up to n missing dependencies at once (default: 1)
+    --vendored              only use dependencies copied by 'orbit vendor'
+    --force                 skip reading from the lock file
+    --no-input              do not ask to choose the top-level or testbench
+    --verbose               display the command being executed
+    -- args...              arguments to pass to the plugin
+
+Use 'orbit help run' to learn more about the command.
+";
\ No newline at end of file
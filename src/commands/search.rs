@@ -3,12 +3,36 @@ use crate::FromCli;
 use crate::core::catalog::Catalog;
 use crate::core::catalog::IpLevel;
 use crate::interface::cli::Cli;
-use crate::interface::arg::{Positional, Flag};
+use crate::interface::arg::{Positional, Flag, Optional};
 use crate::interface::errors::CliError;
 use crate::core::context::Context;
 use crate::core::pkgid::PkgId;
-use crate::util::anyerror::Fault;
+use crate::core::version::AnyVersion;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
 use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// The values accepted by `--format`.
+const FORMATS: &[&str] = &["table", "json"];
+
+#[derive(Debug, PartialEq)]
+enum SearchFormat {
+    Table,
+    Json,
+}
+
+impl std::str::FromStr for SearchFormat {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            _ => Err(AnyError(format!("unknown format '{}'; expecting 'table' or 'json'", s))),
+        }
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub struct Search {
@@ -16,6 +40,7 @@ pub struct Search {
     cached: bool,
     developing: bool,
     available: bool,
+    format: SearchFormat,
 }
 
 impl Command for Search {
@@ -26,7 +51,7 @@ impl Command for Search {
         let mut catalog = Catalog::new();
 
         // collect development IP
-        if default || self.developing { catalog = catalog.development(c.get_development_path().unwrap())?; }
+        if default || self.developing { catalog = catalog.development(c.get_development_paths())?; }
         
         // collect installed IP
         if default || self.cached { catalog = catalog.installations(c.get_cache_path())?; }
@@ -34,12 +59,17 @@ impl Command for Search {
         // collect available IP
         if default || self.available { catalog = catalog.available(c.get_vendors())?; }
 
-        self.run(&catalog)
+        // only point out the development path when there is more than one
+        let dev_paths = match c.get_development_paths().len() > 1 {
+            true => c.get_development_paths(),
+            false => &[],
+        };
+        self.run(&catalog, dev_paths)
     }
 }
 
 impl Search {
-    fn run(&self, catalog: &Catalog) -> Result<(), Fault> {
+    fn run(&self, catalog: &Catalog, dev_paths: &[PathBuf]) -> Result<(), Fault> {
 
         // transform into a BTreeMap for alphabetical ordering
         let mut tree = BTreeMap::new();
@@ -56,25 +86,58 @@ impl Search {
                 tree.insert(key, status);
             });
 
-        println!("{}", Self::fmt_table(tree));
+        match self.format {
+            SearchFormat::Table => println!("{}", Self::fmt_table(tree, dev_paths)),
+            SearchFormat::Json => println!("{}", Self::fmt_json(tree)),
+        }
         Ok(())
     }
 
-    fn fmt_table(catalog: BTreeMap<&PkgId, &IpLevel>) -> String {
-        let header = format!("\
+    /// Lists the `catalog` as a JSON array of its entries.
+    fn fmt_json(catalog: BTreeMap<&PkgId, &IpLevel>) -> String {
+        let entries: Vec<serde_json::Value> = catalog.into_iter()
+            .map(|(ip, status)| status.to_json(ip))
+            .collect();
+        serde_json::to_string_pretty(&entries).unwrap()
+    }
+
+    /// Lists the `catalog`, with a column for which of the `dev_paths` each
+    /// development ip was found in when any are given.
+    fn fmt_table(catalog: BTreeMap<&PkgId, &IpLevel>, dev_paths: &[PathBuf]) -> String {
+        let header = match dev_paths.is_empty() {
+            true => format!("\
 {:<15}{:<15}{:<20}{:<9}
 {:->15}{4:->15}{4:->20}{4:->9}\n", 
-            "Vendor", "Library", "Name", "Status", " ");
+                "Vendor", "Library", "Name", "Status", " "),
+            false => format!("\
+{:<15}{:<15}{:<20}{:<9}{:<9}
+{:->15}{5:->15}{5:->20}{5:->9}{5:->9}\n", 
+                "Vendor", "Library", "Name", "Status", "Dev path", " "),
+        };
         let mut body = String::new();
         for (ip, status) in catalog {
-            body.push_str(&format!("{:<15}{:<15}{:<20}{:<2}{:<2}{:<2}\n", 
+            // flag when the vendor yanked or deprecated the latest available version
+            let notice = match status.get_available(&AnyVersion::Latest) {
+                Some(latest) if status.is_yanked(latest.get_version()) => "Y",
+                Some(latest) if status.get_deprecation(latest.get_version()).is_some() => "X",
+                _ => "",
+            };
+            body.push_str(&format!("{:<15}{:<15}{:<20}{:<2}{:<2}{:<2}{:<3}", 
                 ip.get_vendor().as_ref().unwrap().to_string(),
                 ip.get_library().as_ref().unwrap().to_string(),
                 ip.get_name().to_string(),
                 { if status.is_developing() { "D" } else { "" } },
                 { if status.is_installed() { "I" } else { "" } },
                 { if status.is_available() { "A" } else { "" } },
+                notice,
             ));
+            if !dev_paths.is_empty() {
+                let found = status.get_dev().and_then(|dev| dev_paths.iter().find(|p| filesystem::is_within(p, dev.get_root())));
+                if let Some(path) = found {
+                    body.push_str(&filesystem::normalize_path(path.clone()).display().to_string());
+                }
+            }
+            body.push('\n');
         }
         header + &body
     }
@@ -88,6 +151,7 @@ impl FromCli for Search {
             cached: cli.check_flag(Flag::new("install").switch('i'))?,
             developing: cli.check_flag(Flag::new("develop").switch('d'))?,
             available: cli.check_flag(Flag::new("available").switch('a'))?,
+            format: cli.check_option_default(Optional::new("format").value("fmt").choices(FORMATS).default("table"))?,
         });
         command
     }
@@ -106,6 +170,7 @@ Options:
     --install, -i       filter for ip installed to cache
     --develop, -d       filter for ip in-development
     --available, -a     filter for ip available from vendors
+    --format <fmt>      display as a 'table' or 'json' (default: table)
 
 Use 'orbit help search' to learn more about the command.
 ";
@@ -116,11 +181,81 @@ mod test {
 
     #[test]
     fn fmt_table() {
-        let t = Search::fmt_table(BTreeMap::new());
+        let t = Search::fmt_table(BTreeMap::new(), &[]);
         let table = "\
 Vendor         Library        Name                Status   
 -------------- -------------- ------------------- -------- 
 ";
         assert_eq!(t, table);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn fmt_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("gates");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("Orbit.toml"), "[ip]\nname = \"gates\"\nlibrary = \"rary\"\nversion = \"1.0.0\"\nvendor = \"ks-tech\"\nrepository = \"https://github.com/ks-tech/gates.git\"\n").unwrap();
+        let catalog = Catalog::new().development(&[dir.path().to_path_buf()]).unwrap();
+        let tree: BTreeMap<&PkgId, &IpLevel> = catalog.inner().iter().collect();
+        let json: serde_json::Value = serde_json::from_str(&Search::fmt_json(tree)).unwrap();
+        assert_eq!(json, serde_json::json!([{
+            "pkgid": "ks-tech.rary.gates",
+            "vendor": "ks-tech",
+            "library": "rary",
+            "name": "gates",
+            "source": "https://github.com/ks-tech/gates.git",
+            "develop": { "version": "1.0.0", "path": filesystem::normalize_path(root).display().to_string() },
+            "installed": [],
+            "available": [],
+        }]));
+        assert_eq!(Search::fmt_json(BTreeMap::new()), "[]");
+    }
+
+    #[test]
+    fn fmt_table_dev_paths() {
+        let (first, second) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        for (dir, name) in [(&first, "gates"), (&second, "gates"), (&second, "adder")] {
+            let root = dir.path().join(name);
+            std::fs::create_dir(&root).unwrap();
+            std::fs::write(root.join("Orbit.toml"), format!("[ip]\nname = \"{}\"\nlibrary = \"rary\"\nversion = \"1.0.0\"\nvendor = \"ks-tech\"\n", name)).unwrap();
+        }
+        let dev_paths = [first.path().to_path_buf(), second.path().to_path_buf()];
+        let catalog = Catalog::new().development(&dev_paths).unwrap();
+        let tree: BTreeMap<&PkgId, &IpLevel> = catalog.inner().iter().collect();
+        let t = Search::fmt_table(tree, &dev_paths);
+        // an ip on an earlier development path hides the same ip on later paths
+        let table = format!("\
+Vendor         Library        Name                Status   Dev path 
+-------------- -------------- ------------------- -------- -------- 
+ks-tech        rary           adder               D        {}
+ks-tech        rary           gates               D        {}
+", 
+            filesystem::normalize_path(second.path().to_path_buf()).display(),
+            filesystem::normalize_path(first.path().to_path_buf()).display());
+        assert_eq!(t, table);
+    }
+
+    #[test]
+    fn fmt_table_yanked() {
+        use crate::core::vendor::VendorManifest;
+        let registry = tempfile::tempdir().unwrap();
+        std::fs::write(registry.path().join("index.toml"), "[vendor]\nname = \"ks-tech\"\n").unwrap();
+        for (name, version, notice) in [("gates", "1.0.0", ""), ("gates", "1.1.0", "yanked = true\n"), ("adder", "1.0.0", "deprecated = \"use ks-tech.rary.alu\"\n")] {
+            let dir = registry.path().join("rary").join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join(format!("Orbit-{}.toml", version)), format!("{}[ip]\nname = \"{}\"\nlibrary = \"rary\"\nversion = \"{}\"\nvendor = \"ks-tech\"\n", notice, name, version)).unwrap();
+        }
+        let vendor = VendorManifest::from_path(&registry.path().join("index.toml")).unwrap();
+        let vendors = std::collections::HashMap::from([(vendor.get_name().clone(), vendor)]);
+        let catalog = Catalog::new().available(&vendors).unwrap();
+        let tree: BTreeMap<&PkgId, &IpLevel> = catalog.inner().iter().collect();
+        // the latest version of gates is yanked and the latest version of adder is deprecated
+        let table = "\
+Vendor         Library        Name                Status   
+-------------- -------------- ------------------- -------- 
+ks-tech        rary           adder                   A X  
+ks-tech        rary           gates                   A Y  
+";
+        assert_eq!(Search::fmt_table(tree, &[]), table);
+    }
+}
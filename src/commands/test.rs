@@ -0,0 +1,439 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::adapter::{self, Adapter, AdapterMode};
+use crate::core::manifest::IpManifest;
+use crate::core::plugin::{Plugin, PluginError};
+use crate::core::vhdl::doc;
+use crate::core::vhdl::token::Identifier;
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Flag, Optional};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Category, CategoryError, Fault};
+use crate::util::environment::{self, EnvVar, Environment};
+use crate::util::filesystem;
+use crate::util::progress;
+use crate::util::progress::ProgressBar;
+use super::plan::{Plan, BLUEPRINT_FILE, BLUEPRINT_SUMS_FILE};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, PartialEq)]
+pub struct Test {
+    plugin: Option<String>,
+    bench: Option<Identifier>,
+    adapter: Option<AdapterMode>,
+    build_dir: Option<String>,
+    junit: Option<PathBuf>,
+    list: bool,
+    disable_ssh: bool,
+    vendored: bool,
+    jobs: usize,
+    args: Vec<String>,
+}
+
+impl FromCli for Test {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Test {
+            list: cli.check_flag(Flag::new("list"))?,
+            disable_ssh: cli.check_flag(Flag::new("disable-ssh"))?,
+            vendored: cli.check_flag(Flag::new("vendored"))?,
+            plugin: cli.check_option(Optional::new("plugin").value("alias"))?,
+            bench: cli.check_option(Optional::new("bench").value("tb"))?,
+            adapter: cli.check_option(Optional::new("adapter").value("name"))?,
+            build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
+            junit: cli.check_option(Optional::new("junit").value("file"))?,
+            jobs: cli.check_option_default(Optional::new("jobs").value("n").default("1"))?,
+            args: cli.check_remainder()?,
+        });
+        command
+    }
+}
+
+impl Command for Test {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+        let ip = IpManifest::from_path(c.get_ip_path().unwrap())?;
+
+        // hand the design to an existing test framework
+        if let Some(mode) = &self.adapter {
+            return self.run_adapter(c, &ip, mode)
+        }
+
+        let benches: Vec<Identifier> = discover_testbenches(&ip)?
+            .into_iter()
+            .filter(|tb| self.bench.is_none() || self.bench.as_ref() == Some(tb))
+            .collect();
+        if let Some(tb) = &self.bench {
+            if benches.is_empty() {
+                return Err(AnyError(format!("no testbench named '{}' in the current ip", tb)))?
+            }
+        }
+
+        // display testbench list and exit
+        if self.list {
+            benches.iter().for_each(|tb| println!("{}", tb));
+            return Ok(())
+        }
+        if benches.is_empty() {
+            return Err(AnyError(format!("no testbenches found in ip '{}'", ip.get_pkgid())))?
+        }
+
+        // locate the plugin
+        let plugin = match &self.plugin {
+            Some(alias) => match c.get_plugins().get(alias) {
+                Some(p) => p,
+                None => return Err(PluginError::Missing(alias.to_string()))?,
+            },
+            None => return Err(AnyError(String::from("pass a plugin to simulate the testbenches\n\nTry `orbit test --plugin <alias>`")))?,
+        };
+
+        // each testbench is planned into its own build directory
+        let b_dir = self.build_dir.as_ref().unwrap_or(c.get_build_dir());
+        let mut cases = Vec::new();
+        let mut runnable = Vec::new();
+        for tb in &benches {
+            let tb_dir = format!("{}/test/{}", b_dir, tb);
+            let plan = Plan::for_testbench(tb.clone(), tb_dir.clone(), Some(plugin.alias().clone()), self.disable_ssh, self.jobs, self.vendored);
+            match plan.exec(c) {
+                Ok(()) => runnable.push((tb.to_string(), tb_dir)),
+                Err(e) => cases.push(TestCase {
+                    name: tb.to_string(),
+                    duration: Duration::ZERO,
+                    failure: Some(format!("failed to plan: {}", e)),
+                    log: None,
+                }),
+            }
+        }
+
+        // variables shared by every testbench
+        let base = Test::base_environment(c, &ip)?;
+
+        let mut jobs = Vec::new();
+        for (name, tb_dir) in runnable {
+            jobs.push(TestJob::new(name, &ip, tb_dir, &base)?);
+        }
+        cases.append(&mut Test::run_jobs(plugin, &self.args, &jobs, self.jobs));
+        self.report(&ip, cases)
+    }
+}
+
+impl Test {
+    /// Plans the entire design and exports its file list for the test framework selected by `mode`.
+    /// 
+    /// The framework's own runner is invoked through the plugin, if one was given.
+    fn run_adapter(&self, c: &Context, ip: &IpManifest, mode: &AdapterMode) -> Result<(), Fault> {
+        let adapter = match mode {
+            AdapterMode::Using(a) => *a,
+            AdapterMode::Auto => match Adapter::detect(&ip.get_root()) {
+                Some(a) => a,
+                None => return Err(AnyError(format!("no VUnit or OSVVM test harness found in ip '{}'\n\nTry `orbit test --adapter <vunit|osvvm>` to select one", ip.get_pkgid())))?,
+            },
+        };
+        let plugin = match &self.plugin {
+            Some(alias) => match c.get_plugins().get(alias) {
+                Some(p) => Some(p),
+                None => return Err(PluginError::Missing(alias.to_string()))?,
+            },
+            None => None,
+        };
+
+        let b_dir = self.build_dir.as_ref().unwrap_or(c.get_build_dir());
+        let test_dir = format!("{}/test", b_dir);
+        Plan::for_regression(test_dir.clone(), plugin.map(|p| p.alias().clone()), self.disable_ssh, self.jobs, self.vendored).exec(c)?;
+
+        // translate the blueprint into the framework's format
+        let root = ip.get_root().join(&test_dir);
+        let blueprint = std::fs::read_to_string(root.join(BLUEPRINT_FILE))?;
+        let work_lib = ip.get_pkgid().get_library().as_ref().unwrap().to_string();
+        let export = root.join(adapter.export_file());
+        std::fs::write(&export, adapter.export(&adapter::read_hdl_rules(&blueprint), &work_lib))?;
+
+        let plugin = match plugin {
+            Some(p) => p,
+            None => {
+                println!("info: {} file list created at: {}", adapter, export.display());
+                return Ok(())
+            }
+        };
+        let base = Test::base_environment(c, ip)?
+            .add(EnvVar::new().key(environment::ORBIT_TEST_EXPORT).value(adapter.export_file()));
+        // the framework runs the entire regression as a single job
+        let job = TestJob::new(adapter.to_string(), ip, test_dir, &base)?;
+        let cases = Test::run_jobs(plugin, &self.args, &[job], 1);
+        self.report(ip, cases)
+    }
+
+    /// Collects the variables shared by every testbench in the `ip`.
+    fn base_environment(c: &Context, ip: &IpManifest) -> Result<Environment, Fault> {
+        Ok(Environment::new()
+            .from_config(c.get_config())?
+            .from_ip(ip)?
+            .add(EnvVar::new().key(environment::ORBIT_BLUEPRINT).value(BLUEPRINT_FILE))
+            .add(EnvVar::new().key(environment::ORBIT_BLUEPRINT_SUMS).value(BLUEPRINT_SUMS_FILE)))
+    }
+
+    /// Displays the results and writes the JUnit report, returning an error if any case failed.
+    fn report(&self, ip: &IpManifest, mut cases: Vec<TestCase>) -> Result<(), Fault> {
+        cases.sort_by(|a, b| a.name.cmp(&b.name));
+
+        println!("{}", fmt_table(&cases));
+        if let Some(path) = &self.junit {
+            std::fs::write(path, to_junit(&ip.get_pkgid().to_string(), &cases))?;
+            println!("info: JUnit report created at: {}", path.display());
+        }
+
+        let failures = cases.iter().filter(|t| t.failure.is_some()).count();
+        match failures {
+            0 => Ok(()),
+            _ => Err(CategoryError(Category::Plugin, format!("{} of {} testbenches failed", failures, cases.len())))?,
+        }
+    }
+
+    /// Simulates each testbench in `jobs` with the `plugin` using up to `workers` threads.
+    fn run_jobs(plugin: &Plugin, args: &[String], jobs: &[TestJob], workers: usize) -> Vec<TestCase> {
+        let next = AtomicUsize::new(0);
+        let (tx, rx) = mpsc::channel();
+        let mut bar = ProgressBar::new("testing", jobs.len());
+        let mut cases = Vec::new();
+        std::thread::scope(|s| {
+            for _ in 0..workers.max(1).min(jobs.len()) {
+                let tx = tx.clone();
+                let next = &next;
+                s.spawn(move || {
+                    // the main thread's progress bar owns the terminal line
+                    progress::set_thread_quiet(true);
+                    while let Some(job) = jobs.get(next.fetch_add(1, Ordering::SeqCst)) {
+                        if tx.send(job.run(plugin, args)).is_err() { return }
+                    }
+                });
+            }
+            drop(tx);
+            for case in rx {
+                bar.inc(&format!("{} {}", case.name, case.status()));
+                cases.push(case);
+            }
+        });
+        bar.finish();
+        cases
+    }
+}
+
+/// A testbench ready to be simulated.
+#[derive(Debug, PartialEq)]
+struct TestJob {
+    name: String,
+    envs: Vec<(String, String)>,
+    log: PathBuf,
+}
+
+impl TestJob {
+    /// Prepares the job `name` planned into `tb_dir` with the `base` variables.
+    fn new(name: String, ip: &IpManifest, tb_dir: String, base: &Environment) -> Result<Self, Fault> {
+        let root = ip.get_root().join(&tb_dir);
+        // variables set during planning take priority
+        let mut envs = Environment::new().from_env_file(&root)?;
+        envs.insert(EnvVar::new().key(environment::ORBIT_BUILD_DIR).value(&tb_dir));
+        envs.insert(EnvVar::new().key(environment::ORBIT_LOG_FILE).value(TEST_LOG_FILE));
+        base.iter().for_each(|e| { envs.insert(EnvVar::new().key(e.get_key()).value(e.get_value())); });
+        Ok(Self {
+            name,
+            envs: envs.iter().map(|e| (e.get_key().to_string(), e.get_value().to_string())).collect(),
+            log: root.join(TEST_LOG_FILE),
+        })
+    }
+
+    /// Runs the `plugin` for the testbench with its output captured to the log file.
+    fn run(&self, plugin: &Plugin, args: &[String]) -> TestCase {
+        let start = Instant::now();
+        let (command, args) = match plugin.resolve_command(args) {
+            Ok(r) => r,
+            Err(e) => return TestCase { name: self.name.clone(), duration: start.elapsed(), failure: Some(e.to_string()), log: None },
+        };
+        // appending to the emptied log keeps the lines the plugin writes to it itself
+        let result = std::fs::File::create(&self.log)
+            .and_then(|_| std::fs::OpenOptions::new().append(true).open(&self.log))
+            .and_then(|log| filesystem::invoke_logged(&command, &args, &self.envs, &log, Context::enable_windows_bat_file_match()))
+            .and_then(|mut proc| proc.wait());
+        let failure = match result {
+            Ok(status) => match status.code() {
+                Some(0) => None,
+                Some(num) => Some(format!("exited with error code: {}", num)),
+                None => Some(String::from("terminated by signal")),
+            },
+            Err(e) => Some(e.to_string()),
+        };
+        TestCase { name: self.name.clone(), duration: start.elapsed(), failure, log: Some(self.log.clone()) }
+    }
+}
+
+/// The result of simulating a single testbench.
+#[derive(Debug, PartialEq)]
+struct TestCase {
+    name: String,
+    duration: Duration,
+    failure: Option<String>,
+    log: Option<PathBuf>,
+}
+
+impl TestCase {
+    fn status(&self) -> &str {
+        match self.failure {
+            Some(_) => "failed",
+            None => "passed",
+        }
+    }
+}
+
+/// Finds every entity in the `ip` that is a testbench.
+/// 
+/// An entity given a role in the manifest or annotated with `testbench` is a
+/// testbench accordingly. Otherwise, it is a testbench if it has no ports.
+fn discover_testbenches(ip: &IpManifest) -> Result<Vec<Identifier>, Fault> {
+    let mut benches: Vec<Identifier> = Vec::new();
+    for unit in ip.collect_units(true)?.into_values() {
+        let entity = match unit.get_unit().get_symbol().and_then(|sym| sym.as_entity()) {
+            Some(e) => e,
+            None => continue,
+        };
+        let file = unit.get_unit().get_source_code_file();
+        let docs = doc::read(&std::fs::read_to_string(file)?).unwrap_or_default();
+        let annotated = match docs.iter().find(|d| d.get_name() == entity.get_name()) {
+            Some(d) => match d.get_annotations() {
+                Ok(a) => a.is_testbench(),
+                Err(e) => return Err(AnyError(format!("file {} contains an invalid annotation: {}", file, e)))?,
+            },
+            None => None,
+        };
+        let marked = ip.get_unit_role(unit.get_iden()).map(|r| r.is_testbench()).or(annotated);
+        if marked.unwrap_or(entity.is_testbench()) {
+            benches.push(unit.get_iden().clone());
+        }
+    }
+    benches.sort_by_key(|tb| tb.to_string());
+    Ok(benches)
+}
+
+fn fmt_table(cases: &[TestCase]) -> String {
+    let header = format!("\
+{:<32}{:<10}{:<10}{:<40}
+{:->32}{4:->10}{4:->10}{4:->40}\n",
+        "Testbench", "Result", "Time (s)", "Log", " ");
+    let mut body = String::new();
+    for case in cases {
+        body.push_str(&format!("{:<32}{:<10}{:<10.2}{:<40}\n",
+            case.name,
+            case.status(),
+            case.duration.as_secs_f32(),
+            case.log.as_ref().map(|p| p.display().to_string()).unwrap_or_default(),
+        ));
+    }
+    let failures = cases.iter().filter(|t| t.failure.is_some()).count();
+    body.push_str(&format!("\ntest result: {} passed; {} failed", cases.len() - failures, failures));
+    header + &body
+}
+
+/// Formats the `cases` as a JUnit XML report for the test suite `suite`.
+fn to_junit(suite: &str, cases: &[TestCase]) -> String {
+    let failures = cases.iter().filter(|t| t.failure.is_some()).count();
+    let total: f32 = cases.iter().map(|t| t.duration.as_secs_f32()).sum();
+    let mut xml = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites name=\"orbit\" tests=\"{1}\" failures=\"{2}\" time=\"{3:.3}\">
+  <testsuite name=\"{0}\" tests=\"{1}\" failures=\"{2}\" time=\"{3:.3}\">\n",
+        escape_xml(suite), cases.len(), failures, total);
+    for case in cases {
+        xml.push_str(&format!("    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"", escape_xml(&case.name), escape_xml(suite), case.duration.as_secs_f32()));
+        let output = case.log.as_ref().and_then(|p| std::fs::read(p).ok()).map(|b| String::from_utf8_lossy(&b).to_string());
+        if case.failure.is_none() && output.is_none() {
+            xml.push_str("/>\n");
+            continue
+        }
+        xml.push_str(">\n");
+        if let Some(msg) = &case.failure {
+            xml.push_str(&format!("      <failure message=\"{}\"/>\n", escape_xml(msg)));
+        }
+        if let Some(out) = output {
+            xml.push_str(&format!("      <system-out>{}</system-out>\n", escape_xml(&out)));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Escapes `s` to be written as XML text or an attribute value.
+fn escape_xml(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            // characters not allowed in XML 1.0
+            c if (c as u32) < 0x20 && c != '\n' && c != '\r' && c != '\t' => (),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+const TEST_LOG_FILE: &str = "test.log";
+
+const HELP: &str = "\
+Simulate every testbench in the current ip.
+
+Usage:
+    orbit test [options] [--] [args]...
+
+Options:
+    --plugin <alias>    plugin to simulate each testbench with
+    --bench <tb>        only run the given testbench
+    --adapter <name>    export the design for 'vunit', 'osvvm', or 'auto'
+    --build-dir <dir>   set the output build directory
+    --jobs <n>          simulate up to n testbenches at once (default: 1)
+    --junit <file>      write a JUnit XML report of the results
+    --list              view the testbenches and exit
+    --disable-ssh       convert SSH repositories to HTTPS for dependencies
+    --vendored          only use dependencies copied by 'orbit vendor'
+    -- args...          arguments to pass to the plugin
+
+Use 'orbit help test' to learn more about the command.
+";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn junit_report() {
+        let cases = vec![
+            TestCase { name: String::from("adder_tb"), duration: Duration::from_millis(1500), failure: None, log: None },
+            TestCase { name: String::from("mux_tb"), duration: Duration::from_millis(500), failure: Some(String::from("exited with error code: 1")), log: None },
+        ];
+        assert_eq!(to_junit("ks-tech.rary.gates", &cases), "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites name=\"orbit\" tests=\"2\" failures=\"1\" time=\"2.000\">
+  <testsuite name=\"ks-tech.rary.gates\" tests=\"2\" failures=\"1\" time=\"2.000\">
+    <testcase name=\"adder_tb\" classname=\"ks-tech.rary.gates\" time=\"1.500\"/>
+    <testcase name=\"mux_tb\" classname=\"ks-tech.rary.gates\" time=\"0.500\">
+      <failure message=\"exited with error code: 1\"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+");
+    }
+
+    #[test]
+    fn xml_escape() {
+        assert_eq!(escape_xml("a < b && \"c\""), "a &lt; b &amp;&amp; &quot;c&quot;");
+        assert_eq!(escape_xml("bell\u{7}"), "bell");
+    }
+}
\ No newline at end of file
@@ -12,6 +12,7 @@ use crate::core::manifest::IpManifest;
 use crate::core::vhdl::subunit::SubUnit;
 use crate::core::vhdl::symbol::CompoundIdentifier;
 use crate::core::vhdl::symbol::Entity;
+use crate::core::vhdl::symbol::Instance;
 use crate::interface::cli::Cli;
 use crate::interface::arg::{Flag, Optional};
 use crate::interface::errors::CliError;
@@ -41,9 +42,10 @@ impl std::str::FromStr for IdentifierFormat {
 pub struct Tree {
     root: Option<Identifier>,
     compress: bool,
-    format: Option<IdentifierFormat>,
+    format: IdentifierFormat,
     ascii: bool,
     ip: bool,
+    path: Option<Identifier>,
 }
 
 impl FromCli for Tree {
@@ -53,8 +55,9 @@ impl FromCli for Tree {
             root: cli.check_option(Optional::new("root").value("entity"))?,
             compress: cli.check_flag(Flag::new("compress"))?,
             ascii: cli.check_flag(Flag::new("ascii"))?,
-            format: cli.check_option(Optional::new("format").value("fmt"))?,
+            format: cli.check_option_default(Optional::new("format").value("fmt").choices(&["long", "short"]).default("short"))?,
             ip: cli.check_flag(Flag::new("ip"))?,
+            path: cli.check_option(Optional::new("path").value("entity"))?,
         });
         command
     }
@@ -76,7 +79,8 @@ impl Command for Tree {
         // gather the catalog
         let catalog = Catalog::new()
             .store(c.get_store_path())
-            .development(c.get_development_path().unwrap())?
+            .aliases(c.get_aliases())
+            .development(c.get_development_paths())?
             .installations(c.get_cache_path())?
             .available(c.get_vendors())?;
         
@@ -125,17 +129,73 @@ impl Tree {
             }
         };
 
+        if let Some(ent) = &self.path {
+            return Self::print_instance_paths(&graph, n, ent)
+        }
+
         let tree = graph.get_graph().treeview(n);
         for twig in &tree {
             let branch_str = match self.ascii {
                 true => Self::to_ascii(&twig.0.to_string()),
                 false => twig.0.to_string(),
             };
-            println!("{}{}", branch_str, graph.get_node_by_index(twig.1).unwrap().as_ref().display(self.format.as_ref().unwrap_or(&IdentifierFormat::Short)));            
+            println!("{}{}", branch_str, graph.get_node_by_index(twig.1).unwrap().as_ref().display(&self.format));            
+        }
+        Ok(())
+    }
+
+    /// Prints every hierarchical instance path from the root node `n` down to the
+    /// entity `target`, followed by the generic maps written along the way.
+    fn print_instance_paths(graph: &GraphMap<CompoundIdentifier, EntityNode, ()>, n: usize, target: &Identifier) -> Result<(), Fault> {
+        if graph.get_map().keys().any(|k| k.get_suffix() == target) == false {
+            return Err(PlanError::UnknownEntity(target.clone()))?
+        }
+        let mut paths = Vec::new();
+        Self::find_instance_paths(graph, n, target, &mut Vec::new(), &mut vec![n], &mut paths);
+        let root = graph.get_node_by_index(n).unwrap().as_ref().entity.get_name();
+        if paths.is_empty() {
+            return Err(AnyError(format!("entity '{}' is not instantiated within the hierarchy of '{}'", target, root)))?
+        }
+        for path in paths {
+            let labels: Vec<String> = path.iter().map(|i| i.get_label().to_string()).collect();
+            match labels.is_empty() {
+                true => println!("{}", root),
+                false => println!("{}.{}", root, labels.join(".")),
+            }
+            for inst in path.iter().filter(|i| i.get_generics().is_empty() == false) {
+                println!("    {}: generic map ({})", inst.get_label(), inst.get_generics().join(", "));
+            }
         }
         Ok(())
     }
 
+    /// Collects the instances leading from node `n` to each instantiation of the entity
+    /// `target` into `paths`, in the order they are written.
+    ///
+    /// The `trail` holds the instances taken to reach `n` and `visited` holds the nodes
+    /// along it to stop at recursive instantiations.
+    fn find_instance_paths<'a>(graph: &'a GraphMap<CompoundIdentifier, EntityNode, ()>, n: usize, target: &Identifier, trail: &mut Vec<&'a Instance>, visited: &mut Vec<usize>, paths: &mut Vec<Vec<&'a Instance>>) {
+        let node = graph.get_node_by_index(n).unwrap().as_ref();
+        if node.entity.get_name() == target {
+            paths.push(trail.clone());
+            return
+        }
+        for (key, inst) in &node.instances {
+            let next = match graph.get_node_by_key(key) {
+                Some(m) => m.index(),
+                None => continue,
+            };
+            if visited.contains(&next) {
+                continue;
+            }
+            trail.push(inst);
+            visited.push(next);
+            Self::find_instance_paths(graph, next, target, trail, visited, paths);
+            trail.pop();
+            visited.pop();
+        }
+    }
+
     /// Construct and print the graph at an IP dependency level.
     fn run_ip_graph(&self, target: IpManifest, catalog: Catalog) -> Result<(), Fault> {
         let ip_graph = ip::compute_final_ip_graph(&target, &catalog)?;
@@ -181,8 +241,8 @@ impl Tree {
                 continue;
             }
             // parse VHDL code
-            let contents = std::fs::read_to_string(&source_file.get_file()).unwrap();
-            let symbols = symbol::VHDLParser::read(&contents).into_symbols();
+            let reader = std::io::BufReader::new(std::fs::File::open(&source_file.get_file()).unwrap());
+            let symbols = symbol::VHDLParser::read_from(reader).unwrap().into_symbols();
             
             let lib = source_file.get_library();
             // add all entities to a graph and store architectures for later analysis
@@ -221,6 +281,19 @@ impl Tree {
                 None => continue
             };
             entity_node.as_ref_mut().add_file(node.get_file());
+            // remember the instances by the key of the unit they instantiate
+            for inst in node.get_sub().get_instances() {
+                let unit = inst.get_unit();
+                let key = match unit.get_prefix() {
+                    Some(p) if p == &Identifier::new_working() => CompoundIdentifier::new(node.get_file().get_library().clone(), unit.get_suffix().clone()),
+                    Some(_) => unit.clone(),
+                    None => match component_pairs.get(unit.get_suffix()) {
+                        Some(lib) => CompoundIdentifier::new(lib.clone(), unit.get_suffix().clone()),
+                        None => unit.clone(),
+                    },
+                };
+                entity_node.as_ref_mut().instances.push((key, inst.clone()));
+            }
             // create edges
             for dep in node.get_sub().get_edges() {
                 // verify we are not a package (will mismatch and make inaccurate graph)
@@ -275,6 +348,8 @@ use super::plan::PlanError;
 pub struct EntityNode<'a> {
     entity: symbol::Entity,
     files: Vec<&'a IpFileNode<'a>>,
+    /// instances within the entity's architectures with the key of the unit they instantiate
+    instances: Vec<(CompoundIdentifier, Instance)>,
 }
 
 impl<'a> EntityNode<'a> {
@@ -285,6 +360,7 @@ impl<'a> EntityNode<'a> {
         Self {
             entity: entity,
             files: set,
+            instances: Vec::new(),
         }
     }
 
@@ -293,7 +369,7 @@ impl<'a> EntityNode<'a> {
     }
 
     fn black_box(entity: symbol::Entity) -> Self {
-        Self { entity: entity, files: Vec::new() }
+        Self { entity: entity, files: Vec::new(), instances: Vec::new() }
     }
 
     fn add_file(&mut self, file: &'a IpFileNode<'a>) {
@@ -326,10 +402,10 @@ Usage:
 Options:
     --root <entity>     top entity identifier to mark as the root node
     --compress          replace duplicate branches with a label marking
-    --all               include all possible roots in tree
-    --format <fmt>      select how to display entity names: 'long' or 'short'
+    --format <fmt>      select how to display entity names: 'long' or 'short' (default: short)
     --ascii             use chars from the original 128 ascii set
     --ip                view the ip-level dependency graph
+    --path <entity>     list the instance paths from the root to an entity
 
 Use 'orbit help tree' to learn more about the command.
 ";
\ No newline at end of file
@@ -21,7 +21,7 @@ impl FromCli for Uninstall {
         cli.set_help(HELP);
         let command = Ok(Uninstall {
             ip: cli.require_positional(Positional::new("ip"))?,
-            version: cli.check_option(Optional::new("variant").switch('v').value("version"))?.unwrap_or(AnyVersion::Dev),
+            version: cli.check_option_default(Optional::new("variant").switch('v').value("version").default("dev"))?,
         });
         command
     }
@@ -32,7 +32,7 @@ impl Command for Uninstall {
     fn exec(&self, c: &Context) -> Result<(), Self::Err> {
         // collect the catalog from dev and installations
         let catalog = Catalog::new()
-            .development(c.get_development_path().unwrap())?
+            .development(c.get_development_paths())?
             .installations(c.get_cache_path())?
             .available(c.get_vendors())?;
         // find the target IP
@@ -80,7 +80,9 @@ Usage:
 
 Args:
     <ip>                    the pkgid corresponding to the ip to delete
-    --variant, -v <version> the version of the pkgid to remove
+
+Options:
+    --variant, -v <version> the version of the pkgid to remove (default: dev)
 
 Use 'orbit help uninstall' to learn more about the command.
 ";
\ No newline at end of file
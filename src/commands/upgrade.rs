@@ -0,0 +1,387 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::archive::{self, Format};
+use crate::core::config::Config;
+use crate::core::context::Context;
+use crate::core::network::{self, Network};
+use crate::core::signature::{self, Method};
+use crate::core::version::Version;
+use crate::interface::cli::Cli;
+use crate::interface::arg::Flag;
+use crate::interface::errors::CliError;
+use crate::util::anyerror::{AnyError, Category, CategoryError, Fault};
+use crate::util::filesystem::get_exe_path;
+use crate::util::prompt;
+use crate::util::sha256::{self, Sha256Hash};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The release channel used when `upgrade.url` is not configured.
+pub const DEFAULT_CHANNEL: &str = "https://github.com/c-rus/orbit/releases";
+
+/// Name of the new binary while it is being checked next to the current one.
+const STAGED_EXE: &str = ".orbit-upgrade";
+
+#[derive(Debug, PartialEq, Default)]
+pub struct Upgrade {
+    check: bool,
+}
+
+impl FromCli for Upgrade {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        Ok(Upgrade {
+            check: cli.check_flag(Flag::new("check"))?,
+        })
+    }
+}
+
+impl Command for Upgrade {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        if c.offline == true {
+            return Err(AnyError(String::from("cannot check for a new version of orbit while offline")))?
+        }
+        let channel = Channel::from_config(c.get_config())?;
+        let info = self.run(&channel, c.force)?;
+        println!("info: {}", info);
+        Ok(())
+    }
+}
+
+/// Where releases of orbit are downloaded from and how they are verified.
+#[derive(Debug, PartialEq)]
+struct Channel {
+    url: String,
+    proxy: Option<String>,
+    method: Option<Method>,
+    key: Option<String>,
+}
+
+impl Channel {
+    /// Reads the `[upgrade]` table of the configuration.
+    fn from_config(config: &Config) -> Result<Self, Fault> {
+        let method = match config.get_as_str("upgrade", "method")? {
+            Some(m) => Some(Method::from_str(m)?),
+            None => None,
+        };
+        let key = match config.get_as_str("upgrade", "key")? {
+            // resolve a relative key file against the configuration that defines it
+            Some(k) => {
+                let path = config.get_root().join(k);
+                Some(if path.exists() { path.display().to_string() } else { k.to_string() })
+            },
+            None => None,
+        };
+        if method == Some(Method::Minisign) && key.is_none() {
+            return Err(AnyError(String::from("upgrade method 'minisign' requires a 'key'")))?
+        }
+        Ok(Self {
+            url: config.get_as_str("upgrade", "url")?.unwrap_or(DEFAULT_CHANNEL).trim_end_matches('/').to_string(),
+            proxy: config.get_as_str("upgrade", "proxy")?.map(String::from),
+            method: method,
+            key: key,
+        })
+    }
+
+    /// Determines the network settings to reach the channel with, which use the
+    /// channel's proxy when one is configured.
+    fn network(&self) -> Network {
+        match &self.proxy {
+            Some(proxy) => network::get().with_proxy(proxy),
+            None => network::get(),
+        }
+    }
+}
+
+impl Upgrade {
+    /// Checks the release channel for a newer version of orbit to install.
+    ///
+    /// Steps it follows:
+    /// 1. Removes any old version existing in executables' current folder
+    /// 2. Requests the latest version from the channel
+    /// 3. If new version, download the checksum file (and its signature when a
+    /// method is configured) and search for a compatible platform
+    /// 4. Download compatible platform zip file and verify checksum matches
+    /// 5. Unzip the file and check the new executable runs as the latest version
+    /// 6. Swap the executables, renaming the old executable as `orbit-<version>`
+    #[tokio::main]
+    async fn run(&self, channel: &Channel, force: bool) -> Result<String, Fault> {
+        let exe_path = get_exe_path()?;
+        let exe_dir = exe_path.parent().unwrap().to_path_buf();
+        remove_stale_binaries(&exe_dir)?;
+
+        let network = channel.network();
+        let client = network.client()?;
+        println!("info: checking for latest orbit binary...");
+        let latest = fetch_latest(&network, &client, &channel.url).await?;
+        // our current version is guaranteed to be valid
+        let current = Version::from_str(env!("CARGO_PKG_VERSION")).unwrap();
+        if latest <= current {
+            return Ok(format!("the latest version is already installed ({})", &latest))
+        }
+        if self.check == true {
+            return Ok(format!("a new version is available ({}); run 'orbit upgrade' to install it", latest))
+        }
+        // await user input
+        if force == false {
+            if prompt::prompt(&format!("info: a new version is available ({}), would you like to upgrade", latest))? == false {
+                return Ok(String::from("upgrade cancelled"))
+            }
+        }
+
+        // download the list of checksums
+        println!("info: downloading update...");
+        let sum_url = format!("{0}/download/{1}/orbit-{1}-checksums.txt", &channel.url, &latest);
+        let checksums = download(&network, &client, &sum_url).await?;
+        if let Some(method) = channel.method {
+            let sig_url = format!("{}.sig", sum_url);
+            let signature = match download(&network, &client, &sig_url).await {
+                Ok(s) => s,
+                Err(_) => return Err(CategoryError(Category::Checksum, UpgradeError::MissingSignature(sig_url).to_string()))?,
+            };
+            let dir = tempfile::tempdir()?;
+            std::fs::write(dir.path().join("checksums.txt"), &checksums)?;
+            std::fs::write(dir.path().join("checksums.txt.sig"), &signature)?;
+            if let Err(e) = signature::check_detached(method, channel.key.as_deref(), &dir.path().join("checksums.txt"), &dir.path().join("checksums.txt.sig")) {
+                return Err(CategoryError(Category::Checksum, format!("the release's checksums are not signed by a trusted key: {}", e)))?
+            }
+            println!("info: verified signature");
+        }
+
+        // search the checksums to check if the desired pkg is available for download
+        let target = target_triple();
+        let pkg = format!("orbit-{}-{}.zip", &latest, &target);
+        let cert = match find_checksum(&String::from_utf8_lossy(&checksums), &pkg) {
+            Some(c) => c,
+            None => return Err(UpgradeError::UnsupportedTarget(target))?,
+        };
+
+        // download the zip pkg file and verify the checksums match
+        let pkg_url = format!("{}/download/{}/{}", &channel.url, &latest, &pkg);
+        let body_bytes = download(&network, &client, &pkg_url).await?;
+        let sum = sha256::compute_sha256(&body_bytes);
+        match sum == cert {
+            true => println!("info: verified download"),
+            false => return Err(CategoryError(Category::Checksum, UpgradeError::BadChecksum(sum, cert).to_string()))?,
+        };
+
+        println!("info: installing update...");
+        let temp_dir = tempfile::tempdir()?;
+        archive::extract(&body_bytes, Format::Zip, temp_dir.path())?;
+        let exe_ext = if std::env::consts::EXE_EXTENSION.is_empty() == true { "" } else { ".exe" };
+        let new_exe = temp_dir.path().join(&format!("orbit-{}-{}/bin/orbit{}", &latest, &target, &exe_ext));
+        if new_exe.exists() == false {
+            return Err(UpgradeError::MissingExe)?
+        }
+
+        // stage the new binary on the same filesystem so it can be swapped in by renaming
+        let staged = exe_dir.join(format!("{}{}", STAGED_EXE, exe_ext));
+        std::fs::copy(&new_exe, &staged)?;
+        if let Err(e) = check_binary(&staged, &latest) {
+            std::fs::remove_file(&staged)?;
+            return Err(e)
+        }
+        swap(&staged, &exe_path, &exe_dir.join(format!("orbit-{}{}", current, exe_ext)))?;
+        Ok(format!("successfully upgraded orbit to version {}", &latest))
+    }
+}
+
+/// Returns current machine's target as `<arch>-<os>`.
+fn target_triple() -> String {
+    format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS)
+}
+
+/// Removes the binaries of previous versions kept next to the executable by
+/// earlier upgrades, along with a binary left staged by an interrupted one.
+fn remove_stale_binaries(dir: &Path) -> Result<(), Fault> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(n) => n.strip_suffix(".exe").unwrap_or(n),
+            None => continue,
+        };
+        let stale = match name.strip_prefix("orbit-") {
+            Some(v) => Version::from_str(v).is_ok(),
+            None => name == STAGED_EXE,
+        };
+        if stale == true && path.is_file() {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Requests the latest version released on the channel at `url`.
+async fn fetch_latest(network: &Network, client: &reqwest::Client, url: &str) -> Result<Version, Fault> {
+    let latest_url = format!("{}/latest", url);
+    let transfer = network.download(client, &latest_url).await?;
+    if transfer.status.is_success() == false {
+        return Err(UpgradeError::FailedConnection(latest_url, transfer.status))?
+    }
+    match parse_latest(&transfer.url, &String::from_utf8_lossy(&transfer.body)) {
+        Some(v) => Ok(v),
+        None => Err(UpgradeError::NoReleasesFound(latest_url))?,
+    }
+}
+
+/// Reads the version from the response to a request for the latest release.
+///
+/// GitHub redirects the request to the release's tag, such as `.../releases/tag/1.0.0`,
+/// while other channels answer with the version as the body of the response.
+fn parse_latest(location: &str, body: &str) -> Option<Version> {
+    let text = match location.rsplit_once("/tag/") {
+        Some((_, tag)) => tag.trim_end_matches('/'),
+        None => body.trim(),
+    };
+    Version::from_str(text.strip_prefix('v').unwrap_or(text)).ok()
+}
+
+/// Requests the bytes of the file at `url`.
+async fn download(network: &Network, client: &reqwest::Client, url: &str) -> Result<Vec<u8>, Fault> {
+    let transfer = network.download(client, url).await?;
+    if transfer.status.is_success() == false {
+        return Err(UpgradeError::FailedDownload(url.to_string(), transfer.status))?
+    }
+    Ok(transfer.body)
+}
+
+/// Finds the checksum of the file `pkg` in a list of `<checksum> <file>` lines.
+fn find_checksum(checksums: &str, pkg: &str) -> Option<Sha256Hash> {
+    checksums.lines().find_map(|line| match line.trim().split_once(' ') {
+        Some((sum, file)) if file.trim() == pkg => Sha256Hash::from_str(sum).ok(),
+        _ => None,
+    })
+}
+
+/// Runs the binary at `exe` to check it reports the version `expected`.
+fn check_binary(exe: &Path, expected: &Version) -> Result<(), Fault> {
+    let output = match std::process::Command::new(exe).arg("--version").output() {
+        Ok(o) => o,
+        Err(e) => return Err(UpgradeError::BrokenExe(e.to_string()))?,
+    };
+    let reported = String::from_utf8_lossy(&output.stdout);
+    match output.status.success() && reported.trim() == format!("orbit {}", expected) {
+        true => Ok(()),
+        false => Err(UpgradeError::BrokenExe(format!("expected 'orbit {}' but it reported '{}'", expected, reported.trim())))?,
+    }
+}
+
+/// Replaces the executable at `exe` with the `staged` binary, keeping the
+/// current executable at `backup`.
+///
+/// Each step is a rename within the executable's directory. If the staged binary
+/// cannot be moved into place, the current executable is restored from `backup`.
+fn swap(staged: &Path, exe: &Path, backup: &Path) -> Result<(), Fault> {
+    std::fs::rename(exe, backup)?;
+    if let Err(e) = std::fs::rename(staged, exe) {
+        // roll back to the current executable
+        std::fs::rename(backup, exe)?;
+        return Err(UpgradeError::FailedSwap(PathBuf::from(exe), e.to_string()))?
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum UpgradeError {
+    UnsupportedTarget(String),
+    FailedConnection(String, reqwest::StatusCode),
+    FailedDownload(String, reqwest::StatusCode),
+    NoReleasesFound(String),
+    MissingSignature(String),
+    BadChecksum(Sha256Hash, Sha256Hash),
+    MissingExe,
+    BrokenExe(String),
+    FailedSwap(PathBuf, String),
+}
+
+impl std::error::Error for UpgradeError {}
+
+impl std::fmt::Display for UpgradeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::MissingExe => write!(f, "failed to find the binary in the downloaded package"),
+            Self::BrokenExe(e) => write!(f, "the downloaded binary failed to run: {}\n\nThe current version was left in place", e),
+            Self::FailedSwap(exe, e) => write!(f, "failed to move the new binary to {:?}: {}\n\nThe current version was restored", exe, e),
+            Self::BadChecksum(computed, ideal) => write!(f, "checksums did not match, please try again\n\ncomputed: {}\nexpected: {}", computed, ideal),
+            Self::MissingSignature(url) => write!(f, "failed to download the signature of the release's checksums\n\nurl: {}\n\nThe 'upgrade.method' configuration requires signed releases", url),
+            Self::FailedConnection(url, status) => write!(f, "connection failed\n\nurl: {}\nstatus: {}", url, status),
+            Self::FailedDownload(url, status) => write!(f, "download failed\n\nurl: {}\nstatus: {}", url, status),
+            Self::NoReleasesFound(url) => write!(f, "failed to detect the latest release\n\nurl: {}", url),
+            Self::UnsupportedTarget(t) => write!(f, "no pre-compiled binaries exist for the current target {}", t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn latest_from_response() {
+        let v = |s: &str| Some(Version::from_str(s).unwrap());
+        // github redirects to the release's tag
+        assert_eq!(parse_latest("https://github.com/c-rus/orbit/releases/tag/1.2.0", "<html>"), v("1.2.0"));
+        assert_eq!(parse_latest("https://github.com/c-rus/orbit/releases/tag/v1.2.0/", "<html>"), v("1.2.0"));
+        // other channels answer with the version
+        assert_eq!(parse_latest("https://ks-tech.com/orbit/latest", "0.9.1\n"), v("0.9.1"));
+        assert_eq!(parse_latest("https://ks-tech.com/orbit/latest", "not a version"), None);
+    }
+
+    #[test]
+    fn checksum_of_package() {
+        let sum = "a".repeat(64);
+        let list = format!("{0} orbit-1.0.0-x86_64-linux.zip\n{1} orbit-1.0.0-x86_64-windows.zip\n", sum, "b".repeat(64));
+        assert_eq!(find_checksum(&list, "orbit-1.0.0-x86_64-linux.zip"), Some(Sha256Hash::from_str(&sum).unwrap()));
+        assert_eq!(find_checksum(&list, "orbit-1.0.0-aarch64-macos.zip"), None);
+        // malformed lines are skipped
+        assert_eq!(find_checksum("garbage\n", "garbage"), None);
+    }
+
+    #[test]
+    fn swap_binaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("orbit");
+        let staged = dir.path().join(STAGED_EXE);
+        let backup = dir.path().join("orbit-0.1.0");
+        std::fs::write(&exe, "old").unwrap();
+        std::fs::write(&staged, "new").unwrap();
+        swap(&staged, &exe, &backup).unwrap();
+        assert_eq!(std::fs::read_to_string(&exe).unwrap(), "new");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "old");
+        assert_eq!(staged.exists(), false);
+
+        // the current executable is restored when the new one cannot be moved into place
+        std::fs::remove_file(&backup).unwrap();
+        assert!(swap(&staged, &exe, &backup).is_err());
+        assert_eq!(std::fs::read_to_string(&exe).unwrap(), "new");
+        assert_eq!(backup.exists(), false);
+    }
+
+    #[test]
+    fn stale_binaries() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["orbit", "orbit-0.1.0", "orbit-0.2.0.exe", STAGED_EXE, "orbit-notes.txt"] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+        remove_stale_binaries(dir.path()).unwrap();
+        let mut left: Vec<String> = std::fs::read_dir(dir.path()).unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(left, vec!["orbit", "orbit-notes.txt"]);
+    }
+}
+
+const HELP: &str = "\
+Update orbit to the latest release.
+
+Usage:
+    orbit upgrade [options]
+
+Options:
+    --check             report if a new version is available without installing it
+
+Use 'orbit help upgrade' to learn more about the command.
+";
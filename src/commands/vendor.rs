@@ -0,0 +1,123 @@
+use crate::Command;
+use crate::FromCli;
+use crate::core::catalog::Catalog;
+use crate::core::manifest::{IpManifest, VENDOR_DIR};
+use crate::core::version::AnyVersion;
+use crate::interface::cli::Cli;
+use crate::interface::arg::{Flag, Optional};
+use crate::interface::errors::CliError;
+use crate::core::context::Context;
+use crate::util::anyerror::{AnyError, Category, CategoryError, Fault};
+use crate::util::filesystem;
+use crate::util::progress::ProgressBar;
+use super::plan::Plan;
+
+#[derive(Debug, PartialEq)]
+pub struct Vendor {
+    disable_ssh: bool,
+    jobs: usize,
+}
+
+impl FromCli for Vendor {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self,  CliError<'c>> {
+        cli.set_help(HELP);
+        let command = Ok(Vendor {
+            disable_ssh: cli.check_flag(Flag::new("disable-ssh"))?,
+            jobs: cli.check_option_default(Optional::new("jobs").value("n").default("1"))?,
+        });
+        command
+    }
+}
+
+impl Command for Vendor {
+    type Err = Fault;
+
+    fn exec(&self, c: &Context) -> Result<(), Self::Err> {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+
+        let target = IpManifest::from_path(c.get_ip_path().unwrap())?;
+        // only vendor what a build would currently use
+        if !target.can_use_lock() {
+            return Err(AnyError(format!("lock file for ip '{}' is out of date\n\nTry `orbit plan --lock-only` to update the lock file", target.get_pkgid())))?
+        }
+        let lock = target.into_lockfile()?;
+
+        // gather the catalog
+        let catalog = Catalog::new()
+            .store(c.get_store_path())
+            .development(c.get_development_paths())?
+            .installations(c.get_cache_path())?
+            .available(c.get_vendors())?;
+
+        // the lockfile's own ip has no checksum
+        let entries: Vec<_> = lock.inner()
+            .iter()
+            .filter(|e| e.get_name() != target.get_pkgid() && e.get_sum().is_some())
+            .collect();
+
+        // install any entries missing from the cache
+        let missing: Vec<_> = entries.iter()
+            .filter(|e| !c.get_cache_path().join(e.to_cache_slot().as_ref()).exists())
+            .map(|e| (*e, AnyVersion::Specific(e.get_version().to_partial_version())))
+            .collect();
+        Plan::install_from_lock_entries(&missing, &catalog, self.disable_ssh, self.jobs)?;
+
+        // installations can be referenced from the cache instead of copied
+        let link = match c.get_config().get_as_str("core", "checkout")? {
+            None | Some("copy") => false,
+            Some("link") => true,
+            Some(other) => return Err(AnyError(format!("invalid value '{}' for config entry 'core.checkout'; expecting 'copy' or 'link'", other)))?,
+        };
+
+        // stage the copies so an interrupted run leaves the previous vendor directory intact
+        let staging = tempfile::Builder::new()
+            .prefix(".orbit-vendor-")
+            .tempdir_in(target.get_root())?;
+        let staged_dir = staging.path().join(VENDOR_DIR);
+        std::fs::create_dir_all(&staged_dir)?;
+
+        let mut linked = 0;
+        let mut bar = ProgressBar::new("vendoring", entries.len());
+        for entry in &entries {
+            let slot = entry.to_cache_slot();
+            let installation = c.get_cache_path().join(slot.as_ref());
+            if !installation.exists() {
+                return Err(CategoryError(Category::Resolution, format!("ip '{}' v{} is missing from the cache", entry.get_name(), entry.get_version())))?
+            }
+            let to = staged_dir.join(slot.as_ref());
+            match link {
+                true => if filesystem::link(&installation, &to, true)? { linked += 1 },
+                false => filesystem::copy(&installation, &to, true)?,
+            }
+            bar.inc(&format!("{} v{}", entry.get_name(), entry.get_version()));
+        }
+        bar.finish();
+
+        // swap in the new vendor directory
+        let vendor_dir = target.get_root().join(VENDOR_DIR);
+        if vendor_dir.exists() {
+            std::fs::remove_dir_all(&vendor_dir)?;
+        }
+        std::fs::rename(&staged_dir, &vendor_dir)?;
+
+        println!("info: vendored {} dependencies into {}", entries.len(), vendor_dir.display());
+        if link && linked < entries.len() {
+            println!("info: copied {} dependencies that could not be linked from the cache", entries.len() - linked);
+        }
+        Ok(())
+    }
+}
+
+const HELP: &str = "\
+Copy the locked dependencies into the current ip.
+
+Usage:
+    orbit vendor [options]
+
+Options:
+    --disable-ssh   convert SSH repositories to HTTPS for dependencies
+    --jobs <n>      install up to n missing dependencies at once (default: 1)
+
+Use 'orbit help vendor' to learn more about the command.
+";
\ No newline at end of file
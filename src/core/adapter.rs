@@ -0,0 +1,160 @@
+//! Adapters for driving existing hdl test frameworks with Orbit's planned file list.
+
+use std::path::Path;
+use std::str::FromStr;
+use crate::util::anyerror::AnyError;
+
+/// A test framework that can receive the ordered list of hdl files from a blueprint.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Adapter {
+    /// VUnit: sources are added in `run.py` with `add_source_files_from_csv`
+    VUnit,
+    /// OSVVM: sources are added in a `.pro` script with `include`
+    Osvvm,
+}
+
+/// Selects an adapter explicitly or by the conventions found in an ip.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AdapterMode {
+    Auto,
+    Using(Adapter),
+}
+
+impl FromStr for AdapterMode {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "auto" => Ok(Self::Auto),
+            "vunit" => Ok(Self::Using(Adapter::VUnit)),
+            "osvvm" => Ok(Self::Using(Adapter::Osvvm)),
+            _ => Err(AnyError(format!("unknown adapter '{}'; expecting 'vunit', 'osvvm', or 'auto'", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for Adapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::VUnit => write!(f, "vunit"),
+            Self::Osvvm => write!(f, "osvvm"),
+        }
+    }
+}
+
+impl Adapter {
+    /// Recognizes the test framework used by the ip at `root`.
+    ///
+    /// A VUnit project has a `run.py` script that imports vunit. An OSVVM project has
+    /// at least one `.pro` script.
+    pub fn detect(root: &Path) -> Option<Self> {
+        if let Ok(script) = std::fs::read_to_string(root.join(VUNIT_RUN_SCRIPT)) {
+            if script.to_lowercase().contains("vunit") {
+                return Some(Self::VUnit)
+            }
+        }
+        let has_pro = std::fs::read_dir(root).ok()?
+            .filter_map(|e| e.ok())
+            .any(|e| e.path().is_file() && e.path().extension().map(|x| x == "pro").unwrap_or(false));
+        match has_pro {
+            true => Some(Self::Osvvm),
+            false => None,
+        }
+    }
+
+    /// The filename of the exported file list.
+    pub fn export_file(&self) -> &str {
+        match self {
+            Self::VUnit => "vunit.csv",
+            Self::Osvvm => "orbit.pro",
+        }
+    }
+
+    /// Formats the ordered hdl `rules` (library, filepath) from a blueprint into the framework's format.
+    ///
+    /// The `work` library is renamed to `work_lib` because frameworks compile every
+    /// file into a named library.
+    pub fn export(&self, rules: &[(String, String)], work_lib: &str) -> String {
+        let rename = |lib: &str| if lib.eq_ignore_ascii_case("work") { work_lib.to_string() } else { lib.to_string() };
+        match self {
+            Self::VUnit => rules.iter().fold(String::new(), |acc, (lib, file)| {
+                acc + &format!("{},{}\n", rename(lib), file)
+            }),
+            Self::Osvvm => {
+                let mut result = String::from("# This file is automatically generated by Orbit.\n");
+                let mut current: Option<String> = None;
+                for (lib, file) in rules {
+                    let lib = rename(lib);
+                    // only switch libraries when the next file belongs to a different library
+                    if current.as_ref() != Some(&lib) {
+                        result.push_str(&format!("library {}\n", lib));
+                        current = Some(lib);
+                    }
+                    result.push_str(&format!("analyze {{{}}}\n", file));
+                }
+                result
+            }
+        }
+    }
+}
+
+/// Collects the ordered (library, filepath) pairs of the hdl filesets in the `blueprint` data.
+pub fn read_hdl_rules(blueprint: &str) -> Vec<(String, String)> {
+    blueprint.lines()
+        .filter_map(|line| {
+            // the optional fourth column is the file's vhdl standard
+            let mut parts = line.split('\t');
+            match (parts.next()?, parts.next()?, parts.next()?) {
+                ("VHDL-RTL", lib, file) | ("VHDL-SIM", lib, file) => Some((lib.to_string(), file.to_string())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+const VUNIT_RUN_SCRIPT: &str = "run.py";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BLUEPRINT: &str = "\
+PIN-PLAN\tboard\t/ip/board.pins
+VHDL-RTL\trary\t/cache/gates/and_gate.vhd
+VHDL-RTL\twork\t/ip/top.vhd\t2008
+VHDL-SIM\twork\t/ip/top_tb.vhd
+";
+
+    #[test]
+    fn export_vunit() {
+        let rules = read_hdl_rules(BLUEPRINT);
+        assert_eq!(Adapter::VUnit.export(&rules, "lab"), "\
+rary,/cache/gates/and_gate.vhd
+lab,/ip/top.vhd
+lab,/ip/top_tb.vhd
+");
+    }
+
+    #[test]
+    fn export_osvvm() {
+        let rules = read_hdl_rules(BLUEPRINT);
+        assert_eq!(Adapter::Osvvm.export(&rules, "lab"), "\
+# This file is automatically generated by Orbit.
+library rary
+analyze {/cache/gates/and_gate.vhd}
+library lab
+analyze {/ip/top.vhd}
+analyze {/ip/top_tb.vhd}
+");
+    }
+
+    #[test]
+    fn detect() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(Adapter::detect(dir.path()), None);
+        std::fs::write(dir.path().join("RunAllTests.pro"), "TestSuite lab\n").unwrap();
+        assert_eq!(Adapter::detect(dir.path()), Some(Adapter::Osvvm));
+        std::fs::write(dir.path().join("run.py"), "from vunit import VUnit\n").unwrap();
+        assert_eq!(Adapter::detect(dir.path()), Some(Adapter::VUnit));
+    }
+}
\ No newline at end of file
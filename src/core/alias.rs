@@ -0,0 +1,70 @@
+//! Aliases let manifests keep referring to an ip by its old pkgid after the ip's
+//! vendor, library, or name is renamed.
+//!
+//! Each alias is an entry of the `[[alias]]` array of tables in configuration.
+//! When resolving dependencies, a pkgid with an alias is looked up under the
+//! canonical pkgid it points to.
+
+use std::collections::HashMap;
+use crate::util::anyerror::{AnyError, Fault};
+use super::config::FromToml;
+use super::pkgid::PkgId;
+
+/// Maps each aliased pkgid to its canonical pkgid.
+pub type AliasMap = HashMap<PkgId, PkgId>;
+
+#[derive(Debug, PartialEq)]
+pub struct Alias {
+    from: PkgId,
+    to: PkgId,
+}
+
+impl FromToml for Alias {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        let alias = Self {
+            from: Self::require(table, "from")?,
+            to: Self::require(table, "to")?,
+        };
+        for pkgid in [&alias.from, &alias.to] {
+            if let Err(e) = pkgid.fully_qualified() {
+                return Err(AnyError(format!("pkgid '{}' is not fully qualified: {}", pkgid, e)))?
+            }
+        }
+        if alias.from == alias.to {
+            return Err(AnyError(format!("pkgid '{}' cannot be an alias of itself", alias.from)))?
+        }
+        Ok(alias)
+    }
+}
+
+impl Alias {
+    /// References the old pkgid that manifests may still use.
+    pub fn from(&self) -> &PkgId {
+        &self.from
+    }
+
+    /// References the canonical pkgid the alias resolves to.
+    pub fn to(&self) -> &PkgId {
+        &self.to
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_toml() {
+        let doc = "from = \"ks-tech.rary.gates\"\nto = \"kepler.rary.gates\"\n".parse::<toml_edit::Document>().unwrap();
+        let alias = Alias::from_toml(doc.as_table()).unwrap();
+        assert_eq!(alias.from().to_string(), "ks-tech.rary.gates");
+        assert_eq!(alias.to().to_string(), "kepler.rary.gates");
+
+        let doc = "from = \"gates\"\nto = \"kepler.rary.gates\"\n".parse::<toml_edit::Document>().unwrap();
+        assert!(Alias::from_toml(doc.as_table()).is_err());
+        let doc = "from = \"kepler.rary.gates\"\nto = \"kepler.rary.gates\"\n".parse::<toml_edit::Document>().unwrap();
+        assert!(Alias::from_toml(doc.as_table()).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Release archives as an alternative source to git repositories.
+//!
+//! An archive url points to a `.zip`, `.tar.gz`, `.tgz`, or `.tar` file, such as
+//! the release artifacts forges publish for each tag. An archive holds a single
+//! version of an ip without any git history.
+
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::progress::Spinner;
+use crate::util::url::Url;
+use super::manifest::IP_MANIFEST_FILE;
+use super::network;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    Zip,
+    TarGz,
+    Tar,
+}
+
+impl Format {
+    /// Determines the archive format from the file extension ending the `url`'s path.
+    ///
+    /// Returns `None` if the `url` does not point to an archive.
+    pub fn detect(url: &Url) -> Option<Self> {
+        let path = match url {
+            Url::Https(_) => url.without_subdirectory().to_string().to_lowercase(),
+            Url::Ssh(_) => return None,
+        };
+        let path = path.split('?').next().unwrap_or_default();
+        if path.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if path.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Checks if the `url` points to a release archive rather than a git repository.
+pub fn is_archive(url: &Url) -> bool {
+    Format::detect(url).is_some()
+}
+
+/// Downloads the archive at `url` and extracts it into `dest` while displaying a spinner.
+///
+/// Returns the root directory of the ip within `dest`. See `find_root` for details.
+pub fn fetch(url: &Url, dest: &Path) -> Result<PathBuf, Fault> {
+    let format = match Format::detect(url) {
+        Some(f) => f,
+        None => return Err(AnyError(format!("url '{}' is not a supported archive; expecting a .zip, .tar.gz, .tgz, or .tar file", url)))?,
+    };
+    let spinner = Spinner::new(&format!("downloading {} ...", url));
+    let result = download(&url.without_subdirectory().to_string());
+    spinner.finish(None);
+    extract(&result?, format, dest)?;
+    find_root(dest, url.get_subdirectory())
+}
+
+/// Requests the bytes of the file at `url`.
+#[tokio::main]
+async fn download(url: &str) -> Result<Vec<u8>, Fault> {
+    let network = network::get();
+    let transfer = network.download(&network.client()?, url).await?;
+    if !transfer.status.is_success() {
+        return Err(AnyError(format!("failed to download archive\n\nurl: {}\nstatus: {}", url, transfer.status)))?
+    }
+    Ok(transfer.body)
+}
+
+/// Unpacks the `bytes` of an archive in the given `format` into `dest`.
+///
+/// Entries that would be written outside of `dest` are rejected.
+pub fn extract(bytes: &[u8], format: Format, dest: &Path) -> Result<(), Fault> {
+    std::fs::create_dir_all(dest)?;
+    match format {
+        Format::Zip => zip::ZipArchive::new(Cursor::new(bytes))?.extract(dest)?,
+        Format::TarGz => untar(flate2::read::GzDecoder::new(bytes), dest)?,
+        Format::Tar => untar(bytes, dest)?,
+    }
+    Ok(())
+}
+
+fn untar<R: Read>(reader: R, dest: &Path) -> Result<(), Fault> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        // `unpack_in` skips entries with paths that escape the destination
+        entry?.unpack_in(dest)?;
+    }
+    Ok(())
+}
+
+/// Finds the root directory of the ip within an extracted archive at `dir`.
+///
+/// This also applies to any directory holding the files of a single version. Forges
+/// wrap the files of a release archive in a single top-level directory, which is
+/// entered when `dir` does not have a manifest itself. The ip is then within the
+/// `subdir` when one is given.
+pub fn find_root(dir: &Path, subdir: Option<&str>) -> Result<PathBuf, Fault> {
+    let mut root = dir.to_path_buf();
+    if !root.join(IP_MANIFEST_FILE).exists() {
+        let entries: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect();
+        if let [only] = entries.as_slice() {
+            if only.is_dir() {
+                root = only.to_path_buf();
+            }
+        }
+    }
+    if let Some(subdir) = subdir {
+        root = root.join(subdir);
+    }
+    match root.join(IP_MANIFEST_FILE).exists() {
+        true => Ok(root),
+        false => Err(AnyError(format!("no ip found in {:?}; missing {} file", root, IP_MANIFEST_FILE)))?,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn detect_format() {
+        let detect = |s: &str| Format::detect(&Url::from_str(s).unwrap());
+        assert_eq!(detect("https://github.com/c-rus/gates/archive/refs/tags/1.0.0.zip"), Some(Format::Zip));
+        assert_eq!(detect("https://github.com/c-rus/gates/archive/refs/tags/1.0.0.tar.gz"), Some(Format::TarGz));
+        assert_eq!(detect("https://example.com/gates-1.0.0.TGZ#ip/gates"), Some(Format::TarGz));
+        assert_eq!(detect("https://example.com/gates-1.0.0.tar?raw=true"), Some(Format::Tar));
+        assert_eq!(detect("https://github.com/c-rus/gates.git"), None);
+        assert_eq!(detect("git@github.com:c-rus/gates.zip"), None);
+    }
+
+    #[test]
+    fn extract_tar_gz() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default()));
+        for (path, contents) in [("gates-1.0.0/Orbit.toml", "[ip]\n"), ("gates-1.0.0/rtl/and_gate.vhd", "entity and_gate is end;\n")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, path, contents.as_bytes()).unwrap();
+        }
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        extract(&bytes, Format::TarGz, dir.path()).unwrap();
+        let root = find_root(dir.path(), None).unwrap();
+        assert_eq!(root, dir.path().join("gates-1.0.0"));
+        assert!(root.join("rtl/and_gate.vhd").exists());
+        assert!(find_root(dir.path(), Some("rtl")).is_err());
+    }
+}
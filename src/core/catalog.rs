@@ -1,13 +1,23 @@
 use std::{collections::HashMap, path::PathBuf};
 use crate::util::{anyerror::Fault, sha256::Sha256Hash};
+use crate::util::filesystem;
+use crate::util::profile;
+use serde_json::json;
+use crate::util::progress::Spinner;
 
-use super::{pkgid::{PkgId, PkgPart}, manifest::IpManifest, version::{Version, AnyVersion}, store::Store, vendor::VendorManifest};
+use super::{alias::AliasMap, signature::TrustMap, pkgid::{PkgId, PkgPart}, manifest::IpManifest, version::{Version, AnyVersion}, store::Store, vendor::VendorManifest, extgit::Transport};
 
 #[derive(Debug)]
 pub struct Catalog<'a> {
     inner: HashMap<PkgId, IpLevel>, 
     store: Option<Store<'a>>, 
     cache: Option<&'a PathBuf>,
+    transports: HashMap<PkgPart, Transport>,
+    vendors: Option<&'a HashMap<PkgPart, VendorManifest>>,
+    aliases: AliasMap,
+    trust: TrustMap,
+    ignore_locks: bool,
+    prefer_locks: bool,
 }
 
 #[derive(Debug, PartialEq)]
@@ -127,6 +137,32 @@ impl IpLevel {
         }
     }
 
+    /// References the installed ip matching the most compatible version `version`
+    /// that is not yanked.
+    /// 
+    /// A yanked version is still used when `is_locked` accepts it, so existing lock
+    /// files keep resolving to the versions they recorded.
+    pub fn get_resolvable(&self, version: &AnyVersion, is_locked: &dyn Fn(&Version) -> bool) -> Option<&IpManifest> {
+        match version {
+            AnyVersion::Dev => self.get_dev(),
+            _ => Self::get_target_version(version, self.installs.iter().filter(|ip| {
+                !self.is_yanked(ip.get_version()) || is_locked(ip.get_version())
+            })),
+        }
+    }
+
+    /// Checks if the vendor's registry marks `version` as yanked.
+    pub fn is_yanked(&self, version: &Version) -> bool {
+        self.available.iter().any(|ip| ip.get_version() == version && ip.is_yanked())
+    }
+
+    /// Returns the vendor's reason for deprecating `version`, if the registry marks it as deprecated.
+    pub fn get_deprecation(&self, version: &Version) -> Option<&str> {
+        self.available.iter()
+            .find(|ip| ip.get_version() == version)
+            .and_then(|ip| ip.get_deprecation())
+    }
+
     /// Tracks what level the `manifest` came from.
     pub fn get_state(&self, manifest: &IpManifest) -> IpState {
         if self.installs.iter().find(|f| f == &manifest).is_some() { 
@@ -149,10 +185,10 @@ impl IpLevel {
     /// Returns `None` if no compatible version was found.
     /// 
     /// Panics if a development version is entered as `target`.
-    fn get_target_version<'a>(target: &AnyVersion, space: &'a Vec<IpManifest>) -> Option<&'a IpManifest> {
+    fn get_target_version<'a>(target: &AnyVersion, space: impl IntoIterator<Item = &'a IpManifest>) -> Option<&'a IpManifest> {
         // find the specified version for the given ip
         let mut latest_version: Option<&IpManifest> = None;
-        space.iter()
+        space.into_iter()
             .filter(|ip| match &target {
                 AnyVersion::Specific(v) => crate::core::version::is_compatible(v, ip.get_version()),
                 AnyVersion::Latest => true,
@@ -169,6 +205,39 @@ impl IpLevel {
     pub fn is_developing(&self) -> bool {
         self.dev.is_some()
     }
+
+    /// Serializes the ip `pkgid` with the versions found in each state.
+    /// 
+    /// The fields are kept stable for tools reading `orbit search --format json`.
+    /// Versions within a state are listed from oldest to newest.
+    pub fn to_json(&self, pkgid: &PkgId) -> serde_json::Value {
+        let path = |m: &IpManifest| filesystem::normalize_path(m.get_root()).display().to_string();
+        fn sorted(space: &[IpManifest]) -> Vec<&IpManifest> {
+            let mut space: Vec<&IpManifest> = space.iter().collect();
+            space.sort_by(|a, b| a.get_version().cmp(b.get_version()));
+            space
+        }
+        json!({
+            "pkgid": pkgid.to_string(),
+            "vendor": pkgid.get_vendor().as_ref().map(|v| v.to_string()),
+            "library": pkgid.get_library().as_ref().map(|l| l.to_string()),
+            "name": pkgid.get_name().to_string(),
+            "source": self.try_repository().map(|u| u.to_string()),
+            "develop": self.get_dev().map(|m| json!({
+                "version": m.get_version().to_string(),
+                "path": path(m),
+            })),
+            "installed": sorted(&self.installs).into_iter().map(|m| json!({
+                "version": m.get_version().to_string(),
+                "path": path(m),
+            })).collect::<Vec<_>>(),
+            "available": sorted(&self.available).into_iter().map(|m| json!({
+                "version": m.get_version().to_string(),
+                "yanked": m.is_yanked(),
+                "deprecated": m.get_deprecation(),
+            })).collect::<Vec<_>>(),
+        })
+    }
 }
 
 impl<'a> Catalog<'a> {
@@ -177,6 +246,12 @@ impl<'a> Catalog<'a> {
             inner: HashMap::new(), 
             store: None, 
             cache: None,
+            transports: HashMap::new(),
+            vendors: None,
+            aliases: HashMap::new(),
+            trust: HashMap::new(),
+            ignore_locks: false,
+            prefer_locks: false,
         }
     }
 
@@ -186,9 +261,67 @@ impl<'a> Catalog<'a> {
         self
     }
 
-    /// Searches the `path` for IP under development.
-    pub fn development(self, path: &PathBuf) -> Result<Self, Fault> {
-        self.detect(path, &IpLevel::add_dev, false)
+    /// Sets the aliases to follow when looking up ip.
+    pub fn aliases(mut self, aliases: &AliasMap) -> Self {
+        self.aliases = aliases.clone();
+        self
+    }
+
+    pub fn get_aliases(&self) -> &AliasMap {
+        &self.aliases
+    }
+
+    /// Sets the trust policies to verify installed ip against.
+    pub fn trust(mut self, trust: &TrustMap) -> Self {
+        self.trust = trust.clone();
+        self
+    }
+
+    pub fn get_trust(&self) -> &TrustMap {
+        &self.trust
+    }
+
+    /// Sets if resolution disregards the versions recorded in lock files, which
+    /// otherwise keep resolving after being yanked.
+    pub fn ignore_locks(mut self, ignore: bool) -> Self {
+        self.ignore_locks = ignore;
+        self
+    }
+
+    pub fn is_ignoring_locks(&self) -> bool {
+        self.ignore_locks
+    }
+
+    /// Sets if resolution selects the version recorded in the lock file whenever it
+    /// still satisfies the requirement, even when the lock file is out of sync with the manifest.
+    pub fn prefer_locks(mut self, prefer: bool) -> Self {
+        self.prefer_locks = prefer;
+        self
+    }
+
+    pub fn is_preferring_locks(&self) -> bool {
+        self.prefer_locks
+    }
+
+    /// Determines the pkgid to look up the `target` ip under.
+    /// 
+    /// An aliased `target` is looked up under its canonical pkgid once the catalog
+    /// knows of it, so an ip only released before its rename is still found by its old pkgid.
+    pub fn resolve_alias<'b>(&'b self, target: &'b PkgId) -> &'b PkgId {
+        match self.aliases.get(target) {
+            Some(canonical) if self.inner.contains_key(canonical) => canonical,
+            _ => target,
+        }
+    }
+
+    /// Searches each of the `paths` in order for IP under development.
+    pub fn development(self, paths: &[PathBuf]) -> Result<Self, Fault> {
+        let mut catalog = self;
+        for path in paths {
+            // an ip found on an earlier path hides the same ip on later paths
+            catalog = catalog.detect(path, &|lvl: &mut IpLevel, ip| if lvl.get_dev().is_none() { lvl.add_dev(ip) }, false)?;
+        }
+        Ok(catalog)
     }
 
     /// Uses the cache slot name to check if the directory exists.
@@ -204,14 +337,33 @@ impl<'a> Catalog<'a> {
     }
 
     /// Searches the `path` for IP available.
-    pub fn available(self, vendors: &HashMap<PkgPart, VendorManifest>) -> Result<Self, Fault> {
+    pub fn available(self, vendors: &'a HashMap<PkgPart, VendorManifest>) -> Result<Self, Fault> {
         let mut catalog = self;
-        for (_, v) in vendors {
+        catalog.vendors = Some(vendors);
+        for (name, v) in vendors {
+            catalog.transports.insert(name.clone(), v.get_transport().clone().vendor(name.clone()));
             catalog = catalog.detect(&v.get_root(), &IpLevel::add_available, true)?;
         }
         Ok(catalog)
     }
 
+    /// Returns the transport settings of the vendor for `target`.
+    /// 
+    /// Uses the default settings if the ip has no vendor or its vendor is unknown.
+    pub fn get_transport(&self, target: &PkgId) -> Transport {
+        target.get_vendor()
+            .as_ref()
+            .and_then(|v| self.transports.get(v))
+            .cloned()
+            .unwrap_or(Transport::new())
+    }
+
+    /// References the vendor of `target`, if it is known.
+    pub fn get_vendor(&self, target: &PkgId) -> Option<&VendorManifest> {
+        let name = target.get_vendor().as_ref()?;
+        self.vendors?.get(name)
+    }
+
     pub fn inner(&self) -> &HashMap<PkgId, IpLevel> {
         &self.inner
     }
@@ -235,10 +387,14 @@ impl<'a> Catalog<'a> {
     /// 
     /// This function is generic enough to be used to catch ip at all 3 levels: dev, install, and available.
     fn detect(mut self, path: &PathBuf, add: &dyn Fn(&mut IpLevel, IpManifest) -> (), is_pointers: bool) -> Result<Self, Fault> {
-        match is_pointers {
+        let _phase = profile::Phase::start("catalog scan");
+        let spinner = Spinner::transient(&format!("scanning {} ...", path.display()));
+        let manifests = match is_pointers {
             false => crate::core::manifest::IpManifest::detect_all(path),
             true => crate::core::manifest::IpManifest::detect_available(path)
-        }?.into_iter()
+        }?;
+        spinner.finish(None);
+        manifests.into_iter()
             .for_each(|ip| {
                 match self.inner.get_mut(&ip.get_pkgid()) {
                     Some(lvl) => add(lvl, ip),
@@ -104,10 +104,12 @@ impl Config {
     /// Updates the configuration to see if there is a local project-based configuration
     /// to track.
     /// 
-    /// Keeps `self.local` set to `None` if the file `file` does not exist.
+    /// The local configuration's `include` entry is also read, so a project can share
+    /// configuration files kept within its repository. Keeps `self.local` set to `None`
+    /// if the file `file` does not exist.
     pub fn local(mut self, file: &PathBuf) -> Result<Self, Fault> {
         if file.exists() == true {
-            self.local = Some(Box::new(Self::from_path(&file)?));
+            self.local = Some(Box::new(Self::from_path(&file)?.include()?));
         }
         Ok(self)
     }
@@ -171,6 +173,15 @@ impl Config {
         Self::append_list(tbl, INDEX_KEY, item);
     }
 
+    /// Adds a new value to the `core.paths` entry.
+    pub fn append_dev_path(&mut self, item: &str) {
+        if !self.document.contains_key(CORE_KEY) {
+            self.document.insert(CORE_KEY, Item::Table(Table::new()));
+        }
+        let tbl = self.document.get_mut(CORE_KEY).unwrap().as_table_mut().unwrap();
+        Self::append_list(tbl, PATHS_KEY, item);
+    }
+
     /// Adds a new value to the `include` entry.
     /// 
     /// Automatically creates the new key if it does not exist.
@@ -178,6 +189,27 @@ impl Config {
         Self::append_list(&mut self.document, INCLUDE_KEY, item);
     } 
 
+    /// Adds a new `[[plugin]]` entry defined by `alias` that calls `command`
+    /// with `args`.
+    /// 
+    /// Errors if the existing `plugin` entry is not an array of tables.
+    pub fn append_plugin(&mut self, alias: &str, command: &str, args: &[&str], summary: &str) -> Result<(), Fault> {
+        if self.document.contains_key(PLUGIN_KEY) == false {
+            self.document.insert(PLUGIN_KEY, Item::ArrayOfTables(ArrayOfTables::new()));
+        }
+        let plugins = match self.document.get_mut(PLUGIN_KEY).unwrap().as_array_of_tables_mut() {
+            Some(arr) => arr,
+            None => return Err(ConfigError::BadItem(format!("{}", normalize_path(self.get_root().join(CONFIG_FILE)).display()), String::from("array of tables"), PLUGIN_KEY.to_owned()))?,
+        };
+        let mut table = Table::new();
+        table.insert("alias", toml_edit::value(alias));
+        table.insert("command", toml_edit::value(command));
+        table.insert("args", toml_edit::value(args.iter().copied().collect::<Array>()));
+        table.insert("summary", toml_edit::value(summary));
+        plugins.push(table);
+        Ok(())
+    }
+
     /// Sets a value for the given entry in the toml document.
     /// 
     /// Creates parent table and/or key if does not exist.
@@ -261,6 +293,17 @@ impl Config {
         })
     }
 
+    /// Takes the last integer value.
+    pub fn get_as_int(&self, table: &str, key: &str) -> Result<Option<i64>, Fault> {
+        Ok(self.collect_as_item(Some(table), key, &Item::is_integer, "integer")?.last().map(|f| f.0.as_integer().unwrap()))
+    }
+
+    /// Takes the last value as a path, resolving a relative path from the
+    /// configuration that defines it.
+    pub fn get_as_path(&self, table: &str, key: &str) -> Result<Option<PathBuf>, Fault> {
+        Ok(self.collect_as_item(Some(table), key, &Item::is_str, "string")?.last().map(|f| f.1.join(f.0.as_str().unwrap())))
+    }
+
     /// Tries to visit a value at `table.key`.
     /// 
     /// If `table` is `None`, it will assume its a global-level key/item.
@@ -284,19 +327,7 @@ impl Config {
         let mut values: Vec<(&Item, &PathBuf)> = Vec::new();
         // collect all included (3rd-party) configuration data
         for inc in &self.includes {
-            match inc.access(table, key) {
-                Some(item) => {
-                    // update the value as the list continues
-                    if eval(item) {
-                       values.push((item, inc.get_root()));
-                    } else {
-                        return Err(ConfigError::BadItem(format!("{}", normalize_path(self.get_root().join(CONFIG_FILE)).display()), 
-                            item_name.to_owned(), format!("{}{}", { if table.is_some() { table.unwrap().to_string() + "." } else { "".to_string() } }, 
-                            key.to_owned())))?
-                    }
-                }
-                None => (),
-            }
+            values.append(&mut inc.collect_as_item(table, key, eval, item_name)?);
         }
         // access on current configuration
         match self.access(table, key) {
@@ -312,21 +343,9 @@ impl Config {
             }
             None => (),
         }
-        // access on local configuration
+        // access on local configuration (along with its own included configurations)
         if let Some(cfg) = &self.local {
-            match cfg.access(table, key) {
-                Some(item) => {
-                    // update the value as the list continues
-                    if eval(item) {
-                       values.push((item, cfg.get_root()));
-                    } else {
-                        return Err(ConfigError::BadItem(format!("{}", normalize_path(self.get_root().join(CONFIG_FILE)).display()), 
-                            item_name.to_owned(), format!("{}{}", { if table.is_some() { table.unwrap().to_string() + "." } else { "".to_string() } }, 
-                            key.to_owned())))?
-                    }
-                }
-                None => (),
-            }
+            values.append(&mut cfg.collect_as_item(table, key, eval, item_name)?);
         }
         Ok(values)
     }
@@ -351,7 +370,12 @@ impl std::fmt::Display for ConfigError {
 }
 
 pub const CONFIG_FILE: &str = "config.toml";
+/// Directory within an ip that holds its local configuration.
+pub const LOCAL_CONFIG_DIR: &str = ".orbit";
 const INCLUDE_KEY: &str = "include";
+const CORE_KEY: &str = "core";
+const PATHS_KEY: &str = "paths";
+const PLUGIN_KEY: &str = "plugin";
 pub const VENDOR_KEY: &str = "vendor";
 pub const INDEX_KEY: &str = "index";
 
@@ -399,4 +423,43 @@ mod test {
             .unwrap().into_iter().map(|f| f.0.as_str().unwrap()).collect();
         assert_eq!(items, vec!["vim", "code"]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn local_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("home");
+        let local = dir.path().join("ip").join(LOCAL_CONFIG_DIR);
+        std::fs::create_dir_all(&home).unwrap();
+        std::fs::create_dir_all(&local).unwrap();
+        std::fs::write(home.join(CONFIG_FILE), "[core]\neditor = \"vim\"\nbuild-dir = \"out\"\nuser = \"kepler\"\n").unwrap();
+        std::fs::write(local.join("team.toml"), "[core]\neditor = \"code\"\nbuild-dir = \"build\"\n").unwrap();
+        std::fs::write(local.join(CONFIG_FILE), "include = [\"team.toml\"]\n\n[core]\nbuild-dir = \"target\"\n").unwrap();
+
+        let cfg = Config::from_path(&home.join(CONFIG_FILE)).unwrap()
+            .include().unwrap()
+            .local(&local.join(CONFIG_FILE)).unwrap();
+        // the local configuration's includes take precedence over the home configuration
+        assert_eq!(cfg.collect_as_str("core", "editor").unwrap(), vec!["vim", "code"]);
+        assert_eq!(cfg.get_as_str("core", "editor").unwrap(), Some("code"));
+        // the local configuration takes precedence over its includes
+        assert_eq!(cfg.get_as_str("core", "build-dir").unwrap(), Some("target"));
+        assert_eq!(cfg.get_as_str("core", "user").unwrap(), Some("kepler"));
+    }
+
+    #[test]
+    fn append_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join(CONFIG_FILE);
+        std::fs::write(&file, "[[plugin]]\nalias = \"ghdl\"\ncommand = \"python\"\n").unwrap();
+        let mut cfg = Config::from_path(&file).unwrap();
+        cfg.append_plugin("xsim", "python", &["plugins/xsim.py"], "xsim flow").unwrap();
+        cfg.write().unwrap();
+
+        let cfg = Config::from_path(&file).unwrap();
+        let plugs = cfg.collect_as_array_of_tables("plugin").unwrap();
+        assert_eq!(plugs[0].0.len(), 2);
+        let plug = plugs[0].0.get(1).unwrap();
+        assert_eq!(plug.get("alias").unwrap().as_str(), Some("xsim"));
+        assert_eq!(plug.get("args").unwrap().as_array().unwrap().get(0).unwrap().as_str(), Some("plugins/xsim.py"));
+    }
+}
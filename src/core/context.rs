@@ -11,7 +11,13 @@ use crate::core::template::Template;
 use crate::util::environment::ORBIT_WIN_LITERAL_CMD;
 use crate::util::filesystem;
 use crate::util::filesystem::normalize_path;
-use super::config::CONFIG_FILE;
+use super::alias::{Alias, AliasMap};
+use super::mirror::Mirror;
+use super::network::Network;
+use super::signature::{Trust, TrustMap};
+use super::config::{CONFIG_FILE, LOCAL_CONFIG_DIR};
+use super::layout::Layout;
+use super::manifest::{self, Manifest, IP_MANIFEST_FILE};
 use super::pkgid::PkgPart;
 use super::vendor::VendorManifest;
 
@@ -22,17 +28,36 @@ pub struct Context {
     cache_path: path::PathBuf,
     /// the parent path to the current ip Orbit.toml file
     ip_path: Option<path::PathBuf>,
-    /// holds in-development mutable ip projects
-    dev_path: Option<path::PathBuf>,
+    /// holds in-development mutable ip projects, searched in order
+    dev_paths: Vec<path::PathBuf>,
     /// holds installed immutable git repositories to pull versions from into cache
     store_path: path::PathBuf, 
     /// temporary throwaway directory     
     build_dir: String,
     config: Config,
+    /// directories listed in ORBIT_PLUGIN_PATH to load plugin manifests from
+    plugin_paths: Vec<path::PathBuf>,
     plugins: HashMap<String, Plugin>, // @IDEA optionally move hashmap out of context and create it from fn to allow dynamic loading
     templates: HashMap<String, Template>,
     vendors: HashMap<PkgPart, VendorManifest>,
+    aliases: AliasMap,
+    trust: TrustMap,
+    mirrors: Vec<Mirror>,
+    network: Network,
     pub force: bool,
+    pub verbose: bool,
+    pub offline: bool,
+}
+
+/// Flags given anywhere on the command-line that apply to every command.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct GlobalOptions {
+    /// bypass interactive prompts and safety checks
+    pub force: bool,
+    /// display the commands orbit executes
+    pub verbose: bool,
+    /// skip steps that reach remote repositories
+    pub offline: bool,
 }
 
 impl Context {
@@ -45,18 +70,29 @@ impl Context {
             cache_path: cache,
             store_path: store,
             ip_path: None,
-            dev_path: None,
+            dev_paths: Vec::new(),
+            plugin_paths: Vec::new(),
             plugins: HashMap::new(),
             templates: HashMap::new(),
             config: Config::new(),
             build_dir: String::new(),
             force: false,
+            verbose: false,
+            offline: false,
             vendors: HashMap::new(),
+            aliases: HashMap::new(),
+            trust: HashMap::new(),
+            mirrors: Vec::new(),
+            network: Network::new(),
         }
     }
 
-    pub fn retain_options(mut self, force: bool) -> Context {
-        self.force = force;
+    /// Keeps the global `options` read from the command-line for the commands to access.
+    pub fn retain_options(mut self, options: &GlobalOptions) -> Context {
+        self.force = options.force;
+        self.verbose = options.verbose;
+        self.offline = options.offline;
+        self.network = self.network.with_offline(options.offline);
         self
     }
 
@@ -99,6 +135,21 @@ impl Context {
         Ok(self)
     }
 
+    /// Brings the structure of the home, cache, and store up to date.
+    /// 
+    /// A new home records the current layout version; otherwise, any pending
+    /// migrations are applied. Must be called after the cache and store are set.
+    pub fn layout(self) -> Result<Context, Fault> {
+        let layout = Layout::new(&self.home_path, &self.cache_path, &self.store_path);
+        if layout.is_new()? {
+            layout.initialize()?;
+            eprintln!("info: set up a new orbit home at {}; run `orbit doctor` to check the environment", normalize_path(self.home_path.clone()).display());
+        } else {
+            layout.migrate()?;
+        }
+        Ok(self)
+    }
+
     /// Checks if windows literal command is enabled.
     pub fn enable_windows_bat_file_match() -> bool {
         if cfg!(target_os = "windows") {
@@ -182,15 +233,15 @@ impl Context {
 
         // if in ip, also look along current directory for a /.orbit/config.toml file to load (local configuration) 
         self.config = if let Some(ip_dir) = self.get_ip_path() {
-            cfg.local(&ip_dir.join(".orbit").join(name))?
+            cfg.local(&ip_dir.join(LOCAL_CONFIG_DIR).join(name))?
         } else {
             cfg
         };
 
         // @TODO dynamically set from environment variables from configuration data
 
-        // load plugins and templates
-        self.plugins()?.templates()
+        // load plugins, templates, aliases, trust policies, mirrors, and network settings
+        self.plugins()?.templates()?.aliases()?.trust()?.mirrors()?.network()
     }
 
     /// Accesses the plugins in a map with `alias` as the keys.
@@ -205,7 +256,7 @@ impl Context {
         for (arr_tbl, root) in plugs {
             for tbl in arr_tbl {
                 let plug = match Plugin::from_toml(tbl) {
-                    Ok(r) => r.set_root(&root), // resolve paths from that config file's parent directory
+                    Ok(r) => r.set_root(&root).set_source(&root.join(CONFIG_FILE)), // resolve paths from that config file's parent directory
                     Err(e) => return Err(AnyError(format!("configuration {}: plugin {}", normalize_path(root.join(CONFIG_FILE)).display(), e)))?
                 };
                 // will kick out previous values so last item in array has highest precedence
@@ -215,6 +266,113 @@ impl Context {
         Ok(self)
     }
 
+    /// Adds the plugins defined in the plugin manifests of the directories listed
+    /// in the environment variable `key`.
+    /// 
+    /// The directories are separated like the PATH variable. A plugin defined in
+    /// configuration takes precedence over one with the same alias found here,
+    /// and earlier directories take precedence over later ones. Directories
+    /// without a plugin manifest are skipped.
+    pub fn plugin_path(mut self, key: &str) -> Result<Context, Fault> {
+        if let Some(value) = std::env::var_os(key) {
+            self.plugin_paths = std::env::split_paths(&value)
+                .filter(|p| p.as_os_str().is_empty() == false)
+                .collect();
+        }
+        for dir in &self.plugin_paths {
+            for plug in Plugin::read_manifest(dir)? {
+                if self.plugins.contains_key(plug.alias()) == false {
+                    self.plugins.insert(plug.alias().to_owned(), plug);
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// References the directories searched for plugin manifests.
+    pub fn get_plugin_paths(&self) -> &Vec<PathBuf> {
+        &self.plugin_paths
+    }
+
+    /// References the canonical pkgids in a map with the aliased pkgids as the keys.
+    pub fn get_aliases(&self) -> &AliasMap {
+        &self.aliases
+    }
+
+    /// Iterates through the array of tables to define all aliases.
+    fn aliases(mut self) -> Result<Context, Fault> {
+        let aliases = self.config.collect_as_array_of_tables("alias")?;
+
+        for (arr_tbl, root) in aliases {
+            for tbl in arr_tbl {
+                let alias = match Alias::from_toml(tbl) {
+                    Ok(r) => r,
+                    Err(e) => return Err(AnyError(format!("configuration {}: alias {}", normalize_path(root.join(CONFIG_FILE)).display(), e)))?
+                };
+                // will kick out previous values so last item in array has highest precedence
+                self.aliases.insert(alias.from().clone(), alias.to().clone());
+            }
+        }
+        Ok(self)
+    }
+
+    /// References the trust policies in a map with the vendors' names as the keys.
+    pub fn get_trust(&self) -> &TrustMap {
+        &self.trust
+    }
+
+    /// Iterates through the array of tables to define all trust policies.
+    fn trust(mut self) -> Result<Context, Fault> {
+        let policies = self.config.collect_as_array_of_tables("trust")?;
+
+        for (arr_tbl, root) in policies {
+            for tbl in arr_tbl {
+                let trust = match Trust::from_toml(tbl) {
+                    Ok(r) => r.resolve_key(root), // resolve key files from that config file's parent directory
+                    Err(e) => return Err(AnyError(format!("configuration {}: trust {}", normalize_path(root.join(CONFIG_FILE)).display(), e)))?
+                };
+                // will kick out previous values so last item in array has highest precedence
+                self.trust.insert(trust.get_vendor().clone(), trust);
+            }
+        }
+        Ok(self)
+    }
+
+    /// References the mirrors in the order they are defined.
+    pub fn get_mirrors(&self) -> &Vec<Mirror> {
+        &self.mirrors
+    }
+
+    /// Iterates through the array of tables to define all mirrors.
+    fn mirrors(mut self) -> Result<Context, Fault> {
+        let mirrors = self.config.collect_as_array_of_tables("mirror")?;
+
+        for (arr_tbl, root) in mirrors {
+            for tbl in arr_tbl {
+                match Mirror::from_toml(tbl) {
+                    // later mirrors for the same prefix have higher precedence
+                    Ok(r) => self.mirrors.push(r),
+                    Err(e) => return Err(AnyError(format!("configuration {}: mirror {}", normalize_path(root.join(CONFIG_FILE)).display(), e)))?
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// References the settings for reaching remote hosts.
+    pub fn get_network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Reads the `[network]` table of the configuration.
+    fn network(mut self) -> Result<Context, Fault> {
+        self.network = match Network::from_config(&self.config) {
+            Ok(n) => n.with_offline(self.offline),
+            Err(e) => return Err(AnyError(format!("configuration: {}", e)))?
+        };
+        Ok(self)
+    }
+
     /// References the templates in a map with `alias` as the keys.
     pub fn get_templates(&self) -> &HashMap<String, Template> {
         &self.templates
@@ -236,46 +394,69 @@ impl Context {
         Ok(self)
     }
 
-    /// Determines the orbit ip development path.
+    /// Determines the orbit ip development paths.
     /// 
     /// First checks if the environment already has ORBIT_DEV_PATH set, otherwise it
     /// will look for the value found in the config file. If no development path
     /// is set, it will use the current directory.
     /// 
+    /// ORBIT_DEV_PATH can list multiple paths separated like the PATH variable. In
+    /// the config file, `core.paths` lists the paths to search after `core.path`.
+    /// 
     /// Note: Stange behavior where `edit` with vscode captures current ENV variables
     /// into new window to prevent reading config for things like ORBIT_DEV_PATH.
     /// 
-    /// If `verify` is set to `true`, then it will ensure the path is a directory and exists.
+    /// If `verify` is set to `true`, then it will ensure each path is a directory and exists.
     pub fn development_path(mut self, s: &str, verify: bool) -> Result<Context, Fault> {
         // an explicit environment variable takes precedence over config file data
-        self.dev_path = Some(std::path::PathBuf::from(match std::env::var(s) {
-            Ok(v) => v,
-            Err(_) => {
+        let value = match std::env::var_os(s) {
+            Some(v) => v,
+            None => {
                 // use current directory if the key-value pair is not there
-                let path = match self.get_config().get_as_str("core", "path")? {
+                let mut paths = vec![match self.get_config().get_as_str("core", "path")? {
                     // normalize
-                    Some(p) => crate::util::filesystem::normalize_path(PathBuf::from(p.to_owned())).to_str().unwrap().to_string(),
-                    None => std::env::current_dir().unwrap().display().to_string(),
-                };
-                std::env::set_var(s, &path);
-                path
+                    Some(p) => crate::util::filesystem::normalize_path(PathBuf::from(p.to_owned())),
+                    None => std::env::current_dir().unwrap(),
+                }];
+                for (p, root) in self.get_config().collect_as_array_of_str("core", "paths")? {
+                    let path = normalize_path(PathBuf::from(filesystem::resolve_rel_path(root, p)));
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                }
+                let value = std::env::join_paths(&paths)?;
+                std::env::set_var(s, &value);
+                value
             }
-        }));
+        };
+        self.dev_paths = std::env::split_paths(&value).collect();
 
         if verify == true {
-            // verify the orbit path exists and is a directory
-            if self.dev_path.as_ref().unwrap().exists() == false {
-                return Err(ContextError(format!("orbit dev path '{}' does not exist", self.dev_path.as_ref().unwrap().display())))?
-            } else if self.dev_path.as_ref().unwrap().is_dir() == false {
-                return Err(ContextError(format!("orbit dev path '{}' is not a directory", self.dev_path.as_ref().unwrap().display())))?
+            // verify each orbit path exists and is a directory
+            for path in &self.dev_paths {
+                if !path.exists() {
+                    return Err(ContextError(format!("orbit dev path '{}' does not exist", path.display())))?
+                } else if !path.is_dir() {
+                    return Err(ContextError(format!("orbit dev path '{}' is not a directory", path.display())))?
+                }
             }
         }
         Ok(self)
     }
 
-    /// Access the Orbit development path.
+    /// Access the first Orbit development path, where new ip are placed.
     pub fn get_development_path(&self) -> Option<&path::PathBuf> {
-        self.dev_path.as_ref()
+        self.dev_paths.first()
+    }
+
+    /// Access every Orbit development path in the order they are searched.
+    pub fn get_development_paths(&self) -> &[path::PathBuf] {
+        &self.dev_paths
+    }
+
+    /// Finds the first development path that contains the `path`.
+    pub fn locate_development_path(&self, path: &path::Path) -> Option<&path::PathBuf> {
+        self.dev_paths.iter().find(|dev| filesystem::is_within(dev, path))
     }
 
     /// Access the configuration data.
@@ -322,6 +503,10 @@ impl Context {
     pub fn goto_ip_path(&self) -> Result<(), ContextError> {
         match self.get_ip_path() {
             Some(cwd) => {
+                // verify this orbit can operate on the ip (other errors are left to the command reading the manifest)
+                if let Ok(m) = Manifest::from_path(cwd.join(IP_MANIFEST_FILE)) {
+                    manifest::check_orbit_version(&m).map_err(|e| ContextError(e.to_string()))?;
+                }
                 // set the current working directory to here
                 std::env::set_current_dir(&cwd).expect("could not change directories");
             }
@@ -0,0 +1,242 @@
+//! Exporters for bootstrapping tool projects from Orbit's planned file list.
+
+use std::path::Path;
+use std::str::FromStr;
+use crate::util::anyerror::AnyError;
+use super::vhdl::standard::VhdlStandard;
+
+/// A tool project format that can be generated from a blueprint.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    /// Vivado: a tcl script of `add_files` commands
+    VivadoTcl,
+    /// Quartus: a fragment of global assignments to source from a `.qsf` file
+    QuartusQsf,
+    /// GHDL: a command file that analyzes each file
+    Ghdl,
+    /// nvc: a command file that analyzes each file
+    Nvc,
+}
+
+impl FromStr for Format {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "vivado-tcl" => Ok(Self::VivadoTcl),
+            "quartus-qsf" => Ok(Self::QuartusQsf),
+            "ghdl" => Ok(Self::Ghdl),
+            "nvc" => Ok(Self::Nvc),
+            _ => Err(AnyError(format!("unknown format '{}'; expecting 'vivado-tcl', 'quartus-qsf', 'ghdl', or 'nvc'", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::VivadoTcl => write!(f, "vivado-tcl"),
+            Self::QuartusQsf => write!(f, "quartus-qsf"),
+            Self::Ghdl => write!(f, "ghdl"),
+            Self::Nvc => write!(f, "nvc"),
+        }
+    }
+}
+
+/// The data of a blueprint that tool projects are built from.
+#[derive(Debug, PartialEq)]
+pub struct Blueprint {
+    /// generic overrides of the top-level as (name, value)
+    generics: Vec<(String, String)>,
+    /// ordered hdl files as (library, filepath, standard)
+    hdl: Vec<(String, String, Option<VhdlStandard>)>,
+    /// files from every other fileset
+    others: Vec<String>,
+}
+
+impl Blueprint {
+    /// Collects the generics, ordered hdl files, and remaining files from the `blueprint` data.
+    ///
+    /// Generators are skipped because they must run before the project is created.
+    pub fn read(blueprint: &str) -> Self {
+        let mut result = Self { generics: Vec::new(), hdl: Vec::new(), others: Vec::new() };
+        for line in blueprint.lines() {
+            let mut parts = line.split('\t');
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some("GENERIC"), Some(name), Some(value)) => result.generics.push((name.to_string(), value.to_string())),
+                (Some("GENERATE"), _, _) => (),
+                (Some("VHDL-RTL"), Some(lib), Some(file)) | (Some("VHDL-SIM"), Some(lib), Some(file)) => {
+                    // the optional fourth column is the file's vhdl standard
+                    let std = parts.next().and_then(|s| VhdlStandard::from_str(s).ok());
+                    result.hdl.push((lib.to_string(), file.to_string(), std));
+                },
+                (Some(_), Some(_), Some(file)) => result.others.push(file.to_string()),
+                _ => (),
+            }
+        }
+        result
+    }
+
+    /// Lists the files from other filesets with the extension `ext`.
+    fn others_with(&self, ext: &str) -> impl Iterator<Item = &String> {
+        let ext = ext.to_string();
+        self.others.iter().filter(move |f| Path::new(f).extension().map(|x| x.eq_ignore_ascii_case(ext.as_str())).unwrap_or(false))
+    }
+}
+
+impl Format {
+    /// The filename of the exported project file.
+    pub fn export_file(&self) -> &str {
+        match self {
+            Self::VivadoTcl => "vivado.tcl",
+            Self::QuartusQsf => "quartus.qsf",
+            Self::Ghdl => "ghdl.sh",
+            Self::Nvc => "nvc.sh",
+        }
+    }
+
+    /// Formats the `blueprint` into the tool's project format.
+    ///
+    /// Only constraint files the tool reads (`.xdc` for Vivado and `.sdc` for Quartus) are
+    /// added from the other filesets. Generics are set on the project for Vivado and Quartus;
+    /// the command files only analyze the hdl files.
+    pub fn export(&self, blueprint: &Blueprint) -> String {
+        let mut result = String::new();
+        match self {
+            Self::VivadoTcl => {
+                result.push_str("# This file is automatically generated by Orbit.\n");
+                for (lib, file, std) in &blueprint.hdl {
+                    result.push_str(&format!("add_files -norecurse {{{}}}\n", file));
+                    result.push_str(&format!("set_property library {} [get_files {{{}}}]\n", lib, file));
+                    match std {
+                        Some(VhdlStandard::Vhdl2008) => result.push_str(&format!("set_property file_type {{VHDL 2008}} [get_files {{{}}}]\n", file)),
+                        Some(VhdlStandard::Vhdl2019) => result.push_str(&format!("set_property file_type {{VHDL 2019}} [get_files {{{}}}]\n", file)),
+                        _ => (),
+                    }
+                }
+                for file in blueprint.others_with("xdc") {
+                    result.push_str(&format!("add_files -fileset constrs_1 -norecurse {{{}}}\n", file));
+                }
+                if !blueprint.generics.is_empty() {
+                    let generics: Vec<String> = blueprint.generics.iter().map(|(n, v)| format!("{}={}", n, v)).collect();
+                    result.push_str(&format!("set_property generic {{{}}} [current_fileset]\n", generics.join(" ")));
+                }
+            },
+            Self::QuartusQsf => {
+                result.push_str("# This file is automatically generated by Orbit.\n");
+                for (lib, file, std) in &blueprint.hdl {
+                    result.push_str(&format!("set_global_assignment -name VHDL_FILE {{{}}} -library {}", file, lib));
+                    if let Some(std) = std {
+                        let version = match std {
+                            VhdlStandard::Vhdl1987 => "VHDL_1987",
+                            VhdlStandard::Vhdl1993 | VhdlStandard::Vhdl2002 => "VHDL_1993",
+                            VhdlStandard::Vhdl2008 => "VHDL_2008",
+                            VhdlStandard::Vhdl2019 => "VHDL_2019",
+                        };
+                        result.push_str(&format!(" -hdl_version {}", version));
+                    }
+                    result.push('\n');
+                }
+                for file in blueprint.others_with("sdc") {
+                    result.push_str(&format!("set_global_assignment -name SDC_FILE {{{}}}\n", file));
+                }
+                for (name, value) in &blueprint.generics {
+                    result.push_str(&format!("set_parameter -name {} {}\n", name, value));
+                }
+            },
+            Self::Ghdl | Self::Nvc => {
+                result.push_str("#!/bin/sh\n# This file is automatically generated by Orbit.\nset -e\n");
+                for (lib, file, std) in &blueprint.hdl {
+                    let file = format!("'{}'", file.replace('\'', "'\\''"));
+                    result.push_str(&match (self, std) {
+                        (Self::Ghdl, Some(std)) => format!("ghdl -a --std={} --work={} {}\n", Self::ghdl_std(std), lib, file),
+                        (Self::Ghdl, None) => format!("ghdl -a --work={} {}\n", lib, file),
+                        (_, Some(std)) => format!("nvc --std={} --work={} -a {}\n", Self::nvc_std(std), lib, file),
+                        (_, None) => format!("nvc --work={} -a {}\n", lib, file),
+                    });
+                }
+            },
+        }
+        result
+    }
+
+    /// Translates the `std` into the value of GHDL's `--std` option.
+    fn ghdl_std(std: &VhdlStandard) -> &'static str {
+        match std {
+            VhdlStandard::Vhdl1987 => "87",
+            VhdlStandard::Vhdl1993 => "93c",
+            VhdlStandard::Vhdl2002 => "02",
+            VhdlStandard::Vhdl2008 => "08",
+            VhdlStandard::Vhdl2019 => "19",
+        }
+    }
+
+    /// Translates the `std` into the value of nvc's `--std` option, which starts at VHDL-1993.
+    fn nvc_std(std: &VhdlStandard) -> &'static str {
+        match std {
+            VhdlStandard::Vhdl1987 | VhdlStandard::Vhdl1993 => "1993",
+            VhdlStandard::Vhdl2002 => "2002",
+            VhdlStandard::Vhdl2008 => "2008",
+            VhdlStandard::Vhdl2019 => "2019",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BLUEPRINT: &str = "\
+GENERIC\tWIDTH\t8
+GENERATE\tregs\t/ip\tpython gen.py
+CONSTRAINTS\tboard\t/ip/board.xdc
+CONSTRAINTS\tboard\t/ip/timing.sdc
+VHDL-RTL\trary\t/cache/gates/and_gate.vhd
+VHDL-RTL\twork\t/ip/top.vhd\t2008
+VHDL-SIM\twork\t/ip/top_tb.vhd
+";
+
+    #[test]
+    fn export_vivado_tcl() {
+        let bp = Blueprint::read(BLUEPRINT);
+        assert_eq!(Format::VivadoTcl.export(&bp), "\
+# This file is automatically generated by Orbit.
+add_files -norecurse {/cache/gates/and_gate.vhd}
+set_property library rary [get_files {/cache/gates/and_gate.vhd}]
+add_files -norecurse {/ip/top.vhd}
+set_property library work [get_files {/ip/top.vhd}]
+set_property file_type {VHDL 2008} [get_files {/ip/top.vhd}]
+add_files -norecurse {/ip/top_tb.vhd}
+set_property library work [get_files {/ip/top_tb.vhd}]
+add_files -fileset constrs_1 -norecurse {/ip/board.xdc}
+set_property generic {WIDTH=8} [current_fileset]
+");
+    }
+
+    #[test]
+    fn export_quartus_qsf() {
+        let bp = Blueprint::read(BLUEPRINT);
+        assert_eq!(Format::QuartusQsf.export(&bp), "\
+# This file is automatically generated by Orbit.
+set_global_assignment -name VHDL_FILE {/cache/gates/and_gate.vhd} -library rary
+set_global_assignment -name VHDL_FILE {/ip/top.vhd} -library work -hdl_version VHDL_2008
+set_global_assignment -name VHDL_FILE {/ip/top_tb.vhd} -library work
+set_global_assignment -name SDC_FILE {/ip/timing.sdc}
+set_parameter -name WIDTH 8
+");
+    }
+
+    #[test]
+    fn export_command_files() {
+        let bp = Blueprint::read(BLUEPRINT);
+        assert_eq!(Format::Ghdl.export(&bp), "\
+#!/bin/sh
+# This file is automatically generated by Orbit.
+set -e
+ghdl -a --work=rary '/cache/gates/and_gate.vhd'
+ghdl -a --std=08 --work=work '/ip/top.vhd'
+ghdl -a --work=work '/ip/top_tb.vhd'
+");
+        assert_eq!(Format::Nvc.export(&bp).lines().nth(4), Some("nvc --std=2008 --work=work -a '/ip/top.vhd'"));
+    }
+}
@@ -1,16 +1,22 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use git2::build::CheckoutBuilder;
 use git2::Repository;
 
-use crate::util::anyerror::Fault;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::progress::Spinner;
 
+use super::config::{FromToml, FromTomlError};
+use super::mirror;
+use super::network;
+use super::pkgid::PkgPart;
 use super::version::Version;
 
 /// A series of git commands necessary to run through subprocesses rather than libgit2 bindings.
 pub struct ExtGit {
     command: String,
     root: std::path::PathBuf,
+    transport: Transport,
 }
 
 impl ExtGit {
@@ -21,6 +27,7 @@ impl ExtGit {
         Self {
             command: cmd.unwrap_or("git").to_string(),
             root: PathBuf::new(),
+            transport: Transport::new(),
         }
     }
 
@@ -30,28 +37,61 @@ impl ExtGit {
         self
     }
 
-    /// Clones a repository `url` to `dest`.
+    /// Sets the transport settings to use when reaching remote repositories.
+    pub fn transport(mut self, t: Transport) -> Self {
+        self.transport = t;
+        self
+    }
+
+    /// Clones a repository `url` to `dest` while displaying a spinner.
+    /// 
+    /// See `fetch` for details.
+    pub fn clone(&self, url: &crate::util::url::Url, dest: &std::path::PathBuf, disable_ssh: bool) -> Result<(), Fault> {
+        let spinner = Spinner::new(&format!("cloning {} ...", url));
+        let result = self.fetch(url, dest, disable_ssh);
+        spinner.finish(None);
+        result
+    }
+
+    /// Clones a repository `url` to `dest` without reporting progress.
     /// 
     /// This function uses the actual git command in order to bypass a lot of issues with using libgit with
     /// private repositories.
     /// 
-    /// The `disable_ssh` parameter will convert a url to HTTPS if given as SSH.
-    pub fn clone(&self, url: &crate::util::url::Url, dest: &std::path::PathBuf, disable_ssh: bool) -> Result<(), Fault> {
+    /// The `disable_ssh` parameter will convert a url to HTTPS if given as SSH, taking precedence
+    /// over the transport's protocol preference.
+    /// 
+    /// A url with a subdirectory fragment clones the entire repository and records the
+    /// subdirectory as the ip's root (see `ip_root`). When the transport is sparse, only
+    /// the files within the subdirectory are fetched and checked out.
+    pub fn fetch(&self, url: &crate::util::url::Url, dest: &std::path::PathBuf, disable_ssh: bool) -> Result<(), Fault> {
         let tmp_path = tempfile::tempdir()?;
+        let subdir = url.get_subdirectory();
+        let repo_url = url.without_subdirectory();
         // check if to convert to https when disabling ssh
-        let url = match disable_ssh {
-            true => url.as_https().to_string(),
-            false => url.to_string()
-        };
-        let proc = std::process::Command::new(&self.command)
-            .args(["clone", &url])
-            .current_dir(&tmp_path)
-            .output()?;
-
-        match proc.status.code() {
-            Some(num) => if num != 0 { Err(ExtGitError::NonZeroCode(num, proc.stderr))? } else { () },
-            None => return Err(ExtGitError::SigTermination)?,
+        let url = match (disable_ssh, &self.transport.protocol) {
+            (true, _) | (false, Some(Protocol::Https)) => repo_url.as_https().to_string(),
+            (false, Some(Protocol::Ssh)) => repo_url.as_ssh().to_string(),
+            (false, None) => repo_url.to_string(),
         };
+        // reach the source through a configured mirror
+        let url = mirror::rewrite(&url, self.transport.vendor.as_ref());
+        network::get().connect(&url)?;
+        // clone into an empty directory on every attempt
+        network::get().retry(ExtGitError::is_transient, || {
+            for entry in std::fs::read_dir(&tmp_path)? {
+                std::fs::remove_dir_all(entry?.path())?;
+            }
+            let proc = command(&self.command)
+                .args(self.transport.to_clone_args(&url, subdir.is_some()))
+                .current_dir(&tmp_path)
+                .output()?;
+            match proc.status.code() {
+                Some(0) => Ok(()),
+                Some(num) => Err(ExtGitError::NonZeroCode(num, proc.stderr))?,
+                None => Err(ExtGitError::SigTermination)?,
+            }
+        })?;
         // create the directories
         std::fs::create_dir_all(&dest)?;
 
@@ -59,6 +99,9 @@ impl ExtGit {
         for entry in std::fs::read_dir(&tmp_path)? {
             // copy contents into cache slot
             let temp = entry.unwrap().path();
+            if let Some(subdir) = subdir {
+                self.scope(&temp, subdir)?;
+            }
             let options = fs_extra::dir::CopyOptions::new();
             let mut from_paths = Vec::new();
             for dir_entry in std::fs::read_dir(temp)? {
@@ -74,19 +117,97 @@ impl ExtGit {
         Ok(())
     }
 
+    /// Records `subdir` as the root of the ip within the repository cloned at `repo`,
+    /// limiting the checkout to it when the transport is sparse.
+    fn scope(&self, repo: &Path, subdir: &str) -> Result<(), Fault> {
+        if Path::new(subdir).is_absolute() || Path::new(subdir).components().any(|c| c == std::path::Component::ParentDir) {
+            return Err(AnyError(format!("subdirectory '{}' must be a relative path within the repository", subdir)))?
+        }
+        let git_repo = Repository::open(repo)?;
+        if self.transport.sparse {
+            // the pattern file is written directly because libgit2 rejects the
+            // repository extensions `git sparse-checkout` enables
+            git_repo.config()?.set_bool("core.sparseCheckout", true)?;
+            let info = git_repo.path().join("info");
+            std::fs::create_dir_all(&info)?;
+            std::fs::write(info.join("sparse-checkout"), format!("/{}/\n", subdir))?;
+            Self::read_tree(&git_repo, "HEAD")?;
+        }
+        if !repo.join(subdir).is_dir() {
+            return Err(AnyError(format!("subdirectory '{}' does not exist in the repository", subdir)))?
+        }
+        git_repo.config()?.set_str(SUBDIRECTORY_KEY, subdir)?;
+        Ok(())
+    }
+
+    /// Determines the root directory of the ip within the `repo` located at `root`.
+    /// 
+    /// This is the subdirectory recorded when the repository was cloned from a url
+    /// with a subdirectory fragment, or else the `root` itself.
+    pub fn ip_root(repo: &Repository, root: &Path) -> PathBuf {
+        match repo.config().and_then(|cfg| cfg.get_string(SUBDIRECTORY_KEY)) {
+            Ok(subdir) => root.join(subdir),
+            Err(_) => root.to_path_buf(),
+        }
+    }
+
+    /// Checks if the `repo` only checks out a subset of its files.
+    fn is_sparse(repo: &Repository) -> bool {
+        match repo.config() {
+            Ok(cfg) => cfg.get_bool("core.sparseCheckout").unwrap_or(false),
+            Err(_) => false,
+        }
+    }
+
+    /// Forces the index and working tree of a sparse `repo` to match `rev`.
+    /// 
+    /// Runs the command: `git read-tree --reset -u <rev>`, which respects the sparse
+    /// patterns and fetches any missing files of a partial clone.
+    fn read_tree(repo: &Repository, rev: &str) -> Result<(), Fault> {
+        let output = command("git")
+            .args(["read-tree", "--reset", "-u", rev])
+            .current_dir(repo.workdir().unwrap_or_else(|| repo.path()))
+            .output()?;
+        match output.status.code() {
+            Some(0) => Ok(()),
+            Some(num) => Err(ExtGitError::NonZeroCode(num, output.stderr))?,
+            None => Err(ExtGitError::SigTermination)?,
+        }
+    }
+
     /// Updates a remote repository is up-to-date at `self.root`.
     /// 
     /// Runs the command: `git remote update`.
     pub fn remote_update(&self) -> Result<(), Fault> {
-        let output = std::process::Command::new(&self.command)
-            .args(["remote", "update"])
+        network::get().require_online(&self.remotes())?;
+        network::get().retry(ExtGitError::is_transient, || {
+            let output = command(&self.command)
+                .args(["remote", "update"])
+                .current_dir(&self.root)
+                .output()?;
+            match output.status.code() {
+                Some(0) => Ok(()),
+                Some(num) => Err(ExtGitError::NonZeroCode(num, output.stderr))?,
+                None => Err(ExtGitError::SigTermination)?,
+            }
+        })
+    }
+
+    /// Checks that the default remote of the repository at `self.root` can be reached.
+    /// 
+    /// Runs the command: `git ls-remote --quiet`. Credentials are never prompted for.
+    pub fn ls_remote(&self) -> Result<(), Fault> {
+        network::get().require_online(&self.remotes())?;
+        let output = command(&self.command)
+            .args(["ls-remote", "--quiet"])
             .current_dir(&self.root)
+            .env("GIT_TERMINAL_PROMPT", "0")
             .output()?;
         match output.status.code() {
-            Some(num) => if num != 0 { Err(ExtGitError::NonZeroCode(num, output.stderr))? } else { () },
-            None => return Err(ExtGitError::SigTermination)?,
-        };
-        Ok(())
+            Some(0) => Ok(()),
+            Some(num) => Err(ExtGitError::NonZeroCode(num, output.stderr))?,
+            None => Err(ExtGitError::SigTermination)?,
+        }
     }
 
     /// Pushes to remote repository at the self `path`.
@@ -103,6 +224,11 @@ impl ExtGit {
         self.connect(Request::Pull)
     }
 
+    /// Describes the remotes of the repository at `self.root`.
+    fn remotes(&self) -> String {
+        format!("the remotes of repository {}", self.root.display())
+    }
+
     /// Checks if the current branch has a remote connection.
     pub fn is_remote_linked(repo: &Repository) -> Result<bool, Fault> {
         let b = git2::Branch::wrap(repo.head()?);
@@ -111,7 +237,8 @@ impl ExtGit {
 
     /// Accesses remote through external git call using request `req`.
     fn connect(&self, req: Request) -> Result<(), Fault> {
-        let output = std::process::Command::new(&self.command)
+        network::get().require_online(&self.remotes())?;
+        let output = command(&self.command)
             .args([&req.to_string()])
             .current_dir(&self.root)
             .output()?; // hide output from reaching stdout by using .output()
@@ -120,7 +247,7 @@ impl ExtGit {
             None => return Err(ExtGitError::SigTermination)?,
         };
         // push tags
-        let output = std::process::Command::new(&self.command)
+        let output = command(&self.command)
             .args([&req.to_string(), "--tags"])
             .current_dir(&self.root)
             .output()?;
@@ -133,8 +260,22 @@ impl ExtGit {
 
     /// Takes a repository `repo` and forces the checkout to be at the `tag` commit.
     pub fn checkout_tag_state(repo: &Repository, tag: &Version) -> Result<(), Fault> {
-        // get the tag
-        let obj = repo.revparse_single(tag.to_string().as_ref())?;
+        Self::checkout_rev(repo, &tag.to_string())
+    }
+
+    /// Checks out the exact `commit` in the given `repo`, regardless of which tags point to it.
+    pub fn checkout_commit(repo: &Repository, commit: &str) -> Result<(), Fault> {
+        Self::checkout_rev(repo, commit)
+    }
+
+    /// Forces the working tree of `repo` to match the revision `rev`.
+    fn checkout_rev(repo: &Repository, rev: &str) -> Result<(), Fault> {
+        // libgit2 does not understand sparse checkouts or partial clones
+        if Self::is_sparse(repo) {
+            return Self::read_tree(repo, rev)
+        }
+        // get the revision
+        let obj = repo.revparse_single(rev)?;
         // configure checkout options
         let mut cb = CheckoutBuilder::new();
         cb.force();
@@ -142,6 +283,12 @@ impl ExtGit {
         Ok(repo.checkout_tree(&obj, Some(&mut cb))?)
     }
 
+    /// Resolves the commit hash the `tag` points to in the given `repo`.
+    pub fn tag_commit(repo: &Repository, tag: &Version) -> Result<String, Fault> {
+        let obj = repo.revparse_single(tag.to_string().as_ref())?;
+        Ok(obj.peel_to_commit()?.id().to_string())
+    }
+
     /// Collects all version git tags from the given `repo` repository.
     /// 
     /// The tags must follow semver `[0-9]*.[0-9]*.[0-9]*` specification.
@@ -173,6 +320,9 @@ impl ExtGit {
 
     /// Forces a checkout to be at the tip HEAD.
     pub fn checkout_head(repo: &Repository) -> Result<(), Fault> {
+        if Self::is_sparse(repo) {
+            return Self::read_tree(repo, "HEAD")
+        }
         let obj = repo.head()?.resolve()?.peel(git2::ObjectType::Commit)?;
         // configure checkout options
         let mut cb = CheckoutBuilder::new();
@@ -188,8 +338,34 @@ impl ExtGit {
         let obj = repo.head()?.resolve()?.peel(git2::ObjectType::Commit)?;
         obj.into_commit().map_err(|_| git2::Error::from_str("Couldn't find commit"))
     }
+
+    /// Writes the files of `repo` at the revision `rev` into the directory `dest`, leaving
+    /// the repository's working tree, index, and HEAD untouched.
+    ///
+    /// Returns the commit the revision resolved to.
+    pub fn export_rev(repo: &Repository, rev: &str, dest: &Path) -> Result<git2::Oid, Fault> {
+        let commit = match repo.revparse_single(rev).and_then(|obj| obj.peel_to_commit()) {
+            Ok(c) => c,
+            Err(_) => return Err(AnyError(format!("failed to find revision '{}' in the ip's repository", rev)))?,
+        };
+        let mut cb = CheckoutBuilder::new();
+        cb.force().update_index(false).recreate_missing(true).target_dir(dest);
+        repo.checkout_tree(commit.as_object(), Some(&mut cb))?;
+        Ok(commit.id())
+    }
+}
+
+/// Creates a git subprocess for `program` that reaches remotes with the network
+/// settings of the process.
+fn command(program: &str) -> std::process::Command {
+    let mut cmd = std::process::Command::new(program);
+    network::get().apply_git(&mut cmd);
+    cmd
 }
 
+/// Key in a cloned repository's git config that records the subdirectory holding the ip.
+const SUBDIRECTORY_KEY: &str = "orbit.subdirectory";
+
 enum Request {
     Push,
     Pull
@@ -212,6 +388,34 @@ pub enum ExtGitError {
 
 impl std::error::Error for ExtGitError {}
 
+impl ExtGitError {
+    /// Checks if the failed git command `e` may succeed when run again, such as
+    /// when the remote could not be reached. Failures such as rejected credentials
+    /// or a missing repository are not transient.
+    pub fn is_transient(e: &Fault) -> bool {
+        const TRANSIENT: [&str; 11] = [
+            "could not resolve host",
+            "failed to connect",
+            "connection timed out",
+            "operation timed out",
+            "connection refused",
+            "connection reset",
+            "the remote end hung up unexpectedly",
+            "early eof",
+            "rpc failed",
+            "ssl_error_syscall",
+            "the requested url returned error: 5",
+        ];
+        match e.downcast_ref::<ExtGitError>() {
+            Some(Self::NonZeroCode(_, stderr)) => {
+                let stderr = String::from_utf8_lossy(stderr).to_lowercase();
+                TRANSIENT.iter().any(|t| stderr.contains(t))
+            },
+            _ => false,
+        }
+    }
+}
+
 impl std::fmt::Display for ExtGitError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -219,4 +423,156 @@ impl std::fmt::Display for ExtGitError {
             Self::SigTermination => write!(f, "terminated by signal"),
         }
     }
+}
+
+/// The network protocol used to reach a remote repository.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Protocol {
+    Ssh,
+    Https,
+}
+
+impl FromStr for Protocol {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "ssh" => Ok(Self::Ssh),
+            "https" => Ok(Self::Https),
+            _ => Err(AnyError(format!("unknown protocol '{}'; expecting 'ssh' or 'https'", s))),
+        }
+    }
+}
+
+/// Settings for how git reaches a vendor's remote repositories.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Transport {
+    protocol: Option<Protocol>,
+    credential_helper: Option<String>,
+    depth: Option<usize>,
+    sparse: bool,
+    /// vendor whose mirrors take precedence when rewriting urls
+    vendor: Option<PkgPart>,
+}
+
+impl Transport {
+    /// Creates a `Transport` that leaves urls and git's settings untouched.
+    pub fn new() -> Self {
+        Self { protocol: None, credential_helper: None, depth: None, sparse: false, vendor: None }
+    }
+
+    /// Sets the vendor whose remote repositories are reached with these settings.
+    pub fn vendor(mut self, vendor: PkgPart) -> Self {
+        self.vendor = Some(vendor);
+        self
+    }
+
+    /// Sets whether to only fetch and check out the subdirectory holding the ip
+    /// when a url has one.
+    pub fn sparse(mut self, sparse: bool) -> Self {
+        self.sparse = sparse;
+        self
+    }
+
+    /// Builds the arguments for a `git clone` on `url`.
+    /// 
+    /// A sparse transport performs a partial clone when the ip is within a `subdir`
+    /// of the repository.
+    fn to_clone_args(&self, url: &str, subdir: bool) -> Vec<String> {
+        let mut args = Vec::new();
+        // configuration must be passed before the subcommand
+        if let Some(helper) = &self.credential_helper {
+            args.push(String::from("-c"));
+            args.push(format!("credential.helper={}", helper));
+        }
+        args.push(String::from("clone"));
+        if let Some(depth) = &self.depth {
+            args.push(String::from("--depth"));
+            args.push(depth.to_string());
+            // keep the history of other branches to find version tags
+            args.push(String::from("--no-single-branch"));
+        }
+        if self.sparse && subdir {
+            // file contents are fetched only once they are checked out
+            args.push(String::from("--filter=blob:none"));
+            args.push(String::from("--no-checkout"));
+        }
+        args.push(url.to_string());
+        args
+    }
+}
+
+impl FromToml for Transport {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        Ok(Self {
+            protocol: Self::get(table, "protocol")?,
+            credential_helper: Self::get(table, "credential-helper")?,
+            depth: match table.get("depth") {
+                Some(item) => match item.as_integer() {
+                    Some(n) if n > 0 => Some(n as usize),
+                    _ => return Err(FromTomlError::BadParse(String::from("depth"), item.to_string().trim().to_string(), String::from("expecting a positive integer")))?,
+                },
+                None => None,
+            },
+            sparse: match table.get("sparse") {
+                Some(item) => match item.as_bool() {
+                    Some(b) => b,
+                    None => return Err(FromTomlError::BadParse(String::from("sparse"), item.to_string().trim().to_string(), String::from("expecting a boolean")))?,
+                },
+                None => false,
+            },
+            vendor: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn transient_failures() {
+        let failed = |stderr: &str| -> Fault { Box::new(ExtGitError::NonZeroCode(128, stderr.as_bytes().to_vec())) };
+        assert_eq!(ExtGitError::is_transient(&failed("fatal: unable to access 'https://github.com/c-rus/gates.git/': Could not resolve host: github.com")), true);
+        assert_eq!(ExtGitError::is_transient(&failed("fatal: the remote end hung up unexpectedly")), true);
+        assert_eq!(ExtGitError::is_transient(&failed("fatal: unable to access 'https://github.com/c-rus/gates.git/': The requested URL returned error: 503")), true);
+        // permanent failures
+        assert_eq!(ExtGitError::is_transient(&failed("fatal: Authentication failed for 'https://github.com/c-rus/gates.git/'")), false);
+        assert_eq!(ExtGitError::is_transient(&failed("remote: Repository not found.\nfatal: repository 'https://github.com/c-rus/none.git/' not found")), false);
+        let killed: Fault = Box::new(ExtGitError::SigTermination);
+        assert_eq!(ExtGitError::is_transient(&killed), false);
+    }
+
+    #[test]
+    fn transport_from_toml() {
+        let doc = "\
+protocol = \"https\"
+credential-helper = \"store\"
+depth = 1
+sparse = true
+".parse::<toml_edit::Document>().unwrap();
+        let t = Transport::from_toml(doc.as_table()).unwrap();
+        assert_eq!(t, Transport { protocol: Some(Protocol::Https), credential_helper: Some(String::from("store")), depth: Some(1), sparse: true, vendor: None });
+        assert_eq!(t.to_clone_args("https://github.com/c-rus/gates.git", false), vec![
+            "-c", "credential.helper=store", "clone", "--depth", "1", "--no-single-branch", "https://github.com/c-rus/gates.git"
+        ]);
+        assert_eq!(t.to_clone_args("https://github.com/c-rus/mono.git", true), vec![
+            "-c", "credential.helper=store", "clone", "--depth", "1", "--no-single-branch", "--filter=blob:none", "--no-checkout", "https://github.com/c-rus/mono.git"
+        ]);
+
+        // defaults
+        let t = Transport::from_toml(&toml_edit::Table::new()).unwrap();
+        assert_eq!(t, Transport::new());
+        assert_eq!(t.to_clone_args("git@github.com:c-rus/gates.git", true), vec!["clone", "git@github.com:c-rus/gates.git"]);
+
+        // bad values
+        let doc = "depth = 0".parse::<toml_edit::Document>().unwrap();
+        assert_eq!(Transport::from_toml(doc.as_table()).is_err(), true);
+        let doc = "protocol = \"ftp\"".parse::<toml_edit::Document>().unwrap();
+        assert_eq!(Transport::from_toml(doc.as_table()).is_err(), true);
+        let doc = "sparse = \"yes\"".parse::<toml_edit::Document>().unwrap();
+        assert!(Transport::from_toml(doc.as_table()).is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,303 @@
+//! Features let an ip keep optional source directories out of a plan unless
+//! they are asked for.
+//!
+//! Each feature is an entry of the `[features]` table in an ip's manifest. A
+//! feature gates the files under its `paths` and can require other features of
+//! the same ip or of a dependency. The `default` entry lists the features that
+//! are enabled whenever the ip is planned.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::str::FromStr;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
+use super::config::FromTomlError;
+use super::ip::IpSpec;
+use super::manifest::IpManifest;
+use super::pkgid::PkgId;
+
+/// Maps each ip in a plan to its enabled features.
+pub type FeatureMap = HashMap<IpSpec, BTreeSet<String>>;
+
+/// The key in the `[features]` table listing the features enabled by default.
+const DEFAULT_KEY: &str = "default";
+
+/// A feature of an ip, written as `NAME` or of a dependency, written as `PKGID/NAME`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FeatureRequest {
+    ip: Option<PkgId>,
+    name: String,
+}
+
+impl FeatureRequest {
+    /// References the dependency declaring the feature, if not the requesting ip.
+    pub fn get_ip(&self) -> Option<&PkgId> {
+        self.ip.as_ref()
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl FromStr for FeatureRequest {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (ip, name) = match s.rsplit_once('/') {
+            Some((ip, name)) => match PkgId::from_str(ip) {
+                Ok(pkgid) => (Some(pkgid), name),
+                Err(e) => return Err(AnyError(format!("invalid pkgid '{}' in feature '{}': {}", ip, s, e))),
+            },
+            None => (None, s),
+        };
+        if is_valid_name(name) == false {
+            return Err(AnyError(format!("invalid feature name '{}'; expecting letters, digits, '-', or '_'", name)))
+        }
+        Ok(Self { ip, name: name.to_string() })
+    }
+}
+
+impl std::fmt::Display for FeatureRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.ip {
+            Some(ip) => write!(f, "{}/{}", ip, self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Checks if `name` only uses letters, digits, hyphens, and underscores.
+fn is_valid_name(name: &str) -> bool {
+    name.is_empty() == false && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Feature {
+    paths: Vec<String>,
+    requires: Vec<FeatureRequest>,
+}
+
+impl Feature {
+    /// References the files and directories gated by the feature relative to the ip's root.
+    pub fn get_paths(&self) -> &Vec<String> {
+        &self.paths
+    }
+
+    /// References the other features enabled along with this feature.
+    pub fn get_requires(&self) -> &Vec<FeatureRequest> {
+        &self.requires
+    }
+}
+
+/// The `[features]` table mapping feature names to what they enable.
+#[derive(Debug, PartialEq)]
+pub struct FeatureTable {
+    default: Vec<FeatureRequest>,
+    features: HashMap<String, Feature>,
+}
+
+impl FeatureTable {
+    pub fn new() -> Self {
+        Self { default: Vec::new(), features: HashMap::new() }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Feature> {
+        self.features.get(name)
+    }
+
+    /// References the features enabled whenever the ip is planned.
+    pub fn get_default(&self) -> &Vec<FeatureRequest> {
+        &self.default
+    }
+
+    /// Checks if the `file` of the ip at `root` is planned with the `enabled` features.
+    ///
+    /// A file gated by one or more features is planned when any of them are enabled.
+    pub fn is_file_enabled(&self, root: &Path, file: &str, enabled: &BTreeSet<String>) -> bool {
+        let mut gates = self.features.iter()
+            .filter(|(_, f)| f.paths.iter().any(|p| filesystem::is_within(root.join(p), file)))
+            .peekable();
+        match gates.peek() {
+            Some(_) => gates.any(|(name, _)| enabled.contains(name)),
+            None => true,
+        }
+    }
+}
+
+/// Reads the list of feature requests at `key` from the table at `path`.
+fn read_requests(entry: &dyn toml_edit::TableLike, key: &str, path: &str) -> Result<Vec<FeatureRequest>, Fault> {
+    let mut list = Vec::new();
+    if let Some(item) = entry.get(key) {
+        let arr = match item.as_array() {
+            Some(arr) => arr,
+            None => return Err(FromTomlError::ExpectingStringArray(format!("{}.{}", path, key)))?,
+        };
+        for value in arr {
+            match value.as_str() {
+                Some(s) => list.push(FeatureRequest::from_str(s).map_err(|e| AnyError(format!("key '{}.{}': {}", path, key, e)))?),
+                None => return Err(FromTomlError::ExpectingStringArray(format!("{}.{}", path, key)))?,
+            }
+        }
+    }
+    Ok(list)
+}
+
+impl super::config::FromToml for FeatureTable {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        let mut result = Self::new();
+        for (key, item) in table.iter() {
+            if key == DEFAULT_KEY {
+                result.default = read_requests(table, DEFAULT_KEY, "features")?;
+                continue
+            }
+            let path = format!("features.{}", key);
+            if is_valid_name(key) == false {
+                return Err(AnyError(format!("invalid feature name '{}'; expecting letters, digits, '-', or '_'", key)))?
+            }
+            let entry = match item.as_table_like() {
+                Some(t) => t,
+                None => return Err(AnyError(format!("expects key '{}' to be a toml table", path)))?,
+            };
+            let mut paths = Vec::new();
+            if let Some(i) = entry.get("paths") {
+                match i.as_array().and_then(|arr| arr.iter().map(|f| f.as_str().map(String::from)).collect::<Option<Vec<String>>>()) {
+                    Some(list) => paths = list,
+                    None => return Err(FromTomlError::ExpectingStringArray(format!("{}.paths", path)))?,
+                }
+            }
+            let requires = read_requests(entry, "requires", &path)?;
+            result.features.insert(key.to_string(), Feature { paths, requires });
+        }
+        // features of the same ip must be declared
+        let mut requests = result.default.iter().map(|r| (DEFAULT_KEY, r))
+            .chain(result.features.iter().flat_map(|(k, f)| f.requires.iter().map(move |r| (k.as_str(), r))));
+        if let Some((key, r)) = requests.find(|(_, r)| r.ip.is_none() && result.features.contains_key(&r.name) == false) {
+            return Err(AnyError(format!("key 'features.{}' requires undeclared feature '{}'", key, r.name)))?
+        }
+        Ok(result)
+    }
+}
+
+/// Determines the features enabled for each ip in the `build_list` when the
+/// `target` is planned with the `requested` features.
+///
+/// Every ip's default features are enabled. A feature requested for a dependency
+/// is enabled for every version of the dependency in the build list.
+pub fn resolve(build_list: &[&IpManifest], target: &IpManifest, requested: &[FeatureRequest]) -> Result<FeatureMap, Fault> {
+    let mut map: FeatureMap = build_list.iter().map(|ip| (ip.into_ip_spec(), BTreeSet::new())).collect();
+    // (ip that requests, feature)
+    let mut queue: Vec<(&IpManifest, &FeatureRequest)> = requested.iter().map(|r| (target, r)).collect();
+    for ip in build_list {
+        queue.extend(ip.get_features().get_default().iter().map(|r| (*ip, r)));
+    }
+    while let Some((from, request)) = queue.pop() {
+        let owners: Vec<&IpManifest> = match request.get_ip() {
+            Some(pkgid) => {
+                let found: Vec<&IpManifest> = build_list.iter().copied().filter(|ip| ip.get_pkgid() == pkgid).collect();
+                if found.is_empty() {
+                    return Err(AnyError(format!("ip '{}' requests feature '{}' but '{}' is not a dependency", from.get_pkgid(), request, pkgid)))?
+                }
+                found
+            },
+            None => vec![from],
+        };
+        for ip in owners {
+            let feature = match ip.get_features().get(request.get_name()) {
+                Some(f) => f,
+                None => return Err(AnyError(format!("ip '{}' has no feature named '{}'", ip.into_ip_spec(), request.get_name())))?,
+            };
+            // enable the feature and what it requires only once
+            if map.entry(ip.into_ip_spec()).or_default().insert(request.get_name().to_string()) == true {
+                queue.extend(feature.get_requires().iter().map(|r| (ip, r)));
+            }
+        }
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::config::FromToml;
+
+    const FEATURES: &str = r#"
+[features]
+default = ["uart"]
+
+[features.uart]
+paths = ["rtl/uart"]
+
+[features.axi]
+paths = ["rtl/axi", "rtl/shared"]
+requires = ["uart", "ks-tech.rary.gates/wide"]
+
+[features.apb]
+paths = ["rtl/shared"]
+"#;
+
+    fn table() -> FeatureTable {
+        let doc = FEATURES.parse::<toml_edit::Document>().unwrap();
+        FeatureTable::from_toml(doc["features"].as_table().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn from_toml() {
+        let table = table();
+        assert_eq!(table.get_default(), &vec![FeatureRequest::from_str("uart").unwrap()]);
+        let axi = table.get("axi").unwrap();
+        assert_eq!(axi.get_paths(), &vec![String::from("rtl/axi"), String::from("rtl/shared")]);
+        assert_eq!(axi.get_requires()[1].get_ip(), Some(&PkgId::from_str("ks-tech.rary.gates").unwrap()));
+        assert_eq!(axi.get_requires()[1].get_name(), "wide");
+
+        // local features must be declared
+        let doc = "[features]\ndefault = [\"spi\"]\n".parse::<toml_edit::Document>().unwrap();
+        assert!(FeatureTable::from_toml(doc["features"].as_table().unwrap()).is_err());
+        let doc = "[features.axi]\nrequires = [\"a b\"]\n".parse::<toml_edit::Document>().unwrap();
+        assert!(FeatureTable::from_toml(doc["features"].as_table().unwrap()).is_err());
+    }
+
+    #[test]
+    fn file_gating() {
+        let table = table();
+        let root = Path::new("/ip");
+        let enabled: BTreeSet<String> = [String::from("apb")].iter().cloned().collect();
+        assert_eq!(table.is_file_enabled(root, "/ip/rtl/top.vhd", &enabled), true);
+        assert_eq!(table.is_file_enabled(root, "/ip/rtl/uart/tx.vhd", &enabled), false);
+        assert_eq!(table.is_file_enabled(root, "/ip/rtl/axi/lite.vhd", &enabled), false);
+        // gated by several features where one is enabled
+        assert_eq!(table.is_file_enabled(root, "/ip/rtl/shared/regs.vhd", &enabled), true);
+    }
+
+    /// Writes a manifest for the ip `name` declaring the `features` table to a directory in `root`.
+    fn manifest(root: &Path, name: &str, features: &str) -> IpManifest {
+        let dir = root.join(name);
+        std::fs::create_dir(&dir).unwrap();
+        let text = format!("[ip]\nname = \"{}\"\nlibrary = \"rary\"\nvendor = \"ks-tech\"\nversion = \"1.0.0\"\n\n{}", name, features);
+        std::fs::write(dir.join(crate::core::manifest::IP_MANIFEST_FILE), text).unwrap();
+        IpManifest::from_path(&dir).unwrap()
+    }
+
+    #[test]
+    fn resolve_unifies() {
+        let dir = tempfile::tempdir().unwrap();
+        let gates = manifest(dir.path(), "gates", "[features]\ndefault = [\"narrow\"]\n[features.narrow]\n[features.wide]\n");
+        let soc = manifest(dir.path(), "soc", FEATURES);
+        let build_list = vec![&gates, &soc];
+
+        // default features are enabled for every ip
+        let map = resolve(&build_list, &soc, &[]).unwrap();
+        assert_eq!(map.get(&soc.into_ip_spec()).unwrap().iter().collect::<Vec<&String>>(), vec!["uart"]);
+        assert_eq!(map.get(&gates.into_ip_spec()).unwrap().iter().collect::<Vec<&String>>(), vec!["narrow"]);
+
+        // requirements are followed into dependencies
+        let map = resolve(&build_list, &soc, &[FeatureRequest::from_str("axi").unwrap()]).unwrap();
+        assert_eq!(map.get(&soc.into_ip_spec()).unwrap().iter().collect::<Vec<&String>>(), vec!["axi", "uart"]);
+        assert_eq!(map.get(&gates.into_ip_spec()).unwrap().iter().collect::<Vec<&String>>(), vec!["narrow", "wide"]);
+
+        assert!(resolve(&build_list, &soc, &[FeatureRequest::from_str("spi").unwrap()]).is_err());
+        assert!(resolve(&build_list, &soc, &[FeatureRequest::from_str("ks-tech.rary.util/fast").unwrap()]).is_err());
+    }
+}
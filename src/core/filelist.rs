@@ -0,0 +1,276 @@
+//! Reading and writing the `.f` file lists common to EDA tools.
+//!
+//! A file list names one file per entry, in compile order. Switches set the library
+//! (`-work <lib>`) and VHDL standard (`-2008`, `--std=<std>`) of the entries after them,
+//! `-f <file>` reads a nested file list, and `+incdir+<dir>` and `+define+<name>[=<value>]`
+//! pass include directories and macros to the tool. Comments begin with `//` or `#`.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem;
+use super::template;
+use super::vhdl::standard::VhdlStandard;
+
+/// Levels of `-f` file lists that can be nested within each other.
+const MAX_NESTING: usize = 16;
+
+/// A file entry of a file list.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ListedFile {
+    path: PathBuf,
+    library: Option<String>,
+    standard: Option<VhdlStandard>,
+}
+
+impl ListedFile {
+    /// References the path to the file, joined to the directory of the list that named it.
+    pub fn get_path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// References the library set for the file, if any.
+    pub fn get_library(&self) -> Option<&String> {
+        self.library.as_ref()
+    }
+
+    /// References the VHDL standard set for the file, if any.
+    pub fn get_standard(&self) -> Option<&VhdlStandard> {
+        self.standard.as_ref()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FileList {
+    files: Vec<ListedFile>,
+    incdirs: Vec<PathBuf>,
+    defines: Vec<(String, Option<String>)>,
+}
+
+impl FileList {
+    pub fn new() -> Self {
+        Self { files: Vec::new(), incdirs: Vec::new(), defines: Vec::new() }
+    }
+
+    /// Reads the file list at `path` along with the lists nested within it.
+    ///
+    /// Relative paths are joined to the directory of the list that names them.
+    pub fn read(path: &Path) -> Result<Self, Fault> {
+        let mut list = Self::new();
+        list.read_nested(path, 0)?;
+        Ok(list)
+    }
+
+    fn read_nested(&mut self, path: &Path, depth: usize) -> Result<(), Fault> {
+        if depth > MAX_NESTING {
+            return Err(AnyError(format!("file lists are nested more than {} levels deep", MAX_NESTING)))?
+        }
+        let contents = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => return Err(AnyError(format!("failed to read file list {}: {}", path.display(), e)))?,
+        };
+        self.parse(&contents, path.parent().unwrap_or(Path::new("")), depth)
+    }
+
+    /// Adds the entries from the `contents` of a file list located in `dir`.
+    fn parse(&mut self, contents: &str, dir: &Path, depth: usize) -> Result<(), Fault> {
+        let mut library: Option<String> = None;
+        let mut standard: Option<VhdlStandard> = None;
+        for line in contents.lines() {
+            // remove comments
+            let line = line.split("//").next().unwrap();
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            while let Some(word) = words.next() {
+                match word {
+                    "-f" | "-F" => match words.next() {
+                        Some(list) => self.read_nested(&dir.join(expand_vars(list)?), depth + 1)?,
+                        None => return Err(AnyError(format!("missing file list after '{}'", word)))?,
+                    },
+                    "-work" | "-library" | "-lib" => library = words.next().map(|l| l.to_string()),
+                    "-2008" | "-vhdl2008" => standard = Some(VhdlStandard::Vhdl2008),
+                    "-2019" | "-vhdl2019" => standard = Some(VhdlStandard::Vhdl2019),
+                    w if w.starts_with("--work=") => library = Some(w["--work=".len()..].to_string()),
+                    w if w.starts_with("--std=") => standard = VhdlStandard::from_str(w["--std=".len()..].trim_end_matches('c')).ok(),
+                    w if w.starts_with("+incdir+") => {
+                        for inc in w["+incdir+".len()..].split('+').filter(|s| !s.is_empty()) {
+                            self.add_incdir(dir.join(expand_vars(inc)?));
+                        }
+                    },
+                    w if w.starts_with("+define+") => {
+                        for def in w["+define+".len()..].split('+').filter(|s| !s.is_empty()) {
+                            match def.split_once('=') {
+                                Some((name, value)) => self.add_define(name, Some(value)),
+                                None => self.add_define(def, None),
+                            }
+                        }
+                    },
+                    w if w.starts_with('-') || w.starts_with('+') => (),
+                    w => self.files.push(ListedFile {
+                        path: dir.join(expand_vars(w)?),
+                        library: library.clone(),
+                        standard,
+                    }),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends the file at `path` to be compiled into `library`.
+    pub fn add_file(&mut self, path: PathBuf, library: Option<&str>) {
+        self.files.push(ListedFile { path, library: library.map(|l| l.to_string()), standard: None });
+    }
+
+    /// Adds the include directory `dir` unless it is already listed.
+    pub fn add_incdir(&mut self, dir: PathBuf) {
+        if !self.incdirs.contains(&dir) {
+            self.incdirs.push(dir);
+        }
+    }
+
+    /// Defines the macro `name` with an optional `value`, replacing an earlier definition.
+    pub fn add_define(&mut self, name: &str, value: Option<&str>) {
+        let value = value.map(|v| v.to_string());
+        match self.defines.iter_mut().find(|(n, _)| n == name) {
+            Some(def) => def.1 = value,
+            None => self.defines.push((name.to_string(), value)),
+        }
+    }
+
+    /// References the file entries in list order.
+    pub fn get_files(&self) -> &Vec<ListedFile> {
+        &self.files
+    }
+
+    /// References the include directories in list order.
+    pub fn get_incdirs(&self) -> &Vec<PathBuf> {
+        &self.incdirs
+    }
+
+    /// References the macros as (name, value) in list order.
+    pub fn get_defines(&self) -> &Vec<(String, Option<String>)> {
+        &self.defines
+    }
+
+    /// Writes the list in the `.f` format.
+    ///
+    /// Include directories and macros come first, followed by the files in order. A
+    /// `-work <lib>` switch is written whenever the library changes between files.
+    pub fn write(&self) -> String {
+        let mut result = String::from("// This file is automatically generated by Orbit.\n");
+        for dir in &self.incdirs {
+            result.push_str(&format!("+incdir+{}\n", filesystem::to_standard(dir)));
+        }
+        for (name, value) in &self.defines {
+            match value {
+                Some(v) => result.push_str(&format!("+define+{}={}\n", name, v)),
+                None => result.push_str(&format!("+define+{}\n", name)),
+            }
+        }
+        let mut library: Option<&String> = None;
+        for file in &self.files {
+            if file.library.is_some() && file.library.as_ref() != library {
+                library = file.library.as_ref();
+                result.push_str(&format!("-work {}\n", library.unwrap()));
+            }
+            result.push_str(&format!("{}\n", filesystem::to_standard(&file.path)));
+        }
+        result
+    }
+}
+
+/// Checks if the `file` is a file list (ending with .f).
+pub fn is_filelist(file: &str) -> bool {
+    match file.rsplit_once('.') {
+        Some((_, ending)) => ending.eq_ignore_ascii_case("f"),
+        None => false,
+    }
+}
+
+/// Replaces the `$VAR` and `${VAR}` references in `word` with the values of environment
+/// variables.
+///
+/// Errors if a variable is not set.
+fn expand_vars(word: &str) -> Result<String, Fault> {
+    Ok(template::expand_with(word, true, |name| std::env::var(name).ok())?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let list = "\
+// compile order
++incdir+include+common/include
++define+SIM +define+WIDTH=8
+-work rary
+rtl/and_gate.vhd rtl/or_gate.vhd
+# the design
+--work=work --std=08
+rtl/top.vhd
+";
+        let mut fl = FileList::new();
+        fl.parse(list, Path::new("/soc"), 0).unwrap();
+        let files = fl.get_files();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[1].get_path(), &PathBuf::from("/soc/rtl/or_gate.vhd"));
+        assert_eq!(files[1].get_library(), Some(&String::from("rary")));
+        assert_eq!(files[1].get_standard(), None);
+        assert_eq!(files[2].get_library(), Some(&String::from("work")));
+        assert_eq!(files[2].get_standard(), Some(&VhdlStandard::Vhdl2008));
+        assert_eq!(fl.get_incdirs(), &vec![PathBuf::from("/soc/include"), PathBuf::from("/soc/common/include")]);
+        assert_eq!(fl.get_defines(), &vec![(String::from("SIM"), None), (String::from("WIDTH"), Some(String::from("8")))]);
+    }
+
+    #[test]
+    fn read_nested() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("gates")).unwrap();
+        std::fs::write(dir.path().join("gates/gates.f"), "-work rary\nand_gate.vhd\n").unwrap();
+        std::fs::write(dir.path().join("top.f"), "-f gates/gates.f\ntop.vhd\n").unwrap();
+        let fl = FileList::read(&dir.path().join("top.f")).unwrap();
+        let paths: Vec<&PathBuf> = fl.get_files().iter().map(|f| f.get_path()).collect();
+        assert_eq!(paths, vec![&dir.path().join("gates").join("and_gate.vhd"), &dir.path().join("top.vhd")]);
+        // a switch only lasts until the end of its list
+        assert_eq!(fl.get_files()[1].get_library(), None);
+
+        std::fs::write(dir.path().join("loop.f"), "-f loop.f\n").unwrap();
+        assert!(FileList::read(&dir.path().join("loop.f")).is_err());
+    }
+
+    #[test]
+    fn write() {
+        let mut fl = FileList::new();
+        fl.add_incdir(PathBuf::from("/soc/include"));
+        fl.add_define("WIDTH", Some("8"));
+        fl.add_define("WIDTH", Some("16"));
+        fl.add_file(PathBuf::from("/soc/model.sv"), None);
+        fl.add_file(PathBuf::from("/cache/gates/and_gate.vhd"), Some("rary"));
+        fl.add_file(PathBuf::from("/cache/gates/or_gate.vhd"), Some("rary"));
+        fl.add_file(PathBuf::from("/soc/top.vhd"), Some("work"));
+        assert_eq!(fl.write(), "\
+// This file is automatically generated by Orbit.
++incdir+/soc/include
++define+WIDTH=16
+/soc/model.sv
+-work rary
+/cache/gates/and_gate.vhd
+/cache/gates/or_gate.vhd
+-work work
+/soc/top.vhd
+");
+    }
+
+    #[test]
+    fn expand_env_vars() {
+        std::env::set_var("ORBIT_TEST_FILELIST_ROOT", "/soc");
+        assert_eq!(expand_vars("$ORBIT_TEST_FILELIST_ROOT/rtl/top.vhd").unwrap(), "/soc/rtl/top.vhd");
+        assert_eq!(expand_vars("${ORBIT_TEST_FILELIST_ROOT}_v2/top.vhd").unwrap(), "/soc_v2/top.vhd");
+        assert!(expand_vars("$ORBIT_TEST_FILELIST_UNSET/top.vhd").is_err());
+    }
+}
@@ -1,9 +1,15 @@
 use std::str::FromStr;
 
+/// The format version of the blueprint written by planning.
+/// 
+/// - 1: tab-separated rules of a fileset, identifier, and filepath, with an optional fourth column
+pub const BLUEPRINT_FORMAT: i64 = 1;
+
 #[derive(Debug, PartialEq)]
 pub struct Fileset {
     name: String,
-    pattern: glob::Pattern,
+    includes: Vec<glob::Pattern>,
+    excludes: Vec<glob::Pattern>,
 }
 
 #[derive(Debug)]
@@ -30,29 +36,28 @@ impl std::fmt::Display for FilesetError {
 impl FromStr for Fileset {
     type Err = FilesetError;
 
+    /// Parses a fileset from `NAME=PATTERN[,PATTERN...]`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // split by '=' sign (or ':'?)
         let result = s.split_once('=');
         if result == None {
             return Err(Self::Err::MissingSeparator('='));
         }
-        let (name, pattern) = result.unwrap();
+        let (name, patterns) = result.unwrap();
         // name cannot be empty
         if name.is_empty() {
             return Err(Self::Err::EmptyName)
         }
-        Ok(Fileset {
-            pattern: match glob::Pattern::new(pattern) {
-                // pattern must not be empty
-                Ok(p) => if p.as_str().is_empty() {
-                    return Err(Self::Err::EmptyPattern)
-                } else {
-                    p
-                },
-                Err(e) => return Err(Self::Err::PatternError(pattern.to_string(), e))
-            },
-            name: Self::standardize_name(name),
-        })
+        let mut fset = Fileset::new().name(name);
+        for pattern in patterns.split(',') {
+            // pattern must not be empty
+            if pattern.trim_start_matches(EXCLUDE_PREFIX).is_empty() {
+                return Err(Self::Err::EmptyPattern)
+            }
+            fset = fset.pattern(pattern)
+                .map_err(|e| Self::Err::PatternError(pattern.to_string(), e))?;
+        }
+        fset.verify()
     }
 }
 
@@ -61,7 +66,8 @@ impl Fileset {
     pub fn new() -> Self {
         Fileset {
             name: String::new(),
-            pattern: glob::Pattern::new("*").unwrap(),
+            includes: Vec::new(),
+            excludes: Vec::new(),
         }
     }
 
@@ -71,12 +77,34 @@ impl Fileset {
         self
     }
 
-    /// Set the `Fileset` glob-style pattern.
+    /// Adds a glob-style pattern to the `Fileset`.
+    /// 
+    /// A pattern beginning with `!` excludes the files it matches. Patterns
+    /// match at any depth unless they already begin with `**/`.
     pub fn pattern(mut self, p: &str) -> Result<Self, glob::PatternError>{
-        self.pattern = glob::Pattern::new(&("**/".to_owned() + p))?;
+        let (exclude, p) = match p.strip_prefix(EXCLUDE_PREFIX) {
+            Some(rest) => (true, rest),
+            None => (false, p),
+        };
+        let pattern = match p.starts_with("**/") {
+            true => glob::Pattern::new(p)?,
+            false => glob::Pattern::new(&("**/".to_owned() + p))?,
+        };
+        match exclude {
+            true => self.excludes.push(pattern),
+            false => self.includes.push(pattern),
+        }
         Ok(self)
     }
 
+    /// Checks the `Fileset` has at least one pattern to include files.
+    pub fn verify(self) -> Result<Self, FilesetError> {
+        match self.includes.is_empty() {
+            true => Err(FilesetError::EmptyPattern),
+            false => Ok(self),
+        }
+    }
+
     /// Standardizes the name to be UPPER-AND-HYPHENS.
     /// 
     /// The returned string is its own data (cloned from `s`).
@@ -84,7 +112,11 @@ impl Fileset {
         s.to_uppercase().replace('_', "-")
     }
 
-    /// Uses the given pattern to return a set of build files.
+    /// Uses the given patterns to return a set of build files.
+    /// 
+    /// A file is collected when it matches any include pattern and no exclude
+    /// pattern. The files are returned in lexicographic order by their
+    /// normalized path so the blueprint is the same across filesystems.
     pub fn collect_files<'a>(&self, files: &'a [String]) -> Vec<&'a String> {
         let match_opts = glob::MatchOptions {
             case_sensitive: false,
@@ -92,13 +124,18 @@ impl Fileset {
             require_literal_leading_dot: false,
         };
 
-        files.iter().filter_map(|f| {
-            if self.pattern.matches_with(&f, match_opts) == true {
-                Some(f)
+        let mut found: Vec<(String, &String)> = files.iter().filter_map(|f| {
+            let path = crate::util::filesystem::to_standard(f);
+            if self.includes.iter().any(|p| p.matches_with(&path, match_opts)) == true && 
+                self.excludes.iter().any(|p| p.matches_with(&path, match_opts)) == false {
+                Some((path, f))
             } else {
                 None
             }
-        }).collect()
+        }).collect();
+        found.sort();
+        found.dedup_by(|a, b| a.0 == b.0);
+        found.into_iter().map(|(_, f)| f).collect()
     }
 
     /// Access name.
@@ -106,9 +143,13 @@ impl Fileset {
         &self.name
     }
 
-    /// Access pattern.
-    pub fn get_pattern(&self) -> &glob::Pattern {
-        &self.pattern
+    /// Lists the patterns as they are written, with the exclude patterns
+    /// beginning with `!` following the include patterns.
+    pub fn get_patterns(&self) -> Vec<String> {
+        self.includes.iter()
+            .map(|p| p.to_string())
+            .chain(self.excludes.iter().map(|p| format!("{}{}", EXCLUDE_PREFIX, p)))
+            .collect()
     }
 
     /// Creates format for blueprint.tsv file.
@@ -132,6 +173,9 @@ impl Fileset {
     }
 }
 
+/// Beginning of a pattern that excludes the files it matches from a fileset.
+const EXCLUDE_PREFIX: char = '!';
+
 /// Checks if the `file` is a VHDL file (ending with .vhd or .vhdl).
 pub fn is_vhdl(file: &str) -> bool {
     if let Some((_, ending)) = file.rsplit_once('.') {
@@ -204,7 +248,8 @@ mod test {
         let fset = Fileset::from_str(s);
         assert_eq!(fset.unwrap(), Fileset {
             name: "XSIM-CFG".to_string(),
-            pattern: glob::Pattern::new("*.wcfg").unwrap()
+            includes: vec![glob::Pattern::new("**/*.wcfg").unwrap()],
+            excludes: Vec::new(),
         });
 
         let s = "xsim-cfg=";
@@ -235,4 +280,25 @@ mod test {
         let s: &str = "vhdl_rtl";
         assert_eq!(Fileset::standardize_name(s), "VHDL-RTL");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn exclude_patterns() {
+        let files: Vec<String> = vec![
+            "/ip/sim/tb/adder_tb.vhd",
+            "/ip/rtl/adder.vhd",
+            "/ip/rtl/Add.vhd",
+            "/ip/tb/mux_tb.vhd",
+            "/ip/rtl/mux.vhd",
+        ].into_iter().map(String::from).collect();
+        let fset = Fileset::from_str("rtl=*.vhd,!**/tb/**").unwrap();
+        assert_eq!(fset.get_patterns(), vec!["**/*.vhd", "!**/tb/**"]);
+        // files are ordered lexicographically by path
+        assert_eq!(fset.collect_files(&files), vec!["/ip/rtl/Add.vhd", "/ip/rtl/adder.vhd", "/ip/rtl/mux.vhd"]);
+        // multiple include patterns do not collect a file twice
+        let fset = Fileset::from_str("all=*.vhd,rtl/*.vhd").unwrap();
+        assert_eq!(fset.collect_files(&files).len(), 5);
+        // a fileset must include files
+        assert!(Fileset::from_str("none=!*.vhd").is_err());
+        assert!(Fileset::from_str("none=*.vhd,!").is_err());
+    }
+}
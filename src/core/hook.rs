@@ -1,5 +1,7 @@
 use std::ffi::OsStr;
-use crate::util::anyerror::Fault;
+use std::path::Path;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::environment::Environment;
 
 #[derive(Debug, PartialEq)]
 struct Statement(Vec<Argument>);
@@ -85,17 +87,20 @@ impl std::fmt::Display for Statement {
 }
 
 impl Statement {
-    fn execute(&self) -> Result<(), Fault> {
+    /// Runs the statement from the directory `dir` with the variables of `env` set.
+    fn execute(&self, env: &Environment, dir: &Path) -> Result<(), Fault> {
         println!("hook:{}", self);
         let proc = std::process::Command::new(&self.0.first().unwrap().as_string())
             .args(&self.0.as_slice()[1..])
+            .envs(env.iter().map(|e| (e.get_key(), e.get_value())))
+            .current_dir(dir)
             .output()?;
 
         match proc.status.code() {
-            Some(num) => if num != 0 { panic!("error code") } else { () },
-            None => panic!("sig termination"),
-        };
-        Ok(())
+            Some(0) => Ok(()),
+            Some(num) => Err(AnyError(format!("hook command '{}' exited with error code {}\n\n{}", self.to_string().trim_start(), num, String::from_utf8_lossy(&proc.stderr).trim_end())))?,
+            None => Err(AnyError(format!("hook command '{}' was terminated by a signal", self.to_string().trim_start())))?,
+        }
     }
 }
 
@@ -110,9 +115,12 @@ impl std::str::FromStr for Hook {
 }
 
 impl Hook {
-    pub fn execute(&self) -> Result<(), Fault> {
+    /// Runs each statement in order from the directory `dir` with the variables of `env` set.
+    /// 
+    /// Stops at the first statement that fails.
+    pub fn execute(&self, env: &Environment, dir: &Path) -> Result<(), Fault> {
         for stmt in &self.0 {
-            stmt.execute()?
+            stmt.execute(env, dir)?
         }
         Ok(())
     }
@@ -142,6 +150,19 @@ mod test {
         ]);
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn execute_with_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = Environment::new().add(crate::util::environment::EnvVar::new().key("ORBIT_IP").value("ks-tech.rary.gates"));
+        let hook = Hook::from_str("sh -c 'echo $ORBIT_IP > fetched.txt'").unwrap();
+        hook.execute(&env, dir.path()).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("fetched.txt")).unwrap(), "ks-tech.rary.gates\n");
+
+        let hook = Hook::from_str("sh -c 'exit 3'").unwrap();
+        assert!(hook.execute(&env, dir.path()).is_err());
+    }
+
     #[test]
     fn statement_from_str() {
         let text = "git commit -m \"my message\"";
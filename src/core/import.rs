@@ -0,0 +1,403 @@
+//! Importers for migrating the file lists of existing tool projects into an ip.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use crate::util::anyerror::{AnyError, Fault};
+use super::filelist::FileList;
+use super::vhdl::standard::VhdlStandard;
+
+/// Libraries that tools assign to files when none is chosen.
+const DEFAULT_LIBRARIES: [&str; 2] = ["work", "xil_defaultlib"];
+
+/// A tool project format that file lists can be imported from.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Source {
+    /// Vivado: a `.xpr` project or a tcl script of `add_files`/`read_*` commands
+    Vivado,
+    /// a `.f` file list of paths and `-work` switches
+    FileList,
+}
+
+impl FromStr for Source {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "vivado" => Ok(Self::Vivado),
+            "filelist" | "f" => Ok(Self::FileList),
+            _ => Err(AnyError(format!("unknown source '{}'; expecting 'vivado' or 'filelist'", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Vivado => write!(f, "vivado"),
+            Self::FileList => write!(f, "filelist"),
+        }
+    }
+}
+
+/// A file listed by a tool project.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SourceFile {
+    path: PathBuf,
+    library: Option<String>,
+    standard: Option<VhdlStandard>,
+    constraint: bool,
+}
+
+impl SourceFile {
+    fn new(path: PathBuf) -> Self {
+        Self { path, library: None, standard: None, constraint: false }
+    }
+
+    /// References the path to the file as written by the project, joined to the project's directory.
+    pub fn get_path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// References the library the file was compiled into, if not the tool's default library.
+    pub fn get_library(&self) -> Option<&String> {
+        self.library.as_ref()
+    }
+
+    /// References the VHDL standard the project compiled the file with, if given.
+    pub fn get_standard(&self) -> Option<&VhdlStandard> {
+        self.standard.as_ref()
+    }
+
+    /// Checks if the project read the file as a constraint file.
+    pub fn is_constraint(&self) -> bool {
+        self.constraint
+    }
+
+    /// Checks if the file is named by `pattern`, which is either a path relative to `dir` or a
+    /// bare filename.
+    fn matches(&self, pattern: &str, dir: &Path) -> bool {
+        self.path == dir.join(pattern) || self.path.file_name().map(|n| n == pattern).unwrap_or(false)
+    }
+
+    fn set_library(&mut self, lib: &str) {
+        self.library = match DEFAULT_LIBRARIES.iter().any(|d| d.eq_ignore_ascii_case(lib)) {
+            true => None,
+            false => Some(lib.to_string()),
+        };
+    }
+}
+
+/// The files and device read from a tool project.
+#[derive(Debug, PartialEq)]
+pub struct Project {
+    files: Vec<SourceFile>,
+    part: Option<String>,
+}
+
+impl Project {
+    /// References the files in the order the project listed them.
+    pub fn get_files(&self) -> &Vec<SourceFile> {
+        &self.files
+    }
+
+    /// References the device part number, if the project set one.
+    pub fn get_part(&self) -> Option<&String> {
+        self.part.as_ref()
+    }
+
+    /// Finds the library shared by the most files that were given one.
+    pub fn common_library(&self) -> Option<&String> {
+        let mut counts: Vec<(&String, usize)> = Vec::new();
+        for lib in self.files.iter().filter_map(|f| f.library.as_ref()) {
+            match counts.iter_mut().find(|(l, _)| l == &lib) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((lib, 1)),
+            }
+        }
+        // the earliest listed library wins a tie
+        counts.iter().rev().max_by_key(|(_, n)| *n).map(|(l, _)| *l)
+    }
+
+    /// Finds the index of the file at `path`, adding it to the end of the list when it was not
+    /// already listed.
+    fn entry(&mut self, path: PathBuf) -> usize {
+        match self.files.iter().position(|f| f.path == path) {
+            Some(i) => i,
+            None => {
+                self.files.push(SourceFile::new(path));
+                self.files.len() - 1
+            },
+        }
+    }
+}
+
+impl Source {
+    /// Reads the files of the project at `path`.
+    ///
+    /// Relative paths within the project are joined to the directory holding `path`.
+    pub fn read(&self, path: &Path) -> Result<Project, Fault> {
+        match self {
+            Self::Vivado => {
+                let contents = match std::fs::read_to_string(path) {
+                    Ok(c) => c,
+                    Err(e) => return Err(AnyError(format!("failed to read project file {}: {}", path.display(), e)))?,
+                };
+                let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+                let is_xpr = path.extension().map(|x| x.eq_ignore_ascii_case("xpr")).unwrap_or(false);
+                match is_xpr {
+                    true => {
+                        // sources created from the gui are stored in the project's '.srcs' directory
+                        let srcs = dir.join(format!("{}.srcs", path.file_stem().unwrap().to_string_lossy()));
+                        Ok(read_xpr(&contents, &dir, &srcs))
+                    },
+                    false => Ok(read_tcl(&contents, &dir)),
+                }
+            },
+            Self::FileList => {
+                let mut project = Project { files: Vec::new(), part: None };
+                for listed in FileList::read(path)?.get_files() {
+                    let i = project.entry(listed.get_path().clone());
+                    let file = &mut project.files[i];
+                    if let Some(lib) = listed.get_library() { file.set_library(lib) }
+                    if listed.get_standard().is_some() { file.standard = listed.get_standard().cloned() }
+                }
+                Ok(project)
+            }
+        }
+    }
+}
+
+/// Finds the value of the xml attribute `name` within the `line`.
+fn xml_attr<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!(" {}=\"", name);
+    let start = line.find(&key)? + key.len();
+    let len = line[start..].find('"')?;
+    Some(&line[start..start + len])
+}
+
+/// Reads the files and part from the contents of a Vivado `.xpr` project.
+///
+/// `$PPRDIR` is replaced with the project's directory `dir` and `$PSRCDIR` with its sources
+/// directory `srcs`.
+fn read_xpr(contents: &str, dir: &Path, srcs: &Path) -> Project {
+    let mut project = Project { files: Vec::new(), part: None };
+    let mut constrs = false;
+    let mut current: Option<usize> = None;
+    for line in contents.lines().map(|l| l.trim()) {
+        if line.starts_with("<Option ") && xml_attr(line, "Name") == Some("Part") {
+            project.part = xml_attr(line, "Val").map(|p| p.to_string());
+        } else if line.starts_with("<FileSet ") {
+            constrs = xml_attr(line, "Type") == Some("Constrs");
+        } else if line.starts_with("<File ") {
+            let path = match xml_attr(line, "Path") {
+                Some(p) => p,
+                None => continue,
+            };
+            let path = path.replace("$PPRDIR", &dir.to_string_lossy()).replace("$PSRCDIR", &srcs.to_string_lossy());
+            let i = project.entry(PathBuf::from(path));
+            project.files[i].constraint = constrs;
+            current = match line.ends_with("/>") {
+                true => None,
+                false => Some(i),
+            };
+        } else if line.starts_with("</File>") {
+            current = None;
+        } else if let Some(i) = current {
+            if line.starts_with("<FileInfo ") {
+                if let Some(ty) = xml_attr(line, "SFType") {
+                    project.files[i].standard = match ty {
+                        "VHDL2008" => Some(VhdlStandard::Vhdl2008),
+                        "VHDL2019" => Some(VhdlStandard::Vhdl2019),
+                        _ => None,
+                    };
+                }
+            } else if line.starts_with("<Attr ") && xml_attr(line, "Name") == Some("Library") {
+                if let Some(lib) = xml_attr(line, "Val") {
+                    project.files[i].set_library(lib);
+                }
+            }
+        }
+    }
+    project
+}
+
+/// Splits a line of tcl into words, keeping the contents of braces, quotes, and
+/// brackets together.
+fn tcl_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0;
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '{' | '[' if !quoted => {
+                if depth > 0 { word.push(c) }
+                depth += 1;
+            },
+            '}' | ']' if !quoted && depth > 0 => {
+                depth -= 1;
+                if depth > 0 { word.push(c) }
+            },
+            '"' if depth == 0 => quoted = !quoted,
+            ' ' | '\t' if depth == 0 && !quoted => {
+                if !word.is_empty() { words.push(std::mem::take(&mut word)) }
+            },
+            _ => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Reads the files and part from the commands of a Vivado tcl script.
+///
+/// Recognizes `add_files`, `read_vhdl`, `read_verilog`, `read_xdc`, `set_property library`,
+/// `set_property file_type`, `set_part`, and `create_project -part`. Commands built from
+/// variables or other commands are skipped.
+fn read_tcl(contents: &str, dir: &Path) -> Project {
+    let mut project = Project { files: Vec::new(), part: None };
+    // join lines continued with a trailing backslash
+    let contents = contents.replace("\\\r\n", " ").replace("\\\n", " ");
+    for line in contents.lines().map(|l| l.trim()) {
+        if line.starts_with('#') {
+            continue;
+        }
+        let words = tcl_words(line);
+        let (cmd, args) = match words.split_first() {
+            Some((cmd, args)) => (cmd.as_str(), args),
+            None => continue,
+        };
+        match cmd {
+            "add_files" | "read_vhdl" | "read_verilog" | "read_xdc" => {
+                let mut library = None;
+                let mut standard = None;
+                let mut constraint = cmd == "read_xdc";
+                let mut iter = args.iter();
+                while let Some(arg) = iter.next() {
+                    match arg.as_str() {
+                        "-library" => library = iter.next(),
+                        "-fileset" => constraint = iter.next().map(|f| f.starts_with("constrs")).unwrap_or(false),
+                        "-vhdl2008" => standard = Some(VhdlStandard::Vhdl2008),
+                        "-vhdl2019" => standard = Some(VhdlStandard::Vhdl2019),
+                        a if a.starts_with('-') => (),
+                        a if a.contains('$') => (),
+                        a => for path in a.split_whitespace() {
+                            let i = project.entry(dir.join(path));
+                            let file = &mut project.files[i];
+                            file.constraint = constraint || Path::new(path).extension().map(|x| x.eq_ignore_ascii_case("xdc")).unwrap_or(false);
+                            if let Some(lib) = library { file.set_library(lib) }
+                            if standard.is_some() { file.standard = standard }
+                        },
+                    }
+                }
+            },
+            "set_property" if args.len() >= 3 => {
+                // set_property <name> <value> [get_files <file>...]
+                let query = tcl_words(&args[2]);
+                let files: Vec<&String> = match query.split_first() {
+                    Some((q, files)) if q == "get_files" => files.iter().filter(|f| !f.starts_with('-')).collect(),
+                    _ => continue,
+                };
+                for file in project.files.iter_mut().filter(|f| files.iter().any(|p| f.matches(p, dir))) {
+                    match (args[0].as_str(), args[1].as_str()) {
+                        ("library", lib) => file.set_library(lib),
+                        ("file_type", "VHDL 2008") => file.standard = Some(VhdlStandard::Vhdl2008),
+                        ("file_type", "VHDL 2019") => file.standard = Some(VhdlStandard::Vhdl2019),
+                        _ => (),
+                    }
+                }
+            },
+            "set_part" => project.part = args.first().cloned(),
+            "create_project" => {
+                if let Some(i) = args.iter().position(|a| a == "-part") {
+                    project.part = args.get(i + 1).cloned();
+                }
+            },
+            _ => (),
+        }
+    }
+    project
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const XPR: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Project Version="7" Minor="44" Path="/home/user/soc/soc.xpr">
+  <Configuration>
+    <Option Name="Part" Val="xc7a35ticsg324-1L"/>
+  </Configuration>
+  <FileSets Version="1" Minor="31">
+    <FileSet Name="sources_1" Type="DesignSrcs" RelSrcDir="$PSRCDIR/sources_1">
+      <File Path="$PPRDIR/../rtl/top.vhd">
+        <FileInfo SFType="VHDL2008">
+          <Attr Name="Library" Val="xil_defaultlib"/>
+        </FileInfo>
+      </File>
+      <File Path="$PSRCDIR/sources_1/new/gates.vhd">
+        <FileInfo>
+          <Attr Name="Library" Val="rary"/>
+        </FileInfo>
+      </File>
+    </FileSet>
+    <FileSet Name="constrs_1" Type="Constrs" RelSrcDir="$PSRCDIR/constrs_1">
+      <File Path="$PPRDIR/arty.xdc">
+        <FileInfo>
+          <Attr Name="UsedIn" Val="implementation"/>
+        </FileInfo>
+      </File>
+    </FileSet>
+  </FileSets>
+</Project>
+"#;
+
+    #[test]
+    fn from_xpr() {
+        let project = read_xpr(XPR, Path::new("/soc"), Path::new("/soc/soc.srcs"));
+        assert_eq!(project.get_part(), Some(&String::from("xc7a35ticsg324-1L")));
+        let files = project.get_files();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].get_path(), &PathBuf::from("/soc/../rtl/top.vhd"));
+        assert_eq!(files[0].get_library(), None);
+        assert_eq!(files[0].get_standard(), Some(&VhdlStandard::Vhdl2008));
+        assert_eq!(files[1].get_path(), &PathBuf::from("/soc/soc.srcs/sources_1/new/gates.vhd"));
+        assert_eq!(files[1].get_library(), Some(&String::from("rary")));
+        assert_eq!(files[2].is_constraint(), true);
+        assert_eq!(files[0].is_constraint(), false);
+        assert_eq!(project.common_library(), Some(&String::from("rary")));
+    }
+
+    #[test]
+    fn from_tcl() {
+        let tcl = "\
+# build script
+create_project soc ./build -part xc7a35ticsg324-1L
+read_vhdl -library rary {rtl/and_gate.vhd rtl/or_gate.vhd}
+add_files -norecurse rtl/top.vhd \\
+    rtl/uart.vhd
+add_files -fileset constrs_1 boards/arty.xdc
+read_xdc \"timing.xdc\"
+add_files $src_dir/gen.vhd
+set_property library uart [get_files uart.vhd]
+set_property file_type {VHDL 2008} [get_files {rtl/top.vhd}]
+";
+        let project = read_tcl(tcl, Path::new("/soc"));
+        assert_eq!(project.get_part(), Some(&String::from("xc7a35ticsg324-1L")));
+        let files: Vec<(&Path, Option<&String>, Option<&VhdlStandard>, bool)> = project.get_files().iter()
+            .map(|f| (f.get_path().as_path(), f.get_library(), f.get_standard(), f.is_constraint()))
+            .collect();
+        let rary = String::from("rary");
+        let uart = String::from("uart");
+        assert_eq!(files, vec![
+            (Path::new("/soc/rtl/and_gate.vhd"), Some(&rary), None, false),
+            (Path::new("/soc/rtl/or_gate.vhd"), Some(&rary), None, false),
+            (Path::new("/soc/rtl/top.vhd"), None, Some(&VhdlStandard::Vhdl2008), false),
+            (Path::new("/soc/rtl/uart.vhd"), Some(&uart), None, false),
+            (Path::new("/soc/boards/arty.xdc"), None, None, true),
+            (Path::new("/soc/timing.xdc"), None, None, true),
+        ]);
+    }
+}
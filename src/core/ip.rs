@@ -5,42 +5,56 @@ use tempfile::tempdir;
 
 use crate::core::catalog::CacheSlot;
 use crate::core::manifest;
-use crate::util::graphmap::GraphMap;
-use crate::util::overdetsys;
-use crate::core::pkgid::PkgPart;
-use crate::util::anyerror::{AnyError, Fault};
+use crate::util::graphmap::{GraphMap, Node};
+use crate::util::anyerror::{AnyError, Category, CategoryError, Fault};
 use super::catalog::Catalog;
 use super::lockfile::{LockEntry, LockFile};
 use super::manifest::IpManifest;
 use super::pkgid::PkgId;
-use super::version::Version;
-use super::vhdl::dst;
+use super::version::{AnyVersion, Version};
+use super::lexer::Position;
+use super::vhdl::dst::{self, Rename};
 use super::vhdl::primaryunit::{VhdlIdentifierError, PrimaryUnit};
 use super::vhdl::token::{Identifier, VHDLTokenizer};
 
 /// Given a partial/full ip specification `ip_spec`, sift through the manifests
 /// for a possible determined unique solution.
 /// 
-/// Note: Currently clones each id, possibly look for faster implemtenation avoiding clone.
-pub fn find_ip(ip_spec: &PkgId, universe: Vec<&PkgId>) -> Result<PkgId, AnyError> {
-    // try to find ip name
-    let space: Vec<Vec<PkgPart>> = universe.into_iter().map(|f| { f.into_full_vec().unwrap() }).collect();
-    let result = match overdetsys::solve(space, ip_spec.iter()) {
-        Ok(r) => r,
-        Err(e) => match e {
-            overdetsys::OverDetSysError::NoSolution => Err(AnyError(format!("no ip as '{}' exists", ip_spec)))?,
-            overdetsys::OverDetSysError::Ambiguous(set) => {
-                // assemble error message
-                let mut set = set.into_iter().map(|f| PkgId::from_vec(f) );
-                let mut content = String::new();
-                while let Some(s) = set.next() {
-                    content.push_str(&format!("    {}\n", s.to_string()));
-                }
-                Err(AnyError(format!("ambiguous ip '{}' yields multiple solutions:\n{}", ip_spec, content)))?
-            }
-        }
-    };
-    Ok(PkgId::from_vec(result))
+/// Parts are compared without regard to case, and empty parts match any value.
+/// When no ip matches, the given parts are completed as the beginnings of the
+/// parts in the `universe`.
+pub fn find_ip(ip_spec: &PkgId, universe: Vec<&PkgId>) -> Result<PkgId, Fault> {
+    let mut space: Vec<&PkgId> = universe.iter().copied().filter(|id| ip_spec.partial_match(id)).collect();
+    if space.is_empty() {
+        space = universe.iter().copied().filter(|id| ip_spec.prefix_match(id)).collect();
+    }
+    match space.len() {
+        0 => Err(CategoryError(Category::Resolution, format!("no ip as '{}' exists", ip_spec)))?,
+        1 => Ok(space.pop().unwrap().clone()),
+        _ => Err(ambiguous_ip(ip_spec, space))?,
+    }
+}
+
+/// Finds the ip named by the fully qualified `ip_spec` without completing any
+/// of its parts.
+pub fn find_exact_ip(ip_spec: &PkgId, universe: Vec<&PkgId>) -> Result<PkgId, Fault> {
+    if let Err(e) = ip_spec.fully_qualified() {
+        return Err(CategoryError(Category::Resolution, format!("ip '{}' is {}; an exact pkgid is written as <vendor>.<library>.<name>", ip_spec, e)))?
+    }
+    match universe.into_iter().find(|id| *id == ip_spec) {
+        Some(id) => Ok(id.clone()),
+        None => Err(CategoryError(Category::Resolution, format!("no ip as '{}' exists", ip_spec)))?,
+    }
+}
+
+/// Creates the error listing every ip in `space` that the `ip_spec` could refer to.
+fn ambiguous_ip(ip_spec: &PkgId, mut space: Vec<&PkgId>) -> CategoryError {
+    space.sort();
+    let mut content = String::new();
+    for id in &space {
+        content.push_str(&format!("    {}\n", id));
+    }
+    CategoryError(Category::Resolution, format!("ambiguous ip '{}' yields multiple solutions:\n{}\nTry qualifying the pkgid with its vendor and library, such as '{}'", ip_spec, content, space[0]))
 }
 
 /// Constructs an ip-graph from a lockfile.
@@ -51,16 +65,29 @@ pub fn graph_ip_from_lock(lock: &LockFile) -> Result<GraphMap<IpSpec, &LockEntry
         graph.add_node(f.to_ip_spec(), f);
     });
     // add all edges
-    lock.inner().iter().for_each(|upper| {
+    for upper in lock.inner() {
         // get list of dependencies
         if let Some(deps) = upper.get_deps() {
             for dep in deps {
-                // determine the most compatible entry for this dependency
-                let lower = lock.get_highest(&dep.0, &dep.1).unwrap();
+                // use the resolved entry, otherwise determine the most compatible entry for this dependency
+                let lower = match dep.get_resolved() {
+                    Some(v) => match lock.get(dep.get_name(), v) {
+                        Some(l) => l,
+                        None => return Err(AnyError(format!("lockfile entry for ip {} v{} resolves dependency {} to version {}, which has no entry in the lockfile
+
+Try updating the lockfile: `orbit plan --lock-only`", upper.get_name(), upper.get_version(), dep.get_name(), v)))?,
+                    },
+                    None => match lock.get_highest(dep.get_name(), dep.get_version()) {
+                        Some(l) => l,
+                        None => return Err(AnyError(format!("lockfile entry for ip {} v{} depends on {} v{}, which has no compatible entry in the lockfile
+
+Try updating the lockfile: `orbit plan --lock-only`", upper.get_name(), upper.get_version(), dep.get_name(), dep.get_version())))?,
+                    },
+                };
                 graph.add_edge_by_key(&lower.to_ip_spec(), &upper.to_ip_spec(), ());
             }
         }
-    });
+    }
     Ok(graph)
 }
 
@@ -79,43 +106,69 @@ fn graph_ip<'a>(root: &'a IpManifest, catalog: &'a Catalog<'a>) -> Result<GraphM
     root.collect_units(true)?
         .into_iter()
         .for_each(|(key, unit)| { iden_set.insert(key, unit); } );
+    let root_units: Vec<Identifier> = iden_set.keys().cloned().collect();
+    // remember the ip declaring each identifier for units read without their source file
+    let mut owners: HashMap<Identifier, &IpManifest> = HashMap::new();
 
     let mut is_root: bool = true;
 
+    // versions recorded in the lock file keep resolving even after they are yanked
+    let lock = match !catalog.is_ignoring_locks() && (root.can_use_lock() || catalog.is_preferring_locks()) {
+        true => root.get_lockfile(),
+        false => None,
+    };
+
     while let Some((num, ip)) = processing.pop() {
         // read dependencies
         let deps = ip.get_dependencies();
         for (pkgid, version) in deps.inner() {
+            let pkgid = catalog.resolve_alias(pkgid);
             match catalog.inner().get(pkgid) {
                 Some(status) => {
                     // find this IP to read its dependencies
-                    match status.get(version, true) {
+                    let is_locked = |v: &Version| lock.as_ref().map(|l| l.get(pkgid, v).is_some()).unwrap_or(false);
+                    // keep the locked version when syncing as long as it still satisfies the requirement
+                    let locked = match catalog.is_preferring_locks() {
+                        true => lock.as_ref()
+                            .and_then(|l| l.get_highest(pkgid, version))
+                            .and_then(|e| status.get(&AnyVersion::Specific(e.get_version().to_partial_version()), true)),
+                        false => None,
+                    };
+                    match locked.or_else(|| status.get_resolvable(version, &is_locked)) {
                         Some(dep) => {
+                            manifest::check_orbit_version(dep.get_manifest())?;
                             // check if node is already in graph ????
                             let s = if let Some(existing_node) = g.get_node_by_key(&dep.into_ip_spec()) {
                                 existing_node.index()
                             } else {
                                 // check if identifiers are already taken in graph
                                 let units = dep.collect_units(false)?;
-                                let dst = if let Some(dupe) = units
-                                        .iter()
-                                        .find(|(key, _)| iden_set.contains_key(key)) {
-                                    let dupe = iden_set.get(dupe.0).unwrap();
-                                    if is_root == true {
+                                let dupes: Vec<&Identifier> = units.keys().filter(|key| iden_set.contains_key(key)).collect();
+                                let dst = if dupes.is_empty() {
+                                    false
+                                } else if is_root == true {
+                                    // direct dependencies may both keep a unit when the root chooses its provider
+                                    if let Some(key) = dupes.into_iter().find(|key| root_units.contains(key) || root.get_providers().get(key).is_none()) {
+                                        let dupe = iden_set.get(key).unwrap();
+                                        let location = match (dupe.get_unit().get_source_code_file(), owners.get(key)) {
+                                            ("", Some(owner)) => owner.get_root(),
+                                            (file, _) => PathBuf::from(file),
+                                        };
                                         return Err(VhdlIdentifierError::DuplicateAcrossDirect(
                                             dupe.get_iden().clone(), 
                                             dep.into_ip_spec(),
-                                            PathBuf::from(dupe.get_unit().get_source_code_file().clone()),
-                                            dupe.get_unit().get_symbol().unwrap().get_position().clone()
+                                            location,
+                                            dupe.get_unit().get_symbol().map(|s| s.get_position().clone())
                                         ))?
                                     }
-                                    true
-                                } else {
                                     false
+                                } else {
+                                    true
                                 };
                                 // update the hashset with the new unique non-taken identifiers
                                 if dst == false {
                                     for (key, unit) in units {
+                                        owners.insert(key.clone(), dep);
                                         iden_set.insert(key, unit);
                                     }
                                 }
@@ -125,12 +178,14 @@ fn graph_ip<'a>(root: &'a IpManifest, catalog: &'a Catalog<'a>) -> Result<GraphM
                             g.add_edge_by_index(s, num, ());
                             processing.push((s, dep));
                         },
+                        // only yanked versions are installed
+                        None if status.get(version, true).is_some() => return Err(CategoryError(Category::Resolution, format!("ip '{} v{}' is only installed as yanked versions\n\nTry installing a version that is not yanked with `orbit install`", pkgid, version)))?,
                         // todo: try to use the lock file to fill in missing pieces
-                        None => return Err(AnyError(format!("ip '{} v{}' is not installed", pkgid, version)))?,
+                        None => return Err(CategoryError(Category::Resolution, format!("ip '{} v{}' is not installed", pkgid, version)))?,
                     }
                 },
                 // todo: try to use the lock file to fill in missing pieces
-                None => return Err(AnyError(format!("unknown ip: {}", pkgid)))?,
+                None => return Err(CategoryError(Category::Resolution, format!("unknown ip: {}", pkgid)))?,
             }
         }
         is_root = false;
@@ -145,7 +200,7 @@ pub fn compute_final_ip_graph<'a>(target: &'a IpManifest, catalog: &'a Catalog<'
     let mut rough_ip_graph = graph_ip(&target, &catalog)?;
     
     // keep track of list of neighbors that must perform dst and their lookup-tables to use after processing all direct impacts
-    let mut transforms = HashMap::<IpSpec, HashMap<Identifier, String>>::new();
+    let mut transforms = HashMap::<IpSpec, HashMap<Identifier, Rename>>::new();
 
     // iterate through the graph to find all DST nodes to create their replacements
     {
@@ -194,12 +249,14 @@ pub fn compute_final_ip_graph<'a>(target: &'a IpManifest, catalog: &'a Catalog<'
 }
 
 /// Take the ip graph and create the entire space of VHDL files that could be used for the current design.
+/// 
+/// The files listed by each ip's external units are included as well.
 pub fn build_ip_file_list<'a>(ip_graph: &'a GraphMap<IpSpec, IpNode<'a>, ()>) -> Vec<IpFileNode<'a>> {
     let mut files = Vec::new();
-    ip_graph.get_map().iter().for_each(|(_, ip)| {
+    sorted_ips(ip_graph).into_iter().for_each(|ip| {
         crate::util::filesystem::gather_current_files(&ip.as_ref().as_ip().get_root())
             .into_iter()
-            .filter(|f| crate::core::fileset::is_vhdl(f) )
+            .filter(|f| crate::core::fileset::is_vhdl(f) || ip.as_ref().as_ip().is_external_file(f))
             .for_each(|f| {
                 files.push(IpFileNode { file: f, ip: ip.as_ref().as_ip(), library: ip.as_ref().get_library().clone() });
             })
@@ -207,12 +264,42 @@ pub fn build_ip_file_list<'a>(ip_graph: &'a GraphMap<IpSpec, IpNode<'a>, ()>) ->
     files
 }
 
+/// Lists the ip of the `ip_graph` ordered by their specs, so the files collected from them
+/// are in the same order every time the graph is planned.
+fn sorted_ips<'a, 'b>(ip_graph: &'b GraphMap<IpSpec, IpNode<'a>, ()>) -> Vec<&'b Node<IpNode<'a>>> {
+    let mut ips: Vec<(&IpSpec, &Node<IpNode>)> = ip_graph.get_map().iter().collect();
+    ips.sort_by(|a, b| a.0.cmp(b.0));
+    ips.into_iter().map(|(_, ip)| ip).collect()
+}
+
+/// Lists the outputs of each ip's generators that do not exist yet.
+/// 
+/// Outputs that were already generated are found by `build_ip_file_list`.
+pub fn build_generated_file_list<'a>(ip_graph: &'a GraphMap<IpSpec, IpNode<'a>, ()>) -> Vec<IpFileNode<'a>> {
+    let mut files: Vec<IpFileNode> = Vec::new();
+    sorted_ips(ip_graph).into_iter().for_each(|ip| {
+        let manifest = ip.as_ref().as_ip();
+        for generator in manifest.get_generators().inner().values() {
+            for output in generator.get_outputs() {
+                let path = manifest.get_root().join(output);
+                let file = path.display().to_string();
+                if !path.exists() && !files.iter().any(|f| f.file == file) {
+                    files.push(IpFileNode { file, ip: manifest, library: ip.as_ref().get_library().clone() });
+                }
+            }
+        }
+    });
+    files
+}
+
 #[derive(Debug, PartialEq)]
 pub struct IpNode<'a> {
     dyn_state: DynState,
     original: &'a IpManifest,
     transform: Option<IpManifest>,
     library: Identifier,
+    // `work` references left unchanged by the transform as (file, name, position)
+    unresolved: Vec<(PathBuf, Identifier, Position)>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -223,11 +310,11 @@ pub enum DynState {
 
 impl<'a> IpNode<'a> {
     fn new_keep(og: &'a IpManifest, lib: Identifier) -> Self {
-        Self { dyn_state: DynState::Keep, original: og, transform: None, library: lib }
+        Self { dyn_state: DynState::Keep, original: og, transform: None, library: lib, unresolved: Vec::new() }
     }
 
     fn new_alter(og: &'a IpManifest, lib: Identifier) -> Self {
-        Self { dyn_state: DynState::Alter, original: og, transform: None, library: lib }
+        Self { dyn_state: DynState::Alter, original: og, transform: None, library: lib, unresolved: Vec::new() }
     }
 
     /// References the internal `IpManifest` struct.
@@ -251,6 +338,12 @@ impl<'a> IpNode<'a> {
         &self.library
     }
 
+    /// References the `work` references to renamed units that the transform left unchanged
+    /// because the ip is compiled into a different library, as (file, name, position).
+    pub fn get_unresolved(&self) -> &Vec<(PathBuf, Identifier, Position)> {
+        &self.unresolved
+    }
+
     /// Checks if an ip is a direct result requiring DST.
     fn is_direct_conflict(&self) -> bool {
         match &self.dyn_state {
@@ -265,7 +358,7 @@ impl<'a> IpNode<'a> {
     /// it returns the original manifest.
     /// 
     /// Note: this function can only be applied ip that are already installed to the cache.
-    fn dynamic_symbol_transform(&mut self, lut: &HashMap<Identifier, String>, cache_path: &PathBuf) -> () {
+    fn dynamic_symbol_transform(&mut self, lut: &HashMap<Identifier, Rename>, cache_path: &PathBuf) -> () {
         // create a temporary directory
         let temp = tempdir().unwrap();
         let temp_path = temp.path().to_path_buf();
@@ -285,9 +378,14 @@ impl<'a> IpNode<'a> {
                 let code = std::fs::read_to_string(&vhdl_path).unwrap();
                 let tokens = VHDLTokenizer::from_source_code(&code).into_tokens_all();
                 // perform DYNAMIC SYMBOL TRANSFORM
-                let transform = dst::dyn_symbol_transform(&tokens, &lut);
+                let transform = dst::dyn_symbol_transform(&tokens, &lut, &self.library);
+                // remember the references to review in the original file
+                let original = self.original.get_root().join(vhdl_path.strip_prefix(&temp_path).unwrap());
+                for (name, pos) in transform.get_unresolved() {
+                    self.unresolved.push((original.clone(), name.clone(), pos.clone()));
+                }
                 // rewrite the file
-                std::fs::write(&vhdl_path, transform).unwrap();
+                std::fs::write(&vhdl_path, transform.get_text()).unwrap();
             }
         }
         // update the slot with a transformed IP manifest
@@ -321,11 +419,17 @@ fn install_dst(source_ip: &IpManifest, root: &std::path::PathBuf) -> IpManifest
     cached_ip.stash_units();
     // indicate this installation is dynamic in the metadata
     cached_ip.set_as_dynamic();
+    // carry over the commit the source was installed from
+    if let Some(commit) = source_ip.get_commit() {
+        cached_ip.set_commit(&commit);
+    }
     // save and write the new metadata
     cached_ip.write_metadata().unwrap();
 
     // write the new checksum file
     std::fs::write(&cache_path.join(manifest::ORBIT_SUM_FILE), sum.to_string().as_bytes()).unwrap();
+    // guard the installation against accidental edits
+    crate::util::filesystem::set_readonly(&cache_path, true).unwrap();
 
     cached_ip
 }
@@ -356,7 +460,7 @@ impl<'a> IpFileNode<'a> {
     }
 }
 
-#[derive(Debug, PartialEq, Hash, Eq, Clone)]
+#[derive(Debug, PartialEq, Hash, Eq, Clone, PartialOrd, Ord)]
 pub struct IpSpec(PkgId, Version);
 
 impl IpSpec {
@@ -369,4 +473,44 @@ impl std::fmt::Display for IpSpec {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} v{}", self.0, self.1)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn find_ip() {
+        let universe: Vec<PkgId> = ["ks-tech.rary.gates", "ks-tech.rary.gate_array", "bits.rary.gates", "ks-tech.util.adder"]
+            .iter()
+            .map(|s| PkgId::from_str(s).unwrap())
+            .collect();
+        let find = |spec: &str| super::find_ip(&PkgId::from_str(spec).unwrap(), universe.iter().collect());
+        // parts are compared without regard to case
+        assert_eq!(find("KS-Tech.Rary.Gates").unwrap(), universe[0]);
+        // an empty part matches any value
+        assert_eq!(find("ks-tech..gates").unwrap(), universe[0]);
+        // parts are completed only when nothing matches them in full
+        assert_eq!(find("add").unwrap(), universe[3]);
+        assert_eq!(find("ks-tech.rary.gate").is_err(), true);
+        assert_eq!(find("bi.ra.gat").unwrap(), universe[2]);
+        // every candidate is listed
+        let err = find("gates").unwrap_err().to_string();
+        assert!(err.contains("    bits.rary.gates\n    ks-tech.rary.gates\n"));
+        assert!(find("mux").is_err());
+    }
+
+    #[test]
+    fn find_exact_ip() {
+        let universe: Vec<PkgId> = ["ks-tech.rary.gates", "ks-tech.rary.gates_tb"]
+            .iter()
+            .map(|s| PkgId::from_str(s).unwrap())
+            .collect();
+        let find = |spec: &str| super::find_exact_ip(&PkgId::from_str(spec).unwrap(), universe.iter().collect());
+        assert_eq!(find("ks-tech.rary.gates").unwrap(), universe[0]);
+        assert_eq!(find("KS_TECH.rary.gates").unwrap(), universe[0]);
+        assert!(find("gates").is_err());
+        assert!(find("ks-tech.rary.gate").is_err());
+    }
+}
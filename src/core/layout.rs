@@ -0,0 +1,211 @@
+//! Versions of the directory structure within ORBIT_HOME.
+//!
+//! The version of the structure is recorded in the home directory. When a release
+//! changes how the cache or store is organized, it adds a migration that upgrades
+//! existing installations from the previous version. Pending migrations run
+//! one at a time, and the version is recorded after each one succeeds, so an
+//! interrupted migration resumes from the step that did not finish.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use crate::util::anyerror::{AnyError, Fault};
+use super::store::Store;
+
+/// File within ORBIT_HOME that records the version of its structure.
+pub const LAYOUT_FILE: &str = ".layout";
+
+/// File within ORBIT_HOME that exists while a migration is running.
+const LOCK_FILE: &str = ".layout.lock";
+
+/// The version of the structure this release of orbit expects.
+pub const LAYOUT_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// A step that upgrades the structure to the next version.
+struct Migration {
+    summary: &'static str,
+    apply: fn(&Layout) -> Result<(), Fault>,
+}
+
+/// The migration at index `i` upgrades the structure from version `i`.
+///
+/// Every migration must be safe to run again after being interrupted.
+const MIGRATIONS: [Migration; 1] = [
+    Migration { summary: "share identical files between installations", apply: share_installations },
+];
+
+#[derive(Debug, PartialEq)]
+pub struct Layout<'a> {
+    home: &'a Path,
+    cache: &'a Path,
+    store: &'a Path,
+}
+
+impl<'a> Layout<'a> {
+    pub fn new(home: &'a Path, cache: &'a Path, store: &'a Path) -> Self {
+        Self { home, cache, store }
+    }
+
+    /// Reads the version of the structure.
+    ///
+    /// A home without a recorded version has the structure of version 0.
+    pub fn version(&self) -> Result<u32, Fault> {
+        let path = self.home.join(LAYOUT_FILE);
+        if !path.exists() {
+            return Ok(0)
+        }
+        let text = std::fs::read_to_string(&path)?;
+        match text.trim().parse::<u32>() {
+            Ok(v) => Ok(v),
+            Err(_) => Err(AnyError(format!("invalid layout version '{}' in {}", text.trim(), path.display())))?,
+        }
+    }
+
+    /// Checks if nothing has been installed since the home was created.
+    pub fn is_new(&self) -> Result<bool, Fault> {
+        Ok(!self.home.join(LAYOUT_FILE).exists() && Self::is_empty(self.cache)? && Self::is_empty(self.store)?)
+    }
+
+    /// Lists the summaries of the migrations that have not been applied.
+    ///
+    /// Errors if the structure is from a newer release of orbit.
+    pub fn pending(&self) -> Result<Vec<&'static str>, Fault> {
+        let version = self.version()?;
+        if version > LAYOUT_VERSION {
+            return Err(AnyError(format!("ORBIT_HOME has layout version {}, but this release of orbit only supports up to version {}; upgrade orbit to use this home", version, LAYOUT_VERSION)))?
+        }
+        Ok(MIGRATIONS[version as usize..].iter().map(|m| m.summary).collect())
+    }
+
+    /// Records the current version for a home that needs no migrations.
+    pub fn initialize(&self) -> Result<(), Fault> {
+        self.record(LAYOUT_VERSION)
+    }
+
+    /// Applies every pending migration in order.
+    ///
+    /// Errors if another process is already migrating the home. Returns the number
+    /// of migrations applied.
+    pub fn migrate(&self) -> Result<usize, Fault> {
+        let steps = self.pending()?;
+        if steps.is_empty() {
+            return Ok(0)
+        }
+        let _lock = LayoutLock::acquire(&self.home.join(LOCK_FILE))?;
+        // another process may have migrated before the lock was acquired
+        let start = self.version()?;
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(start as usize) {
+            eprintln!("info: migrating ORBIT_HOME to layout version {}: {} ...", i + 1, migration.summary);
+            (migration.apply)(self)?;
+            self.record(i as u32 + 1)?;
+        }
+        Ok(LAYOUT_VERSION as usize - start as usize)
+    }
+
+    /// Writes the `version` by replacing the layout file in a single rename.
+    fn record(&self, version: u32) -> Result<(), Fault> {
+        let temp = self.home.join(format!("{}.tmp", LAYOUT_FILE));
+        std::fs::write(&temp, format!("{}\n", version))?;
+        std::fs::rename(&temp, self.home.join(LAYOUT_FILE))?;
+        Ok(())
+    }
+
+    fn is_empty(dir: &Path) -> Result<bool, Fault> {
+        match dir.exists() {
+            true => Ok(std::fs::read_dir(dir)?.next().is_none()),
+            false => Ok(true),
+        }
+    }
+}
+
+/// Holds the lock file for as long as a migration runs.
+struct LayoutLock {
+    path: PathBuf,
+}
+
+impl LayoutLock {
+    fn acquire(path: &Path) -> Result<Self, Fault> {
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                writeln!(file, "{}", std::process::id())?;
+                Ok(Self { path: path.to_path_buf() })
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                Err(AnyError(format!("another orbit process is migrating ORBIT_HOME; if none is running, remove {} and try again", path.display())))?
+            }
+            Err(e) => Err(e)?,
+        }
+    }
+}
+
+impl Drop for LayoutLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Version 1: installations share identical files through the store's objects.
+fn share_installations(layout: &Layout) -> Result<(), Fault> {
+    if !layout.cache.exists() {
+        return Ok(())
+    }
+    let root = layout.store.to_path_buf();
+    let store = Store::new(&root);
+    for entry in std::fs::read_dir(layout.cache)? {
+        let slot = entry?.path();
+        if slot.is_dir() && !slot.file_name().unwrap().to_string_lossy().starts_with('.') {
+            store.share(&slot)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn migrate_versions() {
+        let home = tempdir().unwrap();
+        let (cache, store) = (home.path().join("cache"), home.path().join("store"));
+        std::fs::create_dir_all(&store).unwrap();
+        for slot in ["gates-1.0.0", "gates-1.1.0"] {
+            std::fs::create_dir_all(cache.join(slot)).unwrap();
+            std::fs::write(cache.join(slot).join("gate.vhd"), "entity and_gate").unwrap();
+        }
+        let layout = Layout::new(home.path(), &cache, &store);
+        assert!(!layout.is_new().unwrap());
+        assert_eq!(layout.version().unwrap(), 0);
+        assert_eq!(layout.pending().unwrap().len(), LAYOUT_VERSION as usize);
+
+        assert_eq!(layout.migrate().unwrap(), LAYOUT_VERSION as usize);
+        assert_eq!(layout.version().unwrap(), LAYOUT_VERSION);
+        assert!(layout.pending().unwrap().is_empty());
+        assert!(!home.path().join(LOCK_FILE).exists());
+        assert!(store.join(crate::core::store::OBJECTS_DIR).exists());
+        // nothing is left to apply
+        assert_eq!(layout.migrate().unwrap(), 0);
+
+        // a held lock stops a second migration
+        std::fs::write(home.path().join(LAYOUT_FILE), "0").unwrap();
+        std::fs::write(home.path().join(LOCK_FILE), "").unwrap();
+        assert!(layout.migrate().is_err());
+        assert_eq!(layout.version().unwrap(), 0);
+
+        // a home from a newer release is not touched
+        std::fs::write(home.path().join(LAYOUT_FILE), format!("{}", LAYOUT_VERSION + 1)).unwrap();
+        assert!(layout.pending().is_err());
+    }
+
+    #[test]
+    fn new_home() {
+        let home = tempdir().unwrap();
+        let (cache, store) = (home.path().join("cache"), home.path().join("store"));
+        std::fs::create_dir_all(&cache).unwrap();
+        let layout = Layout::new(home.path(), &cache, &store);
+        assert!(layout.is_new().unwrap());
+        layout.initialize().unwrap();
+        assert!(!layout.is_new().unwrap());
+        assert_eq!(layout.version().unwrap(), LAYOUT_VERSION);
+    }
+}
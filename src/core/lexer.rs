@@ -1,5 +1,8 @@
 use std::iter::Peekable;
 use std::fmt::Display;
+use std::io::BufRead;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 pub trait Tokenize {
     type TokenType;
@@ -9,12 +12,20 @@ pub trait Tokenize {
         where <Self as Tokenize>::Err: Display;
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub struct Token<T> {
     position: Position,
+    end: Position,
     ttype: T,
 }
 
+impl<T: PartialEq> PartialEq for Token<T> {
+    fn eq(&self, other: &Self) -> bool {
+        // the end is determined by the start and the token's text
+        self.position == other.position && self.ttype == other.ttype
+    }
+}
+
 impl<T> Token<T> {
     pub fn as_type(&self) -> &T {
         &self.ttype
@@ -30,14 +41,38 @@ impl<T> Token<T> {
         &self.position
     }
 
+    /// Returns the position in the file just past the end of the token.
+    pub fn locate_end(&self) -> &Position {
+        &self.end
+    }
+
+    /// Returns the region of the file the token was captured from.
+    pub fn span(&self) -> Span {
+        Span::new(self.position.clone(), self.end.clone())
+    }
+
+    /// Returns the byte offset in the file where the token was captured.
+    pub fn byte_offset(&self) -> usize {
+        self.position.offset()
+    }
+
     /// Creates a new token.
+    /// 
+    /// The token ends where it begins until set with `with_end`.
     pub fn new(ttype: T, loc: Position) -> Self {
         Self {
+            end: loc.clone(),
             position: loc,
             ttype: ttype,
         }
     }
 
+    /// Sets the position just past the end of the token.
+    pub fn with_end(mut self, end: Position) -> Self {
+        self.end = end;
+        self
+    }
+
     /// Transforms the data into a `Position`.
     pub fn into_position(self) -> Position {
         self.position
@@ -63,6 +98,21 @@ impl<T: Display> TokenError<T> {
             err: err
         }
     }
+
+    /// Returns the position in the file where the error occurred.
+    pub fn locate(&self) -> &Position {
+        &self.position
+    }
+
+    /// References the inner error.
+    pub fn as_err(&self) -> &T {
+        &self.err
+    }
+
+    /// Takes the inner error, discarding its position.
+    pub fn into_err(self) -> T {
+        self.err
+    }
 }
 
 impl<T: Display> Display for TokenError<T> {
@@ -71,19 +121,29 @@ impl<T: Display> Display for TokenError<T> {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
-/// (Line, Col)
-pub struct Position(usize, usize);
+/// The number of columns a tab character advances by default.
+pub const DEFAULT_TAB_WIDTH: usize = 1;
+
+#[derive(Debug, Clone)]
+/// (Line, Col, Byte offset)
+pub struct Position(usize, usize, usize);
+
+impl PartialEq for Position {
+    fn eq(&self, other: &Self) -> bool {
+        // the byte offset is determined by the line and column within the same text
+        self.0 == other.0 && self.1 == other.1
+    }
+}
 
 impl Position {
     /// Creates a new `Position` struct as line 1, col 0.
     pub fn new() -> Self {
-        Position(1, 0)
+        Position(1, 0, 0)
     }
 
     /// Creates a `Position` struct at a particular location `line`:`col`.
     pub fn place(line: usize, col: usize) -> Self {
-        Self(line, col)
+        Self(line, col, 0)
     }
 
     /// Increments the column counter by 1.
@@ -94,7 +154,22 @@ impl Position {
     /// Increments the column counter by 1. If the current char `c` is a newline,
     /// it will then drop down to the next line.
     pub fn step(&mut self, c: &char) {
-        self.next_col();
+        self.step_with(c, DEFAULT_TAB_WIDTH)
+    }
+
+    /// Moves the column counter past the char `c`. If the current char `c` is a 
+    /// newline, it will then drop down to the next line.
+    /// 
+    /// A tab moves to the next multiple of `tab_width` columns. Characters that
+    /// combine with the previous character (such as accents) do not take a column, 
+    /// so the column counts graphemes rather than chars.
+    pub fn step_with(&mut self, c: &char, tab_width: usize) {
+        if c == &'\t' {
+            let width = tab_width.max(1);
+            self.1 = (self.1 / width + 1) * width;
+        } else if !is_zero_width(c) {
+            self.next_col();
+        }
         if c == &'\n' {
             self.next_line();
         }
@@ -118,6 +193,11 @@ impl Position {
         self.1
     }
 
+    /// Access the byte offset (`.2`) from the start of the text.
+    pub fn offset(&self) -> usize {
+        self.2
+    }
+
     /// Appends the position by adding lines and setting column.
     pub fn fast_forward(&mut self, other: &Position) {
         if other.0 > 1 {
@@ -130,6 +210,43 @@ impl Position {
     }
 }
 
+/// A region of text from a `start` position up to an `end` position, exclusive.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Span {
+    start: Position,
+    end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+
+    /// Access the position of the first character in the region.
+    pub fn start(&self) -> &Position {
+        &self.start
+    }
+
+    /// Access the position just past the last character in the region.
+    pub fn end(&self) -> &Position {
+        &self.end
+    }
+
+    /// Returns the range of byte offsets covered by the region.
+    pub fn byte_range(&self) -> std::ops::Range<usize> {
+        self.start.offset()..self.end.offset()
+    }
+}
+
+/// Checks if `c` extends the previous character into a single grapheme.
+/// 
+/// Covers combining diacritical marks, variation selectors, and the zero width joiner.
+fn is_zero_width(c: &char) -> bool {
+    matches!(*c as u32,
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF |
+        0xFE00..=0xFE0F | 0xFE20..=0xFE2F | 0x200D | 0xE0100..=0xE01EF)
+}
+
 impl std::fmt::Display for Position {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, ":{}:{}", self.0, self.1)
@@ -140,6 +257,8 @@ impl std::fmt::Display for Position {
 pub struct TrainCar<T> where T: Iterator<Item=char> {
     contents: Peekable<T>,
     loc: Position,
+    bytes: usize,
+    tab_width: usize,
 }
 
 impl<T> TrainCar<T> where T: Iterator<Item=char> {
@@ -149,14 +268,25 @@ impl<T> TrainCar<T> where T: Iterator<Item=char> {
         Self {
             loc: Position::new(),
             contents: s.peekable(),
+            bytes: 0,
+            tab_width: DEFAULT_TAB_WIDTH,
         }
     }
 
+    /// Sets the number of columns a tab character advances to `n`.
+    pub fn tab_width(mut self, n: usize) -> Self {
+        self.tab_width = n;
+        self
+    }
+
     /// Takes the next char in the iterator and steps the `Position` marker 
     /// accordingly, if a char exists.
     pub fn consume(&mut self) -> Option<char> {
         if let Some(c) = self.contents.next() {
-            self.loc.step(&c);
+            self.loc.step_with(&c, self.tab_width);
+            // the position's offset is where the consumed char begins
+            self.loc.2 = self.bytes;
+            self.bytes += c.len_utf8();
             Some(c)
         } else {
             None
@@ -173,12 +303,65 @@ impl<T> TrainCar<T> where T: Iterator<Item=char> {
         &self.loc
     }
 
+    /// Access the position just past the last consumed character.
+    pub fn locate_end(&self) -> Position {
+        Position(self.loc.0, self.loc.1 + 1, self.bytes)
+    }
+
     /// References the entire iterator still remaining in `self`.
     pub fn peekable(&self) -> &Peekable<T> {
         &self.contents
     }
 }
 
+/// Decodes the chars from a reader one line at a time, so only the current line
+/// is held in memory.
+///
+/// Iteration stops early if the reader fails or the text is not valid UTF-8; the
+/// failure is kept in a shared slot to be checked once the chars are consumed.
+pub struct ReadChars<R: BufRead> {
+    reader: R,
+    line: String,
+    offset: usize,
+    error: Rc<RefCell<Option<std::io::Error>>>,
+}
+
+impl<R: BufRead> ReadChars<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader: reader, line: String::new(), offset: 0, error: Rc::new(RefCell::new(None)) }
+    }
+
+    /// Shares the slot holding the error that stopped the iteration, which stays
+    /// reachable after `self` is moved into a `TrainCar`.
+    pub fn error_slot(&self) -> Rc<RefCell<Option<std::io::Error>>> {
+        self.error.clone()
+    }
+}
+
+impl<R: BufRead> Iterator for ReadChars<R> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.line.len() {
+            if self.error.borrow().is_some() {
+                return None
+            }
+            self.line.clear();
+            self.offset = 0;
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => (),
+                Err(e) => {
+                    self.error.replace(Some(e));
+                    return None
+                }
+            }
+        }
+        let c = self.line[self.offset..].chars().next()?;
+        self.offset += c.len_utf8();
+        Some(c)
+    }
+}
 
 #[cfg(test)]
 mod test {
@@ -197,4 +380,36 @@ mod test {
         pos.next_line();
         assert_eq!(pos, Position::place(3, 0));
     }
+
+    #[test]
+    fn tabs_and_graphemes() {
+        let mut tc = TrainCar::new("\ta\tbe\u{301}!".chars()).tab_width(4);
+        tc.consume();
+        assert_eq!(tc.locate(), &Position::place(1, 4));
+        tc.consume();
+        assert_eq!(tc.locate(), &Position::place(1, 5));
+        tc.consume();
+        assert_eq!(tc.locate(), &Position::place(1, 8));
+        tc.consume();
+        assert_eq!(tc.locate(), &Position::place(1, 9));
+        // 'e' followed by a combining acute accent is one column
+        tc.consume();
+        tc.consume();
+        assert_eq!(tc.locate(), &Position::place(1, 10));
+        // byte offsets count the 2-byte accent
+        tc.consume();
+        assert_eq!(tc.locate(), &Position::place(1, 11));
+        assert_eq!(tc.locate().offset(), 7);
+    }
+
+    #[test]
+    fn read_chars() {
+        let text = "ab\ncé\n\nd";
+        assert_eq!(ReadChars::new(text.as_bytes()).collect::<String>(), text);
+        // invalid utf-8 stops at the line it is found on
+        let chars = ReadChars::new(&b"ok\n\xff\nlost\n"[..]);
+        let error = chars.error_slot();
+        assert_eq!(chars.collect::<String>(), "ok\n");
+        assert_eq!(error.take().unwrap().kind(), std::io::ErrorKind::InvalidData);
+    }
 }
\ No newline at end of file
@@ -3,17 +3,35 @@ use toml_edit::{Document, InlineTable, Formatted, Array};
 use crate::{util::{sha256::Sha256Hash, anyerror::{AnyError, Fault}}, core::{pkgid::PkgId, version::{Version, AnyVersion, self}, config::FromToml, manifest::IpManifest}};
 use crate::util::url::Url;
 
-use super::{ip::IpSpec, catalog::CacheSlot};
+use super::{alias::AliasMap, ip::IpSpec, catalog::CacheSlot, feature::FeatureMap};
 
-type Module = (PkgId, AnyVersion);
+/// The format version written to new lockfiles.
+/// 
+/// - 1: entries record their name, version, checksum, source, and requested dependencies
+/// - 2: adds the resolved commit and the resolved version of each dependency
+pub const LOCK_FORMAT: i64 = 2;
 
 #[derive(Debug)]
-pub struct LockFile(Vec<LockEntry>);
+pub struct LockFile {
+    format: i64,
+    entries: Vec<LockEntry>,
+}
 
 impl FromToml for LockFile {
     type Err = Fault;
     
     fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        // lockfiles before versioning was introduced are version 1
+        let format = match table.get("version") {
+            Some(item) => match item.as_integer() {
+                Some(n) => n,
+                None => return Err(AnyError("expects 'version' to be an integer".to_string()))?
+            },
+            None => 1,
+        };
+        if format > LOCK_FORMAT {
+            return Err(AnyError(format!("lockfile format version {} is newer than the supported version {}\n\nTry upgrading orbit: `orbit upgrade`", format, LOCK_FORMAT)))?
+        }
         let mut inner = Vec::new();
         // take array as as tables
         if let Some(item) = table.get("ip") {
@@ -29,13 +47,20 @@ impl FromToml for LockFile {
                 }
             }
         }
-        Ok(Self(inner))
+        let mut lock = Self { format, entries: inner };
+        if format < LOCK_FORMAT {
+            lock.migrate();
+        }
+        Ok(lock)
     }
 }
 
 impl LockFile {
     /// Creates a lockfile from a build list.
-    pub fn from_build_list(build_list: &mut Vec<&IpManifest>) -> Self {
+    /// 
+    /// Dependencies requested by a pkgid in `aliases` are recorded under their canonical
+    /// pkgid when it is in the build list, with the requested pkgid noted as the alias.
+    pub fn from_build_list(build_list: &mut Vec<&IpManifest>, aliases: &AliasMap) -> Self {
         // sort the build list by pkgid and then version
         build_list.sort_by(|&x, &y| { match x.get_pkgid().cmp(y.get_pkgid()) {
             std::cmp::Ordering::Less => std::cmp::Ordering::Less,
@@ -43,9 +68,58 @@ impl LockFile {
             std::cmp::Ordering::Greater => std::cmp::Ordering::Greater,
         } });
         
-        Self(build_list.into_iter()
-            .map(|ip| LockEntry::from(*ip))
-            .collect())
+        let mut lock = Self {
+            format: LOCK_FORMAT,
+            entries: build_list.iter_mut()
+                .map(|ip| LockEntry::from(*ip))
+                .collect(),
+        };
+        lock.apply_aliases(aliases);
+        lock.resolve_dependencies();
+        lock
+    }
+
+    /// Renames each dependency requested by an alias to the canonical pkgid found in the lockfile.
+    fn apply_aliases(&mut self, aliases: &AliasMap) {
+        let names: Vec<PkgId> = self.entries.iter().map(|e| e.name.clone()).collect();
+        for dep in self.entries.iter_mut().flat_map(|e| e.dependencies.iter_mut().flatten()) {
+            if let Some(canonical) = aliases.get(&dep.name) {
+                if names.contains(canonical) {
+                    dep.alias = Some(std::mem::replace(&mut dep.name, canonical.clone()));
+                }
+            }
+        }
+    }
+
+    /// Upgrades entries read from an older format to the current format. The format
+    /// the lockfile was read as is kept so it can be detected as outdated.
+    /// 
+    /// Dependencies are resolved to the highest compatible version in the lockfile, which is
+    /// how older formats were interpreted. Commits cannot be recovered and are left empty.
+    fn migrate(&mut self) {
+        self.resolve_dependencies();
+    }
+
+    /// Sets each unresolved dependency to the highest compatible version found within the lockfile.
+    fn resolve_dependencies(&mut self) {
+        let resolved: Vec<Vec<Option<Version>>> = self.entries.iter()
+            .map(|e| e.dependencies.iter().flatten().map(|d| {
+                match &d.resolved {
+                    Some(v) => Some(v.clone()),
+                    None => self.get_highest(&d.name, &d.version).map(|f| f.version.clone()),
+                }
+            }).collect())
+            .collect();
+        for (entry, versions) in self.entries.iter_mut().zip(resolved) {
+            for (dep, v) in entry.dependencies.iter_mut().flatten().zip(versions) {
+                dep.resolved = v;
+            }
+        }
+    }
+
+    /// Checks if the lockfile was read from an older format and should be rewritten.
+    pub fn is_outdated(&self) -> bool {
+        self.format < LOCK_FORMAT
     }
 
     /// Loads a lockfile from the `root` path.
@@ -59,32 +133,89 @@ impl LockFile {
             // parse toml syntax
             Ok(Self::from_toml(contents.parse::<Document>()?.as_table())?)
         } else {
-            Ok(Self(Vec::new()))
+            Ok(Self { format: LOCK_FORMAT, entries: Vec::new() })
         }
     }
 
     /// Returns an exact match of `target` and `version` from within the lockfile.
     pub fn get(&self, target: &PkgId, version: &Version) -> Option<&LockEntry> {
-        self.0.iter().find(|&f| &f.name == target && &f.version == version )
+        self.entries.iter().find(|&f| &f.name == target && &f.version == version )
     }
 
     /// Returns the highest compatible version from the lockfile for the given `target`.
     pub fn get_highest(&self, target: &PkgId, version: &AnyVersion) -> Option<&LockEntry> {
         // collect all versions
-        let space: Vec<&Version> = self.0.iter().filter_map(|f| if &f.name == target { Some(&f.version) } else { None }).collect();
+        let space: Vec<&Version> = self.entries.iter().filter_map(|f| if &f.name == target { Some(&f.version) } else { None }).collect();
         match version::get_target_version(&version, &space) {
-            Ok(v) => self.0.iter().find(|f| &f.name == target && f.version == v),
+            Ok(v) => self.entries.iter().find(|f| &f.name == target && f.version == v),
             Err(_) => None
         }
     }
 
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.entries.len()
     }
 
     pub fn inner(&self) -> &Vec<LockEntry> {
-        &self.0
+        &self.entries
     } 
+
+    /// Records the enabled features of each entry from the `features` of a plan.
+    pub fn set_features(&mut self, features: &FeatureMap) {
+        for entry in &mut self.entries {
+            entry.features = features.get(&entry.to_ip_spec())
+                .map(|f| f.iter().cloned().collect())
+                .unwrap_or_default();
+        }
+    }
+
+    /// Checks if every entry records the same enabled features as found in `features`.
+    pub fn has_features(&self, features: &FeatureMap) -> bool {
+        self.entries.iter().all(|e| {
+            let enabled: Vec<&String> = features.get(&e.to_ip_spec()).map(|f| f.iter().collect()).unwrap_or_default();
+            e.features.iter().collect::<Vec<&String>>() == enabled
+        })
+    }
+
+    /// Returns the format version the lockfile was read as.
+    pub fn get_format(&self) -> i64 {
+        self.format
+    }
+}
+
+/// A dependency of a lock entry.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LockDependency {
+    name: PkgId,
+    /// version requested by the ip's manifest
+    version: AnyVersion,
+    /// exact version selected in the lockfile
+    resolved: Option<Version>,
+    /// pkgid requested by the ip's manifest when it is an alias of `name`
+    alias: Option<PkgId>,
+}
+
+impl LockDependency {
+    pub fn get_name(&self) -> &PkgId {
+        &self.name
+    }
+
+    pub fn get_version(&self) -> &AnyVersion {
+        &self.version
+    }
+
+    pub fn get_resolved(&self) -> Option<&Version> {
+        self.resolved.as_ref()
+    }
+
+    pub fn get_alias(&self) -> Option<&PkgId> {
+        self.alias.as_ref()
+    }
+
+    /// References the pkgid as it is written in the ip's manifest.
+    pub fn get_requested_name(&self) -> &PkgId {
+        self.alias.as_ref().unwrap_or(&self.name)
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -93,7 +224,9 @@ pub struct LockEntry {
     version: Version,
     sum: Option<Sha256Hash>,
     source: Option<crate::util::url::Url>,
-    dependencies: Option<Vec<Module>>,
+    commit: Option<String>,
+    features: Vec<String>,
+    dependencies: Option<Vec<LockDependency>>,
 }
 
 impl From<&IpManifest> for LockEntry {
@@ -103,17 +236,19 @@ impl From<&IpManifest> for LockEntry {
             version: ip.get_version().clone(), 
             sum: Some(ip.read_checksum_proof().unwrap_or(ip.compute_checksum())), 
             source: if ip.get_repository().is_some() { Some(ip.get_repository().unwrap().clone()) } else { None },
+            commit: ip.get_commit(),
+            features: Vec::new(),
             dependencies: match ip.get_dependencies().inner().len() {
                 0 => None,
                 _ => Some({
-                    let mut result: Vec<(PkgId, AnyVersion)> = ip.get_dependencies()
+                    let mut result: Vec<LockDependency> = ip.get_dependencies()
                         .inner()
                         .into_iter()
-                        .map(|e| { (e.0.clone(), e.1.clone()) })
+                        .map(|e| LockDependency { name: e.0.clone(), version: e.1.clone(), resolved: None, alias: None })
                         .collect();
-                    result.sort_by(|x, y| { match x.0.cmp(&y.0) {
+                    result.sort_by(|x, y| { match x.name.cmp(&y.name) {
                         std::cmp::Ordering::Less => std::cmp::Ordering::Less,
-                        std::cmp::Ordering::Equal => x.1.cmp(&y.1),
+                        std::cmp::Ordering::Equal => x.version.cmp(&y.version),
                         std::cmp::Ordering::Greater => std::cmp::Ordering::Greater,
                     } });
                     result
@@ -127,18 +262,32 @@ impl LockEntry {
     /// Performs an equality check against a target entry `other`.
     /// 
     /// Ignores the checksum comparison because the target ip should not have its
-    /// checksum computed in the .lock file.
+    /// checksum computed in the .lock file. Dependencies are only compared by their
+    /// requested pkgids and versions.
     pub fn matches_target(&self, other: &LockEntry) -> bool {
+        fn requests(e: &LockEntry) -> Option<Vec<(&PkgId, &AnyVersion)>> {
+            e.get_deps().map(|d| d.iter().map(|f| (f.get_requested_name(), &f.version)).collect())
+        }
         self.get_name() == other.get_name() && 
         self.get_version() == other.get_version() &&
         self.get_source() == other.get_source() &&
-        self.get_deps() == other.get_deps()
+        requests(self) == requests(other)
     }
 
-    pub fn get_deps(&self) -> Option<&Vec<Module>> {
+    pub fn get_deps(&self) -> Option<&Vec<LockDependency>> {
         self.dependencies.as_ref()
     }
 
+    /// References the commit the installation was checked out from, if known.
+    pub fn get_commit(&self) -> Option<&String> {
+        self.commit.as_ref()
+    }
+
+    /// References the features that were enabled for the ip when planned.
+    pub fn get_features(&self) -> &Vec<String> {
+        &self.features
+    }
+
     pub fn get_sum(&self) -> Option<&Sha256Hash> {
         self.sum.as_ref()
     }
@@ -168,13 +317,25 @@ impl LockEntry {
         if let Some(src) = self.get_source() {
             table["source"] = toml_edit::value(src.to_string());
         }
+        if let Some(commit) = self.get_commit() {
+            table["commit"] = toml_edit::value(commit);
+        }
+        if self.features.is_empty() == false {
+            table["features"] = toml_edit::value(self.features.iter().map(|f| f.as_str()).collect::<Array>());
+        }
         if let Some(deps) = &self.dependencies {
             table.insert("dependencies", toml_edit::Item::Value(toml_edit::Value::Array(Array::new())));
             for entry in deps {
                 let mut inline = InlineTable::new();
                 // @TODO write newlines after each item?
-                inline.insert("name", toml_edit::Value::String(Formatted::new(entry.0.to_string())));
-                inline.insert("version", toml_edit::Value::String(Formatted::new(entry.1.to_string())));
+                inline.insert("name", toml_edit::Value::String(Formatted::new(entry.name.to_string())));
+                inline.insert("version", toml_edit::Value::String(Formatted::new(entry.version.to_string())));
+                if let Some(v) = &entry.resolved {
+                    inline.insert("resolved", toml_edit::Value::String(Formatted::new(v.to_string())));
+                }
+                if let Some(alias) = &entry.alias {
+                    inline.insert("alias", toml_edit::Value::String(Formatted::new(alias.to_string())));
+                }
                 inline.decor_mut().set_prefix("\n    ");
                 table["dependencies"].as_array_mut()
                     .unwrap()
@@ -201,18 +362,29 @@ impl FromToml for LockEntry {
                 None => None,
             },
             source: if let Some(src) = table.get("source") { Some(Url::from_str(src.as_str().unwrap())?) } else { None },
+            commit: table.get("commit").and_then(|c| c.as_str()).map(|c| c.to_string()),
+            features: table.get("features")
+                .and_then(|f| f.as_array())
+                .map(|arr| arr.iter().filter_map(|f| f.as_str().map(String::from)).collect())
+                .unwrap_or_default(),
             dependencies: {
                 match table.get("dependencies") {
                     Some(item) => {
-                        let mut result: Vec<Module> = Vec::new();
+                        let mut result: Vec<LockDependency> = Vec::new();
                         for entry in item.as_array().unwrap() {
                             let entry = entry.as_inline_table().unwrap();
-                            result.push(
-                                (
-                                    PkgId::from_str(entry["name"].as_str().unwrap()).unwrap(),
-                                    AnyVersion::from_str(entry["version"].as_str().unwrap()).unwrap(),
-                                )
-                            );
+                            result.push(LockDependency {
+                                name: PkgId::from_str(entry["name"].as_str().unwrap()).unwrap(),
+                                version: AnyVersion::from_str(entry["version"].as_str().unwrap()).unwrap(),
+                                resolved: match entry.get("resolved").and_then(|r| r.as_str()) {
+                                    Some(r) => Some(Version::from_str(r)?),
+                                    None => None,
+                                },
+                                alias: match entry.get("alias").and_then(|a| a.as_str()) {
+                                    Some(a) => Some(PkgId::from_str(a)?),
+                                    None => None,
+                                },
+                            });
                         }
                         Some(result)
                     },
@@ -223,4 +395,138 @@ impl FromToml for LockEntry {
     }
 }
 
-pub const IP_LOCK_FILE: &str = "Orbit.lock";
\ No newline at end of file
+pub const IP_LOCK_FILE: &str = "Orbit.lock";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const V1_LOCK: &str = r#"
+[[ip]]
+name = "ks-tech.rary.gates"
+version = "1.0.0"
+dependencies = [{ name = "ks-tech.rary.util", version = "1" }]
+
+[[ip]]
+name = "ks-tech.rary.util"
+version = "1.2.0"
+
+[[ip]]
+name = "ks-tech.rary.util"
+version = "1.4.1"
+"#;
+
+    #[test]
+    fn migrate_v1() {
+        let lock = LockFile::from_toml(V1_LOCK.parse::<Document>().unwrap().as_table()).unwrap();
+        assert_eq!(lock.get_format(), 1);
+        assert_eq!(lock.is_outdated(), true);
+        // dependencies resolve to the highest compatible version in the lockfile
+        let entry = lock.get(&PkgId::from_str("ks-tech.rary.gates").unwrap(), &Version::from_str("1.0.0").unwrap()).unwrap();
+        let dep = &entry.get_deps().unwrap()[0];
+        assert_eq!(dep.get_resolved(), Some(&Version::from_str("1.4.1").unwrap()));
+        assert_eq!(entry.get_commit(), None);
+    }
+
+    #[test]
+    fn missing_resolved_entry() {
+        let text = r#"
+version = 2
+
+[[ip]]
+name = "ks-tech.rary.gates"
+version = "1.0.0"
+dependencies = [{ name = "ks-tech.rary.util", version = "1", resolved = "1.3.0" }]
+
+[[ip]]
+name = "ks-tech.rary.util"
+version = "1.4.1"
+"#;
+        let lock = LockFile::from_toml(text.parse::<Document>().unwrap().as_table()).unwrap();
+        let err = match crate::core::ip::graph_ip_from_lock(&lock) {
+            Ok(_) => panic!("expected an error for the missing entry"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("ks-tech.rary.util to version 1.3.0"), "{}", err);
+
+        // the resolved entry exists
+        let text = text.replace("1.3.0", "1.4.1");
+        let lock = LockFile::from_toml(text.parse::<Document>().unwrap().as_table()).unwrap();
+        let graph = crate::core::ip::graph_ip_from_lock(&lock).unwrap();
+        assert_eq!(graph.get_map().len(), 2);
+        assert_eq!(graph.find_root().is_ok(), true);
+    }
+
+    #[test]
+    fn round_trip_v2() {
+        let mut table = toml_edit::Table::new();
+        table["version"] = toml_edit::value(LOCK_FORMAT);
+        let lock = LockFile::from_toml(V1_LOCK.parse::<Document>().unwrap().as_table()).unwrap();
+        let mut entries = toml_edit::ArrayOfTables::new();
+        for entry in lock.inner() {
+            let mut t = toml_edit::Table::new();
+            entry.to_toml(&mut t);
+            entries.push(t);
+        }
+        table["ip"] = toml_edit::Item::ArrayOfTables(entries);
+        table["ip"][0]["commit"] = toml_edit::value("8f3a9c1");
+        table["ip"][0]["features"] = toml_edit::value(["axi", "uart"].iter().copied().collect::<Array>());
+
+        let mut lock = LockFile::from_toml(&table).unwrap();
+        assert_eq!(lock.is_outdated(), false);
+        let entry = &lock.inner()[0];
+        assert_eq!(entry.get_commit(), Some(&String::from("8f3a9c1")));
+        assert_eq!(entry.get_features(), &vec![String::from("axi"), String::from("uart")]);
+        assert_eq!(entry.get_deps().unwrap()[0].get_resolved(), Some(&Version::from_str("1.4.1").unwrap()));
+
+        let spec = entry.to_ip_spec();
+        let mut features = FeatureMap::new();
+        features.insert(spec.clone(), ["axi", "uart"].iter().copied().map(String::from).collect());
+        assert_eq!(lock.has_features(&features), true);
+        features.get_mut(&spec).unwrap().remove("axi");
+        assert_eq!(lock.has_features(&features), false);
+        lock.set_features(&features);
+        assert_eq!(lock.has_features(&features), true);
+    }
+
+    #[test]
+    fn aliased_dependencies() {
+        let text = r#"
+version = 2
+
+[[ip]]
+name = "ks-tech.rary.gates"
+version = "1.0.0"
+dependencies = [{ name = "ks-tech.rary.util", version = "1" }]
+
+[[ip]]
+name = "kepler.rary.util"
+version = "1.4.1"
+"#;
+        let mut lock = LockFile::from_toml(text.parse::<Document>().unwrap().as_table()).unwrap();
+        let mut aliases = AliasMap::new();
+        aliases.insert(PkgId::from_str("ks-tech.rary.util").unwrap(), PkgId::from_str("kepler.rary.util").unwrap());
+        lock.apply_aliases(&aliases);
+        lock.resolve_dependencies();
+
+        let dep = &lock.inner()[0].get_deps().unwrap()[0];
+        assert_eq!(dep.get_name(), &PkgId::from_str("kepler.rary.util").unwrap());
+        assert_eq!(dep.get_alias(), Some(&PkgId::from_str("ks-tech.rary.util").unwrap()));
+        assert_eq!(dep.get_requested_name(), &PkgId::from_str("ks-tech.rary.util").unwrap());
+        assert_eq!(dep.get_resolved(), Some(&Version::from_str("1.4.1").unwrap()));
+
+        // the alias is kept when the lockfile is written and read again
+        let mut table = toml_edit::Table::new();
+        lock.inner()[0].to_toml(&mut table);
+        let entry = LockEntry::from_toml(&table).unwrap();
+        assert_eq!(&entry, &lock.inner()[0]);
+        assert!(entry.matches_target(&lock.inner()[0]));
+    }
+
+    #[test]
+    fn newer_format_errors() {
+        let mut doc = V1_LOCK.parse::<Document>().unwrap();
+        doc["version"] = toml_edit::value(LOCK_FORMAT + 1);
+        assert!(LockFile::from_toml(doc.as_table()).is_err());
+    }
+}
\ No newline at end of file
@@ -7,9 +7,10 @@ use std::path;
 use std::path::PathBuf;
 use std::error::Error;
 use crate::core::pkgid::PkgId;
-use crate::core::lockfile::{LockFile, IP_LOCK_FILE};
+use crate::core::lockfile::{LockFile, IP_LOCK_FILE, LOCK_FORMAT};
 use crate::util::anyerror::{AnyError, Fault};
 use crate::util::sha256::{Sha256Hash, self};
+use crate::util::profile;
 use crate::util::url::Url;
 use std::str::FromStr;
 use crate::core::version::Version;
@@ -18,11 +19,17 @@ use crate::util::filesystem::{normalize_path, self, Unit};
 use super::catalog::IpState;
 use super::config::{FromToml, FromTomlError};
 use super::extgit::ExtGit;
+use super::feature::FeatureTable;
+use super::fileset::Fileset;
 use super::lockfile::LockEntry;
 use super::ip::IpSpec;
 use super::store::Store;
-use super::version::AnyVersion;
+use super::template;
+use super::variable::VariableTable;
+use super::version::{AnyVersion, VersionReq};
+use super::vhdl::dst::Rename;
 use super::vhdl::primaryunit::PrimaryUnit;
+use super::vhdl::standard::VhdlStandard;
 use super::vhdl::token::{Identifier, IdentifierError};
 
 /// Takes an iterative approach to iterating through directories to find a file
@@ -33,7 +40,7 @@ use super::vhdl::token::{Identifier, IdentifierError};
 /// Stops descending the directories upon finding first match of `name`. 
 /// The match must be case-sensitive. If `is_exclusive` is `false`, then the directory
 /// with match will continued to be searched at that level and then re-track.
-fn find_file(path: &PathBuf, name: &str, is_exclusive: bool) -> Result<Vec<PathBuf>, Fault> {
+pub fn find_file(path: &std::path::Path, name: &str, is_exclusive: bool) -> Result<Vec<PathBuf>, Fault> {
     // create a glob-style pattern
     let pattern = glob::Pattern::new(name).unwrap();
     // list of directories to continue to process
@@ -61,7 +68,8 @@ fn find_file(path: &PathBuf, name: &str, is_exclusive: bool) -> Result<Vec<PathB
                     if is_exclusive == true {
                         break;
                     }
-                } else if e.file_type().unwrap().is_dir() == true {
+                } else if e.path().is_dir() {
+                    // follows links to directories, such as linked vendor checkouts
                     next_to_process.push(e.path());
                 }
             }
@@ -160,7 +168,17 @@ pub const IP_MANIFEST_FILE: &str = "Orbit.toml";
 pub const IP_MANIFEST_PATTERN_FILE : &str = "Orbit-*.toml";
 const DEPENDENCIES_KEY: &str = "dependencies";
 pub const ORBIT_SUM_FILE: &str = ".orbit-checksum";
+pub const ORBIT_SIGNATURE_FILE: &str = ".orbit-signature";
 pub const ORBIT_METADATA_FILE: &str = ".orbit-metadata";
+/// Directory at an ip's root holding copies of its locked dependencies.
+///
+/// The name is reserved for orbit so it never hides a `vendor/` directory of
+/// the user's own sources.
+pub const VENDOR_DIR: &str = ".orbit-vendor";
+/// Fileset of an external unit's files when the manifest does not name one.
+pub const EXTERNAL_FILESET: &str = "NETLIST";
+/// Fileset of a target's constraint files when the manifest does not name one.
+pub const CONSTRAINT_FILESET: &str = "CONSTRAINTS";
 
 #[derive(Debug)]
 pub struct IpManifest{ 
@@ -182,11 +200,19 @@ impl PartialEq for IpManifest {
 pub struct IpToml {
     ip: Ip,
     deps: DependencyTable,
+    externals: ExternalTable,
+    generators: GeneratorTable,
+    plan: PlanTable,
+    standards: StandardTable,
+    targets: TargetTable,
+    features: FeatureTable,
+    defines: DefineTable,
+    providers: ProviderTable,
 }
 
 impl IpToml {
     pub fn new() -> Self {
-        Self { ip: Ip::new(), deps: DependencyTable::new() }
+        Self { ip: Ip::new(), deps: DependencyTable::new(), externals: ExternalTable::new(), generators: GeneratorTable::new(), plan: PlanTable::new(), standards: StandardTable::new(), targets: TargetTable::new(), features: FeatureTable::new(), defines: DefineTable::new(), providers: ProviderTable::new() }
     }
 }
 
@@ -261,6 +287,37 @@ pub struct Ip {
     changelog: Option<String>,
     readme: Option<String>,
     units: Option<Vec<Identifier>>,
+    roles: HashMap<Identifier, UnitRole>,
+    hdl_standard: Option<VhdlStandard>,
+    orbit_version: Option<VersionReq>,
+    reexports: Vec<PkgId>,
+    include_dirs: Vec<String>,
+}
+
+/// The role of a primary design unit declared in the `[ip.units]` table.
+#[derive(Debug, PartialEq, Clone)]
+pub enum UnitRole {
+    Testbench,
+    Top,
+}
+
+impl UnitRole {
+    /// Checks if the role marks the unit as a testbench.
+    pub fn is_testbench(&self) -> bool {
+        self == &Self::Testbench
+    }
+}
+
+impl FromStr for UnitRole {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "testbench" => Ok(Self::Testbench),
+            "top" => Ok(Self::Top),
+            _ => Err(AnyError(String::from("unknown role; expecting 'testbench' or 'top'"))),
+        }
+    }
 }
 
 impl Ip {
@@ -273,6 +330,11 @@ impl Ip {
             changelog: None, 
             readme: None,
             units: None,
+            roles: HashMap::new(),
+            hdl_standard: None,
+            orbit_version: None,
+            reexports: Vec::new(),
+            include_dirs: Vec::new(),
         }
     }
 
@@ -291,6 +353,29 @@ impl Ip {
     pub fn get_summary(&self) -> Option<&String> {
         self.summary.as_ref()
     }
+
+    pub fn get_hdl_standard(&self) -> Option<&VhdlStandard> {
+        self.hdl_standard.as_ref()
+    }
+
+    /// References the requirement on the version of orbit that can use the ip.
+    pub fn get_orbit_version(&self) -> Option<&VersionReq> {
+        self.orbit_version.as_ref()
+    }
+
+    pub fn get_unit_role(&self, unit: &Identifier) -> Option<&UnitRole> {
+        self.roles.get(unit)
+    }
+
+    /// References the dependencies whose units are visible to the ip's consumers.
+    pub fn get_reexports(&self) -> &Vec<PkgId> {
+        &self.reexports
+    }
+
+    /// References the header directories relative to the ip's root.
+    pub fn get_include_dirs(&self) -> &Vec<String> {
+        &self.include_dirs
+    }
 }
 
 // #[derive(Debug, PartialEq)]
@@ -324,6 +409,17 @@ impl FromToml for Ip {
     type Err = Fault;
 
     fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        // the `[ip.units]` table assigns roles to units (metadata stores a list of units instead)
+        let mut roles = HashMap::new();
+        if let Some(tbl) = table.get("units").and_then(|i| i.as_table()) {
+            for (key, _) in tbl.iter() {
+                let unit = match key.parse::<Identifier>() {
+                    Ok(iden) => iden,
+                    Err(e) => return Err(AnyError(format!("invalid unit '{}' in table 'ip.units': {}", key, e)))?,
+                };
+                roles.insert(unit, Self::require(tbl, key)?);
+            }
+        }
         Ok(Self {
             name: {
                 let name: String = Self::require(table, "name")?;
@@ -332,7 +428,20 @@ impl FromToml for Ip {
                 PkgId::new().name(&name)?.library(&library)?.vendor(&vendor)?
             },
             version: Self::require(table, "version")?,
-            repository: Self::get(table, "repository")?,
+            repository: match Self::get::<String>(table, "repository")? {
+                // the url can reference environment variables
+                Some(text) => {
+                    let url = match template::expand(&text, &VariableTable::new().load_system()) {
+                        Ok(u) => u,
+                        Err(e) => return Err(AnyError(format!("key 'repository': {}", e)))?,
+                    };
+                    match url.parse::<Url>() {
+                        Ok(u) => Some(u),
+                        Err(e) => return Err(FromTomlError::BadParse(String::from("repository"), url, e.to_string()))?,
+                    }
+                },
+                None => None,
+            },
             summary: Self::get(table, "summary")?,
             changelog: Self::get(table, "changelog")?,
             readme: Self::get(table, "readme")?,
@@ -345,10 +454,25 @@ impl FromToml for Ip {
                             .collect();
                         Some(result?)
                     }
+                    None if i.is_table() => None,
                     None => return Err(FromTomlError::ExpectingStringArray("units".to_owned()))?,
                 }
                 None => None,
             },
+            roles,
+            hdl_standard: Self::get(table, "hdl-standard")?,
+            orbit_version: Self::get(table, "orbit-version")?,
+            reexports: {
+                let mut list = Vec::new();
+                for pkgid in read_strings(table, "reexports", "ip")? {
+                    match pkgid.parse::<PkgId>() {
+                        Ok(p) => list.push(p),
+                        Err(e) => return Err(FromTomlError::BadParse(String::from("reexports"), pkgid, e.to_string()))?,
+                    }
+                }
+                list
+            },
+            include_dirs: read_strings(table, "include-dirs", "ip")?,
         })
     }
 }
@@ -375,9 +499,85 @@ impl FromToml for IpToml {
         } else {
             DependencyTable::new()
         };
+        // only direct dependencies can be re-exported
+        if let Some(pkgid) = ip.get_reexports().iter().find(|p| dt.inner().contains_key(p) == false) {
+            return Err(AnyError(format!("key 'ip.reexports' lists '{}', which is not a dependency", pkgid)))?
+        }
+        // grab the externals table
+        let externals = match table.get("externals") {
+            Some(item) => match item.as_table() {
+                Some(tbl) => ExternalTable::from_toml(tbl)?,
+                None => return Err(AnyError(String::from("expects key 'externals' to be a toml table")))?
+            },
+            None => ExternalTable::new(),
+        };
+        // grab the generators table
+        let generators = match table.get("generators") {
+            Some(item) => match item.as_table() {
+                Some(tbl) => GeneratorTable::from_toml(tbl)?,
+                None => return Err(AnyError(String::from("expects key 'generators' to be a toml table")))?
+            },
+            None => GeneratorTable::new(),
+        };
+        // grab the plan table
+        let plan = match table.get("plan") {
+            Some(item) => match item.as_table() {
+                Some(tbl) => PlanTable::from_toml(tbl)?,
+                None => return Err(AnyError(String::from("expects key 'plan' to be a toml table")))?
+            },
+            None => PlanTable::new(),
+        };
+        // grab the standards table
+        let standards = match table.get("standards") {
+            Some(item) => match item.as_table() {
+                Some(tbl) => StandardTable::from_toml(tbl)?,
+                None => return Err(AnyError(String::from("expects key 'standards' to be a toml table")))?
+            },
+            None => StandardTable::new(),
+        };
+        // grab the targets table
+        let targets = match table.get("targets") {
+            Some(item) => match item.as_table() {
+                Some(tbl) => TargetTable::from_toml(tbl)?,
+                None => return Err(AnyError(String::from("expects key 'targets' to be a toml table")))?
+            },
+            None => TargetTable::new(),
+        };
+        // grab the features table
+        let features = match table.get("features") {
+            Some(item) => match item.as_table() {
+                Some(tbl) => FeatureTable::from_toml(tbl)?,
+                None => return Err(AnyError(String::from("expects key 'features' to be a toml table")))?
+            },
+            None => FeatureTable::new(),
+        };
+        // grab the defines table
+        let defines = match table.get("defines") {
+            Some(item) => match item.as_table() {
+                Some(tbl) => DefineTable::from_toml(tbl)?,
+                None => return Err(AnyError(String::from("expects key 'defines' to be a toml table")))?
+            },
+            None => DefineTable::new(),
+        };
+        // grab the providers table
+        let providers = match table.get("providers") {
+            Some(item) => match item.as_table() {
+                Some(tbl) => ProviderTable::from_toml(tbl)?,
+                None => return Err(AnyError(String::from("expects key 'providers' to be a toml table")))?
+            },
+            None => ProviderTable::new(),
+        };
         Ok(Self {
             ip: ip,
             deps: dt,
+            externals,
+            generators,
+            plan,
+            standards,
+            targets,
+            features,
+            defines,
+            providers,
         })
     }
 }
@@ -428,6 +628,369 @@ impl DependencyTable {
     }
 }
 
+/// A unit provided outside of the ip's VHDL source code, such as a vendor
+/// primitive or an encrypted netlist.
+#[derive(Debug, PartialEq)]
+pub struct External {
+    files: Vec<String>,
+    fileset: String,
+}
+
+impl External {
+    /// References the paths of the unit's files relative to the ip's root.
+    pub fn get_files(&self) -> &Vec<String> {
+        &self.files
+    }
+
+    /// References the fileset the unit's files are written to the blueprint under.
+    pub fn get_fileset(&self) -> &str {
+        &self.fileset
+    }
+}
+
+/// The `[externals]` table mapping unit names to their files.
+#[derive(Debug, PartialEq)]
+pub struct ExternalTable(HashMap<Identifier, External>);
+
+impl ExternalTable {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    pub fn inner(&self) -> &HashMap<Identifier, External> {
+        &self.0
+    }
+}
+
+impl FromToml for ExternalTable {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        let mut map = HashMap::new();
+        for (key, item) in table.iter() {
+            let unit = match key.parse::<Identifier>() {
+                Ok(iden) => iden,
+                Err(e) => return Err(AnyError(format!("invalid unit '{}' in table 'externals': {}", key, e)))?,
+            };
+            let entry = match item.as_table_like() {
+                Some(t) => t,
+                None => return Err(AnyError(format!("expects key 'externals.{}' to be a toml table", key)))?,
+            };
+            let files = read_strings(entry, "files", &format!("externals.{}", key))?;
+            let fileset = match entry.get("fileset") {
+                Some(i) => match i.as_str() {
+                    Some(f) => f.to_string(),
+                    None => return Err(FromTomlError::ExpectingString(format!("externals.{}.fileset", key)))?,
+                },
+                None => String::from(EXTERNAL_FILESET),
+            };
+            map.insert(unit, External { files, fileset });
+        }
+        Ok(Self(map))
+    }
+}
+
+/// A board or device the ip can be built for along with its constraint files.
+#[derive(Debug, PartialEq)]
+pub struct Target {
+    part: Option<String>,
+    constraints: Fileset,
+}
+
+impl Target {
+    /// References the device part number, if given.
+    pub fn get_part(&self) -> Option<&String> {
+        self.part.as_ref()
+    }
+
+    /// References the fileset collecting the target's constraint files.
+    pub fn get_constraints(&self) -> &Fileset {
+        &self.constraints
+    }
+}
+
+/// The `[targets]` table mapping target names to their constraints.
+#[derive(Debug, PartialEq)]
+pub struct TargetTable(HashMap<String, Target>);
+
+impl TargetTable {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    pub fn inner(&self) -> &HashMap<String, Target> {
+        &self.0
+    }
+}
+
+impl FromToml for TargetTable {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        let mut map = HashMap::new();
+        for (key, item) in table.iter() {
+            let path = format!("targets.{}", key);
+            let entry = match item.as_table_like() {
+                Some(t) => t,
+                None => return Err(AnyError(format!("expects key '{}' to be a toml table", path)))?,
+            };
+            let part = match entry.get("part") {
+                Some(i) => match i.as_str() {
+                    Some(p) => Some(p.to_string()),
+                    None => return Err(FromTomlError::ExpectingString(format!("{}.part", path)))?,
+                },
+                None => None,
+            };
+            let fileset = match entry.get("fileset") {
+                Some(i) => match i.as_str() {
+                    Some(f) => f,
+                    None => return Err(FromTomlError::ExpectingString(format!("{}.fileset", path)))?,
+                },
+                None => CONSTRAINT_FILESET,
+            };
+            let mut constraints = Fileset::new().name(fileset);
+            for pattern in read_strings(entry, "constraints", &path)? {
+                constraints = constraints.pattern(&pattern)
+                    .map_err(|e| FromTomlError::BadParse(format!("{}.constraints", path), pattern.clone(), e.to_string()))?;
+            }
+            let constraints = constraints.verify()
+                .map_err(|e| AnyError(format!("key '{}.constraints': {}", path, e)))?;
+            map.insert(key.to_string(), Target { part, constraints });
+        }
+        Ok(Self(map))
+    }
+}
+
+/// The `[plan]` table naming the top-level and testbench to plan by default.
+#[derive(Debug, PartialEq)]
+pub struct PlanTable {
+    top: Option<Identifier>,
+    bench: Option<Identifier>,
+}
+
+impl PlanTable {
+    pub fn new() -> Self {
+        Self { top: None, bench: None }
+    }
+
+    pub fn get_top(&self) -> Option<&Identifier> {
+        self.top.as_ref()
+    }
+
+    pub fn get_bench(&self) -> Option<&Identifier> {
+        self.bench.as_ref()
+    }
+}
+
+impl FromToml for PlanTable {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        Ok(Self {
+            top: Self::get(table, "top")?,
+            bench: Self::get(table, "bench")?,
+        })
+    }
+}
+
+/// The `[standards]` table mapping files and directories to the VHDL standard they are written in.
+#[derive(Debug, PartialEq)]
+pub struct StandardTable(Vec<(String, VhdlStandard)>);
+
+impl StandardTable {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn inner(&self) -> &Vec<(String, VhdlStandard)> {
+        &self.0
+    }
+}
+
+impl FromToml for StandardTable {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        let mut list = Vec::new();
+        for (key, _) in table.iter() {
+            list.push((key.to_string(), Self::require(table, key)?));
+        }
+        Ok(Self(list))
+    }
+}
+
+/// The `[defines]` table mapping preprocessor macros to their values.
+#[derive(Debug, PartialEq)]
+pub struct DefineTable(Vec<(String, Option<String>)>);
+
+impl DefineTable {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// References the macros as (name, value), where a macro set to `true` has no value.
+    pub fn inner(&self) -> &Vec<(String, Option<String>)> {
+        &self.0
+    }
+}
+
+impl FromToml for DefineTable {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        let mut list = Vec::new();
+        for (key, item) in table.iter() {
+            if is_macro_name(key) == false {
+                return Err(AnyError(format!("invalid macro name '{}' in table 'defines'", key)))?
+            }
+            let value = match item.as_value() {
+                Some(toml_edit::Value::String(s)) => Some(s.value().to_string()),
+                Some(toml_edit::Value::Integer(i)) => Some(i.value().to_string()),
+                Some(toml_edit::Value::Boolean(b)) if *b.value() == true => None,
+                _ => return Err(AnyError(format!("expects key 'defines.{}' to be a string, an integer, or true", key)))?,
+            };
+            // the values are joined by semicolons for plugins
+            if value.as_ref().map(|v| v.contains(|c| c == '\t' || c == '\n' || c == '\r' || c == ';')).unwrap_or(false) {
+                return Err(AnyError(format!("value for key 'defines.{}' cannot contain tabs, newlines, or semicolons", key)))?
+            }
+            list.push((key.to_string(), value));
+        }
+        Ok(Self(list))
+    }
+}
+
+/// The `[providers]` table choosing the ip to use for a unit declared by more than one ip.
+#[derive(Debug, PartialEq)]
+pub struct ProviderTable(HashMap<Identifier, PkgId>);
+
+impl ProviderTable {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    /// References the ip chosen to provide the unit `name`, if any.
+    pub fn get(&self, name: &Identifier) -> Option<&PkgId> {
+        self.0.get(name)
+    }
+
+    pub fn inner(&self) -> &HashMap<Identifier, PkgId> {
+        &self.0
+    }
+}
+
+impl FromToml for ProviderTable {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        let mut map = HashMap::new();
+        for (key, _) in table.iter() {
+            let unit = match key.parse::<Identifier>() {
+                Ok(iden) => iden,
+                Err(e) => return Err(AnyError(format!("invalid unit '{}' in table 'providers': {}", key, e)))?,
+            };
+            let pkgid: PkgId = Self::require(table, key)?;
+            if let Err(e) = pkgid.fully_qualified() {
+                return Err(AnyError(format!("invalid pkgid for key 'providers.{}': {}", key, e)))?
+            }
+            map.insert(unit, pkgid);
+        }
+        Ok(Self(map))
+    }
+}
+
+/// Checks if `name` can be the name of a preprocessor macro.
+pub fn is_macro_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) => (c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        None => false,
+    }
+}
+
+/// Reads the array of strings at `key` from the table at `path`.
+/// 
+/// Returns an empty list when the key is missing.
+fn read_strings(entry: &dyn toml_edit::TableLike, key: &str, path: &str) -> Result<Vec<String>, FromTomlError> {
+    match entry.get(key) {
+        Some(i) => match i.as_array().and_then(|arr| arr.iter().map(|f| f.as_str().map(String::from)).collect::<Option<Vec<String>>>()) {
+            Some(list) => Ok(list),
+            None => Err(FromTomlError::ExpectingStringArray(format!("{}.{}", path, key))),
+        },
+        None => Ok(Vec::new()),
+    }
+}
+
+/// A command that produces source files when the ip is built.
+#[derive(Debug, PartialEq)]
+pub struct Generator {
+    command: String,
+    outputs: Vec<String>,
+    units: Vec<Identifier>,
+}
+
+impl Generator {
+    /// References the command run from the ip's root to produce the outputs.
+    pub fn get_command(&self) -> &str {
+        &self.command
+    }
+
+    /// References the paths of the produced files relative to the ip's root.
+    pub fn get_outputs(&self) -> &Vec<String> {
+        &self.outputs
+    }
+
+    /// References the primary design units declared in the produced files.
+    pub fn get_units(&self) -> &Vec<Identifier> {
+        &self.units
+    }
+}
+
+/// The `[generators]` table mapping generator names to their commands.
+#[derive(Debug, PartialEq)]
+pub struct GeneratorTable(HashMap<String, Generator>);
+
+impl GeneratorTable {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    pub fn inner(&self) -> &HashMap<String, Generator> {
+        &self.0
+    }
+}
+
+impl FromToml for GeneratorTable {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        let mut map = HashMap::new();
+        for (key, item) in table.iter() {
+            let path = format!("generators.{}", key);
+            let entry = match item.as_table_like() {
+                Some(t) => t,
+                None => return Err(AnyError(format!("expects key '{}' to be a toml table", path)))?,
+            };
+            let command = match entry.get("command") {
+                Some(i) => match i.as_str() {
+                    Some(c) => c.to_string(),
+                    None => return Err(FromTomlError::ExpectingString(format!("{}.command", path)))?,
+                },
+                None => return Err(FromTomlError::MissingEntry(format!("{}.command", path)))?,
+            };
+            let outputs = read_strings(entry, "outputs", &path)?;
+            let mut units = Vec::new();
+            for unit in read_strings(entry, "units", &path)? {
+                match unit.parse::<Identifier>() {
+                    Ok(iden) => units.push(iden),
+                    Err(e) => return Err(AnyError(format!("invalid unit '{}' in key '{}.units': {}", unit, path, e)))?,
+                }
+            }
+            map.insert(key.to_string(), Generator { command, outputs, units });
+        }
+        Ok(Self(map))
+    }
+}
+
 impl FromToml for DependencyTable {
     type Err = Fault;
     
@@ -513,7 +1076,7 @@ impl IpManifest {
             false => {
                 // collect all files
                 let files = crate::util::filesystem::gather_current_files(&self.get_manifest().get_path().parent().unwrap().to_path_buf());
-                Ok(crate::core::vhdl::primaryunit::collect_units(&files)?)
+                Ok(profile::time("parse", || crate::core::vhdl::primaryunit::collect_units(&files))?)
             }
         }
     }
@@ -563,20 +1126,18 @@ impl IpManifest {
 
     /// Deletes the project and its files found at the root path.
     pub fn remove(&self) -> Result<(), Fault> {
-        std::fs::remove_dir_all(self.get_root())?;
+        filesystem::remove_all(&self.get_root())?;
         Ok(())
     }
 
     /// Computes the checksum on the root of the IP.
     /// 
-    /// Changes the current working directory to the root for consistent computation.
+    /// Paths are taken relative to the root for consistent computation without
+    /// changing the current working directory (safe to call from multiple threads).
     pub fn compute_checksum(&self) -> Sha256Hash {
-        let cd = std::env::current_dir().unwrap();
-        std::env::set_current_dir(&self.get_root()).unwrap();
-        let ip_files = crate::util::filesystem::gather_current_files(&PathBuf::from("."));
-        let checksum = crate::util::checksum::checksum(&ip_files);
-        std::env::set_current_dir(&cd).unwrap();
-        checksum
+        let root = self.get_root();
+        let ip_files = crate::util::filesystem::gather_files_from(&root);
+        crate::util::checksum::checksum_at(&root, &ip_files)
     }
 
     /// Isolates the project into a temporary directory (as if it was installed)
@@ -672,10 +1233,17 @@ impl IpManifest {
             std::fs::remove_file(&lock_file)?;
         }
         // write the new lock file
-        let mut lock = std::fs::File::create(lock_file)?;
-        lock.write(LOCK_HEADER.as_bytes())?;
+        std::fs::write(lock_file, self.render_lock(lf, ver))?;
+        Ok(())
+    }
+
+    /// Writes the contents of the lock file for `lf` without saving them.
+    /// 
+    /// The current ip's entry is given the version `ver` when provided.
+    pub fn render_lock(&self, lf: &LockFile, ver: Option<&Version>) -> String {
         // load as toml and save as toml
         let mut toml = Document::new();
+        toml["version"] = value(LOCK_FORMAT);
         toml["ip"] = toml_edit::Item::ArrayOfTables(ArrayOfTables::new());
         let lock_table = toml["ip"].as_array_of_tables_mut().unwrap();
 
@@ -693,10 +1261,7 @@ impl IpManifest {
             }
             lock_table.push(table);
         });
-
-        // write to disk
-        lock.write(toml.to_string().as_bytes())?;
-        Ok(())
+        format!("{}{}", LOCK_HEADER, toml)
     }
 
     /// Updates the dependencies table.
@@ -817,6 +1382,16 @@ impl IpManifest {
         self.ip.ip.get_summary()
     }
 
+    /// References the VHDL standard the ip's files are written in, if declared.
+    pub fn get_hdl_standard(&self) -> Option<&VhdlStandard> {
+        self.ip.ip.get_hdl_standard()
+    }
+
+    /// References the role assigned to the `unit` in the manifest's `[ip.units]` table, if any.
+    pub fn get_unit_role(&self, unit: &Identifier) -> Option<&UnitRole> {
+        self.ip.ip.get_unit_role(unit)
+    }
+
     pub fn get_manifest_mut(&mut self) -> &mut Manifest {
         &mut self.manifest
     }
@@ -830,7 +1405,12 @@ impl IpManifest {
     /// Errors on parsing errors for toml and errors on any particular rules for
     /// manifest formatting/required keys.
     fn from_manifest(m: Manifest) -> Result<Self, Box<dyn Error>> {
-        Ok(IpManifest { ip: Self::wrap_toml(&m, IpToml::from_toml(&m.get_doc().as_table()))?, manifest: m, })
+        let ip = IpToml::from_toml(m.get_doc().as_table());
+        // a manifest using features of a newer orbit is likely to fail to parse
+        if ip.is_err() {
+            check_orbit_version(&m)?;
+        }
+        Ok(IpManifest { ip: Self::wrap_toml(&m, ip)?, manifest: m, })
     }
 
     /// Loads an existing `IpManifest` from `path` by reading it as a TOML file. 
@@ -838,11 +1418,7 @@ impl IpManifest {
     /// Assumes `path` is the root of the ip project. The `IP_MANIFEST_FILE` is assumed
     /// to be on located directly within the `path`.
     pub fn from_path(path: &PathBuf) -> Result<Self, Box<dyn Error>> {
-        let man = Manifest::from_path(path.join(IP_MANIFEST_FILE))?;
-        Ok(Self {
-            ip: Self::wrap_toml(&man, IpToml::from_toml(man.get_doc().as_table()))?,
-            manifest: man,
-        })
+        Self::from_manifest(Manifest::from_path(path.join(IP_MANIFEST_FILE))?)
     }
 
     /// Tries to load a manifest from the store.
@@ -858,8 +1434,8 @@ impl IpManifest {
         let target_ver = super::version::get_target_version(version, &space.iter().collect())?;
         // checkout the selected version
         ExtGit::checkout_tag_state(&repo, &target_ver)?;
-        // load the manifest
-        let ip = Self::from_path(&root)?;
+        // load the manifest, which may be within a subdirectory of the repository
+        let ip = Self::from_path(&ExtGit::ip_root(&repo, &root))?;
         // verify the package id's match because store could overwrite with hash collision
         if ip.get_pkgid() != pkgid { return Ok(None) }
         Ok(Some(ip))
@@ -874,6 +1450,82 @@ impl IpManifest {
         &self.ip.deps
     }
 
+    /// References the units declared in the manifest's `[externals]` table.
+    pub fn get_externals(&self) -> &ExternalTable {
+        &self.ip.externals
+    }
+
+    /// References the default top-level and testbench declared in the manifest's `[plan]` table.
+    pub fn get_plan_defaults(&self) -> &PlanTable {
+        &self.ip.plan
+    }
+
+    /// Determines the VHDL standard the `file` is written in, if declared.
+    /// 
+    /// The most specific path in the `[standards]` table that contains the file takes
+    /// precedence over the ip's `hdl-standard`.
+    pub fn get_file_standard(&self, file: &str) -> Option<&VhdlStandard> {
+        let root = self.get_root();
+        self.ip.standards.inner().iter()
+            .filter(|(path, _)| filesystem::is_within(root.join(path), file))
+            .max_by_key(|(path, _)| filesystem::to_standard(path).trim_end_matches('/').split('/').count())
+            .map(|(_, std)| std)
+            .or(self.get_hdl_standard())
+    }
+
+    /// References the boards and devices declared in the manifest's `[targets]` table.
+    pub fn get_targets(&self) -> &TargetTable {
+        &self.ip.targets
+    }
+
+    /// References the dependencies whose units are visible to the ip's consumers.
+    pub fn get_reexports(&self) -> &Vec<PkgId> {
+        self.ip.ip.get_reexports()
+    }
+
+    /// Lists the header directories declared by `ip.include-dirs`, joined to the ip's root.
+    pub fn get_include_dirs(&self) -> Vec<PathBuf> {
+        let root = self.get_root();
+        self.ip.ip.get_include_dirs().iter().map(|d| root.join(d)).collect()
+    }
+
+    /// References the macros declared in the manifest's `[defines]` table.
+    pub fn get_defines(&self) -> &DefineTable {
+        &self.ip.defines
+    }
+
+    /// References the ips chosen in the manifest's `[providers]` table for units declared
+    /// by more than one ip.
+    pub fn get_providers(&self) -> &ProviderTable {
+        &self.ip.providers
+    }
+
+    /// References the optional features declared in the manifest's `[features]` table.
+    pub fn get_features(&self) -> &FeatureTable {
+        &self.ip.features
+    }
+
+    /// References the commands declared in the manifest's `[generators]` table.
+    pub fn get_generators(&self) -> &GeneratorTable {
+        &self.ip.generators
+    }
+
+    /// Finds the generator that lists the `file` as an output, if any.
+    pub fn get_generator_of(&self, file: &str) -> Option<(&String, &Generator)> {
+        let root = self.get_root();
+        self.get_generators().inner().iter().find(|(_, g)| {
+            g.get_outputs().iter().any(|f| filesystem::paths_equal(root.join(f), file))
+        })
+    }
+
+    /// Checks if the `file` is listed by any of the ip's external units.
+    pub fn is_external_file(&self, file: &str) -> bool {
+        let root = self.get_root();
+        self.get_externals().inner().values().any(|e| {
+            e.get_files().iter().any(|f| filesystem::paths_equal(root.join(f), file))
+        })
+    }
+
     pub fn get_repository(&self) -> Option<&Url> {
         self.ip.ip.get_repository()
     }
@@ -906,15 +1558,16 @@ impl IpManifest {
     }
 
 
-    pub fn generate_dst_lut(&self) -> HashMap<Identifier, String> {
+    pub fn generate_dst_lut(&self) -> HashMap<Identifier, Rename> {
         let units = self.read_units_from_metadata().unwrap();
         let checksum = self.read_checksum_proof().unwrap();
+        let library = Identifier::from(self.get_pkgid().get_library().as_ref().unwrap());
         // compose the lut for symbol transformation
         let mut lut = HashMap::new();
         units.into_iter().for_each(|(key, _)| {
             lut.insert(
                 key.clone(), 
-                "_".to_string() + checksum.to_string().get(0..10).unwrap()
+                Rename::new(library.clone(), "_".to_string() + checksum.to_string().get(0..10).unwrap())
             );
         });
         lut
@@ -951,6 +1604,56 @@ impl IpManifest {
         self.get_manifest_mut().get_mut_doc().as_table_mut()["dynamic"] = value(true);
     }
 
+    /// Checks the vendor registry's copy of the manifest for a `yanked` entry.
+    pub fn is_yanked(&self) -> bool {
+        self.get_manifest().get_doc().get("yanked").and_then(|i| i.as_bool()).unwrap_or(false)
+    }
+
+    /// Reads the reason from the `deprecated` entry of the vendor registry's copy of the manifest.
+    pub fn get_deprecation(&self) -> Option<&str> {
+        self.get_manifest().get_doc().get("deprecated")?.as_str()
+    }
+
+    /// Reads the commit the installation was checked out from in the metadata file.
+    pub fn get_commit(&self) -> Option<String> {
+        let meta_path = self.get_root().join(ORBIT_METADATA_FILE);
+        let contents = std::fs::read_to_string(meta_path).ok()?;
+        let table = contents.parse::<Document>().ok()?;
+        table.get("commit")?.as_str().map(|c| c.to_string())
+    }
+
+    /// Adds to manifest file to record the commit the installation was checked out from.
+    pub fn set_commit(&mut self, commit: &str) {
+        self.get_manifest_mut().get_mut_doc().as_table_mut()["commit"] = value(commit);
+    }
+
+}
+
+/// Returns the version of the running orbit.
+fn orbit_version() -> Version {
+    Version::from_str(env!("CARGO_PKG_VERSION")).unwrap()
+}
+
+/// Checks the running version of orbit satisfies the `ip.orbit-version` requirement
+/// of the manifest `m`.
+/// 
+/// The requirement is read directly from the document, so it is checked even when
+/// the rest of the manifest cannot be parsed. A malformed requirement is left to be
+/// reported when parsing the manifest.
+pub fn check_orbit_version(m: &Manifest) -> Result<(), AnyError> {
+    let req = m.get_doc().get("ip")
+        .and_then(|i| i.get("orbit-version"))
+        .and_then(|i| i.as_str())
+        .and_then(|i| VersionReq::from_str(i).ok());
+    match req {
+        Some(req) if !req.matches(&orbit_version()) => Err(unsupported_orbit_version(m, &req)),
+        _ => Ok(()),
+    }
+}
+
+/// Creates the error for a manifest `m` requiring a different version of orbit than the one running.
+fn unsupported_orbit_version(m: &Manifest, req: &VersionReq) -> AnyError {
+    AnyError(format!("manifest {}: ip requires orbit {} but the running orbit is version {}\n\nTry `orbit upgrade` to install the latest version of orbit", normalize_path(m.get_path().clone()).display(), req, orbit_version()))
 }
 
 const BARE_MANIFEST: &str = "\
@@ -1039,4 +1742,228 @@ c-rus.eel4712c.lab1 = "4.2"
 
         assert_eq!(DependencyTable::from_toml(doc.as_table().get("dependencies").unwrap().as_table().unwrap()).unwrap(), DependencyTable(map));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn reexports() {
+        let toml_code = r#"
+[ip]
+vendor = "ks-tech"
+library = "rary"
+name = "soc"
+version = "1.0.0"
+reexports = ["ks-tech.rary.gates"]
+
+[dependencies]
+ks-tech.rary.gates = "1"
+ks-tech.util.toolbox = "2"
+"#;
+        let ip = IpToml::from_toml(toml_code.parse::<Document>().unwrap().as_table()).unwrap();
+        assert_eq!(ip.ip.get_reexports(), &vec![PkgId::from_str("ks-tech.rary.gates").unwrap()]);
+
+        // only dependencies can be re-exported
+        let toml_code = toml_code.replace("ks-tech.rary.gates = \"1\"\n", "");
+        assert!(IpToml::from_toml(toml_code.parse::<Document>().unwrap().as_table()).is_err());
+    }
+
+    #[test]
+    fn includes_and_defines() {
+        let toml_code = r#"
+[ip]
+vendor = "ks-tech"
+library = "rary"
+name = "soc"
+version = "1.0.0"
+include-dirs = ["include", "vlog/headers"]
+
+[defines]
+SIM = true
+WIDTH = 8
+MODE = "fast"
+"#;
+        let ip = IpToml::from_toml(toml_code.parse::<Document>().unwrap().as_table()).unwrap();
+        assert_eq!(ip.ip.get_include_dirs(), &vec![String::from("include"), String::from("vlog/headers")]);
+        assert_eq!(ip.defines.inner(), &vec![
+            (String::from("SIM"), None),
+            (String::from("WIDTH"), Some(String::from("8"))),
+            (String::from("MODE"), Some(String::from("fast"))),
+        ]);
+
+        let doc = "[defines]
+SIM = false
+".parse::<Document>().unwrap();
+        assert!(DefineTable::from_toml(doc.as_table().get("defines").unwrap().as_table().unwrap()).is_err());
+        let doc = "[defines]
+\"2FAST\" = 1
+".parse::<Document>().unwrap();
+        assert!(DefineTable::from_toml(doc.as_table().get("defines").unwrap().as_table().unwrap()).is_err());
+    }
+
+    #[test]
+    fn providers() {
+        let toml_code = r#"
+[ip]
+vendor = "ks-tech"
+library = "rary"
+name = "soc"
+version = "1.0.0"
+
+[providers]
+and_gate = "ks-tech.rary.gates"
+"#;
+        let ip = IpToml::from_toml(toml_code.parse::<Document>().unwrap().as_table()).unwrap();
+        assert_eq!(ip.providers.get(&Identifier::from_str("AND_GATE").unwrap()), Some(&PkgId::from_str("ks-tech.rary.gates").unwrap()));
+        assert_eq!(ip.providers.get(&Identifier::from_str("or_gate").unwrap()), None);
+
+        // the provider must be a full pkgid
+        let doc = "[providers]
+and_gate = \"gates\"
+".parse::<Document>().unwrap();
+        assert!(ProviderTable::from_toml(doc.as_table().get("providers").unwrap().as_table().unwrap()).is_err());
+    }
+
+    #[test]
+    fn unit_roles() {
+        let toml_code = r#"
+[ip]
+vendor = "v"
+library = "l"
+name = "n"
+version = "0.1.0"
+
+[ip.units]
+uart_tb = "testbench"
+soc = "top"
+"#;
+        let doc = toml_code.parse::<Document>().unwrap();
+        let ip = Ip::from_toml(doc.as_table().get("ip").unwrap().as_table().unwrap()).unwrap();
+        assert_eq!(ip.get_unit_role(&Identifier::from_str("uart_tb").unwrap()), Some(&UnitRole::Testbench));
+        assert_eq!(ip.get_unit_role(&Identifier::from_str("soc").unwrap()), Some(&UnitRole::Top));
+        assert_eq!(ip.get_unit_role(&Identifier::from_str("uart").unwrap()), None);
+
+        // unknown role
+        let doc = toml_code.replace("\"top\"", "\"bench\"").parse::<Document>().unwrap();
+        assert!(Ip::from_toml(doc.as_table().get("ip").unwrap().as_table().unwrap()).is_err());
+    }
+
+    #[test]
+    fn externals() {
+        let toml_code = r#"
+[externals]
+ramb36 = { files = ["netlist/ramb36.edf"] }
+bufg = {}
+
+[externals.fifo_gen]
+files = ["ip/fifo_gen.edn", "ip/fifo_gen.xci"]
+fileset = "xci"
+"#;
+        let doc = toml_code.parse::<Document>().unwrap();
+        let table = ExternalTable::from_toml(doc.as_table().get("externals").unwrap().as_table().unwrap()).unwrap();
+        let ramb = table.inner().get(&Identifier::from_str("ramb36").unwrap()).unwrap();
+        assert_eq!(ramb.get_files(), &vec![String::from("netlist/ramb36.edf")]);
+        assert_eq!(ramb.get_fileset(), EXTERNAL_FILESET);
+        let bufg = table.inner().get(&Identifier::from_str("bufg").unwrap()).unwrap();
+        assert!(bufg.get_files().is_empty());
+        let fifo = table.inner().get(&Identifier::from_str("fifo_gen").unwrap()).unwrap();
+        assert_eq!(fifo.get_files().len(), 2);
+        assert_eq!(fifo.get_fileset(), "xci");
+
+        // files must be strings
+        let doc = "[externals]\nramb36 = { files = [1] }\n".parse::<Document>().unwrap();
+        assert!(ExternalTable::from_toml(doc.as_table().get("externals").unwrap().as_table().unwrap()).is_err());
+    }
+
+    #[test]
+    fn standards() {
+        let toml_code = r#"
+[standards]
+"legacy/" = "1987"
+"rtl/fifo.vhd" = "2019"
+"#;
+        let doc = toml_code.parse::<Document>().unwrap();
+        let table = StandardTable::from_toml(doc.as_table().get("standards").unwrap().as_table().unwrap()).unwrap();
+        assert_eq!(table.inner(), &vec![
+            (String::from("legacy/"), VhdlStandard::Vhdl1987),
+            (String::from("rtl/fifo.vhd"), VhdlStandard::Vhdl2019),
+        ]);
+
+        let doc = "[standards]\n\"legacy/\" = \"2000\"\n".parse::<Document>().unwrap();
+        assert!(StandardTable::from_toml(doc.as_table().get("standards").unwrap().as_table().unwrap()).is_err());
+    }
+
+    #[test]
+    fn targets() {
+        let toml_code = r#"
+[targets.arty]
+part = "xc7a35ticsg324-1L"
+constraints = ["boards/arty/*.xdc", "!**/unused_*.xdc"]
+
+[targets.de10]
+constraints = ["boards/de10/*.sdc"]
+fileset = "sdc"
+"#;
+        let doc = toml_code.parse::<Document>().unwrap();
+        let table = TargetTable::from_toml(doc.as_table().get("targets").unwrap().as_table().unwrap()).unwrap();
+        let arty = table.inner().get("arty").unwrap();
+        assert_eq!(arty.get_part(), Some(&String::from("xc7a35ticsg324-1L")));
+        assert_eq!(arty.get_constraints().get_name(), CONSTRAINT_FILESET);
+        assert_eq!(arty.get_constraints().get_patterns(), vec!["**/boards/arty/*.xdc", "!**/unused_*.xdc"]);
+        let de10 = table.inner().get("de10").unwrap();
+        assert_eq!(de10.get_part(), None);
+        assert_eq!(de10.get_constraints().get_name(), "SDC");
+
+        // a target needs constraints to collect
+        let doc = "[targets.arty]\npart = \"xc7a35t\"\n".parse::<Document>().unwrap();
+        assert!(TargetTable::from_toml(doc.as_table().get("targets").unwrap().as_table().unwrap()).is_err());
+    }
+
+    #[test]
+    fn generators() {
+        let toml_code = r#"
+[generators.fifo]
+command = "vivado -mode batch -source gen/fifo.tcl"
+outputs = ["gen/fifo_core.vhd"]
+units = ["fifo_core"]
+"#;
+        let doc = toml_code.parse::<Document>().unwrap();
+        let table = GeneratorTable::from_toml(doc.as_table().get("generators").unwrap().as_table().unwrap()).unwrap();
+        let fifo = table.inner().get("fifo").unwrap();
+        assert_eq!(fifo.get_command(), "vivado -mode batch -source gen/fifo.tcl");
+        assert_eq!(fifo.get_outputs(), &vec![String::from("gen/fifo_core.vhd")]);
+        assert_eq!(fifo.get_units(), &vec![Identifier::from_str("fifo_core").unwrap()]);
+
+        // the command is required
+        let doc = "[generators.fifo]\noutputs = []\n".parse::<Document>().unwrap();
+        assert!(GeneratorTable::from_toml(doc.as_table().get("generators").unwrap().as_table().unwrap()).is_err());
+    }
+
+    #[test]
+    fn plan_defaults() {
+        let doc = "[plan]\ntop = \"soc\"\nbench = \"soc_tb\"\n".parse::<Document>().unwrap();
+        let table = PlanTable::from_toml(doc.as_table().get("plan").unwrap().as_table().unwrap()).unwrap();
+        assert_eq!(table.get_top(), Some(&Identifier::from_str("soc").unwrap()));
+        assert_eq!(table.get_bench(), Some(&Identifier::from_str("soc_tb").unwrap()));
+
+        // either entry can be left out
+        let doc = "[plan]\nbench = \"soc_tb\"\n".parse::<Document>().unwrap();
+        let table = PlanTable::from_toml(doc.as_table().get("plan").unwrap().as_table().unwrap()).unwrap();
+        assert_eq!(table.get_top(), None);
+
+        // units must be valid identifiers
+        let doc = "[plan]\ntop = \"2soc\"\n".parse::<Document>().unwrap();
+        assert!(PlanTable::from_toml(doc.as_table().get("plan").unwrap().as_table().unwrap()).is_err());
+    }
+
+    #[test]
+    fn orbit_version() {
+        let m = |ip: &str| Manifest { path: PathBuf::from("Orbit.toml"), document: format!("[ip]\nname = \"gates\"\nlibrary = \"rary\"\nversion = \"1.0.0\"\nvendor = \"ks-tech\"\n{}", ip).parse::<Document>().unwrap() };
+        assert!(check_orbit_version(&m("")).is_ok());
+        assert!(check_orbit_version(&m("orbit-version = \">=0.1\"\n")).is_ok());
+        assert!(check_orbit_version(&m("orbit-version = \">=999\"\n")).is_err());
+
+        // the requirement is reported over the parsing errors of newer features
+        let err = IpManifest::from_manifest(m("orbit-version = \">=999\"\nunits = 5\n")).unwrap_err();
+        assert!(err.to_string().contains("requires orbit >=999"));
+        let err = IpManifest::from_manifest(m("orbit-version = \">=0.1\"\nunits = 5\n")).unwrap_err();
+        assert!(!err.to_string().contains("requires orbit"));
+    }
+}
@@ -0,0 +1,119 @@
+//! Mirrors redirect the git sources of ip to other hosts, such as an internal
+//! mirror within a firewalled network.
+//!
+//! Each mirror is an entry of the `[[mirror]]` array of tables in configuration
+//! that rewrites the urls starting with `from` to start with `to` instead. A
+//! mirror with a `vendor` only applies to the ip of that vendor and takes
+//! precedence over the mirrors without one.
+
+use std::sync::Mutex;
+use crate::util::anyerror::{AnyError, Fault};
+use super::config::FromToml;
+use super::pkgid::PkgPart;
+
+/// The mirrors applied to every url before reaching a git source.
+static MIRRORS: Mutex<Vec<Mirror>> = Mutex::new(Vec::new());
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Mirror {
+    from: String,
+    to: String,
+    vendor: Option<PkgPart>,
+}
+
+impl FromToml for Mirror {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        let mirror = Self {
+            from: Self::require(table, "from")?,
+            to: Self::require(table, "to")?,
+            vendor: Self::get(table, "vendor")?,
+        };
+        if mirror.from.is_empty() {
+            return Err(AnyError(String::from("key 'from' cannot be empty")))?
+        }
+        Ok(mirror)
+    }
+}
+
+impl Mirror {
+    /// References the url prefix the mirror replaces.
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// References the url prefix the mirror replaces it with.
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    /// References the vendor the mirror is limited to, if any.
+    pub fn get_vendor(&self) -> Option<&PkgPart> {
+        self.vendor.as_ref()
+    }
+}
+
+/// Sets the `mirrors` to apply to urls for the remainder of the process.
+pub fn set_mirrors(mirrors: Vec<Mirror>) {
+    *MIRRORS.lock().unwrap() = mirrors;
+}
+
+/// Rewrites the `url` of an ip from `vendor` with the mirrors set for the process.
+pub fn rewrite(url: &str, vendor: Option<&PkgPart>) -> String {
+    apply(&MIRRORS.lock().unwrap(), url, vendor)
+}
+
+/// Rewrites the `url` of an ip from `vendor` with the mirror matching the longest prefix.
+///
+/// Mirrors for the `vendor` are checked before mirrors for any vendor. The `url` is
+/// returned unchanged if no mirror matches.
+fn apply(mirrors: &[Mirror], url: &str, vendor: Option<&PkgPart>) -> String {
+    let best = |scoped: bool| mirrors.iter()
+        .filter(|m| match scoped {
+            true => m.vendor.is_some() && m.vendor.as_ref() == vendor,
+            false => m.vendor.is_none(),
+        })
+        .filter(|m| url.starts_with(&m.from))
+        .max_by_key(|m| m.from.len());
+    match best(true).or_else(|| best(false)) {
+        Some(m) => format!("{}{}", m.to, &url[m.from.len()..]),
+        None => url.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    fn mirror(from: &str, to: &str, vendor: Option<&str>) -> Mirror {
+        Mirror { from: from.to_string(), to: to.to_string(), vendor: vendor.map(|v| PkgPart::from_str(v).unwrap()) }
+    }
+
+    #[test]
+    fn from_toml() {
+        let doc = "from = \"https://github.com/\"\nto = \"https://git.ks-tech.com/github/\"\nvendor = \"ks-tech\"\n".parse::<toml_edit::Document>().unwrap();
+        assert_eq!(Mirror::from_toml(doc.as_table()).unwrap(), mirror("https://github.com/", "https://git.ks-tech.com/github/", Some("ks-tech")));
+
+        let doc = "from = \"https://github.com/\"\n".parse::<toml_edit::Document>().unwrap();
+        assert!(Mirror::from_toml(doc.as_table()).is_err());
+        let doc = "from = \"\"\nto = \"https://git.ks-tech.com/\"\n".parse::<toml_edit::Document>().unwrap();
+        assert!(Mirror::from_toml(doc.as_table()).is_err());
+    }
+
+    #[test]
+    fn apply_longest_prefix() {
+        let mirrors = vec![
+            mirror("https://github.com/", "https://mirror.local/github/", None),
+            mirror("https://github.com/c-rus/", "https://mirror.local/c-rus/", None),
+            mirror("https://github.com/", "https://git.ks-tech.com/", Some("ks-tech")),
+        ];
+        let ks_tech = PkgPart::from_str("ks-tech").unwrap();
+        assert_eq!(apply(&mirrors, "https://github.com/c-rus/gates.git", None), "https://mirror.local/c-rus/gates.git");
+        assert_eq!(apply(&mirrors, "https://github.com/uart/uart.git", None), "https://mirror.local/github/uart/uart.git");
+        // mirrors for the vendor take precedence
+        assert_eq!(apply(&mirrors, "https://github.com/c-rus/gates.git", Some(&ks_tech)), "https://git.ks-tech.com/c-rus/gates.git");
+        assert_eq!(apply(&mirrors, "https://gitlab.com/c-rus/gates.git", Some(&ks_tech)), "https://gitlab.com/c-rus/gates.git");
+    }
+}
@@ -16,4 +16,17 @@ pub mod store;
 pub mod lockfile;
 pub mod catalog;
 pub mod variable;
-pub mod hook;
\ No newline at end of file
+pub mod hook;
+pub mod sbom;
+pub mod adapter;
+pub mod export;
+pub mod filelist;
+pub mod import;
+pub mod layout;
+pub mod report;
+pub mod alias;
+pub mod archive;pub mod signature;
+
+pub mod feature;
+pub mod mirror;
+pub mod network;
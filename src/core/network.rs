@@ -0,0 +1,555 @@
+//! Network settings shared by every operation that reaches a remote host, such
+//! as cloning git sources, downloading release archives, and upgrading orbit.
+//!
+//! The settings are read from the `[network]` table of configuration. Requests
+//! orbit sends itself are configured directly, while the git subprocesses receive
+//! the settings through `-c` options and proxy environment variables. Proxies set
+//! in configuration replace the proxies set by the environment.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use crate::util::anyerror::{AnyError, Fault};
+use super::config::Config;
+
+/// The settings used for every remote operation of the process.
+static NETWORK: Mutex<Network> = Mutex::new(Network::new());
+
+const TABLE: &str = "network";
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Network {
+    http_proxy: Option<String>,
+    https_proxy: Option<String>,
+    /// hosts reached without a proxy
+    no_proxy: Vec<String>,
+    ca_bundle: Option<PathBuf>,
+    /// seconds to wait on a host that stops responding
+    timeout: Option<u64>,
+    retries: usize,
+    /// refuse to reach remote hosts
+    offline: bool,
+}
+
+/// Sets the `network` settings for the remainder of the process.
+pub fn set_network(network: Network) {
+    *NETWORK.lock().unwrap() = network;
+}
+
+/// Copies the network settings set for the process.
+pub fn get() -> Network {
+    NETWORK.lock().unwrap().clone()
+}
+
+impl Network {
+    /// Creates settings that leave the defaults of git and the environment untouched.
+    pub const fn new() -> Self {
+        Self { http_proxy: None, https_proxy: None, no_proxy: Vec::new(), ca_bundle: None, timeout: None, retries: 0, offline: false }
+    }
+
+    /// Reads the `[network]` table of the configuration.
+    ///
+    /// The `http-proxy` and `https-proxy` entries take precedence over `proxy`,
+    /// which sets both.
+    pub fn from_config(config: &Config) -> Result<Self, Fault> {
+        let proxy = config.get_as_str(TABLE, "proxy")?;
+        let read_proxy = |key: &str| -> Result<Option<String>, Fault> {
+            match config.get_as_str(TABLE, key)?.or(proxy) {
+                Some(p) => match reqwest::Url::parse(p) {
+                    Ok(_) => Ok(Some(p.to_string())),
+                    Err(e) => Err(AnyError(format!("invalid network proxy url '{}': {}", p, e)))?,
+                },
+                None => Ok(None),
+            }
+        };
+        let timeout = match config.get_as_int(TABLE, "timeout")? {
+            Some(n) if n > 0 => Some(n as u64),
+            Some(n) => return Err(AnyError(format!("network timeout must be a positive number of seconds but got {}", n)))?,
+            None => None,
+        };
+        let retries = match config.get_as_int(TABLE, "retries")? {
+            Some(n) if n >= 0 => n as usize,
+            Some(n) => return Err(AnyError(format!("network retries cannot be negative but got {}", n)))?,
+            None => 0,
+        };
+        Ok(Self {
+            http_proxy: read_proxy("http-proxy")?,
+            https_proxy: read_proxy("https-proxy")?,
+            no_proxy: config.collect_as_array_of_str(TABLE, "no-proxy")?.into_iter().map(|(h, _)| h.to_string()).collect(),
+            ca_bundle: config.get_as_path(TABLE, "ca-bundle")?,
+            timeout: timeout,
+            retries: retries,
+            offline: false,
+        })
+    }
+
+    /// Sets the `proxy` to reach every host through, keeping the other settings.
+    pub fn with_proxy(mut self, proxy: &str) -> Self {
+        self.http_proxy = Some(proxy.to_string());
+        self.https_proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// Sets whether remote hosts are `offline`, keeping the other settings.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Checks if remote hosts are refused.
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Checks that `remote`, a description of what is reached, can be reached.
+    ///
+    /// Errors when offline.
+    pub fn require_online(&self, remote: &str) -> Result<(), Fault> {
+        match self.offline {
+            true => Err(NetworkError::Offline(remote.to_string()))?,
+            false => Ok(()),
+        }
+    }
+
+    /// Checks that the source `url` can be reached.
+    ///
+    /// Local paths and `file` urls can always be reached. Errors for any other url
+    /// when offline.
+    pub fn connect(&self, url: &str) -> Result<(), Fault> {
+        match is_remote(url) {
+            true => self.require_online(url),
+            false => Ok(()),
+        }
+    }
+
+    /// Checks if any proxy is set in configuration.
+    pub fn has_proxy(&self) -> bool {
+        self.http_proxy.is_some() || self.https_proxy.is_some()
+    }
+
+    /// Determines the configured proxy to reach `url` through.
+    ///
+    /// Returns `None` when no proxy is configured for the url's scheme or when its
+    /// host is listed in `no-proxy`.
+    pub fn proxy_for(&self, url: &str) -> Option<&str> {
+        let url = reqwest::Url::parse(url).ok()?;
+        let host = url.host_str()?;
+        if self.no_proxy.iter().any(|h| is_host_match(h, host)) {
+            return None
+        }
+        match url.scheme() {
+            "http" => self.http_proxy.as_deref(),
+            "https" => self.https_proxy.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Creates a client to send requests with these settings.
+    pub fn client(&self) -> Result<reqwest::Client, Fault> {
+        let mut builder = reqwest::Client::builder();
+        if self.has_proxy() == true {
+            let settings = self.clone();
+            builder = builder
+                .no_proxy()
+                .proxy(reqwest::Proxy::custom(move |url| settings.proxy_for(url.as_str()).and_then(|p| reqwest::Url::parse(p).ok())));
+        }
+        if let Some(bundle) = &self.ca_bundle {
+            let bytes = match std::fs::read(bundle) {
+                Ok(b) => b,
+                Err(e) => return Err(AnyError(format!("failed to read the certificate authority bundle {}: {}", bundle.display(), e)))?,
+            };
+            for pem in split_certificates(&String::from_utf8_lossy(&bytes)) {
+                builder = builder.add_root_certificate(reqwest::Certificate::from_pem(pem.as_bytes())?);
+            }
+        }
+        if let Some(secs) = self.timeout {
+            builder = builder.connect_timeout(Duration::from_secs(secs));
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Sends a GET request for `url`, retrying when the host cannot be reached,
+    /// does not respond within the timeout, or answers with a server error.
+    ///
+    /// Only the response's status and headers are awaited. Use `download` to also
+    /// receive the body.
+    pub async fn send(&self, client: &reqwest::Client, url: &str) -> Result<reqwest::Response, Fault> {
+        self.connect(url)?;
+        let mut attempt = 0;
+        loop {
+            match self.request(client, url).await {
+                Ok(res) if res.status().is_server_error() == false || attempt == self.retries => return Ok(res),
+                Err(e) if is_transient(&e) == false || attempt == self.retries => return Err(e),
+                _ => (),
+            }
+            tokio::time::sleep(backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Sends a GET request for `url` and receives the entire response, retrying
+    /// when the host cannot be reached, does not respond within the timeout, answers
+    /// with a server error, or stalls while sending the body.
+    ///
+    /// The delay between attempts starts at one second and doubles each retry.
+    pub async fn download(&self, client: &reqwest::Client, url: &str) -> Result<Transfer, Fault> {
+        self.connect(url)?;
+        let mut attempt = 0;
+        loop {
+            let result = match self.request(client, url).await {
+                Ok(res) => {
+                    let status = res.status();
+                    let location = res.url().to_string();
+                    self.read(res).await.map(|body| Transfer { url: location, status: status, body: body })
+                },
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(t) if t.status.is_server_error() == false || attempt == self.retries => return Ok(t),
+                Err(e) if is_transient(&e) == false || attempt == self.retries => return Err(e),
+                _ => (),
+            }
+            tokio::time::sleep(backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Sends a single GET request for `url`, failing when no response arrives
+    /// within the timeout.
+    async fn request(&self, client: &reqwest::Client, url: &str) -> Result<reqwest::Response, Fault> {
+        match self.timeout {
+            Some(secs) => match tokio::time::timeout(Duration::from_secs(secs), client.get(url).send()).await {
+                Ok(r) => Ok(r?),
+                Err(_) => Err(NetworkError::TimedOut(url.to_string(), secs))?,
+            },
+            None => Ok(client.get(url).send().await?),
+        }
+    }
+
+    /// Reads the body of the response `res`, failing once the transfer stalls
+    /// for longer than the timeout.
+    async fn read(&self, mut res: reqwest::Response) -> Result<Vec<u8>, Fault> {
+        let url = res.url().to_string();
+        let mut body = Vec::new();
+        loop {
+            let chunk = match self.timeout {
+                Some(secs) => match tokio::time::timeout(Duration::from_secs(secs), res.chunk()).await {
+                    Ok(c) => c?,
+                    Err(_) => return Err(NetworkError::Stalled(url, secs))?,
+                },
+                None => res.chunk().await?,
+            };
+            match chunk {
+                Some(bytes) => body.extend_from_slice(&bytes),
+                None => return Ok(body),
+            }
+        }
+    }
+
+    /// Runs the remote operation `f`, running it again after a delay when it fails
+    /// with an error that `transient` accepts. Other errors are returned at once.
+    ///
+    /// The delay between attempts starts at one second and doubles each retry.
+    pub fn retry<T>(&self, transient: impl Fn(&Fault) -> bool, mut f: impl FnMut() -> Result<T, Fault>) -> Result<T, Fault> {
+        let mut attempt = 0;
+        loop {
+            match f() {
+                Err(e) if attempt < self.retries && transient(&e) => {
+                    std::thread::sleep(backoff(attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Builds the `-c` options that configure a git subprocess with these settings.
+    ///
+    /// The options must be passed before the git subcommand.
+    pub fn to_git_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(bundle) = &self.ca_bundle {
+            args.push(String::from("-c"));
+            args.push(format!("http.sslCAInfo={}", bundle.display()));
+        }
+        // abort transfers that stall for the timeout
+        if let Some(secs) = self.timeout {
+            args.push(String::from("-c"));
+            args.push(String::from("http.lowSpeedLimit=1"));
+            args.push(String::from("-c"));
+            args.push(format!("http.lowSpeedTime={}", secs));
+        }
+        args
+    }
+
+    /// Builds the proxy environment variables for a git subprocess.
+    ///
+    /// Git only reaches HTTP(S) remotes through these proxies.
+    pub fn to_git_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
+        if let Some(p) = &self.http_proxy {
+            env.push(("http_proxy", p.clone()));
+        }
+        if let Some(p) = &self.https_proxy {
+            env.push(("https_proxy", p.clone()));
+        }
+        if self.no_proxy.is_empty() == false {
+            env.push(("no_proxy", self.no_proxy.join(",")));
+        }
+        env
+    }
+
+    /// Applies these settings to the git subprocess `cmd` before its subcommand
+    /// is added.
+    pub fn apply_git(&self, cmd: &mut std::process::Command) {
+        cmd.args(self.to_git_args()).envs(self.to_git_env());
+    }
+}
+
+/// A response received in its entirety.
+#[derive(Debug, PartialEq)]
+pub struct Transfer {
+    /// the url that answered, after any redirects
+    pub url: String,
+    pub status: reqwest::StatusCode,
+    pub body: Vec<u8>,
+}
+
+/// Checks if the request error `e` may not occur when the request is sent again.
+fn is_transient(e: &Fault) -> bool {
+    match (e.downcast_ref::<reqwest::Error>(), e.downcast_ref::<NetworkError>()) {
+        // a connection dropped while sending the body is also a body error
+        (Some(e), _) => e.is_connect() || e.is_timeout() || e.is_body(),
+        (_, Some(e)) => !matches!(e, NetworkError::Offline(_)),
+        _ => false,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NetworkError {
+    TimedOut(String, u64),
+    Stalled(String, u64),
+    Offline(String),
+}
+
+impl std::error::Error for NetworkError {}
+
+impl std::fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TimedOut(url, secs) => write!(f, "no response after {} seconds\n\nurl: {}", secs, url),
+            Self::Stalled(url, secs) => write!(f, "transfer stalled for {} seconds\n\nurl: {}", secs, url),
+            Self::Offline(remote) => write!(f, "cannot reach {} while offline\n\nTry again without `--offline`", remote),
+        }
+    }
+}
+
+/// Checks if the source `url` is on a remote host rather than a local path.
+///
+/// Scp-like ssh urls (`git@host:path`) are remote.
+fn is_remote(url: &str) -> bool {
+    match reqwest::Url::parse(url) {
+        // a windows drive letter parses as a one-letter scheme
+        Ok(u) => u.scheme() != "file" && u.scheme().len() > 1,
+        Err(_) => match url.find(':') {
+            Some(i) => !url[..i].contains('/') && !std::path::Path::new(url).exists(),
+            None => false,
+        },
+    }
+}
+
+/// Checks if the `no-proxy` entry `pattern` covers the `host`.
+///
+/// An entry covers its domain and every subdomain, and `*` covers every host.
+fn is_host_match(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().trim_start_matches('.');
+    pattern == "*" || host.eq_ignore_ascii_case(pattern) || host.to_lowercase().ends_with(&format!(".{}", pattern.to_lowercase()))
+}
+
+/// Splits a PEM bundle `text` into its individual certificates.
+fn split_certificates(text: &str) -> Vec<String> {
+    const END: &str = "-----END CERTIFICATE-----";
+    text.split_inclusive(END)
+        .filter(|c| c.contains(END))
+        .map(|c| c.trim().to_string())
+        .collect()
+}
+
+/// Returns the delay before the retry following the `attempt`.
+fn backoff(attempt: usize) -> Duration {
+    Duration::from_secs(1 << attempt.min(6))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(text: &str) -> (tempfile::TempDir, Config) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, text).unwrap();
+        let cfg = Config::from_path(&path).unwrap();
+        (dir, cfg)
+    }
+
+    #[test]
+    fn from_config() {
+        let (dir, cfg) = config("\
+[network]
+proxy = \"http://proxy.ks-tech.com:8080\"
+https-proxy = \"http://secure.ks-tech.com:8443\"
+no-proxy = [\"ks-tech.com\", \"localhost\"]
+ca-bundle = \"certs/ks-tech.pem\"
+timeout = 30
+retries = 2
+");
+        let net = Network::from_config(&cfg).unwrap();
+        assert_eq!(net, Network {
+            http_proxy: Some(String::from("http://proxy.ks-tech.com:8080")),
+            https_proxy: Some(String::from("http://secure.ks-tech.com:8443")),
+            no_proxy: vec![String::from("ks-tech.com"), String::from("localhost")],
+            ca_bundle: Some(dir.path().join("certs/ks-tech.pem")),
+            timeout: Some(30),
+            retries: 2,
+            offline: false,
+        });
+
+        // defaults
+        let (_dir, cfg) = config("");
+        assert_eq!(Network::from_config(&cfg).unwrap(), Network::new());
+
+        // bad values
+        for text in ["[network]\nproxy = \"not a url\"", "[network]\ntimeout = 0", "[network]\nretries = -1", "[network]\ntimeout = \"30\""] {
+            let (_dir, cfg) = config(text);
+            assert_eq!(Network::from_config(&cfg).is_err(), true, "{}", text);
+        }
+    }
+
+    #[test]
+    fn proxy_by_host() {
+        let net = Network::new().with_proxy("http://proxy.ks-tech.com:8080");
+        let net = Network { no_proxy: vec![String::from(".ks-tech.com")], https_proxy: None, ..net };
+        assert_eq!(net.proxy_for("http://github.com/c-rus/gates.git"), Some("http://proxy.ks-tech.com:8080"));
+        assert_eq!(net.proxy_for("https://github.com/c-rus/gates.git"), None);
+        // listed hosts and their subdomains are reached directly
+        assert_eq!(net.proxy_for("http://ks-tech.com/orbit"), None);
+        assert_eq!(net.proxy_for("http://git.KS-TECH.com/orbit"), None);
+        assert_eq!(net.proxy_for("http://notks-tech.com/orbit"), Some("http://proxy.ks-tech.com:8080"));
+        assert_eq!(Network::new().proxy_for("https://github.com"), None);
+    }
+
+    #[test]
+    fn git_settings() {
+        let net = Network {
+            http_proxy: Some(String::from("http://proxy:8080")),
+            https_proxy: Some(String::from("http://proxy:8443")),
+            no_proxy: vec![String::from("localhost"), String::from("ks-tech.com")],
+            ca_bundle: Some(PathBuf::from("/etc/ks-tech.pem")),
+            timeout: Some(20),
+            retries: 0,
+            offline: false,
+        };
+        assert_eq!(net.to_git_args(), vec![
+            "-c", "http.sslCAInfo=/etc/ks-tech.pem", "-c", "http.lowSpeedLimit=1", "-c", "http.lowSpeedTime=20"
+        ]);
+        assert_eq!(net.to_git_env(), vec![
+            ("http_proxy", String::from("http://proxy:8080")),
+            ("https_proxy", String::from("http://proxy:8443")),
+            ("no_proxy", String::from("localhost,ks-tech.com")),
+        ]);
+        assert_eq!(Network::new().to_git_args().is_empty(), true);
+        assert_eq!(Network::new().to_git_env().is_empty(), true);
+    }
+
+    #[test]
+    fn retry_attempts() {
+        let transient = |e: &Fault| e.to_string() == "down";
+        let mut calls = 0;
+        let result: Result<(), Fault> = Network::new().retry(transient, || { calls += 1; Err(AnyError(String::from("down")))? });
+        assert_eq!(result.is_err(), true);
+        assert_eq!(calls, 1);
+
+        let net = Network { retries: 1, ..Network::new() };
+        let mut calls = 0;
+        let result = net.retry(transient, || { calls += 1; match calls { 1 => Err(AnyError(String::from("down")))?, n => Ok(n) } });
+        assert_eq!(result.unwrap(), 2);
+
+        // permanent failures are not tried again
+        let mut calls = 0;
+        let result: Result<(), Fault> = net.retry(transient, || { calls += 1; Err(AnyError(String::from("denied")))? });
+        assert_eq!(result.is_err(), true);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn stalled_transfer() {
+        use std::io::{Read, Write};
+        // a host that stops responding partway through the body twice before
+        // sending the entire body
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/orbit.zip", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut held = Vec::new();
+            for body in [&b"PK"[..], &b"PK"[..], &b"PK\x03\x04zipped"[..]] {
+                let (mut stream, _) = listener.accept().unwrap();
+                stream.read(&mut [0; 1024]).unwrap();
+                stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 10\r\n\r\n").unwrap();
+                stream.write_all(body).unwrap();
+                held.push(stream);
+            }
+        });
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let net = Network { timeout: Some(1), ..Network::new() };
+        let err = runtime.block_on(net.download(&net.client().unwrap(), &url)).unwrap_err();
+        assert_eq!(err.downcast_ref::<NetworkError>(), Some(&NetworkError::Stalled(url.clone(), 1)));
+        assert_eq!(is_transient(&err), true);
+
+        // the request is sent again after the transfer stalls
+        let net = Network { timeout: Some(1), retries: 1, ..Network::new() };
+        let transfer = runtime.block_on(net.download(&net.client().unwrap(), &url)).unwrap();
+        assert_eq!(transfer.status, reqwest::StatusCode::OK);
+        assert_eq!(transfer.body, b"PK\x03\x04zipped");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn offline() {
+        let net = Network::new().with_offline(true);
+        for url in ["https://github.com/ks-tech/gates.git", "git@github.com:ks-tech/gates.git", "ssh://git@github.com/ks-tech/gates.git"] {
+            let err = net.connect(url).unwrap_err();
+            assert_eq!(err.downcast_ref::<NetworkError>(), Some(&NetworkError::Offline(url.to_string())));
+            assert_eq!(is_transient(&err), false);
+        }
+        // local sources can always be reached
+        for url in ["/mirrors/gates.git", "../gates", "file:///mirrors/gates.git", "C:/mirrors/gates.git"] {
+            assert_eq!(net.connect(url).is_ok(), true, "{}", url);
+        }
+        assert_eq!(net.require_online("the remotes of repository gates").is_err(), true);
+        assert_eq!(Network::new().connect("https://github.com/ks-tech/gates.git").is_ok(), true);
+
+        // requests are refused before anything is sent
+        let result = tokio::runtime::Runtime::new().unwrap().block_on(async {
+            net.send(&net.client().unwrap(), "http://127.0.0.1:9/orbit.zip").await
+        });
+        assert_eq!(result.unwrap_err().downcast_ref::<NetworkError>(), Some(&NetworkError::Offline(String::from("http://127.0.0.1:9/orbit.zip"))));
+    }
+
+    #[test]
+    fn certificate_bundle() {
+        let bundle = "\
+# root
+-----BEGIN CERTIFICATE-----
+AAAA
+-----END CERTIFICATE-----
+-----BEGIN CERTIFICATE-----
+BBBB
+-----END CERTIFICATE-----
+";
+        assert_eq!(split_certificates(bundle), vec![
+            "# root\n-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----",
+            "-----BEGIN CERTIFICATE-----\nBBBB\n-----END CERTIFICATE-----",
+        ]);
+        assert_eq!(split_certificates("").is_empty(), true);
+    }
+}
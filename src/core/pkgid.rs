@@ -23,6 +23,11 @@ impl PkgPart {
     pub fn as_ref(&self) -> &str {
         &self.0
     }
+
+    /// Checks if the part begins `other` when both are normalized.
+    pub fn is_prefix_of(&self, other: &PkgPart) -> bool {
+        other.to_normal().0.starts_with(&self.to_normal().0)
+    }
 }
 
 impl AsRef<std::path::Path> for PkgPart {
@@ -166,6 +171,16 @@ impl PkgId {
         true
     }
 
+    /// Checks if each part given in the current name begins the same part of `other`.
+    /// 
+    /// Assumes `other` is fully qualified.
+    pub fn prefix_match(&self, other: &PkgId) -> bool {
+        if self.name.is_prefix_of(&other.name) == false { return false }
+        if let (Some(l), Some(o)) = (&self.library, &other.library) { if l.is_prefix_of(o) == false { return false } }
+        if let (Some(v), Some(o)) = (&self.vendor, &other.vendor) { if v.is_prefix_of(o) == false { return false } }
+        true
+    }
+
     pub fn into_hash(&self) -> u64 {
         let mut hasher = DefaultHasher::new();
         self.hash(&mut hasher);
@@ -607,4 +622,16 @@ mod test {
         let p2 = PkgId::from_str("ks-tech").unwrap();
         assert_eq!(p2.partial_match(&p1), false);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn prefix_match() {
+        let p1 = PkgId::from_str("ks-tech.rary.gates").unwrap();
+        assert_eq!(PkgId::from_str("gat").unwrap().prefix_match(&p1), true);
+        assert_eq!(PkgId::from_str("GAT").unwrap().prefix_match(&p1), true);
+        assert_eq!(PkgId::from_str("ra.gat").unwrap().prefix_match(&p1), true);
+        assert_eq!(PkgId::from_str("ks_.rary.").unwrap().prefix_match(&p1), true);
+        assert_eq!(PkgId::from_str("ks-tech.rary.gates").unwrap().prefix_match(&p1), true);
+        assert_eq!(PkgId::from_str("ates").unwrap().prefix_match(&p1), false);
+        assert_eq!(PkgId::from_str("lib.gat").unwrap().prefix_match(&p1), false);
+    }
+}
@@ -2,10 +2,12 @@ use std::path::PathBuf;
 use std::error::Error;
 use crate::core::fileset::Fileset;
 use crate::core::config::FromToml;
-use crate::util::anyerror::{AnyError, Fault};
+use crate::util::anyerror::{AnyError, Category, CategoryError, Fault};
 use crate::util::filesystem;
 use super::config::FromTomlError;
 use super::context::Context;
+use super::template;
+use super::variable::VariableTable;
 
 #[derive(Debug, PartialEq)]
 pub struct Plugin {
@@ -16,6 +18,7 @@ pub struct Plugin {
     summary: Option<String>,
     details: Option<String>,
     root: Option<PathBuf>,
+    source: Option<PathBuf>,
 }
 
 impl Plugin {
@@ -28,6 +31,7 @@ impl Plugin {
             summary: None,
             details: None,
             root: None,
+            source: None,
             filesets: Vec::new(),
         }
     }
@@ -49,29 +53,63 @@ impl Plugin {
         list
     }
 
-    /// Runs the given `command` with the set `args` for the plugin.
-    pub fn execute(&self, extra_args: &[String], verbose: bool) -> Result<(), Fault> {
+    /// Determines the command and its arguments to run for the plugin with `extra_args`
+    /// appended.
+    /// 
+    /// Environment variables written as `${NAME}` in the configured command and arguments
+    /// are expanded. Errors if a variable is not defined.
+    pub fn resolve_command(&self, extra_args: &[String]) -> Result<(String, Vec<String>), Fault> {
+        let vars = VariableTable::new().load_system();
+        let expand = |text: &str| -> Result<String, AnyError> {
+            template::expand(text, &vars).map_err(|e| AnyError(format!("plugin '{}': {}", self.alias, e)))
+        };
         // resolve the relative paths in the command and arguments defined in original configuration
         let root_path = self.root.as_ref().expect("root path not defined for plugin");
-        let command = crate::util::filesystem::resolve_rel_path(root_path, &self.command);
-        let arguments: Vec<String> = self.args.iter()
-            .map(|f| crate::util::filesystem::resolve_rel_path(root_path, f) )
-            .collect();
+        let command = crate::util::filesystem::resolve_rel_path(root_path, &expand(&self.command)?);
+        let mut arguments: Vec<String> = Vec::with_capacity(self.args.len());
+        for arg in &self.args {
+            arguments.push(crate::util::filesystem::resolve_rel_path(root_path, &expand(arg)?));
+        }
         // append args set on the command-line to the base-line of arguments
-        let args = [&arguments, extra_args].concat();
+        Ok((command, [&arguments, extra_args].concat()))
+    }
+
+    /// Runs the given `command` with the set `args` for the plugin.
+    /// 
+    /// The plugin's output is copied to `log` as it is displayed.
+    pub fn execute(&self, extra_args: &[String], verbose: bool, log: &std::fs::File) -> Result<(), Fault> {
+        let (command, args) = self.resolve_command(extra_args)?;
         // display the literal command being ran
         if verbose == true {
             let s = args.iter().fold(String::new(), |x, y| { x + "\"" + &y + "\" " });
             println!("running: {} {}", command, s);
         }
-        let mut proc = crate::util::filesystem::invoke(&command, &args, Context::enable_windows_bat_file_match())?;
-        let exit_code = proc.wait()?;
+        let exit_code = crate::util::filesystem::invoke_teed(&command, &args, log, Context::enable_windows_bat_file_match())?;
         match exit_code.code() {
-            Some(num) => if num != 0 { Err(AnyError(format!("exited with error code: {}", num)))? } else { Ok(()) },
-            None =>  Err(AnyError(format!("terminated by signal")))?
+            Some(num) => if num != 0 { Err(CategoryError(Category::Plugin, format!("exited with error code: {}", num)))? } else { Ok(()) },
+            None =>  Err(CategoryError(Category::Plugin, String::from("terminated by signal")))?
         }
     }
 
+    /// Describes the plugin as a JSON object.
+    /// 
+    /// The `config` field is the configuration file or plugin manifest that
+    /// defines the plugin.
+    pub fn to_json(&self) -> serde_json::Value {
+        let filesets: serde_json::Map<String, serde_json::Value> = self.filesets.iter()
+            .map(|f| (f.get_name().to_string(), serde_json::Value::from(f.get_patterns())))
+            .collect();
+        serde_json::json!({
+            "alias": self.alias,
+            "command": self.command,
+            "args": self.args,
+            "filesets": filesets,
+            "summary": self.summary,
+            "details": self.details,
+            "config": self.source.as_ref().map(|s| filesystem::normalize_path(s.to_path_buf()).display().to_string()),
+        })
+    }
+
     /// References the plugin's `alias`.
     pub fn alias(&self) -> &String {
         &self.alias
@@ -87,6 +125,42 @@ impl Plugin {
         self.root = Some(root.to_path_buf());
         self
     }
+
+    /// Sets the file that defines the plugin.
+    pub fn set_source(mut self, file: &PathBuf) -> Self {
+        self.source = Some(file.to_path_buf());
+        self
+    }
+
+    /// Reads the plugins defined in the plugin manifest inside the directory `dir`.
+    /// 
+    /// Each plugin's paths are resolved from `dir`. Returns an empty list if the
+    /// directory does not have a plugin manifest.
+    pub fn read_manifest(dir: &PathBuf) -> Result<Vec<Self>, Fault> {
+        let file = dir.join(PLUGIN_MANIFEST_FILE);
+        if file.is_file() == false {
+            return Ok(Vec::new())
+        }
+        let display = filesystem::normalize_path(file.clone()).display().to_string();
+        let doc = std::fs::read_to_string(&file)?
+            .parse::<toml_edit::Document>()
+            .map_err(|e| AnyError(format!("plugin manifest {}: {}", display, e)))?;
+        let tables = match doc.get("plugin") {
+            Some(item) => match item.as_array_of_tables() {
+                Some(arr) => arr,
+                None => return Err(AnyError(format!("plugin manifest {}: expecting toml array of tables for key 'plugin'", display)))?,
+            },
+            None => return Ok(Vec::new()),
+        };
+        let mut plugs = Vec::new();
+        for tbl in tables {
+            match Self::from_toml(tbl) {
+                Ok(p) => plugs.push(p.set_root(dir).set_source(&file)),
+                Err(e) => return Err(AnyError(format!("plugin manifest {}: plugin {}", display, e)))?,
+            }
+        }
+        Ok(plugs)
+    }
 }
 
 impl std::fmt::Display for Plugin {
@@ -100,7 +174,7 @@ filesets:
             self.alias,
             self.command, self.args.iter().fold(String::new(), |x, y| { x + "\"" + &y + "\" " }),
             filesystem::normalize_path(self.root.as_ref().unwrap().to_path_buf()).display(),
-            { if self.filesets.is_empty() { String::from("    None\n") } else { self.filesets.iter().fold(String::new(), |x, y| { x + &format!("    {:<16}{}\n", y.get_name(), y.get_pattern())}) } },
+            { if self.filesets.is_empty() { String::from("    None\n") } else { self.filesets.iter().fold(String::new(), |x, y| { x + &format!("    {:<16}{}\n", y.get_name(), y.get_patterns().join(", "))}) } },
             { if let Some(text) = &self.summary { format!("\n{}\n", text) } else { String::new() } },
             { if let Some(text) = &self.details { format!("\n{}", text) } else { String::new() } },
         )
@@ -125,6 +199,7 @@ impl FromToml for Plugin {
                 Vec::new()
             },
             root: None,
+            source: None,
             summary: Self::get(table, "summary")?,
             details: Self::get(table, "details")?,
             filesets: {
@@ -134,8 +209,18 @@ impl FromToml for Plugin {
                     let mut iter = inner_table.iter();
                     let mut filesets = Vec::new();
                     while let Some((key, value)) = iter.next() {
-                        let value = value.as_str().unwrap(); 
-                        filesets.push(Fileset::new().name(key).pattern(value).unwrap())
+                        // a fileset is a single pattern or a list of patterns
+                        let patterns: Vec<&str> = match (value.as_str(), value.as_array()) {
+                            (Some(s), _) => vec![s],
+                            (None, Some(arr)) if arr.iter().all(|v| v.is_str()) => arr.iter().map(|v| v.as_str().unwrap()).collect(),
+                            _ => return Err(FromTomlError::ExpectingStringArray(format!("fileset.{}", key)))?,
+                        };
+                        let mut fset = Fileset::new().name(key);
+                        for pattern in patterns {
+                            fset = fset.pattern(pattern)
+                                .map_err(|e| FromTomlError::BadParse(format!("fileset.{}", key), pattern.to_string(), e.to_string()))?;
+                        }
+                        filesets.push(fset.verify().map_err(|e| FromTomlError::BadParse(format!("fileset.{}", key), value.to_string().trim().to_string(), e.to_string()))?)
                     }
                     filesets
                 } else {
@@ -147,6 +232,9 @@ impl FromToml for Plugin {
     }
 }
 
+/// File inside a directory of `ORBIT_PLUGIN_PATH` that defines its plugins.
+pub const PLUGIN_MANIFEST_FILE: &str = "plugin.toml";
+
 #[derive(Debug, PartialEq)]
 pub enum PluginError {
     Missing(String)
@@ -173,6 +261,7 @@ mod test {
             summary: None,
             details: None,
             root: None,
+            source: None,
             alias: String::new(), 
             command: String::new(), 
             args: Vec::new(),
@@ -195,6 +284,7 @@ details = "more info"
         assert_eq!(plug, Plugin { 
             summary: None,
             root: None,
+            source: None,
             details: Some(String::from("more info")),
             alias: String::from("ghdl"), 
             command: String::from("python"), 
@@ -206,4 +296,63 @@ details = "more info"
             ],
         });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn to_json() {
+        let toml = r#"
+[[plugin]]
+alias = "ghdl"
+command = "python"
+args = ["orbit-ghdl.py"]
+fileset.py-model = "*_mdl.py"
+summary = "ghdl flow"
+"#;
+        let doc = toml.parse::<toml_edit::Document>().unwrap();
+        let plug = Plugin::from_toml(&doc["plugin"].as_array_of_tables().unwrap().get(0).unwrap()).unwrap()
+            .set_root(&PathBuf::from("/home/kepler/.orbit"))
+            .set_source(&PathBuf::from("/home/kepler/.orbit/config.toml"));
+        let json = plug.to_json();
+        assert_eq!(json["alias"], "ghdl");
+        assert_eq!(json["args"], serde_json::json!(["orbit-ghdl.py"]));
+        assert_eq!(json["filesets"], serde_json::json!({ "PY-MODEL": ["**/*_mdl.py"] }));
+        assert_eq!(json["summary"], "ghdl flow");
+        assert_eq!(json["details"], serde_json::Value::Null);
+        assert_eq!(json["config"], "/home/kepler/.orbit/config.toml");
+    }
+
+    #[test]
+    fn read_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        assert_eq!(Plugin::read_manifest(&root).unwrap(), Vec::new());
+
+        std::fs::write(root.join(PLUGIN_MANIFEST_FILE), "[[plugin]]\nalias = \"vivado\"\ncommand = \"python\"\nargs = [\"vivado.py\"]\n").unwrap();
+        std::fs::write(root.join("vivado.py"), "").unwrap();
+        let plugs = Plugin::read_manifest(&root).unwrap();
+        assert_eq!(plugs.len(), 1);
+        assert_eq!(plugs[0].alias(), "vivado");
+        assert_eq!(plugs[0].resolve_command(&[]).unwrap().1, vec![filesystem::normalize_path(root.join("vivado.py")).display().to_string()]);
+        assert_eq!(plugs[0].to_json()["config"], filesystem::normalize_path(root.join(PLUGIN_MANIFEST_FILE)).display().to_string());
+
+        std::fs::write(root.join(PLUGIN_MANIFEST_FILE), "plugin = \"vivado\"\n").unwrap();
+        assert!(Plugin::read_manifest(&root).is_err());
+    }
+
+    #[test]
+    fn from_toml_fileset_list() {
+        let toml = r#"
+[[plugin]]
+alias = "ghdl"
+command = "python"
+fileset.vhdl-sim = ["*_tb.vhd", "!**/old/**"]
+fileset.bad = ["!*.vhd"]
+"#;
+        let doc = toml.parse::<toml_edit::Document>().unwrap();
+        assert!(Plugin::from_toml(&doc["plugin"].as_array_of_tables().unwrap().get(0).unwrap()).is_err());
+
+        let toml = toml.replace("fileset.bad = [\"!*.vhd\"]\n", "");
+        let doc = toml.parse::<toml_edit::Document>().unwrap();
+        let plug = Plugin::from_toml(&doc["plugin"].as_array_of_tables().unwrap().get(0).unwrap()).unwrap();
+        assert_eq!(plug.filesets()[0].get_patterns(), vec!["**/*_tb.vhd", "!**/old/**"]);
+    }
+}
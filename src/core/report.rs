@@ -0,0 +1,271 @@
+//! Local records of the plans and builds run within a build directory.
+//!
+//! Recording is opt-in through the `core.report` configuration entry. Each run
+//! appends one tab-separated line to the report file, and nothing leaves the
+//! machine.
+
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, FixedOffset, Local};
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::environment::{self, Environment};
+use super::config::Config;
+
+/// File within the build directory that keeps a record of every run.
+pub const REPORT_FILE: &str = "report.tsv";
+
+/// Checks the configuration for whether runs are recorded.
+pub fn is_enabled(config: &Config) -> Result<bool, Fault> {
+    match config.get_as_str("core", "report")? {
+        None | Some("off") => Ok(false),
+        Some("on") => Ok(true),
+        Some(v) => Err(AnyError(format!("invalid value '{}' for config entry 'core.report'; expecting 'on' or 'off'", v)))?,
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Step {
+    Plan,
+    Build,
+}
+
+impl FromStr for Step {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plan" => Ok(Self::Plan),
+            "build" => Ok(Self::Build),
+            _ => Err(format!("unknown step '{}'", s)),
+        }
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Plan => write!(f, "plan"),
+            Self::Build => write!(f, "build"),
+        }
+    }
+}
+
+/// A run of a step that has started but has not been recorded.
+pub struct Run {
+    step: Step,
+    started: DateTime<Local>,
+    clock: Instant,
+}
+
+impl Run {
+    pub fn start(step: Step) -> Self {
+        Self { step, started: Local::now(), clock: Instant::now() }
+    }
+
+    /// Appends the record of the run to the report next to the `blueprint` when
+    /// recording is enabled.
+    ///
+    /// The top-level, testbench, and number of files are read from the last plan
+    /// written to the blueprint's build directory.
+    pub fn finish(self, config: &Config, blueprint: &Path, plugin: Option<&str>, success: bool) -> Result<(), Fault> {
+        let build_path = match blueprint.parent() {
+            Some(p) if p.is_dir() => p,
+            _ => return Ok(()),
+        };
+        if !is_enabled(config)? {
+            return Ok(())
+        }
+        let envs = Environment::new().from_env_file(&build_path.to_path_buf())?;
+        let read = |key: &str| envs.get(key).map(|e| e.get_value().to_string()).unwrap_or_default();
+        let files = match std::fs::read_to_string(blueprint) {
+            Ok(text) => text.lines().filter(|l| !l.is_empty()).count(),
+            Err(_) => 0,
+        };
+        let record = Record {
+            started: self.started.into(),
+            step: self.step,
+            success,
+            duration: self.clock.elapsed(),
+            files,
+            plugin: plugin.unwrap_or_default().to_string(),
+            top: read(environment::ORBIT_TOP),
+            bench: read(environment::ORBIT_BENCH),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        };
+        let mut log = std::fs::OpenOptions::new().create(true).append(true).open(build_path.join(REPORT_FILE))?;
+        std::io::Write::write_all(&mut log, format!("{}\n", record).as_bytes())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Record {
+    started: DateTime<FixedOffset>,
+    step: Step,
+    success: bool,
+    duration: Duration,
+    files: usize,
+    plugin: String,
+    top: String,
+    bench: String,
+    version: String,
+}
+
+impl Record {
+    pub fn get_started(&self) -> &DateTime<FixedOffset> {
+        &self.started
+    }
+
+    pub fn get_step(&self) -> Step {
+        self.step
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.success
+    }
+
+    pub fn get_duration(&self) -> &Duration {
+        &self.duration
+    }
+
+    /// Accesses the number of files in the blueprint.
+    pub fn get_files(&self) -> usize {
+        self.files
+    }
+
+    /// References the plugin or command that ran, which is empty if none was given.
+    pub fn get_plugin(&self) -> &str {
+        &self.plugin
+    }
+
+    pub fn get_top(&self) -> &str {
+        &self.top
+    }
+
+    pub fn get_bench(&self) -> &str {
+        &self.bench
+    }
+
+    /// References the version of orbit that performed the run.
+    pub fn get_version(&self) -> &str {
+        &self.version
+    }
+}
+
+impl FromStr for Record {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split('\t').collect();
+        if fields.len() != 9 {
+            return Err(format!("expecting 9 fields but found {}", fields.len()))
+        }
+        let text = |i: usize| match fields[i] {
+            "-" => String::new(),
+            f => f.to_string(),
+        };
+        Ok(Self {
+            started: DateTime::parse_from_rfc3339(fields[0]).map_err(|e| format!("invalid time '{}': {}", fields[0], e))?,
+            step: fields[1].parse()?,
+            success: match fields[2] {
+                "ok" => true,
+                "failed" => false,
+                _ => return Err(format!("unknown status '{}'", fields[2])),
+            },
+            duration: Duration::from_millis(fields[3].parse().map_err(|_| format!("invalid duration '{}'", fields[3]))?),
+            files: fields[4].parse().map_err(|_| format!("invalid number of files '{}'", fields[4]))?,
+            plugin: text(5),
+            top: text(6),
+            bench: text(7),
+            version: text(8),
+        })
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = |s: &str| match s.is_empty() {
+            true => String::from("-"),
+            false => s.to_string(),
+        };
+        write!(f, "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.started.to_rfc3339(),
+            self.step,
+            match self.success { true => "ok", false => "failed" },
+            self.duration.as_millis(),
+            self.files,
+            text(&self.plugin),
+            text(&self.top),
+            text(&self.bench),
+            text(&self.version),
+        )
+    }
+}
+
+/// Reads every record from the report in `build_path`, oldest first.
+///
+/// Returns an empty list if no runs have been recorded.
+pub fn read(build_path: &Path) -> Result<Vec<Record>, Fault> {
+    let path = build_path.join(REPORT_FILE);
+    if !path.exists() {
+        return Ok(Vec::new())
+    }
+    let mut records = Vec::new();
+    for (i, line) in std::fs::read_to_string(&path)?.lines().enumerate() {
+        if line.is_empty() {
+            continue
+        }
+        match line.parse::<Record>() {
+            Ok(r) => records.push(r),
+            Err(e) => return Err(AnyError(format!("{} line {}: {}", path.display(), i + 1, e)))?,
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_round_trip() {
+        let line = "2022-08-01T09:30:00+00:00\tbuild\tfailed\t1520\t12\tfakesim\tcounter\t-\t0.9.0";
+        let record: Record = line.parse().unwrap();
+        assert_eq!(record.get_step(), Step::Build);
+        assert!(!record.is_success());
+        assert_eq!(record.get_duration(), &Duration::from_millis(1520));
+        assert_eq!(record.get_files(), 12);
+        assert_eq!(record.get_bench(), "");
+        assert_eq!(record.to_string(), line);
+
+        assert!("2022-08-01T09:30:00+00:00\tbuild\tok\t1520".parse::<Record>().is_err());
+        assert!("yesterday\tplan\tok\t1\t1\t-\t-\t-\t-".parse::<Record>().is_err());
+    }
+
+    #[test]
+    fn records_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".env"), "ORBIT_TOP=counter\nORBIT_BENCH=counter_tb\n").unwrap();
+        std::fs::write(dir.path().join("blueprint.tsv"), "VHDL-RTL\twork\tcounter.vhd\nVHDL-SIM\twork\tcounter_tb.vhd\n").unwrap();
+        let blueprint = dir.path().join("blueprint.tsv");
+        let mut config = Config::new();
+        config.set("core", "report", "on");
+
+        Run::start(Step::Plan).finish(&config, &blueprint, Some("fakesim"), true).unwrap();
+        Run::start(Step::Build).finish(&config, &blueprint, None, false).unwrap();
+        let records = read(dir.path()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get_top(), "counter");
+        assert_eq!(records[0].get_files(), 2);
+        assert_eq!(records[0].get_plugin(), "fakesim");
+        assert_eq!(records[1].get_step(), Step::Build);
+        assert!(!records[1].is_success());
+
+        // nothing is recorded unless enabled
+        let dir = tempfile::tempdir().unwrap();
+        Run::start(Step::Plan).finish(&Config::new(), &dir.path().join("blueprint.tsv"), None, true).unwrap();
+        assert!(read(dir.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+//! Software bill of materials (SBOM) for a planned design.
+//!
+//! The document follows the CycloneDX 1.4 JSON format. Every ip in the lockfile
+//! is a `library` component and every hdl file written to the blueprint is a
+//! `file` component.
+
+use std::path::Path;
+use serde_json::{json, Value};
+use crate::util::anyerror::Fault;
+use crate::util::checksum;
+use crate::util::sha256;
+use super::ip::IpFileNode;
+use super::lockfile::{LockEntry, LockFile};
+
+const SPEC_VERSION: &str = "1.4";
+
+#[derive(Debug, PartialEq)]
+struct FileComponent {
+    path: String,
+    library: String,
+    ip: String,
+    sum: sha256::Sha256Hash,
+}
+
+#[derive(Debug)]
+pub struct Sbom<'a> {
+    target: &'a LockEntry,
+    lock: &'a LockFile,
+    files: Vec<FileComponent>,
+}
+
+impl<'a> Sbom<'a> {
+    /// Collects the bill of materials for the `target` entry using every ip in the `lock`
+    /// and the ordered hdl `files` from the blueprint.
+    pub fn new(target: &'a LockEntry, lock: &'a LockFile, files: &[&IpFileNode]) -> Result<Self, Fault> {
+        let mut components = Vec::with_capacity(files.len());
+        for f in files {
+            let ip = f.get_ip_manifest();
+            // list the file relative to its ip so the document does not depend on the machine
+            let path = Path::new(f.get_file())
+                .strip_prefix(ip.get_root())
+                .map(|p| p.display().to_string().replace('\\', "/"))
+                .unwrap_or_else(|_| f.get_file().to_string());
+            components.push(FileComponent {
+                path,
+                library: f.get_library().to_string(),
+                ip: bom_ref(ip.get_pkgid(), ip.get_version()),
+                sum: checksum::file_sum(Path::new(f.get_file()))?,
+            });
+        }
+        Ok(Self { target, lock, files: components })
+    }
+
+    /// Writes the bill of materials as a JSON document to `path`.
+    pub fn write(&self, path: &Path) -> Result<(), Fault> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let doc = self.to_json(&chrono::offset::Utc::now().to_rfc3339());
+        std::fs::write(path, serde_json::to_string_pretty(&doc)? + "\n")?;
+        Ok(())
+    }
+
+    /// Creates the bill of materials as a JSON document created at `timestamp`.
+    fn to_json(&self, timestamp: &str) -> Value {
+        let mut components = Vec::new();
+        for entry in self.lock.inner() {
+            if entry.get_name() == self.target.get_name() && entry.get_version() == self.target.get_version() {
+                continue
+            }
+            components.push(ip_component(entry));
+        }
+        for f in &self.files {
+            components.push(json!({
+                "type": "file",
+                "bom-ref": format!("{}/{}", f.ip, f.path),
+                "name": f.path,
+                "hashes": [{ "alg": "SHA-256", "content": f.sum.to_string() }],
+                "properties": [
+                    { "name": "orbit:ip", "value": f.ip },
+                    { "name": "orbit:library", "value": f.library },
+                ],
+            }));
+        }
+
+        let dependencies: Vec<Value> = self.lock.inner()
+            .iter()
+            .map(|entry| {
+                let depends_on: Vec<String> = entry.get_deps()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|d| d.get_resolved().map(|v| bom_ref(d.get_name(), v)))
+                    .collect();
+                json!({
+                    "ref": bom_ref(entry.get_name(), entry.get_version()),
+                    "dependsOn": depends_on,
+                })
+            })
+            .collect();
+
+        json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": SPEC_VERSION,
+            "version": 1,
+            "metadata": {
+                "timestamp": timestamp,
+                "tools": [{ "vendor": "orbit", "name": "orbit", "version": env!("CARGO_PKG_VERSION") }],
+                "component": ip_component(self.target),
+            },
+            "components": components,
+            "dependencies": dependencies,
+        })
+    }
+}
+
+/// Creates the reference identifying the ip `name` at `version` within the document.
+fn bom_ref(name: &impl std::fmt::Display, version: &impl std::fmt::Display) -> String {
+    format!("{}@{}", name, version)
+}
+
+/// Creates a `library` component for the lock `entry`.
+fn ip_component(entry: &LockEntry) -> Value {
+    let group: Vec<String> = [entry.get_name().get_vendor(), entry.get_name().get_library()]
+        .iter()
+        .filter_map(|p| p.as_ref().map(|p| p.to_string()))
+        .collect();
+    let mut component = json!({
+        "type": "library",
+        "bom-ref": bom_ref(entry.get_name(), entry.get_version()),
+        "group": group.join("."),
+        "name": entry.get_name().get_name().to_string(),
+        "version": entry.get_version().to_string(),
+    });
+    if let Some(sum) = entry.get_sum() {
+        component["hashes"] = json!([{ "alg": "SHA-256", "content": sum.to_string() }]);
+    }
+    if let Some(src) = entry.get_source() {
+        component["externalReferences"] = json!([{ "type": "vcs", "url": src.to_string() }]);
+    }
+    if let Some(commit) = entry.get_commit() {
+        component["properties"] = json!([{ "name": "orbit:commit", "value": commit }]);
+    }
+    component
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use toml_edit::Document;
+    use crate::core::config::FromToml;
+
+    const LOCK: &str = r#"
+version = 2
+
+[[ip]]
+name = "ks-tech.rary.top"
+version = "0.1.0"
+dependencies = [{ name = "ks-tech.rary.gates", version = "1", resolved = "1.0.0" }]
+
+[[ip]]
+name = "ks-tech.rary.gates"
+version = "1.0.0"
+sum = "c4cb4e9c5b7e0e8b5f5b3bfe7b63bd1e2e48e4d0e2a5f0e5d7d4e0b5a4f3e2d1"
+source = "https://github.com/ks-tech/gates.git"
+dependencies = [{ name = "ks-tech.util", version = "2.1", resolved = "2.1.3" }]
+
+[[ip]]
+name = "ks-tech.util"
+version = "2.1.3"
+"#;
+
+    #[test]
+    fn dependency_graph() {
+        let lock = LockFile::from_toml(LOCK.parse::<Document>().unwrap().as_table()).unwrap();
+        let target = &lock.inner()[0];
+        let sbom = Sbom { target, lock: &lock, files: Vec::new() };
+        // the document is valid json
+        let doc: Value = serde_json::from_str(&serde_json::to_string_pretty(&sbom.to_json("2023-01-01T00:00:00+00:00")).unwrap()).unwrap();
+
+        assert_eq!(doc["bomFormat"], "CycloneDX");
+        assert_eq!(doc["metadata"]["timestamp"], "2023-01-01T00:00:00+00:00");
+        assert_eq!(doc["metadata"]["component"]["bom-ref"], "ks-tech.rary.top@0.1.0");
+
+        // the target is not listed among its components
+        let components = doc["components"].as_array().unwrap();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0]["bom-ref"], "ks-tech.rary.gates@1.0.0");
+        assert_eq!(components[0]["group"], "ks-tech.rary");
+        assert_eq!(components[0]["name"], "gates");
+        assert_eq!(components[0]["hashes"][0]["content"], "c4cb4e9c5b7e0e8b5f5b3bfe7b63bd1e2e48e4d0e2a5f0e5d7d4e0b5a4f3e2d1");
+        assert_eq!(components[0]["externalReferences"][0]["url"], "https://github.com/ks-tech/gates.git");
+        assert_eq!(components[1]["bom-ref"], "ks-tech.util@2.1.3");
+        assert_eq!(components[1]["group"], "ks-tech");
+        assert_eq!(components[1].get("hashes"), None);
+
+        // every ip lists the resolved versions it depends on
+        assert_eq!(doc["dependencies"], json!([
+            { "ref": "ks-tech.rary.top@0.1.0", "dependsOn": ["ks-tech.rary.gates@1.0.0"] },
+            { "ref": "ks-tech.rary.gates@1.0.0", "dependsOn": ["ks-tech.util@2.1.3"] },
+            { "ref": "ks-tech.util@2.1.3", "dependsOn": [] },
+        ]));
+    }
+}
@@ -0,0 +1,237 @@
+//! Verification of the signatures attached to ip releases.
+//!
+//! A release is signed by signing its checksum, the hex digest orbit writes to an
+//! installation's `.orbit-checksum` file. The detached signature is found among the
+//! released files as `.orbit-signature`, or in the vendor's registry next to the
+//! published manifest as `Orbit-<version>.sig`.
+//!
+//! Each vendor's trust policy is an entry of the `[[trust]]` array of tables in
+//! configuration, which decides whether a missing or invalid signature fails the
+//! installation or only warns.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use crate::util::anyerror::{AnyError, Category, CategoryError, Fault};
+use crate::util::sha256::Sha256Hash;
+use crate::util::theme;
+use super::config::FromToml;
+use super::manifest::{IpManifest, ORBIT_SIGNATURE_FILE};
+use super::pkgid::PkgPart;
+use super::vendor::VendorManifest;
+
+/// Maps each vendor's name to its trust policy.
+pub type TrustMap = HashMap<PkgPart, Trust>;
+
+/// What to do when a release's signature is missing or invalid.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Policy {
+    Off,
+    Warn,
+    Require,
+}
+
+impl FromStr for Policy {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "require" => Ok(Self::Require),
+            _ => Err(AnyError(format!("unknown policy '{}'; expecting 'off', 'warn', or 'require'", s))),
+        }
+    }
+}
+
+/// The program that checks signatures.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Method {
+    Gpg,
+    Minisign,
+}
+
+impl FromStr for Method {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gpg" => Ok(Self::Gpg),
+            "minisign" => Ok(Self::Minisign),
+            _ => Err(AnyError(format!("unknown method '{}'; expecting 'gpg' or 'minisign'", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gpg => write!(f, "gpg"),
+            Self::Minisign => write!(f, "minisign"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Trust {
+    vendor: PkgPart,
+    policy: Policy,
+    method: Method,
+    key: Option<String>,
+}
+
+impl FromToml for Trust {
+    type Err = Fault;
+
+    fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
+        let trust = Self {
+            vendor: Self::require(table, "vendor")?,
+            policy: Self::get(table, "policy")?.unwrap_or(Policy::Require),
+            method: Self::get(table, "method")?.unwrap_or(Method::Gpg),
+            key: Self::get(table, "key")?,
+        };
+        if trust.method == Method::Minisign && trust.key.is_none() {
+            return Err(AnyError(String::from("method 'minisign' requires a 'key'")))?
+        }
+        Ok(trust)
+    }
+}
+
+impl Trust {
+    /// References the name of the vendor the policy applies to.
+    pub fn get_vendor(&self) -> &PkgPart {
+        &self.vendor
+    }
+
+    pub fn get_policy(&self) -> Policy {
+        self.policy
+    }
+
+    /// Resolves a relative `key` path against the directory of the configuration that defined it.
+    pub fn resolve_key(mut self, root: &Path) -> Self {
+        if let Some(key) = &self.key {
+            let path = root.join(key);
+            if path.exists() {
+                self.key = Some(path.display().to_string());
+            }
+        }
+        self
+    }
+
+    /// Checks the `signature` file is a valid signature of the `checksum`.
+    fn check(&self, checksum: &Sha256Hash, signature: &Path) -> Result<(), Fault> {
+        let dir = tempfile::tempdir()?;
+        let message = dir.path().join("checksum");
+        std::fs::write(&message, checksum.to_string())?;
+        check_detached(self.method, self.key.as_deref(), &message, signature)
+    }
+}
+
+/// Checks the `signature` file is a valid detached signature of the `message` file
+/// using the program `method`.
+///
+/// For gpg, the `key` is a keyring holding the trusted public keys (default is the
+/// user's keyring). For minisign, it is a public key file or the public key itself.
+pub fn check_detached(method: Method, key: Option<&str>, message: &Path, signature: &Path) -> Result<(), Fault> {
+    let mut command = match method {
+        Method::Gpg => {
+            let mut cmd = std::process::Command::new("gpg");
+            cmd.arg("--batch");
+            if let Some(keyring) = key {
+                cmd.args(["--no-default-keyring", "--keyring", keyring]);
+            }
+            cmd.arg("--verify").arg(signature).arg(message);
+            cmd
+        },
+        Method::Minisign => {
+            let mut cmd = std::process::Command::new("minisign");
+            cmd.args(["-V", "-q"]);
+            match key {
+                Some(key) if Path::new(key).exists() => cmd.arg("-p").arg(key),
+                Some(key) => cmd.arg("-P").arg(key),
+                None => &mut cmd,
+            };
+            cmd.arg("-m").arg(message).arg("-x").arg(signature);
+            cmd
+        },
+    };
+    let output = match command.output() {
+        Ok(o) => o,
+        Err(e) => return Err(AnyError(format!("failed to run '{}': {}", method, e)))?,
+    };
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(AnyError(format!("{} rejected the signature: {}", method, String::from_utf8_lossy(&output.stderr).trim())))?,
+    }
+}
+
+/// Finds the signature of the installed `ip`, first among its files and then in
+/// its `vendor`'s registry.
+pub fn find_signature(ip: &IpManifest, vendor: Option<&VendorManifest>) -> Option<PathBuf> {
+    let local = ip.get_root().join(ORBIT_SIGNATURE_FILE);
+    if local.exists() {
+        return Some(local)
+    }
+    let pkgid = ip.get_pkgid();
+    let registry = vendor?.get_root()
+        .join(pkgid.get_library().as_ref()?.to_string())
+        .join(pkgid.get_name().to_string())
+        .join(format!("Orbit-{}.sig", ip.get_version()));
+    match registry.exists() {
+        true => Some(registry),
+        false => None,
+    }
+}
+
+/// Verifies the signature of the installed `ip` according to the trust policy for
+/// its vendor.
+///
+/// Errors only when the policy requires a valid signature; otherwise a problem is
+/// reported as a warning.
+pub fn verify(ip: &IpManifest, trust: &TrustMap, vendor: Option<&VendorManifest>) -> Result<(), Fault> {
+    let trust = match ip.get_pkgid().get_vendor().as_ref().and_then(|v| trust.get(v)) {
+        Some(t) if t.policy != Policy::Off => t,
+        _ => return Ok(()),
+    };
+    let problem = match (find_signature(ip, vendor), ip.read_checksum_proof()) {
+        (Some(signature), Some(checksum)) => match trust.check(&checksum, &signature) {
+            Ok(()) => return Ok(()),
+            Err(e) => format!("has an invalid signature: {}", e),
+        },
+        _ => String::from("is not signed"),
+    };
+    let message = format!("ip '{}' v{} {}", ip.get_pkgid(), ip.get_version(), problem);
+    match trust.policy {
+        Policy::Require => Err(CategoryError(Category::Checksum, format!("{}\n\nThe trust policy for vendor '{}' requires signed releases", message, trust.vendor)))?,
+        _ => {
+            println!("{}", theme::warning(message));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_toml() {
+        let doc = "vendor = \"ks-tech\"\npolicy = \"warn\"\nmethod = \"minisign\"\nkey = \"RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3\"\n".parse::<toml_edit::Document>().unwrap();
+        let trust = Trust::from_toml(doc.as_table()).unwrap();
+        assert_eq!(trust.get_vendor().to_string(), "ks-tech");
+        assert_eq!(trust.get_policy(), Policy::Warn);
+        assert_eq!(trust.method, Method::Minisign);
+
+        // defaults to requiring gpg signatures
+        let doc = "vendor = \"ks-tech\"\n".parse::<toml_edit::Document>().unwrap();
+        let trust = Trust::from_toml(doc.as_table()).unwrap();
+        assert_eq!(trust.get_policy(), Policy::Require);
+        assert_eq!(trust.method, Method::Gpg);
+
+        // minisign cannot verify without a public key
+        let doc = "vendor = \"ks-tech\"\nmethod = \"minisign\"\n".parse::<toml_edit::Document>().unwrap();
+        assert!(Trust::from_toml(doc.as_table()).is_err());
+        let doc = "vendor = \"ks-tech\"\npolicy = \"sometimes\"\n".parse::<toml_edit::Document>().unwrap();
+        assert!(Trust::from_toml(doc.as_table()).is_err());
+    }
+}
@@ -1,7 +1,17 @@
-use std::{path::PathBuf, collections::hash_map::DefaultHasher};
+use std::{path::{Path, PathBuf}, collections::hash_map::DefaultHasher};
+use std::collections::HashMap;
+use ignore::WalkBuilder;
 
-use crate::util::{anyerror::Fault, filesystem, url::Url};
-use super::{pkgid::PkgId, manifest::IpManifest};
+use crate::util::{anyerror::Fault, checksum, filesystem, url::Url};
+use super::{pkgid::PkgId, manifest::{self, IpManifest}, version::Version};
+
+/// Directory within the store that keeps the contents shared between installations,
+/// each file named by the sha256 digest of its contents.
+pub const OBJECTS_DIR: &str = ".objects";
+
+/// Namespace of the git references in a stored repository that keep each installed
+/// version's exact commit, named by the version.
+pub const SNAPSHOT_REFS: &str = "refs/orbit/snapshots/";
 
 #[derive(Debug, PartialEq)]
 pub struct Store<'a> {
@@ -36,19 +46,20 @@ impl<'a> Store<'a> {
         Store { root: root }
     }
 
-    /// Stashes the `repo` for the `ip` into the .orbit/store folder.
+    /// Stashes the repository at `repo` for the `ip` into the .orbit/store folder.
     /// 
-    /// It will completely replace the existing store slot or create a new one.
-    /// Assumes the `ip` is not located within the store.
-    pub fn store(&self, ip: &IpManifest) -> Result<PathBuf, Fault> {
-        let id_dir = ip.get_pkgid().into_hash().to_string();
+    /// The `repo` is the root of the repository, which may hold the ip within one of
+    /// its subdirectories. It will completely replace the existing store slot or create
+    /// a new one. Assumes the `repo` is not located within the store.
+    pub fn store(&self, ip: &PkgId, repo: &PathBuf) -> Result<PathBuf, Fault> {
+        let id_dir = ip.into_hash().to_string();
         let store_ip_dir = self.root.join(&id_dir);
         // force removal of the existing directory
         if store_ip_dir.exists() == true {
             std::fs::remove_dir_all(&store_ip_dir)?;
         }
         // copy the repository to the store location
-        filesystem::copy(&ip.get_root(), &store_ip_dir, false)?;
+        filesystem::copy(repo, &store_ip_dir, false)?;
         Ok(store_ip_dir)
     }
 
@@ -70,4 +81,225 @@ impl<'a> Store<'a> {
     pub fn is_stored(&self, ip: &PkgId) -> bool {
         std::path::Path::exists(&self.root.join(ip.into_hash().to_string()))
     }
+
+    /// Records the exact `commit` the `ip` was installed from as `version` in its stored repository.
+    /// 
+    /// The commit is kept by a reference under `SNAPSHOT_REFS`, so the version can be installed
+    /// again from the store after its tag is moved or removed, or its remote disappears. A commit
+    /// missing from the stored repository is fetched through the `version` tag of the repository
+    /// at `source`. Does nothing if the ip is not stored.
+    pub fn snapshot(&self, ip: &PkgId, version: &Version, commit: &str, source: &Path) -> Result<(), Fault> {
+        let root = match self.as_stored(ip) {
+            Some(r) => r,
+            None => return Ok(()),
+        };
+        let repo = git2::Repository::open(&root)?;
+        let oid = git2::Oid::from_str(commit)?;
+        let name = format!("{}{}", SNAPSHOT_REFS, version);
+        if repo.find_commit(oid).is_err() {
+            repo.remote_anonymous(&filesystem::to_standard(source))?
+                .fetch(&[format!("+refs/tags/{}:{}", version, name)], None, None)?;
+        }
+        repo.reference(&name, oid, true, &format!("snapshot {} v{}", ip, version))?;
+        Ok(())
+    }
+
+    /// Finds the stored repository of the `ip` that holds the exact `commit`.
+    pub fn find_snapshot(&self, ip: &PkgId, commit: &str) -> Option<PathBuf> {
+        let root = self.as_stored(ip)?;
+        let repo = git2::Repository::open(&root).ok()?;
+        let oid = git2::Oid::from_str(commit).ok()?;
+        let found = repo.find_commit(oid).is_ok();
+        match found {
+            true => Some(root),
+            false => None,
+        }
+    }
+
+    /// Accesses the directory of shared file contents.
+    pub fn get_objects_path(&self) -> PathBuf {
+        self.root.join(OBJECTS_DIR)
+    }
+
+    /// Shares the contents of every file in the installation at `slot` with the
+    /// installations that already have identical files.
+    /// 
+    /// Each file is hard linked to the object named by its digest, and new contents are
+    /// added as objects. Files that cannot be linked, such as across filesystems, keep
+    /// their own copy. The metadata and checksum files are never shared because they are
+    /// rewritten in place.
+    /// 
+    /// Returns the number of files that reuse an existing object and the bytes saved.
+    pub fn share(&self, slot: &Path) -> Result<(usize, u64), Fault> {
+        let objects = self.get_objects_path();
+        let mut reused = 0;
+        let mut saved = 0;
+        for entry in WalkBuilder::new(slot).standard_filters(false).build() {
+            let file = entry?.into_path();
+            let name = file.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if !file.is_file() || name == manifest::ORBIT_METADATA_FILE || name == manifest::ORBIT_SUM_FILE {
+                continue
+            }
+            let digest = checksum::file_sum(&file)?;
+            let object = Self::object_path(&objects, &digest.to_string());
+            // an object edited through one of its links no longer matches its name
+            if object.exists() && checksum::file_sum(&object)? != digest {
+                // windows refuses to delete read-only files
+                #[cfg(not(unix))]
+                filesystem::set_readonly(&object, false)?;
+                std::fs::remove_file(&object)?;
+            }
+            if object.exists() {
+                // swap in the link so the file is never missing if linking fails
+                let temp = file.with_file_name(format!(".{}.orbit-share", name));
+                if std::fs::hard_link(&object, &temp).is_ok() {
+                    std::fs::rename(&temp, &file)?;
+                    reused += 1;
+                    saved += std::fs::metadata(&file)?.len();
+                }
+            } else {
+                std::fs::create_dir_all(object.parent().unwrap())?;
+                let _ = std::fs::hard_link(&file, &object);
+            }
+        }
+        Ok((reused, saved))
+    }
+
+    /// Counts the installation files in `cache` that reference each object.
+    /// 
+    /// Returns the path, size in bytes, and number of references for every object.
+    pub fn objects(&self, cache: &Path) -> Result<Vec<(PathBuf, u64, u64)>, Fault> {
+        let objects = self.get_objects_path();
+        let mut result = Vec::new();
+        if !objects.exists() {
+            return Ok(result)
+        }
+        // platforms without link counts need the digest of every installed file
+        let mut digests: Option<HashMap<String, u64>> = None;
+        for entry in WalkBuilder::new(&objects).standard_filters(false).build() {
+            let object = entry?.into_path();
+            if !object.is_file() {
+                continue
+            }
+            let size = std::fs::metadata(&object)?.len();
+            let refs = match filesystem::link_count(&object) {
+                // the object's own name is one of the links
+                Some(n) => n.saturating_sub(1),
+                None => {
+                    if digests.is_none() {
+                        digests = Some(Self::count_digests(cache)?);
+                    }
+                    let name = Self::object_digest(&objects, &object);
+                    digests.as_ref().unwrap().get(&name).copied().unwrap_or(0)
+                }
+            };
+            result.push((object, size, refs));
+        }
+        result.sort();
+        Ok(result)
+    }
+
+    /// Computes the path of the object holding contents with the `digest`.
+    fn object_path(objects: &Path, digest: &str) -> PathBuf {
+        objects.join(&digest[..2]).join(&digest[2..])
+    }
+
+    /// Recovers the digest from the path of an `object`.
+    fn object_digest(objects: &Path, object: &Path) -> String {
+        filesystem::remove_base(&objects.to_path_buf(), &object.to_path_buf())
+            .iter()
+            .map(|c| c.to_string_lossy())
+            .collect()
+    }
+
+    /// Counts the files under `cache` by the digest of their contents.
+    fn count_digests(cache: &Path) -> Result<HashMap<String, u64>, Fault> {
+        let mut counts = HashMap::new();
+        if !cache.exists() {
+            return Ok(counts)
+        }
+        for entry in WalkBuilder::new(cache).standard_filters(false).build() {
+            let file = entry?.into_path();
+            if file.is_file() {
+                *counts.entry(checksum::file_sum(&file)?.to_string()).or_insert(0) += 1;
+            }
+        }
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+    use tempfile::tempdir;
+
+    #[test]
+    fn share_identical_files() {
+        let home = tempdir().unwrap();
+        let root = home.path().join("store");
+        let cache = home.path().join("cache");
+        let store = Store::new(&root);
+        for (slot, text) in [("gates-1.0.0", "entity and_gate"), ("gates-1.1.0", "entity and_gate"), ("gates-2.0.0", "entity or_gate")] {
+            std::fs::create_dir_all(cache.join(slot)).unwrap();
+            std::fs::write(cache.join(slot).join("gate.vhd"), text).unwrap();
+            std::fs::write(cache.join(slot).join(manifest::ORBIT_METADATA_FILE), "").unwrap();
+        }
+        assert_eq!(store.share(&cache.join("gates-1.0.0")).unwrap(), (0, 0));
+        assert_eq!(store.share(&cache.join("gates-1.1.0")).unwrap(), (1, 15));
+        assert_eq!(store.share(&cache.join("gates-2.0.0")).unwrap(), (0, 0));
+        assert_eq!(std::fs::read_to_string(cache.join("gates-1.1.0").join("gate.vhd")).unwrap(), "entity and_gate");
+
+        let mut refs: Vec<u64> = store.objects(&cache).unwrap().iter().map(|(_, _, r)| *r).collect();
+        refs.sort();
+        assert_eq!(refs, vec![1, 2]);
+
+        // removing the installations leaves the objects unreferenced
+        std::fs::remove_dir_all(cache.join("gates-1.1.0")).unwrap();
+        std::fs::remove_dir_all(cache.join("gates-2.0.0")).unwrap();
+        let refs: Vec<u64> = store.objects(&cache).unwrap().iter().map(|(_, _, r)| *r).collect();
+        assert_eq!(refs.iter().sum::<u64>(), 1);
+        assert!(refs.contains(&0));
+    }
+
+    /// Commits the `text` as the only file of the repository at `path` and tags it as `tag`.
+    fn commit_and_tag(path: &Path, text: &str, tag: &str) -> String {
+        let repo = git2::Repository::open(path).unwrap_or_else(|_| git2::Repository::init(path).unwrap());
+        std::fs::write(path.join("gate.vhd"), text).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("gate.vhd")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("orbit", "orbit@localhost").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        let oid = repo.commit(Some("HEAD"), &sig, &sig, tag, &tree, &parents).unwrap();
+        repo.tag_lightweight(tag, &repo.find_object(oid, None).unwrap(), false).unwrap();
+        oid.to_string()
+    }
+
+    #[test]
+    fn snapshot_outlives_tag() {
+        let home = tempdir().unwrap();
+        let root = home.path().join("store");
+        let source = home.path().join("source");
+        std::fs::create_dir_all(&source).unwrap();
+        let store = Store::new(&root);
+        let pkgid = PkgId::from_str("ks-tech.rary.gates").unwrap();
+
+        let v1 = commit_and_tag(&source, "entity and_gate", "1.0.0");
+        let stored = store.store(&pkgid, &source).unwrap();
+        store.snapshot(&pkgid, &Version::from_str("1.0.0").unwrap(), &v1, &source).unwrap();
+
+        // a commit made after the repository was stored is fetched from the source
+        let v2 = commit_and_tag(&source, "entity or_gate", "1.1.0");
+        assert_eq!(store.find_snapshot(&pkgid, &v2), None);
+        store.snapshot(&pkgid, &Version::from_str("1.1.0").unwrap(), &v2, &source).unwrap();
+        assert_eq!(store.find_snapshot(&pkgid, &v2), Some(stored.clone()));
+
+        // the snapshot keeps the commit after its tag is removed
+        let repo = git2::Repository::open(&stored).unwrap();
+        repo.tag_delete("1.0.0").unwrap();
+        assert_eq!(repo.refname_to_id(&format!("{}1.0.0", SNAPSHOT_REFS)).unwrap().to_string(), v1);
+        assert_eq!(store.find_snapshot(&pkgid, &v1), Some(stored));
+    }
 }
\ No newline at end of file
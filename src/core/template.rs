@@ -1,4 +1,4 @@
-use crate::util::{anyerror::Fault, filesystem};
+use crate::util::{anyerror::{AnyError, Fault}, filesystem};
 use std::{path::PathBuf, collections::HashSet};
 use ignore;
 use ignore::overrides::OverrideBuilder;
@@ -255,6 +255,68 @@ pub fn substitute(text: String, code: &VariableTable) -> String {
     result
 }
 
+/// Replaces each `${NAME}` in `text` with the value of the variable `NAME` from
+/// the `vars`.
+/// 
+/// A `$$` is written as a literal `$`. Errors if a variable is not defined or
+/// is missing its closing brace.
+pub fn expand(text: &str, vars: &VariableTable) -> Result<String, AnyError> {
+    expand_with(text, false, |name| vars.get(name).cloned())
+}
+
+/// Replaces each `${NAME}` in `text` with the value `lookup` finds for `NAME`.
+/// 
+/// When `bare` is set, a `$NAME` of letters, digits, and underscores is also
+/// replaced, as environment variables are written in shell scripts and file lists.
+/// A `$$` is written as a literal `$`. Errors if `lookup` does not find a variable
+/// or a variable is missing its closing brace.
+pub fn expand_with<F>(text: &str, bare: bool, lookup: F) -> Result<String, AnyError>
+where F: Fn(&str) -> Option<String> {
+    let mut result = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue
+        }
+        let name = match chars.peek() {
+            Some('$') => {
+                chars.next();
+                result.push('$');
+                continue
+            },
+            Some('{') => {
+                chars.next();
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(n) => name.push(n),
+                        None => return Err(AnyError(format!("variable '${{{}' is missing a closing '}}' in \"{}\"", name, text))),
+                    }
+                }
+                name.trim().to_string()
+            },
+            Some(n) if bare == true && (n.is_ascii_alphanumeric() || *n == '_') => {
+                let mut name = String::new();
+                while let Some(n) = chars.next_if(|n| n.is_ascii_alphanumeric() || *n == '_') {
+                    name.push(n);
+                }
+                name
+            },
+            _ => {
+                result.push(c);
+                continue
+            },
+        };
+        match lookup(&name) {
+            Some(value) => result.push_str(&value),
+            None => return Err(AnyError(format!("undefined variable '{}' in \"{}\"", name, text))),
+        }
+    }
+    Ok(result)
+}
+
 /// Builds a variable following the syntax `c0c0*c_nc_n`.
 /// 
 /// Assumes the first token was already consumed and is passed as `c0`.
@@ -323,6 +385,24 @@ mod test {
         assert_eq!(substitute(text.to_owned(), &code), "A duck, a bear, and a {{ animal }} walk into a bar...".to_owned());
     }
 
+    #[test]
+    fn expand_variables() {
+        let mut code = create_code();
+        code.add("GIT_HOST", "https://github.com");
+        assert_eq!(expand("${GIT_HOST}/ks-tech/${ orbit.name }.git", &code).unwrap(), "https://github.com/ks-tech/gates.git");
+        assert_eq!(expand("cost: $$5 or $5", &code).unwrap(), "cost: $5 or $5");
+
+        assert_eq!(expand("${ANIMAL}.vhd", &code).unwrap_err().to_string(), "undefined variable 'ANIMAL' in \"${ANIMAL}.vhd\"");
+        assert_eq!(expand("${GIT_HOST", &code).unwrap_err().to_string(), "variable '${GIT_HOST' is missing a closing '}' in \"${GIT_HOST\"");
+
+        // bare names end at the first character that cannot be part of a name
+        let env = |name: &str| match name { "ROOT" => Some(String::from("/soc")), _ => None };
+        assert_eq!(expand_with("$ROOT/rtl/${ROOT}_v2.vhd", true, env).unwrap(), "/soc/rtl//soc_v2.vhd");
+        assert_eq!(expand_with("$ROOT/rtl", false, env).unwrap(), "$ROOT/rtl");
+        assert_eq!(expand_with("$$ROOT $ $/", true, env).unwrap(), "$ROOT $ $/");
+        assert_eq!(expand_with("$UNSET/top.vhd", true, env).unwrap_err().to_string(), "undefined variable 'UNSET' in \"$UNSET/top.vhd\"");
+    }
+
     #[test]
     fn from_toml() {
         let toml = r#"
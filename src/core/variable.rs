@@ -36,6 +36,14 @@ impl VariableTable {
         Ok(self)
     }
 
+    /// Loads every variable from the process's environment under its own name.
+    pub fn load_system(mut self) -> Self {
+        for (key, value) in std::env::vars() {
+            self.0.insert(key, value);
+        }
+        self
+    }
+
     pub fn load_pkgid(mut self, pkgid: &PkgId) -> Result<Self, Fault> {
         self.add("orbit.ip.name", pkgid.get_name().as_ref());
         self.add("orbit.ip.library", pkgid.get_library().as_ref().unwrap().as_ref());
@@ -1,17 +1,19 @@
 use crate::{core::manifest::Manifest, util::{anyerror::{Fault, AnyError}, filesystem::{normalize_path, self}}};
-use std::{path::PathBuf, str::FromStr};
-use super::{pkgid::PkgPart, config::FromToml, manifest::IpManifest, version::Version, hook::Hook, variable::{VariableTable}, template};
+use crate::util::environment::{self, EnvVar, Environment};
+use std::{path::{Path, PathBuf}, str::FromStr};
+use super::{network, pkgid::{PkgId, PkgPart}, config::FromToml, manifest::IpManifest, version::Version, hook::Hook, variable::{VariableTable}, template, extgit::Transport};
 use std::io::Write;
 
 #[derive(Debug, PartialEq)]
 pub struct VendorToml {
     vendor: Vendor,
     hooks: HookTable,
+    transport: Transport,
 }
 
 impl VendorToml {
     fn new() -> Self {
-        Self { vendor: Vendor::new(), hooks: HookTable::new() }
+        Self { vendor: Vendor::new(), hooks: HookTable::new(), transport: Transport::new() }
     }
 }
 
@@ -22,6 +24,7 @@ impl FromToml for VendorToml {
         Ok(Self {
             vendor: Vendor::from_toml(table.get("vendor").unwrap().as_table().unwrap())?,
             hooks: if let Some(tbl) = table.get("hook") { HookTable::from_toml(tbl.as_table().unwrap())? } else { HookTable::new() },
+            transport: if let Some(tbl) = table.get("transport") { Transport::from_toml(tbl.as_table().unwrap())? } else { Transport::new() },
         })
     }
 }
@@ -29,12 +32,14 @@ impl FromToml for VendorToml {
 #[derive(Debug, PartialEq)]
 pub struct HookTable {
     pre_publish: Option<String>,
+    publish: Option<String>,
     post_publish: Option<String>,
+    fetch: Option<String>,
 }
 
 impl HookTable {
     pub fn new() -> Self {
-        Self { pre_publish: None, post_publish: None }
+        Self { pre_publish: None, publish: None, post_publish: None, fetch: None }
     }
 }
 
@@ -43,7 +48,9 @@ impl FromToml for HookTable {
     fn from_toml(table: &toml_edit::Table) -> Result<Self, Self::Err> where Self: Sized {
         Ok(Self {
             pre_publish: Self::get(&table, "pre-publish")?,
+            publish: Self::get(table, "publish")?,
             post_publish: Self::get(&table, "post-publish")?,
+            fetch: Self::get(table, "fetch")?,
         })
     }
 }
@@ -94,6 +101,11 @@ impl VendorManifest {
         &self.vendor.vendor.name
     }
 
+    /// References the vendor's settings for reaching remote repositories.
+    pub fn get_transport(&self) -> &Transport {
+        &self.vendor.transport
+    }
+
     /// Loads the manifest document from 
     pub fn from_path(file: &PathBuf) -> Result<Self, Fault> {
         // load the toml document
@@ -116,7 +128,10 @@ impl VendorManifest {
         let cwd = std::env::current_dir()?;
         std::env::set_current_dir(self.get_root())?;
 
-        self.pre_publish_hook(&vtable)?;
+        let env = self.hook_environment(ip.get_pkgid(), &next.to_string())
+            .add(EnvVar::new().key(environment::ORBIT_IP_PATH).value(&ip.get_root().display().to_string()));
+
+        self.run_hook(&self.vendor.hooks.pre_publish, &vtable, &env)?;
 
         // create the path to write to destination
         let pkgid = ip.get_pkgid();
@@ -138,7 +153,8 @@ impl VendorManifest {
             pub_file.write(ip.get_manifest().get_doc().to_string().as_bytes())?;
         }
         
-        self.post_publish_hook(&vtable)?;
+        self.run_hook(&self.vendor.hooks.publish, &vtable, &env)?;
+        self.run_hook(&self.vendor.hooks.post_publish, &vtable, &env)?;
 
         std::env::set_current_dir(cwd)?;
         Ok(())
@@ -154,20 +170,47 @@ impl VendorManifest {
         Ok(Some(Hook::from_str(&template::substitute(contents, vtable)).unwrap()))
     }
 
-    fn pre_publish_hook(&self, vtable: &VariableTable) -> Result<(), Fault>  {
-        let hook = self.get_hook(&self.vendor.hooks.pre_publish, vtable)?;
-        if let Some(h) = hook {
-            h.execute()?;
+    /// Runs the `hook` from the vendor's root directory, if it is set.
+    fn run_hook(&self, hook: &Option<String>, vtable: &VariableTable, env: &Environment) -> Result<(), Fault> {
+        if let Some(h) = self.get_hook(hook, vtable)? {
+            h.execute(env, &self.get_root())?;
         }
         Ok(())
     }
 
-    fn post_publish_hook(&self, vtable: &VariableTable) -> Result<(), Fault>  {
-        let hook = self.get_hook(&self.vendor.hooks.post_publish, vtable)?;
-        if let Some(h) = hook {
-            h.execute()?;
-        }
-        Ok(())
+    /// Creates the environment every hook runs with for the `ip` at `version`.
+    fn hook_environment(&self, ip: &PkgId, version: &str) -> Environment {
+        let part = |p: &Option<PkgPart>| p.as_ref().map(|p| p.to_string()).unwrap_or_default();
+        Environment::new()
+            .add(EnvVar::new().key(environment::ORBIT_VENDOR).value(&self.get_name().to_string()))
+            .add(EnvVar::new().key(environment::ORBIT_VENDOR_PATH).value(&self.get_root().display().to_string()))
+            .add(EnvVar::new().key("ORBIT_IP").value(&ip.to_string()))
+            .add(EnvVar::new().key("ORBIT_IP_NAME").value(&ip.get_name().to_string()))
+            .add(EnvVar::new().key("ORBIT_IP_LIBRARY").value(&part(ip.get_library())))
+            .add(EnvVar::new().key("ORBIT_IP_VENDOR").value(&part(ip.get_vendor())))
+            .add(EnvVar::new().key("ORBIT_IP_VERSION").value(version))
+    }
+
+    /// Checks if the vendor delivers releases through its own publish hook.
+    pub fn has_publish_hook(&self) -> bool {
+        self.vendor.hooks.publish.is_some()
+    }
+
+    /// Runs the vendor's fetch hook to place the `ip` at `version` into the directory `dest`.
+    /// 
+    /// Returns `false` without doing anything when the vendor does not have a fetch hook.
+    pub fn fetch(&self, ip: &PkgId, version: &str, dest: &Path) -> Result<bool, Fault> {
+        let hook = match self.get_hook(&self.vendor.hooks.fetch, &VariableTable::new())? {
+            Some(h) => h,
+            None => return Ok(false),
+        };
+        // the hook is expected to download the ip
+        network::get().require_online(&format!("the fetch hook of vendor '{}'", self.get_name()))?;
+        std::fs::create_dir_all(dest)?;
+        let env = self.hook_environment(ip, version)
+            .add(EnvVar::new().key(environment::ORBIT_FETCH_PATH).value(&dest.display().to_string()));
+        hook.execute(&env, &self.get_root())?;
+        Ok(true)
     }
 
     /// Pulls and pushes the underlying git repository, if it exists.
@@ -324,6 +324,89 @@ impl Display for Version {
     }
 }
 
+/// A requirement on versions as a list of comparisons, such as `>=0.5, <2`.
+/// 
+/// A version without a comparison operator is a minimum version.
+#[derive(Debug, PartialEq, Clone)]
+pub struct VersionReq(Vec<(Comparator, PartialVersion)>);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Comparator {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+}
+
+impl Display for Comparator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exact => write!(f, "="),
+            Self::Greater => write!(f, ">"),
+            Self::GreaterEq => write!(f, ">="),
+            Self::Less => write!(f, "<"),
+            Self::LessEq => write!(f, "<="),
+        }
+    }
+}
+
+impl VersionReq {
+    /// Checks if `ver` satisfies every comparison of the requirement.
+    /// 
+    /// Omitted levels of a partial version match any value, so `=0.5` accepts `0.5.2`
+    /// and `>0.5` requires at least `0.6.0`.
+    pub fn matches(&self, ver: &Version) -> bool {
+        self.0.iter().all(|(cmp, pv)| {
+            let within = is_compatible(pv, ver);
+            let lowest = Version::from(pv.clone());
+            match cmp {
+                Comparator::Exact => within,
+                Comparator::Greater => ver > &lowest && !within,
+                Comparator::GreaterEq => ver >= &lowest,
+                Comparator::Less => ver < &lowest,
+                Comparator::LessEq => ver < &lowest || within,
+            }
+        })
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut comparisons = Vec::new();
+        for part in s.split(',').map(|p| p.trim()) {
+            // check the two-character operators first
+            let (cmp, ver) = if let Some(v) = part.strip_prefix(">=") {
+                (Comparator::GreaterEq, v)
+            } else if let Some(v) = part.strip_prefix("<=") {
+                (Comparator::LessEq, v)
+            } else if let Some(v) = part.strip_prefix('>') {
+                (Comparator::Greater, v)
+            } else if let Some(v) = part.strip_prefix('<') {
+                (Comparator::Less, v)
+            } else if let Some(v) = part.strip_prefix('=') {
+                (Comparator::Exact, v)
+            } else {
+                (Comparator::GreaterEq, part)
+            };
+            match PartialVersion::from_str(ver) {
+                Ok(pv) => comparisons.push((cmp, pv)),
+                Err(e) => return Err(AnyError(format!("invalid version requirement '{}': {}", part, e))),
+            }
+        }
+        Ok(Self(comparisons))
+    }
+}
+
+impl Display for VersionReq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(|(cmp, pv)| format!("{}{}", cmp, pv)).collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum VersionError {
     EmptyVersion,
@@ -580,4 +663,28 @@ mod test {
         assert_eq!(v0.in_domain(&v1), true);
         assert_eq!(v1.in_domain(&v0), true);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn version_req() {
+        let req = VersionReq::from_str(">=0.5").unwrap();
+        assert!(req.matches(&Version::from_str("0.5.0").unwrap()));
+        assert!(req.matches(&Version::from_str("1.2.0").unwrap()));
+        assert!(!req.matches(&Version::from_str("0.4.9").unwrap()));
+        // a bare version is a minimum
+        assert_eq!(VersionReq::from_str("0.5").unwrap(), req);
+
+        let req = VersionReq::from_str(">0.5, <=1").unwrap();
+        assert_eq!(req.to_string(), ">0.5, <=1");
+        assert!(!req.matches(&Version::from_str("0.5.3").unwrap()));
+        assert!(req.matches(&Version::from_str("0.6.0").unwrap()));
+        assert!(req.matches(&Version::from_str("1.9.0").unwrap()));
+        assert!(!req.matches(&Version::from_str("2.0.0").unwrap()));
+
+        let req = VersionReq::from_str("=0.5").unwrap();
+        assert!(req.matches(&Version::from_str("0.5.2").unwrap()));
+        assert!(!req.matches(&Version::from_str("0.6.0").unwrap()));
+
+        assert!(VersionReq::from_str(">=").is_err());
+        assert!(VersionReq::from_str("~0.5").is_err());
+    }
+}
@@ -0,0 +1,298 @@
+//! Documentation read from the declarations of primary design units.
+//!
+//! A unit's doc comment is the block of comments on the lines directly above its
+//! declaration, with no blank lines between them. Comments in the block that begin
+//! with `--!` are annotations written as `--! key: value`, which set properties
+//! of the unit instead of documenting it.
+
+use crate::util::anyerror::AnyError;
+use super::refactor;
+use super::standard::VhdlStandard;
+use super::syntax::SourceFile;
+use super::token::{Comment, Delimiter, Identifier, Keyword, VHDLToken, VHDLTokenizer};
+
+/// The properties of a unit set by its annotations.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Annotations {
+    synthesis: Option<bool>,
+    testbench: Option<bool>,
+    standard: Option<VhdlStandard>,
+}
+
+impl Annotations {
+    /// Accesses the annotation that marks the unit's files for the rtl fileset, if given.
+    pub fn is_synthesizable(&self) -> Option<bool> {
+        self.synthesis
+    }
+
+    /// Accesses the annotation that marks the unit as a testbench, if given.
+    pub fn is_testbench(&self) -> Option<bool> {
+        self.testbench
+    }
+
+    /// Accesses the annotation that sets the VHDL standard of the unit's file, if given.
+    pub fn get_standard(&self) -> Option<&VhdlStandard> {
+        self.standard.as_ref()
+    }
+
+    /// Sets the property written as `key: value`.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let property = match key {
+            "synthesis" => &mut self.synthesis,
+            "testbench" => &mut self.testbench,
+            "standard" => {
+                self.standard = Some(value.parse::<VhdlStandard>().map_err(|e| e.to_string())?);
+                return Ok(())
+            },
+            _ => return Err(format!("unknown annotation '{}'; expecting 'synthesis', 'testbench', or 'standard'", key)),
+        };
+        *property = match value {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => return Err(format!("invalid value '{}' for annotation '{}'; expecting 'true' or 'false'", value, key)),
+        };
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnitDoc {
+    kind: Keyword,
+    name: Identifier,
+    comment: String,
+    declaration: String,
+    /// (line, text) of each annotation
+    annotations: Vec<(usize, String)>,
+}
+
+impl UnitDoc {
+    /// Accesses the keyword that declares the unit.
+    pub fn get_kind(&self) -> &Keyword {
+        &self.kind
+    }
+
+    pub fn get_name(&self) -> &Identifier {
+        &self.name
+    }
+
+    /// References the doc comment with its comment delimiters removed.
+    pub fn get_comment(&self) -> &str {
+        &self.comment
+    }
+
+    /// References the source code of the unit's declaration.
+    pub fn get_declaration(&self) -> &str {
+        &self.declaration
+    }
+
+    /// Reads the unit's annotations.
+    ///
+    /// Errors on the first annotation with an unknown key or an invalid value.
+    pub fn get_annotations(&self) -> Result<Annotations, AnyError> {
+        let mut result = Annotations::default();
+        for (line, text) in &self.annotations {
+            let (key, value) = match text.split_once(':') {
+                Some((k, v)) => (k.trim(), v.trim()),
+                None => return Err(AnyError(format!("line {}: annotation '{}' is missing a value; expecting 'key: value'", line, text.trim()))),
+            };
+            if let Err(e) = result.set(key, value) {
+                return Err(AnyError(format!("line {}: {}", line, e)))
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Reads the documentation of every entity, package, context, and configuration
+/// declared in the source code `text`.
+pub fn read(text: &str) -> Result<Vec<UnitDoc>, AnyError> {
+    let file = SourceFile::parse(text)?;
+    let tokens = file.get_tokens();
+    // (byte offset, line, comment) of every comment
+    let comments: Vec<(usize, usize, Comment)> = VHDLTokenizer::from_source_code(text)
+        .into_tokens_all()
+        .into_iter()
+        .filter_map(|t| {
+            let (offset, line) = (t.byte_offset(), t.locate().line());
+            match t.take() {
+                VHDLToken::Comment(c) => Some((offset, line, c)),
+                _ => None,
+            }
+        })
+        .collect();
+    let line_of = |offset: usize| text[..offset].matches('\n').count() + 1;
+
+    let mut docs = Vec::new();
+    for (i, tk) in tokens.iter().enumerate() {
+        let kind = match tk.as_type() {
+            VHDLToken::Keyword(kw) if matches!(kw, Keyword::Entity | Keyword::Package | Keyword::Context | Keyword::Configuration) => kw,
+            _ => continue,
+        };
+        // primary units are declared as `<kind> <name> is` or `<kind> <name> of`
+        let name = match tokens.get(i + 1).and_then(|t| t.as_type().as_identifier()) {
+            Some(n) => n,
+            None => continue,
+        };
+        let declares = tokens.get(i + 2)
+            .map(|t| t.as_type().check_keyword(&Keyword::Is) || t.as_type().check_keyword(&Keyword::Of))
+            .unwrap_or(false);
+        if !declares || (i > 0 && !tokens[i - 1].as_type().check_delimiter(&Delimiter::Terminator)) {
+            continue
+        }
+
+        // collect the comments between the previous token and the declaration
+        let start = match i {
+            0 => 0,
+            _ => tokens[i - 1].get_span().end,
+        };
+        let previous_line = match i {
+            0 => 0,
+            _ => line_of(start),
+        };
+        let block = comment_block(&comments, start, previous_line, tk.get_span().start, line_of(tk.get_span().start));
+        let (notes, comment): (Vec<_>, Vec<_>) = block
+            .into_iter()
+            .partition(|(_, c)| is_annotation(c));
+
+        // an instantiated package is declared in a single statement
+        let end = match tokens.get(i + 3).map(|t| t.as_type().check_keyword(&Keyword::New)).unwrap_or(false) {
+            true => (i..tokens.len()).find(|j| tokens[*j].as_type().check_delimiter(&Delimiter::Terminator)).unwrap_or(tokens.len()),
+            false => refactor::region_end(tokens, i, kind.clone()),
+        }.min(tokens.len() - 1);
+        docs.push(UnitDoc {
+            kind: kind.clone(),
+            name: name.clone(),
+            comment: describe(&comment),
+            declaration: text[tk.get_span().start..tokens[end].get_span().end].to_string(),
+            annotations: notes.iter().map(|(line, c)| (*line, c.as_str()[1..].to_string())).collect(),
+        });
+    }
+    Ok(docs)
+}
+
+/// Finds the block of comments directly above a declaration beginning at byte `offset` on
+/// `line`, where the code before the declaration ends at byte `start` on `previous_line`.
+///
+/// The `comments` are (byte offset, line, comment) in order. Returns the (line, comment) of
+/// each comment in the block in order.
+pub fn comment_block(comments: &[(usize, usize, Comment)], start: usize, previous_line: usize, offset: usize, line: usize) -> Vec<(usize, &Comment)> {
+    let mut next_line = line;
+    let mut block: Vec<(usize, &Comment)> = Vec::new();
+    for (at, line, comment) in comments.iter().rev() {
+        if *at < start || *at >= offset {
+            continue
+        }
+        // the block must be directly above and not trail a line of code
+        let last_line = line + comment.as_str().matches('\n').count();
+        if last_line + 1 != next_line || *line == previous_line {
+            break
+        }
+        block.push((*line, comment));
+        next_line = *line;
+    }
+    block.reverse();
+    block
+}
+
+/// Joins the comments of a `block` that document the unit, leaving out its annotations.
+pub fn describe(block: &[(usize, &Comment)]) -> String {
+    block.iter()
+        .filter(|(_, c)| !is_annotation(c))
+        .map(|(_, c)| uncomment(c))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Checks if the `comment` is an annotation (`--! key: value`).
+fn is_annotation(comment: &Comment) -> bool {
+    matches!(comment, Comment::Single(text) if text.starts_with('!'))
+}
+
+/// Removes the delimiters and leading decorations from the text of `comment`.
+fn uncomment(comment: &Comment) -> String {
+    match comment {
+        Comment::Single(note) => note.strip_prefix(' ').unwrap_or(note).trim_end().to_string(),
+        Comment::Delimited(note) => note.lines()
+            .map(|l| l.trim().trim_start_matches('*').trim())
+            .skip_while(|l| l.is_empty())
+            .collect::<Vec<&str>>()
+            .join("\n")
+            .trim_end()
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DESIGN: &str = "\
+-- file header
+
+library ieee;
+use ieee.std_logic_1164.all;
+
+-- Combines two signals.
+--
+--! synthesis: false
+-- The output is registered.
+entity combiner is
+    port (a, b : in std_logic; q : out std_logic);
+end entity;
+
+architecture rtl of combiner is begin end architecture; -- not documentation
+package util is
+    function parity(v : std_logic_vector) return std_logic;
+end package;
+
+/*
+ * Shared clauses.
+ */
+context bundle is
+    library ieee;
+end context;
+";
+
+    #[test]
+    fn reads_units() {
+        let docs = read(DESIGN).unwrap();
+        assert_eq!(docs.len(), 3);
+
+        assert_eq!(docs[0].get_kind(), &Keyword::Entity);
+        assert_eq!(docs[0].get_name(), &Identifier::Basic("combiner".into()));
+        assert_eq!(docs[0].get_comment(), "Combines two signals.\n\nThe output is registered.");
+        assert_eq!(docs[0].get_declaration(), "entity combiner is\n    port (a, b : in std_logic; q : out std_logic);\nend entity;");
+
+        // a trailing comment does not document the next unit
+        assert_eq!(docs[1].get_kind(), &Keyword::Package);
+        assert_eq!(docs[1].get_comment(), "");
+        assert!(docs[1].get_declaration().ends_with("return std_logic;\nend package;"));
+
+        assert_eq!(docs[2].get_kind(), &Keyword::Context);
+        assert_eq!(docs[2].get_comment(), "Shared clauses.");
+    }
+
+    #[test]
+    fn reads_annotations() {
+        let docs = read(DESIGN).unwrap();
+        let notes = docs[0].get_annotations().unwrap();
+        assert_eq!(notes.is_synthesizable(), Some(false));
+        assert_eq!(notes.is_testbench(), None);
+        assert_eq!(docs[1].get_annotations().unwrap(), Annotations::default());
+
+        let docs = read("--! testbench: true\nentity bench is port (clk : in bit); end entity;\n").unwrap();
+        let notes = docs[0].get_annotations().unwrap();
+        assert_eq!(notes.is_testbench(), Some(true));
+        assert_eq!(notes.is_synthesizable(), None);
+
+        let docs = read("--! testbench: yes\n--! top\nentity bench is end entity;\n").unwrap();
+        assert_eq!(docs[0].get_annotations().unwrap_err().to_string(), "line 1: invalid value 'yes' for annotation 'testbench'; expecting 'true' or 'false'");
+        let docs = read("--! fileset: sim\nentity bench is end entity;\n").unwrap();
+        assert_eq!(docs[0].get_annotations().unwrap_err().to_string(), "line 1: unknown annotation 'fileset'; expecting 'synthesis', 'testbench', or 'standard'");
+
+        let docs = read("--! standard: 93\nentity legacy is end entity;\n").unwrap();
+        assert_eq!(docs[0].get_annotations().unwrap().get_standard(), Some(&VhdlStandard::Vhdl1993));
+        let docs = read("--! standard: 2000\nentity legacy is end entity;\n").unwrap();
+        assert!(docs[0].get_annotations().is_err());
+    }
+}
\ No newline at end of file
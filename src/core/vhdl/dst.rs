@@ -2,21 +2,68 @@
 
 use std::collections::HashMap;
 use crate::core::lexer::{Token, Position};
-use super::token::{VHDLToken, Identifier};
+use super::token::{VHDLToken, Identifier, Delimiter};
 
-/// Takes in a list of tokens, and a hashmap of the identifiers and their respective 
-/// UIE (unique identifier extension).
+/// The extension appended to a unit's name along with the library the unit belongs to.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Rename {
+    library: Identifier,
+    ext: String,
+}
+
+impl Rename {
+    pub fn new(library: Identifier, ext: String) -> Self {
+        Self { library, ext }
+    }
+
+    /// References the library of the renamed unit.
+    pub fn get_library(&self) -> &Identifier {
+        &self.library
+    }
+
+    /// References the unique identifier extension.
+    pub fn get_ext(&self) -> &str {
+        &self.ext
+    }
+}
+
+/// The VHDL text written by a transform and the references it left unchanged.
+#[derive(Debug, PartialEq)]
+pub struct Transform {
+    text: String,
+    unresolved: Vec<(Identifier, Position)>,
+}
+
+impl Transform {
+    /// References the transformed VHDL text.
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// References the `work` references to a renamed name that were left unchanged
+    /// because the file is compiled into a different library than the renamed unit.
+    pub fn get_unresolved(&self) -> &Vec<(Identifier, Position)> {
+        &self.unresolved
+    }
+}
+
+/// Takes in a list of tokens from a file compiled into the library `work`, and a hashmap of
+/// the identifiers and their respective library and UIE (unique identifier extension).
 /// 
-/// Performs a swap on the identifiers (keys) and appends their extensions (values) to write to 
-/// new VHDL text.
-pub fn dyn_symbol_transform(tkns: &[Token<VHDLToken>], lut: &HashMap<Identifier, String>) -> String {
+/// Performs a swap on the identifiers (keys) and appends their extensions to write to 
+/// new VHDL text. A selected name (`lib.unit`) is only swapped when its prefix is the
+/// renamed unit's library, where the prefix `work` is the library of the file itself.
+pub fn dyn_symbol_transform(tkns: &[Token<VHDLToken>], lut: &HashMap<Identifier, Rename>, work: &Identifier) -> Transform {
     let mut result = String::with_capacity(tkns.len());
+    let mut unresolved = Vec::new();
     let mut tkns_iter = tkns.into_iter();
 
     let mut prev_pos = Position::new();
     let mut offset: usize = 0;
     let mut transform_diff: usize = 0;
     let mut comment_lines: usize = 0;
+    // the last two tokens other than comments to detect a selected name
+    let mut prev_tkns: (Option<&VHDLToken>, Option<&VHDLToken>) = (None, None);
     while let Some(tkn) = tkns_iter.next() {
         let pos = tkn.locate().clone();
 
@@ -39,9 +86,21 @@ pub fn dyn_symbol_transform(tkns: &[Token<VHDLToken>], lut: &HashMap<Identifier,
         // check if the identifier needs to be transformed
         let (diff, text) = match tkn.as_ref() {
             VHDLToken::Identifier(id) => {
-                match lut.get(id) {
-                    Some(ext) => { 
-                        let t = id.into_extension(ext).to_string();
+                let prefix = match prev_tkns {
+                    (Some(p), Some(d)) if d.check_delimiter(&Delimiter::Dot) => p.as_identifier(),
+                    _ => None,
+                };
+                let rename = match (lut.get(id), prefix) {
+                    (Some(r), Some(lib)) if lib == &Identifier::new_working() && work != r.get_library() => {
+                        unresolved.push((id.clone(), pos.clone()));
+                        None
+                    },
+                    (Some(r), Some(lib)) if lib != &Identifier::new_working() && lib != r.get_library() => None,
+                    (r, _) => r,
+                };
+                match rename {
+                    Some(r) => { 
+                        let t = id.into_extension(r.get_ext()).to_string();
                         // compute the extra space shifted for next token
                         transform_diff = t.len() - id.len();
                         (t.len(), t)
@@ -64,6 +123,10 @@ pub fn dyn_symbol_transform(tkns: &[Token<VHDLToken>], lut: &HashMap<Identifier,
             }
         };
         offset = diff;
+        match tkn.as_ref() {
+            VHDLToken::Comment(_) => (),
+            t => prev_tkns = (prev_tkns.1, Some(t)),
+        }
 
         // println!("text: {}, os: {}", text, offset);
 
@@ -71,7 +134,7 @@ pub fn dyn_symbol_transform(tkns: &[Token<VHDLToken>], lut: &HashMap<Identifier,
         // update position
         prev_pos = pos.clone();
     }
-    result
+    Transform { text: result, unresolved }
 }
 
 
@@ -83,9 +146,10 @@ mod test {
     #[test]
     fn simple() {
 
+        let work = Identifier::new_working();
         let mut map = HashMap::new();
-        map.insert(Identifier::Basic(String::from("adder")), "_sha12345".to_string());
-        map.insert(Identifier::Extended(String::from("adder_tb")), "_sha12345".to_string());
+        map.insert(Identifier::Basic("adder".into()), Rename::new(work.clone(), "_sha12345".to_string()));
+        map.insert(Identifier::Extended("adder_tb".into()), Rename::new(work.clone(), "_sha12345".to_string()));
 
         let code: &str = r#"
 --! module: adder (name here is untouched)
@@ -123,7 +187,8 @@ entity adder is end entity  adder;
 entity \adder_tb\ is generic (WIDTH : positive := 2); end entity \adder_tb\;
         "#;
         let tokens = VHDLTokenizer::from_source_code(&code).into_tokens_all();
-        let transform = dyn_symbol_transform(&tokens, &map);
+        let transform = dyn_symbol_transform(&tokens, &map, &work);
+        let transform = transform.get_text();
         let result: &str = r#"
 --! module: adder (name here is untouched)
 library ieee;
@@ -162,4 +227,49 @@ entity \adder_tb_sha12345\ is generic (WIDTH : positive := 2); end entity \adder
         println!("{}", transform);
         assert_eq!(result, transform);
     }
+
+    #[test]
+    fn selected_names() {
+        let rary = Identifier::Basic("rary".into());
+        let mut map = HashMap::new();
+        map.insert(Identifier::Basic("reg".into()), Rename::new(rary.clone(), "_sha12345".to_string()));
+        map.insert(Identifier::Basic("reg_pkg".into()), Rename::new(rary.clone(), "_sha12345".to_string()));
+
+        let code: &str = r#"
+library rary, util;
+use rary.reg_pkg.all;
+
+entity top is end entity;
+
+architecture rtl of top is
+    signal cfg : cfg_t := util.reg; -- a different library
+begin
+    u0 : entity rary.reg port map (d => cfg.reg);
+    u1 : entity work.reg;
+end architecture;
+"#;
+        let tokens = VHDLTokenizer::from_source_code(&code).into_tokens_all();
+        // a file from a dependent ip compiled into another library
+        let transform = dyn_symbol_transform(&tokens, &map, &Identifier::Basic("soc".into()));
+        assert_eq!(transform.get_text(), r#"
+library rary, util;
+use rary.reg_pkg_sha12345.all;
+
+entity top is end entity;
+
+architecture rtl of top is
+    signal cfg : cfg_t := util.reg; -- a different library
+begin
+    u0 : entity rary.reg_sha12345 port map (d => cfg.reg);
+    u1 : entity work.reg;
+end architecture;
+"#);
+        assert_eq!(transform.get_unresolved().len(), 1);
+        assert_eq!(transform.get_unresolved()[0].1.line(), 11);
+
+        // the same file compiled into the renamed unit's library
+        let transform = dyn_symbol_transform(&tokens, &map, &rary);
+        assert!(transform.get_text().contains("u1 : entity work.reg_sha12345;"));
+        assert!(transform.get_unresolved().is_empty());
+    }
 }
\ No newline at end of file
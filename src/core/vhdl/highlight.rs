@@ -5,6 +5,8 @@ use palette::*;
 use colored::Colorize;
 
 use crate::util::anyerror::AnyError;
+use crate::core::lexer::Span;
+use super::token::{VHDLToken, VHDLTokenizer};
 
 pub type Rgb = (u8, u8, u8);
 
@@ -67,4 +69,89 @@ impl FromStr for ColorMode {
             _ => Err(AnyError(format!("value must be 'auto', 'always', or 'never'")))
         }
     }
-}
\ No newline at end of file
+}
+/// The kinds of text distinguished when highlighting vhdl source code.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TokenCategory {
+    Keyword,
+    Identifier,
+    Literal,
+    Comment,
+    Delimiter,
+}
+
+impl std::fmt::Display for TokenCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Keyword => "keyword",
+            Self::Identifier => "identifier",
+            Self::Literal => "literal",
+            Self::Comment => "comment",
+            Self::Delimiter => "delimiter",
+        })
+    }
+}
+
+impl From<&VHDLToken> for Option<TokenCategory> {
+    fn from(token: &VHDLToken) -> Self {
+        Some(match token {
+            VHDLToken::Keyword(_) => TokenCategory::Keyword,
+            VHDLToken::Identifier(_) => TokenCategory::Identifier,
+            VHDLToken::AbstLiteral(_) | VHDLToken::CharLiteral(_) | 
+            VHDLToken::StrLiteral(_) | VHDLToken::BitStrLiteral(_) => TokenCategory::Literal,
+            VHDLToken::Comment(_) => TokenCategory::Comment,
+            VHDLToken::Delimiter(_) => TokenCategory::Delimiter,
+            VHDLToken::EOF => return None,
+        })
+    }
+}
+
+/// Lexes the source code `text` into the regions to highlight and their categories,
+/// in the order they appear.
+/// 
+/// Text that fails to lex, such as a malformed literal, is left out along with the
+/// whitespace between tokens.
+pub fn classify(text: &str) -> Vec<(Span, TokenCategory)> {
+    VHDLTokenizer::from_source_code(text)
+        .into_tokens_all()
+        .into_iter()
+        .filter_map(|t| Option::<TokenCategory>::from(t.as_ref()).map(|c| (t.span(), c)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn classify_tokens() {
+        let text = "\
+-- adder
+entity add is port(a : in bit_vector(3 downto 0) := x\"F\"); end;
+";
+        let regions: Vec<(&str, TokenCategory)> = classify(text)
+            .into_iter()
+            .map(|(span, c)| (&text[span.byte_range()], c))
+            .collect();
+        use TokenCategory::*;
+        assert_eq!(regions, vec![
+            ("-- adder", Comment),
+            ("entity", Keyword), ("add", Identifier), ("is", Keyword), ("port", Keyword),
+            ("(", Delimiter), ("a", Identifier), (":", Delimiter), ("in", Keyword),
+            ("bit_vector", Identifier), ("(", Delimiter), ("3", Literal), ("downto", Keyword),
+            ("0", Literal), (")", Delimiter), (":=", Delimiter), ("x\"F\"", Literal),
+            (")", Delimiter), (";", Delimiter), ("end", Keyword), (";", Delimiter),
+        ]);
+        // lines and columns start from 1
+        let (span, _) = &classify(text)[1];
+        assert_eq!((span.start().line(), span.start().col()), (2, 1));
+        assert_eq!((span.end().line(), span.end().col()), (2, 7));
+    }
+
+    #[test]
+    fn classify_skips_errors() {
+        let text = "a := 16#FG#; b";
+        let regions: Vec<&str> = classify(text).into_iter().map(|(span, _)| &text[span.byte_range()]).collect();
+        assert_eq!(regions, vec!["a", ":=", ";", "b"]);
+    }
+}
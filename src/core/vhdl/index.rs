@@ -0,0 +1,105 @@
+//! A lookup table of values stored by VHDL identifier.
+//!
+//! Basic identifiers are case-insensitive, so they are stored under their
+//! lowercase spelling, while extended identifiers are stored exactly as written.
+//! Several values may share a name, such as a unit declared by more than one ip.
+
+use std::collections::HashMap;
+use std::iter::FromIterator;
+use super::token::Identifier;
+
+/// The case-folded form of an identifier used to store its values.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+enum Key {
+    Basic(String),
+    Extended(String),
+}
+
+impl From<&Identifier> for Key {
+    fn from(name: &Identifier) -> Self {
+        match name {
+            Identifier::Basic(s) => Self::Basic(s.to_lowercase()),
+            Identifier::Extended(s) => Self::Extended(s.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IdentifierIndex<T> {
+    map: HashMap<Key, Vec<T>>,
+}
+
+impl<T> IdentifierIndex<T> {
+    pub fn new() -> Self {
+        Self { map: HashMap::new() }
+    }
+
+    /// Stores `value` under `name` after any values already stored there.
+    pub fn insert(&mut self, name: &Identifier, value: T) {
+        self.map.entry(Key::from(name)).or_default().push(value);
+    }
+
+    /// References the values stored under `name` in the order they were inserted.
+    pub fn get(&self, name: &Identifier) -> &[T] {
+        self.map.get(&Key::from(name)).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Takes the values stored under `name` out of the index.
+    pub fn remove(&mut self, name: &Identifier) -> Vec<T> {
+        self.map.remove(&Key::from(name)).unwrap_or_default()
+    }
+
+    /// Checks if any value is stored under `name`.
+    pub fn contains(&self, name: &Identifier) -> bool {
+        self.map.contains_key(&Key::from(name))
+    }
+
+    /// Counts the distinct names in the index.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<T> Default for IdentifierIndex<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Identifier, T)> for IdentifierIndex<T> {
+    fn from_iter<I: IntoIterator<Item = (Identifier, T)>>(iter: I) -> Self {
+        let mut index = Self::new();
+        iter.into_iter().for_each(|(name, value)| index.insert(&name, value));
+        index
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn case_folding() {
+        let iden = |s: &str| Identifier::from_str(s).unwrap();
+        let mut index: IdentifierIndex<u8> = vec![
+            (iden("and_gate"), 0),
+            (iden("AND_GATE"), 1),
+            (iden("\\And_Gate\\"), 2),
+        ].into_iter().collect();
+        // basic identifiers share a key regardless of case
+        assert_eq!(index.get(&iden("And_Gate")), &[0, 1]);
+        // extended identifiers only match when written exactly
+        assert_eq!(index.get(&iden("\\And_Gate\\")), &[2]);
+        assert_eq!(index.contains(&iden("\\and_gate\\")), false);
+        assert_eq!(index.len(), 2);
+
+        assert_eq!(index.remove(&iden("and_GATE")), vec![0, 1]);
+        assert_eq!(index.get(&iden("and_gate")).is_empty(), true);
+        assert_eq!(index.len(), 1);
+    }
+}
@@ -22,6 +22,16 @@ impl Display for ColorTone {
     }
 }
 
+impl ColorTone {
+    /// References the text without its color.
+    fn as_plain(&self) -> &str {
+        match &self {
+            Self::Color(c) => &c,
+            Self::Bland(s) => &s,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct ColorVec(Vec<ColorTone>);
 
@@ -33,15 +43,26 @@ impl Display for ColorVec {
 }
 
 impl ColorVec {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self(Vec::new())
     }
 
-    fn push_str(&mut self, s: &str) -> () {
+    /// Writes the text without any colors, regardless of whether colors are
+    /// enabled for the terminal.
+    pub fn to_plain(&self) -> String {
+        self.0.iter().map(|item| item.as_plain()).collect()
+    }
+
+    /// Checks if there is no text.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|item| item.as_plain().is_empty())
+    }
+
+    pub fn push_str(&mut self, s: &str) -> () {
         self.0.push(ColorTone::Bland(String::from(s)));
     }
 
-    fn push_color(&mut self, c: ColoredString) -> () {
+    pub fn push_color(&mut self, c: ColoredString) -> () {
         self.0.push(ColorTone::Color(c));
     }
 
@@ -49,7 +70,7 @@ impl ColorVec {
         self.0.push(ct);
     }
 
-    fn append(&mut self, mut cv: ColorVec) -> () {
+    pub fn append(&mut self, mut cv: ColorVec) -> () {
         self.0.append(&mut cv.0);
     }
 
@@ -243,6 +264,24 @@ fn tokens_to_string(tokens: &Vec<VHDLToken>) -> ColorVec {
 }
 
 impl InterfaceDeclaration {
+    pub fn get_identifier(&self) -> &Identifier {
+        &self.identifier
+    }
+
+    /// Accesses the declaration's mode, which is `in` when omitted from a port.
+    pub fn get_mode(&self) -> &Keyword {
+        self.mode.as_ref().unwrap_or(&Keyword::In)
+    }
+
+    pub fn get_type(&self) -> &[VHDLToken] {
+        &self.datatype.0
+    }
+
+    /// Checks if the declaration is given a default value.
+    pub fn has_default(&self) -> bool {
+        self.expr.is_some()
+    }
+
     fn into_interface_string(&self, offset: usize) -> ColorVec {
         let mut result = ColorVec::new();
         // identifier
@@ -327,6 +366,10 @@ impl InterfaceDeclarations {
         self.0.len()
     }
 
+    pub fn iter(&self) -> std::slice::Iter<'_, InterfaceDeclaration> {
+        self.0.iter()
+    }
+
     /// Determines the length of the longest identifier.
     pub fn longest_identifier(&self) -> usize {
         let longest = self.0
@@ -484,5 +527,17 @@ impl InterfaceDeclarations {
 
 #[cfg(test)]
 mod test {
-    // @todo
+    use super::*;
+
+    #[test]
+    fn plain_text() {
+        let mut cv = ColorVec::new();
+        assert_eq!(cv.is_empty(), true);
+        cv.push_color(Keyword::Signal.to_color());
+        cv.push_str(" ");
+        cv.push_color(color("clk", SIGNAL_DEC_IDENTIFIER));
+        // colors are left out no matter the terminal
+        assert_eq!(cv.to_plain(), "signal clk");
+        assert_eq!(cv.is_empty(), false);
+    }
 }
\ No newline at end of file
@@ -4,4 +4,11 @@ pub mod symbol;
 pub mod primaryunit;
 pub mod subunit;
 pub mod dst;
-pub mod highlight;
\ No newline at end of file
+pub mod highlight;
+pub mod standard;
+pub mod printer;
+pub mod syntax;
+pub mod refactor;
+pub mod testbench;
+pub mod doc;
+pub mod index;
\ No newline at end of file
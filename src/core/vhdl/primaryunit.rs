@@ -41,6 +41,9 @@ impl PrimaryUnit {
         let tbl = item.as_inline_table_mut().unwrap();
         tbl.insert("identifier", toml_edit::value(&self.get_iden().to_string()).into_value().unwrap());
         tbl.insert("type", toml_edit::value(&self.to_string()).into_value().unwrap());
+        if let Some(text) = self.get_unit().get_description() {
+            tbl.insert("description", toml_edit::value(text).into_value().unwrap());
+        }
         item
     }
 
@@ -50,6 +53,7 @@ impl PrimaryUnit {
             name: Identifier::from_str(tbl.get("identifier")?.as_str()?).unwrap(), 
             symbol: None,
             source: String::new(),
+            description: tbl.get("description").and_then(|d| d.as_str()).unwrap_or_default().to_string(),
         };
         Some(match tbl.get("type")?.as_str()? {
             "entity" => Self::Entity(unit),
@@ -77,6 +81,8 @@ pub struct Unit {
     symbol: Option<VHDLSymbol>,
     /// source code file
     source: String,
+    /// doc comment from above the unit's declaration
+    description: String,
 }
 
 impl Unit {
@@ -87,6 +93,14 @@ impl Unit {
     pub fn get_source_code_file(&self) -> &str {
         &self.source
     }
+
+    /// References the doc comment written above the unit's declaration, if any.
+    pub fn get_description(&self) -> Option<&str> {
+        match self.description.is_empty() {
+            true => None,
+            false => Some(&self.description),
+        }
+    }
 }
 
 impl std::hash::Hash for Unit {
@@ -110,16 +124,17 @@ pub fn collect_units(files: &Vec<String>) -> Result<HashMap<Identifier, PrimaryU
         // only read the HDL files
         if crate::core::fileset::is_vhdl(&source_file) == true {
             // parse text into VHDL symbols
-            let contents = std::fs::read_to_string(&source_file).unwrap();
-            let symbols = VHDLParser::read(&contents).into_symbols();
+            let reader = std::io::BufReader::new(std::fs::File::open(&source_file)?);
+            let symbols = VHDLParser::read_from(reader)?.into_symbols();
             // transform into primary design units
             let units: Vec<PrimaryUnit> = symbols.into_iter().filter_map(|sym| {
                 let name = sym.as_iden()?.clone();
+                let description = sym.get_comment().unwrap_or_default().to_string();
                 match sym {
-                    VHDLSymbol::Entity(_) => Some(PrimaryUnit::Entity(Unit{ name: name, symbol: Some(sym), source: source_file.clone() })),
-                    VHDLSymbol::Package(_) => Some(PrimaryUnit::Package(Unit{ name: name, symbol: Some(sym), source: source_file.clone() })),
-                    VHDLSymbol::Configuration(_) => Some(PrimaryUnit::Configuration(Unit{ name: name, symbol: Some(sym), source: source_file.clone() })),
-                    VHDLSymbol::Context(_) => Some(PrimaryUnit::Context(Unit{ name: name, symbol: Some(sym), source: source_file.clone() })),
+                    VHDLSymbol::Entity(_) => Some(PrimaryUnit::Entity(Unit{ name: name, symbol: Some(sym), source: source_file.clone(), description: description })),
+                    VHDLSymbol::Package(_) => Some(PrimaryUnit::Package(Unit{ name: name, symbol: Some(sym), source: source_file.clone(), description: description })),
+                    VHDLSymbol::Configuration(_) => Some(PrimaryUnit::Configuration(Unit{ name: name, symbol: Some(sym), source: source_file.clone(), description: description })),
+                    VHDLSymbol::Context(_) => Some(PrimaryUnit::Context(Unit{ name: name, symbol: Some(sym), source: source_file.clone(), description: description })),
                     _ => None,
                 }
             }).collect();
@@ -143,7 +158,7 @@ pub fn collect_units(files: &Vec<String>) -> Result<HashMap<Identifier, PrimaryU
 #[derive(Debug)]
 pub enum VhdlIdentifierError {
     DuplicateIdentifier(Identifier, PathBuf, Position, PathBuf, Position),
-    DuplicateAcrossDirect(Identifier, IpSpec, PathBuf, Position),
+    DuplicateAcrossDirect(Identifier, IpSpec, PathBuf, Option<Position>),
 }
 
 impl std::error::Error for VhdlIdentifierError {}
@@ -166,7 +181,7 @@ impl std::fmt::Display for VhdlIdentifierError {
                 let location = filesystem::remove_base(&current_dir, &path);
                 write!(f, "duplicate primary design units identified as '{}'\n\nlocation: {}{}\nconflicts with direct dependency {}\n\n{}", 
                 iden, 
-                filesystem::normalize_path(location).display(), pos,
+                filesystem::normalize_path(location).display(), pos.as_ref().map(|p| p.to_string()).unwrap_or_default(),
                 dep,
                 HINT_2)
             }
@@ -182,4 +197,5 @@ const HINT: &str = "hint: To resolve this error either
 const HINT_2: &str = "hint: To resolve this error either
     1) rename the unit in the current ip to a unique identifier
     2) remove the direct dependency from Orbit.toml
-    3) add the file path for the unit from the current ip to a .orbitignore file";
\ No newline at end of file
+    3) add the file path for the unit from the current ip to a .orbitignore file
+    4) choose the ip to provide the unit in the [providers] table of Orbit.toml";
\ No newline at end of file
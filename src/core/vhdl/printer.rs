@@ -0,0 +1,574 @@
+//! Prints VHDL source code in a consistent layout from its token stream.
+//!
+//! The printer keeps the original line breaks and the spacing between tokens on
+//! the same line. It re-indents every line by its nesting depth, changes the case
+//! of keywords, and aligns the `=>` of associations listed one per line in
+//! generic and port maps. Comments are copied byte-for-byte.
+
+use std::str::FromStr;
+use crate::core::lexer::Token;
+use crate::util::anyerror::AnyError;
+use super::token::{Comment, Delimiter, Keyword, VHDLToken, VHDLTokenizer};
+
+/// The case to write every keyword in.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum KeywordCase {
+    Lower,
+    Upper,
+}
+
+impl FromStr for KeywordCase {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lower" => Ok(Self::Lower),
+            "upper" => Ok(Self::Upper),
+            _ => Err(AnyError(format!("unknown keyword case '{}'; expecting 'lower' or 'upper'", s))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Printer {
+    indent: usize,
+    case: KeywordCase,
+}
+
+impl Printer {
+    pub fn new() -> Self {
+        Self { indent: DEFAULT_INDENT, case: KeywordCase::Lower }
+    }
+
+    /// Sets the number of spaces per indentation level.
+    pub fn indent(mut self, n: usize) -> Self {
+        self.indent = n;
+        self
+    }
+
+    /// Sets the case to write keywords in.
+    pub fn keyword_case(mut self, case: KeywordCase) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Formats the VHDL source code `text`.
+    ///
+    /// Errors if the text cannot be tokenized, or if the formatted text would not
+    /// read back as the same sequence of tokens.
+    pub fn format(&self, text: &str) -> Result<String, AnyError> {
+        if let Some(e) = VHDLTokenizer::from_source_code(text).into_errors().first() {
+            return Err(AnyError(format!("invalid syntax at line {} col {}: {}", e.locate().line(), e.locate().col(), e.as_err().to_string().trim_end())))
+        }
+        let tokens: Vec<Token<VHDLToken>> = VHDLTokenizer::from_source_code(text)
+            .into_tokens_all()
+            .into_iter()
+            .filter(|t| !t.as_type().is_eof())
+            .collect();
+        if tokens.is_empty() {
+            return Ok(text.to_string())
+        }
+        let result = self.print(text, &tokens);
+
+        // formatting may only change whitespace and the case of keywords
+        let before = tokens.iter().map(|t| t.as_type());
+        let after = VHDLTokenizer::from_source_code(&result).into_tokens_all();
+        if after.iter().map(|t| t.as_type()).filter(|t| !t.is_eof()).ne(before) {
+            return Err(AnyError(String::from("formatting would change the meaning of the code")))
+        }
+        Ok(result)
+    }
+
+    fn print(&self, text: &str, tokens: &[Token<VHDLToken>]) -> String {
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut state = State::new();
+        let mut lines: Vec<String> = vec![String::new()];
+        let mut alignments: Vec<Alignment> = Vec::new();
+        let mut prev_end = 0;
+        let mut line_frames = 0;
+        let mut line_aligned = false;
+
+        for (i, tk) in tokens.iter().enumerate() {
+            let start = tk.byte_offset();
+            let next = tokens.get(i + 1).map(|t| t.byte_offset()).unwrap_or(text.len());
+            let source = source_text(tk.as_type(), &text[start..next]);
+            let gap = &text[prev_end..start];
+            prev_end = start + source.len();
+
+            let breaks = gap.matches('\n').count();
+            if i == 0 || breaks > 0 {
+                (0..breaks).for_each(|_| lines.push(String::new()));
+                let depth = match tk.as_type() {
+                    VHDLToken::Comment(_) => state.depth(),
+                    _ => state.begin_line(tk.as_type(), i),
+                };
+                lines.last_mut().unwrap().push_str(&" ".repeat(depth * self.indent));
+                line_frames = state.frames.len();
+                line_aligned = false;
+            } else {
+                lines.last_mut().unwrap().push_str(gap);
+            }
+
+            // align the first arrow of an association that begins its own line
+            if tk.as_type().check_delimiter(&Delimiter::Arrow) && !line_aligned && state.frames.len() == line_frames {
+                if let Some(Frame::Paren { map: true, id, .. }) = state.frames.last() {
+                    alignments.push(Alignment { group: *id, line: lines.len() - 1, at: lines.last().unwrap().len() });
+                    line_aligned = true;
+                }
+            }
+
+            match tk.as_type() {
+                VHDLToken::Keyword(_) => lines.last_mut().unwrap().push_str(&match self.case {
+                    KeywordCase::Lower => source.to_lowercase(),
+                    KeywordCase::Upper => source.to_uppercase(),
+                }),
+                _ => lines.last_mut().unwrap().push_str(source),
+            }
+            if tk.as_type().as_comment().is_none() {
+                state.step(tokens, i);
+            }
+        }
+
+        // pad each aligned line's formal part to the widest in its association list
+        let mut groups: Vec<usize> = alignments.iter().map(|a| a.group).collect();
+        groups.sort();
+        groups.dedup();
+        for group in groups {
+            let members: Vec<&Alignment> = alignments.iter().filter(|a| a.group == group).collect();
+            let width = members.iter().map(|a| lines[a.line][..a.at].trim_end().chars().count()).max().unwrap_or(0);
+            for a in members {
+                let (formal, rest) = lines[a.line].split_at(a.at);
+                lines[a.line] = format!("{:<width$} {}", formal.trim_end(), rest.trim_start(), width = width);
+            }
+        }
+        lines.join(newline) + newline
+    }
+}
+
+/// Captures the exact text of a token that begins the `rest` of the source code.
+fn source_text<'a>(tk: &VHDLToken, rest: &'a str) -> &'a str {
+    match tk {
+        // a single-line comment ends at the newline and may end with spaces
+        VHDLToken::Comment(Comment::Single(_)) => {
+            let line = rest.split('\n').next().unwrap();
+            line.strip_suffix('\r').unwrap_or(line)
+        },
+        // every other token is followed only by whitespace until the next token
+        _ => rest.trim_end(),
+    }
+}
+
+/// A group of lines whose arrows are aligned at the same column.
+struct Alignment {
+    group: usize,
+    line: usize,
+    at: usize,
+}
+
+/// The kind of region closed by an `end`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Region {
+    Configuration,
+    For,
+    If,
+    Case,
+    Other,
+}
+
+#[derive(Debug, PartialEq)]
+enum Frame {
+    /// parentheses, where multiple opened on the same line only indent once
+    Paren { weight: usize, line: usize, map: bool, id: usize },
+    /// the remaining lines of a statement that spans multiple lines
+    Continuation,
+    /// a region closed by `end`, which is open once its header is complete
+    Block { region: Region, open: bool },
+}
+
+impl Frame {
+    fn weight(&self) -> usize {
+        match self {
+            Self::Paren { weight, .. } => *weight,
+            Self::Continuation => 1,
+            Self::Block { open: false, .. } => 0,
+            // case alternatives are indented once and their statements twice
+            Self::Block { region: Region::Case, .. } => 2,
+            Self::Block { .. } => 1,
+        }
+    }
+}
+
+/// Tracks the nesting of the code as each token is read.
+struct State {
+    frames: Vec<Frame>,
+    stmt_start: bool,
+    head: usize,
+    after_end: bool,
+    in_choice: bool,
+    prev: Option<VHDLToken>,
+    line: usize,
+    parens: usize,
+}
+
+impl State {
+    fn new() -> Self {
+        Self {
+            frames: Vec::new(),
+            stmt_start: true,
+            head: 0,
+            after_end: false,
+            in_choice: false,
+            prev: None,
+            line: 0,
+            parens: 0,
+        }
+    }
+
+    /// Computes the indentation level for a line that does not begin with code.
+    fn depth(&self) -> usize {
+        self.frames.iter().map(|f| f.weight()).sum()
+    }
+
+    /// Updates the nesting for a new line beginning with `first` at index `i` and
+    /// computes its indentation level.
+    fn begin_line(&mut self, first: &VHDLToken, i: usize) -> usize {
+        self.line = i;
+        // a header that continues onto the next line is considered complete
+        if let Some(Frame::Block { open: open @ false, .. }) = self.frames.last_mut() {
+            *open = true;
+            self.stmt_start = true;
+        }
+        let continues = match &self.prev {
+            Some(VHDLToken::Delimiter(Delimiter::ParenL)) | Some(VHDLToken::Delimiter(Delimiter::Comma)) => false,
+            Some(_) => !self.stmt_start,
+            None => false,
+        };
+        if continues && self.frames.last() != Some(&Frame::Continuation) && !first.check_delimiter(&Delimiter::ParenR) {
+            self.frames.push(Frame::Continuation);
+        }
+
+        let depth = self.depth();
+        let below = |pred: &dyn Fn(&Frame) -> bool| -> usize {
+            match self.frames.iter().rposition(pred) {
+                Some(n) => self.frames[..n].iter().map(|f| f.weight()).sum(),
+                None => depth,
+            }
+        };
+        match first {
+            VHDLToken::Delimiter(Delimiter::ParenR) => below(&|f| matches!(f, Frame::Paren { .. })),
+            VHDLToken::Keyword(Keyword::End) | VHDLToken::Keyword(Keyword::Begin) => below(&|f| matches!(f, Frame::Block { .. })),
+            VHDLToken::Keyword(Keyword::When) if self.stmt_start && self.top_region() == Some(Region::Case) => depth - 1,
+            VHDLToken::Keyword(Keyword::Elsif) | VHDLToken::Keyword(Keyword::Else) if self.stmt_start && self.top_region() == Some(Region::If) => depth - 1,
+            _ => depth,
+        }
+    }
+
+    fn top_region(&self) -> Option<Region> {
+        match self.frames.last() {
+            Some(Frame::Block { region, .. }) => Some(*region),
+            _ => None,
+        }
+    }
+
+    fn pop_continuations(&mut self) {
+        while self.frames.last() == Some(&Frame::Continuation) {
+            self.frames.pop();
+        }
+    }
+
+    /// Completes the header of the innermost region.
+    fn open_region(&mut self) {
+        self.pop_continuations();
+        if let Some(Frame::Block { open, .. }) = self.frames.last_mut() {
+            *open = true;
+        }
+        self.stmt_start = true;
+    }
+
+    /// Checks if the token at `i` begins the statement, optionally after a label.
+    fn is_head(&self, tokens: &[Token<VHDLToken>], i: usize, labeled: bool) -> bool {
+        if i == self.head {
+            return true
+        }
+        let labeled = labeled
+            && tokens[self.head].as_type().as_identifier().is_some()
+            && tokens.get(self.head + 1).map(|t| t.as_type().check_delimiter(&Delimiter::Colon)).unwrap_or(false);
+        // allow the prefix keywords of processes, assertions, and subprograms
+        let prefixed = |at: usize| at < i && tokens[at..i].iter().all(|t| {
+            t.as_type().check_keyword(&Keyword::Postponed) || t.as_type().check_keyword(&Keyword::Pure) || t.as_type().check_keyword(&Keyword::Impure)
+        });
+        (labeled && (i == self.head + 2 || prefixed(self.head + 2))) || prefixed(self.head)
+    }
+
+    /// Checks if the subprogram beginning at `i` has a body.
+    fn has_body(tokens: &[Token<VHDLToken>], i: usize) -> bool {
+        let mut parens = 0;
+        for (j, t) in tokens.iter().enumerate().skip(i + 1) {
+            match t.as_type() {
+                VHDLToken::Delimiter(Delimiter::ParenL) => parens += 1,
+                VHDLToken::Delimiter(Delimiter::ParenR) => parens -= 1,
+                VHDLToken::Delimiter(Delimiter::Terminator) if parens == 0 => return false,
+                VHDLToken::Keyword(Keyword::Is) if parens == 0 => {
+                    return !tokens.get(j + 1).map(|t| t.as_type().check_keyword(&Keyword::New)).unwrap_or(false)
+                },
+                _ => (),
+            }
+        }
+        false
+    }
+
+    /// Updates the nesting after reading the code token at index `i`.
+    fn step(&mut self, tokens: &[Token<VHDLToken>], i: usize) {
+        let tk = tokens[i].as_type();
+        let was_start = self.stmt_start;
+        if self.stmt_start {
+            self.head = i;
+            self.stmt_start = false;
+        }
+        let in_region = !matches!(self.frames.last(), Some(Frame::Paren { .. })) && !self.after_end;
+        match tk {
+            VHDLToken::Delimiter(Delimiter::ParenL) => {
+                // parentheses opened on the same line share one level of indentation
+                let weight = match self.frames.last() {
+                    Some(Frame::Paren { line, .. }) if *line == self.line => 0,
+                    _ => 1,
+                };
+                let map = self.prev.as_ref().map(|p| p.check_keyword(&Keyword::Map)).unwrap_or(false);
+                self.parens += 1;
+                self.frames.push(Frame::Paren { weight, line: self.line, map, id: self.parens });
+            },
+            VHDLToken::Delimiter(Delimiter::ParenR) => {
+                self.pop_continuations();
+                if let Some(Frame::Paren { .. }) = self.frames.last() {
+                    self.frames.pop();
+                }
+            },
+            VHDLToken::Delimiter(Delimiter::Terminator) => {
+                self.pop_continuations();
+                self.stmt_start = true;
+                self.after_end = false;
+                self.in_choice = false;
+            },
+            VHDLToken::Delimiter(Delimiter::Comma) => {
+                // a new element in a list ends a continued element
+                let below = self.frames.iter().rev().find(|f| f != &&Frame::Continuation);
+                if let Some(Frame::Paren { .. }) = below {
+                    self.pop_continuations();
+                }
+            },
+            VHDLToken::Delimiter(Delimiter::Arrow) if self.in_choice && self.top_region() == Some(Region::Case) => {
+                self.in_choice = false;
+                self.stmt_start = true;
+            },
+            VHDLToken::Keyword(Keyword::End) => {
+                self.pop_continuations();
+                while let Some(f) = self.frames.pop() {
+                    if let Frame::Block { .. } = f {
+                        break
+                    }
+                }
+                self.after_end = true;
+            },
+            VHDLToken::Keyword(Keyword::Is) => {
+                self.pop_continuations();
+                if let Some(Frame::Block { open: false, .. }) = self.frames.last() {
+                    self.open_region();
+                }
+            },
+            // an instantiated package or subprogram has no region
+            VHDLToken::Keyword(Keyword::New) if self.prev == Some(VHDLToken::Keyword(Keyword::Is)) && was_start => {
+                self.frames.pop();
+            },
+            VHDLToken::Keyword(Keyword::Then) | VHDLToken::Keyword(Keyword::Begin) => self.open_region(),
+            VHDLToken::Keyword(Keyword::Loop) if !self.after_end => {
+                self.pop_continuations();
+                self.frames.push(Frame::Block { region: Region::Other, open: true });
+                self.stmt_start = true;
+            },
+            VHDLToken::Keyword(Keyword::Generate) if !self.after_end => {
+                self.pop_continuations();
+                // if and case generate statements opened their region at their first keyword
+                let is_for = tokens[self.head..i].iter().take(3).any(|t| t.as_type().check_keyword(&Keyword::For));
+                match is_for {
+                    true => self.frames.push(Frame::Block { region: Region::Other, open: true }),
+                    false => self.open_region(),
+                }
+                self.stmt_start = true;
+            },
+            VHDLToken::Keyword(Keyword::Else) if i == self.head && !self.after_end => {
+                self.stmt_start = true;
+            },
+            VHDLToken::Keyword(Keyword::When) if i == self.head && self.top_region() == Some(Region::Case) => {
+                self.in_choice = true;
+            },
+            VHDLToken::Keyword(kw) if in_region => {
+                let region = match kw {
+                    Keyword::Entity | Keyword::Architecture | Keyword::Package | Keyword::Component | Keyword::View
+                        if i == self.head => Some(Region::Other),
+                    Keyword::Configuration if i == self.head => Some(Region::Configuration),
+                    Keyword::Context if i == self.head && tokens.get(i + 2).map(|t| t.as_type().check_keyword(&Keyword::Is)).unwrap_or(false) => Some(Region::Other),
+                    Keyword::Function | Keyword::Procedure if self.is_head(tokens, i, false) && Self::has_body(tokens, i) => Some(Region::Other),
+                    Keyword::Process | Keyword::Block if self.is_head(tokens, i, true) => Some(Region::Other),
+                    Keyword::If if self.is_head(tokens, i, true) => Some(Region::If),
+                    Keyword::Case if self.is_head(tokens, i, true) => Some(Region::Case),
+                    Keyword::For if i == self.head && matches!(self.top_region(), Some(Region::Configuration) | Some(Region::For)) => Some(Region::For),
+                    Keyword::Record | Keyword::Protected | Keyword::Units => Some(Region::Other),
+                    _ => None,
+                };
+                if let Some(region) = region {
+                    // a record, protected type, or physical type follows a completed `is`
+                    let open = matches!(kw, Keyword::Record | Keyword::Protected | Keyword::Units);
+                    self.frames.push(Frame::Block { region, open });
+                    if open {
+                        self.stmt_start = true;
+                    }
+                }
+            },
+            _ => (),
+        }
+        self.prev = Some(tk.clone());
+    }
+}
+
+const DEFAULT_INDENT: usize = 4;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn indent_and_case() {
+        let s = "\
+LIBRARY ieee;
+  use ieee.std_logic_1164.all;
+ENTITY top IS
+port(
+clk : in std_logic;   -- the clock
+q : out std_logic);
+end entity;
+
+architecture rtl of top is
+signal a : std_logic;
+  begin
+process(clk) begin
+if rising_edge(clk) then
+case a is
+when '0' =>
+q <= '1';
+when others =>
+q <= '0';
+end case;
+elsif a = '1' then
+  q <= a;
+else
+null;
+end if;
+end process;
+u0 : entity work.and_gate
+generic map(N => 1)
+port map(
+a => a,
+bb => clk,
+q => open
+);
+y <= a when clk = '1' else
+'0';
+end architecture;
+";
+        assert_eq!(Printer::new().indent(2).format(s).unwrap(), "\
+library ieee;
+use ieee.std_logic_1164.all;
+entity top is
+  port(
+    clk : in std_logic;   -- the clock
+    q : out std_logic);
+end entity;
+
+architecture rtl of top is
+  signal a : std_logic;
+begin
+  process(clk) begin
+    if rising_edge(clk) then
+      case a is
+        when '0' =>
+          q <= '1';
+        when others =>
+          q <= '0';
+      end case;
+    elsif a = '1' then
+      q <= a;
+    else
+      null;
+    end if;
+  end process;
+  u0 : entity work.and_gate
+    generic map(N => 1)
+    port map(
+      a  => a,
+      bb => clk,
+      q  => open
+    );
+  y <= a when clk = '1' else
+    '0';
+end architecture;
+");
+    }
+
+    #[test]
+    fn packages_and_subprograms() {
+        let s = "\
+package p is
+function f(a : integer) return integer;
+type rec is record
+x : bit;
+end record;
+end package;
+
+package body p is
+pure function f(a : integer) return integer is
+variable v : integer;
+begin
+for i in 0 to 3 loop
+v := v + i;
+end loop;
+return v;
+end function;
+end package body;
+
+package q is new work.g generic map(N => 2);
+/* a delimited
+   comment */
+";
+        assert_eq!(Printer::new().keyword_case(KeywordCase::Upper).format(s).unwrap(), "\
+PACKAGE p IS
+    FUNCTION f(a : integer) RETURN integer;
+    TYPE rec IS RECORD
+        x : bit;
+    END RECORD;
+END PACKAGE;
+
+PACKAGE BODY p IS
+    PURE FUNCTION f(a : integer) RETURN integer IS
+        VARIABLE v : integer;
+    BEGIN
+        FOR i IN 0 TO 3 LOOP
+            v := v + i;
+        END LOOP;
+        RETURN v;
+    END FUNCTION;
+END PACKAGE BODY;
+
+PACKAGE q IS NEW work.g GENERIC MAP(N => 2);
+/* a delimited
+   comment */
+");
+    }
+
+    #[test]
+    fn stable() {
+        let s = "entity e is end;\n";
+        let p = Printer::new();
+        assert_eq!(p.format(s).unwrap(), s);
+        assert!(p.format("x <= \"abc\n").is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,403 @@
+//! Automated refactors that rewrite references across VHDL source code.
+//!
+//! Each refactor only replaces the text of identifiers, so the layout and
+//! comments of the source code are kept as written.
+
+use super::syntax::{Rewriter, SourceFile, SyntaxToken};
+use super::token::{Delimiter, Identifier, Keyword, VHDLToken};
+
+/// Renames the entity `old` to `new` in its declaration, architectures,
+/// configurations, component declarations, and instantiations.
+pub fn rename_entity<'a>(file: &'a SourceFile, old: &Identifier, new: &Identifier) -> Rewriter<'a> {
+    let tokens = file.get_tokens();
+    let libraries = libraries(tokens);
+    // labels ending the declarations of the entity and its components, as other
+    // regions such as an architecture may share the entity's name
+    let closings: Vec<usize> = tokens.iter().enumerate()
+        .filter_map(|(i, tk)| match tk.as_type() {
+            VHDLToken::Keyword(kw @ (Keyword::Entity | Keyword::Component)) if is_declaration_of(tokens, i, old) => Some(region_end(tokens, i, kw.clone())),
+            _ => None,
+        })
+        .filter(|end| *end < tokens.len() && tokens[end - 1].is_identifier(old))
+        .map(|end| end - 1)
+        .collect();
+    let mut edits = file.rewrite();
+    for (i, tk) in tokens.iter().enumerate() {
+        if !tk.is_identifier(old) || i == 0 {
+            continue
+        }
+        let refers = match tokens[i - 1].as_type() {
+            // declarations and instantiations by keyword
+            VHDLToken::Keyword(Keyword::Entity) | VHDLToken::Keyword(Keyword::Component) => true,
+            // endings of the entity's declaration
+            VHDLToken::Keyword(Keyword::End) => closings.contains(&i),
+            // secondary units and configurations of the entity
+            VHDLToken::Keyword(Keyword::Of) => i >= 3 && (is_keyword(tokens, i - 3, Keyword::Architecture) || is_keyword(tokens, i - 3, Keyword::Configuration)),
+            // a unit selected from a library
+            VHDLToken::Delimiter(Delimiter::Dot) => {
+                i >= 2 && !is_delimiter(tokens, i.wrapping_sub(3), Delimiter::Dot)
+                    && tokens[i - 2].as_type().as_identifier().map(|l| libraries.contains(l)).unwrap_or(false)
+            },
+            // component instantiations and configuration specifications
+            VHDLToken::Delimiter(Delimiter::Colon) => {
+                is_keyword(tokens, i + 1, Keyword::Port) || is_keyword(tokens, i + 1, Keyword::Generic) || is_keyword(tokens, i + 1, Keyword::Use)
+            },
+            _ => false,
+        };
+        if refers {
+            edits.replace(i, &new.to_string());
+        }
+    }
+    edits
+}
+
+/// Renames the port `old` of the entity `entity` to `new` in the entity's
+/// declaration, architectures, component declarations, and the port maps of its
+/// instantiations.
+pub fn rename_port<'a>(file: &'a SourceFile, entity: &Identifier, old: &Identifier, new: &Identifier) -> Rewriter<'a> {
+    let tokens = file.get_tokens();
+    let mut edits = file.rewrite();
+    let mut i = 0;
+    while i < tokens.len() {
+        let region = match tokens[i].as_type() {
+            VHDLToken::Keyword(Keyword::Entity) if is_declaration_of(tokens, i, entity) => Some((i, region_end(tokens, i, Keyword::Entity))),
+            VHDLToken::Keyword(Keyword::Component) if is_declaration_of(tokens, i, entity) => Some((i, region_end(tokens, i, Keyword::Component))),
+            VHDLToken::Keyword(Keyword::Architecture) if tokens.get(i + 3).map(|t| t.is_identifier(entity)).unwrap_or(false) && is_keyword(tokens, i + 2, Keyword::Of) => {
+                Some((i, region_end(tokens, i, Keyword::Architecture)))
+            },
+            _ => None,
+        };
+        if let Some((start, end)) = region {
+            // the formals of other instantiations name the ports of other entities
+            let formals = formals(tokens, start, end);
+            (start..end)
+                .filter(|j| tokens[*j].is_identifier(old) && !is_delimiter(tokens, j.wrapping_sub(1), Delimiter::Dot) && !formals.contains(j))
+                .for_each(|j| edits.replace(j, &new.to_string()));
+            i = end;
+            continue
+        }
+        if is_instantiation_of(tokens, i, entity) {
+            let end = statement_end(tokens, i);
+            formals(tokens, i, end)
+                .into_iter()
+                .filter(|j| tokens[*j].is_identifier(old))
+                .for_each(|j| edits.replace(j, &new.to_string()));
+            i = end;
+            continue
+        }
+        i += 1;
+    }
+    edits
+}
+
+/// Renames the library `old` to `new` in library clauses and selected names.
+pub fn change_library<'a>(file: &'a SourceFile, old: &Identifier, new: &Identifier) -> Rewriter<'a> {
+    let tokens = file.get_tokens();
+    let mut edits = file.rewrite();
+    let mut in_clause = false;
+    for (i, tk) in tokens.iter().enumerate() {
+        match tk.as_type() {
+            VHDLToken::Keyword(Keyword::Library) => in_clause = true,
+            VHDLToken::Delimiter(Delimiter::Terminator) => in_clause = false,
+            _ => (),
+        }
+        let prefix = is_delimiter(tokens, i + 1, Delimiter::Dot) && !is_delimiter(tokens, i.wrapping_sub(1), Delimiter::Dot);
+        if tk.is_identifier(old) && (in_clause || prefix) {
+            edits.replace(i, &new.to_string());
+        }
+    }
+    edits
+}
+
+/// Lists the names of the ports declared by the entity `entity`.
+pub fn declared_ports(file: &SourceFile, entity: &Identifier) -> Vec<Identifier> {
+    let tokens = file.get_tokens();
+    let start = match (0..tokens.len()).find(|i| is_keyword(tokens, *i, Keyword::Entity) && is_declaration_of(tokens, *i, entity)) {
+        Some(i) => i,
+        None => return Vec::new(),
+    };
+    let end = region_end(tokens, start, Keyword::Entity);
+    let clause = match (start..end).find(|i| is_keyword(tokens, *i, Keyword::Port) && is_delimiter(tokens, i + 1, Delimiter::ParenL)) {
+        Some(i) => i + 1,
+        None => return Vec::new(),
+    };
+    let mut ports = Vec::new();
+    let mut depth = 0;
+    let mut naming = false;
+    for tk in &tokens[clause..end] {
+        match tk.as_type() {
+            VHDLToken::Delimiter(Delimiter::ParenL) => {
+                depth += 1;
+                naming = depth == 1;
+            },
+            VHDLToken::Delimiter(Delimiter::ParenR) => {
+                depth -= 1;
+                if depth == 0 {
+                    break
+                }
+            },
+            VHDLToken::Delimiter(Delimiter::Terminator) if depth == 1 => naming = true,
+            VHDLToken::Delimiter(Delimiter::Colon) if depth == 1 => naming = false,
+            VHDLToken::Identifier(id) if naming => ports.push(id.clone()),
+            _ => (),
+        }
+    }
+    ports
+}
+
+fn is_keyword(tokens: &[SyntaxToken], i: usize, kw: Keyword) -> bool {
+    tokens.get(i).map(|t| t.as_type().check_keyword(&kw)).unwrap_or(false)
+}
+
+fn is_delimiter(tokens: &[SyntaxToken], i: usize, d: Delimiter) -> bool {
+    tokens.get(i).map(|t| t.as_type().check_delimiter(&d)).unwrap_or(false)
+}
+
+/// Collects the libraries that can prefix a selected name.
+fn libraries(tokens: &[SyntaxToken]) -> Vec<Identifier> {
+    let mut libs = vec![Identifier::new_working()];
+    let mut in_clause = false;
+    for tk in tokens {
+        match tk.as_type() {
+            VHDLToken::Keyword(Keyword::Library) => in_clause = true,
+            VHDLToken::Delimiter(Delimiter::Terminator) => in_clause = false,
+            VHDLToken::Identifier(id) if in_clause => libs.push(id.clone()),
+            _ => (),
+        }
+    }
+    libs
+}
+
+/// Checks if the keyword at `i` declares `name` rather than ending or instantiating it.
+fn is_declaration_of(tokens: &[SyntaxToken], i: usize, name: &Identifier) -> bool {
+    let after_end = i > 0 && is_keyword(tokens, i - 1, Keyword::End);
+    let instance = i > 0 && is_delimiter(tokens, i - 1, Delimiter::Colon);
+    !after_end && !instance && tokens.get(i + 1).map(|t| t.is_identifier(name)).unwrap_or(false)
+}
+
+/// Checks if the token at `i` begins an instantiation of the entity `name`.
+fn is_instantiation_of(tokens: &[SyntaxToken], i: usize, name: &Identifier) -> bool {
+    if i == 0 || !is_delimiter(tokens, i - 1, Delimiter::Colon) {
+        return false
+    }
+    let unit = match tokens[i].as_type() {
+        // entity lib.name or component name
+        VHDLToken::Keyword(Keyword::Entity) => i + 3,
+        VHDLToken::Keyword(Keyword::Component) => i + 1,
+        VHDLToken::Identifier(_) => i,
+        _ => return false,
+    };
+    tokens.get(unit).map(|t| t.is_identifier(name)).unwrap_or(false)
+}
+
+/// Finds the index of the terminator ending the statement that includes `i`.
+fn statement_end(tokens: &[SyntaxToken], i: usize) -> usize {
+    (i..tokens.len()).find(|j| is_delimiter(tokens, *j, Delimiter::Terminator)).unwrap_or(tokens.len())
+}
+
+/// Finds the index of the `end` that closes the region declared by the keyword
+/// `kind` at `start`.
+///
+/// Subprogram bodies within the region may also end with `end;` or `end name;`,
+/// so they are counted as they are declared.
+pub fn region_end(tokens: &[SyntaxToken], start: usize, kind: Keyword) -> usize {
+    let mut bodies = 0;
+    let mut i = start + 1;
+    while i < tokens.len() {
+        match tokens[i].as_type() {
+            VHDLToken::Keyword(Keyword::Function) | VHDLToken::Keyword(Keyword::Procedure) if !is_keyword(tokens, i - 1, Keyword::End) && has_body(tokens, i) => {
+                bodies += 1;
+            },
+            VHDLToken::Keyword(Keyword::End) => {
+                let next = tokens.get(i + 1).map(|t| t.as_type());
+                let closes = match next {
+                    Some(VHDLToken::Delimiter(Delimiter::Terminator)) | Some(VHDLToken::Identifier(_)) => true,
+                    Some(VHDLToken::Keyword(kw)) => kw == &kind || kw == &Keyword::Function || kw == &Keyword::Procedure,
+                    _ => false,
+                };
+                let subprogram = is_keyword(tokens, i + 1, Keyword::Function) || is_keyword(tokens, i + 1, Keyword::Procedure);
+                if closes && bodies > 0 && (subprogram || kind != Keyword::Component) {
+                    bodies -= 1;
+                } else if closes && !subprogram {
+                    return statement_end(tokens, i)
+                }
+            },
+            _ => (),
+        }
+        i += 1;
+    }
+    tokens.len()
+}
+
+/// Checks if the subprogram declared at `i` has a body.
+fn has_body(tokens: &[SyntaxToken], i: usize) -> bool {
+    let mut parens = 0;
+    for (j, t) in tokens.iter().enumerate().skip(i + 1) {
+        match t.as_type() {
+            VHDLToken::Delimiter(Delimiter::ParenL) => parens += 1,
+            VHDLToken::Delimiter(Delimiter::ParenR) => parens -= 1,
+            VHDLToken::Delimiter(Delimiter::Terminator) if parens == 0 => return false,
+            VHDLToken::Keyword(Keyword::Is) if parens == 0 => return !is_keyword(tokens, j + 1, Keyword::New),
+            _ => (),
+        }
+    }
+    false
+}
+
+/// Finds the index of the parenthesis that closes the one opened at `i`.
+fn closing_paren(tokens: &[SyntaxToken], i: usize) -> usize {
+    let mut depth = 0;
+    for (j, tk) in tokens.iter().enumerate().skip(i) {
+        match tk.as_type() {
+            VHDLToken::Delimiter(Delimiter::ParenL) => depth += 1,
+            VHDLToken::Delimiter(Delimiter::ParenR) => {
+                depth -= 1;
+                if depth == 0 {
+                    return j
+                }
+            },
+            _ => (),
+        }
+    }
+    tokens.len()
+}
+
+/// Collects the indices of the formal ports in every port map between `start` and `end`.
+fn formals(tokens: &[SyntaxToken], start: usize, end: usize) -> Vec<usize> {
+    let mut result = Vec::new();
+    let mut i = start;
+    while i < end {
+        if is_keyword(tokens, i, Keyword::Port) && is_keyword(tokens, i + 1, Keyword::Map) && is_delimiter(tokens, i + 2, Delimiter::ParenL) {
+            let mut depth = 0;
+            let mut element = true;
+            for (j, tk) in tokens.iter().enumerate().take(end).skip(i + 2) {
+                match tk.as_type() {
+                    VHDLToken::Delimiter(Delimiter::ParenL) => depth += 1,
+                    VHDLToken::Delimiter(Delimiter::ParenR) => {
+                        depth -= 1;
+                        if depth == 0 {
+                            i = j;
+                            break
+                        }
+                    },
+                    VHDLToken::Delimiter(Delimiter::Comma) if depth == 1 => {
+                        element = true;
+                        continue
+                    },
+                    // the formal is the first name of an association followed by `=>` or a slice
+                    VHDLToken::Identifier(_) if depth == 1 && element => {
+                        let after = match is_delimiter(tokens, j + 1, Delimiter::ParenL) {
+                            true => closing_paren(tokens, j + 1) + 1,
+                            false => j + 1,
+                        };
+                        if is_delimiter(tokens, after, Delimiter::Arrow) {
+                            result.push(j);
+                        }
+                    },
+                    _ => (),
+                }
+                if depth == 1 && tk.as_type() != &VHDLToken::Delimiter(Delimiter::ParenL) {
+                    element = false;
+                }
+            }
+        }
+        i += 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DESIGN: &str = "\
+library ieee, rary;
+use ieee.std_logic_1164.all;
+
+entity adder is
+    port (a, b : in std_logic; sum : out std_logic);
+end entity adder;
+
+architecture rtl of adder is
+    function f(x : std_logic) return std_logic is
+    begin
+        return x;
+    end;
+begin
+    u0 : entity work.half port map(a => a, sum => sum);
+    sum <= f(a) xor b;
+end architecture;
+
+architecture sim of tb is
+    component adder is
+        port (a, b : in std_logic; sum : out std_logic);
+    end component;
+    signal sum : std_logic;
+begin
+    u1 : entity work.adder port map(a => '0', b => '1', sum => sum);
+    u2 : adder port map(sum(0) => sum, a => rary.pkg.x, b => open);
+end architecture;
+";
+
+    fn id(s: &str) -> Identifier {
+        Identifier::Basic(s.into())
+    }
+
+    #[test]
+    fn rename_entity_references() {
+        let file = SourceFile::parse(DESIGN).unwrap();
+        let result = rename_entity(&file, &id("adder"), &id("full_adder")).apply();
+        assert_eq!(result.matches("full_adder").count(), 6);
+        assert!(result.contains("entity full_adder is"));
+        assert!(result.contains("end entity full_adder;"));
+        assert!(result.contains("architecture rtl of full_adder is"));
+        assert!(result.contains("component full_adder is"));
+        assert!(result.contains("entity work.full_adder port map"));
+        assert!(result.contains("u2 : full_adder port map"));
+    }
+
+    #[test]
+    fn rename_entity_keeps_other_labels() {
+        let text = "\
+entity and_gate is
+end and_gate;
+
+architecture and_gate of and_gate is
+begin
+end and_gate;
+";
+        let file = SourceFile::parse(text).unwrap();
+        let result = rename_entity(&file, &id("and_gate"), &id("or_gate")).apply();
+        // the architecture keeps its name and its closing label
+        assert_eq!(result, "\
+entity or_gate is
+end or_gate;
+
+architecture and_gate of or_gate is
+begin
+end and_gate;
+");
+    }
+
+    #[test]
+    fn rename_port_references() {
+        let file = SourceFile::parse(DESIGN).unwrap();
+        assert_eq!(declared_ports(&file, &id("adder")), vec![id("a"), id("b"), id("sum")]);
+        let result = rename_port(&file, &id("adder"), &id("sum"), &id("s")).apply();
+        assert_eq!(result, DESIGN
+            .replace("b : in std_logic; sum : out", "b : in std_logic; s : out")
+            .replace("sum => sum);\n    sum <= f(a)", "sum => s);\n    s <= f(a)")
+            .replace("b => '1', sum => sum", "b => '1', s => sum")
+            .replace("port map(sum(0) => sum", "port map(s(0) => sum")
+        );
+    }
+
+    #[test]
+    fn change_library_references() {
+        let file = SourceFile::parse(DESIGN).unwrap();
+        let result = change_library(&file, &id("rary"), &id("gates")).apply();
+        assert!(result.starts_with("library ieee, gates;"));
+        assert!(result.contains("a => gates.pkg.x"));
+        assert_eq!(result.matches("gates").count(), 2);
+    }
+}
\ No newline at end of file
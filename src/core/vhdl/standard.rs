@@ -0,0 +1,175 @@
+//! VHDL language standards selectable by an ip.
+
+use std::str::FromStr;
+use crate::core::lexer::{Position, Span, Token};
+use crate::util::anyerror::AnyError;
+use crate::util::intern::intern;
+use super::token::{Delimiter, Identifier, Keyword, VHDLToken};
+
+/// A revision of the VHDL language reference manual (LRM).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum VhdlStandard {
+    Vhdl1987,
+    Vhdl1993,
+    Vhdl2002,
+    Vhdl2008,
+    Vhdl2019,
+}
+
+impl FromStr for VhdlStandard {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1987" | "87" => Ok(Self::Vhdl1987),
+            "1993" | "93" => Ok(Self::Vhdl1993),
+            "2002" | "02" => Ok(Self::Vhdl2002),
+            "2008" | "08" => Ok(Self::Vhdl2008),
+            "2019" | "19" => Ok(Self::Vhdl2019),
+            _ => Err(AnyError(format!("unknown vhdl standard '{}'; expecting '1987', '1993', '2002', '2008', or '2019'", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for VhdlStandard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Vhdl1987 => write!(f, "1987"),
+            Self::Vhdl1993 => write!(f, "1993"),
+            Self::Vhdl2002 => write!(f, "2002"),
+            Self::Vhdl2008 => write!(f, "2008"),
+            Self::Vhdl2019 => write!(f, "2019"),
+        }
+    }
+}
+
+impl VhdlStandard {
+    /// Returns the earliest selectable standard that reserves the keyword `kw`.
+    ///
+    /// Keywords reserved in VHDL-2000 belong to VHDL-2002.
+    pub fn of_keyword(kw: &Keyword) -> Self {
+        match kw {
+            Keyword::Group | Keyword::Impure | Keyword::Inertial | Keyword::Literal |
+            Keyword::Postponed | Keyword::Pure | Keyword::Reject | Keyword::Rol |
+            Keyword::Ror | Keyword::Shared | Keyword::Sla | Keyword::Sll |
+            Keyword::Sra | Keyword::Srl | Keyword::Unaffected | Keyword::Xnor => Self::Vhdl1993,
+            Keyword::Protected => Self::Vhdl2002,
+            Keyword::Assume | Keyword::Context | Keyword::Cover |
+            Keyword::Default | Keyword::Fairness | Keyword::Force | Keyword::Parameter |
+            Keyword::Property | Keyword::Release | Keyword::Restrict | Keyword::Sequence |
+            Keyword::Strong | Keyword::Vmode | Keyword::Vprop | Keyword::Vunit => Self::Vhdl2008,
+            Keyword::Private | Keyword::View | Keyword::Vpkg => Self::Vhdl2019,
+            _ => Self::Vhdl1987,
+        }
+    }
+
+    /// Returns the earliest selectable standard that defines the delimiter `d`.
+    pub fn of_delimiter(d: &Delimiter) -> Self {
+        match d {
+            Delimiter::Question | Delimiter::AtSymbol | Delimiter::CondConv |
+            Delimiter::MatchEQ | Delimiter::MatchNE | Delimiter::MatchLT |
+            Delimiter::MatchLTE | Delimiter::MatchGT | Delimiter::MatchGTE |
+            Delimiter::DoubleLT | Delimiter::DoubleGT => Self::Vhdl2008,
+            Delimiter::BackTick => Self::Vhdl2019,
+            _ => Self::Vhdl1987,
+        }
+    }
+
+    /// Restricts the `tokens` to the features available in `self`.
+    ///
+    /// Keywords reserved in a later standard are valid identifiers in this standard, so
+    /// they are transformed into identifiers. Delimiters defined in a later standard
+    /// cannot be recovered and are returned as violations.
+    pub fn restrict(&self, tokens: Vec<Token<VHDLToken>>) -> (Vec<Token<VHDLToken>>, Vec<StandardViolation>) {
+        let mut violations = Vec::new();
+        let tokens = tokens.into_iter().map(|t| {
+            match t.as_type() {
+                VHDLToken::Keyword(kw) if &Self::of_keyword(kw) > self => {
+                    let text = t.as_type().to_string();
+                    let end = t.locate_end().clone();
+                    Token::new(VHDLToken::Identifier(Identifier::Basic(intern(&text))), t.into_position()).with_end(end)
+                },
+                VHDLToken::Delimiter(d) if &Self::of_delimiter(d) > self => {
+                    violations.push(StandardViolation {
+                        token: t.as_type().to_string(),
+                        since: Self::of_delimiter(d),
+                        span: t.span(),
+                    });
+                    t
+                },
+                _ => t,
+            }
+        }).collect();
+        (tokens, violations)
+    }
+}
+
+/// A token used in a file that requires a later standard than the one declared.
+#[derive(Debug, PartialEq)]
+pub struct StandardViolation {
+    token: String,
+    since: VhdlStandard,
+    span: Span,
+}
+
+impl StandardViolation {
+    pub fn get_position(&self) -> &Position {
+        self.span.start()
+    }
+
+    /// References the region of the file covered by the token.
+    pub fn get_span(&self) -> &Span {
+        &self.span
+    }
+}
+
+impl std::fmt::Display for StandardViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' requires VHDL-{}", self.token, self.since)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::vhdl::token::VHDLTokenizer;
+
+    #[test]
+    fn restrict_1993() {
+        let s = "context <= a ?= b;";
+        let tokens = VHDLTokenizer::from_source_code(s).into_tokens();
+        let (tokens, violations) = VhdlStandard::Vhdl1993.restrict(tokens);
+        // keyword from a later standard is read as an identifier
+        assert_eq!(tokens[0].as_type(), &VHDLToken::Identifier(Identifier::Basic("context".into())));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].to_string(), "'?=' requires VHDL-2008");
+        assert_eq!(violations[0].get_position(), &Position::place(1, 14));
+
+        let tokens = VHDLTokenizer::from_source_code(s).into_tokens();
+        let (_, violations) = VhdlStandard::Vhdl2008.restrict(tokens);
+        assert_eq!(violations, vec![]);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(VhdlStandard::from_str("2008").unwrap(), VhdlStandard::Vhdl2008);
+        assert_eq!(VhdlStandard::from_str("93").unwrap(), VhdlStandard::Vhdl1993);
+        assert_eq!(VhdlStandard::from_str("02").unwrap(), VhdlStandard::Vhdl2002);
+        assert_eq!(VhdlStandard::from_str("1987").unwrap(), VhdlStandard::Vhdl1987);
+        assert!(VhdlStandard::from_str("2000").is_err());
+        assert!(VhdlStandard::Vhdl1993 < VhdlStandard::Vhdl2019);
+    }
+
+    #[test]
+    fn restrict_1987() {
+        let s = "shared variable pure : bit;";
+        let tokens = VHDLTokenizer::from_source_code(s).into_tokens();
+        let (tokens, _) = VhdlStandard::Vhdl1987.restrict(tokens);
+        assert_eq!(tokens[0].as_type(), &VHDLToken::Identifier(Identifier::Basic("shared".into())));
+        assert_eq!(tokens[2].as_type(), &VHDLToken::Identifier(Identifier::Basic("pure".into())));
+
+        let tokens = VHDLTokenizer::from_source_code(s).into_tokens();
+        let (tokens, _) = VhdlStandard::Vhdl1993.restrict(tokens);
+        assert_eq!(tokens[0].as_type(), &VHDLToken::Keyword(Keyword::Shared));
+    }
+}
\ No newline at end of file
@@ -35,4 +35,12 @@ impl SubUnit {
             Self::Configuration(u) => u.get_refs(),
         }
     }
+
+    /// References the instantiations of an architecture. Configurations have none.
+    pub fn get_instances(&self) -> &[symbol::Instance] {
+        match self {
+            Self::Architecture(u) => u.get_instances(),
+            Self::Configuration(_) => &[],
+        }
+    }
 }
\ No newline at end of file
@@ -44,6 +44,56 @@ impl VHDLSymbol {
         }
     }
 
+    /// Returns the region in the file from the unit's first keyword through its closing statement.
+    pub fn get_span(&self) -> Span {
+        match self {
+            Self::Entity(unit) => unit.get_span(),
+            Self::Architecture(unit) => unit.get_span(),
+            Self::Package(unit) => unit.get_span(),
+            Self::PackageBody(unit) => unit.get_span(),
+            Self::Configuration(unit) => unit.get_span(),
+            Self::Context(unit) => unit.get_span(),
+        }
+    }
+
+    /// References the doc comment written directly above a primary design unit, if any.
+    pub fn get_comment(&self) -> Option<&str> {
+        let comment = match self {
+            Self::Entity(unit) => &unit.comment,
+            Self::Package(unit) => &unit.comment,
+            Self::Configuration(unit) => &unit.comment,
+            Self::Context(unit) => &unit.comment,
+            Self::Architecture(_) | Self::PackageBody(_) => return None,
+        };
+        match comment.is_empty() {
+            true => None,
+            false => Some(comment),
+        }
+    }
+
+    /// Sets the doc comment of a primary design unit.
+    fn set_comment(&mut self, comment: String) {
+        match self {
+            Self::Entity(unit) => unit.comment = comment,
+            Self::Package(unit) => unit.comment = comment,
+            Self::Configuration(unit) => unit.comment = comment,
+            Self::Context(unit) => unit.comment = comment,
+            Self::Architecture(_) | Self::PackageBody(_) => (),
+        }
+    }
+
+    /// Sets the position just past the unit's closing statement.
+    fn set_end(&mut self, end: Position) {
+        match self {
+            Self::Entity(unit) => unit.end = end,
+            Self::Architecture(unit) => unit.end = end,
+            Self::Package(unit) => unit.end = end,
+            Self::PackageBody(unit) => unit.end = end,
+            Self::Configuration(unit) => unit.end = end,
+            Self::Context(unit) => unit.end = end,
+        }
+    }
+
     /// Casts `self` to package.
     pub fn as_package(&self) -> Option<&Package> {
         match self {
@@ -137,6 +187,9 @@ pub struct Package {
     body: Option<PackageBody>,
     refs: IdentifierList,
     pos: Position,
+    end: Position,
+    /// doc comment from the lines directly above the declaration
+    comment: String,
 }
 
 impl Package {
@@ -148,6 +201,11 @@ impl Package {
     pub fn get_position(&self) -> &Position {
         &self.pos
     }
+
+    /// Returns the region in the file from the unit's first keyword through its closing statement.
+    pub fn get_span(&self) -> Span {
+        Span::new(self.pos.clone(), self.end.clone())
+    }
 }
 
 impl Display for Package {
@@ -161,6 +219,7 @@ pub struct PackageBody {
     owner: Identifier,
     refs: IdentifierList,
     pos: Position,
+    end: Position,
 }
 
 impl PackageBody {
@@ -177,6 +236,11 @@ impl PackageBody {
         &self.pos
     }
 
+    /// Returns the region in the file from the unit's first keyword through its closing statement.
+    pub fn get_span(&self) -> Span {
+        Span::new(self.pos.clone(), self.end.clone())
+    }
+
     pub fn take_refs(self) -> IdentifierList {
         self.refs
     }
@@ -196,6 +260,9 @@ pub struct Entity {
     architectures: Vec<Architecture>,
     refs: IdentifierList,
     pos: Position,
+    end: Position,
+    /// doc comment from the lines directly above the declaration
+    comment: String,
 }
 
 use crate::core::vhdl::interface::*;
@@ -210,6 +277,8 @@ impl Entity {
             architectures: Vec::new(),
             refs: LinkedList::new(),
             pos: Position::new(),
+            end: Position::new(),
+            comment: String::new(),
         }
     }
 
@@ -223,6 +292,8 @@ impl Entity {
             architectures: Vec::new(), 
             refs: LinkedList::new(), 
             pos: Position::new(),
+            end: Position::new(),
+            comment: String::new(),
         }
     }
 
@@ -230,6 +301,11 @@ impl Entity {
         &self.pos
     }
 
+    /// Returns the region in the file from the unit's first keyword through its closing statement.
+    pub fn get_span(&self) -> Span {
+        Span::new(self.pos.clone(), self.end.clone())
+    }
+
     /// Checks if the current `Entity` is a testbench.
     /// 
     /// This is determined by checking if the ports list is empty.
@@ -242,54 +318,83 @@ impl Entity {
         &self.name
     }
 
+    pub fn get_ports(&self) -> &Ports {
+        &self.ports
+    }
+
+    pub fn get_generics(&self) -> &Generics {
+        &self.generics
+    }
+
     /// References the references for the entity.
     pub fn get_refs(&self) -> &IdentifierList {
         &self.refs
     }
 
     // Generates VHDL component code from the entity.
-    pub fn into_component(&self) -> String {
-        let mut result = format!("{} ", Keyword::Component.to_color());
-        result.push_str(&format!("{}", color(&self.get_name().to_string(), ENTITY_NAME)));
+    pub fn into_component(&self) -> ColorVec {
+        let mut result = ColorVec::new();
+        result.push_color(Keyword::Component.to_color());
+        result.push_str(" ");
+        result.push_color(color(&self.get_name().to_string(), ENTITY_NAME));
 
         if self.generics.0.len() > 0 {
-            result.push_str(&format!("\n{} ", Keyword::Generic.to_color()));
-            result.push_str(&self.generics.0.to_interface_part_string().to_string());
+            result.push_str("\n");
+            result.push_color(Keyword::Generic.to_color());
+            result.push_str(" ");
+            result.append(self.generics.0.to_interface_part_string());
         }
         if self.ports.0.len() > 0 {
-            result.push_str(&format!("\n{} ", Keyword::Port.to_color()));
-            result.push_str(&self.ports.0.to_interface_part_string().to_string());
+            result.push_str("\n");
+            result.push_color(Keyword::Port.to_color());
+            result.push_str(" ");
+            result.append(self.ports.0.to_interface_part_string());
         }
-        result.push_str(&format!("\n{} {}{}\n", Keyword::End.to_color(), Keyword::Component.to_color(), Delimiter::Terminator.to_color()));
+        result.push_str("\n");
+        result.push_color(Keyword::End.to_color());
+        result.push_str(" ");
+        result.push_color(Keyword::Component.to_color());
+        result.push_color(Delimiter::Terminator.to_color());
+        result.push_str("\n");
         result
     }
 
     /// Generates VHDL signal declaration code from the entity data.
-    pub fn into_signals(&self) -> String {
-        self.ports.0.to_declaration_part_string(Keyword::Signal).to_string()
+    pub fn into_signals(&self) -> ColorVec {
+        self.ports.0.to_declaration_part_string(Keyword::Signal)
     }
 
     /// Generates VHDL constant declaration code from the entity data.
-    pub fn into_constants(&self) -> String {
-        self.generics.0.to_declaration_part_string(Keyword::Constant).to_string()
+    pub fn into_constants(&self) -> ColorVec {
+        self.generics.0.to_declaration_part_string(Keyword::Constant)
     }
 
     /// Generates VHDL instantiation code from the entity data.
-    pub fn into_instance(&self, inst: &Identifier, library: Option<Identifier>) -> String {
-        let prefix = match library {
-            Some(lib) => format!("{} {}{}", Keyword::Entity.to_color(), color(&lib.to_string(), ENTITY_NAME), Delimiter::Dot.to_color()),
-            None => String::new()
-        };
-        let mut result = String::from(format!("{} {} {}{}\n", inst.to_color(), Delimiter::Colon.to_color(), prefix, color(&self.get_name().to_string(), ENTITY_NAME)));
+    pub fn into_instance(&self, inst: &Identifier, library: Option<Identifier>) -> ColorVec {
+        let mut result = ColorVec::new();
+        result.push_color(inst.to_color());
+        result.push_str(" ");
+        result.push_color(Delimiter::Colon.to_color());
+        result.push_str(" ");
+        if let Some(lib) = library {
+            result.push_color(Keyword::Entity.to_color());
+            result.push_str(" ");
+            result.push_color(color(&lib.to_string(), ENTITY_NAME));
+            result.push_color(Delimiter::Dot.to_color());
+        }
+        result.push_color(color(&self.get_name().to_string(), ENTITY_NAME));
+        result.push_str("\n");
         if self.generics.0.len() > 0 {
-            result.push_str(&(format!("{} ", Keyword::Generic.to_color())));
-            result.push_str(&self.generics.0.to_instantiation_part().to_string())
+            result.push_color(Keyword::Generic.to_color());
+            result.push_str(" ");
+            result.append(self.generics.0.to_instantiation_part());
         }
         if self.ports.0.len() > 0 {
             // add extra spacing
-            if self.generics.0.len() > 0 { result.push(' '); }
-            result.push_str(&format!("{} ", Keyword::Port.to_color()));
-            result.push_str(&self.ports.0.to_instantiation_part().to_string())
+            if self.generics.0.len() > 0 { result.push_str(" "); }
+            result.push_color(Keyword::Port.to_color());
+            result.push_str(" ");
+            result.append(self.ports.0.to_instantiation_part());
         }
         result.push_str(&Delimiter::Terminator.to_string());
         result
@@ -334,6 +439,8 @@ impl Entity {
             generics: Generics(InterfaceDeclarations::from_double_listed_tokens(generics)),
             ports: Ports(InterfaceDeclarations::from_double_listed_tokens(ports)),
             refs: entity_refs,
+            end: pos.clone(),
+            comment: String::new(),
             pos: pos,
         }
     }
@@ -345,7 +452,9 @@ pub struct Architecture {
     owner: Identifier,
     dependencies: IdentifierList,
     refs: IdentifierList,
+    instances: Vec<Instance>,
     pos: Position,
+    end: Position,
 }
 
 impl Architecture {
@@ -357,6 +466,11 @@ impl Architecture {
         &self.pos
     }
 
+    /// Returns the region in the file from the unit's first keyword through its closing statement.
+    pub fn get_span(&self) -> Span {
+        Span::new(self.pos.clone(), self.end.clone())
+    }
+
     pub fn entity(&self) -> &Identifier {
         &self.owner
     }
@@ -369,6 +483,42 @@ impl Architecture {
     pub fn get_refs(&self) -> &IdentifierList {
         &self.refs
     }
+
+    /// References the instantiations in the architecture's statement section in order.
+    pub fn get_instances(&self) -> &Vec<Instance> {
+        &self.instances
+    }
+}
+
+/// An instantiation of a design unit within an architecture.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Instance {
+    label: Identifier,
+    unit: CompoundIdentifier,
+    /// associations of the generic map as written in the source code
+    generics: Vec<String>,
+    pos: Position,
+}
+
+impl Instance {
+    /// References the instance's label.
+    pub fn get_label(&self) -> &Identifier {
+        &self.label
+    }
+
+    /// References the instantiated unit, with its library when it was named.
+    pub fn get_unit(&self) -> &CompoundIdentifier {
+        &self.unit
+    }
+
+    /// References the associations of the generic map, such as `WIDTH => 8`.
+    pub fn get_generics(&self) -> &Vec<String> {
+        &self.generics
+    }
+
+    pub fn get_position(&self) -> &Position {
+        &self.pos
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -376,6 +526,9 @@ pub struct Context {
     name: Identifier,
     refs: IdentifierList,
     pos: Position,
+    end: Position,
+    /// doc comment from the lines directly above the declaration
+    comment: String,
 }
 
 impl Context {
@@ -387,6 +540,11 @@ impl Context {
     pub fn get_position(&self) -> &Position {
         &self.pos
     }
+
+    /// Returns the region in the file from the unit's first keyword through its closing statement.
+    pub fn get_span(&self) -> Span {
+        Span::new(self.pos.clone(), self.end.clone())
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -414,6 +572,9 @@ pub struct Configuration {
     dependencies: IdentifierList,
     refs: IdentifierList,
     pos: Position,
+    end: Position,
+    /// doc comment from the lines directly above the declaration
+    comment: String,
 }
 
 impl Configuration {
@@ -425,6 +586,11 @@ impl Configuration {
         &self.pos
     }
 
+    /// Returns the region in the file from the unit's first keyword through its closing statement.
+    pub fn get_span(&self) -> Span {
+        Span::new(self.pos.clone(), self.end.clone())
+    }
+
     pub fn entity(&self) -> &Identifier {
         &self.owner
     }
@@ -554,6 +720,31 @@ impl Parse<VHDLToken> for VHDLParser {
         where <Self as Parse<VHDLToken>>::Err: Display {
             
         let mut symbols = Vec::new();
+        // set aside the comments as (byte offset, line, comment) to document the units below them
+        let mut comments = Vec::new();
+        let tokens: Vec<Token<VHDLToken>> = tokens.into_iter().filter_map(|t| {
+            let (offset, line) = (t.byte_offset(), t.locate().line());
+            match t.as_ref() {
+                VHDLToken::Comment(_) => {
+                    if let VHDLToken::Comment(c) = t.take() {
+                        comments.push((offset, line, c));
+                    }
+                    None
+                },
+                _ => Some(t),
+            }
+        }).collect();
+        // remember where each token ends to find where each unit ends
+        let ends: Vec<Position> = tokens.iter().map(|t| t.locate_end().clone()).collect();
+        let last_end = |remaining: usize| ends[ends.len() - remaining - 1].clone();
+        // reads the doc comment above the token `t` with `remaining` tokens after it
+        let comment_above = |t: &Token<VHDLToken>, remaining: usize| {
+            let (start, previous_line) = match ends.len() - remaining - 1 {
+                0 => (0, 0),
+                i => (ends[i - 1].offset(), ends[i - 1].line()),
+            };
+            doc::describe(&doc::comment_block(&comments, start, previous_line, t.byte_offset(), t.locate().line()))
+        };
         let mut tokens = tokens.into_iter().peekable();
 
         let mut global_refs = IdentifierList::new();
@@ -561,33 +752,46 @@ impl Parse<VHDLToken> for VHDLParser {
         while let Some(t) = tokens.next() {
             // create entity symbol
             if t.as_ref().check_keyword(&Keyword::Entity) {
+                let comment = comment_above(&t, tokens.len());
                 // get the position
                 let mut ent = VHDLSymbol::parse_entity(&mut tokens, t.into_position());
+                ent.set_end(last_end(tokens.len()));
+                ent.set_comment(comment);
                 ent.add_refs(&mut global_refs);
                 // println!("info: detected {}", ent);
                 symbols.push(Ok(Symbol::new(ent)));
             // create architecture symbol
             } else if t.as_ref().check_keyword(&Keyword::Architecture) {
                 let mut arch = VHDLSymbol::parse_architecture(&mut tokens, t.into_position());
+                arch.set_end(last_end(tokens.len()));
                 arch.add_refs(&mut global_refs);
                 // println!("info: detected {}", arch);
                 symbols.push(Ok(Symbol::new(arch)));
             // create configuration symbol
             } else if t.as_ref().check_keyword(&Keyword::Configuration) {
-                let config = VHDLSymbol::parse_configuration(&mut tokens, t.into_position());
+                let comment = comment_above(&t, tokens.len());
+                let mut config = VHDLSymbol::parse_configuration(&mut tokens, t.into_position());
+                config.set_end(last_end(tokens.len()));
+                config.set_comment(comment);
                 // println!("info: detected {}", config);
                 symbols.push(Ok(Symbol::new(config)));
             // create package symbol
             } else if t.as_ref().check_keyword(&Keyword::Package) {
+                let comment = comment_above(&t, tokens.len());
                 let mut pack = VHDLSymbol::route_package_parse(&mut tokens, t.into_position());
+                pack.set_end(last_end(tokens.len()));
+                pack.set_comment(comment);
                 pack.add_refs(&mut global_refs);
                 // println!("info: detected {}", pack);
                 symbols.push(Ok(Symbol::new(pack)));
             // create a context symbol or context reference
             } else if t.as_ref().check_keyword(&Keyword::Context) {
+                let comment = comment_above(&t, tokens.len());
                 match VHDLSymbol::parse_context(&mut tokens, t.into_position()) {
                     ContextUsage::ContextDeclaration(dec) => {
                         let mut context = VHDLSymbol::Context(dec);
+                        context.set_end(last_end(tokens.len()));
+                        context.set_comment(comment);
                         // println!("info: detected {}", context);
                         context.add_refs(&mut global_refs);
                         symbols.push(Ok(Symbol::new(context)));
@@ -610,12 +814,34 @@ impl Parse<VHDLToken> for VHDLParser {
 
 impl VHDLParser {
     pub fn read(s: &str) -> Self {
-        let symbols = VHDLParser::parse(VHDLTokenizer::from_source_code(&s).into_tokens());
+        let symbols = VHDLParser::parse(VHDLTokenizer::from_source_code(&s).into_tokens_all());
         Self {
             symbols: symbols.into_iter().filter_map(|f| { if f.is_ok() { Some(f.unwrap()) } else { None } }).collect()
         }
     }
 
+    /// Reads the source code from `reader` a line at a time instead of loading the
+    /// entire text, which keeps large generated files (such as memory initialization
+    /// packages) from being held in memory alongside their tokens.
+    pub fn read_from<R: std::io::BufRead>(reader: R) -> Result<Self, std::io::Error> {
+        let mut stream = VHDLTokenStream::from_reader(reader);
+        let tokens: Vec<Token<VHDLToken>> = stream.by_ref().filter_map(|t| t.ok()).collect();
+        if let Some(e) = stream.take_error() {
+            return Err(e)
+        }
+        let symbols = VHDLParser::parse(tokens);
+        Ok(Self { symbols: symbols.into_iter().filter_map(|f| f.ok()).collect() })
+    }
+
+    /// Reads the source code `s` using only the features available in the standard `std`.
+    /// 
+    /// Returns the tokens that require a later standard alongside the symbols.
+    pub fn read_as(s: &str, std: &VhdlStandard) -> (Self, Vec<StandardViolation>) {
+        let (tokens, violations) = std.restrict(VHDLTokenizer::from_source_code(s).into_tokens_all());
+        let symbols = VHDLParser::parse(tokens);
+        (Self { symbols: symbols.into_iter().filter_map(|f| f.ok()).collect() }, violations)
+    }
+
     pub fn into_symbols(self) -> Vec<VHDLSymbol> {
         self.symbols.into_iter().map(|f| f.take()).collect()
     }
@@ -625,6 +851,8 @@ impl VHDLParser {
 use std::iter::Peekable;
 
 use super::highlight::*;
+use super::standard::{StandardViolation, VhdlStandard};
+use super::doc;
 
 /// A `Statement` is a vector of tokens, similiar to how a `String` is a vector
 /// of characters.
@@ -749,6 +977,8 @@ impl VHDLSymbol {
                 generics: Generics::new(),
                 refs: clause.take_refs(),
                 body: None,
+                end: pos.clone(),
+                comment: String::new(),
                 pos: pos,
             })
         }
@@ -786,9 +1016,14 @@ impl VHDLSymbol {
             } else if t.as_type().check_keyword(&Keyword::Component) {
                 let _comp = Self::parse_component(tokens);
                 // println!("component declared: {}", comp);
+            // grab references from mode view declarations
+            } else if t.as_type().check_keyword(&Keyword::View) {
+                refs.append(&mut Self::parse_mode_view(tokens));
             } else if t.as_type().check_keyword(&Keyword::End) {
-                Self::parse_statement(tokens);
-                break;
+                // records and protected types also end within the declaration
+                if Self::is_primary_ending(&Self::parse_statement(tokens)) {
+                    break;
+                }
             } else {
                 refs.append(&mut Self::parse_statement(tokens).take_refs());
             }
@@ -803,6 +1038,8 @@ impl VHDLSymbol {
             generics: Generics(InterfaceDeclarations::from_double_listed_tokens(generics)),
             refs: refs,
             body: None,
+            end: pos.clone(),
+            comment: String::new(),
             pos: pos,
         })
     }
@@ -819,6 +1056,8 @@ impl VHDLSymbol {
             ContextUsage::ContextDeclaration(Context { 
                 name: iden, 
                 refs: Self::parse_context_declaration(tokens),
+                end: pos.clone(),
+                comment: String::new(),
                 pos: pos
             })
         // parse statement
@@ -910,13 +1149,14 @@ impl VHDLSymbol {
         if tokens.next().take().unwrap().as_type().check_keyword(&Keyword::Is) == false {
             panic!("expecting keyword IS")
         }
-        let (_, refs) = VHDLSymbol::parse_body(tokens, &Self::is_primary_ending);
+        let (_, refs, _) = VHDLSymbol::parse_body(tokens, &Self::is_primary_ending);
         PackageBody {
             owner: match pack_name {
                 VHDLToken::Identifier(id) => id,
                 _ => panic!("expected an identifier")
             },
             refs: refs,
+            end: pos.clone(),
             pos: pos,
         }
     }
@@ -983,42 +1223,81 @@ impl VHDLSymbol {
     /// 
     /// Assumes the next token to consume is instance name of the instantiation and
     /// the token to follow is the COLON ':' delimiter.
-    fn parse_instantiation(statement: Statement) -> Option<IdentifierList> {
+    ///
+    /// Returns the dependencies along with the instance.
+    fn parse_instantiation(statement: Statement) -> Option<(IdentifierList, Instance)> {
         let mut tokens = Self::statement_to_tokens(statement).into_iter().peekable();
         // force identifier (instance name)
-        tokens.next()?.take().as_identifier()?;
+        let label = tokens.next()?;
+        let pos = label.locate().clone();
+        let label = label.take().take_identifier()?;
         // force colon
         if tokens.next()?.take().check_delimiter(&Delimiter::Colon) == false { return None };
         // check what is instantiated
         match tokens.peek()?.as_type() {
-            VHDLToken::Identifier(_) => {
-                let mut deps = IdentifierList::new();
-                // take entity identifier
-                deps.append(&mut Self::compose_name(&mut tokens).into_compound_identifiers(true));
-                // take remaining possible references
-                Self::update_deps_from_statement(&mut deps, &mut tokens);
-                Some(deps)
-            }
+            VHDLToken::Identifier(_) => (),
             VHDLToken::Keyword(kw) => {
                 if kw == &Keyword::Component || kw == &Keyword::Entity || kw == &Keyword::Configuration {
                     tokens.next();
                     match tokens.peek()?.as_type() {
-                        VHDLToken::Identifier(_) => {
-                            let mut deps = IdentifierList::new();
-                            // take entity identifier
-                            deps.append(&mut Self::compose_name(&mut tokens).into_compound_identifiers(true));
-                            // take remaining possible references
-                            Self::update_deps_from_statement(&mut deps, &mut tokens);
-                            Some(deps)
-                        },
-                        _ => None,
+                        VHDLToken::Identifier(_) => (),
+                        _ => return None,
                     }
                 } else {
-                    None
+                    return None
                 }
             }
-            _ => None,
+            _ => return None,
         }
+        let mut deps = IdentifierList::new();
+        // take entity identifier
+        let name = Self::compose_name(&mut tokens);
+        let unit = match name.0.len() {
+            0 => return None,
+            1 => CompoundIdentifier::new_minimal(name.get_suffix().clone()),
+            n => CompoundIdentifier::new(name.0[n - 2].clone(), name.get_suffix().clone()),
+        };
+        deps.append(&mut name.into_compound_identifiers(true));
+        let rest: Vec<Token<VHDLToken>> = tokens.collect();
+        let generics = Self::parse_generic_map(&rest);
+        // take remaining possible references
+        Self::update_deps_from_statement(&mut deps, &mut rest.into_iter().peekable());
+        Some((deps, Instance { label: label, unit: unit, generics: generics, pos: pos }))
+    }
+
+    /// Collects the associations written in the generic map of an instantiation's
+    /// remaining `tokens`.
+    fn parse_generic_map(tokens: &[Token<VHDLToken>]) -> Vec<String> {
+        let start = match tokens.windows(3).position(|w| {
+            w[0].as_type().check_keyword(&Keyword::Generic)
+                && w[1].as_type().check_keyword(&Keyword::Map)
+                && w[2].as_type().check_delimiter(&Delimiter::ParenL)
+        }) {
+            Some(i) => i + 3,
+            None => return Vec::new(),
+        };
+        let mut generics = Vec::new();
+        let mut association: Vec<Token<VHDLToken>> = Vec::new();
+        let mut depth = 0;
+        for t in &tokens[start..] {
+            if t.as_type().check_delimiter(&Delimiter::ParenL) {
+                depth += 1;
+            } else if t.as_type().check_delimiter(&Delimiter::ParenR) {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            } else if depth == 0 && t.as_type().check_delimiter(&Delimiter::Comma) {
+                generics.push(Statement(association, IdentifierList::new()).to_string());
+                association = Vec::new();
+                continue;
+            }
+            association.push(t.clone());
+        }
+        if association.is_empty() == false {
+            generics.push(Statement(association, IdentifierList::new()).to_string());
+        }
+        generics
     }
 
     fn parse_configuration<I>(tokens: &mut Peekable<I>, pos: Position) -> VHDLSymbol 
@@ -1058,6 +1337,8 @@ impl VHDLSymbol {
             owner: entity_name,
             dependencies: deps,
             refs: refs,
+            end: pos.clone(),
+            comment: String::new(),
             pos: pos,
         })
     }
@@ -1136,12 +1417,14 @@ impl VHDLSymbol {
         let entity_name = VHDLSymbol::parse_owner_design_unit(tokens);
         // println!("*--- unit {}", arch_name);
 
-        let (deps, refs) =  VHDLSymbol::parse_declaration(tokens, &Self::is_primary_ending);
+        let (deps, refs, instances) =  VHDLSymbol::parse_declaration(tokens, &Self::is_primary_ending);
         VHDLSymbol::Architecture(Architecture {
             name: arch_name,
             owner: entity_name,
             dependencies: deps,
             refs: refs,
+            instances: instances,
+            end: pos.clone(),
             pos: pos,
         })
     }
@@ -1334,7 +1617,7 @@ impl VHDLSymbol {
             // stop the declaration section and enter a statement section
             if t.as_type().check_keyword(&Keyword::Begin) {
                 tokens.next();
-                let (_, mut refs) = Self::parse_body(tokens, &Self::is_primary_ending);
+                let (_, mut refs, _) = Self::parse_body(tokens, &Self::is_primary_ending);
                 entity_refs.append(&mut refs);
                 break;
             // the declaration is over and there is no statement section
@@ -1351,6 +1634,9 @@ impl VHDLSymbol {
                 pack_name.as_package().unwrap().get_refs().into_iter().for_each(|f| {
                     entity_refs.push_back(f.clone());
                 });
+            // grab references from mode view declarations
+            } else if t.as_type().check_keyword(&Keyword::View) {
+                entity_refs.append(&mut Self::parse_mode_view(tokens));
             // build statements to throw away
             } else {
                 let mut clause = Self::parse_statement(tokens);
@@ -1407,7 +1693,7 @@ impl VHDLSymbol {
             // determine when to branch to declaration section or body section
             if t.as_type().check_keyword(&Keyword::Is) {
                 // println!("OUT SUB: {:?}", t);
-                let (mut sub_deps, mut sub_refs) = Self::parse_declaration(tokens, &Self::is_subprogram_ending);
+                let (mut sub_deps, mut sub_refs, _) = Self::parse_declaration(tokens, &Self::is_subprogram_ending);
                 deps.append(&mut sub_deps);
                 refs.append(&mut sub_refs);
                 break;
@@ -1427,8 +1713,8 @@ impl VHDLSymbol {
     /// 
     /// Assumes the next token to consume is `IS` and throws it away.
     /// 
-    /// Returns (`deps`, `refs`)
-    fn parse_declaration<I>(tokens: &mut Peekable<I>, eval_exit: &dyn Fn(&Statement) -> bool) -> (IdentifierList, IdentifierList)
+    /// Returns (`deps`, `refs`, `instances`)
+    fn parse_declaration<I>(tokens: &mut Peekable<I>, eval_exit: &dyn Fn(&Statement) -> bool) -> (IdentifierList, IdentifierList, Vec<Instance>)
         where I: Iterator<Item=Token<VHDLToken>> {
         // println!("*--- declaration section");
         // force taking the 'is' keyword
@@ -1437,15 +1723,17 @@ impl VHDLSymbol {
         }
         let mut refs = IdentifierList::new();
         let mut deps = IdentifierList::new();
+        let mut instances = Vec::new();
         while let Some(t) = tokens.peek() {
             // println!("dec: {:?}", t);
             // stop the declaration section and enter a statement section
             if t.as_type().check_keyword(&Keyword::Begin) {
                 tokens.next();
                 // combine refs from declaration and from body
-                let (mut body_deps, mut body_refs) = Self::parse_body(tokens, &eval_exit);
+                let (mut body_deps, mut body_refs, mut body_instances) = Self::parse_body(tokens, &eval_exit);
                 refs.append(&mut body_refs);
                 deps.append(&mut body_deps);
+                instances.append(&mut body_instances);
                 // println!("{}", "stop reading tokens");
                 // STOP READING TOKENS
                 break;
@@ -1465,6 +1753,9 @@ impl VHDLSymbol {
                 let inner_pos = tokens.next().unwrap().into_position();
                 let _pack_name = Self::route_package_parse(tokens, inner_pos);
                 // println!("**** INFO: detected nested package \"{}\"", pack_name);
+            // grab references from mode view declarations
+            } else if t.as_type().check_keyword(&Keyword::View) {
+                refs.append(&mut Self::parse_mode_view(tokens));
             // detect subprograms
             } else if t.as_type().as_keyword().is_some() && Self::is_subprogram(t.as_type().as_keyword().unwrap()) == true {
                 // println!("{}", "sub program");
@@ -1485,7 +1776,7 @@ impl VHDLSymbol {
                 }
             }
         }
-        (deps, refs)
+        (deps, refs, instances)
     }
 
     /// Checks if the statement is a valid primary unit END statement.
@@ -1504,13 +1795,38 @@ impl VHDLSymbol {
                 Keyword::Loop | Keyword::Generate | Keyword::Process |
                 Keyword::Postponed | Keyword::If | Keyword::Block | 
                 Keyword::Protected | Keyword::Record | Keyword::Case | 
-                Keyword::Component | Keyword::For => false,
+                Keyword::Component | Keyword::For | Keyword::View => false,
                 _ => true,
             },
             _ => true,
         }
     }
 
+    /// Parses a VHDL-2019 mode view declaration, consuming the tokens `VIEW` until
+    /// its `END VIEW` statement.
+    /// 
+    /// Returns the references found in the composite type and the element modes.
+    /// Assumes the first token to consume is `VIEW`.
+    fn parse_mode_view<I>(tokens: &mut Peekable<I>) -> IdentifierList
+    where I: Iterator<Item=Token<VHDLToken>> {
+        let mut refs = IdentifierList::new();
+        // the first statement is the view header along with the first element mode
+        let mut header = Self::parse_statement(tokens);
+        refs.append(header.get_refs_mut());
+        // a view without element modes ends within the header
+        if header.get_tokens().iter().any(|t| t.as_type().check_keyword(&Keyword::End)) {
+            return refs
+        }
+        while tokens.peek().is_some() {
+            let mut stmt = Self::parse_statement(tokens);
+            refs.append(stmt.get_refs_mut());
+            if stmt.get_tokens().first().map(|t| t.as_type().check_keyword(&Keyword::End)).unwrap_or(false) {
+                break;
+            }
+        }
+        refs
+    }
+
     /// Checks if the statement is a valid non-primary unit END statement.
     /// 
     /// This is the negation of `is_primary_ending`.
@@ -1584,11 +1900,12 @@ impl VHDLSymbol {
     /// Builds statements and stops after finding the `END` keyword statement. If
     /// the `END` keyword statement is detected, it will have to pass the `eval_exit`
     /// function to properly exit scope. Assumes the last token consumed was `BEGIN`.
-    fn parse_body<I>(tokens: &mut Peekable<I>, eval_exit: &dyn Fn(&Statement) -> bool) -> (IdentifierList, IdentifierList)
+    fn parse_body<I>(tokens: &mut Peekable<I>, eval_exit: &dyn Fn(&Statement) -> bool) -> (IdentifierList, IdentifierList, Vec<Instance>)
         where I: Iterator<Item=Token<VHDLToken>> {
         // collect component names
         let mut deps = IdentifierList::new();
         let mut refs = IdentifierList::new();
+        let mut instances = Vec::new();
         // println!("*--- statement section");
         while let Some(t) = tokens.peek() {
             if t.as_type().check_keyword(&Keyword::End) == true {
@@ -1620,14 +1937,15 @@ impl VHDLSymbol {
                 // println!("in body: {:?}", stmt);
                 refs.append(&mut stmt.1);
                 // check if statement is an instantiation
-                if let Some(mut inst) = Self::parse_instantiation(stmt) {
+                if let Some((mut inst, instance)) = Self::parse_instantiation(stmt) {
                     // println!("info: detected dependency \"{}\"", inst);
                     deps.append(&mut inst);
+                    instances.push(instance);
                 }
             }
         }
         // println!("{:?}", deps);
-        (deps, refs)
+        (deps, refs, instances)
     }
 }
 
@@ -1635,6 +1953,92 @@ impl VHDLSymbol {
 mod test {
     use super::*;
 
+    #[test]
+    fn unit_spans() {
+        let s = "library ieee;
+
+entity adder is
+    port (a : in bit);
+end entity;
+
+architecture rtl of adder is
+begin
+end architecture rtl;
+";
+        let symbols = VHDLParser::read(s).into_symbols();
+        let entity = symbols[0].get_span();
+        assert_eq!(entity.start(), &Position::place(3, 1));
+        assert_eq!(entity.end(), &Position::place(5, 12));
+        assert_eq!(&s[entity.byte_range()], "entity adder is\n    port (a : in bit);\nend entity;");
+        let arch = symbols[1].get_span();
+        assert_eq!(arch.start(), &Position::place(7, 1));
+        assert_eq!(arch.end(), &Position::place(9, 22));
+        assert!(s[arch.byte_range()].ends_with("end architecture rtl;"));
+    }
+
+    #[test]
+    fn architecture_instances() {
+        let s = "architecture rtl of top is
+begin
+    u_fifo: entity work.fifo(rtl) generic map (DEPTH => 16, WIDTH => W * 2) port map (clk => clk);
+    u_and: and_gate port map (a, b, y);
+    u_cfg: configuration lib.cfg_core;
+end architecture;
+";
+        let symbols = VHDLParser::read(s).into_symbols();
+        let insts = match &symbols[0] {
+            VHDLSymbol::Architecture(arch) => arch.get_instances(),
+            _ => panic!("expecting an architecture"),
+        };
+        let summary: Vec<(String, String, &Vec<String>)> = insts.iter()
+            .map(|i| (i.get_label().to_string(), i.get_unit().to_string(), i.get_generics()))
+            .collect();
+        assert_eq!(summary, vec![
+            (String::from("u_fifo"), String::from("work.fifo"), &vec![String::from("DEPTH => 16"), String::from("WIDTH => W * 2")]),
+            (String::from("u_and"), String::from("and_gate"), &vec![]),
+            (String::from("u_cfg"), String::from("lib.cfg_core"), &vec![]),
+        ]);
+        assert_eq!(insts[1].get_position(), &Position::place(4, 5));
+    }
+
+    #[test]
+    fn read_from_reader() {
+        let s = "\
+entity nor_gate is end entity;
+architecture rtl of nor_gate is begin end architecture;
+";
+        let symbols = VHDLParser::read_from(s.as_bytes()).unwrap().into_symbols();
+        assert_eq!(symbols, VHDLParser::read(s).into_symbols());
+        assert!(VHDLParser::read_from(&b"entity \xff is end;"[..]).is_err());
+    }
+
+    #[test]
+    fn unit_comments() {
+        let s = "library ieee; -- not about the adder
+
+-- Adds two bits.
+-- Has no carry out.
+--! author: ks-tech
+entity adder is
+end entity;
+
+-- separated by a blank line
+
+architecture rtl of adder is
+begin
+end architecture rtl;
+package p is end package; -- trails the package
+/* Shared
+ * constants. */
+package consts is end package;
+";
+        let symbols = VHDLParser::read(s).into_symbols();
+        assert_eq!(symbols[0].get_comment(), Some("Adds two bits.\nHas no carry out."));
+        assert_eq!(symbols[1].get_comment(), None);
+        assert_eq!(symbols[2].get_comment(), None);
+        assert_eq!(symbols[3].get_comment(), Some("Shared\nconstants."));
+    }
+
     #[test]
     fn parse_use_clause() {
         let s = "use eel4712c.pkg1, eel4712c.pkg2; entity";
@@ -1645,12 +2049,12 @@ mod test {
         assert_eq!(using_imports, UseClause { 
             imports: vec![
                 SelectedName(vec![
-                    Identifier::Basic("eel4712c".to_owned()),
-                    Identifier::Basic("pkg1".to_owned()),
+                    Identifier::Basic("eel4712c".into()),
+                    Identifier::Basic("pkg1".into()),
                 ]),
                 SelectedName(vec![
-                    Identifier::Basic("eel4712c".to_owned()),
-                    Identifier::Basic("pkg2".to_owned()),
+                    Identifier::Basic("eel4712c".into()),
+                    Identifier::Basic("pkg2".into()),
                 ]),
         ]});
         assert_eq!(tokens.next().unwrap().as_type(), &VHDLToken::Keyword(Keyword::Entity));
@@ -1662,12 +2066,12 @@ mod test {
         let tokens = VHDLTokenizer::from_source_code(&s).into_tokens();
         let mut iter = tokens.into_iter().peekable();
         assert_eq!(VHDLSymbol::parse_statement(&mut iter).as_types(), vec![
-            &VHDLToken::Identifier(Identifier::Basic("P1".to_owned())),
+            &VHDLToken::Identifier(Identifier::Basic("P1".into())),
             &VHDLToken::Delimiter(Delimiter::Comma),
-            &VHDLToken::Identifier(Identifier::Basic("P2".to_owned())),
+            &VHDLToken::Identifier(Identifier::Basic("P2".into())),
             &VHDLToken::Delimiter(Delimiter::Colon),
             &VHDLToken::Keyword(Keyword::Inout),
-            &VHDLToken::Identifier(Identifier::Basic("BIT".to_owned())),
+            &VHDLToken::Identifier(Identifier::Basic("BIT".into())),
             &VHDLToken::Delimiter(Delimiter::ParenR),
         ]);
         assert_eq!(iter.next().unwrap().as_type(), &VHDLToken::Keyword(Keyword::Constant));
@@ -1679,8 +2083,8 @@ mod test {
         let mut tokens = VHDLTokenizer::from_source_code(&s).into_tokens().into_iter().peekable();
         let sel_name = VHDLSymbol::compose_name(&mut tokens);
         assert_eq!(sel_name, SelectedName(vec![
-            Identifier::Basic("eel4712c".to_owned()),
-            Identifier::Basic("nor_gate".to_owned()),
+            Identifier::Basic("eel4712c".into()),
+            Identifier::Basic("nor_gate".into()),
         ]));
         assert_eq!(tokens.next().unwrap().as_type(), &VHDLToken::Keyword(Keyword::Port));
     }
@@ -1692,7 +2096,7 @@ mod test {
         let mut tokens = VHDLTokenizer::from_source_code(&s).into_tokens().into_iter().peekable();
         let sel_name = VHDLSymbol::compose_name(&mut tokens);
         assert_eq!(sel_name, SelectedName(vec![
-            Identifier::Basic("eel4712c".to_owned()),
+            Identifier::Basic("eel4712c".into()),
         ]));
         assert_eq!(tokens.next().unwrap().as_type(), &VHDLToken::Delimiter(Delimiter::Plus));
     }
@@ -1809,7 +2213,7 @@ signal ready: std_logic;";
         let stmt = Statement(vec![
             Token::new(VHDLToken::Keyword(Keyword::End), Position::new()),
             Token::new(VHDLToken::Keyword(Keyword::Case), Position::new()),
-            Token::new(VHDLToken::Identifier(Identifier::Basic("case_label".to_owned())), Position::new()),
+            Token::new(VHDLToken::Identifier(Identifier::Basic("case_label".into())), Position::new()),
         ], IdentifierList::new());
         assert_eq!(VHDLSymbol::is_primary_ending(&stmt), false);
 
@@ -1823,14 +2227,14 @@ signal ready: std_logic;";
         let stmt = Statement(vec![
             Token::new(VHDLToken::Keyword(Keyword::End), Position::new()),
             Token::new(VHDLToken::Keyword(Keyword::Architecture), Position::new()),
-            Token::new(VHDLToken::Identifier(Identifier::Basic("architecture_name".to_owned())), Position::new()),
+            Token::new(VHDLToken::Identifier(Identifier::Basic("architecture_name".into())), Position::new()),
         ], IdentifierList::new());
         assert_eq!(VHDLSymbol::is_primary_ending(&stmt), true);
 
         // primary endings can have their keyword omitted and also include the identifier label
         let stmt = Statement(vec![
             Token::new(VHDLToken::Keyword(Keyword::End), Position::new()),
-            Token::new(VHDLToken::Identifier(Identifier::Basic("architecture_name".to_owned())), Position::new()),
+            Token::new(VHDLToken::Identifier(Identifier::Basic("architecture_name".into())), Position::new()),
         ], IdentifierList::new());
         assert_eq!(VHDLSymbol::is_primary_ending(&stmt), true);
     }
@@ -1851,7 +2255,7 @@ end entity nor_gate;";
         let mut tokens = VHDLTokenizer::from_source_code(&s).into_tokens().into_iter().peekable();
         let e = Entity::from_tokens(&mut tokens, Position::place(1, 2));
         assert_eq!(e.pos, Position::place(1, 2));
-        assert_eq!(e.name, Identifier::Basic(String::from("nor_gate")));
+        assert_eq!(e.name, Identifier::Basic("nor_gate".into()));
         assert_eq!(e.generics.0.len(), 1);
         assert_eq!(e.ports.0.len(), 3);
     }
@@ -1890,10 +2294,10 @@ end entity nor_gate;";
         let tokens = VHDLTokenizer::from_source_code(&s).into_tokens();
         let mut iter = tokens.into_iter().peekable();
         assert_eq!(VHDLSymbol::parse_statement(&mut iter).as_types(), vec![
-            &VHDLToken::Identifier(Identifier::Basic("a".to_owned())),
+            &VHDLToken::Identifier(Identifier::Basic("a".into())),
             &VHDLToken::Delimiter(Delimiter::Colon),
             &VHDLToken::Keyword(Keyword::In),
-            &VHDLToken::Identifier(Identifier::Basic("std_logic_vector".to_owned())),
+            &VHDLToken::Identifier(Identifier::Basic("std_logic_vector".into())),
             &VHDLToken::Delimiter(Delimiter::ParenL),
             &VHDLToken::AbstLiteral(AbstLiteral::Decimal("3".to_owned())),
             &VHDLToken::Keyword(Keyword::Downto),
@@ -1905,10 +2309,10 @@ end entity nor_gate;";
         let tokens = VHDLTokenizer::from_source_code(&s).into_tokens();
         let mut iter = tokens.into_iter().peekable();
         assert_eq!(VHDLSymbol::parse_statement(&mut iter).as_types(), vec![
-            &VHDLToken::Identifier(Identifier::Basic("a".to_owned())),
+            &VHDLToken::Identifier(Identifier::Basic("a".into())),
             &VHDLToken::Delimiter(Delimiter::Colon),
             &VHDLToken::Keyword(Keyword::In),
-            &VHDLToken::Identifier(Identifier::Basic("std_logic_vector".to_owned())),
+            &VHDLToken::Identifier(Identifier::Basic("std_logic_vector".into())),
             &VHDLToken::Delimiter(Delimiter::ParenL),
             &VHDLToken::AbstLiteral(AbstLiteral::Decimal("3".to_owned())),
             &VHDLToken::Keyword(Keyword::Downto),
@@ -1951,8 +2355,8 @@ end HA_Config;
         let symbols = VHDLParser::parse(VHDLTokenizer::from_source_code(&s).into_tokens());
         assert_eq!(symbols.first().unwrap().as_ref().unwrap().as_ref().as_configuration().unwrap().edges(),
             &IdentifierList::from([
-                CompoundIdentifier::new_minimal(Identifier::Basic(String::from("HA_Comp_Entity"))), 
-                CompoundIdentifier::new_minimal(Identifier::Basic(String::from("HA_Comp_Entity2")))
+                CompoundIdentifier::new_minimal(Identifier::Basic("HA_Comp_Entity".into())), 
+                CompoundIdentifier::new_minimal(Identifier::Basic("HA_Comp_Entity2".into()))
             ]));
     }
 
@@ -1968,10 +2372,10 @@ for L1: XOR_GATE use entity WORK.XOR_GATE(Behavior) -- or L1 = 'others' = 'L1, L
         let st = VHDLSymbol::parse_statement(&mut iter);
         let iden = VHDLSymbol::parse_configuration_spec(st);
         assert_eq!(iden.unwrap(), IdentifierList::from([
-            CompoundIdentifier::new(Identifier::Basic(String::from("WORK")), Identifier::Basic(String::from("XOR_GATE"))),
-            CompoundIdentifier::new_minimal(Identifier::Basic(String::from("XOR_GATE"))),
-            CompoundIdentifier::new(Identifier::Basic(String::from("work")), Identifier::Basic(String::from("lab1_pkg"))),
-            CompoundIdentifier::new(Identifier::Basic(String::from("lab1_pkg")), Identifier::Basic(String::from("MAGIC_NUM"))),
+            CompoundIdentifier::new(Identifier::Basic("WORK".into()), Identifier::Basic("XOR_GATE".into())),
+            CompoundIdentifier::new_minimal(Identifier::Basic("XOR_GATE".into())),
+            CompoundIdentifier::new(Identifier::Basic("work".into()), Identifier::Basic("lab1_pkg".into())),
+            CompoundIdentifier::new(Identifier::Basic("lab1_pkg".into()), Identifier::Basic("MAGIC_NUM".into())),
         ]));
 
         let s = r#"
@@ -1982,7 +2386,7 @@ for all: xor_gate use configuration cfg1;
         let st = VHDLSymbol::parse_statement(&mut iter);
         let iden = VHDLSymbol::parse_configuration_spec(st);
         assert_eq!(iden.unwrap(), IdentifierList::from([
-            CompoundIdentifier::new_minimal(Identifier::Basic(String::from("cfg1")))
+            CompoundIdentifier::new_minimal(Identifier::Basic("cfg1".into()))
             ]));
     }
 
@@ -2138,11 +2542,61 @@ end architecture sim;
         assert_eq!(
             syms.get(1).as_ref().unwrap().as_ref().unwrap().as_ref().as_architecture().unwrap().edges(), 
             &LinkedList::from([
-                CompoundIdentifier { prefix: Some(Basic("work".to_string())), suffix: Basic("fib".to_string()) }, 
-                CompoundIdentifier { prefix: None, suffix: Basic("fib".to_string()) }])
+                CompoundIdentifier { prefix: Some(Basic("work".into())), suffix: Basic("fib".into()) }, 
+                CompoundIdentifier { prefix: None, suffix: Basic("fib".into()) }])
         );
     }
 
+    #[test]
+    fn vhdl_2019_declarations() {
+        let s = "\
+package bus_pkg is
+    type bus_t is record
+        addr : work.types.addr_t;
+        data : std_ulogic_vector(7 downto 0);
+    end record;
+
+    view bus_master of bus_t is
+        addr : out;
+        data : inout;
+    end view bus_master;
+
+    type counter_t is protected
+        procedure inc;
+        private variable count : work.types.count_t;
+    end protected counter_t;
+
+    constant RESET : work.consts.reset_t;
+end package bus_pkg;
+
+entity endpoint is
+    port (
+        bus_if : view work.bus_pkg.bus_master;
+    );
+end entity;
+
+architecture rtl of endpoint is
+    view bus_slave of work.bus_pkg.bus_t is
+    end view;
+    signal s : work.more.sig_t;
+begin
+end architecture;
+";
+        let symbols = VHDLParser::read(s).into_symbols();
+        assert_eq!(symbols.len(), 3);
+        // the package does not end early at the record, view, or protected type
+        let pkg = symbols[0].as_package().unwrap();
+        assert_eq!(pkg.get_refs().iter().map(|r| r.to_string()).collect::<Vec<String>>(), vec![
+            "work.types", "types.addr_t", "work.types", "types.count_t", "work.consts", "consts.reset_t",
+        ]);
+        let ent = symbols[1].as_entity().unwrap();
+        assert!(ent.get_refs().contains(&CompoundIdentifier::new(Identifier::from_str("work").unwrap(), Identifier::from_str("bus_pkg").unwrap())));
+        let arch = symbols[2].as_architecture().unwrap();
+        assert_eq!(arch.get_refs().iter().map(|r| r.to_string()).collect::<Vec<String>>(), vec![
+            "work.bus_pkg", "bus_pkg.bus_t", "work.more", "more.sig_t",
+        ]);
+    }
+
     #[test]
     #[ignore]
     fn parse_basic() {
@@ -0,0 +1,159 @@
+//! A lossless view of VHDL source code for rewriting it without disturbing its
+//! layout.
+//!
+//! The source code is divided into significant tokens and the trivia (whitespace
+//! and comments) between them. Writing every piece of trivia and every token's
+//! original text back in order reproduces the source code exactly.
+
+use std::ops::Range;
+use crate::util::anyerror::AnyError;
+use super::token::{Comment, Identifier, VHDLToken, VHDLTokenizer};
+
+/// A significant token and the span of bytes it was read from.
+#[derive(Debug, PartialEq)]
+pub struct SyntaxToken {
+    token: VHDLToken,
+    span: Range<usize>,
+}
+
+impl SyntaxToken {
+    pub fn as_type(&self) -> &VHDLToken {
+        &self.token
+    }
+
+    pub fn get_span(&self) -> &Range<usize> {
+        &self.span
+    }
+
+    /// Checks if the token is the identifier `name`.
+    pub fn is_identifier(&self, name: &Identifier) -> bool {
+        self.token.as_identifier() == Some(name)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SourceFile {
+    text: String,
+    tokens: Vec<SyntaxToken>,
+}
+
+impl SourceFile {
+    /// Reads the source code `text`.
+    ///
+    /// Errors if the text cannot be tokenized, because its trivia would be unknown.
+    pub fn parse(text: &str) -> Result<Self, AnyError> {
+        if let Some(e) = VHDLTokenizer::from_source_code(text).into_errors().first() {
+            return Err(AnyError(format!("invalid syntax at line {} col {}: {}", e.locate().line(), e.locate().col(), e.as_err().to_string().trim_end())))
+        }
+        let all: Vec<_> = VHDLTokenizer::from_source_code(text)
+            .into_tokens_all()
+            .into_iter()
+            .filter(|t| !t.as_type().is_eof())
+            .collect();
+        let mut tokens = Vec::new();
+        for (i, tk) in all.iter().enumerate() {
+            let start = tk.byte_offset();
+            let next = all.get(i + 1).map(|t| t.byte_offset()).unwrap_or(text.len());
+            let rest = &text[start..next];
+            let len = match tk.as_type() {
+                // a single-line comment ends at the newline
+                VHDLToken::Comment(Comment::Single(_)) => rest.split('\n').next().unwrap().trim_end_matches('\r').len(),
+                _ => rest.trim_end().len(),
+            };
+            if tk.as_type().as_comment().is_none() {
+                tokens.push(SyntaxToken { token: tk.as_type().clone(), span: start..start + len });
+            }
+        }
+        Ok(Self { text: text.to_string(), tokens })
+    }
+
+    pub fn get_tokens(&self) -> &[SyntaxToken] {
+        &self.tokens
+    }
+
+    /// References the original text of the token at index `i`.
+    pub fn text_of(&self, i: usize) -> &str {
+        &self.text[self.tokens[i].span.clone()]
+    }
+
+    /// References the whitespace and comments before the token at index `i`.
+    pub fn trivia_before(&self, i: usize) -> &str {
+        let start = match i {
+            0 => 0,
+            _ => self.tokens[i - 1].span.end,
+        };
+        &self.text[start..self.tokens[i].span.start]
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Creates an empty set of edits to the source code.
+    pub fn rewrite(&self) -> Rewriter<'_> {
+        Rewriter { file: self, edits: Vec::new() }
+    }
+}
+
+/// Replaces the text of tokens while leaving all trivia untouched.
+#[derive(Debug, PartialEq)]
+pub struct Rewriter<'a> {
+    file: &'a SourceFile,
+    edits: Vec<(usize, String)>,
+}
+
+impl<'a> Rewriter<'a> {
+    /// Replaces the text of the token at index `i` with `text`.
+    ///
+    /// A later replacement of the same token takes precedence.
+    pub fn replace(&mut self, i: usize, text: &str) {
+        self.edits.retain(|(j, _)| *j != i);
+        self.edits.push((i, text.to_string()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Writes the source code with every replacement applied.
+    pub fn apply(&self) -> String {
+        let mut edits: Vec<&(usize, String)> = self.edits.iter().collect();
+        edits.sort_by_key(|(i, _)| *i);
+        let mut result = String::with_capacity(self.file.text.len());
+        let mut copied = 0;
+        for (i, text) in edits {
+            let span = &self.file.tokens[*i].span;
+            result.push_str(&self.file.text[copied..span.start]);
+            result.push_str(text);
+            copied = span.end;
+        }
+        result.push_str(&self.file.text[copied..]);
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lossless() {
+        let s = "entity  top is -- the top\n  /* no ports */ end;\r\n";
+        let file = SourceFile::parse(s).unwrap();
+        assert_eq!(file.get_tokens().len(), 5);
+        assert_eq!(file.text_of(1), "top");
+        assert_eq!(file.trivia_before(3), " -- the top\n  /* no ports */ ");
+
+        // no edits reproduces the original text
+        assert_eq!(file.rewrite().apply(), s);
+
+        let mut edits = file.rewrite();
+        edits.replace(1, "chip");
+        edits.replace(3, "END");
+        assert_eq!(edits.apply(), "entity  chip is -- the top\n  /* no ports */ END;\r\n");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,218 @@
+//! Testbench skeletons generated from an entity's interface.
+
+use super::printer::Printer;
+use super::standard::VhdlStandard;
+use super::symbol::Entity;
+use super::syntax::SourceFile;
+use super::token::{Delimiter, Identifier, Keyword, VHDLToken};
+
+/// Copies the library, use, and context clauses written directly before the
+/// declaration of `entity` in `file`.
+///
+/// Returns an empty string when the entity is not declared in the file.
+pub fn context_clause(file: &SourceFile, entity: &Identifier) -> String {
+    let tokens = file.get_tokens();
+    let decl = tokens.iter().enumerate().position(|(i, tk)| {
+        tk.as_type().check_keyword(&Keyword::Entity)
+            && tokens.get(i + 1).map(|t| t.is_identifier(entity)).unwrap_or(false)
+            && tokens.get(i + 2).map(|t| t.as_type().check_keyword(&Keyword::Is)).unwrap_or(false)
+    });
+    let decl = match decl {
+        Some(d) => d,
+        None => return String::new(),
+    };
+    // walk backward over each clause ending directly before the declaration
+    let mut start = decl;
+    while start > 0 && tokens[start - 1].as_type().check_delimiter(&Delimiter::Terminator) {
+        let begin = tokens[..start - 1].iter()
+            .rposition(|t| t.as_type().check_delimiter(&Delimiter::Terminator))
+            .map(|k| k + 1)
+            .unwrap_or(0);
+        match tokens[begin].as_type() {
+            VHDLToken::Keyword(Keyword::Library) | VHDLToken::Keyword(Keyword::Use) | VHDLToken::Keyword(Keyword::Context) => start = begin,
+            _ => break,
+        }
+    }
+    match start == decl {
+        true => String::new(),
+        false => file.as_str()[tokens[start].get_span().start..tokens[decl - 1].get_span().end].to_string(),
+    }
+}
+
+/// Checks if the port is a clock driven by the testbench.
+fn is_clock(name: &str) -> bool {
+    name.contains("clk") || name.contains("clock")
+}
+
+/// Checks if the port is a reset driven by the testbench.
+fn is_reset(name: &str) -> bool {
+    name.contains("rst") || name.contains("reset")
+}
+
+/// Checks if the reset is asserted with a logic '0'.
+fn is_active_low(name: &str) -> bool {
+    name.ends_with("_n") || name.ends_with("rstn") || name.ends_with("resetn") || name.starts_with('n')
+}
+
+/// Writes a testbench for `entity` that instantiates it as a component.
+///
+/// The code is written without colors since it is saved to a file.
+/// The clock and reset inputs are identified by name and driven by their own
+/// processes. The testbench ends itself with `std.env.finish` for VHDL-2008 and
+/// later. Otherwise, the clock is stopped so the simulation runs out of events.
+pub fn generate(entity: &Entity, context: &str, standard: Option<&VhdlStandard>) -> String {
+    let finish = standard.map(|s| s >= &VhdlStandard::Vhdl2008).unwrap_or(false);
+    let name = entity.get_name().into_extension("_tb");
+
+    // only single-bit inputs can be toggled by the generated processes
+    let mut clock = None;
+    let mut reset = None;
+    for port in entity.get_ports().0.iter() {
+        let is_bit = match port.get_type() {
+            [VHDLToken::Identifier(t)] => ["std_logic", "std_ulogic", "bit"].iter().any(|b| t == &Identifier::Basic((*b).into())),
+            _ => false,
+        };
+        if port.get_mode() != &Keyword::In || !is_bit {
+            continue
+        }
+        let iden = port.get_identifier().to_string().to_lowercase();
+        if clock.is_none() && is_clock(&iden) {
+            clock = Some(port.get_identifier());
+        } else if reset.is_none() && is_reset(&iden) {
+            reset = Some((port.get_identifier(), is_active_low(&iden)));
+        }
+    }
+
+    let indent = |text: &str| -> String {
+        text.lines()
+            .map(|l| match l.is_empty() { true => String::from("\n"), false => format!("    {}\n", l) })
+            .collect()
+    };
+
+    let mut result = String::new();
+    if !context.is_empty() {
+        result.push_str(&format!("{}\n\n", context));
+    }
+    result.push_str(&format!("entity {} is\nend entity;\n\n", name));
+    result.push_str(&format!("architecture sim of {} is\n", name));
+
+    let constants = entity.into_constants().to_plain();
+    if !constants.is_empty() {
+        result.push_str("    -- configuration of the unit under test\n");
+        for (line, generic) in constants.lines().zip(entity.get_generics().0.iter()) {
+            match generic.has_default() {
+                true => result.push_str(&format!("    {}\n", line)),
+                false => result.push_str(&format!("    {} -- @todo: assign a value\n", line)),
+            }
+        }
+        result.push('\n');
+    }
+    let signals = entity.into_signals().to_plain();
+    if !signals.is_empty() {
+        result.push_str(&indent(&signals));
+        result.push('\n');
+    }
+    if clock.is_some() {
+        result.push_str("    constant CLK_PERIOD : time := 10 ns;\n");
+        if !finish {
+            result.push_str("    signal halt : boolean := false;\n");
+        }
+        result.push('\n');
+    }
+    result.push_str(&indent(&entity.into_component().to_plain()));
+    result.push_str("begin\n\n");
+
+    result.push_str("    -- unit under test\n");
+    result.push_str(&indent(&entity.into_instance(&Identifier::Basic("uut".into()), None).to_plain()));
+    result.push('\n');
+
+    if let Some(clk) = clock {
+        let stop = match finish {
+            true => "",
+            false => "\n        if halt then\n            wait;\n        end if;",
+        };
+        result.push_str(&format!("    clock: process\n    begin\n        {0} <= '0';\n        wait for CLK_PERIOD / 2;\n        {0} <= '1';\n        wait for CLK_PERIOD / 2;{1}\n    end process;\n\n", clk, stop));
+    }
+    // the reset is held for a few clock periods
+    let hold = match clock.is_some() {
+        true => "CLK_PERIOD * 4",
+        false => "40 ns",
+    };
+    if let Some((rst, active_low)) = reset {
+        let (on, off) = match active_low {
+            true => ('0', '1'),
+            false => ('1', '0'),
+        };
+        result.push_str(&format!("    reset: process\n    begin\n        {0} <= '{1}';\n        wait for {3};\n        {0} <= '{2}';\n        wait;\n    end process;\n\n", rst, on, off, hold));
+    }
+
+    result.push_str("    stimulus: process\n    begin\n");
+    if reset.is_some() {
+        result.push_str(&format!("        wait for {};\n", hold));
+    }
+    result.push_str("        -- @todo: drive the inputs and check the outputs\n\n");
+    result.push_str("        report \"simulation complete\";\n");
+    match (finish, clock.is_some()) {
+        (true, _) => result.push_str("        std.env.finish;\n"),
+        (false, true) => result.push_str("        halt <= true;\n        wait;\n"),
+        (false, false) => result.push_str("        wait;\n"),
+    }
+    result.push_str("    end process;\n\nend architecture;\n");
+    // indent the interface lists within the component and instance
+    Printer::new().format(&result).unwrap_or(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::symbol::{VHDLParser, VHDLSymbol};
+
+    const DESIGN: &str = "\
+library ieee;
+use ieee.std_logic_1164.all;
+
+-- counts the rising edges of the clock
+entity counter is
+    generic (WIDTH : positive := 8; STEP : natural);
+    port (clk : in std_logic; rst_n : in std_logic; count : out std_logic_vector(WIDTH-1 downto 0));
+end entity;
+";
+
+    fn entity() -> Entity {
+        match VHDLParser::read(DESIGN).into_symbols().remove(0) {
+            VHDLSymbol::Entity(e) => e,
+            _ => panic!("expected an entity"),
+        }
+    }
+
+    #[test]
+    fn copies_context_clause() {
+        let file = SourceFile::parse(DESIGN).unwrap();
+        assert_eq!(context_clause(&file, &Identifier::Basic("counter".into())), "library ieee;\nuse ieee.std_logic_1164.all;");
+        assert_eq!(context_clause(&file, &Identifier::Basic("timer".into())), "");
+
+        let file = SourceFile::parse("entity a is end entity;\nentity b is end entity;\n").unwrap();
+        assert_eq!(context_clause(&file, &Identifier::Basic("b".into())), "");
+    }
+
+    #[test]
+    fn skeleton() {
+        let tb = generate(&entity(), "library ieee;", Some(&VhdlStandard::Vhdl2008));
+        assert!(tb.starts_with("library ieee;\n\nentity counter_tb is\nend entity;\n\narchitecture sim of counter_tb is\n"));
+        assert!(tb.contains("    constant WIDTH : positive := 8;\n    constant STEP  : natural; -- @todo: assign a value\n"));
+        assert!(tb.contains("    component counter\n"));
+        assert!(tb.contains("    uut : counter\n        generic map (\n"));
+        assert!(tb.contains("        clk <= '0';\n"));
+        assert!(tb.contains("        rst_n <= '0';\n        wait for CLK_PERIOD * 4;\n        rst_n <= '1';\n"));
+        assert!(tb.contains("        std.env.finish;\n"));
+        assert!(!tb.contains("halt"));
+        assert!(!tb.contains('\x1b'));
+
+        // earlier standards stop the clock instead
+        let tb = generate(&entity(), "", None);
+        assert!(tb.starts_with("entity counter_tb is\n"));
+        assert!(tb.contains("    signal halt : boolean := false;\n"));
+        assert!(tb.contains("        halt <= true;\n        wait;\n"));
+        assert!(!tb.contains("std.env.finish"));
+    }
+}
\ No newline at end of file
@@ -5,12 +5,18 @@ use colored::ColoredString;
 use crate::core::lexer;
 use crate::core::lexer::Position;
 use crate::core::lexer::TrainCar;
+use crate::core::lexer::ReadChars;
 use crate::core::lexer::Tokenize;
 use crate::core::pkgid::PkgPart;
 use std::fmt::Display;
 use crate::util::strcmp;
+use crate::util::intern::intern;
+use std::sync::Arc;
 use std::hash::Hasher;
 use std::hash::Hash;
+use std::cell::RefCell;
+use std::io::BufRead;
+use std::rc::Rc;
 
 pub trait ToColor : Display {
     fn to_color(&self) -> ColoredString;
@@ -20,21 +26,24 @@ pub trait ToColor : Display {
 /// 
 /// Assumes the integer is valid under the following production rule:
 /// - integer ::= digit { \[ underline ] digit }
+/// 
+/// Integers too large to fit saturate at `usize::MAX`.
 fn interpret_integer(s: &str) -> usize {
     let mut chars = s.chars();
-    let mut number = String::from(chars.next().expect("must have a lead-off digit"));
-    while let Some(c) = chars.next() {
-        if c != char_set::UNDERLINE {
-            number.push(c);
-        }
-    }
-    number.parse::<usize>().expect("integer can only contain 0..=9 or underline '_'")
+    let lead = chars.next().expect("must have a lead-off digit");
+    std::iter::once(lead)
+        .chain(chars)
+        .filter(|c| c != &char_set::UNDERLINE)
+        .fold(0usize, |n, c| {
+            let digit = c.to_digit(10).expect("integer can only contain 0..=9 or underline '_'");
+            n.saturating_mul(10).saturating_add(digit as usize)
+        })
 }
 
 #[derive(Debug, Clone, PartialOrd, Ord)]
 pub enum Identifier {
-    Basic(String),
-    Extended(String),
+    Basic(Arc<str>),
+    Extended(Arc<str>),
 }
 
 impl std::cmp::Eq for Identifier {}
@@ -42,12 +51,12 @@ impl std::cmp::Eq for Identifier {}
 impl Identifier {
     /// Creates an empty basic identifier.
     pub fn new() -> Self {
-        Self::Basic(String::new())
+        Self::Basic(intern(""))
     }
 
     /// Creates a new basic identifier for the working library: `work`.
     pub fn new_working() -> Self {
-        Self::Basic(String::from("work"))
+        Self::Basic(intern("work"))
     }
 
     // Returns the reference to the inner `String` struct.
@@ -61,8 +70,8 @@ impl Identifier {
     /// Modifies the ending of the identifier with `ext` and writes as a String
     pub fn into_extension(&self, ext: &str) -> Identifier {
         match self {
-            Self::Basic(s) => Self::Basic(s.clone() + ext),
-            Self::Extended(s) => Self::Extended(s.clone() + ext)
+            Self::Basic(s) => Self::Basic(intern(&(s.to_string() + ext))),
+            Self::Extended(s) => Self::Extended(intern(&(s.to_string() + ext)))
         }
     }
 
@@ -82,11 +91,11 @@ impl Identifier {
     }
 }
 
-// @todo: test
 impl Hash for Identifier {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
-            Self::Basic(id) => { id.to_lowercase().hash(state) },
+            // fold the case one char at a time to avoid allocating a lowercase copy
+            Self::Basic(id) => { id.chars().flat_map(|c| c.to_lowercase()).for_each(|c| c.hash(state)) },
             Self::Extended(id) => { id.hash(state) }
         }
     }
@@ -94,7 +103,7 @@ impl Hash for Identifier {
 
 impl From<&PkgPart> for Identifier {
     fn from(part: &PkgPart) -> Self {
-        Identifier::Basic(part.to_normal().to_string())
+        Identifier::Basic(intern(&part.to_normal().to_string()))
     }
 }
 
@@ -103,6 +112,8 @@ pub enum IdentifierError {
     Empty,
     InvalidFirstChar(char),
     CharsAfterDelimiter(String),
+    CharsAfterIdentifier(String),
+    Invalid(VHDLTokenError),
 }
 
 impl std::error::Error for IdentifierError {}
@@ -113,6 +124,8 @@ impl std::fmt::Display for IdentifierError {
             Self::Empty => write!(f, "empty identifier"),
             Self::CharsAfterDelimiter(s) => write!(f, "characters \'{}\' found following closing extended backslash, ", s),
             Self::InvalidFirstChar(c) => write!(f, "first character must be letter but found \'{}\'", c),
+            Self::CharsAfterIdentifier(s) => write!(f, "characters \'{}\' found following identifier", s),
+            Self::Invalid(e) => write!(f, "{}", e),
         }
     }
 }
@@ -122,31 +135,31 @@ impl FromStr for Identifier {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut chars = TrainCar::new(s.chars());
-        match chars.consume() {
-            // check what type of identifier it is
-            Some(c) => Ok(
-                match c {
-                    '\\' => {
-                        let result = Self::Extended(VHDLToken::consume_literal(&mut chars, &char_set::BACKSLASH).unwrap());
-                        // gather remaining characters
-                        let mut rem = String::new();
-                        while let Some(c) = chars.consume() {
-                            rem.push(c);
-                        }
-                        match rem.is_empty() {
-                            true => result,
-                            false => return Err(Self::Err::CharsAfterDelimiter(rem)),
-                        }
-                    }
-                    _ => {
-                        // verify the first character was a letter
-                        match char_set::is_letter(&c) { 
-                            true => Self::Basic(VHDLToken::consume_value_pattern(&mut chars, Some(c), char_set::is_letter_or_digit).unwrap()),
-                            false => return Err(Self::Err::InvalidFirstChar(c)),
-                        } 
-                    }
-                }),
-            None => Err(Self::Err::Empty)
+        // check what type of identifier it is
+        let result = match chars.consume() {
+            Some('\\') => {
+                let id = VHDLToken::consume_literal(&mut chars, &char_set::BACKSLASH).map_err(Self::Err::Invalid)?;
+                match id.is_empty() {
+                    true => return Err(Self::Err::Empty),
+                    false => Self::Extended(intern(&id)),
+                }
+            }
+            // verify the first character was a letter
+            Some(c) => match char_set::is_letter(&c) {
+                true => Self::Basic(intern(&VHDLToken::consume_value_pattern(&mut chars, Some(c), char_set::is_letter_or_digit).map_err(Self::Err::Invalid)?)),
+                false => return Err(Self::Err::InvalidFirstChar(c)),
+            },
+            None => return Err(Self::Err::Empty),
+        };
+        // gather remaining characters
+        let mut rem = String::new();
+        while let Some(c) = chars.consume() {
+            rem.push(c);
+        }
+        match (rem.is_empty(), &result) {
+            (true, _) => Ok(result),
+            (false, Self::Extended(_)) => Err(Self::Err::CharsAfterDelimiter(rem)),
+            (false, Self::Basic(_)) => Err(Self::Err::CharsAfterIdentifier(rem)),
         }
     }
 }
@@ -185,7 +198,8 @@ pub enum Comment {
 }
 
 impl Comment {
-    fn as_str(&self) -> &str {
+    /// References the text of the comment without its delimiters.
+    pub fn as_str(&self) -> &str {
         match self {
             Self::Single(note) => note.as_ref(),
             Self::Delimited(note) => note.as_ref(),
@@ -240,6 +254,17 @@ impl std::fmt::Display for BitStrLiteral {
     }
 }
 
+impl FromStr for BitStrLiteral {
+    type Err = VHDLTokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match VHDLToken::from_str(s)? {
+            VHDLToken::BitStrLiteral(b) => Ok(b),
+            _ => Err(VHDLTokenError::Any(format!("expecting a bit string literal but found '{}'", s))),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum AbstLiteral {
     Decimal(String),
@@ -255,6 +280,17 @@ impl std::fmt::Display for AbstLiteral {
     }
 }
 
+impl FromStr for AbstLiteral {
+    type Err = VHDLTokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match VHDLToken::from_str(s)? {
+            VHDLToken::AbstLiteral(a) => Ok(a),
+            _ => Err(VHDLTokenError::Any(format!("expecting an abstract literal but found '{}'", s))),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Keyword {
     Abs,            // VHDL-1987 LRM - current 
@@ -789,7 +825,7 @@ impl ToColor for VHDLToken {
             Self::Identifier(i) => i.to_color(),
             Self::AbstLiteral(a) => a.to_color(),
             Self::CharLiteral(c) => c.to_color(),
-            Self::StrLiteral(s) => color(&format!("\"{}\"", s.to_string()), STRINGS),
+            Self::StrLiteral(_) => color(&self.to_string(), STRINGS),
             Self::BitStrLiteral(b) => b.to_color(),
             Self::Keyword(k) => k.to_color(),
             Self::Delimiter(d) => d.to_color(),
@@ -852,7 +888,8 @@ impl Display for VHDLToken {
             Self::Identifier(id) => id.to_string(),
             Self::AbstLiteral(a) => a.to_string(),
             Self::CharLiteral(c) => c.to_string(),
-            Self::StrLiteral(s) => format!("\"{}\"", s),
+            // a double quote within the literal is escaped by doubling it
+            Self::StrLiteral(s) => format!("\"{}\"", s.replace('"', "\"\"")),
             Self::BitStrLiteral(b) => b.to_string(),
             Self::Keyword(kw) => kw.to_string(),
             Self::Delimiter(d) => d.to_string(),
@@ -861,6 +898,26 @@ impl Display for VHDLToken {
     }
 }
 
+impl FromStr for VHDLToken {
+    type Err = VHDLTokenError;
+
+    /// Lexes `s` as exactly one token, the inverse of displaying the token.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut stream = VHDLTokenStream::new(TrainCar::new(s.chars()));
+        // a lone single quote is a tick, otherwise it begins a character literal
+        stream.after_delimiter = s.trim() != "'";
+        let token = match stream.next() {
+            Some(Ok(tk)) if tk.as_ref().is_eof() == false => tk.take(),
+            Some(Err(e)) => return Err(e.into_err()),
+            _ => return Err(VHDLTokenError::Any(String::from("expecting a token but found nothing"))),
+        };
+        match stream.next() {
+            Some(Ok(tk)) if tk.as_ref().is_eof() => Ok(token),
+            _ => Err(VHDLTokenError::Any(format!("expecting a single token but found more after '{}'", token))),
+        }
+    }
+}
+
 impl VHDLToken {
     /// Takes the identifier from the token.
     pub fn take_identifier(self) -> Option<Identifier> {
@@ -914,8 +971,31 @@ impl VHDLToken {
     /// decimal literal, based_literal, and bit_string_literals.
     /// 
     /// Assumes the incoming char `c0` was last char consumed as it a digit `0..=9`.
-    fn consume_numeric(train: &mut TrainCar<impl Iterator<Item=char>>, c0: char) -> Result<VHDLToken, VHDLTokenError> {
+    /// 
+    /// An error is located at the char that broke the literal (or at the start of
+    /// the literal for a base out of range), and the rest of the malformed literal
+    /// is skipped so lexing resumes after it.
+    fn consume_numeric(train: &mut TrainCar<impl Iterator<Item=char>>, c0: char) -> Result<VHDLToken, lexer::TokenError<VHDLTokenError>> {
+        let start = train.locate().clone();
         let mut based_delim: Option<char> = None;
+        match Self::consume_numeric_literal(train, c0, &mut based_delim) {
+            Ok(tk) => Ok(tk),
+            Err(e) => {
+                let loc = match e {
+                    VHDLTokenError::BaseOutOfRange(_) => start,
+                    _ => train.locate().clone(),
+                };
+                Self::skip_numeric(train, based_delim);
+                Err(lexer::TokenError::new(e, loc))
+            }
+        }
+    }
+
+    /// Captures the characters of a numeric literal, stopping at the first char
+    /// that does not fit.
+    /// 
+    /// The delimiter of a based literal is stored in `based_delim` once found.
+    fn consume_numeric_literal(train: &mut TrainCar<impl Iterator<Item=char>>, c0: char, based_delim: &mut Option<char>) -> Result<VHDLToken, VHDLTokenError> {
         let mut number = Self::consume_value_pattern(train, Some(c0), char_set::is_digit)?;
         // check if the next char should be included
         if let Some(mut c) = train.peek() {
@@ -938,60 +1018,28 @@ impl VHDLToken {
                 };
             // * based_literal (can begin with '#' or ':')
             } else if c == &char_set::HASH || c == &char_set::COLON {
+                let delim = *c;
+                *based_delim = Some(delim);
                 // verify 2 <= number <= 16
                 let base = interpret_integer(&number);
                 if base < 2 || base > 16 {
-                    return Err(VHDLTokenError::Any(String::from("based literal must have base of at least 2 and at most 16")))
+                    return Err(VHDLTokenError::BaseOutOfRange(number))
                 }
-                based_delim = Some(*c);
                 number.push(train.consume().unwrap());
                 // gather initial extended digits
-                // select the `eval` fn to evaluate digits
-                let eval = based_integer::as_fn(base);
-                let base_integers = Self::consume_value_pattern(train, None, eval)?;
-
-                number.push_str(&base_integers);
-                // stil expecting another token
-                if let Some(c_next) = train.consume() {
-                    // closing with a '#' or ':'
-                    if c_next == based_delim.unwrap() {
-                        number.push(c_next);
-                    // is there a dot?
-                    } else if c_next == char_set::DOT {
-                        number.push(c_next);
-                        // gather more integers (must exist)
-                        let fraction = Self::consume_value_pattern(train, None, eval)?;
-                        number.push_str(&fraction);
-                        // make sure there is a closing character '#' or ':'
-                        if let Some(c_next_next) = train.consume() {
-                            // did not find the closing character '#' or ':'
-                            if c_next_next != based_delim.unwrap() {
-                                return Err(VHDLTokenError::Any(String::from("expecting closing '#' but found something else")))
-                            }
-                            if fraction.is_empty() {
-                                return Err(VHDLTokenError::Any(String::from("expecting an integer after the dot")))
-                            }
-                            number.push(c_next_next);
-                        // there is no more characters left to consume
-                        } else {
-                            if fraction.is_empty() {
-                                return Err(VHDLTokenError::Any(String::from("expecting an integer after the dot")))
-                            }
-                            return Err(VHDLTokenError::Any(String::from("expecting closing '#'")))
-                        }
-                    // an unknown character
-                    } else {
-                        return Err(VHDLTokenError::Any(String::from("expecting closing '#' but got something else")))
-                    }
-                    // update c if there is another token to grab!
-                    c = if let Some(c_next_next) = train.peek() {
-                        c_next_next
-                    } else {
-                        return Ok(VHDLToken::AbstLiteral(AbstLiteral::Based(number)))
-                    }
-                // there is no more characters to consume
+                number.push_str(&Self::consume_based_integer(train, base)?);
+                // gather more extended digits after a dot (must exist)
+                if train.peek() == Some(&char_set::DOT) {
+                    number.push(train.consume().unwrap());
+                    number.push_str(&Self::consume_based_integer(train, base)?);
+                }
+                // closing with the same '#' or ':'
+                number.push(Self::consume_based_closing(train, delim, base)?);
+                // update c if there is another token to grab!
+                c = if let Some(c_next) = train.peek() {
+                    c_next
                 } else {
-                    return Err(VHDLTokenError::Any(String::from("expecting closing '#'")))
+                    return Ok(VHDLToken::AbstLiteral(AbstLiteral::Based(number)))
                 }
             // * bit string literal
             } else if c != &'e' && c != &'E' && char_set::is_letter(&c) {
@@ -1032,6 +1080,51 @@ impl VHDLToken {
         }
     }
 
+    /// Captures at least one extended digit of `base`.
+    /// - based_integer ::= extended_digit { \[ underline ] extended_digit }
+    fn consume_based_integer(train: &mut TrainCar<impl Iterator<Item=char>>, base: usize) -> Result<String, VHDLTokenError> {
+        let digits = Self::consume_value_pattern(train, None, based_integer::as_fn(base))?;
+        if digits.is_empty() == false {
+            return Ok(digits)
+        }
+        match train.consume() {
+            Some(c) if char_set::is_extended_digit(&c) => Err(VHDLTokenError::DigitOutOfRange(c, base)),
+            Some(c) => Err(VHDLTokenError::Any(format!("expecting a digit but got '{}'", c))),
+            None => Err(VHDLTokenError::Any(String::from("expecting a digit"))),
+        }
+    }
+
+    /// Captures the `delim` closing a based literal of `base`.
+    /// 
+    /// A char that cannot belong to the literal is left to be lexed.
+    fn consume_based_closing(train: &mut TrainCar<impl Iterator<Item=char>>, delim: char, base: usize) -> Result<char, VHDLTokenError> {
+        match train.peek() {
+            Some(c) if c == &delim => Ok(train.consume().unwrap()),
+            Some(c) if char_set::is_extended_digit(c) => Err(VHDLTokenError::DigitOutOfRange(train.consume().unwrap(), base)),
+            Some(c) if c == &char_set::HASH || c == &char_set::COLON || c == &char_set::DOT => {
+                Err(VHDLTokenError::MissingClosingAndGot(delim, train.consume().unwrap()))
+            }
+            _ => Err(VHDLTokenError::MissingClosing(delim)),
+        }
+    }
+
+    /// Skips over the rest of a malformed numeric literal, so it is reported as a
+    /// single error instead of a cascade of them.
+    fn skip_numeric(train: &mut TrainCar<impl Iterator<Item=char>>, based_delim: Option<char>) {
+        while let Some(c) = train.peek() {
+            if c == &char_set::DOUBLE_QUOTE {
+                // the value of a bit string literal with an invalid base specifier
+                train.consume();
+                let _ = Self::consume_literal(train, &char_set::DOUBLE_QUOTE);
+                break;
+            } else if char_set::is_extended_digit(c) || c == &char_set::UNDERLINE || c == &char_set::DOT || c == &char_set::HASH || Some(*c) == based_delim {
+                train.consume();
+            } else {
+                break;
+            }
+        }
+    }
+
     /// Captures VHDL Tokens: keywords, basic identifiers, and regular bit string literals.
     /// 
     /// Assumes the first `letter` char was the last char consumed before the function call.
@@ -1050,7 +1143,7 @@ impl VHDLToken {
                         return Ok(Self::consume_bit_str_literal(train, word)?)
                     }
                 }
-                Ok(VHDLToken::Identifier(Identifier::Basic(word)))
+                Ok(VHDLToken::Identifier(Identifier::Basic(intern(&word))))
             }
         }
     }
@@ -1084,7 +1177,7 @@ impl VHDLToken {
         if id.is_empty() { 
             Err(VHDLTokenError::Any(String::from("extended identifier cannot be empty")))
         } else {
-            Ok(VHDLToken::Identifier(Identifier::Extended(id)))
+            Ok(VHDLToken::Identifier(Identifier::Extended(intern(&id))))
         }
     }
 
@@ -1152,12 +1245,13 @@ impl VHDLToken {
         train.consume(); 
         // consume characters to form the comment
         let mut note = String::new();
-        while let Some(c) = train.consume() {
+        // leave the line break unconsumed so the comment ends on its own line
+        while let Some(c) = train.peek() {
             // cannot be vt, cr (\r), lf (\n)
-            if c == '\u{000B}' || c == '\u{000D}' || c == '\u{000A}' {
+            if c == &'\u{000B}' || c == &'\u{000D}' || c == &'\u{000A}' {
                 break;
             } else {
-                note.push(c);
+                note.push(train.consume().unwrap());
             }
         }
         Ok(VHDLToken::Comment(Comment::Single(note)))
@@ -1173,7 +1267,8 @@ impl VHDLToken {
         if let Some(c) = c0 { delim.push(c); };
         // check the next character in the sequence
         while let Some(c) = train.peek() {
-            match delim.len() {
+            // count chars rather than bytes, since the first char may be any unicode char
+            match delim.chars().count() {
                 0 => match c {
                     // ambiguous characters...read another character (could be a len-2 delimiter)
                     '?' | '<' | '>' | '/' | '=' | '*' | ':' => delim.push(train.consume().unwrap()),
@@ -1181,16 +1276,9 @@ impl VHDLToken {
                     _ => return Self::match_delimiter(&String::from(train.consume().unwrap())),
                 }
                 1 => match delim.chars().nth(0).unwrap() {
-                    '?' => match c {
-                            // move on to next round (could be a len-3 delimiter)
-                            '/' | '<' | '>' => delim.push(train.consume().unwrap()),
-                            _ => return Ok(Self::match_delimiter(&delim).expect("invalid token")),
-                        },
-                    '<' => match c {
-                            // move on to next round (could be a len-3 delimiter)
-                            '=' => delim.push(train.consume().unwrap()),
-                            _ => return Ok(Self::match_delimiter(&delim).expect("invalid token")),
-                        },
+                    // move on to next round (could be a len-3 delimiter)
+                    '?' if c == &'/' || c == &'<' || c == &'>' => delim.push(train.consume().unwrap()),
+                    '<' if c == &'=' => delim.push(train.consume().unwrap()),
                     _ => {
                         // try with 2
                         delim.push(*c);
@@ -1211,9 +1299,9 @@ impl VHDLToken {
                         train.consume();
                         return Ok(op)
                     } else {
-                        // revert back to 2 (guaranteed to exist)
+                        // revert back to 2 ('?/' only begins a delimiter)
                         delim.pop();
-                        return Ok(Self::match_delimiter(&delim).expect("invalid token"))
+                        return Self::match_delimiter(&delim)
                     }
                 }
                 _ => panic!("delimiter matching exceeds 3 characters")
@@ -1233,17 +1321,14 @@ impl VHDLToken {
             if eval(&c) == true {
                 car.push(train.consume().unwrap());
             } else if c == &char_set::UNDERLINE {
-                if car.is_empty() == true { return Err(VHDLTokenError::Any(String::from("expecting a digit before underline"))) }
+                // errors are located at the underline, leaving the following char to be lexed
                 car.push(train.consume().unwrap());
+                if car.len() == 1 { return Err(VHDLTokenError::Any(String::from("expecting a digit before underline"))) }
                 // a digit must proceed the underline
-                if let Some(c_next) = train.consume() {
-                    if eval(&c_next) == false {
-                        return Err(VHDLTokenError::Any(String::from("expecting a digit to follow underline")))
-                    } else {
-                        car.push(c_next);
-                    }
-                } else {
-                    return Err(VHDLTokenError::Any(String::from("expecting a digit")))
+                match train.peek() {
+                    Some(c_next) if eval(c_next) == true => car.push(train.consume().unwrap()),
+                    Some(_) => return Err(VHDLTokenError::Any(String::from("expecting a digit to follow underline"))),
+                    None => return Err(VHDLTokenError::Any(String::from("expecting a digit"))),
                 }
             } else {
                 break;
@@ -1260,14 +1345,10 @@ impl VHDLToken {
         // start with 'E'
         let mut expon = String::from(c0);
         // check for sign
-        let sign = if let Some(c1) = train.consume() {
-            if c1 != char_set::PLUS && c1 != char_set::DASH && char_set::is_digit(&c1) == false {
-                return Err(VHDLTokenError::Any(String::from("expecting +, -, or a digit")))
-            } else {
-                c1
-            }
-        } else {
-            return Err(VHDLTokenError::Any(String::from("expecting +, -, or digit but got nothing")))
+        let sign = match train.peek() {
+            Some(c1) if c1 == &char_set::PLUS || c1 == &char_set::DASH || char_set::is_digit(c1) => train.consume().unwrap(),
+            Some(_) => return Err(VHDLTokenError::Any(String::from("expecting +, -, or a digit"))),
+            None => return Err(VHDLTokenError::Any(String::from("expecting +, -, or digit but got nothing"))),
         };
         // determine if c0 was a digit 
         let c0 = if char_set::is_digit(&sign) == true {
@@ -1532,6 +1613,14 @@ impl VHDLTokenizer {
         Self { tokens: Self::tokenize(s).into_iter().map(|f| VHDLElement(f) ).collect() }
     }
 
+    /// Generates a `VHDLTokenizer` struct from source code `s` where a tab advances
+    /// the column to the next multiple of `tab_width`.
+    /// 
+    /// Editor integrations use this to report columns that match the displayed text.
+    pub fn from_source_code_with_tabs(s: &str, tab_width: usize) -> Self {
+        Self { tokens: Self::tokenize_train(TrainCar::new(s.chars()).tab_width(tab_width)).into_iter().map(VHDLElement).collect() }
+    }
+
     /// Transforms the list of results into a list of tokens, silently skipping over
     /// errors.
     /// 
@@ -1551,6 +1640,19 @@ impl VHDLTokenizer {
         } ).collect()
     }
 
+    /// Performs lexical analysis on the source code `s`, stopping at the first
+    /// error instead of skipping over it.
+    /// 
+    /// The tokens include `Comment`s and end with an `EOF` token.
+    pub fn tokenize_strict(s: &str) -> Result<Vec<lexer::Token<VHDLToken>>, lexer::TokenError<VHDLTokenError>> {
+        VHDLTokenStream::new(TrainCar::new(s.chars())).collect()
+    }
+
+    /// Transforms the list of results into only the errors found while tokenizing.
+    pub fn into_errors(self) -> Vec<lexer::TokenError<VHDLTokenError>> {
+        self.tokens.into_iter().filter_map(|f| f.0.err()).collect()
+    }
+
     /// Transforms the list of results into a list of tokens, silently skipping over
     /// errors.
     pub fn into_tokens_all(self) -> Vec<lexer::Token<VHDLToken>> {
@@ -1618,58 +1720,107 @@ impl Tokenize for VHDLTokenizer {
     type Err = VHDLTokenError;
 
     fn tokenize(s: &str) -> Vec<Result<lexer::Token<Self::TokenType>, lexer::TokenError<Self::Err>>> {
+        Self::tokenize_train(TrainCar::new(s.chars()))
+    }
+}
+
+impl VHDLTokenizer {
+    /// Performs lexical analysis on the characters remaining in the `train`.
+    fn tokenize_train(train: TrainCar<impl Iterator<Item=char>>) -> Vec<Result<lexer::Token<VHDLToken>, lexer::TokenError<VHDLTokenError>>> {
+        VHDLTokenStream::new(train).collect()
+    }
+}
+
+/// Performs lexical analysis one token at a time as the characters are consumed.
+/// 
+/// The stream ends with an `EOF` token.
+pub struct VHDLTokenStream<T> where T: Iterator<Item=char> {
+    train: TrainCar<T>,
+    after_delimiter: bool,
+    done: bool,
+    read_error: Option<Rc<RefCell<Option<std::io::Error>>>>,
+}
+
+impl<R: BufRead> VHDLTokenStream<ReadChars<R>> {
+    /// Creates a stream over the source code read from `reader`, so only the line
+    /// being lexed is held in memory rather than the entire text.
+    /// 
+    /// Check `take_error` once the stream is consumed, since a failed read ends the
+    /// stream early.
+    pub fn from_reader(reader: R) -> Self {
+        let chars = ReadChars::new(reader);
+        let read_error = chars.error_slot();
+        Self { read_error: Some(read_error), ..Self::new(TrainCar::new(chars)) }
+    }
+}
+
+impl<T> VHDLTokenStream<T> where T: Iterator<Item=char> {
+    pub fn new(train: TrainCar<T>) -> Self {
+        Self { train: train, after_delimiter: false, done: false, read_error: None }
+    }
+
+    /// Takes the error that ended the stream while reading, if any.
+    pub fn take_error(&mut self) -> Option<std::io::Error> {
+        self.read_error.as_ref().and_then(|e| e.take())
+    }
+}
+
+impl<T> Iterator for VHDLTokenStream<T> where T: Iterator<Item=char> {
+    type Item = Result<lexer::Token<VHDLToken>, lexer::TokenError<VHDLTokenError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
         use lexer::{Token, TokenError};
 
-        let mut train = TrainCar::new(s.chars());
-        // store results here as we consume the characters
-        let mut tokens: Vec<Result<Token<Self::TokenType>, TokenError<Self::Err>>> = Vec::new();
-        // consume every character (lexical analysis)
+        if self.done == true {
+            return None
+        }
+        let train = &mut self.train;
+        // consume characters until a token is built (lexical analysis)
         while let Some(c) = train.consume() {
             // skip over whitespace
             if char_set::is_separator(&c) { continue; }
             let tk_loc = train.locate().clone();
             // build a token
-            tokens.push(
-            if char_set::is_letter(&c) {
+            let result = if char_set::is_letter(&c) {
                 // collect general identifier
-                match Self::TokenType::consume_word(&mut train, c) {
-                    Ok(tk) => Ok(Token::new(tk, tk_loc)),
+                match VHDLToken::consume_word(train, c) {
+                    Ok(tk) => Ok(Token::new(tk, tk_loc).with_end(train.locate_end())),
                     Err(e) => Err(TokenError::new(e, train.locate().clone()))
                 }
             } else if c == char_set::BACKSLASH {
                 // collect extended identifier
-                match Self::TokenType::consume_extended_identifier(&mut train) {
-                    Ok(tk) => Ok(Token::new(tk, tk_loc)),
+                match VHDLToken::consume_extended_identifier(train) {
+                    Ok(tk) => Ok(Token::new(tk, tk_loc).with_end(train.locate_end())),
                     Err(e) => Err(TokenError::new(e, train.locate().clone()))
                 }
             } else if c == char_set::DOUBLE_QUOTE {
                 // collect string literal
-                match Self::TokenType::consume_str_lit(&mut train) {
-                    Ok(tk) => Ok(Token::new(tk, tk_loc)),
+                match VHDLToken::consume_str_lit(train) {
+                    Ok(tk) => Ok(Token::new(tk, tk_loc).with_end(train.locate_end())),
                     Err(e) => Err(TokenError::new(e, train.locate().clone()))
                 }
-            } else if c == char_set::SINGLE_QUOTE && tokens.last().is_some() && tokens.last().unwrap().as_ref().is_ok() && tokens.last().unwrap().as_ref().unwrap().as_ref().is_delimiter() {
+            } else if c == char_set::SINGLE_QUOTE && self.after_delimiter == true {
                 // collect character literal
-                match Self::TokenType::consume_char_lit(&mut train) {
-                    Ok(tk) => Ok(Token::new(tk, tk_loc)),
+                match VHDLToken::consume_char_lit(train) {
+                    Ok(tk) => Ok(Token::new(tk, tk_loc).with_end(train.locate_end())),
                     Err(e) => Err(TokenError::new(e, train.locate().clone()))
                 }
             } else if char_set::is_digit(&c) {
                 // collect decimal literal (or bit string literal or based literal)
-                match Self::TokenType::consume_numeric(&mut train, c) {
-                    Ok(tk) => Ok(Token::new(tk, tk_loc)),
-                    Err(e) => Err(TokenError::new(e, train.locate().clone()))
+                match VHDLToken::consume_numeric(train, c) {
+                    Ok(tk) => Ok(Token::new(tk, tk_loc).with_end(train.locate_end())),
+                    Err(e) => Err(e)
                 }
             } else if c == char_set::DASH && train.peek().is_some() && train.peek().unwrap() == &char_set::DASH {    
                 // collect a single-line comment           
-                match Self::TokenType::consume_comment(&mut train) {
-                    Ok(tk) => Ok(Token::new(tk, tk_loc)),
+                match VHDLToken::consume_comment(train) {
+                    Ok(tk) => Ok(Token::new(tk, tk_loc).with_end(train.locate_end())),
                     Err(e) => Err(TokenError::new(e, train.locate().clone()))
                 }
             } else if c == char_set::FWDSLASH && train.peek().is_some() && train.peek().unwrap() == &char_set::STAR {
                 // collect delimited (multi-line) comment
-                match Self::TokenType::consume_delim_comment(&mut train) {
-                    Ok(tk) => Ok(Token::new(tk, tk_loc)),
+                match VHDLToken::consume_delim_comment(train) {
+                    Ok(tk) => Ok(Token::new(tk, tk_loc).with_end(train.locate_end())),
                     Err(e) => {
                         let mut tk_loc = train.locate().clone();
                         tk_loc.next_col();// +1 col for correct alignment
@@ -1678,26 +1829,35 @@ impl Tokenize for VHDLTokenizer {
                 }
             } else {
                 // collect delimiter
-                match Self::TokenType::collect_delimiter(&mut train, Some(c)) {
-                    Ok(tk) => Ok(Token::new(tk, tk_loc)),
+                match VHDLToken::collect_delimiter(train, Some(c)) {
+                    Ok(tk) => Ok(Token::new(tk, tk_loc).with_end(train.locate_end())),
                     Err(e) => Err(TokenError::new(e, train.locate().clone()))
                 }
-            });
+            };
+            // a single quote after a delimiter begins a character literal
+            self.after_delimiter = match &result {
+                Ok(tk) => tk.as_ref().is_delimiter(),
+                Err(_) => false,
+            };
+            return Some(result)
         }
         // push final EOF token
+        self.done = true;
         let mut tk_loc = train.locate().clone();
         tk_loc.next_col();
-        tokens.push(Ok(Token::new(VHDLToken::EOF,  tk_loc)));
-        tokens
+        Some(Ok(Token::new(VHDLToken::EOF,  tk_loc)))
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum VHDLTokenError {
     Any(String),
     Invalid(String),
     MissingAndEmpty(char),
+    MissingClosing(char),
     MissingClosingAndGot(char, char),
+    BaseOutOfRange(String),
+    DigitOutOfRange(char, usize),
 }
 
 impl Display for VHDLTokenError {
@@ -1705,7 +1865,11 @@ impl Display for VHDLTokenError {
         write!(f, "{}", match self {
             Self::Any(s) => s.to_string(),
             Self::Invalid(c) => format!("invalid character '{}' ", c),
-            _ => todo!("write error message!")
+            Self::MissingAndEmpty(c) => format!("missing closing '{}' and the literal is empty", c),
+            Self::MissingClosing(c) => format!("expecting closing '{}'", c),
+            Self::MissingClosingAndGot(c0, c1) => format!("expecting closing '{}' but got '{}'", c0, c1),
+            Self::BaseOutOfRange(b) => format!("base '{}' of based literal must be at least 2 and at most 16", b),
+            Self::DigitOutOfRange(c, b) => format!("digit '{}' is out of range for base {}", c, b),
         })
     }
 }
@@ -1715,17 +1879,49 @@ mod test {
     use super::*;
     use crate::core::lexer::*;
 
+    #[test]
+    fn token_spans() {
+        let tokens = VHDLTokenizer::from_source_code("entity \\top\\ is\n  constant c : string := \"é\";").into_tokens();
+        assert_eq!(tokens[0].span(), Span::new(Position::place(1, 1), Position::place(1, 7)));
+        assert_eq!(tokens[0].span().byte_range(), 0..6);
+        assert_eq!(tokens[1].span().byte_range(), 7..12);
+        // the string literal's range counts bytes while its columns count characters
+        let lit = &tokens[tokens.len() - 3];
+        assert_eq!(lit.locate(), &Position::place(2, 26));
+        assert_eq!(lit.locate_end(), &Position::place(2, 29));
+        assert_eq!(lit.span().byte_range(), 41..45);
+    }
+
     #[test]
     fn iden_from_str() {
         let iden = "top_level";
-        assert_eq!(Identifier::from_str(&iden).unwrap(), Identifier::Basic("top_level".to_owned()));
+        assert_eq!(Identifier::from_str(&iden).unwrap(), Identifier::Basic("top_level".into()));
 
         let iden = "\\Top_LEVEL\\";
-        assert_eq!(Identifier::from_str(&iden).unwrap(), Identifier::Extended("Top_LEVEL".to_owned()));
+        assert_eq!(Identifier::from_str(&iden).unwrap(), Identifier::Extended("Top_LEVEL".into()));
 
         // extra characters after closing
         let iden = "\\Top_\\LEVEL\\";
         assert_eq!(Identifier::from_str(&iden).is_err(), true);
+
+        // extra characters after a basic identifier
+        assert_eq!(Identifier::from_str("top level"), Err(IdentifierError::CharsAfterIdentifier(String::from(" level"))));
+        // missing closing backslash
+        assert_eq!(Identifier::from_str("\\top").is_err(), true);
+        assert_eq!(Identifier::from_str("top__level").is_err(), true);
+        assert_eq!(Identifier::from_str("\\\\"), Err(IdentifierError::Empty));
+    }
+
+    #[test]
+    fn token_from_str() {
+        assert_eq!(VHDLToken::from_str("'"), Ok(VHDLToken::Delimiter(Delimiter::SingleQuote)));
+        assert_eq!(VHDLToken::from_str("'''"), Ok(VHDLToken::CharLiteral(Character(String::from("'")))));
+        assert_eq!(VHDLToken::from_str("\"say \"\"hi\"\"\""), Ok(VHDLToken::StrLiteral(String::from("say \"hi\""))));
+        assert_eq!(VHDLToken::from_str("16#FF#").unwrap().to_string(), "16#FF#");
+        assert_eq!(VHDLToken::from_str("").is_err(), true);
+        assert_eq!(VHDLToken::from_str("a b").is_err(), true);
+        assert_eq!(AbstLiteral::from_str("x\"1F\"").is_err(), true);
+        assert_eq!(BitStrLiteral::from_str("8ux\"1F\"").unwrap().to_string(), "8ux\"1F\"");
     }
 
     #[test]
@@ -1763,9 +1959,9 @@ foo <= std_logic_vector'('a','b','c');";
             .map(|f| { f.unwrap().take() })
             .collect();
         assert_eq!(tokens, vec![
-            VHDLToken::Identifier(Identifier::Basic("foo".to_owned())),
+            VHDLToken::Identifier(Identifier::Basic("foo".into())),
             VHDLToken::Delimiter(Delimiter::SigAssign),
-            VHDLToken::Identifier(Identifier::Basic("std_logic_vector".to_owned())),
+            VHDLToken::Identifier(Identifier::Basic("std_logic_vector".into())),
             VHDLToken::Delimiter(Delimiter::SingleQuote),
             VHDLToken::Delimiter(Delimiter::ParenL),
             VHDLToken::CharLiteral(Character("a".to_owned())),
@@ -1786,9 +1982,9 @@ foo <= std_logic_vector'('a','b','c');";
             .collect();
         assert_eq!(tokens, vec![
             VHDLToken::Delimiter(Delimiter::ParenL),
-            VHDLToken::Identifier(Identifier::Basic("clk".to_owned())),
+            VHDLToken::Identifier(Identifier::Basic("clk".into())),
             VHDLToken::Delimiter(Delimiter::SingleQuote),
-            VHDLToken::Identifier(Identifier::Basic("event".to_owned())),
+            VHDLToken::Identifier(Identifier::Basic("event".into())),
             VHDLToken::Delimiter(Delimiter::Eq),
             VHDLToken::CharLiteral(Character("1".to_owned())),
             VHDLToken::Delimiter(Delimiter::ParenR),
@@ -1891,52 +2087,92 @@ foo <= std_logic_vector'('a','b','c');";
         assert_eq!(VHDLToken::consume_numeric(&mut tc, c0).is_err(), true);
     }
 
-    #[test] 
-    fn based_literal_base_out_of_range() {
-        let contents = "1#0123456789AaBbCcDdEeFfGg#";
-        let mut tc = TrainCar::new(contents.chars());
+    /// Lexes the numeric literal `s`, returning its error and column.
+    fn numeric_error(s: &str) -> (VHDLTokenError, usize) {
+        let mut tc = TrainCar::new(s.chars());
         let c0 = tc.consume().unwrap();
-        assert_eq!(VHDLToken::consume_numeric(&mut tc, c0).is_err(), true);
-        assert_eq!(tc.peekable().clone().collect::<String>(), "#0123456789AaBbCcDdEeFfGg#");
+        let err = VHDLToken::consume_numeric(&mut tc, c0).unwrap_err();
+        // the rest of the malformed literal is skipped
+        assert_eq!(tc.peekable().clone().collect::<String>(), "");
+        (err.as_err().clone(), err.locate().col())
+    }
 
-        let contents = "17#0123456789AaBbCcDdEeFfGg#";
-        let mut tc = TrainCar::new(contents.chars());
-        let c0 = tc.consume().unwrap();
-        assert_eq!(VHDLToken::consume_numeric(&mut tc, c0).is_err(), true);
-        assert_eq!(tc.peekable().clone().collect::<String>(), "#0123456789AaBbCcDdEeFfGg#");
+    #[test] 
+    fn based_literal_base_out_of_range() {
+        assert_eq!(numeric_error("1#0123456789AaBbCcDdEeFfGg#"), (VHDLTokenError::BaseOutOfRange(String::from("1")), 1));
+        assert_eq!(numeric_error("17#0123456789AaBbCcDdEeFfGg#"), (VHDLTokenError::BaseOutOfRange(String::from("17")), 1));
+        // a base too large to fit in an integer is still out of range
+        assert_eq!(numeric_error("99999999999999999999#9#"), (VHDLTokenError::BaseOutOfRange(String::from("99999999999999999999")), 1));
     }
 
     #[test]
-    fn based_literal_digit_out_of_range() {
-        let contents = "2#0123456789AaBbCcDdEeFfGg#";
-        let mut tc = TrainCar::new(contents.chars());
-        let c0 = tc.consume().unwrap();
-        assert_eq!(VHDLToken::consume_numeric(&mut tc, c0).is_err(), true);
-        assert_eq!(tc.peekable().clone().collect::<String>(), "3456789AaBbCcDdEeFfGg#");
-
-        let contents = "9#0123456789AaBbCcDdEeFfGg#";
-        let mut tc = TrainCar::new(contents.chars());
-        let c0 = tc.consume().unwrap();
-        assert_eq!(VHDLToken::consume_numeric(&mut tc, c0).is_err(), true);
-        assert_eq!(tc.peekable().clone().collect::<String>(), "AaBbCcDdEeFfGg#");
-
-        let contents = "1_0#0123456789AaBbCcDdEeFfGg#";
-        let mut tc = TrainCar::new(contents.chars());
-        let c0 = tc.consume().unwrap();
-        assert_eq!(VHDLToken::consume_numeric(&mut tc, c0).is_err(), true);
-        assert_eq!(tc.peekable().clone().collect::<String>(), "aBbCcDdEeFfGg#");
+    fn numeric_literals_from_lrm() {
+        let decimals = [
+            "12", "0", "1E6", "123_456",
+            "12.0", "0.0", "0.456", "3.14159_26",
+            "1.34E-12", "1.0E+6", "6.023E+24",
+        ];
+        for s in decimals {
+            assert_eq!(VHDLTokenizer::tokenize_strict(s).unwrap()[0].as_ref(), &VHDLToken::AbstLiteral(AbstLiteral::Decimal(s.to_owned())));
+        }
+        let based = [
+            "2#1111_1111#", "16#FF#", "016#0FF#",
+            "16#E#E1", "2#1110_0000#",
+            "16#F.FF#E+2", "2#1.1111_1111_111#E11",
+            "16:FF:", "2:1.1:e-2",
+        ];
+        for s in based {
+            assert_eq!(VHDLTokenizer::tokenize_strict(s).unwrap()[0].as_ref(), &VHDLToken::AbstLiteral(AbstLiteral::Based(s.to_owned())));
+        }
+        let bit_strings = ["12UB\"X1\"", "12SB\"X1\"", "12UX\"F-\"", "12SX\"F-\"", "8O\"7_7\""];
+        for s in bit_strings {
+            assert_eq!(VHDLTokenizer::tokenize_strict(s).unwrap()[0].as_ref(), &VHDLToken::BitStrLiteral(BitStrLiteral(s.to_owned())));
+        }
+    }
 
-        let contents = "11#0123456789AaBbCcDdEeFfGg#";
-        let mut tc = TrainCar::new(contents.chars());
-        let c0 = tc.consume().unwrap();
-        assert_eq!(VHDLToken::consume_numeric(&mut tc, c0).is_err(), true);
-        assert_eq!(tc.peekable().clone().collect::<String>(), "bCcDdEeFfGg#");
+    #[test]
+    fn numeric_literal_diagnostics() {
+        let cases = [
+            ("16#FF:", "expecting closing '#' but got ':'", 6),
+            ("16:FF#", "expecting closing ':' but got '#'", 6),
+            ("16#FF", "expecting closing '#'", 5),
+            ("16#FF.F", "expecting closing '#'", 7),
+            ("2#102#", "digit '2' is out of range for base 2", 5),
+            ("8#7.8#", "digit '8' is out of range for base 8", 5),
+            ("17#1#", "base '17' of based literal must be at least 2 and at most 16", 1),
+            ("0:1:", "base '0' of based literal must be at least 2 and at most 16", 1),
+            ("16##", "expecting a digit but got '#'", 4),
+            ("16#.8#", "expecting a digit but got '.'", 4),
+            ("8#7.#", "expecting a digit but got '#'", 5),
+            ("1_", "expecting a digit to follow underline", 2),
+            ("1__0", "expecting a digit to follow underline", 2),
+            ("3.14_", "expecting a digit to follow underline", 5),
+            ("16#F_F_#", "expecting a digit to follow underline", 7),
+            ("16#_FF#", "expecting a digit before underline", 4),
+            ("1.", "cannot have trailing decimal point", 2),
+            ("1.e3", "cannot have trailing decimal point", 2),
+            ("1.0e", "expecting +, -, or a digit", 4),
+            ("1.0e+", "expecting an integer exponent value but got nothing", 5),
+            ("2#1#e", "expecting +, -, or a digit", 5),
+        ];
+        for (s, msg, col) in cases {
+            let text = format!("{};", s);
+            let results = VHDLTokenizer::tokenize_train(TrainCar::new(text.chars()));
+            // a malformed literal is reported once and lexing resumes after it
+            assert_eq!(results.len(), 3, "{}", s);
+            let err = results[0].as_ref().unwrap_err();
+            assert_eq!((err.as_err().to_string(), err.locate().col()), (msg.to_owned(), col), "{}", s);
+            assert_eq!(results[1].as_ref().unwrap().as_ref(), &VHDLToken::Delimiter(Delimiter::Terminator), "{}", s);
+        }
+    }
 
-        let contents = "16#0123456789AaBbCcDdEeFfGg#";
-        let mut tc = TrainCar::new(contents.chars());
-        let c0 = tc.consume().unwrap();
-        assert_eq!(VHDLToken::consume_numeric(&mut tc, c0).is_err(), true);
-        assert_eq!(tc.peekable().clone().collect::<String>(), "g#");
+    #[test]
+    fn based_literal_digit_out_of_range() {
+        assert_eq!(numeric_error("2#0123456789AaBbCcDdEeFfGg#"), (VHDLTokenError::DigitOutOfRange('2', 2), 5));
+        assert_eq!(numeric_error("9#0123456789AaBbCcDdEeFfGg#"), (VHDLTokenError::DigitOutOfRange('9', 9), 12));
+        assert_eq!(numeric_error("1_0#0123456789AaBbCcDdEeFfGg#"), (VHDLTokenError::DigitOutOfRange('A', 10), 15));
+        assert_eq!(numeric_error("11#0123456789AaBbCcDdEeFfGg#"), (VHDLTokenError::DigitOutOfRange('B', 11), 16));
+        assert_eq!(numeric_error("16#0123456789AaBbCcDdEeFfGg#"), (VHDLTokenError::DigitOutOfRange('G', 16), 26));
     }
 
     #[test]
@@ -1955,8 +2191,9 @@ entity fa is end entity;";
         let mut tc = TrainCar::new(contents.chars());
         tc.consume(); // already determined first dash
         assert_eq!(VHDLToken::consume_comment(&mut tc).unwrap(), VHDLToken::Comment(Comment::Single("here is a vhdl comment".to_owned())));
-        assert_eq!(tc.peekable().clone().collect::<String>(), "entity fa is end entity;");
-        assert_eq!(tc.locate(), &Position::place(2, 0));
+        // the line break is left for the tokenizer to skip
+        assert_eq!(tc.peekable().clone().collect::<String>(), "\nentity fa is end entity;");
+        assert_eq!(tc.locate(), &Position::place(1, 24));
     }
 
     #[test]
@@ -2087,14 +2324,14 @@ delimited-line comment. Look at all the space! ".to_owned())));
         let words = "std_logic_1164.all;";
         let mut tc = TrainCar::new(words.chars());
         let c0 = tc.consume().unwrap();
-        assert_eq!(VHDLToken::consume_word(&mut tc, c0).unwrap(), VHDLToken::Identifier(Identifier::Basic("std_logic_1164".to_owned())));
+        assert_eq!(VHDLToken::consume_word(&mut tc, c0).unwrap(), VHDLToken::Identifier(Identifier::Basic("std_logic_1164".into())));
         assert_eq!(tc.peekable().clone().collect::<String>(), ".all;");
         assert_eq!(tc.locate(), &Position::place(1, 14));
 
         let words = "ready_OUT<=";
         let mut tc = TrainCar::new(words.chars());
         let c0 = tc.consume().unwrap();
-        assert_eq!(VHDLToken::consume_word(&mut tc, c0).unwrap(), VHDLToken::Identifier(Identifier::Basic("ready_OUT".to_owned())));
+        assert_eq!(VHDLToken::consume_word(&mut tc, c0).unwrap(), VHDLToken::Identifier(Identifier::Basic("ready_OUT".into())));
         assert_eq!(tc.peekable().clone().collect::<String>(), "<=");
         assert_eq!(tc.locate(), &Position::place(1, 9));
     }
@@ -2165,7 +2402,7 @@ entity fa is end entity;";
             .collect();
         assert_eq!(tokens, vec![
             VHDLToken::Keyword(Keyword::Entity),
-            VHDLToken::Identifier(Identifier::Basic("fa".to_owned())),
+            VHDLToken::Identifier(Identifier::Basic("fa".into())),
             VHDLToken::Keyword(Keyword::Is),
             VHDLToken::Keyword(Keyword::End),
             VHDLToken::Keyword(Keyword::Entity),
@@ -2205,9 +2442,9 @@ signal magic_num : std_logic := '1';";
         let tokens: Vec<Token<VHDLToken>> = VHDLTokenizer::tokenize(s).into_iter().map(|f| f.unwrap()).collect();
         assert_eq!(tokens, vec![
             Token::new(VHDLToken::Keyword(Keyword::Signal), Position::place(1, 1)),
-            Token::new(VHDLToken::Identifier(Identifier::Basic("magic_num".to_owned())), Position::place(1, 8)),
+            Token::new(VHDLToken::Identifier(Identifier::Basic("magic_num".into())), Position::place(1, 8)),
             Token::new(VHDLToken::Delimiter(Delimiter::Colon), Position::place(1, 18)),
-            Token::new(VHDLToken::Identifier(Identifier::Basic("std_logic".to_owned())), Position::place(1, 20)),
+            Token::new(VHDLToken::Identifier(Identifier::Basic("std_logic".into())), Position::place(1, 20)),
             Token::new(VHDLToken::Delimiter(Delimiter::VarAssign), Position::place(1, 30)),
             Token::new(VHDLToken::CharLiteral(Character("1".to_owned())), Position::place(1, 33)),
             Token::new(VHDLToken::Delimiter(Delimiter::Terminator), Position::place(1, 36)),
@@ -2234,6 +2471,19 @@ entity fa is end entity;";
         ]);  
     }
 
+    #[test]
+    fn tokenize_with_tabs() {
+        let s = "\tsignal\tÿ : bit;";
+        let tokens = VHDLTokenizer::from_source_code_with_tabs(s, 4).into_tokens();
+        assert_eq!(tokens[0].locate(), &Position::place(1, 5));
+        assert_eq!(tokens[0].byte_offset(), 1);
+        assert_eq!(tokens[1].locate(), &Position::place(1, 13));
+        assert_eq!(tokens[1].byte_offset(), 8);
+        // the identifier's 2-byte letter shifts the byte offset but not the column
+        assert_eq!(tokens[2].locate(), &Position::place(1, 15));
+        assert_eq!(tokens[2].byte_offset(), 11);
+    }
+
     #[test]
     fn lex_delimiter_single() {
         let contents = "&";
@@ -2268,6 +2518,12 @@ entity fa is end entity;";
         assert_eq!(VHDLToken::collect_delimiter(&mut tc, None).is_err(), true);
         assert_eq!(tc.peekable().clone().collect::<String>(), "a");
         assert_eq!(tc.locate(), &Position::place(1, 1));
+
+        let contents = "\u{FFFD}\u{FFFD}";
+        let mut tc = TrainCar::new(contents.chars());
+        let c0 = tc.consume();
+        assert_eq!(VHDLToken::collect_delimiter(&mut tc, c0).is_err(), true);
+        assert_eq!(tc.peekable().clone().collect::<String>(), "\u{FFFD}");
     }
 
     #[test]
@@ -2283,6 +2539,21 @@ entity fa is end entity;";
         assert_eq!(VHDLToken::collect_delimiter(&mut tc, None), Ok(VHDLToken::Delimiter(Delimiter::DoubleStar)));
         assert_eq!(tc.peekable().clone().collect::<String>(), "WIDTH");
         assert_eq!(tc.locate(), &Position::place(1, 2));
+
+        let contents = "?= b";
+        let mut tc = TrainCar::new(contents.chars());
+        assert_eq!(VHDLToken::collect_delimiter(&mut tc, None), Ok(VHDLToken::Delimiter(Delimiter::MatchEQ)));
+        assert_eq!(tc.peekable().clone().collect::<String>(), " b");
+
+        let contents = "<<signal";
+        let mut tc = TrainCar::new(contents.chars());
+        assert_eq!(VHDLToken::collect_delimiter(&mut tc, None), Ok(VHDLToken::Delimiter(Delimiter::DoubleLT)));
+        assert_eq!(tc.peekable().clone().collect::<String>(), "signal");
+
+        let contents = "<>)";
+        let mut tc = TrainCar::new(contents.chars());
+        assert_eq!(VHDLToken::collect_delimiter(&mut tc, None), Ok(VHDLToken::Delimiter(Delimiter::Box)));
+        assert_eq!(tc.peekable().clone().collect::<String>(), ")");
     }
 
     #[test]
@@ -2298,6 +2569,12 @@ entity fa is end entity;";
         assert_eq!(VHDLToken::collect_delimiter(&mut tc, None), Ok(VHDLToken::Delimiter(Delimiter::MatchNE)));
         assert_eq!(tc.peekable().clone().collect::<String>(), " MAGIC_NUM");
         assert_eq!(tc.locate(), &Position::place(1, 3));
+
+        // '?/' only begins a delimiter
+        let contents = "?/*";
+        let mut tc = TrainCar::new(contents.chars());
+        assert_eq!(VHDLToken::collect_delimiter(&mut tc, None), Err(VHDLTokenError::Invalid(String::from("?/"))));
+        assert_eq!(tc.peekable().clone().collect::<String>(), "*");
     }
 
     #[test]
@@ -2353,31 +2630,44 @@ entity fa is end entity;";
     
     #[test]
     fn identifier_equality_and_len() {
-        let id0 = Identifier::Basic("fa".to_owned());
-        let id1 = Identifier::Basic("Fa".to_owned());
+        let id0 = Identifier::Basic("fa".into());
+        let id1 = Identifier::Basic("Fa".into());
         assert_eq!(id1.len(), 2);
         assert_eq!(id0, id1);
 
-        let id0 = Identifier::Basic("fa".to_owned());
-        let id1 = Identifier::Basic("Full_adder".to_owned());
+        let id0 = Identifier::Basic("fa".into());
+        let id1 = Identifier::Basic("Full_adder".into());
         assert_ne!(id0, id1);
 
-        let id0 = Identifier::Basic("VHDL".to_owned());    // written as: VHDL
-        let id1 = Identifier::Extended("VHDL".to_owned()); // written as: \VHDL\
+        let id0 = Identifier::Basic("VHDL".into());    // written as: VHDL
+        let id1 = Identifier::Extended("VHDL".into()); // written as: \VHDL\
         assert_ne!(id0, id1);
 
-        let id0 = Identifier::Extended("vhdl".to_owned()); // written as: \vhdl\
-        let id1 = Identifier::Extended("VHDL".to_owned()); // written as: \VHDL\
+        let id0 = Identifier::Extended("vhdl".into()); // written as: \vhdl\
+        let id1 = Identifier::Extended("VHDL".into()); // written as: \VHDL\
         assert_ne!(id0, id1);
         assert_eq!(id1.len(), 6);
 
-        let id0 = Identifier::Extended("I\\D".to_owned()); // written as: \I\\D\
+        let id0 = Identifier::Extended("I\\D".into()); // written as: \I\\D\
         assert_eq!(id0.len(), 6);
         
         let id0 = Identifier::from_str("\\I\\\\DEN\\").unwrap(); // written as: \I\\D\
         assert_eq!(id0.len(), 8);
     }
 
+    #[test]
+    fn identifier_hash_folds_case() {
+        let mut set = std::collections::HashSet::new();
+        set.insert(Identifier::from_str("Full_Adder").unwrap());
+        assert!(set.contains(&Identifier::from_str("FULL_adder").unwrap()));
+        assert!(!set.contains(&Identifier::from_str("\\FULL_adder\\").unwrap()));
+        // identifiers read from source code share their spelling's allocation
+        let (Identifier::Basic(a), Identifier::Basic(b)) = (Identifier::from_str("fa").unwrap(), Identifier::from_str("fa").unwrap()) else {
+            panic!("expecting basic identifiers")
+        };
+        assert!(Arc::ptr_eq(&a, &b));
+    }
+
     #[test]
     fn comment_ending_pos() {
         let comment = Comment::Delimited("gators".to_string());
@@ -2389,6 +2679,29 @@ entity fa is end entity;";
         assert_eq!(comment.ending_position(), Position::place(2, 2));
     }
 
+    #[test]
+    fn tokenize_strict() {
+        let tokens = VHDLTokenizer::tokenize_strict("a <= b; -- note").unwrap();
+        assert_eq!(tokens.len(), 6);
+        assert_eq!(tokens.last().unwrap().as_ref(), &VHDLToken::EOF);
+        // stops at the first error
+        let err = VHDLTokenizer::tokenize_strict("a <= 2.; b ?< c; \\\\").unwrap_err();
+        assert_eq!(err.as_err(), &VHDLTokenError::Any(String::from("cannot have trailing decimal point")));
+        assert_eq!(err.locate(), &Position::place(1, 7));
+    }
+
+    #[test]
+    fn stream_from_reader() {
+        let s = "\
+package mem_init is
+    constant ROM : rom_t := (x\"00\", x\"ff\", 'a'); -- generated
+end package;
+";
+        let streamed: Vec<_> = VHDLTokenStream::from_reader(s.as_bytes()).collect();
+        assert_eq!(streamed, VHDLTokenizer::tokenize(s));
+        assert_eq!(streamed.last().unwrap().as_ref().unwrap().as_ref(), &VHDLToken::EOF);
+    }
+
     #[test]
     #[ignore]
     fn playground_code() {
@@ -2422,4 +2735,119 @@ end architecture rtl; /* long comment */";
         println!("{:?}", vhdl);
         panic!("manually inspect token list")
     }
+
+    mod roundtrip {
+        use super::*;
+        use proptest::prelude::*;
+        use proptest::sample::select;
+
+        const KEYWORDS: [&str; 12] = [
+            "entity", "architecture", "of", "is", "begin", "end", "signal",
+            "port", "map", "process", "generic", "downto",
+        ];
+
+        const DELIMITERS: [&str; 39] = [
+            "&", "'", "(", ")", "*", "+", ",", "-", ".", "/", ":", ";", "<", "=", ">", "`",
+            "!", "[", "]", "?", "@", "=>", "**", ":=", "/=", ">=", "<=", "<>", "<=>", "??",
+            "?=", "?/=", "?<", "?<=", "?>", "?>=", "<<", ">>", "|",
+        ];
+
+        fn identifier() -> impl Strategy<Value = Identifier> {
+            prop_oneof![
+                "[a-zA-ZÀ-ÖØ-Þß-öø-ÿ](_?[a-zA-Z0-9À-ÖØ-Þß-öø-ÿ]){0,10}"
+                    .prop_filter("reserved word", |s| Keyword::match_keyword(s).is_none())
+                    .prop_map(|s| Identifier::Basic(s.into())),
+                "[ -~¡-ÿ]{1,10}".prop_map(|s| Identifier::Extended(s.into())),
+            ]
+        }
+
+        /// Writes 1 to 5 digits of the `base`.
+        fn digits(base: u32) -> impl Strategy<Value = String> {
+            proptest::collection::vec(0..base, 1..6)
+                .prop_map(|d| d.into_iter().map(|n| std::char::from_digit(n, 16).unwrap()).collect())
+        }
+
+        fn abst_literal() -> impl Strategy<Value = AbstLiteral> {
+            prop_oneof![
+                "[0-9](_?[0-9]){0,5}(\\.[0-9](_?[0-9]){0,3})?([eE][+-]?[0-9]{1,2})?".prop_map(AbstLiteral::Decimal),
+                (2u32..=16).prop_flat_map(|base| (Just(base), select(&['#', ':'][..]), digits(base), proptest::option::of(digits(base)), "([eE][+-]?[0-9]{1,2})?"))
+                    .prop_map(|(base, delim, int, frac, exp)| AbstLiteral::Based(match frac {
+                        Some(frac) => format!("{1}{0}{2}.{3}{0}{4}", delim, base, int, frac, exp),
+                        None => format!("{1}{0}{2}{0}{3}", delim, base, int, exp),
+                    })),
+            ]
+        }
+
+        fn bit_str_literal() -> impl Strategy<Value = BitStrLiteral> {
+            "([0-9]{1,2})?(b|O|x|UB|uo|Ux|sB|so|SX|d)\"[ !#-~¡-ÿ]{0,8}\"".prop_map(BitStrLiteral)
+        }
+
+        fn token() -> impl Strategy<Value = VHDLToken> {
+            prop_oneof![
+                "[ \t!-~¡-ÿ]{0,12}".prop_map(|s| VHDLToken::Comment(Comment::Single(s))),
+                "([ -~¡-ÿ]|\n){0,12}"
+                    .prop_filter("closing delimiter", |s| s.contains("*/") == false)
+                    .prop_map(|s| VHDLToken::Comment(Comment::Delimited(s))),
+                identifier().prop_map(VHDLToken::Identifier),
+                abst_literal().prop_map(VHDLToken::AbstLiteral),
+                "[ -~¡-ÿ]".prop_map(|s| VHDLToken::CharLiteral(Character(s))),
+                "[ -~¡-ÿ]{0,10}".prop_map(VHDLToken::StrLiteral),
+                bit_str_literal().prop_map(VHDLToken::BitStrLiteral),
+                select(&KEYWORDS[..]).prop_map(|s| VHDLToken::Keyword(Keyword::match_keyword(s).unwrap())),
+                select(&DELIMITERS[..]).prop_map(|s| VHDLToken::Delimiter(Delimiter::transform(s).unwrap())),
+            ]
+        }
+
+        /// Writes the `tokens` separated by whitespace.
+        fn render(tokens: &[VHDLToken]) -> String {
+            tokens.iter().map(|t| match t {
+                // a single-line comment runs until the end of the line
+                VHDLToken::Comment(Comment::Single(_)) => format!("{}\n", t),
+                _ => format!("{} ", t),
+            }).collect()
+        }
+
+        proptest! {
+            #[test]
+            fn identifier_display_parses(id in identifier()) {
+                prop_assert_eq!(Identifier::from_str(&id.to_string()), Ok(id));
+            }
+
+            #[test]
+            fn abst_literal_display_parses(lit in abst_literal()) {
+                prop_assert_eq!(AbstLiteral::from_str(&lit.to_string()), Ok(lit));
+            }
+
+            #[test]
+            fn bit_str_literal_display_parses(lit in bit_str_literal()) {
+                prop_assert_eq!(BitStrLiteral::from_str(&lit.to_string()), Ok(lit));
+            }
+
+            #[test]
+            fn token_display_parses(tk in token()) {
+                prop_assert_eq!(VHDLToken::from_str(&tk.to_string()), Ok(tk));
+            }
+
+            #[test]
+            fn rendered_tokens_tokenize(tokens in proptest::collection::vec(token(), 0..24)) {
+                // a single quote only begins a character literal after a delimiter,
+                // otherwise it is a tick
+                let tokens: Vec<VHDLToken> = tokens.into_iter()
+                    .filter(|t| t != &VHDLToken::Delimiter(Delimiter::SingleQuote))
+                    .flat_map(|t| match t {
+                        VHDLToken::CharLiteral(_) => vec![VHDLToken::Delimiter(Delimiter::Comma), t],
+                        _ => vec![t],
+                    })
+                    .collect();
+                let text = render(&tokens);
+                let result: Vec<VHDLToken> = VHDLTokenizer::tokenize_strict(&text)
+                    .map_err(|e| TestCaseError::fail(format!("{} in {:?}", e, text)))?
+                    .into_iter()
+                    .map(|t| t.take())
+                    .filter(|t| t.is_eof() == false)
+                    .collect();
+                prop_assert_eq!(result, tokens);
+            }
+        }
+    }
 }
\ No newline at end of file
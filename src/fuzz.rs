@@ -0,0 +1,20 @@
+//! Entry points for the fuzz targets under `fuzz/`.
+//!
+//! Each function feeds arbitrary input to a part of orbit that reads untrusted
+//! text, so any panic it raises is a bug. Run them with `cargo fuzz run <target>`
+//! from the `fuzz/` directory.
+
+use crate::core::vhdl::token::{VHDLToken, VHDLTokenStream, VHDLTokenizer};
+
+/// Lexes `data` as vhdl source code, both as text and as read from a file.
+pub fn tokenize(data: &[u8]) {
+    let text = String::from_utf8_lossy(data);
+    match VHDLTokenizer::tokenize_strict(&text) {
+        Ok(tokens) => assert_eq!(tokens.last().map(|t| t.as_ref()), Some(&VHDLToken::EOF)),
+        Err(e) => { e.to_string(); },
+    }
+    // the reader stops at invalid UTF-8 instead of replacing it
+    let mut stream = VHDLTokenStream::from_reader(data);
+    stream.by_ref().filter_map(|r| r.err()).for_each(|e| { e.to_string(); });
+    stream.take_error();
+}
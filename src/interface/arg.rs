@@ -36,6 +36,10 @@ impl<'a> Positional<'a> {
     pub fn new(s: &'a str) -> Self {
         Positional { name: s, }
     }
+
+    pub fn get_name_ref(&self) -> &str {
+        self.name
+    }
 }
 
 impl<'a> Display for Positional<'a> {
@@ -75,15 +79,146 @@ impl<'a> Display for Flag<'a> {
     }
 }
 
+/// How the value given to an `Optional` is divided into items.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Values {
+    /// The entire value is a single item.
+    One,
+    /// Items are separated by commas, as in `--features a,b`.
+    List,
+    /// Items are `key=value` entries separated by commas, as in `--generic A=1,B=2`.
+    Map,
+}
+
+impl Values {
+    /// Divides the value `s` into its items.
+    /// 
+    /// For a map, a comma-separated piece without '=' continues the value of the
+    /// entry before it, so values such as lists of globs can still hold commas.
+    pub fn split<'s>(&self, s: &'s str) -> Result<Vec<&'s str>, ValueError> {
+        match self {
+            Self::One => Ok(vec![s]),
+            Self::List => s.split(',').map(|item| match item.trim() {
+                "" => Err(ValueError::EmptyItem),
+                item => Ok(item),
+            }).collect(),
+            Self::Map => {
+                let mut entries: Vec<&str> = Vec::new();
+                let mut start = 0;
+                for (i, piece) in s.split(',').enumerate() {
+                    // find where the piece begins in the entire value
+                    let offset = piece.as_ptr() as usize - s.as_ptr() as usize;
+                    if i > 0 && piece.contains('=') {
+                        entries.push(&s[start..offset - 1]);
+                        start = offset;
+                    }
+                }
+                entries.push(&s[start..]);
+                for entry in &entries {
+                    match entry.split_once('=') {
+                        Some((key, _)) => if key.trim().is_empty() {
+                            return Err(ValueError::EmptyKey(entry.to_string()))
+                        },
+                        None => return Err(ValueError::MissingSeparator(entry.to_string())),
+                    }
+                }
+                Ok(entries)
+            }
+        }
+    }
+
+    /// Checks that no key is repeated among the map `entries`, ignoring case.
+    pub fn check_keys(entries: &[&str]) -> Result<(), ValueError> {
+        let keys: Vec<&str> = entries.iter().filter_map(|e| e.split_once('=')).map(|(k, _)| k.trim()).collect();
+        for (i, key) in keys.iter().enumerate() {
+            if keys[..i].iter().any(|k| k.eq_ignore_ascii_case(key)) {
+                return Err(ValueError::DuplicateKey(key.to_string()))
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ValueError {
+    EmptyItem,
+    EmptyKey(String),
+    MissingSeparator(String),
+    DuplicateKey(String),
+    /// the value given and the values allowed
+    UnknownChoice(String, String),
+}
+
+impl std::error::Error for ValueError {}
+
+impl Display for ValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> { 
+        match self {
+            Self::EmptyItem => write!(f, "an empty item in the comma-separated list"),
+            Self::EmptyKey(e) => write!(f, "an empty key in '{}'", e),
+            Self::MissingSeparator(e) => write!(f, "missing separator '=' in '{}'; expecting 'key=value'", e),
+            Self::DuplicateKey(k) => write!(f, "key '{}' being given more than once", k),
+            Self::UnknownChoice(v, choices) => write!(f, "unknown value '{}'; expected one of: {}", v, choices),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Optional<'a> {
     option: Flag<'a>,
     value: Positional<'a>,
+    values: Values,
+    default: Option<&'a str>,
+    choices: &'a [&'a str],
 }
 
 impl<'a> Optional<'a> {
     pub fn new(s: &'a str) -> Self {
-        Optional { option: Flag::new(s), value: Positional::new(s), }
+        Optional { option: Flag::new(s), value: Positional::new(s), values: Values::One, default: None, choices: &[], }
+    }
+
+    /// Sets the value used when the option is not given.
+    pub fn default(mut self, s: &'a str) -> Self {
+        self.default = Some(s);
+        self
+    }
+
+    /// Restricts each value (or item of a list) to one of the `choices`.
+    pub fn choices(mut self, c: &'a [&'a str]) -> Self {
+        self.choices = c;
+        self
+    }
+
+    pub fn get_default(&self) -> Option<&'a str> {
+        self.default
+    }
+
+    pub fn get_choices(&self) -> &'a [&'a str] {
+        self.choices
+    }
+
+    /// Checks the value `s` is one of the allowed choices, if any are set.
+    pub fn check_choice(&self, s: &str) -> Result<(), ValueError> {
+        match self.choices.is_empty() || self.choices.contains(&s) {
+            true => Ok(()),
+            false => Err(ValueError::UnknownChoice(s.to_string(), self.choices.join(", "))),
+        }
+    }
+
+    /// Accepts comma-separated items in each value.
+    pub fn list(mut self) -> Self {
+        self.values = Values::List;
+        self
+    }
+
+    /// Accepts comma-separated `key=value` entries in each value.
+    pub fn map(mut self) -> Self {
+        self.values = Values::Map;
+        self
+    }
+
+    pub fn get_values(&self) -> Values {
+        self.values
     }
 
     pub fn value(mut self, s: &'a str) -> Self {
@@ -100,7 +235,7 @@ impl<'a> Optional<'a> {
         &self.option
     }
 
-    pub fn _get_pos_ref(&self) -> &Positional {
+    pub fn get_pos_ref(&self) -> &Positional<'_> {
         &self.value
     }
 }
@@ -171,6 +306,9 @@ mod test {
         assert_eq!(code, Optional {
             option: Flag::new("code"),
             value: Positional::new("code"),
+            values: Values::One,
+            default: None,
+            choices: &[],
         });
         assert_eq!(code.get_flag_ref().get_switch_ref(), None);
 
@@ -178,6 +316,9 @@ mod test {
         assert_eq!(version, Optional {
             option: Flag::new("color"),
             value: Positional::new("rgb"),
+            values: Values::One,
+            default: None,
+            choices: &[],
         });
         assert_eq!(version.get_flag_ref().get_switch_ref(), None);
 
@@ -185,10 +326,25 @@ mod test {
         assert_eq!(version, Optional {
             option: Flag::new("color").switch('c'),
             value: Positional::new("rgb"),
+            values: Values::One,
+            default: None,
+            choices: &[],
         });
         assert_eq!(version.get_flag_ref().get_switch_ref(), Some(&'c'));
 
-        assert_eq!(version._get_pos_ref(), &Positional::new("rgb"));
+        assert_eq!(version.get_pos_ref(), &Positional::new("rgb"));
+    }
+
+    #[test]
+    fn optional_choices() {
+        let format = Optional::new("format").value("fmt").choices(&["table", "json"]).default("table");
+        assert_eq!(format.get_default(), Some("table"));
+        assert_eq!(format.check_choice("json"), Ok(()));
+        // choices are spelled exactly as the command reads them
+        assert_eq!(format.check_choice("JSON").unwrap_err().to_string(), "unknown value 'JSON'; expected one of: table, json");
+        assert_eq!(format.check_choice("tsv").unwrap_err().to_string(), "unknown value 'tsv'; expected one of: table, json");
+        // any value is allowed without choices
+        assert_eq!(Optional::new("top").check_choice("tsv"), Ok(()));
     }
 
     #[test]
@@ -219,6 +375,25 @@ mod test {
         assert_eq!(color.as_flag_ref().get_name_ref(), "color");
     }
 
+    #[test]
+    fn split_values() {
+        assert_eq!(Values::One.split("a,b"), Ok(vec!["a,b"]));
+
+        assert_eq!(Values::List.split("a, b,c"), Ok(vec!["a", "b", "c"]));
+        assert_eq!(Values::List.split("a,,b"), Err(ValueError::EmptyItem));
+        assert_eq!(Values::List.split(""), Err(ValueError::EmptyItem));
+
+        assert_eq!(Values::Map.split("A=1,B=2"), Ok(vec!["A=1", "B=2"]));
+        // pieces without '=' belong to the entry before them
+        assert_eq!(Values::Map.split("rtl=*.vhd,!*_tb.vhd,sim=*.do"), Ok(vec!["rtl=*.vhd,!*_tb.vhd", "sim=*.do"]));
+        assert_eq!(Values::Map.split("A="), Ok(vec!["A="]));
+        assert_eq!(Values::Map.split("A"), Err(ValueError::MissingSeparator("A".to_string())));
+        assert_eq!(Values::Map.split("A=1,=2"), Err(ValueError::EmptyKey("=2".to_string())));
+
+        assert_eq!(Values::check_keys(&["A=1", "B=2"]), Ok(()));
+        assert_eq!(Values::check_keys(&["A=1", "b=2", "a=3"]), Err(ValueError::DuplicateKey("a".to_string())));
+    }
+
     #[test]
     #[should_panic]
     fn arg_impossible_pos_as_flag() {
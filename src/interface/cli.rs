@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use crate::interface::errors::CliError;
 use crate::interface::arg::*;
+use crate::interface::help;
 use std::str::FromStr;
 use crate::interface::command::FromCli;
 use crate::util::seqalin;
@@ -43,7 +44,10 @@ pub struct Cli<'c> {
     opt_store: HashMap<String, Vec<usize>>,
     known_args: Vec<Arg<'c>>,
     help: &'c str,
+    help_start: usize,
     asking_for_help: bool,
+    /// the number of known arguments that are global options
+    globals: usize,
 }
 
 impl<'c> Cli<'c> {
@@ -53,7 +57,9 @@ impl<'c> Cli<'c> {
             opt_store: HashMap::new(),
             known_args: Vec::new(),
             help: "",
+            help_start: 0,
             asking_for_help: false,
+            globals: 0,
         }
     }
 
@@ -117,20 +123,47 @@ impl<'c> Cli<'c> {
             opt_store: store,
             known_args: vec![],
             help: "",
+            help_start: 0,
             asking_for_help: false,
+            globals: 0,
         }
     }
 
     /// Sets the help text to display when detecting `--help` on the command-line.
+    /// 
+    /// The arguments registered after this call are the ones listed in the help
+    /// text's options section.
     pub fn set_help(&mut self, s: &'c str) {
         self.help = s;
+        self.help_start = self.known_args.len();
+    }
+
+    /// Marks the arguments registered so far as global options.
+    /// 
+    /// Global options are read before the subcommand, so they are taken from
+    /// anywhere on the command-line. A subcommand cannot register an argument of
+    /// the same name since the global option would have already consumed it.
+    pub fn end_globals(&mut self) {
+        self.globals = self.known_args.len();
+    }
+
+    /// Panics if the flag `name` belongs to a global option.
+    fn assert_not_global(&self, name: &str) {
+        let taken = self.known_args[..self.globals].iter()
+            .any(|a| !matches!(a, Arg::Positional(_)) && a.as_flag_ref().get_name_ref() == name);
+        assert!(taken == false, "'--{}' is a global option and cannot be registered again", name);
+    }
+
+    /// References the help text set by the last command to read its arguments.
+    pub fn get_help(&self) -> &str {
+        self.help
     }
 
     /// Checks if help has been raised and will return its own error for displaying
     /// help.
     fn prioritize_help(&self) -> Result<(), CliError<'c>> {
         if self.asking_for_help == true {
-            Err(CliError::Help(self.help))
+            Err(CliError::Help(self.help.to_string()))
         } else {
             Ok(())
         }
@@ -280,27 +313,33 @@ impl<'c> Cli<'c> {
 
     /// Queries for all values behind an `Optional`.
     /// 
-    /// Errors if a parsing fails from string.
+    /// Each value is divided into items according to the `Optional`'s `Values`, and
+    /// the items of every occurrence are collected in order.
+    /// 
+    /// Errors if a value cannot be divided, a map key is repeated, or parsing an
+    /// item fails from string.
     pub fn check_option_all<'a, T: FromStr>(&mut self, o: Optional<'c>) -> Result<Option<Vec<T>>, CliError<'c>>
     where <T as FromStr>::Err: std::error::Error {
+        self.assert_not_global(o.get_flag_ref().get_name_ref());
         // collect information on where the flag can be found
         let mut locs = self.take_flag_locs(o.get_flag_ref().get_name_ref());
         if let Some(c) = o.get_flag_ref().get_switch_ref() {
             locs.extend(self.take_switch_locs(c));
         }
+        let kind = o.get_values();
+        let choices = o.get_choices();
         self.known_args.push(Arg::Optional(o));
         // pull values from where the option flags were found (including switch)
         let values = self.pull_flag(locs, true);
         if values.is_empty() {
             return Ok(None)
         }
-        // try to convert each value into the type T
-        let mut transform = Vec::<T>::with_capacity(values.len());
+        // divide each value into its items
+        let mut items = Vec::<String>::with_capacity(values.len());
         for val in values {
             if let Some(s) = val {
-                let result = s.parse::<T>();
-                match result {
-                    Ok(r) => transform.push(r),
+                match kind.split(&s) {
+                    Ok(list) => items.extend(list.into_iter().map(String::from)),
                     Err(e) => {
                         self.prioritize_help()?;
                         return Err(CliError::BadType(self.known_args.pop().unwrap(), e.to_string()))
@@ -311,25 +350,61 @@ impl<'c> Cli<'c> {
                 return Err(CliError::ExpectingValue(self.known_args.pop().unwrap()))
             }
         }
+        if let Some(item) = items.iter().find(|s| !choices.is_empty() && !choices.contains(&s.as_str())) {
+            let e = ValueError::UnknownChoice(item.to_string(), choices.join(", "));
+            self.prioritize_help()?;
+            return Err(CliError::BadType(self.known_args.pop().unwrap(), e.to_string()))
+        }
+        if kind == Values::Map {
+            if let Err(e) = Values::check_keys(&items.iter().map(|s| s.as_str()).collect::<Vec<&str>>()) {
+                self.prioritize_help()?;
+                return Err(CliError::BadType(self.known_args.pop().unwrap(), e.to_string()))
+            }
+        }
+        // try to convert each item into the type T
+        let mut transform = Vec::<T>::with_capacity(items.len());
+        for item in items {
+            match item.parse::<T>() {
+                Ok(r) => transform.push(r),
+                Err(e) => {
+                    self.prioritize_help()?;
+                    return Err(CliError::BadType(self.known_args.pop().unwrap(), e.to_string()))
+                }
+            }
+        }
         Ok(Some(transform))
     }
 
-    /// Queries for a value of `Optional`.
+    /// Queries for a value of `Optional`, using its default when it is not given.
     /// 
-    /// Errors if there are multiple values or if parsing fails.
+    /// Errors if there are multiple values, the value is not one of the allowed
+    /// choices, or if parsing fails.
     pub fn check_option<'a, T: FromStr>(&mut self, o: Optional<'c>) -> Result<Option<T>, CliError<'c>>
     where <T as FromStr>::Err: std::error::Error {
+        self.assert_not_global(o.get_flag_ref().get_name_ref());
         // collect information on where the flag can be found
         let mut locs = self.take_flag_locs(o.get_flag_ref().get_name_ref());
         if let Some(c) = o.get_flag_ref().get_switch_ref() {
             locs.extend(self.take_switch_locs(c));
         }
+        let default = o.get_default();
         self.known_args.push(Arg::Optional(o));
         // pull values from where the option flags were found (including switch)
         let mut values = self.pull_flag(locs, true);
+        if values.is_empty() {
+            values.extend(default.map(|s| Some(s.to_string())));
+        }
         match values.len() {
             1 => {
                 if let Some(s) = values.pop().unwrap() {
+                    let allowed = match self.known_args.last() {
+                        Some(Arg::Optional(o)) => o.check_choice(&s),
+                        _ => Ok(()),
+                    };
+                    if let Err(e) = allowed {
+                        self.prioritize_help()?;
+                        return Err(CliError::BadType(self.known_args.pop().unwrap(), e.to_string()))
+                    }
                     let result = s.parse::<T>();
                     match result {
                         Ok(r) => Ok(Some(r)),
@@ -351,10 +426,20 @@ impl<'c> Cli<'c> {
         }
     }
 
+    /// Queries for a value of an `Optional` that has a default.
+    /// 
+    /// Panics if the `Optional` was not given a default.
+    pub fn check_option_default<'a, T: FromStr>(&mut self, o: Optional<'c>) -> Result<T, CliError<'c>>
+    where <T as FromStr>::Err: std::error::Error {
+        assert!(o.get_default().is_some(), "option '{}' has no default value", o);
+        Ok(self.check_option(o)?.unwrap())
+    }
+
     /// Queries if a flag was raised once and only once. 
     /// 
     /// Errors if the flag has an attached value or was raised multiple times.
     pub fn check_flag<'a>(&mut self, f: Flag<'c>) -> Result<bool, CliError<'c>> {
+        self.assert_not_global(f.get_name_ref());
         // collect information on where the flag can be found
         let mut locs = self.take_flag_locs(f.get_name_ref());
         if let Some(c) = f.get_switch_ref() {
@@ -442,25 +527,38 @@ impl<'c> Cli<'c> {
 
     /// Verifies there are no more tokens remaining in the stream. 
     /// 
-    /// Note this mutates the referenced self only if an error is found.
+    /// Called once every command has read its arguments. Errors with every argument
+    /// left over in the order given, or with a suggestion if a flag is misspelled.
     pub fn is_empty<'a>(&'a self) -> Result<(), CliError<'c>> {
-        self.prioritize_help()?;
-        // check if map is empty, and return the minimum found index.
-        if let Some((prefix, key, _)) = self.capture_bad_flag(self.tokens.len())? {
-            Err(CliError::UnexpectedArg(format!("{}{}", prefix, key)))
-        // find first non-none token
-        } else if let Some(t) = self.tokens.iter().find(|p| p.is_some()) {
+        // every argument is registered by now, so the help can list exactly what is accepted
+        if self.asking_for_help {
+            return Err(CliError::Help(help::sync_options(self.help, &self.known_args[self.help_start..], &self.known_args)))
+        }
+        // a misspelled flag is reported with the closest accepted flag
+        self.capture_bad_flag(self.tokens.len())?;
+        // the flags left in the store are named by their position in the token stream
+        let names: HashMap<usize, &str> = self.opt_store.iter()
+            .flat_map(|(name, locs)| locs.iter().map(move |i| (*i, name.as_str())))
+            .collect();
+        let mut leftovers: Vec<String> = Vec::new();
+        for (i, t) in self.tokens.iter().enumerate() {
             match t {
-                Some(Token::UnattachedArgument(_, s)) => {
-                    Err(CliError::UnexpectedArg(s.to_string()))
-                }
+                Some(Token::Flag(_)) => leftovers.push(format!("--{}", names[&i])),
+                Some(Token::Switch(_, c)) => leftovers.push(format!("-{}", c)),
+                Some(Token::UnattachedArgument(_, s)) => leftovers.push(s.to_string()),
                 Some(Token::Terminator(_)) => {
-                    Err(CliError::UnexpectedArg("--".to_string()))
+                    // the arguments after the terminator were not asked for either
+                    leftovers.push("--".to_string());
+                    break
                 }
-                _ => panic!("no other tokens types should be left")
+                // values attached to leftover flags are not listed on their own
+                Some(Token::AttachedArgument(_, _)) | Some(Token::Ignore(_, _)) | None => (),
             }
-        } else {
-            Ok(())
+        }
+        match leftovers.len() {
+            0 => Ok(()),
+            1 => Err(CliError::UnexpectedArg(leftovers.pop().unwrap())),
+            _ => Err(CliError::UnexpectedArgs(leftovers)),
         }
     }
 
@@ -585,6 +683,39 @@ mod test {
         assert_eq!(sets, None);
     }
 
+    #[test]
+    fn get_list_and_map_optionals() {
+        // items of repeated and comma-separated values are collected in order
+        let mut cli = Cli::tokenize(args(
+            vec!["orbit", "plan", "--digit", "10,9", "--digit=1"]
+        ));
+        let digits: Vec<i32> = cli.check_option_all(Optional::new("digit").list()).unwrap().unwrap();
+        assert_eq!(digits, vec![10, 9, 1]);
+        // failing case- empty item
+        let mut cli = Cli::tokenize(args(
+            vec!["orbit", "plan", "--digit", "10,"]
+        ));
+        assert_eq!(cli.check_option_all::<i32>(Optional::new("digit").list()),
+            Err(CliError::BadType(Arg::Optional(Optional::new("digit").list()), ValueError::EmptyItem.to_string())));
+
+        let mut cli = Cli::tokenize(args(
+            vec!["orbit", "plan", "--fileset", "a=*.vhd,*.v,b=*.txt", "--fileset", "c=*.do"]
+        ));
+        let sets: Vec<String> = cli.check_option_all(Optional::new("fileset").map()).unwrap().unwrap();
+        assert_eq!(sets, vec!["a=*.vhd,*.v", "b=*.txt", "c=*.do"]);
+        // failing case- key repeated across occurrences
+        let mut cli = Cli::tokenize(args(
+            vec!["orbit", "plan", "--fileset", "a=*.vhd", "--fileset", "A=*.v"]
+        ));
+        assert_eq!(cli.check_option_all::<String>(Optional::new("fileset").map()),
+            Err(CliError::BadType(Arg::Optional(Optional::new("fileset").map()), ValueError::DuplicateKey("A".to_string()).to_string())));
+        // failing case- missing separator
+        let mut cli = Cli::tokenize(args(
+            vec!["orbit", "plan", "--fileset", "a"]
+        ));
+        assert_eq!(cli.check_option_all::<String>(Optional::new("fileset").map()).is_err(), true);
+    }
+
     #[test]
     fn match_command() {
         let mut cli = Cli::tokenize(args(
@@ -667,6 +798,16 @@ mod test {
         let _: Vec<String> = cli.check_remainder().unwrap();
         // terminator removed as well as its arguments that were ignored
         assert_eq!(cli.is_empty(), Ok(()));
+
+        // every leftover argument is listed in the order given
+        let mut cli = Cli::tokenize(args(
+            vec!["orbit", "new", "rary.gates", "extra", "--vcs=git", "-z", "--", "words"]
+        ));
+        let _: String = cli.require_positional(Positional::new("command")).unwrap();
+        let _: String = cli.require_positional(Positional::new("ip")).unwrap();
+        assert_eq!(cli.is_empty(), Err(CliError::UnexpectedArgs(vec![
+            "extra".to_string(), "--vcs".to_string(), "-z".to_string(), "--".to_string(),
+        ])));
     }
 
     #[test]
@@ -754,24 +895,24 @@ mod test {
         ));
 
         // detects 0
-        assert_eq!(cli.take_flag_locs("version"), vec![]);
+        assert_eq!(cli.take_flag_locs("version"), Vec::<usize>::new());
         // detects 1
         assert_eq!(cli.take_flag_locs("lib"), vec![4]);
         // detects multiple
         assert_eq!(cli.take_flag_locs("help"), vec![0, 7]);
         // flag was past terminator and marked as ignore
-        assert_eq!(cli.take_flag_locs("map"), vec![]);
+        assert_eq!(cli.take_flag_locs("map"), Vec::<usize>::new());
         // filters out arguments
-        assert_eq!(cli.take_flag_locs("rary.gates"), vec![]);
+        assert_eq!(cli.take_flag_locs("rary.gates"), Vec::<usize>::new());
 
         // detects 0
-        assert_eq!(cli.take_switch_locs(&'q'), vec![]);
+        assert_eq!(cli.take_switch_locs(&'q'), Vec::<usize>::new());
         // detects 1
         assert_eq!(cli.take_switch_locs(&'v'), vec![1]);
         // detects multiple
         assert_eq!(cli.take_switch_locs(&'i'), vec![10, 11]);
         // switch was past terminator and marked as ignore
-        assert_eq!(cli.take_switch_locs(&'j'), vec![]);
+        assert_eq!(cli.take_switch_locs(&'j'), Vec::<usize>::new());
     }
 
     #[test]
@@ -929,6 +1070,50 @@ mod test {
         assert!(cli.check_option::<i32>(Optional::new("rate")).is_err());
     }
 
+    #[test]
+    fn global_options() {
+        // global options are found on either side of the subcommand
+        let mut cli = Cli::tokenize(args(vec!["orbit", "--force", "plan", "--verbose", "--top", "adder"]));
+        assert_eq!(cli.check_flag(Flag::new("force")), Ok(true));
+        assert_eq!(cli.check_flag(Flag::new("verbose")), Ok(true));
+        assert_eq!(cli.check_flag(Flag::new("offline")), Ok(false));
+        cli.end_globals();
+        assert_eq!(cli.check_positional(Positional::new("command")), Ok(Some(String::from("plan"))));
+        assert_eq!(cli.check_option(Optional::new("top")), Ok(Some(String::from("adder"))));
+        assert_eq!(cli.is_empty(), Ok(()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn global_option_redeclared() {
+        let mut cli = Cli::tokenize(args(vec!["orbit", "clean", "--force"]));
+        let _ = cli.check_flag(Flag::new("force"));
+        cli.end_globals();
+        let _ = cli.check_flag(Flag::new("force"));
+    }
+
+    #[test]
+    fn check_option_default_and_choices() {
+        const FORMATS: &[&str] = &["table", "json"];
+        let format = || Optional::new("format").value("fmt").choices(FORMATS).default("table");
+
+        let mut cli = Cli::tokenize(args(vec!["orbit", "search"]));
+        assert_eq!(cli.check_option_default(format()), Ok(String::from("table")));
+
+        let mut cli = Cli::tokenize(args(vec!["orbit", "search", "--format", "json"]));
+        assert_eq!(cli.check_option_default(format()), Ok(String::from("json")));
+
+        let mut cli = Cli::tokenize(args(vec!["orbit", "search", "--format=tsv"]));
+        assert_eq!(cli.check_option_default::<String>(format()), Err(CliError::BadType(Arg::Optional(format()), 
+            String::from("unknown value 'tsv'; expected one of: table, json"))));
+
+        // choices apply to each item of a list
+        let mut cli = Cli::tokenize(args(vec!["orbit", "plan", "--format", "table,tsv"]));
+        assert!(cli.check_option_all::<String>(Optional::new("format").list().choices(FORMATS)).is_err());
+        let mut cli = Cli::tokenize(args(vec!["orbit", "plan", "--format", "table,JSON"]));
+        assert!(cli.check_option_all::<String>(Optional::new("format").list().choices(FORMATS)).is_err());
+    }
+
     #[test]
     fn take_token_str() {
         let t = Token::UnattachedArgument(0, "get".to_string());
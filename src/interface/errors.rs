@@ -12,11 +12,12 @@ pub enum CliError<'a> {
     UnexpectedValue(Arg<'a>, String),
     OutOfContextArgSuggest(String, String),
     UnexpectedArg(String),
+    UnexpectedArgs(Vec<String>),
     SuggestArg(String, String),
     SuggestSubcommand(String, String),
     UnknownSubcommand(Arg<'a>, String),
     BrokenRule(String),
-    Help(&'a str),
+    Help(String),
 }
 
 impl<'a> Error for CliError<'a> {}
@@ -44,6 +45,7 @@ impl<'a> Display for CliError<'a> {
             ExpectingValue(x) => write!(f, "option '{}' expects a value but none was supplied{}", x.to_string().yellow(), footer),
             UnexpectedValue(x, s) => write!(f, "flag '{}' cannot accept values but one was supplied \"{}\"{}", x.to_string().yellow(), s, footer),
             UnexpectedArg(s) => write!(f, "unknown argument '{}'{}", s.yellow(), footer),
+            UnexpectedArgs(list) => write!(f, "unknown arguments {}{}", list.iter().map(|s| format!("'{}'", s.yellow())).collect::<Vec<String>>().join(", "), footer),
             UnknownSubcommand(c, a) => write!(f, "'{}' is not a valid subcommand for {}{}", a, c.to_string().yellow(), footer),
             BrokenRule(r) => write!(f, "a rule conflict occurred from {}", r),
         }
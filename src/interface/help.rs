@@ -0,0 +1,256 @@
+//! Keeps the options section of a command's help text in sync with the arguments
+//! the command accepts.
+//! 
+//! Descriptions are written by hand in each command's help text, while the list
+//! of options is checked against the arguments registered during `from_cli`.
+
+use crate::interface::arg::Arg;
+
+/// The argument described by a line of an options section.
+#[derive(Debug, PartialEq)]
+enum Entry<'a> {
+    Flag(&'a str),
+    Positional(&'a str),
+    Terminator,
+    /// A line that continues the description of the entry above it.
+    Continued,
+}
+
+impl<'a> Entry<'a> {
+    fn read(line: &'a str) -> Self {
+        let token = line.split_whitespace().next().unwrap_or("");
+        if token == "--" {
+            Self::Terminator
+        } else if let Some(name) = token.strip_prefix("--") {
+            Self::Flag(name.trim_end_matches(','))
+        } else if let Some(name) = token.strip_prefix('<') {
+            Self::Positional(name.split('>').next().unwrap())
+        } else {
+            Self::Continued
+        }
+    }
+
+    /// Checks if any of the `args` is the argument this entry describes.
+    fn is_accepted(&self, args: &[Arg]) -> bool {
+        match self {
+            Self::Flag(name) => args.iter().any(|a| !matches!(a, Arg::Positional(_)) && a.as_flag_ref().get_name_ref() == *name),
+            Self::Positional(name) => args.iter().any(|a| matches!(a, Arg::Positional(p) if p.get_name_ref() == *name)),
+            Self::Terminator | Self::Continued => true,
+        }
+    }
+
+    /// Checks if this entry describes the `arg`.
+    fn describes(&self, arg: &Arg) -> bool {
+        match (self, arg) {
+            (Self::Positional(name), Arg::Positional(p)) => p.get_name_ref() == *name,
+            (Self::Flag(name), Arg::Flag(_)) | (Self::Flag(name), Arg::Optional(_)) => arg.as_flag_ref().get_name_ref() == *name,
+            _ => false,
+        }
+    }
+}
+
+/// Writes the usage of an `arg` in the style of a hand-written entry.
+fn write_usage(arg: &Arg) -> String {
+    let flag = arg.as_flag_ref();
+    let switch = match flag.get_switch_ref() {
+        Some(c) => format!(", -{}", c),
+        None => String::new(),
+    };
+    match arg {
+        Arg::Optional(o) => {
+            let mut usage = format!("--{}{} {}", flag.get_name_ref(), switch, o.get_pos_ref());
+            if o.get_choices().is_empty() == false {
+                usage.push_str(&format!("  one of: {}", o.get_choices().join(", ")));
+            }
+            if let Some(d) = o.get_default() {
+                usage.push_str(&format!(" (default: {})", d));
+            }
+            usage
+        }
+        _ => format!("--{}{}", flag.get_name_ref(), switch),
+    }
+}
+
+/// Notes the `default` value at the end of the entry starting at line `head` of
+/// the `entries`, unless its description already mentions one.
+fn note_default(entries: &mut Vec<String>, head: usize, default: &str) {
+    if head < entries.len() && entries[head..].iter().all(|l| l.contains("default") == false) {
+        let last = entries.last_mut().unwrap();
+        *last = format!("{} (default: {})", last.trim_end(), default);
+    }
+}
+
+/// Rewrites the "Options:" section of the help `text` to match the arguments a
+/// command accepts.
+/// 
+/// The `local` arguments are those registered by the command itself, while the
+/// `known` arguments also include the arguments registered before the command
+/// (such as `--force`). Lines for arguments that are not known are removed, and
+/// flags from `local` without a line are appended to the section. Positional
+/// arguments are left to the usage statement.
+pub fn sync_options(text: &str, local: &[Arg], known: &[Arg]) -> String {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let header = lines.iter().position(|l| l.trim() == "Options:");
+    // the section ends at the first blank line
+    let (start, end) = match header {
+        Some(h) => (h + 1, lines.iter().skip(h + 1).position(|l| l.trim().is_empty()).map(|i| i + h + 1).unwrap_or(lines.len())),
+        // place a new section after the usage statement
+        None => match lines.iter().position(|l| l.trim() == "Usage:") {
+            Some(u) => {
+                let i = lines.iter().skip(u).position(|l| l.trim().is_empty()).map(|i| i + u).unwrap_or(lines.len());
+                (i, i)
+            }
+            None => return text.to_string(),
+        },
+    };
+    let indent = match lines[start..end].iter().find(|l| Entry::read(l) != Entry::Continued) {
+        Some(l) => l.len() - l.trim_start().len(),
+        None => 4,
+    };
+
+    let mut entries: Vec<String> = Vec::new();
+    let mut keep = true;
+    // the first line of the entry being read and the default of its option
+    let mut head: Option<(usize, &str)> = None;
+    for line in &lines[start..end] {
+        let entry = Entry::read(line);
+        if entry != Entry::Continued {
+            if let Some((i, default)) = head.take() {
+                note_default(&mut entries, i, default);
+            }
+            keep = entry.is_accepted(known);
+            head = known.iter().find_map(|a| match a {
+                Arg::Optional(o) if entry.describes(a) => o.get_default().map(|d| (entries.len(), d)),
+                _ => None,
+            });
+        }
+        if keep {
+            entries.push(line.to_string());
+        }
+    }
+    if let Some((i, default)) = head.take() {
+        note_default(&mut entries, i, default);
+    }
+    for arg in local.iter().filter(|a| !matches!(a, Arg::Positional(_))) {
+        if !lines[start..end].iter().any(|l| Entry::read(l).describes(arg)) {
+            entries.push(format!("{:indent$}{}", "", write_usage(arg), indent = indent));
+        }
+    }
+
+    let mut result: Vec<String> = lines[..start].iter().map(|l| l.to_string()).collect();
+    match header {
+        Some(h) if entries.is_empty() => {
+            // remove the header and the blank line that follows the section
+            result.truncate(h);
+            return result.into_iter().chain(lines[end..].iter().skip(1).map(|l| l.to_string())).collect::<Vec<String>>().join("\n")
+        }
+        Some(_) => result.extend(entries),
+        None if entries.is_empty() => return text.to_string(),
+        None => {
+            result.push(String::new());
+            result.push(String::from("Options:"));
+            result.extend(entries);
+        }
+    }
+    result.extend(lines[end..].iter().map(|l| l.to_string()));
+    result.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::arg::{Flag, Optional, Positional};
+
+    const HELP: &str = "\
+Build the design.
+
+Usage:
+    orbit build [options]
+
+Options:
+    --plugin <alias>    plugin to execute
+    --list              view available plugins
+    --legacy            removed in a previous release
+                        but still documented
+    -- args...          arguments to pass to the requested command
+
+Use 'orbit help build' to learn more about the command.
+";
+
+    #[test]
+    fn unchanged() {
+        let known = vec![
+            Arg::Flag(Flag::new("force")),
+            Arg::Optional(Optional::new("plugin").value("alias")),
+            Arg::Flag(Flag::new("list")),
+            Arg::Flag(Flag::new("legacy")),
+        ];
+        assert_eq!(sync_options(HELP, &known[1..], &known), HELP);
+    }
+
+    #[test]
+    fn removes_and_appends() {
+        let known = vec![
+            Arg::Optional(Optional::new("plugin").value("alias")),
+            Arg::Flag(Flag::new("list")),
+            Arg::Optional(Optional::new("jobs").value("n").switch('j')),
+            Arg::Flag(Flag::new("verbose")),
+            Arg::Positional(Positional::new("target")),
+        ];
+        assert_eq!(sync_options(HELP, &known, &known), "\
+Build the design.
+
+Usage:
+    orbit build [options]
+
+Options:
+    --plugin <alias>    plugin to execute
+    --list              view available plugins
+    -- args...          arguments to pass to the requested command
+    --jobs, -j <n>
+    --verbose
+
+Use 'orbit help build' to learn more about the command.
+");
+    }
+
+    #[test]
+    fn notes_defaults() {
+        let text = "\
+Usage:
+    orbit search [options]
+
+Options:
+    --format <fmt>      display as a 'table' or 'json' (default: table)
+    --limit <n>         maximum number of results
+                        to display
+    --plugin <alias>    plugin to execute
+";
+        let known = vec![
+            Arg::Optional(Optional::new("format").value("fmt").choices(&["table", "json"]).default("table")),
+            Arg::Optional(Optional::new("limit").value("n").default("10")),
+            Arg::Optional(Optional::new("plugin").value("alias")),
+            Arg::Optional(Optional::new("sort").value("key").choices(&["name", "date"]).default("name")),
+        ];
+        assert_eq!(sync_options(text, &known, &known), "\
+Usage:
+    orbit search [options]
+
+Options:
+    --format <fmt>      display as a 'table' or 'json' (default: table)
+    --limit <n>         maximum number of results
+                        to display (default: 10)
+    --plugin <alias>    plugin to execute
+    --sort <key>  one of: name, date (default: name)
+");
+    }
+
+    #[test]
+    fn new_section() {
+        let text = "Remove an ip.\n\nUsage:\n    orbit uninstall <ip>\n\nMore text.\n";
+        let known = vec![Arg::Positional(Positional::new("ip")), Arg::Flag(Flag::new("all"))];
+        assert_eq!(sync_options(text, &known, &known), "Remove an ip.\n\nUsage:\n    orbit uninstall <ip>\n\nOptions:\n    --all\n\nMore text.\n");
+        // nothing to add
+        assert_eq!(sync_options(text, &known[..1], &known), text);
+    }
+}
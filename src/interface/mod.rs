@@ -1,4 +1,5 @@
 pub mod arg;
 pub mod cli;
 pub mod command;
-pub mod errors;
\ No newline at end of file
+pub mod errors;
+pub mod help;
\ No newline at end of file
@@ -4,13 +4,18 @@ mod interface;
 mod commands;
 pub mod util;
 mod core;
+pub mod syntax;
+#[doc(hidden)]
+pub mod perf;
+#[doc(hidden)]
+pub mod fuzz;
 
 use crate::interface::cli::*;
 use crate::interface::errors::*;
 use crate::interface::command::*;
 use crate::commands::orbit::*;
-use colored::*;
 use crate::core::context::Context;
+use crate::util::anyerror::{Category, CategoryError, Fault};
 
 pub fn run() -> u8 {
     // interface level
@@ -23,9 +28,9 @@ pub fn run() -> u8 {
                     println!("{}", s);
                     return 0;
                 }
-                _ => eprintln!("{} {}", "error:".red().bold(), e)
+                _ => eprintln!("{}", util::theme::error(e))
             }
-            return 101;
+            return Category::Usage.exit_code();
         }
     };
     if let Err(e) = cli.is_empty() {
@@ -34,17 +39,50 @@ pub fn run() -> u8 {
                 println!("{}", s);
                 return 0;
             }
-            _ => eprintln!("{} {}", "error:".red().bold(), e),
+            _ => eprintln!("{}", util::theme::error(e)),
         }
-        return 101;
+        return Category::Usage.exit_code();
     }
     std::mem::drop(cli);
     // program level
     match orbit.exec(&Context::new()) {
         Ok(_) => 0,
         Err(e) => {
-            eprintln!("{} {}", "error:".red().bold(), e); 
-            101
+            eprintln!("{}", util::theme::error(&e));
+            exit_code(&e)
         }
     }
+}
+
+/// Determines the exit code for the category of the error `e`.
+fn exit_code(e: &Fault) -> u8 {
+    let category = if let Some(e) = e.downcast_ref::<CategoryError>() {
+        Some(e.0)
+    } else if e.is::<crate::core::plugin::PluginError>() {
+        Some(Category::Plugin)
+    } else if e.is::<crate::core::catalog::CatalogError>() {
+        Some(Category::Resolution)
+    } else {
+        None
+    };
+    match category {
+        Some(c) => c.exit_code(),
+        None => Category::UNCATEGORIZED,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::anyerror::AnyError;
+
+    #[test]
+    fn exit_codes() {
+        let e: Fault = Box::new(CategoryError(Category::Checksum, String::from("checksums did not match")));
+        assert_eq!(exit_code(&e), 4);
+        let e: Fault = Box::new(crate::core::plugin::PluginError::Missing(String::from("vivado")));
+        assert_eq!(exit_code(&e), 5);
+        let e: Fault = Box::new(AnyError(String::from("failed to read file")));
+        assert_eq!(exit_code(&e), 101);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,156 @@
+//! Entry points for the benchmarks under `benches/`.
+//!
+//! The benchmarks link against the library, which keeps its modules private, so
+//! the phases they measure are exposed here along with a generator for the
+//! sample vhdl they read. Run them with `cargo bench`.
+
+use std::path::Path;
+use crate::commands::orbit::Orbit;
+use crate::commands::plan::Plan;
+use crate::core::context::Context;
+use crate::core::ip::IpFileNode;
+use crate::core::manifest::IpManifest;
+use crate::core::vhdl::symbol::VHDLParser;
+use crate::core::vhdl::token::{Identifier, VHDLTokenizer};
+use crate::interface::cli::Cli;
+use crate::interface::command::{Command, FromCli};
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::environment;
+
+/// Writes an ip of generated vhdl to `dir`.
+///
+/// The ip declares `units` entities instantiated as a binary tree under `top`,
+/// along with a memory initialization package holding a constant of `words`
+/// values, which mimics the large generated files found in real projects.
+pub fn generate_corpus(dir: &Path, units: usize, words: usize) -> Result<(), Fault> {
+    let rtl = dir.join("rtl");
+    std::fs::create_dir_all(&rtl)?;
+    std::fs::write(dir.join("Orbit.toml"), MANIFEST)?;
+    std::fs::write(rtl.join("mem_init.vhd"), mem_init_package(words))?;
+    std::fs::write(rtl.join("top.vhd"), format!("{}{}", HEADER, unit_design("top", (units > 0).then(|| 0), None)))?;
+    for i in 0..units {
+        let child = |n: usize| (n < units).then(|| n);
+        let name = format!("unit_{}", i);
+        std::fs::write(rtl.join(format!("{}.vhd", name)), format!("{}{}", HEADER, unit_design(&name, child(2 * i + 1), child(2 * i + 2))))?;
+    }
+    Ok(())
+}
+
+/// Writes an entity and architecture named `name` that instantiates the units
+/// numbered `left` and `right`.
+fn unit_design(name: &str, left: Option<usize>, right: Option<usize>) -> String {
+    let instance = |label: &str, n: Option<usize>, q: &str| match n {
+        Some(n) => format!("    {}: entity work.unit_{} generic map (WIDTH => WIDTH) port map (clk => clk, d => d, q => {});\n", label, n, q),
+        None => format!("    {} <= d;\n", q),
+    };
+    format!("\
+-- A generated unit of the benchmark corpus.
+entity {0} is
+    generic (WIDTH : positive := 8);
+    port (
+        clk : in std_logic;
+        d : in std_logic_vector(WIDTH-1 downto 0);
+        q : out std_logic_vector(WIDTH-1 downto 0)
+    );
+end entity;
+
+architecture rtl of {0} is
+    signal r0, r1 : std_logic_vector(WIDTH-1 downto 0);
+begin
+{1}{2}
+    process (clk) begin
+        if rising_edge(clk) then
+            q <= r0 xor r1 xor ROM(0)(WIDTH-1 downto 0);
+        end if;
+    end process;
+end architecture;
+", name, instance("u_l", left, "r0"), instance("u_r", right, "r1"))
+}
+
+/// Writes a package declaring a constant array of `words` bytes.
+fn mem_init_package(words: usize) -> String {
+    let values: Vec<String> = (0..words.max(1)).map(|i| format!("x\"{:02x}\"", i % 256)).collect();
+    let lines: Vec<String> = values.chunks(8).map(|c| format!("        {}", c.join(", "))).collect();
+    format!("\
+library ieee;
+use ieee.std_logic_1164.all;
+
+package mem_init is
+    type rom_t is array (natural range <>) of std_logic_vector(7 downto 0);
+    constant ROM : rom_t(0 to {}) := (
+{}
+    );
+end package;
+", words.max(1) - 1, lines.join(",\n"))
+}
+
+const MANIFEST: &str = "\
+[ip]
+name = \"corpus\"
+library = \"bench\"
+vendor = \"orbit\"
+version = \"0.1.0\"
+
+[dependencies]
+";
+
+const HEADER: &str = "\
+library ieee;
+use ieee.std_logic_1164.all;
+use work.mem_init.all;
+
+";
+
+/// Lexes the source code `text`, returning the number of tokens.
+pub fn tokenize(text: &str) -> usize {
+    VHDLTokenizer::from_source_code(text).into_tokens_all().len()
+}
+
+/// Parses the source code `text`, returning the number of design units.
+pub fn parse(text: &str) -> usize {
+    VHDLParser::read(text).into_symbols().len()
+}
+
+/// Builds the hdl graph of the ip at `dir` as planning does, returning the number
+/// of design units in the graph.
+pub fn build_graph(dir: &Path) -> Result<usize, Fault> {
+    let ip = IpManifest::from_path(&dir.to_path_buf())?;
+    let files: Vec<IpFileNode> = crate::util::filesystem::gather_current_files(&ip.get_root())
+        .into_iter()
+        .filter(|f| crate::core::fileset::is_vhdl(f))
+        .map(|f| IpFileNode::new(f, &ip, Identifier::new_working()))
+        .collect();
+    Ok(Plan::build_full_graph(&files, ip.get_providers())?.get_map().len())
+}
+
+/// Runs the orbit command given by `args` within the current process, as if
+/// from the command-line.
+///
+/// The context exports the paths it resolves to the environment, so they are
+/// cleared beforehand to be resolved again from the current directory.
+pub fn run(args: &[&str]) -> Result<(), Fault> {
+    for key in [environment::ORBIT_IP_PATH, environment::ORBIT_DEV_PATH, environment::ORBIT_BUILD_DIR, environment::ORBIT_CACHE, environment::ORBIT_STORE] {
+        std::env::remove_var(key);
+    }
+    let mut cli = Cli::tokenize(std::iter::once("orbit").chain(args.iter().copied()).map(String::from));
+    let orbit = Orbit::from_cli(&mut cli).map_err(|e| AnyError(e.to_string()))?;
+    cli.is_empty().map_err(|e| AnyError(e.to_string()))?;
+    drop(cli);
+    orbit.exec(&Context::new())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn corpus_builds_graph() {
+        let dir = tempfile::tempdir().unwrap();
+        generate_corpus(dir.path(), 6, 20).unwrap();
+        let text = std::fs::read_to_string(dir.path().join("rtl/mem_init.vhd")).unwrap();
+        assert!(tokenize(&text) > 20);
+        assert_eq!(parse(&text), 1);
+        // 6 units, top, and the package
+        assert_eq!(build_graph(dir.path()).unwrap(), 8);
+    }
+}
@@ -0,0 +1,8 @@
+//! Classification of vhdl source code for syntax highlighting.
+//!
+//! Editor plugins and documentation generators can use `classify` to color
+//! source code with the same tokens Orbit reads, rather than reimplementing
+//! the lexer.
+
+pub use crate::core::lexer::{Position, Span};
+pub use crate::core::vhdl::highlight::{classify, TokenCategory};
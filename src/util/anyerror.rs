@@ -12,4 +12,44 @@ impl std::fmt::Display for AnyError {
     }
 }
 
-pub type Fault = Box<dyn std::error::Error>;
\ No newline at end of file
+pub type Fault = Box<dyn std::error::Error>;
+
+/// The kinds of failures that exit orbit with their own code so scripts can
+/// branch on why a command failed.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Category {
+    /// The command-line arguments were invalid.
+    Usage,
+    /// An ip or one of its dependencies could not be found.
+    Resolution,
+    /// Downloaded or installed contents did not match their expected checksum.
+    Checksum,
+    /// A plugin was missing or did not run successfully.
+    Plugin,
+}
+
+impl Category {
+    /// Exit code for any failure without a category.
+    pub const UNCATEGORIZED: u8 = 101;
+
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Usage => 2,
+            Self::Resolution => 3,
+            Self::Checksum => 4,
+            Self::Plugin => 5,
+        }
+    }
+}
+
+/// An error message that belongs to a category of failures.
+#[derive(Debug, PartialEq)]
+pub struct CategoryError(pub Category, pub String);
+
+impl std::error::Error for CategoryError {}
+
+impl std::fmt::Display for CategoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.1)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,149 @@
+//! Details about how this orbit binary was built (shown by `--version`).
+//!
+//! The commit, date, target, profile, and features are recorded by the build
+//! script. The format versions let bug reports and plugins check which lockfiles,
+//! blueprints, and ORBIT_HOME layouts this release reads and writes.
+
+use std::str::FromStr;
+use crate::core::fileset::BLUEPRINT_FORMAT;
+use crate::core::layout::LAYOUT_VERSION;
+use crate::core::lockfile::LOCK_FORMAT;
+use crate::util::anyerror::AnyError;
+
+/// How the build information is written.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(AnyError(format!("unknown format '{}'; expecting 'text' or 'json'", s))),
+        }
+    }
+}
+
+/// Details about how this orbit binary was built and the formats it reads and writes.
+#[derive(Debug, PartialEq)]
+pub struct BuildInfo {
+    version: &'static str,
+    commit: Option<&'static str>,
+    date: &'static str,
+    target: &'static str,
+    profile: &'static str,
+    features: Vec<&'static str>,
+    lockfile: i64,
+    blueprint: i64,
+    layout: u32,
+}
+
+impl BuildInfo {
+    /// Collects the details recorded for this binary by the build script.
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            commit: Some(env!("BUILD_COMMIT")).filter(|c| c.is_empty() == false),
+            date: env!("BUILD_DATE"),
+            target: env!("BUILD_TARGET"),
+            profile: env!("BUILD_PROFILE"),
+            features: env!("BUILD_FEATURES").split(',').filter(|f| f.is_empty() == false).collect(),
+            lockfile: LOCK_FORMAT,
+            blueprint: BLUEPRINT_FORMAT,
+            layout: LAYOUT_VERSION,
+        }
+    }
+}
+
+/// Writes the version of orbit, followed by one `key: value` line for each
+/// detail of the build when `verbose` is set.
+pub fn format_text(info: &BuildInfo, verbose: bool) -> String {
+    let mut text = format!("orbit {}\n", info.version);
+    if verbose == true {
+        let features = match info.features.is_empty() {
+            true => String::from("none"),
+            false => info.features.join(", "),
+        };
+        text.push_str(&format!("\
+commit-hash: {}
+build-date: {}
+target: {}
+profile: {}
+features: {}
+lockfile-format: {}
+blueprint-format: {}
+home-layout: {}
+", info.commit.unwrap_or("unknown"), info.date, info.target, info.profile, features, info.lockfile, info.blueprint, info.layout));
+    }
+    text
+}
+
+/// Writes every detail of the build as a JSON object. The commit is `null` when
+/// orbit was not built from a git repository.
+pub fn format_json(info: &BuildInfo) -> String {
+    serde_json::json!({
+        "version": info.version,
+        "commit": info.commit,
+        "build_date": info.date,
+        "target": info.target,
+        "profile": info.profile,
+        "features": info.features,
+        "formats": {
+            "lockfile": info.lockfile,
+            "blueprint": info.blueprint,
+            "home_layout": info.layout,
+        },
+    }).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn info() -> BuildInfo {
+        BuildInfo {
+            version: "0.9.0",
+            commit: None,
+            date: "2026-01-31",
+            target: "x86_64-unknown-linux-gnu",
+            profile: "release",
+            features: vec![],
+            lockfile: 2,
+            blueprint: 1,
+            layout: 3,
+        }
+    }
+
+    #[test]
+    fn format_build_info() {
+        assert_eq!(format_text(&info(), false), "orbit 0.9.0\n");
+        assert_eq!(format_text(&info(), true), "\
+orbit 0.9.0
+commit-hash: unknown
+build-date: 2026-01-31
+target: x86_64-unknown-linux-gnu
+profile: release
+features: none
+lockfile-format: 2
+blueprint-format: 1
+home-layout: 3
+");
+        let json: serde_json::Value = serde_json::from_str(&format_json(&info())).unwrap();
+        assert_eq!(json["commit"], serde_json::Value::Null);
+        assert_eq!(json["formats"]["lockfile"], 2);
+        assert_eq!(json["features"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn current_build_info() {
+        let info = BuildInfo::current();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.lockfile, LOCK_FORMAT);
+        assert_eq!(info.date.len(), "YYYY-MM-DD".len());
+    }
+}
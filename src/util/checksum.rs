@@ -1,5 +1,33 @@
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::util::profile;
+use crate::util::progress::Spinner;
 use crate::util::sha256;
 
+/// Number of bytes read from a file at a time while hashing.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Total size of files (in bytes) to checksum before displaying progress.
+const PROGRESS_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// Computes the sha256 digest of the raw bytes of the file at `path`.
+/// 
+/// Unlike `checksum`, no bytes are removed before hashing, so the result is
+/// identical to common tools such as `sha256sum`. The file is read in chunks
+/// so large files are not held in memory.
+pub fn file_sum(path: &std::path::Path) -> Result<sha256::Sha256Hash, std::io::Error> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = sha256::Sha256::new();
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+        match file.read(&mut buf)? {
+            0 => break,
+            n => hasher.update(&buf[..n]),
+        }
+    }
+    Ok(hasher.finish())
+}
+
 /// Given a list of files, compute a single sha256 digest to encapsulate the
 /// entire directory state.
 /// 
@@ -9,23 +37,39 @@ use crate::util::sha256;
 /// This function also skips binary files (not intended for reading) by detecting
 /// if a NUL character appears in the byte vector.
 pub fn checksum(files: &[String]) -> sha256::Sha256Hash {
+    checksum_at(std::path::Path::new(""), files)
+}
+
+/// Computes the same digest as `checksum` with the relative filepaths `files`
+/// read from `root` rather than the current working directory.
+/// 
+/// Files are hashed in parallel and streamed in chunks. A spinner is displayed
+/// when the files are large enough to take a noticeable amount of time.
+pub fn checksum_at(root: &std::path::Path, files: &[String]) -> sha256::Sha256Hash {
+    let _phase = profile::Phase::start("checksum");
+    let total_size: u64 = files.iter()
+        .filter_map(|f| std::fs::metadata(root.join(f)).ok())
+        .map(|m| m.len())
+        .sum();
+    let spinner = match total_size >= PROGRESS_THRESHOLD {
+        true => Some(Spinner::transient(&format!("computing checksum of {} files ({:.2} MB)", files.len(), total_size as f64 / 1_000_000.0))),
+        false => None,
+    };
+    let digests = text_sums(root, files);
+    if let Some(s) = spinner {
+        s.finish(None);
+    }
+
     // determine the amount of bytes required
     let total_hashes = files.len() + 1;
     let mut total_bytes = Vec::<u8>::with_capacity(total_hashes*32);
-    
     let mut filename_bytes = Vec::<u8>::new();
-    // use a single vector to keep allocated capacity throughout rounds
-    let mut bytes = Vec::new();
-    // perform a hash on contents
-    for file in files {
-        bytes.clear();
-        bytes.append(&mut std::fs::read(&file).expect("failed to read as bytes"));
-        // detect and skip binary-encoded files (.pdf, .jpg, etc.) by reading NUL char
-        if bytes.contains(&0x00) == true { continue; }
-        // @NOTE windows uses \r\n for newlines, compared to unix systems using just \n
-        bytes = bytes.into_iter().filter(|f| f != &0x0d ).collect::<Vec<u8>>();
-        total_bytes.append(&mut sha256::compute_sha256(&bytes).into_bytes().to_vec());
-        filename_bytes.append(&mut file.as_bytes().to_vec());
+    for (file, digest) in files.iter().zip(digests) {
+        // binary-encoded files (.pdf, .jpg, etc.) are skipped
+        if let Some(digest) = digest {
+            total_bytes.append(&mut digest.into_bytes().to_vec());
+            filename_bytes.append(&mut file.as_bytes().to_vec());
+        }
     }
     // perform hash on filenames
     total_bytes.append(&mut sha256::compute_sha256(&filename_bytes).into_bytes().to_vec());
@@ -34,6 +78,69 @@ pub fn checksum(files: &[String]) -> sha256::Sha256Hash {
     sha256::compute_sha256(&total_bytes)
 }
 
+/// Computes `text_sum` for each of the `files` under `root`, split across a
+/// worker thread per available cpu.
+/// 
+/// The digests are returned in the same order as `files`.
+fn text_sums(root: &std::path::Path, files: &[String]) -> Vec<Option<sha256::Sha256Hash>> {
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(files.len());
+    if workers <= 1 {
+        return files.iter().map(|f| text_sum(&root.join(f))).collect()
+    }
+    let next = AtomicUsize::new(0);
+    let mut digests = vec![None; files.len()];
+    std::thread::scope(|s| {
+        let handles: Vec<_> = (0..workers).map(|_| s.spawn(|| {
+            let mut found = Vec::new();
+            // take the next file that has not been hashed yet
+            loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                if i >= files.len() {
+                    break found
+                }
+                found.push((i, text_sum(&root.join(&files[i]))));
+            }
+        })).collect();
+        for h in handles {
+            let found = h.join().unwrap_or_else(|e| std::panic::resume_unwind(e));
+            for (i, digest) in found {
+                digests[i] = digest;
+            }
+        }
+    });
+    digests
+}
+
+/// Computes the sha256 digest of the file at `path` with its \r carriage
+/// return bytes removed.
+/// 
+/// Returns `None` if the file is binary-encoded (a NUL character appears).
+fn text_sum(path: &std::path::Path) -> Option<sha256::Sha256Hash> {
+    let mut file = std::fs::File::open(path).expect("failed to read as bytes");
+    let mut hasher = sha256::Sha256::new();
+    // use vectors allocated once to keep capacity throughout rounds
+    let mut buf = vec![0; CHUNK_SIZE];
+    let mut bytes = Vec::with_capacity(CHUNK_SIZE);
+    loop {
+        let n = file.read(&mut buf).expect("failed to read as bytes");
+        if n == 0 {
+            break
+        }
+        // detect and skip binary-encoded files by reading NUL char
+        if buf[..n].contains(&0x00) == true {
+            return None
+        }
+        // @NOTE windows uses \r\n for newlines, compared to unix systems using just \n
+        bytes.clear();
+        bytes.extend(buf[..n].iter().filter(|f| **f != 0x0d));
+        hasher.update(&bytes);
+    }
+    Some(hasher.finish())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -89,4 +196,39 @@ mod test {
             1604039077, 517642943, 2999962878, 2156562165]
         ));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn file_sum_raw_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hello.txt");
+        std::fs::write(&path, "hello world").unwrap();
+        assert_eq!(file_sum(&path).unwrap().to_string(), "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9");
+        // carriage returns are kept
+        std::fs::write(&path, "hello\r\nworld").unwrap();
+        assert_ne!(file_sum(&path).unwrap(), sha256::compute_sha256("hello\nworld".as_bytes()));
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = Vec::new();
+        for i in 0..20 {
+            let name = format!("file{:02}.txt", i);
+            // larger than a single read to cross chunk boundaries
+            let text = format!("line {}\r\n", i).repeat(CHUNK_SIZE / 4);
+            std::fs::write(dir.path().join(&name), text).unwrap();
+            files.push(name);
+        }
+        std::fs::write(dir.path().join("image.bin"), [1, 2, 0, 3]).unwrap();
+        files.push(String::from("image.bin"));
+
+        let digests = text_sums(dir.path(), &files);
+        for (file, digest) in files.iter().zip(&digests) {
+            assert_eq!(digest, &text_sum(&dir.path().join(file)));
+        }
+        // carriage returns are removed and binary files are skipped
+        let text = std::fs::read(dir.path().join("file03.txt")).unwrap();
+        assert_eq!(digests[3], Some(sha256::compute_sha256(&text.into_iter().filter(|b| *b != 0x0d).collect::<Vec<u8>>())));
+        assert_eq!(digests[20], None);
+    }
+}
@@ -0,0 +1,138 @@
+//! Line-based differences between two versions of a text file.
+
+/// The number of unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Computes the edit script from `old` to `new` using their longest common
+/// subsequence of lines.
+fn edit_script<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let (n, m) = (old.len(), new.len());
+    // lcs[i][j] is the length of the lcs of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+    let mut script = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            script.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            script.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            script.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    script
+}
+
+/// Writes the changes from `old` to `new` in the unified diff format, labeling
+/// the versions `a` and `b`.
+///
+/// Returns an empty string when the texts are equal.
+pub fn unified(old: &str, new: &str, a: &str, b: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let script = edit_script(&old_lines, &new_lines);
+
+    let changes: Vec<usize> = script.iter().enumerate()
+        .filter(|(_, l)| !matches!(l, Line::Same(_)))
+        .map(|(k, _)| k)
+        .collect();
+    if changes.is_empty() {
+        return String::new()
+    }
+    // group changes whose context overlaps into the same hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for k in changes {
+        let start = k.saturating_sub(CONTEXT);
+        let end = (k + CONTEXT + 1).min(script.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut result = format!("--- {}\n+++ {}\n", a, b);
+    for (start, end) in hunks {
+        // count the lines of each version before the hunk
+        let old_start = script[..start].iter().filter(|l| !matches!(l, Line::Added(_))).count();
+        let new_start = script[..start].iter().filter(|l| !matches!(l, Line::Removed(_))).count();
+        let old_len = script[start..end].iter().filter(|l| !matches!(l, Line::Added(_))).count();
+        let new_len = script[start..end].iter().filter(|l| !matches!(l, Line::Removed(_))).count();
+        result.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start + 1, old_len, new_start + 1, new_len));
+        for line in &script[start..end] {
+            match line {
+                Line::Same(s) => result.push_str(&format!(" {}\n", s)),
+                Line::Removed(s) => result.push_str(&format!("-{}\n", s)),
+                Line::Added(s) => result.push_str(&format!("+{}\n", s)),
+            }
+        }
+    }
+    result
+}
+
+/// Finds the lines found in both `old` and `new` that moved to a different position
+/// in `new`.
+///
+/// The lines of their longest common subsequence are considered in place.
+pub fn moved<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<&'a str> {
+    let kept_old: Vec<&str> = old.iter().filter(|l| new.contains(l)).copied().collect();
+    let kept_new: Vec<&str> = new.iter().filter(|l| old.contains(l)).copied().collect();
+    edit_script(&kept_old, &kept_new).into_iter()
+        .filter_map(|l| match l {
+            Line::Added(s) => Some(s),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        assert_eq!(unified(old, new, "a/x.vhd", "b/x.vhd"), "\
+--- a/x.vhd
++++ b/x.vhd
+@@ -1,5 +1,5 @@
+ a
+-b
++B
+ c
+ d
+ e
+@@ -8,3 +8,4 @@
+ h
+ i
+ j
++k
+");
+        assert_eq!(unified(old, old, "a", "b"), "");
+    }
+
+    #[test]
+    fn moved_lines() {
+        assert_eq!(moved(&["a", "b", "c", "d"], &["c", "a", "b", "e"]), vec!["c"]);
+        assert_eq!(moved(&["a", "b"], &["a", "b", "c"]), Vec::<&str>::new());
+    }
+}
\ No newline at end of file
@@ -2,7 +2,6 @@ use crate::core::config::Config;
 use crate::core::manifest::IpManifest;
 use crate::util::anyerror::Fault;
 use std::hash::Hash;
-use std::io::Write;
 use std::io::Read;
 
 use std::collections::btree_set::Iter;
@@ -88,7 +87,8 @@ impl std::fmt::Display for EnvVar {
 impl Environment {
     /// Sets environment variables from a '.env' file living at `root`.
     /// 
-    /// Silently skips text lines that do not have proper delimiter `=` between key and value.
+    /// Silently skips comments and text lines that do not have proper delimiter `=`
+    /// between key and value.
     /// This function will not add any environment variables if the file does not exist.
     pub fn from_env_file(mut self, root: &std::path::PathBuf) -> Result<Self, Fault> {
         // read the .env file
@@ -98,12 +98,8 @@ impl Environment {
             let mut contents = String::new();
             file.read_to_string(&mut contents).expect("failed to read contents");
             // transform into environment variables
-            for line in contents.split_terminator('\n') {
-                let result = line.split_once('=');
-                // set env variables
-                if let Some((name, value)) = result {
-                    self.insert(EnvVar::new().key(name).value(value));
-                }
+            for var in contents.lines().filter_map(parse_line) {
+                self.insert(var);
             }
         }
         Ok(self)
@@ -149,18 +145,86 @@ impl Environment {
     }
 }
 
+/// Header of the section in a .env file that orbit rewrites.
+const ORBIT_SECTION: &str = "# [orbit] written by orbit; values here are replaced on every plan";
+
+/// Header of the section in a .env file that orbit keeps as written.
+const PLUGIN_SECTION: &str = "# [plugin] written by plugins and users; kept by orbit";
+
+/// Variables that only orbit writes to a .env file.
+const MANAGED_KEYS: [&str; 5] = [ORBIT_PLUGIN, ORBIT_TOP, ORBIT_BENCH, ORBIT_TARGET, ORBIT_TARGET_PART];
+
 /// Stores a list of `EnvVar` at root in a file named ".env".
+/// 
+/// The variables are written in order by key to the orbit section of the file. The
+/// comments and other variables already in the file, such as those written by a
+/// plugin, are kept in their original order in the plugin section. Saving the same
+/// variables again leaves the file unchanged.
 pub fn save_environment(env: &Environment, root: &std::path::PathBuf) -> Result<(), Fault> {
-    // create the file
-    let mut env_file = std::fs::File::create(&root.join(".env")).expect("could not create .env file");
-    // prepare the data into a single string for writing
-    let contents = env.iter()
-        .fold(String::new(), |x, y| x + &y.to_string() + &"\n");
-    // write the data to the file
-    env_file.write_all(contents.as_bytes()).expect("failed to write data to .env file");
+    let path = root.join(DOT_ENV_FILE);
+    let existing = match path.exists() {
+        true => std::fs::read_to_string(&path)?,
+        false => String::new(),
+    };
+    std::fs::write(&path, environment_contents(env, &existing))?;
     Ok(())
 }
 
+/// Creates the text of a .env file storing `env` in place of the `existing` text.
+/// 
+/// See `save_environment` for how the existing lines are kept.
+pub fn environment_contents(env: &Environment, existing: &str) -> String {
+    let mut contents = format!("{}\n", ORBIT_SECTION);
+    env.iter().for_each(|e| contents.push_str(&format!("{}\n", e)));
+    let kept = keep_unmanaged(existing, env);
+    if !kept.is_empty() {
+        contents.push_str(&format!("\n{}\n", PLUGIN_SECTION));
+        kept.iter().for_each(|l| contents.push_str(&format!("{}\n", l)));
+    }
+    contents
+}
+
+/// Collects the lines of the .env file `text` that orbit does not manage.
+/// 
+/// Assignments to the variables in `env` or any other variable orbit writes are
+/// removed along with the section headers. Lines appended after the orbit section
+/// are kept.
+fn keep_unmanaged<'a>(text: &'a str, env: &Environment) -> Vec<&'a str> {
+    let mut kept = Vec::new();
+    for line in text.lines().map(|l| l.trim_end()) {
+        let managed = match parse_line(line) {
+            Some(var) => env.get(var.get_key()).is_some() || MANAGED_KEYS.contains(&var.get_key()) || var.get_key().starts_with(ORBIT_GENERIC_PREFIX),
+            None => line == ORBIT_SECTION || line == PLUGIN_SECTION,
+        };
+        if !managed {
+            kept.push(line);
+        }
+    }
+    // blank lines around the kept lines would grow the file on every save
+    while kept.first().map(|l| l.is_empty()).unwrap_or(false) {
+        kept.remove(0);
+    }
+    while kept.last().map(|l| l.is_empty()).unwrap_or(false) {
+        kept.pop();
+    }
+    kept
+}
+
+/// Reads the variable assigned on a line of a .env file.
+/// 
+/// Returns `None` for blank lines, comments, and lines without a `=` delimiter.
+fn parse_line(line: &str) -> Option<EnvVar> {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None
+    }
+    let (name, value) = line.split_once('=')?;
+    match name.trim().is_empty() {
+        true => None,
+        false => Some(EnvVar::new().key(name.trim()).value(value)),
+    }
+}
+
 pub struct Environment(BTreeSet<EnvVar>);
 
 impl Environment {
@@ -199,6 +263,10 @@ impl Environment {
 pub const ORBIT_PLUGIN: &str = "ORBIT_PLUGIN";
 pub const ORBIT_TOP: &str = "ORBIT_TOP";
 pub const ORBIT_BENCH: &str = "ORBIT_BENCH";
+pub const ORBIT_TARGET: &str = "ORBIT_TARGET";
+pub const ORBIT_TARGET_PART: &str = "ORBIT_TARGET_PART";
+pub const ORBIT_INCDIRS: &str = "ORBIT_INCDIRS";
+pub const ORBIT_DEFINES: &str = "ORBIT_DEFINES";
 pub const ORBIT_BUILD_DIR: &str = "ORBIT_BUILD_DIR";
 pub const ORBIT_CACHE: &str = "ORBIT_CACHE";
 pub const ORBIT_HOME: &str = "ORBIT_HOME";
@@ -206,8 +274,70 @@ pub const ORBIT_STORE: &str = "ORBIT_STORE";
 pub const ORBIT_IP_PATH: &str = "ORBIT_IP_PATH";
 pub const ORBIT_DEV_PATH: &str = "ORBIT_DEV_PATH";
 pub const ORBIT_BLUEPRINT: &str = "ORBIT_BLUEPRINT";
+pub const ORBIT_BLUEPRINT_SUMS: &str = "ORBIT_BLUEPRINT_SUMS";
+pub const ORBIT_TEST_EXPORT: &str = "ORBIT_TEST_EXPORT";
+pub const ORBIT_LOG_FILE: &str = "ORBIT_LOG_FILE";
 pub const ORBIT_WIN_LITERAL_CMD: &str = "ORBIT_WIN_LITERAL_CMD";
+pub const ORBIT_VENDOR: &str = "ORBIT_VENDOR";
+pub const ORBIT_VENDOR_PATH: &str = "ORBIT_VENDOR_PATH";
+pub const ORBIT_FETCH_PATH: &str = "ORBIT_FETCH_PATH";
+pub const ORBIT_PLUGIN_PATH: &str = "ORBIT_PLUGIN_PATH";
 
 pub const ORBIT_ENV_PREFIX: &str = "ORBIT_ENV_";
+pub const ORBIT_GENERIC_PREFIX: &str = "ORBIT_GENERIC_";
+
+pub const DOT_ENV_FILE: &str = ".env";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn save_keeps_plugin_lines() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        // a file from before sections were written, with a line added by a plugin
+        std::fs::write(root.join(DOT_ENV_FILE), "ORBIT_BENCH=old_tb\nORBIT_PLUGIN=ghdl\nORBIT_TOP=old\n# set by ghdl\nGHDL_STD=08\n").unwrap();
+
+        let env = Environment::from_vec(vec![
+            EnvVar::new().key(ORBIT_TOP).value("chip"),
+            EnvVar::new().key(ORBIT_BENCH).value("chip_tb"),
+        ]);
+        save_environment(&env, &root).unwrap();
+        let text = std::fs::read_to_string(root.join(DOT_ENV_FILE)).unwrap();
+        assert_eq!(text, format!("{}\nORBIT_BENCH=chip_tb\nORBIT_TOP=chip\n\n{}\n# set by ghdl\nGHDL_STD=08\n", ORBIT_SECTION, PLUGIN_SECTION));
 
-pub const DOT_ENV_FILE: &str = ".env";
\ No newline at end of file
+        // saving again does not change the file
+        save_environment(&env, &root).unwrap();
+        assert_eq!(std::fs::read_to_string(root.join(DOT_ENV_FILE)).unwrap(), text);
+
+        // lines appended by a plugin move to the plugin section
+        std::fs::write(root.join(DOT_ENV_FILE), format!("{}GHDL_WORK=lib\n", text)).unwrap();
+        save_environment(&env, &root).unwrap();
+        let text = std::fs::read_to_string(root.join(DOT_ENV_FILE)).unwrap();
+        assert!(text.ends_with("# set by ghdl\nGHDL_STD=08\nGHDL_WORK=lib\n"));
+
+        // comments are not read as variables
+        let loaded = Environment::new().from_env_file(&root).unwrap();
+        assert_eq!(loaded.iter().count(), 4);
+        assert_eq!(loaded.get("GHDL_STD").unwrap().get_value(), "08");
+        assert!(loaded.get(ORBIT_PLUGIN).is_none());
+    }
+
+    #[test]
+    fn save_replaces_generics() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::write(root.join(DOT_ENV_FILE), format!("{}\nORBIT_GENERIC_WIDTH=8\nORBIT_TOP=chip\n", ORBIT_SECTION)).unwrap();
+
+        // generics from a previous plan are not kept
+        let env = Environment::from_vec(vec![
+            EnvVar::new().key(ORBIT_TOP).value("chip"),
+            EnvVar::new().key("ORBIT_GENERIC_DEPTH").value("16"),
+        ]);
+        save_environment(&env, &root).unwrap();
+        let text = std::fs::read_to_string(root.join(DOT_ENV_FILE)).unwrap();
+        assert_eq!(text, format!("{}\nORBIT_GENERIC_DEPTH=16\nORBIT_TOP=chip\n", ORBIT_SECTION));
+    }
+}
\ No newline at end of file
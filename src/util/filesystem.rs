@@ -7,6 +7,7 @@ use std::path::PathBuf;
 use std::env;
 use crate::core::manifest;
 use crate::core::lockfile;
+use crate::core::config;
 
 use super::anyerror::Fault;
 
@@ -15,15 +16,18 @@ use super::anyerror::Fault;
 /// Returns the resulting list of filepath strings. This function silently skips result errors
 /// while walking. The collected set of paths are also standardized to use forward slashes '/'.
 /// 
-/// Ignores ORBIT_SUM_FILE, .git directory, ORBIT_METADATA_FILE, and IP_LOCK_FILE.
+/// Ignores ORBIT_SUM_FILE, .git directory, ORBIT_METADATA_FILE, IP_LOCK_FILE, and the VENDOR_DIR
+/// and LOCAL_CONFIG_DIR directly under `path`.
 pub fn gather_current_files(path: &std::path::PathBuf) -> Vec<String> {
-    let m = WalkBuilder::new(path)
+    let m = WalkBuilder::new(to_extended(path))
         .hidden(false)
         .git_ignore(true)
         .add_custom_ignore_filename(ORBIT_IGNORE_FILE)
         .filter_entry(|p| {
             match p.file_name().to_str().unwrap() {
-                manifest::ORBIT_SUM_FILE | GIT_DIR | lockfile::IP_LOCK_FILE | manifest::ORBIT_METADATA_FILE => false,
+                manifest::ORBIT_SUM_FILE | manifest::ORBIT_SIGNATURE_FILE | GIT_DIR | lockfile::IP_LOCK_FILE | manifest::ORBIT_METADATA_FILE => false,
+                // vendored dependencies and local configurations are not part of the ip itself
+                manifest::VENDOR_DIR | config::LOCAL_CONFIG_DIR => p.depth() != 1 || !p.path().is_dir(),
                 _ => true,
             }
         })
@@ -32,8 +36,7 @@ pub fn gather_current_files(path: &std::path::PathBuf) -> Vec<String> {
         match result {
             Ok(entry) => {
                 if entry.path().is_file() {
-                    // replace backslash \ with single forward slash /
-                    Some(entry.into_path().display().to_string().replace(r"\", "/"))
+                    Some(to_standard(entry.path()))
                 } else {
                     None
                 }
@@ -46,6 +49,37 @@ pub fn gather_current_files(path: &std::path::PathBuf) -> Vec<String> {
     files
 }
 
+/// Walks `root` for the same files as `gather_current_files`, listing each path
+/// as if `root` were the current working directory (`./...`).
+pub fn gather_files_from(root: &std::path::PathBuf) -> Vec<String> {
+    let base = to_standard(root);
+    let base = base.trim_end_matches('/');
+    gather_current_files(root)
+        .into_iter()
+        .map(|f| match f.strip_prefix(base) {
+            Some(rest) => format!(".{}", rest),
+            None => f,
+        })
+        .collect()
+}
+
+/// Flushes every file under `path` (and the directories themselves where supported)
+/// to the disk.
+pub fn sync_all(path: &std::path::Path) -> Result<(), Fault> {
+    for entry in WalkBuilder::new(to_extended(path)).standard_filters(false).build() {
+        let entry = entry?;
+        if entry.path().is_file() {
+            std::fs::File::open(entry.path())?.sync_all()?;
+        } else if entry.path().is_dir() {
+            // directories cannot be opened as files on every platform
+            if let Ok(dir) = std::fs::File::open(entry.path()) {
+                let _ = dir.sync_all();
+            }
+        }
+    }
+    Ok(())
+}
+
 pub enum Unit {
     MegaBytes,
     Bytes,
@@ -70,7 +104,7 @@ where P: AsRef<Path> {
 /// Attempts to return the executable's path.
 pub fn get_exe_path() -> Result<PathBuf, Fault> {
     match env::current_exe() {    
-        Ok(exe_path) => Ok(std::fs::canonicalize(exe_path)?),
+        Ok(exe_path) => Ok(PathBuf::from(to_standard(&std::fs::canonicalize(exe_path)?))),
         Err(e) => Err(Box::new(e)),
     }
 }
@@ -104,7 +138,7 @@ pub fn remove_base(base: &PathBuf, full: &PathBuf) -> PathBuf {
             Some(full_c) => {
                 match b_comps.next() {
                     Some(base_c) => {
-                        if full_c == base_c { 
+                        if same_component(full_c, base_c) { 
                             continue 
                         } else {
                             break PathBuf::from(full_c)
@@ -125,19 +159,132 @@ pub fn remove_base(base: &PathBuf, full: &PathBuf) -> PathBuf {
 /// Assumes `target` directory does not already exist. Ignores the `.git/` folder
 /// if `ignore_git` is set to `true`. Respects `.gitignore` files.
 /// 
-/// If immutable is `true`, then read_only permissions will be enabled, else the files
-/// will be mutable. Silently skips files that could be changed with mutability/permissions.
+/// The copied files are always writable, even when copied out of a read-only installation.
 pub fn copy(source: &PathBuf, target: &PathBuf, ignore_git: bool) -> Result<(), Fault> {
+    transfer(source, target, ignore_git, &|from, to| {
+        std::fs::copy(from, to)?;
+        let mut perms = std::fs::metadata(to)?.permissions();
+        if perms.readonly() {
+            set_permissions_readonly(&mut perms, false);
+            std::fs::set_permissions(to, perms)?;
+        }
+        Ok(true)
+    })?;
+    Ok(())
+}
+
+/// Sets whether the files under `path` are read-only.
+/// 
+/// Directories keep their permissions so the files can still be removed together
+/// with the directory.
+pub fn set_readonly(path: &Path, readonly: bool) -> Result<(), Fault> {
+    for entry in WalkBuilder::new(to_extended(path)).standard_filters(false).build() {
+        let entry = entry?;
+        if entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            let mut perms = entry.metadata()?.permissions();
+            set_permissions_readonly(&mut perms, readonly);
+            std::fs::set_permissions(entry.path(), perms)?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes the directory at `path` along with its contents, including read-only files.
+pub fn remove_all(path: &Path) -> Result<(), Fault> {
+    // windows refuses to delete read-only files
+    #[cfg(not(unix))]
+    set_readonly(path, false)?;
+    std::fs::remove_dir_all(path)?;
+    Ok(())
+}
+
+/// Updates `perms` to deny or allow writing.
+/// 
+/// On unix, only the owner regains write access so a file never becomes world-writable.
+fn set_permissions_readonly(perms: &mut std::fs::Permissions, readonly: bool) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = perms.mode();
+        perms.set_mode(match readonly {
+            true => mode & !0o222,
+            false => mode | 0o200,
+        });
+    }
+    #[cfg(not(unix))]
+    perms.set_readonly(readonly);
+}
+
+/// Places the files from `source` into the `target` directory by referencing them
+/// instead of copying them.
+/// 
+/// On unix, `target` becomes a symbolic link to `source`. On windows, each file is
+/// hard linked because directory links require elevated privileges. Falls back to
+/// copying when a link cannot be made, such as across volumes.
+/// 
+/// Returns `true` if every file is linked rather than copied.
+pub fn link(source: &PathBuf, target: &PathBuf, ignore_git: bool) -> Result<bool, Fault> {
+    #[cfg(unix)]
+    {
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if std::os::unix::fs::symlink(std::fs::canonicalize(source)?, target).is_ok() {
+            return Ok(true)
+        }
+        copy(source, target, ignore_git)?;
+        Ok(false)
+    }
+    #[cfg(not(unix))]
+    {
+        transfer(source, target, ignore_git, &|from, to| {
+            match std::fs::hard_link(from, to) {
+                Ok(()) => Ok(true),
+                Err(_) => std::fs::copy(from, to).and(Ok(false)),
+            }
+        })
+    }
+}
+
+/// Counts the hard links to the file at `path`.
+/// 
+/// Returns `None` if the platform does not report link counts.
+pub fn link_count(path: &Path) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata(path).ok().map(|m| m.nlink())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+/// Recreates the directories of `source` under `target` and places each file with
+/// `place`, which returns `false` when the file had to be copied.
+/// 
+/// Returns `true` if `place` never reported a copy.
+fn transfer(source: &PathBuf, target: &PathBuf, ignore_git: bool, place: &dyn Fn(&Path, &Path) -> std::io::Result<bool>) -> Result<bool, Fault> {
+    // deeply nested caches can exceed the windows path length limit
+    let source = to_extended(source);
+    let target = to_extended(target);
     // create missing directories to `target`
     std::fs::create_dir_all(&target)?;
     // gather list of paths to copy
     let mut from_paths = Vec::new();
 
     // respect .gitignore by using `WalkBuilder`
-    for result in WalkBuilder::new(&source)
-        .hidden(false)
-        .git_ignore(true)
-        .filter_entry(move |f| (f.file_name() != GIT_DIR || ignore_git == false))
+    let mut walker = WalkBuilder::new(&source);
+    walker.hidden(false).git_ignore(true);
+    // files ignored by .orbitignore are not part of the ip, so they are left out with git
+    if ignore_git == true {
+        walker.add_custom_ignore_filename(ORBIT_IGNORE_FILE);
+    }
+    for result in walker
+        // the local configuration and vendored dependencies belong to the working copy and are left out with git
+        .filter_entry(move |f| ignore_git == false || (f.file_name() != GIT_DIR && (f.depth() != 1 || (f.file_name() != config::LOCAL_CONFIG_DIR && f.file_name() != manifest::VENDOR_DIR))))
         .build() {
             match result {
                 Ok(entry) => from_paths.push(entry.path().to_path_buf()),
@@ -151,14 +298,15 @@ pub fn copy(source: &PathBuf, target: &PathBuf, ignore_git: bool) -> Result<(),
         std::fs::create_dir_all(&to)?;
     }
     // create all missing files
+    let mut linked = true;
     for from in from_paths.iter().filter(|f| f.is_file()) {
         // grab the parent
         if let Some(parent) = from.parent() {
             let to = target.join(remove_base(&source, &parent.to_path_buf())).join(from.file_name().unwrap());
-            std::fs::copy(from, &to)?;
+            linked &= place(from, &to)?;
         }
     }
-    Ok(())
+    Ok(linked)
 }
 
 /// This function creates a universally accepted syntax for a full absolute path.
@@ -191,7 +339,7 @@ pub fn to_absolute(p: PathBuf) -> Result<PathBuf, Fault> {
 /// 
 /// It expands leading '~' to be the user's home directory, or expands leading '.' to the
 /// current directory. It also handles back-tracking '..' and intermediate current directory '.'
-/// notations.
+/// notations. Extended-length prefixes are removed and UNC shares keep their leading `//`.
 /// 
 /// This function is mainly used for display purposes back to the user and is not safe to use
 /// for converting filepaths within logic.
@@ -217,7 +365,7 @@ pub fn normalize_path(p: PathBuf) -> PathBuf {
         } else if root.as_os_str() == OsStr::new(".") {
             for part in std::env::current_dir().unwrap().components() { result.push(c_str(part)) }
         } else {
-            result.push(String::from(root.as_os_str().to_str().unwrap()))
+            result.push(c_str(root))
         }
     }
     // push remaining components
@@ -225,21 +373,190 @@ pub fn normalize_path(p: PathBuf) -> PathBuf {
         result.push(c_str(part))
     }
     // assemble new path
-    let mut first = true;
-    PathBuf::from(result.into_iter().fold(String::new(), |x, y| if first == true { first = false; x + &y } else { x + "/" + &y }).replace("\\", "/").replace("//", "/"))
+    PathBuf::from(standardize(&result.join("/")))
     // @todo: add some fail-safe where if the final path does not exist then return the original path?
 }
 
+/// Writes `p` as a plain path string that uses forward slashes '/'.
+///
+/// Removes the extended-length prefix (`\\?\`) that windows adds to canonical
+/// paths, and writes a UNC share (`\\server\share`) as `//server/share`.
+pub fn to_standard<P: AsRef<Path>>(p: P) -> String {
+    standardize(&p.as_ref().display().to_string())
+}
+
+fn standardize(s: &str) -> String {
+    let s = s.replace('\\', "/");
+    let (unc, rest) = if let Some(rest) = s.strip_prefix("//?/UNC/") {
+        (true, rest)
+    } else if let Some(rest) = s.strip_prefix("//?/") {
+        (false, rest)
+    } else if let Some(rest) = s.strip_prefix("//") {
+        (true, rest)
+    } else {
+        (false, s.as_str())
+    };
+    // collapse repeated separators
+    let mut result = String::with_capacity(rest.len() + 2);
+    if unc {
+        result.push_str("//");
+    }
+    for c in rest.chars() {
+        if c != '/' || !result.ends_with('/') {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Writes the absolute path `p` in the extended-length form (`\\?\C:\...` or
+/// `\\?\UNC\server\share\...`) that lets windows access paths longer than
+/// `MAX_PATH`.
+///
+/// Relative paths and paths on other platforms are returned unchanged.
+pub fn to_extended<P: AsRef<Path>>(p: P) -> PathBuf {
+    let p = p.as_ref();
+    match cfg!(windows) && p.is_absolute() {
+        true => match extend(&p.display().to_string()) {
+            Some(e) => PathBuf::from(e),
+            None => p.to_path_buf(),
+        },
+        false => p.to_path_buf(),
+    }
+}
+
+fn extend(s: &str) -> Option<String> {
+    let plain = standardize(s);
+    // extended-length paths are not normalized by windows, so '.' and '..' must
+    // not remain
+    if plain.split('/').any(|c| c == "." || c == "..") {
+        return None
+    }
+    match plain.strip_prefix("//") {
+        Some(share) => Some(format!(r"\\?\UNC\{}", share.replace('/', r"\"))),
+        None if plain.as_bytes().get(1) == Some(&b':') => Some(format!(r"\\?\{}", plain.replace('/', r"\"))),
+        None => None,
+    }
+}
+
+/// Compares two path components, ignoring case on windows.
+fn same_component(a: &OsStr, b: &OsStr) -> bool {
+    match cfg!(windows) {
+        true => a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase(),
+        false => a == b,
+    }
+}
+
+/// Checks if the paths `a` and `b` name the same location.
+///
+/// Both are compared in their standard form, so extended-length prefixes, separators,
+/// and trailing slashes do not matter. Comparison ignores case on windows.
+pub fn paths_equal<P: AsRef<Path>, Q: AsRef<Path>>(a: P, b: Q) -> bool {
+    let a = to_standard(a);
+    let b = to_standard(b);
+    same_component(OsStr::new(a.trim_end_matches('/')), OsStr::new(b.trim_end_matches('/')))
+}
+
+/// Checks if `path` is `base` or is located under it.
+///
+/// Follows the same rules as `paths_equal` for each component.
+pub fn is_within<P: AsRef<Path>, Q: AsRef<Path>>(base: P, path: Q) -> bool {
+    let base = PathBuf::from(to_standard(base));
+    let path = PathBuf::from(to_standard(path));
+    let mut comps = path.iter();
+    base.iter().all(|b| comps.next().map(|p| same_component(b, p)).unwrap_or(false))
+}
+
+/// Searches for the program `cmd` the same way a process would be spawned.
+/// 
+/// A `cmd` with a directory is checked as a path. Otherwise, each directory in the
+/// PATH environment variable is searched. On windows, the extensions in PATHEXT
+/// and `.bat` are also tried.
+pub fn find_program(cmd: &str) -> Option<PathBuf> {
+    let extensions: Vec<String> = match cfg!(windows) {
+        true => std::iter::once(String::new())
+            .chain(env::var("PATHEXT").unwrap_or_default().split(';').filter(|e| !e.is_empty()).map(|e| e.to_lowercase()))
+            .chain(std::iter::once(String::from(".bat")))
+            .collect(),
+        false => vec![String::new()],
+    };
+    let candidates = |base: PathBuf| -> Option<PathBuf> {
+        extensions.iter()
+            .map(|ext| PathBuf::from(format!("{}{}", base.display(), ext)))
+            .find(|p| p.is_file())
+    };
+    if Path::new(cmd).components().count() > 1 {
+        return candidates(PathBuf::from(cmd))
+    }
+    env::split_paths(&env::var_os("PATH")?).find_map(|dir| candidates(dir.join(cmd)))
+}
+
 /// Executes the process invoking the `cmd` with the following `args`.
 /// 
 /// Performs a fix to allow .bat files to be searched on windows given the option
 /// is enabled through environment variables.
 pub fn invoke(cmd: &String, args: &Vec<String>, try_again: bool) -> std::io::Result<std::process::Child> {
-    match std::process::Command::new(cmd)
-        .args(args)
-        .stdout(std::process::Stdio::inherit())
-        .stderr(std::process::Stdio::inherit())
-        .spawn() { 
+    spawn(cmd, args, try_again, &|proc| {
+        proc.stdout(std::process::Stdio::inherit())
+            .stderr(std::process::Stdio::inherit());
+    })
+}
+
+/// Attempts to execute a command with its output written to `log` and the additional
+/// environment variables `envs`.
+/// 
+/// Performs the same .bat file fix as `invoke`.
+pub fn invoke_logged(cmd: &String, args: &Vec<String>, envs: &[(String, String)], log: &std::fs::File, try_again: bool) -> std::io::Result<std::process::Child> {
+    spawn(cmd, args, try_again, &|proc| {
+        proc.envs(envs.iter().map(|(k, v)| (k, v)));
+        if let (Ok(out), Ok(err)) = (log.try_clone(), log.try_clone()) {
+            proc.stdout(out).stderr(err);
+        }
+    })
+}
+
+/// Executes the process invoking the `cmd` with the following `args`, copying its
+/// output to `log` while still displaying it.
+/// 
+/// Waits for the process to exit. Performs the same .bat file fix as `invoke`.
+pub fn invoke_teed(cmd: &String, args: &Vec<String>, log: &std::fs::File, try_again: bool) -> std::io::Result<std::process::ExitStatus> {
+    let mut proc = spawn(cmd, args, try_again, &|proc| {
+        proc.stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
+    })?;
+    let out = proc.stdout.take().unwrap();
+    let err = proc.stderr.take().unwrap();
+    let (out_log, err_log) = (log.try_clone()?, log.try_clone()?);
+    std::thread::scope(|s| {
+        s.spawn(move || tee(out, std::io::stdout(), out_log));
+        s.spawn(move || tee(err, std::io::stderr(), err_log));
+    });
+    proc.wait()
+}
+
+/// Copies everything read from `source` to both `console` and `log` until `source` closes.
+/// 
+/// Stops writing to the log if it fails so the output keeps reaching the console.
+fn tee(mut source: impl std::io::Read, mut console: impl std::io::Write, mut log: std::fs::File) {
+    let mut buf = [0u8; 4096];
+    let mut logging = true;
+    loop {
+        let n = match source.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        let _ = console.write_all(&buf[..n]).and_then(|_| console.flush());
+        if logging {
+            logging = std::io::Write::write_all(&mut log, &buf[..n]).is_ok();
+        }
+    }
+}
+
+fn spawn(cmd: &String, args: &Vec<String>, try_again: bool, configure: &dyn Fn(&mut std::process::Command)) -> std::io::Result<std::process::Child> {
+    let mut proc = std::process::Command::new(cmd);
+    proc.args(args);
+    configure(&mut proc);
+    match proc.spawn() { 
             Ok(r) => Ok(r),
             Err(e) => {
                 // check if there is no file extension
@@ -248,7 +565,7 @@ pub fn invoke(cmd: &String, args: &Vec<String>, try_again: bool) -> std::io::Res
                     None => true,
                 };
                 if repeat == true && e.kind() == std::io::ErrorKind::NotFound {
-                    invoke(&format!("{}.bat", cmd), args, false)
+                    spawn(&format!("{}.bat", cmd), args, false, configure)
                 } else {
                     Err(e)
                 }
@@ -257,13 +574,24 @@ pub fn invoke(cmd: &String, args: &Vec<String>, try_again: bool) -> std::io::Res
 }
 
 const GIT_DIR: &str = ".git";
-const ORBIT_IGNORE_FILE: &str = ".orbitignore";
+pub const ORBIT_IGNORE_FILE: &str = ".orbitignore";
 
 #[cfg(test)]
 mod test {
     use tempfile::{tempdir};
     use super::*;
     
+    #[test]
+    fn tee_copies_to_console_and_log() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("build.log");
+        let log = std::fs::File::create(&path).unwrap();
+        let mut console = Vec::new();
+        tee(&b"analyzing top.vhd\n"[..], &mut console, log);
+        assert_eq!(console, b"analyzing top.vhd\n");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "analyzing top.vhd\n");
+    }
+
     #[test]
     fn resolve_path_simple() {
         let rel_root = std::env::current_dir().unwrap();
@@ -312,6 +640,67 @@ mod test {
         copy(&source, &target.as_ref().to_path_buf(), true).unwrap();
     }
 
+    #[test]
+    fn orbitignore() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::create_dir_all(root.join("rtl")).unwrap();
+        std::fs::create_dir_all(root.join("scratch")).unwrap();
+        std::fs::write(root.join("rtl").join("and_gate.vhd"), "-- and gate").unwrap();
+        std::fs::write(root.join("rtl").join("and_gate.vhd.bak"), "-- old and gate").unwrap();
+        std::fs::write(root.join("scratch").join("notes.txt"), "todo").unwrap();
+        std::fs::write(root.join(ORBIT_IGNORE_FILE), "scratch/\n").unwrap();
+        std::fs::write(root.join("rtl").join(ORBIT_IGNORE_FILE), "*.bak\n").unwrap();
+
+        let files = gather_files_from(&root);
+        assert_eq!(files, vec!["./.orbitignore", "./rtl/.orbitignore", "./rtl/and_gate.vhd"]);
+        // changing an ignored file keeps the same checksum
+        let sum = crate::util::checksum::checksum_at(&root, &files);
+        std::fs::write(root.join("scratch").join("notes.txt"), "done").unwrap();
+        assert_eq!(crate::util::checksum::checksum_at(&root, &gather_files_from(&root)), sum);
+        // ignored files are left out of an installation
+        let target = tempdir().unwrap();
+        copy(&root, &target.path().to_path_buf(), true).unwrap();
+        assert_eq!(target.path().join("scratch").exists(), false);
+        assert_eq!(target.path().join("rtl").join("and_gate.vhd.bak").exists(), false);
+        assert_eq!(gather_files_from(&target.path().to_path_buf()), files);
+    }
+
+    #[test]
+    fn vendored_dependencies() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        // a vendor/ directory holds the user's own sources
+        std::fs::create_dir_all(root.join("rtl")).unwrap();
+        std::fs::create_dir_all(root.join("vendor")).unwrap();
+        std::fs::create_dir_all(root.join(manifest::VENDOR_DIR).join("gates")).unwrap();
+        std::fs::write(root.join("rtl").join("and_gate.vhd"), "-- and gate").unwrap();
+        std::fs::write(root.join("vendor").join("vend.vhd"), "-- vendor's unit").unwrap();
+        std::fs::write(root.join(manifest::VENDOR_DIR).join("gates").join("or_gate.vhd"), "-- dependency").unwrap();
+
+        let files = gather_files_from(&root);
+        assert_eq!(files, vec!["./rtl/and_gate.vhd", "./vendor/vend.vhd"]);
+        // changing a user's vendor/ file changes the checksum
+        let sum = crate::util::checksum::checksum_at(&root, &files);
+        std::fs::write(root.join("vendor").join("vend.vhd"), "-- vendor's unit (changed)").unwrap();
+        assert_ne!(crate::util::checksum::checksum_at(&root, &gather_files_from(&root)), sum);
+        // the installation holds the same files the checksum covers
+        let target = tempdir().unwrap();
+        copy(&root, &target.path().to_path_buf(), true).unwrap();
+        assert_eq!(target.path().join(manifest::VENDOR_DIR).exists(), false);
+        assert_eq!(gather_files_from(&target.path().to_path_buf()), files);
+    }
+
+    #[test]
+    fn link_all() {
+        let source = PathBuf::from("./tests/data/poems");
+        let target = tempdir().unwrap();
+        let slot = target.path().join("poems");
+        assert!(link(&source, &slot, true).unwrap());
+        assert_eq!(gather_files_from(&slot), gather_files_from(&source));
+        assert_eq!(std::fs::read_to_string(slot.join("file1.txt")).unwrap(), std::fs::read_to_string(source.join("file1.txt")).unwrap());
+    }
+
     // only works on windows system
     #[test]
     #[ignore]
@@ -325,4 +714,79 @@ mod test {
         let path = PathBuf::from("./readme.md");
         assert_eq!(to_absolute(path).unwrap(), PathBuf::from("/Users/cruskin/Develop/rust/orbit/README.md"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn standard_paths() {
+        assert_eq!(standardize(r"\\?\C:\users\kepler\ip"), "C:/users/kepler/ip");
+        assert_eq!(standardize(r"\\?\UNC\server\share\ip"), "//server/share/ip");
+        assert_eq!(standardize(r"\\server\share\ip\"), "//server/share/ip/");
+        assert_eq!(standardize("/home//kepler/./ip"), "/home/kepler/./ip");
+        assert_eq!(to_standard(PathBuf::from("c:/users/kepler")), "c:/users/kepler");
+    }
+
+    #[test]
+    fn extended_paths() {
+        assert_eq!(extend("C:/users/kepler/ip").unwrap(), r"\\?\C:\users\kepler\ip");
+        assert_eq!(extend(r"\\server\share\ip").unwrap(), r"\\?\UNC\server\share\ip");
+        // already extended paths keep a single prefix
+        assert_eq!(extend(r"\\?\C:\ip").unwrap(), r"\\?\C:\ip");
+        assert_eq!(extend(r"\\?\UNC\server\share").unwrap(), r"\\?\UNC\server\share");
+        assert_eq!(extend("C:/users/../ip"), None);
+        assert_eq!(extend("/home/kepler"), None);
+        // no other platform needs a prefix
+        if !cfg!(windows) {
+            assert_eq!(to_extended("/home/kepler"), PathBuf::from("/home/kepler"));
+        }
+    }
+
+    #[test]
+    fn compare_paths() {
+        assert!(paths_equal("/home/kepler/ip/", "/home//kepler/ip"));
+        assert!(paths_equal(r"\\?\C:\users\ip", "C:/users/ip"));
+        assert!(!paths_equal("/home/kepler/ip", "/home/kepler/ip2"));
+        assert_eq!(paths_equal("C:/Users/IP", "c:/users/ip"), cfg!(windows));
+
+        assert!(is_within("/home/kepler", "/home/kepler/ip/src"));
+        assert!(is_within(r"\\?\C:\users", "C:/users/ip"));
+        assert!(!is_within("/home/kepler/ip", "/home/kepler"));
+        assert!(!is_within("/home/kepler", "/home/kepl"));
+    }
+
+    #[test]
+    fn find_programs() {
+        assert_eq!(find_program("orbit-program-that-does-not-exist"), None);
+        assert_eq!(find_program("./Cargo.toml"), Some(PathBuf::from("./Cargo.toml")));
+        assert_eq!(find_program("./src/missing.rs"), None);
+    }
+
+    #[test]
+    fn gather_files_relative_to_root() {
+        let files = gather_files_from(&PathBuf::from("./tests/data/poems/"));
+        assert_eq!(files, vec![
+            "./file1.txt".to_owned(),
+            "./file2.txt".to_owned(),
+            "./file3.txt".to_owned(),
+            "./file3copy.txt".to_owned(),
+        ]);
+    }
+
+    #[test]
+    fn readonly_installation() {
+        let dir = tempdir().unwrap();
+        let slot = dir.path().join("gates-1.0.0");
+        std::fs::create_dir_all(slot.join("rtl")).unwrap();
+        std::fs::write(slot.join("rtl/and_gate.vhd"), "entity and_gate is end;\n").unwrap();
+        set_readonly(&slot, true).unwrap();
+        let readonly = |p: &Path| std::fs::metadata(p).unwrap().permissions().readonly();
+        assert!(readonly(&slot.join("rtl/and_gate.vhd")));
+        assert!(!readonly(&slot.join("rtl")));
+
+        // copies are working copies
+        let copy_path = dir.path().join("copy");
+        copy(&slot, &copy_path, true).unwrap();
+        assert!(!readonly(&copy_path.join("rtl/and_gate.vhd")));
+
+        remove_all(&slot).unwrap();
+        assert!(!slot.exists());
+    }
+}
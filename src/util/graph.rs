@@ -1,6 +1,6 @@
 /// Basic graph data structure
 /// - source: http://smallcultfollowing.com/babysteps/blog/2015/04/06/modeling-graphs-in-rust-using-vector-indices/
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 
 use super::graphmap::GraphMap;
 
@@ -168,14 +168,108 @@ impl<V, E> Graph<V, E> {
 
     /// Checks if the graph contains a cycle.
     pub fn is_cyclic(&self) -> bool {
-        if self.is_empty() { return false }
+        // self-loops are never added, so only a component of several nodes has a cycle
+        self.strongly_connected_components().iter().any(|c| c.len() > 1)
+    }
 
-        for i in 0..self.node_count() {
-            if self.in_degree(i) == 0 {
-                return false
+    /// Groups the nodes into strongly connected components using Tarjan's algorithm.
+    /// 
+    /// Every node belongs to exactly one component, which lists its nodes in
+    /// ascending order. Components are returned in reverse topological order.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeIndex>> {
+        let count = self.node_count();
+        let mut index: Vec<Option<usize>> = vec![None; count];
+        let mut low: Vec<usize> = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack: Vec<NodeIndex> = Vec::new();
+        let mut components = Vec::new();
+        let mut next = 0;
+        for root in 0..count {
+            if index[root].is_some() { continue }
+            // each frame holds a node and the successors it has left to visit
+            let mut frames: Vec<(NodeIndex, Vec<NodeIndex>)> = Vec::new();
+            let mut visit = Some(root);
+            loop {
+                if let Some(v) = visit.take() {
+                    index[v] = Some(next);
+                    low[v] = next;
+                    next += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                    frames.push((v, self.successors(v).collect()));
+                }
+                let (v, w) = match frames.last_mut() {
+                    Some((v, remaining)) => (*v, remaining.pop()),
+                    None => break,
+                };
+                match w {
+                    Some(w) => match index[w] {
+                        None => visit = Some(w),
+                        Some(i) => if on_stack[w] { low[v] = low[v].min(i) },
+                    },
+                    // every successor is visited
+                    None => {
+                        frames.pop();
+                        if let Some((u, _)) = frames.last() {
+                            low[*u] = low[*u].min(low[v]);
+                        }
+                        if index[v] == Some(low[v]) {
+                            let mut component = Vec::new();
+                            while let Some(w) = stack.pop() {
+                                on_stack[w] = false;
+                                component.push(w);
+                                if w == v { break }
+                            }
+                            component.sort();
+                            components.push(component);
+                        }
+                    }
+                }
             }
         }
-        true
+        components
+    }
+
+    /// Finds a shortest cycle in the graph.
+    /// 
+    /// Returns the nodes along the cycle in the direction of its edges, where the
+    /// last node has an edge back to the first node. Returns `None` if the graph
+    /// is acyclic.
+    pub fn find_cycle(&self) -> Option<Vec<NodeIndex>> {
+        let mut shortest: Option<Vec<NodeIndex>> = None;
+        for component in self.strongly_connected_components().into_iter().filter(|c| c.len() > 1) {
+            let members: HashSet<NodeIndex> = component.iter().copied().collect();
+            for &start in &component {
+                // breadth-first search within the component for the nearest way back to `start`
+                let mut parent: Vec<Option<NodeIndex>> = vec![None; self.node_count()];
+                let mut queue = VecDeque::from([start]);
+                let mut last = None;
+                while let Some(v) = queue.pop_front() {
+                    if self.successors(v).any(|w| w == start) {
+                        last = Some(v);
+                        break
+                    }
+                    for w in self.successors(v) {
+                        if w != start && members.contains(&w) && parent[w].is_none() {
+                            parent[w] = Some(v);
+                            queue.push_back(w);
+                        }
+                    }
+                }
+                // walk back from the node closing the cycle
+                let mut cycle = Vec::new();
+                let mut node = last;
+                while let Some(v) = node {
+                    cycle.push(v);
+                    node = parent[v];
+                }
+                cycle.reverse();
+                if !cycle.is_empty() && shortest.as_ref().map(|s| cycle.len() < s.len()).unwrap_or(true) {
+                    shortest = Some(cycle);
+                }
+            }
+        }
+        shortest
     }
 
     /// Determines which node has zero outgoing edges as the 'root' node.
@@ -594,6 +688,32 @@ mod test {
         assert_eq!(g.is_cyclic(), false);
     }
 
+    #[test]
+    fn strongly_connected_components() {
+        let g = basic_graph();
+        assert_eq!(g.strongly_connected_components(), vec![vec![2], vec![1], vec![3], vec![0]]);
+
+        // n1 -> n2 -> n3 -> n1
+        let mut g = basic_graph();
+        g.add_edge(2, 3, ());
+        g.add_edge(3, 1, ());
+        assert_eq!(g.strongly_connected_components(), vec![vec![1, 2, 3], vec![0]]);
+    }
+
+    #[test]
+    fn find_cycle() {
+        let g = binary_tree();
+        assert_eq!(g.find_cycle(), None);
+
+        // n0 -> n3 -> n2 -> n0 is as short as n0 -> n1 -> n2 -> n0
+        let mut g = basic_graph();
+        g.add_edge(2, 0, ());
+        assert_eq!(g.find_cycle(), Some(vec![0, 3, 2]));
+        // the shortest cycle is reported
+        g.add_edge(3, 0, ());
+        assert_eq!(g.find_cycle(), Some(vec![0, 3]));
+    }
+
     #[test]
     fn topological_sort() {
         let mut g = basic_graph();
@@ -676,14 +796,14 @@ mod test {
         let g = basic_graph();
         assert_eq!(g.successors(0).collect::<Vec<NodeIndex>>(), vec![3, 1]);
         assert_eq!(g.successors(1).collect::<Vec<NodeIndex>>(), vec![2]);
-        assert_eq!(g.successors(2).collect::<Vec<NodeIndex>>(), vec![]);
+        assert_eq!(g.successors(2).collect::<Vec<NodeIndex>>(), Vec::<NodeIndex>::new());
         assert_eq!(g.successors(3).collect::<Vec<NodeIndex>>(), vec![2]);
     }
 
     #[test]
     fn predecessors() {
         let g = basic_graph();
-        assert_eq!(g.predecessors(0).collect::<Vec<usize>>(), vec![]);
+        assert_eq!(g.predecessors(0).collect::<Vec<usize>>(), Vec::<usize>::new());
         assert_eq!(g.predecessors(1).collect::<Vec<usize>>(), vec![0]);
         assert_eq!(g.predecessors(2).collect::<Vec<usize>>(), vec![3, 1]);
         assert_eq!(g.predecessors(3).collect::<Vec<usize>>(), vec![0]);
@@ -0,0 +1,37 @@
+//! A process-wide cache of shared strings.
+//!
+//! A project names the same design units, libraries, and signals many times
+//! over. Interning each spelling once lets every copy share one allocation, so
+//! cloning a name only increments a reference count.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock};
+
+static STRINGS: OnceLock<Mutex<HashSet<Arc<str>>>> = OnceLock::new();
+
+/// Returns the shared copy of `s`, adding it to the cache if it is not there yet.
+pub fn intern(s: &str) -> Arc<str> {
+    let mut strings = STRINGS.get_or_init(|| Mutex::new(HashSet::new())).lock().unwrap();
+    match strings.get(s) {
+        Some(shared) => shared.clone(),
+        None => {
+            let shared: Arc<str> = Arc::from(s);
+            strings.insert(shared.clone());
+            shared
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shares_allocation() {
+        let a = intern("nor_gate");
+        let b = intern(&String::from("nor_gate"));
+        assert!(Arc::ptr_eq(&a, &b));
+        // spellings are kept exactly as written
+        assert_eq!(Arc::ptr_eq(&a, &intern("NOR_GATE")), false);
+    }
+}
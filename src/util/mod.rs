@@ -9,4 +9,11 @@ pub mod checksum;
 pub mod filesystem;
 pub mod environment;
 pub mod graphmap;
-pub mod url;
\ No newline at end of file
+pub mod url;
+pub mod progress;
+pub mod profile;
+pub mod diff;
+pub mod writeguard;
+pub mod theme;
+pub mod intern;
+pub mod buildinfo;
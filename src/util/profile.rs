@@ -0,0 +1,150 @@
+//! Timing of the major phases of a command (set by `--profile`).
+//!
+//! Phases are only recorded once profiling is enabled, so commands mark their
+//! phases unconditionally. A phase entered more than once, such as parsing each
+//! file, accumulates its time under a single row of the breakdown.
+
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use crate::util::anyerror::AnyError;
+
+/// Global switch to record phases (set by `--profile`).
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The recorded phases in the order they were first entered.
+static PHASES: Mutex<Vec<Record>> = Mutex::new(Vec::new());
+
+/// How the breakdown is written.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            _ => Err(AnyError(format!("unknown format '{}'; expecting 'table' or 'json'", s))),
+        }
+    }
+}
+
+/// The time spent in a phase across every time it was entered.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Record {
+    name: &'static str,
+    calls: usize,
+    elapsed: Duration,
+}
+
+/// Starts recording phases for the remainder of the process.
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Measures a phase from when it starts until it is dropped.
+pub struct Phase {
+    name: &'static str,
+    clock: Option<Instant>,
+}
+
+impl Phase {
+    pub fn start(name: &'static str) -> Self {
+        Self { name, clock: is_enabled().then(Instant::now) }
+    }
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        if let Some(clock) = self.clock {
+            record(self.name, clock.elapsed());
+        }
+    }
+}
+
+/// Runs `f` as the phase `name`.
+pub fn time<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    let _phase = Phase::start(name);
+    f()
+}
+
+fn record(name: &'static str, elapsed: Duration) {
+    let mut phases = PHASES.lock().unwrap();
+    match phases.iter_mut().find(|r| r.name == name) {
+        Some(r) => {
+            r.calls += 1;
+            r.elapsed += elapsed;
+        },
+        None => phases.push(Record { name, calls: 1, elapsed }),
+    }
+}
+
+/// Copies the phases recorded so far, leaving them to be reported again.
+pub fn snapshot() -> Vec<Record> {
+    PHASES.lock().unwrap().clone()
+}
+
+/// Takes the phases recorded so far.
+pub fn take() -> Vec<Record> {
+    std::mem::take(&mut *PHASES.lock().unwrap())
+}
+
+/// Writes the breakdown of the `phases` of a command that took `total` time.
+pub fn fmt(phases: &[Record], total: Duration, format: Format) -> String {
+    let ms = |d: &Duration| d.as_secs_f64() * 1000.0;
+    let share = |d: &Duration| match total.is_zero() {
+        true => 0.0,
+        false => d.as_secs_f64() / total.as_secs_f64() * 100.0,
+    };
+    match format {
+        Format::Table => {
+            let mut table = format!("\
+{:<18}{:<7}{:<12}{}
+{:->18}{4:->7}{4:->12}{:->7}\n",
+                "Phase", "Calls", "Time (ms)", "Share", " ", "");
+            for r in phases {
+                table.push_str(&format!("{:<18}{:<7}{:<12.2}{:.1}%\n", r.name, r.calls, ms(&r.elapsed), share(&r.elapsed)));
+            }
+            table.push_str(&format!("{:<18}{:<7}{:.2}\n", "total", "", ms(&total)));
+            table
+        },
+        Format::Json => {
+            let rows: Vec<String> = phases.iter()
+                .map(|r| format!("{{\"name\":\"{}\",\"calls\":{},\"ms\":{:.3}}}", r.name, r.calls, ms(&r.elapsed)))
+                .collect();
+            format!("{{\"total_ms\":{:.3},\"phases\":[{}]}}\n", ms(&total), rows.join(","))
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fmt_breakdown() {
+        let phases = vec![
+            Record { name: "catalog scan", calls: 3, elapsed: Duration::from_millis(25) },
+            Record { name: "parse", calls: 12, elapsed: Duration::from_millis(50) },
+        ];
+        let total = Duration::from_millis(100);
+        assert_eq!(fmt(&phases, total, Format::Table), "\
+Phase             Calls  Time (ms)   Share
+----------------- ------ ----------- -------
+catalog scan      3      25.00       25.0%
+parse             12     50.00       50.0%
+total                    100.00
+");
+        assert_eq!(fmt(&phases, total, Format::Json), "{\"total_ms\":100.000,\"phases\":[{\"name\":\"catalog scan\",\"calls\":3,\"ms\":25.000},{\"name\":\"parse\",\"calls\":12,\"ms\":50.000}]}\n");
+    }
+}
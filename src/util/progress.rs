@@ -0,0 +1,220 @@
+use std::cell::Cell;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Global switch to suppress all progress reporting (set by `--quiet`).
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Per-thread switch to suppress progress reporting (set by worker threads).
+    static THREAD_QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Suppresses (or re-enables) all progress output for the remainder of the process.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::SeqCst);
+}
+
+/// Suppresses (or re-enables) progress output from the calling thread only.
+/// 
+/// Worker threads use this so their spinners do not compete with the progress
+/// reported by the thread that spawned them.
+pub fn set_thread_quiet(quiet: bool) {
+    THREAD_QUIET.with(|q| q.set(quiet));
+}
+
+/// Checks if progress output is suppressed for the calling thread.
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::SeqCst) || THREAD_QUIET.with(|q| q.get())
+}
+
+/// The style progress is reported in for the current process.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Mode {
+    /// animated spinners and bars redrawn on a single line
+    Interactive,
+    /// one plain line of text per event
+    Plain,
+    /// no output
+    Silent,
+}
+
+impl Mode {
+    /// Determines the mode from the `--quiet` setting, stderr being a terminal,
+    /// and the `NO_COLOR` environment variable.
+    pub fn detect() -> Self {
+        if is_quiet() {
+            Self::Silent
+        } else if !std::io::stderr().is_terminal() || std::env::var_os("NO_COLOR").is_some() {
+            Self::Plain
+        } else {
+            Self::Interactive
+        }
+    }
+}
+
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+const SPINNER_TICK: Duration = Duration::from_millis(100);
+
+/// Reports an ongoing operation of unknown length.
+///
+/// In interactive mode, a background thread animates the spinner until
+/// `finish` is called.
+pub struct Spinner {
+    message: String,
+    mode: Mode,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    /// Starts a spinner displaying `msg`. In plain mode, `msg` is printed once.
+    pub fn new(msg: &str) -> Self {
+        Self::start(msg, false)
+    }
+
+    /// Starts a spinner displaying `msg` that leaves no output behind.
+    ///
+    /// Useful for quick operations where a plain line of text would be noise
+    /// (only visible in interactive mode).
+    pub fn transient(msg: &str) -> Self {
+        Self::start(msg, true)
+    }
+
+    fn start(msg: &str, transient: bool) -> Self {
+        let mode = Mode::detect();
+        let running = Arc::new(AtomicBool::new(true));
+        let handle = match mode {
+            Mode::Interactive => {
+                let flag = running.clone();
+                let text = msg.to_string();
+                Some(std::thread::spawn(move || {
+                    let mut frame = 0;
+                    while flag.load(Ordering::SeqCst) {
+                        eprint!("\r{} {}", SPINNER_FRAMES[frame % SPINNER_FRAMES.len()], text);
+                        let _ = std::io::stderr().flush();
+                        frame += 1;
+                        std::thread::sleep(SPINNER_TICK);
+                    }
+                }))
+            }
+            Mode::Plain => {
+                if !transient {
+                    eprintln!("info: {}", msg);
+                }
+                None
+            }
+            Mode::Silent => None,
+        };
+        Self { message: msg.to_string(), mode, running, handle }
+    }
+
+    /// Stops the spinner. If `msg` is given, it replaces the spinner's line as a
+    /// completed status.
+    ///
+    /// In plain mode the starting message already serves as the record, so `msg`
+    /// is not printed.
+    pub fn finish(mut self, msg: Option<&str>) {
+        self.stop();
+        if self.mode == Mode::Interactive {
+            clear_line(self.message.len() + 2);
+            if let Some(m) = msg {
+                eprintln!("info: {}", m);
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(h) = self.handle.take() {
+            let _ = h.join();
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        // clean up the line if the spinner was abandoned (such as on an error)
+        if self.handle.is_some() {
+            self.stop();
+            clear_line(self.message.len() + 2);
+        }
+    }
+}
+
+/// Reports an operation with a known number of steps.
+pub struct ProgressBar {
+    label: String,
+    total: usize,
+    current: usize,
+    mode: Mode,
+}
+
+impl ProgressBar {
+    const WIDTH: usize = 24;
+
+    /// Creates a new progress bar for `total` steps described by `label`.
+    pub fn new(label: &str, total: usize) -> Self {
+        Self { label: label.to_string(), total, current: 0, mode: Mode::detect() }
+    }
+
+    /// Advances the bar by one step while describing the step with `msg`.
+    pub fn inc(&mut self, msg: &str) {
+        self.current += 1;
+        match self.mode {
+            Mode::Interactive => {
+                let filled = match self.total {
+                    0 => Self::WIDTH,
+                    _ => (self.current * Self::WIDTH) / self.total,
+                };
+                eprint!("\r\x1b[2K{} [{}{}] {}/{} {}",
+                    self.label,
+                    "=".repeat(filled),
+                    " ".repeat(Self::WIDTH - filled.min(Self::WIDTH)),
+                    self.current,
+                    self.total,
+                    msg
+                );
+                let _ = std::io::stderr().flush();
+            }
+            Mode::Plain => eprintln!("info: [{}/{}] {} {}", self.current, self.total, self.label, msg),
+            Mode::Silent => (),
+        }
+    }
+
+    /// Completes the progress bar and moves output to the next line.
+    pub fn finish(self) {
+        if self.mode == Mode::Interactive {
+            eprintln!();
+        }
+    }
+}
+
+/// Erases the current terminal line of `len` characters.
+fn clear_line(len: usize) {
+    eprint!("\r{}\r", " ".repeat(len));
+    let _ = std::io::stderr().flush();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quiet_is_silent() {
+        set_quiet(true);
+        assert_eq!(Mode::detect(), Mode::Silent);
+        let mut bar = ProgressBar::new("installing", 2);
+        bar.inc("a");
+        bar.inc("b");
+        assert_eq!(bar.current, 2);
+        bar.finish();
+        Spinner::new("fetching").finish(Some("done"));
+        set_quiet(false);
+        assert_ne!(Mode::detect(), Mode::Silent);
+    }
+}
\ No newline at end of file
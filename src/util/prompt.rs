@@ -1,5 +1,5 @@
 use std::io;
-use std::io::{Error, Read};
+use std::io::{Error, IsTerminal, Read};
 use colored::ColoredString;
 use colored::Colorize;
 
@@ -20,13 +20,16 @@ pub fn prompt(s: &str) -> Result<bool, Error> {
 }
 
 /// Infinitely loops until a valid response is entered. "Y\n" and "\n" map to `true`, while
-/// "N\n" maps to `false`.
+/// "N\n" maps to `false`. Reaching the end of the input maps to `false`.
 /// 
 /// Also supports checking windows-style line endings `\r\n`.
 fn check_for_response(input: &mut (impl Read + std::io::BufRead)) -> Result<bool, Error> {
     let mut buffer: String = String::new();
     loop {
-        input.read_line(&mut buffer)?;
+        // no answer can be given without input
+        if input.read_line(&mut buffer)? == 0 {
+            break Ok(false)
+        }
         let result = match buffer.to_uppercase().as_ref() {
             "\r\n" | "\n" | "Y\n" | "Y\r\n" => Some(true),
             "N\n" | "N\r\n" => Some(false),
@@ -41,6 +44,42 @@ fn check_for_response(input: &mut (impl Read + std::io::BufRead)) -> Result<bool
     }
 }
 
+/// Checks if the user can answer prompts: both the input and output must be a terminal.
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Outputs the text `s` followed by the numbered `options` and waits for one to be chosen.
+/// 
+/// Returns the index of the chosen option, or `None` if no option was chosen.
+pub fn select(s: &str, options: &[String]) -> Result<Option<usize>, Error> {
+    println!("{}:", s);
+    for (i, option) in options.iter().enumerate() {
+        println!("  {}) {}", i + 1, option);
+    }
+    println!("enter a number (or nothing to cancel):");
+    check_for_selection(&mut io::stdin().lock(), options.len())
+}
+
+/// Infinitely loops until a number from 1 to `count` is entered. An empty line or
+/// reaching the end of the input maps to `None`.
+fn check_for_selection(input: &mut impl std::io::BufRead, count: usize) -> Result<Option<usize>, Error> {
+    let mut buffer: String = String::new();
+    loop {
+        if input.read_line(&mut buffer)? == 0 {
+            break Ok(None)
+        }
+        match buffer.trim() {
+            "" => break Ok(None),
+            n => match n.parse::<usize>() {
+                Ok(i) if (1..=count).contains(&i) => break Ok(Some(i - 1)),
+                _ => println!("enter a number from 1 to {}:", count),
+            }
+        }
+        buffer.clear();
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -68,4 +107,18 @@ mod test {
         let r = check_for_response(&mut "N\r\n".as_bytes()).unwrap();
         assert_eq!(r, false);
     }
+
+    #[test]
+    fn end_of_input() {
+        assert!(!check_for_response(&mut "".as_bytes()).unwrap());
+        assert!(check_for_response(&mut "maybe\ny\n".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn selection() {
+        assert_eq!(check_for_selection(&mut "2\n".as_bytes(), 3).unwrap(), Some(1));
+        assert_eq!(check_for_selection(&mut "0\nfour\n3\r\n".as_bytes(), 3).unwrap(), Some(2));
+        assert_eq!(check_for_selection(&mut "\n".as_bytes(), 3).unwrap(), None);
+        assert_eq!(check_for_selection(&mut "5\n".as_bytes(), 3).unwrap(), None);
+    }
 }
\ No newline at end of file
@@ -74,126 +74,160 @@ impl Display for Sha256Hash {
 
 /// Compute the SHA-256 hash function for a slice of bytes.
 pub fn compute_sha256(s: &[u8]) -> Sha256Hash {
-    // [1] preprocessing
-    // convert the string into bytes
-    let mut bytes = s.to_vec();
-    // compute the input bytes total length to store in 8 bytes
-    let message_len: u64 = (bytes.len() as u64) * 8;
-    // append a single '1' as 1000 0000 (0x80)
-    bytes.push(128);
-    
-    // small boost if vector has not already reserved enough space for adding another chunk
-    bytes.reserve(64 - (bytes.len() % 64));
+    let mut hasher = Sha256::new();
+    hasher.update(s);
+    hasher.finish()
+}
 
-    // pad with zeros until data is a multiple of 512 -> 64 bytes
-    let zero_cnt_bytes = {
-        let zero_cnt = 512 - ((bytes.len()*8 + 64) % 512);
-        if zero_cnt == 512 { 0 } else { zero_cnt }
-    } / 8;
+/// Computes a SHA-256 digest incrementally over data given in pieces, such as
+/// the chunks of a file read from disk.
+/// 
+/// Only the current (partially filled) 64-byte chunk is kept in memory.
+#[derive(Debug, Clone)]
+pub struct Sha256 {
+    hashes: [u32; 8],
+    chunk: [u8; 64],
+    chunk_len: usize,
+    message_len: u64,
+}
 
-    for _ in 0..zero_cnt_bytes { bytes.push(0); }
-    
-    // append 64 bits to the end, where 64 bits represent integer length of original input in binary
-    {
-        // store as big-endian
-        for i in (0..8).rev() {
-            bytes.push((message_len >> (8*i)) as u8);
-        }
-    }   
-    assert_eq!(bytes.len() % 64, 0); // 512 bits -> 64 bytes
-
-    // [2] initialize hash values
-    let mut hashes: [u32; 8] = [
-        0x6a09e667,
-        0xbb67ae85,
-        0x3c6ef372,
-        0xa54ff53a,
-        0x510e527f,
-        0x9b05688c,
-        0x1f83d9ab,
-        0x5be0cd19,
-    ];
-
-    // [3] initialize array of round constants
-    let k: [u32; 64] = [
-        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
-        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
-        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
-        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
-        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
-        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
-        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
-        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
-    ];
-
-    // [4] chunk loop
-    let chunk_count = bytes.len()/(512/8);
-    //dbg!(chunk_count);
-    for n in 0..chunk_count {
-        // access correct slice of chunk from byte vector
-        let chunk = &bytes[n*64..(n+1)*64];
-        // create 64-entry message schedule array w[0..63] of 32-bit words
-        let mut message: [u32; 64] = [0; 64];
-        // copy chunk into first 16 words 
-        for j in 0..16 {
-            message[j] = (chunk[4*j] as u32) << 24 | 
-                (chunk[4*j+1] as u32) << 16 |
-                (chunk[4*j+2] as u32) << 8 |
-                (chunk[4*j+3] as u32);
+impl Sha256 {
+    /// Creates a new hasher with no data.
+    pub fn new() -> Self {
+        Self {
+            // initialize hash values
+            hashes: [
+                0x6a09e667,
+                0xbb67ae85,
+                0x3c6ef372,
+                0xa54ff53a,
+                0x510e527f,
+                0x9b05688c,
+                0x1f83d9ab,
+                0x5be0cd19,
+            ],
+            chunk: [0; 64],
+            chunk_len: 0,
+            message_len: 0,
         }
+    }
 
-        // extend the first 16 words into the remaining 48 words w[16..63] of the message
-        for j in 16..64 {
-            //s0 := (w[i-15] rightrotate  7) xor (w[i-15] rightrotate 18) xor (w[i-15] rightshift  3)
-            let s0: u32 = message[j-15].rotate_right(7) ^ message[j-15].rotate_right(18) ^ (message[j-15] >> 3);
-            //s1 := (w[i-2] rightrotate 17) xor (w[i-2] rightrotate 19) xor (w[i-2] rightshift 10)
-            let s1: u32 = message[j-2].rotate_right(17) ^ message[j-2].rotate_right(19) ^ (message[j-2] >> 10);
-            message[j] = message[j-16].wrapping_add(s0).wrapping_add(message[j-7]).wrapping_add(s1);
+    /// Appends the bytes `data` to the message being hashed.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.message_len += data.len() as u64;
+        // fill the remaining space of a partial chunk
+        if self.chunk_len > 0 {
+            let take = (64 - self.chunk_len).min(data.len());
+            self.chunk[self.chunk_len..self.chunk_len+take].copy_from_slice(&data[..take]);
+            self.chunk_len += take;
+            data = &data[take..];
+            if self.chunk_len < 64 {
+                return
+            }
+            compress(&mut self.hashes, &self.chunk);
+            self.chunk_len = 0;
         }
-
-        // initialize current working variables to the current hash values
-        let mut wh = hashes;
-
-        // compression function main loop
-        for i in 0..64 {
-            // S1 := (e rightrotate 6) xor (e rightrotate 11) xor (e rightrotate 25)
-            let s1 = wh[4].rotate_right(6) ^ wh[4].rotate_right(11) ^ wh[4].rotate_right(25);
-            // ch := (e and f) xor ((not e) and g)
-            let ch = (wh[4] & wh[5]) ^ ((!wh[4]) & wh[6]);
-            // temp1 := h + S1 + ch + k[i] + w[i]
-            let temp1 = wh[7].wrapping_add(s1).wrapping_add(ch).wrapping_add(k[i]).wrapping_add(message[i]);
-            // S0 := (a rightrotate 2) xor (a rightrotate 13) xor (a rightrotate 22)
-            let s0 = wh[0].rotate_right(2) ^ wh[0].rotate_right(13) ^ wh[0].rotate_right(22);
-            // maj := (a and b) xor (a and c) xor (b and c)
-            let maj = (wh[0] & wh[1]) ^ (wh[0] & wh[2]) ^ (wh[1] & wh[2]);
-            // temp2 := S0 + maj
-            let temp2 = s0.wrapping_add(maj);
-     
-            // h := g
-            wh[7] = wh[6];
-            // g := f
-            wh[6] = wh[5];
-            // f := e
-            wh[5] = wh[4];
-            // e := d + temp1
-            wh[4] = wh[3].wrapping_add(temp1);
-            // d := c
-            wh[3] = wh[2];
-            // c := b
-            wh[2] = wh[1];
-            // b := a
-            wh[1] = wh[0];
-            // a := temp1 + temp2
-            wh[0] = temp1.wrapping_add(temp2);
+        // process whole chunks directly from the data
+        while data.len() >= 64 {
+            compress(&mut self.hashes, &data[..64]);
+            data = &data[64..];
         }
-        // add the compressed chunk to the current hash value
-        for i in 0..8 {
-            hashes[i] = hashes[i].wrapping_add(wh[i]);
+        // save the leftover bytes for the next update
+        self.chunk[..data.len()].copy_from_slice(data);
+        self.chunk_len = data.len();
+    }
+
+    /// Pads the message and produces the final hash value.
+    pub fn finish(mut self) -> Sha256Hash {
+        // compute the input bytes total length to store in 8 bytes
+        let message_len: u64 = self.message_len * 8;
+        // append a single '1' as 1000 0000 (0x80), then pad with zeros until 
+        // the length (with 8 bytes to follow) is a multiple of 512 bits -> 64 bytes
+        let zero_cnt_bytes = (64 + 56 - (self.chunk_len + 1) % 64) % 64;
+        let mut padding = vec![128];
+        padding.resize(1 + zero_cnt_bytes, 0);
+        // append 64 bits to the end, where 64 bits represent integer length of original input in binary (big-endian)
+        padding.extend_from_slice(&message_len.to_be_bytes());
+        self.update(&padding);
+        assert_eq!(self.chunk_len, 0); // 512 bits -> 64 bytes
+        // produce the final hash value (big-endian)
+        Sha256Hash {
+            digest: self.hashes
         }
     }
-    // produce the final hash value (big-endian)
-    Sha256Hash {
-        digest: hashes
+}
+
+/// Array of round constants.
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
+];
+
+/// Processes a single 64-byte `chunk` of the message into the `hashes`.
+fn compress(hashes: &mut [u32; 8], chunk: &[u8]) {
+    // create 64-entry message schedule array w[0..63] of 32-bit words
+    let mut message: [u32; 64] = [0; 64];
+    // copy chunk into first 16 words 
+    for j in 0..16 {
+        message[j] = (chunk[4*j] as u32) << 24 | 
+            (chunk[4*j+1] as u32) << 16 |
+            (chunk[4*j+2] as u32) << 8 |
+            (chunk[4*j+3] as u32);
+    }
+
+    // extend the first 16 words into the remaining 48 words w[16..63] of the message
+    for j in 16..64 {
+        //s0 := (w[i-15] rightrotate  7) xor (w[i-15] rightrotate 18) xor (w[i-15] rightshift  3)
+        let s0: u32 = message[j-15].rotate_right(7) ^ message[j-15].rotate_right(18) ^ (message[j-15] >> 3);
+        //s1 := (w[i-2] rightrotate 17) xor (w[i-2] rightrotate 19) xor (w[i-2] rightshift 10)
+        let s1: u32 = message[j-2].rotate_right(17) ^ message[j-2].rotate_right(19) ^ (message[j-2] >> 10);
+        message[j] = message[j-16].wrapping_add(s0).wrapping_add(message[j-7]).wrapping_add(s1);
+    }
+
+    // initialize current working variables to the current hash values
+    let mut wh = *hashes;
+
+    // compression function main loop
+    for i in 0..64 {
+        // S1 := (e rightrotate 6) xor (e rightrotate 11) xor (e rightrotate 25)
+        let s1 = wh[4].rotate_right(6) ^ wh[4].rotate_right(11) ^ wh[4].rotate_right(25);
+        // ch := (e and f) xor ((not e) and g)
+        let ch = (wh[4] & wh[5]) ^ ((!wh[4]) & wh[6]);
+        // temp1 := h + S1 + ch + K[i] + w[i]
+        let temp1 = wh[7].wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(message[i]);
+        // S0 := (a rightrotate 2) xor (a rightrotate 13) xor (a rightrotate 22)
+        let s0 = wh[0].rotate_right(2) ^ wh[0].rotate_right(13) ^ wh[0].rotate_right(22);
+        // maj := (a and b) xor (a and c) xor (b and c)
+        let maj = (wh[0] & wh[1]) ^ (wh[0] & wh[2]) ^ (wh[1] & wh[2]);
+        // temp2 := S0 + maj
+        let temp2 = s0.wrapping_add(maj);
+ 
+        // h := g
+        wh[7] = wh[6];
+        // g := f
+        wh[6] = wh[5];
+        // f := e
+        wh[5] = wh[4];
+        // e := d + temp1
+        wh[4] = wh[3].wrapping_add(temp1);
+        // d := c
+        wh[3] = wh[2];
+        // c := b
+        wh[2] = wh[1];
+        // b := a
+        wh[1] = wh[0];
+        // a := temp1 + temp2
+        wh[0] = temp1.wrapping_add(temp2);
+    }
+    // add the compressed chunk to the current hash value
+    for i in 0..8 {
+        hashes[i] = hashes[i].wrapping_add(wh[i]);
     }
 }
 
@@ -360,4 +394,25 @@ An interview.".as_bytes()),
             "0d732bb7f24e68fb3858646ba33bc9ce3240def191cde285a3f03ad1f763f52d"
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn streaming() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        // split the message at every kind of boundary around a chunk
+        for split in [0, 1, 55, 56, 63, 64, 65, 128, 999, 1000] {
+            let mut hasher = Sha256::new();
+            hasher.update(&data[..split]);
+            hasher.update(&data[split..]);
+            assert_eq!(hasher.finish(), compute_sha256(&data), "split at {}", split);
+        }
+        // message lengths that pad into an extra chunk
+        for len in [55, 56, 57, 63, 64] {
+            let mut hasher = Sha256::new();
+            data[..len].chunks(7).for_each(|c| hasher.update(c));
+            assert_eq!(hasher.finish(), compute_sha256(&data[..len]));
+        }
+        assert_eq!(compute_sha256(&[b'a'; 56]),
+            Sha256Hash::from_str("b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a").unwrap()
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Consistent styling for the messages and tables orbit prints.
+//!
+//! Styles are only applied when colors are enabled, which is decided once by the
+//! global `--color` option (`auto` detects a terminal and respects `NO_COLOR`).
+//! Each function has a `_with` form that takes the decision explicitly so the
+//! styled output can be tested.
+
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Style {
+    Info,
+    Warning,
+    Error,
+    Hint,
+    /// A unit, ip, or file named within a message.
+    Name,
+    /// A command-line argument named within a message.
+    Argument,
+    /// The column titles of a table.
+    Header,
+}
+
+impl Style {
+    /// Returns the ANSI select graphic rendition parameters for the style.
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Info => "1;36",
+            Self::Warning => "1;33",
+            Self::Error => "1;31",
+            Self::Hint => "1;32",
+            Self::Name => "34",
+            Self::Argument => "33",
+            Self::Header => "1",
+        }
+    }
+
+    /// Returns the label that begins a message at this style's level.
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Info => "info:",
+            Self::Warning => "warning:",
+            Self::Error => "error:",
+            Self::Hint => "hint:",
+            _ => "",
+        }
+    }
+}
+
+/// Checks if colors are enabled for this run.
+pub fn is_enabled() -> bool {
+    colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+/// Applies the `style` to the `text` when colors are enabled.
+pub fn paint(style: Style, text: &str) -> String {
+    paint_with(style, text, is_enabled())
+}
+
+pub fn paint_with(style: Style, text: &str, color: bool) -> String {
+    match color && !text.is_empty() {
+        true => format!("\x1b[{}m{}\x1b[0m", style.code(), text),
+        false => text.to_string(),
+    }
+}
+
+/// Writes the `msg` after the label for the `style` (such as "warning:").
+pub fn message_with<T: Display>(style: Style, msg: T, color: bool) -> String {
+    format!("{} {}", paint_with(style, style.label(), color), msg)
+}
+
+pub fn info<T: Display>(msg: T) -> String {
+    message_with(Style::Info, msg, is_enabled())
+}
+
+pub fn warning<T: Display>(msg: T) -> String {
+    message_with(Style::Warning, msg, is_enabled())
+}
+
+pub fn error<T: Display>(msg: T) -> String {
+    message_with(Style::Error, msg, is_enabled())
+}
+
+pub fn hint<T: Display>(msg: T) -> String {
+    message_with(Style::Hint, msg, is_enabled())
+}
+
+/// Highlights a unit, ip, or file named within a message.
+pub fn name<T: Display>(s: T) -> String {
+    paint(Style::Name, &s.to_string())
+}
+
+/// Highlights a command-line argument named within a message.
+pub fn argument<T: Display>(s: T) -> String {
+    paint(Style::Argument, &s.to_string())
+}
+
+/// Styles the first line of a formatted `table` as its header.
+pub fn table_with(table: &str, color: bool) -> String {
+    match table.split_once('\n') {
+        Some((header, body)) => format!("{}\n{}", paint_with(Style::Header, header, color), body),
+        None => paint_with(Style::Header, table, color),
+    }
+}
+
+pub fn table(table: &str) -> String {
+    table_with(table, is_enabled())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn messages() {
+        let snapshot = [
+            message_with(Style::Info, "top-level set to counter", true),
+            message_with(Style::Warning, "no testbench set", true),
+            message_with(Style::Error, format!("unknown plugin '{}'", paint_with(Style::Argument, "vivado", true)), true),
+            message_with(Style::Hint, format!("try `orbit plan --bench {}`", paint_with(Style::Name, "counter_tb", true)), true),
+        ].join("\n");
+        assert_eq!(snapshot, "\
+\x1b[1;36minfo:\x1b[0m top-level set to counter
+\x1b[1;33mwarning:\x1b[0m no testbench set
+\x1b[1;31merror:\x1b[0m unknown plugin '\x1b[33mvivado\x1b[0m'
+\x1b[1;32mhint:\x1b[0m try `orbit plan --bench \x1b[34mcounter_tb\x1b[0m`");
+    }
+
+    #[test]
+    fn no_color() {
+        assert_eq!(message_with(Style::Warning, "no testbench set", false), "warning: no testbench set");
+        assert_eq!(paint_with(Style::Name, "counter", false), "counter");
+        assert_eq!(paint_with(Style::Name, "", true), "");
+    }
+
+    #[test]
+    fn tables() {
+        let table = "Version        Status\n-------------- --------\n1.0.0          I\n";
+        assert_eq!(table_with(table, true), "\x1b[1mVersion        Status\x1b[0m\n-------------- --------\n1.0.0          I\n");
+        assert_eq!(table_with(table, false), table);
+    }
+}
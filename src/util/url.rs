@@ -17,6 +17,36 @@ impl Url {
             Self::Ssh(url) => url.to_https(),
         }
     }
+
+    /// Casts the url to ssh.
+    pub fn as_ssh(&self) -> Ssh {
+        match self {
+            Self::Https(url) => url.to_ssh(),
+            Self::Ssh(url) => url.to_owned(),
+        }
+    }
+
+    /// References the subdirectory of the repository that holds the ip, given
+    /// as the url's fragment (`#path/to/ip`).
+    pub fn get_subdirectory(&self) -> Option<&str> {
+        let dir = match self {
+            Self::Https(url) => url.url.fragment(),
+            Self::Ssh(url) => url.subdir.as_deref(),
+        };
+        dir.map(|d| d.trim_matches('/')).filter(|d| !d.is_empty())
+    }
+
+    /// Creates the url to the repository itself, without any subdirectory.
+    pub fn without_subdirectory(&self) -> Url {
+        match self {
+            Self::Https(url) => {
+                let mut url = url.to_owned();
+                url.url.set_fragment(None);
+                Self::Https(url)
+            },
+            Self::Ssh(url) => Self::Ssh(Ssh { subdir: None, ..url.to_owned() }),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -77,6 +107,19 @@ pub struct Https {
     url: url::Url
 }
 
+impl Https {
+    fn to_ssh(&self) -> Ssh {
+        Ssh {
+            prefix: None,
+            user: String::from("git"),
+            host: self.url.host_str().unwrap_or_default().to_string(),
+            port: None,
+            path: self.url.path().trim_start_matches('/').to_string(),
+            subdir: self.url.fragment().map(|f| f.to_string()),
+        }
+    }
+}
+
 impl std::str::FromStr for Https {
     type Err = url::ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -91,25 +134,32 @@ impl std::fmt::Display for Https {
 }
 
 #[derive(Debug, PartialEq, Clone, Eq, Hash)]
-/// SSH ::= [ssh://]<user>@<host>:[port]</path/to/repo> or {user}@<host>:<path/to/repo>
+/// SSH ::= [ssh://]<user>@<host>:[port]</path/to/repo>[#subdir] or {user}@<host>:<path/to/repo>[#subdir]
 pub struct Ssh {
     prefix: Option<String>,
     user: String,
     host: String,
     port: Option<u16>,
     path: String,
+    subdir: Option<String>,
 }
 
 impl Ssh {
     fn to_https(&self) -> Https {
-        Https { url: url::Url::from_str(&format!("https://{}/{}", self.host, self.path)).unwrap() }
+        let mut url = url::Url::from_str(&format!("https://{}/{}", self.host, self.path)).unwrap();
+        url.set_fragment(self.subdir.as_deref());
+        Https { url }
     }
 }
 
 impl std::str::FromStr for Ssh {
     type Err = SshError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        
+        // separate the subdirectory fragment
+        let (s, subdir) = match s.split_once('#') {
+            Some((s, subdir)) => (s, Some(subdir.to_owned())),
+            None => (s, None),
+        };
         // check if the string begins with ssh://
         let (prefix, url) = if let Some((base, url)) = s.split_once("://") {
             match strcmp::cmp_ascii_ignore_case(base, "ssh") {
@@ -156,6 +206,7 @@ impl std::str::FromStr for Ssh {
             host: host,
             port: port,
             path: url.to_owned(),
+            subdir,
         })
     }
 }
@@ -169,7 +220,11 @@ impl std::fmt::Display for Ssh {
         if let Some(port) = &self.port {
             write!(f, "{}/", port)?;
         }
-        write!(f, "{}", self.path)
+        write!(f, "{}", self.path)?;
+        if let Some(subdir) = &self.subdir {
+            write!(f, "#{}", subdir)?;
+        }
+        Ok(())
     }
 }
 
@@ -225,6 +280,23 @@ mod test {
         );
     }
 
+    #[test]
+    fn subdirectory() {
+        let url = Url::from_str("git@github.ks-tech.org:rary/mono.git#ip/gates").unwrap();
+        assert_eq!(url.to_string(), "git@github.ks-tech.org:rary/mono.git#ip/gates");
+        assert_eq!(url.get_subdirectory(), Some("ip/gates"));
+        assert_eq!(url.without_subdirectory().to_string(), "git@github.ks-tech.org:rary/mono.git");
+        assert_eq!(url.as_https().to_string(), "https://github.ks-tech.org/rary/mono.git#ip/gates");
+
+        let url = Url::from_str("https://github.ks-tech.org/rary/mono.git#ip/gates/").unwrap();
+        assert_eq!(url.get_subdirectory(), Some("ip/gates"));
+        assert_eq!(url.without_subdirectory().to_string(), "https://github.ks-tech.org/rary/mono.git");
+        assert_eq!(url.as_ssh().to_string(), "git@github.ks-tech.org:rary/mono.git#ip/gates/");
+
+        let url = Url::from_str("https://github.ks-tech.org/rary/gates.git").unwrap();
+        assert_eq!(url.get_subdirectory(), None);
+    }
+
     #[test]
     fn https_from_str() {
         let url = Https::from_str("https://github.ks-tech.org/rary/gates.git").unwrap();
@@ -237,4 +309,10 @@ mod test {
         let url = Ssh::from_str("git@github.ks-tech.org:rary/gates.git").unwrap();
         assert_eq!(url.to_https(), Https::from_str("https://github.ks-tech.org/rary/gates.git").unwrap())
     }
+
+    #[test]
+    fn https_to_ssh() {
+        let url = Https::from_str("https://github.ks-tech.org/rary/gates.git").unwrap();
+        assert_eq!(url.to_ssh(), Ssh::from_str("git@github.ks-tech.org:rary/gates.git").unwrap())
+    }
 }
\ No newline at end of file
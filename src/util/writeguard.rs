@@ -0,0 +1,91 @@
+//! Guards the changes a command makes to the filesystem so a dry run can report
+//! them instead.
+
+use std::path::Path;
+use crate::util::anyerror::Fault;
+use crate::util::theme;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct WriteGuard {
+    dry_run: bool,
+}
+
+impl WriteGuard {
+    pub fn new(dry_run: bool) -> Self {
+        Self { dry_run }
+    }
+
+    /// Checks if changes are only reported.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Performs the `action` described by `summary`, or reports it during a dry run.
+    ///
+    /// Returns `None` when the action was not performed.
+    pub fn perform<T, F>(&self, summary: &str, action: F) -> Result<Option<T>, Fault>
+    where F: FnOnce() -> Result<T, Fault> {
+        match self.dry_run {
+            true => {
+                println!("{}", theme::info(format!("would {}", summary)));
+                Ok(None)
+            }
+            false => Ok(Some(action()?)),
+        }
+    }
+
+    /// Writes `contents` to the file at `path`, or reports the write during a dry run.
+    pub fn write(&self, path: &Path, contents: &str) -> Result<(), Fault> {
+        self.perform(&format!("write {}", path.display()), || Ok(std::fs::write(path, contents)?))?;
+        Ok(())
+    }
+
+    /// Writes `contents` to the file at `path` only when they differ from what is
+    /// already there, leaving its modification time alone otherwise. Setting `touch`
+    /// always writes the file.
+    ///
+    /// Returns `true` when the file was written.
+    pub fn update(&self, path: &Path, contents: &str, touch: bool) -> Result<bool, Fault> {
+        if !touch && std::fs::read_to_string(path).map(|s| s == contents).unwrap_or(false) {
+            return Ok(false)
+        }
+        self.write(path, contents)?;
+        Ok(!self.dry_run)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dry_run_skips_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blueprint.tsv");
+        let guard = WriteGuard::new(true);
+        guard.write(&path, "VHDL-RTL\twork\ttop.vhd\n").unwrap();
+        assert!(!path.exists());
+        assert_eq!(guard.perform("count", || Ok(1)).unwrap(), None);
+
+        let guard = WriteGuard::new(false);
+        guard.write(&path, "VHDL-RTL\twork\ttop.vhd\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "VHDL-RTL\twork\ttop.vhd\n");
+        assert_eq!(guard.perform("count", || Ok(1)).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn update_skips_unchanged_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blueprint.tsv");
+        let guard = WriteGuard::new(false);
+        assert_eq!(guard.update(&path, "VHDL-RTL\twork\ttop.vhd\n", false).unwrap(), true);
+        assert_eq!(guard.update(&path, "VHDL-RTL\twork\ttop.vhd\n", false).unwrap(), false);
+        assert_eq!(guard.update(&path, "VHDL-RTL\twork\ttop.vhd\n", true).unwrap(), true);
+        assert_eq!(guard.update(&path, "VHDL-SIM\twork\ttop_tb.vhd\n", false).unwrap(), true);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "VHDL-SIM\twork\ttop_tb.vhd\n");
+
+        let guard = WriteGuard::new(true);
+        assert_eq!(guard.update(&path, "VHDL-RTL\twork\ttop.vhd\n", false).unwrap(), false);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "VHDL-SIM\twork\ttop_tb.vhd\n");
+    }
+}